@@ -127,6 +127,8 @@ typedef struct ToCssOptions {
   struct Targets targets;
   bool analyze_dependencies;
   struct PseudoClasses pseudo_classes;
+  bool preserve_position_keywords;
+  bool shortest_units;
 } ToCssOptions;
 
 bool lightningcss_browserslist_to_targets(const char *query,
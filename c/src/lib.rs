@@ -117,6 +117,7 @@ impl Into<Browsers> for Targets {
       opera: browser!(self.opera),
       safari: browser!(self.safari),
       samsung: browser!(self.samsung),
+      ..Browsers::default()
     }
   }
 }
@@ -140,7 +141,7 @@ pub extern "C" fn lightningcss_browserslist_to_targets(
   error: *mut *mut CssError,
 ) -> bool {
   let string = unsafe { std::str::from_utf8_unchecked(CStr::from_ptr(query).to_bytes()) };
-  match Browsers::from_browserslist([string]) {
+  match Browsers::from_browserslist_query(string) {
     Ok(Some(browsers)) => {
       let targets = unsafe { &mut *targets };
       targets.android = browsers.android.unwrap_or_default();
@@ -185,6 +186,7 @@ impl Into<MinifyOptions> for TransformOptions {
         None
       },
       unused_symbols,
+      ..MinifyOptions::default()
     }
   }
 }
@@ -199,6 +201,8 @@ pub struct ToCssOptions {
   targets: Targets,
   analyze_dependencies: bool,
   pseudo_classes: PseudoClasses,
+  preserve_position_keywords: bool,
+  shortest_units: bool,
 }
 
 #[derive(PartialEq)]
@@ -278,6 +282,7 @@ pub extern "C" fn lightningcss_stylesheet_parse(
       Some(lightningcss::css_modules::Config {
         pattern,
         dashed_idents: options.css_modules_dashed_idents,
+        ..Default::default()
       })
     } else {
       None
@@ -286,6 +291,7 @@ pub extern "C" fn lightningcss_stylesheet_parse(
     source_index: 0,
     warnings: Some(warnings.clone()),
     at_rule_parser: None,
+    preserve_comments: Default::default(),
   };
 
   let stylesheet = unwrap!(StyleSheet::parse(code, opts), error, std::ptr::null_mut());
@@ -331,6 +337,7 @@ pub extern "C" fn lightningcss_stylesheet_to_css(
       Some(unsafe { std::str::from_utf8_unchecked(CStr::from_ptr(options.project_root).to_bytes()) })
     },
     source_map: source_map.as_mut(),
+    source_map_options: Default::default(),
     targets: if options.targets != Targets::default() {
       Some(options.targets.into())
     } else {
@@ -346,6 +353,15 @@ pub extern "C" fn lightningcss_stylesheet_to_css(
     } else {
       None
     },
+    preserve_position_keywords: options.preserve_position_keywords,
+    shortest_units: options.shortest_units,
+    precision: Default::default(),
+    compression: Default::default(),
+    urls: Default::default(),
+    banner: None,
+    footer: None,
+    source_comments: false,
+    icss: false,
   };
 
   let res = unwrap!(wrapper.stylesheet.to_css(opts), error, ToCssResult::default());
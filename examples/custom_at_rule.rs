@@ -16,6 +16,7 @@ use lightningcss::{
   visit_types,
   visitor::{Visit, VisitTypes, Visitor},
 };
+use smallvec::SmallVec;
 
 fn main() {
   let args: Vec<String> = std::env::args().collect();
@@ -29,6 +30,7 @@ fn main() {
     error_recovery: false,
     warnings: None,
     source_index: 0,
+    preserve_comments: Default::default(),
   };
 
   let mut stylesheet = StyleSheet::parse(&source, opts).unwrap();
@@ -149,7 +151,7 @@ struct StyleRuleCollector<'i, 'a> {
 impl<'i, 'a> Visitor<'i, AtRule> for StyleRuleCollector<'i, 'a> {
   const TYPES: VisitTypes = VisitTypes::RULES;
 
-  fn visit_rule(&mut self, rule: &mut lightningcss::rules::CssRule<'i, AtRule>) {
+  fn visit_rule(&mut self, rule: &mut CssRule<'i, AtRule>) -> Option<SmallVec<[CssRule<'i, AtRule>; 1]>> {
     match rule {
       CssRule::Style(rule) => {
         for selector in rule.selectors.0.iter() {
@@ -169,7 +171,8 @@ impl<'i, 'a> Visitor<'i, AtRule> for StyleRuleCollector<'i, 'a> {
       _ => {}
     }
 
-    rule.visit_children(self)
+    rule.visit_children(self);
+    None
   }
 }
 
@@ -180,7 +183,7 @@ struct ApplyVisitor<'a, 'i> {
 impl<'a, 'i> Visitor<'i, AtRule> for ApplyVisitor<'a, 'i> {
   const TYPES: VisitTypes = visit_types!(RULES | COLORS | LENGTHS | DASHED_IDENTS | SELECTORS | TOKENS);
 
-  fn visit_rule(&mut self, rule: &mut CssRule<'i, AtRule>) {
+  fn visit_rule(&mut self, rule: &mut CssRule<'i, AtRule>) -> Option<SmallVec<[CssRule<'i, AtRule>; 1]>> {
     // Replace @apply rule with nested style rule.
     if let CssRule::Custom(AtRule::Apply(apply)) = rule {
       let mut declarations = DeclarationBlock::new();
@@ -204,7 +207,8 @@ impl<'a, 'i> Visitor<'i, AtRule> for ApplyVisitor<'a, 'i> {
       })
     }
 
-    rule.visit_children(self)
+    rule.visit_children(self);
+    None
   }
 
   fn visit_url(&mut self, url: &mut lightningcss::values::url::Url<'i>) {
@@ -12,8 +12,10 @@ export interface TransformOptions {
   minify?: boolean,
   /** Whether to output a source map. */
   sourceMap?: boolean,
-  /** An input source map to extend. */
+  /** An input source map to extend. Index maps with `sections` are also supported. */
   inputSourceMap?: string,
+  /** The `sourceRoot` of the output source map, e.g. `webpack://`. */
+  sourceRoot?: string,
   /** 
    * An optional project root path, used as the source root in the output source map.
    * Also used to generate relative paths for sources used in CSS module hashes.
@@ -39,16 +41,149 @@ export interface TransformOptions {
   pseudoClasses?: PseudoClasses,
   /**
    * A list of class names, ids, and custom identifiers (e.g. @keyframes) that are known
-   * to be unused. These will be removed during minification. Note that these are not
-   * selectors but individual names (without any . or # prefixes).
+   * to be unused. These will be removed during minification. Plain names match both classes
+   * and ids. Use a `.` or `#` prefix to match only classes or ids, or wrap a name in `[]` to
+   * match attribute selectors. A `*` wildcard matches any sequence of characters, e.g. `.btn-*`.
    */
   unusedSymbols?: string[],
+  /**
+   * The names of the CSS module exports that are imported from JavaScript, e.g. as determined by a bundler.
+   * Unused classes, ids, and `@keyframes` are removed, except those composed by used classes.
+   */
+  usedExports?: string[],
+  /**
+   * A fixed document direction to assume when compiling logical properties for browsers
+   * that don't support them. When set, properties such as `margin-inline-start` compile to
+   * a single physical property rather than duplicated rules using the `:dir()` selector.
+   */
+  direction?: 'ltr' | 'rtl',
+  /**
+   * Whether to remove entries from `will-change` declarations that refer to properties
+   * which are not declared anywhere in the style sheet.
+   */
+  removeUnusedWillChange?: boolean,
+  /**
+   * How `@document` and `@-moz-document` rules are handled. By default, they are removed when
+   * none of the browser targets support them. Use `preserve` to keep them, e.g. for user style sheets.
+   */
+  documentRules?: 'auto' | 'preserve' | 'remove',
+  /**
+   * Whether to remove `@keyframes` rules that are not referenced by any animation in the style sheet.
+   * Only enable this when the style sheet is complete, and animations are not referenced elsewhere.
+   */
+  removeUnusedKeyframes?: boolean,
+  /**
+   * Whether to remove `@font-face` rules whose family is not referenced by any `font` or `font-family`
+   * declaration in the style sheet. Only enable this when the style sheet is complete.
+   */
+  removeUnusedFontFaces?: boolean,
+  /** Font families whose `@font-face` rules are always preserved by `removeUnusedFontFaces`. */
+  preservedFontFamilies?: string[],
+  /**
+   * Whether to remove custom property declarations that are never read by a `var()` reference,
+   * directly or via other custom properties. Only enable this when the style sheet is complete.
+   */
+  removeUnusedCustomProperties?: boolean,
+  /** Patterns for custom properties that are always preserved, e.g. `--theme-*`. */
+  preservedCustomProperties?: string[],
+  /** Whether to preserve all custom properties declared in `:root` rules. */
+  preserveRootCustomProperties?: boolean,
+  /**
+   * The class names, ids, and tag names used in the documents the style sheet applies to.
+   * When set, rules that cannot match any of this content are removed.
+   */
+  purge?: PurgeContent,
+  /**
+   * Whether to replace `var()` references to custom properties registered by `@property` rules with
+   * their initial value, when the property is never declared. Only enable this when the style sheet is complete.
+   */
+  inlineRegisteredProperties?: boolean,
+  /**
+   * Whether to merge `@keyframes` rules with identical keyframes but different names, and rename
+   * references to the removed rules. Only enable this when the style sheet is complete.
+   */
+  mergeDuplicateKeyframes?: boolean,
+  /**
+   * Whether to preserve the structure of the rules, i.e. never merge adjacent rules or unwrap
+   * nested conditional rules, so each rule in the output corresponds to a rule in the source.
+   */
+  preserveRuleStructure?: boolean,
+  /**
+   * Whether to sort declarations by property name and adjacent style rules by selector where their
+   * order cannot affect the cascade, for deterministic output that compresses better.
+   */
+  canonicalOrder?: boolean,
+  /**
+   * Whether to remove declarations that are overridden by later rules with the same selectors, and merge
+   * non-adjacent rules with the same selectors. This assumes the style sheet is complete.
+   */
+  restructureRules?: boolean,
+  /**
+   * Whether to compile `scroll-snap-type` to the legacy `-ms-scroll-snap-type` and
+   * `-ms-scroll-snap-points-x/y` properties for IE and legacy Edge targets. The legacy
+   * syntax snaps at a fixed interval, so the snap points assume each child fills the scroll container.
+   */
+  legacyScrollSnap?: boolean,
+  /**
+   * A minification preset that sets the defaults for the other minification options.
+   * `O1` preserves the rule structure, `O2` is the default, and `O3` enables all options
+   * that assume the style sheet is complete. Individual options override the preset.
+   */
+  level?: 'O1' | 'O2' | 'O3',
   /**
    * Whether to ignore invalid rules and declarations rather than erroring.
    * When enabled, warnings are returned, and the invalid rule or declaration is
    * omitted from the output code.
    */
   errorRecovery?: boolean,
+  /**
+   * Which comments between rules to preserve, e.g. license comments.
+   * By default, all comments are removed.
+   */
+  preserveComments?: CommentPolicy,
+  /**
+   * Whether to preserve position keywords such as `left` and `top` rather than converting them
+   * to percentages, e.g. in `background-position` and `transform-origin`.
+   */
+  preservePositionKeywords?: boolean,
+  /**
+   * Whether to print time, angle, and resolution values using whichever equivalent unit
+   * is shortest when minifying, e.g. `.25turn` instead of `90deg`.
+   */
+  shortestUnits?: boolean,
+  /**
+   * The maximum number of decimal places to keep when printing numeric values,
+   * either globally or per type of value.
+   */
+  precision?: Precision,
+  /**
+   * How to choose between equivalent serializations of values, to improve compression of the output.
+   * `consistent` always prints colors as hex, and `estimate` tries named and hex colors, and double and
+   * single quotes, and keeps whichever output has the smallest estimated compressed size.
+   */
+  compression?: 'none' | 'consistent' | 'estimate',
+  /**
+   * The path of the output file. When set, relative urls in `url()` references and `@import` rules
+   * are rebased from the source file they appear in to the directory of the output file.
+   */
+  outputPath?: string,
+  /**
+   * Text to print at the start of the output, e.g. a license header. It is printed as is,
+   * and should therefore be a valid CSS comment.
+   */
+  banner?: string,
+  /** Text to print at the end of the output. */
+  footer?: string,
+  /**
+   * Whether to print a `/* source: path.css *\/` comment before the rules from each file in a bundle,
+   * to help debugging. This is ignored when minifying.
+   */
+  sourceComments?: boolean,
+  /**
+   * Whether to append the CSS module exports and references to the output as ICSS `:import` and `:export` rules,
+   * for use with css-loader and other tools that exchange module metadata via ICSS.
+   */
+  icss?: boolean,
   /**
    * An AST visitor object. This allows custom transforms or analysis to be implemented in JavaScript.
    * Multiple visitors can be composed into one using the `composeVisitors` function.
@@ -173,6 +308,44 @@ export interface Drafts {
   customMedia?: boolean
 }
 
+export interface CommentPolicy {
+  /** Whether to preserve comments starting with `!`, e.g. `/*! Copyright *\/`. */
+  important?: boolean,
+  /** Whether to preserve comments containing a `@license` or `@preserve` annotation. */
+  license?: boolean,
+  /**
+   * Additional patterns to match the trimmed text of comments against, e.g. `#region *`.
+   * A `*` wildcard matches any sequence of characters.
+   */
+  patterns?: string[]
+}
+
+export interface Precision {
+  /** The number of decimal places used for all values, unless overridden for a specific type. */
+  default?: number,
+  /** The number of decimal places used for unitless numbers. */
+  number?: number,
+  /** The number of decimal places used for lengths. */
+  length?: number,
+  /** The number of decimal places used for percentages. */
+  percentage?: number,
+  /** The number of decimal places used for angles. */
+  angle?: number,
+  /** The number of decimal places used for times. */
+  time?: number,
+  /** The number of decimal places used for resolutions. */
+  resolution?: number
+}
+
+export interface PurgeContent {
+  /** The class names used in the documents. */
+  classes?: string[],
+  /** The ids used in the documents. */
+  ids?: string[],
+  /** The tag names used in the documents, in lowercase. */
+  tags?: string[]
+}
+
 export interface PseudoClasses {
   hover?: string,
   active?: string,
@@ -188,6 +361,8 @@ export interface TransformResult {
   map: Buffer | void,
   /** CSS module exports, if enabled. */
   exports: CSSModuleExports | void,
+  /** The CSS module exports that were removed because they are unused, if `usedExports` is given. */
+  removedExports: string[] | void,
   /** CSS module references, if `dashedIdents` is enabled. */
   references: CSSModuleReferences,
   /** `@import` and `url()` dependencies, if enabled. */
@@ -204,10 +379,43 @@ export interface Warning {
 }
 
 export interface CSSModulesConfig {
-  /** The pattern to use when renaming class names and other identifiers. Default is `[hash]_[local]`. */
+  /**
+   * The pattern to use when renaming class names and other identifiers. Default is `[hash]_[local]`.
+   * Supports the `[name]`, `[local]`, `[hash]` (of the file path), and `[contenthash]` (of the file contents) placeholders.
+   */
   pattern: string,
   /** Whether to rename dashed identifiers, e.g. custom properties. */
-  dashedIdents: boolean
+  dashedIdents: boolean,
+  /**
+   * Whether to rename custom properties declared in the module, and the `var()` references to them.
+   * References to custom properties that are not declared in the module are left as is. Default is `false`.
+   */
+  customProperties?: boolean,
+  /** The number of characters to shorten hashes to. By default, the full hash is used. */
+  hashLength?: number,
+  /** The alphabet to encode hashes with. Default is `base64url`. */
+  hashEncoding?: 'base64url' | 'base36',
+  /** Whether to scope class names. Default is `true`. */
+  classes?: boolean,
+  /** Whether to scope ids. Default is `true`. */
+  ids?: boolean,
+  /** Whether to scope `@keyframes` names. Default is `true`. */
+  animation?: boolean,
+  /** Whether to scope `@counter-style` names. Default is `true`. */
+  counterStyle?: boolean,
+  /** Whether to scope container names. Default is `true`. */
+  container?: boolean,
+  /** Whether to scope grid line and area names. Default is `true`. */
+  grid?: boolean,
+  /** Whether to scope scroll and view timeline names. Default is `true`. */
+  timeline?: boolean,
+  /** Whether to support the `@value` rule from postcss-modules-values. Default is `false`. */
+  values?: boolean
+  /**
+   * Whether to require each selector to contain at least one local class or id, as in css-loader's pure mode.
+   * Selectors that do not are an error, or a warning when `errorRecovery` is enabled. Default is `false`.
+   */
+  pure?: boolean
 }
 
 export type CSSModuleExports = {
@@ -221,7 +429,16 @@ export interface CSSModuleExport {
   /** Whether the export is referenced in this file. */
   isReferenced: boolean,
   /** Other names that are composed by this export. */
-  composes: CSSModuleReference[]
+  composes: CSSModuleReference[],
+  /** The location of the rule where the name first appears in the source file. */
+  loc?: {
+    /** The index of the source file. */
+    source_index: number,
+    /** The line number (0-based). */
+    line: number,
+    /** The column number (1-based). */
+    column: number
+  }
 }
 
 export type CSSModuleReferences = {
@@ -251,7 +468,7 @@ export interface DependencyCSSModuleReference {
   specifier: string
 }
 
-export type Dependency = ImportDependency | UrlDependency;
+export type Dependency = ImportDependency | UrlDependency | PaintDependency;
 
 export interface ImportDependency {
   type: 'import',
@@ -272,7 +489,17 @@ export interface UrlDependency {
   /** The source location where the `url()` was found. */
   loc: SourceLocation,
   /** The placeholder that the url was replaced with. */
-  placeholder: string
+  placeholder: string,
+  /** Any url modifiers, e.g. `crossorigin(anonymous)`. */
+  modifiers?: string[]
+}
+
+export interface PaintDependency {
+  type: 'paint',
+  /** The name of the paint worklet referenced by `paint()`. */
+  name: string,
+  /** The source location where the `paint()` function was found. */
+  loc: SourceLocation
 }
 
 export interface SourceLocation {
@@ -323,6 +550,32 @@ export interface TransformAttributeOptions {
    * omitted from the output code.
    */
   errorRecovery?: boolean,
+  /**
+   * Which comments between rules to preserve, e.g. license comments.
+   * By default, all comments are removed.
+   */
+  preserveComments?: CommentPolicy,
+  /**
+   * Whether to preserve position keywords such as `left` and `top` rather than converting them
+   * to percentages, e.g. in `background-position` and `transform-origin`.
+   */
+  preservePositionKeywords?: boolean,
+  /**
+   * Whether to print time, angle, and resolution values using whichever equivalent unit
+   * is shortest when minifying, e.g. `.25turn` instead of `90deg`.
+   */
+  shortestUnits?: boolean,
+  /**
+   * The maximum number of decimal places to keep when printing numeric values,
+   * either globally or per type of value.
+   */
+  precision?: Precision,
+  /**
+   * How to choose between equivalent serializations of values, to improve compression of the output.
+   * `consistent` always prints colors as hex, and `estimate` tries named and hex colors, and double and
+   * single quotes, and keeps whichever output has the smallest estimated compressed size.
+   */
+  compression?: 'none' | 'consistent' | 'estimate',
   /**
    * An AST visitor object. This allows custom transforms or analysis to be implemented in JavaScript.
    * Multiple visitors can be composed into one using the `composeVisitors` function.
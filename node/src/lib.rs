@@ -3,11 +3,17 @@
 static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 use lightningcss::bundler::{BundleErrorKind, Bundler, FileProvider, SourceProvider};
-use lightningcss::css_modules::{CssModuleExports, CssModuleReferences, PatternParseError};
+use lightningcss::css_modules::{CssModuleExports, CssModuleReferences, HashEncoding, PatternParseError};
 use lightningcss::dependencies::{Dependency, DependencyOptions};
 use lightningcss::error::{Error, ErrorLocation, MinifyErrorKind, ParserError, PrinterErrorKind};
+use lightningcss::printer::{Compression, Precision, UrlOptions};
+use lightningcss::rules::comment::CommentPolicy;
+use lightningcss::rules::document::DocumentRulePolicy;
+use lightningcss::selector::Direction;
+use lightningcss::sourcemap::source_map_from_json;
 use lightningcss::stylesheet::{
-  MinifyOptions, ParserOptions, PrinterOptions, PseudoClasses, StyleAttribute, StyleSheet,
+  MinifyLevel, MinifyOptions, ParserOptions, PrinterOptions, PseudoClasses, PurgeContent, StyleAttribute,
+  StyleSheet,
 };
 use lightningcss::targets::Browsers;
 use lightningcss::visitor::Visit;
@@ -35,6 +41,7 @@ struct TransformResult<'i> {
   #[serde(with = "serde_bytes")]
   map: Option<Vec<u8>>,
   exports: Option<CssModuleExports>,
+  removed_exports: Option<Vec<String>>,
   references: Option<CssModuleReferences>,
   dependencies: Option<Vec<Dependency>>,
   warnings: Vec<Warning<'i>>,
@@ -57,6 +64,7 @@ impl<'i> TransformResult<'i> {
       },
     )?;
     obj.set_named_property("exports", env.to_js_value(&self.exports)?)?;
+    obj.set_named_property("removedExports", env.to_js_value(&self.removed_exports)?)?;
     obj.set_named_property("references", env.to_js_value(&self.references)?)?;
     obj.set_named_property("dependencies", env.to_js_value(&self.dependencies)?)?;
     obj.set_named_property("warnings", env.to_js_value(&self.warnings)?)?;
@@ -503,12 +511,41 @@ struct Config {
   pub minify: Option<bool>,
   pub source_map: Option<bool>,
   pub input_source_map: Option<String>,
+  pub source_root: Option<String>,
   pub drafts: Option<Drafts>,
   pub css_modules: Option<CssModulesOption>,
   pub analyze_dependencies: Option<AnalyzeDependenciesOption>,
   pub pseudo_classes: Option<OwnedPseudoClasses>,
   pub unused_symbols: Option<HashSet<String>>,
+  pub used_exports: Option<HashSet<String>>,
+  pub direction: Option<Direction>,
+  pub remove_unused_will_change: Option<bool>,
+  pub document_rules: Option<DocumentRulePolicy>,
+  pub remove_unused_keyframes: Option<bool>,
+  pub remove_unused_font_faces: Option<bool>,
+  pub preserved_font_families: Option<HashSet<String>>,
+  pub remove_unused_custom_properties: Option<bool>,
+  pub preserved_custom_properties: Option<Vec<String>>,
+  pub preserve_root_custom_properties: Option<bool>,
+  pub purge: Option<PurgeContent>,
+  pub inline_registered_properties: Option<bool>,
+  pub merge_duplicate_keyframes: Option<bool>,
+  pub preserve_rule_structure: Option<bool>,
+  pub canonical_order: Option<bool>,
+  pub restructure_rules: Option<bool>,
+  pub legacy_scroll_snap: Option<bool>,
+  pub level: Option<MinifyLevel>,
   pub error_recovery: Option<bool>,
+  pub preserve_comments: Option<CommentPolicy>,
+  pub preserve_position_keywords: Option<bool>,
+  pub shortest_units: Option<bool>,
+  pub precision: Option<Precision>,
+  pub compression: Option<Compression>,
+  pub output_path: Option<String>,
+  pub banner: Option<String>,
+  pub footer: Option<String>,
+  pub source_comments: Option<bool>,
+  pub icss: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -536,6 +573,18 @@ enum CssModulesOption {
 struct CssModulesConfig {
   pattern: Option<String>,
   dashed_idents: Option<bool>,
+  custom_properties: Option<bool>,
+  hash_length: Option<usize>,
+  hash_encoding: Option<HashEncoding>,
+  classes: Option<bool>,
+  ids: Option<bool>,
+  animation: Option<bool>,
+  counter_style: Option<bool>,
+  container: Option<bool>,
+  grid: Option<bool>,
+  timeline: Option<bool>,
+  values: Option<bool>,
+  pure: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -546,12 +595,40 @@ struct BundleConfig {
   pub targets: Option<Browsers>,
   pub minify: Option<bool>,
   pub source_map: Option<bool>,
+  pub source_root: Option<String>,
   pub drafts: Option<Drafts>,
   pub css_modules: Option<CssModulesOption>,
   pub analyze_dependencies: Option<AnalyzeDependenciesOption>,
   pub pseudo_classes: Option<OwnedPseudoClasses>,
   pub unused_symbols: Option<HashSet<String>>,
+  pub direction: Option<Direction>,
+  pub remove_unused_will_change: Option<bool>,
+  pub document_rules: Option<DocumentRulePolicy>,
+  pub remove_unused_keyframes: Option<bool>,
+  pub remove_unused_font_faces: Option<bool>,
+  pub preserved_font_families: Option<HashSet<String>>,
+  pub remove_unused_custom_properties: Option<bool>,
+  pub preserved_custom_properties: Option<Vec<String>>,
+  pub preserve_root_custom_properties: Option<bool>,
+  pub purge: Option<PurgeContent>,
+  pub inline_registered_properties: Option<bool>,
+  pub merge_duplicate_keyframes: Option<bool>,
+  pub preserve_rule_structure: Option<bool>,
+  pub canonical_order: Option<bool>,
+  pub restructure_rules: Option<bool>,
+  pub legacy_scroll_snap: Option<bool>,
+  pub level: Option<MinifyLevel>,
   pub error_recovery: Option<bool>,
+  pub preserve_comments: Option<CommentPolicy>,
+  pub preserve_position_keywords: Option<bool>,
+  pub shortest_units: Option<bool>,
+  pub precision: Option<Precision>,
+  pub compression: Option<Compression>,
+  pub output_path: Option<String>,
+  pub banner: Option<String>,
+  pub footer: Option<String>,
+  pub source_comments: Option<bool>,
+  pub icss: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -604,6 +681,7 @@ fn compile<'i>(
     None
   };
 
+  let mut removed_exports = None;
   let res = {
     let mut stylesheet = StyleSheet::parse(
       &code,
@@ -625,6 +703,19 @@ fn compile<'i>(
                 Default::default()
               },
               dashed_idents: c.dashed_idents.unwrap_or_default(),
+              custom_properties: c.custom_properties.unwrap_or_default(),
+              hash_length: c.hash_length,
+              hash_encoding: c.hash_encoding.unwrap_or_default(),
+              classes: c.classes.unwrap_or(true),
+              ids: c.ids.unwrap_or(true),
+              animation: c.animation.unwrap_or(true),
+              counter_style: c.counter_style.unwrap_or(true),
+              container: c.container.unwrap_or(true),
+              grid: c.grid.unwrap_or(true),
+              timeline: c.timeline.unwrap_or(true),
+              values: c.values.unwrap_or_default(),
+              pure: c.pure.unwrap_or_default(),
+              name_allocator: None,
             }),
           }
         } else {
@@ -634,6 +725,7 @@ fn compile<'i>(
         error_recovery: config.error_recovery.unwrap_or_default(),
         warnings: warnings.clone(),
         at_rule_parser: ParserOptions::default_at_rule_parser(),
+        preserve_comments: config.preserve_comments.clone().unwrap_or_default(),
       },
     )?;
 
@@ -641,14 +733,40 @@ fn compile<'i>(
       stylesheet.visit(visitor);
     }
 
+    if let Some(used_exports) = &config.used_exports {
+      removed_exports = Some(stylesheet.remove_unused_exports(used_exports));
+    }
+
+    let preset = MinifyOptions::level(config.level.unwrap_or_default());
     stylesheet.minify(MinifyOptions {
       targets: config.targets,
       unused_symbols: config.unused_symbols.clone().unwrap_or_default(),
+      direction: config.direction,
+      remove_unused_will_change: config.remove_unused_will_change.unwrap_or(preset.remove_unused_will_change),
+      document_rules: config.document_rules.unwrap_or_default(),
+      remove_unused_keyframes: config.remove_unused_keyframes.unwrap_or(preset.remove_unused_keyframes),
+      remove_unused_font_faces: config.remove_unused_font_faces.unwrap_or(preset.remove_unused_font_faces),
+      preserved_font_families: config.preserved_font_families.clone().unwrap_or_default(),
+      remove_unused_custom_properties: config
+        .remove_unused_custom_properties
+        .unwrap_or(preset.remove_unused_custom_properties),
+      preserved_custom_properties: config.preserved_custom_properties.clone().unwrap_or_default(),
+      preserve_root_custom_properties: config.preserve_root_custom_properties.unwrap_or_default(),
+      purge: config.purge.clone(),
+      inline_registered_properties: config
+        .inline_registered_properties
+        .unwrap_or(preset.inline_registered_properties),
+      merge_duplicate_keyframes: config.merge_duplicate_keyframes.unwrap_or(preset.merge_duplicate_keyframes),
+      preserve_rule_structure: config.preserve_rule_structure.unwrap_or(preset.preserve_rule_structure),
+      canonical_order: config.canonical_order.unwrap_or(preset.canonical_order),
+      restructure_rules: config.restructure_rules.unwrap_or(preset.restructure_rules),
+      legacy_scroll_snap: config.legacy_scroll_snap.unwrap_or_default(),
     })?;
 
     stylesheet.to_css(PrinterOptions {
       minify: config.minify.unwrap_or_default(),
       source_map: source_map.as_mut(),
+      source_map_options: Default::default(),
       project_root,
       targets: config.targets,
       analyze_dependencies: if let Some(d) = &config.analyze_dependencies {
@@ -663,17 +781,29 @@ fn compile<'i>(
         None
       },
       pseudo_classes: config.pseudo_classes.as_ref().map(|p| p.into()),
+      preserve_position_keywords: config.preserve_position_keywords.unwrap_or_default(),
+      shortest_units: config.shortest_units.unwrap_or_default(),
+      precision: config.precision.unwrap_or_default(),
+      compression: config.compression.unwrap_or_default(),
+      urls: UrlOptions {
+        output_path: config.output_path.as_deref().map(Path::new),
+        rewrite: None,
+      },
+      banner: config.banner.as_deref(),
+      footer: config.footer.as_deref(),
+      source_comments: config.source_comments.unwrap_or_default(),
+      icss: config.icss.unwrap_or_default(),
     })?
   };
 
   let map = if let Some(mut source_map) = source_map {
     if let Some(input_source_map) = &config.input_source_map {
-      if let Ok(mut sm) = SourceMap::from_json("/", input_source_map) {
+      if let Ok(mut sm) = source_map_from_json("/", input_source_map) {
         let _ = source_map.extends(&mut sm);
       }
     }
 
-    source_map.to_json(None).ok()
+    source_map.to_json(config.source_root.as_deref()).ok()
   } else {
     None
   };
@@ -682,6 +812,7 @@ fn compile<'i>(
     code: res.code.into_bytes(),
     map: map.map(|m| m.into_bytes()),
     exports: res.exports,
+    removed_exports,
     references: res.references,
     dependencies: res.dependencies,
     warnings: warnings.map_or(Vec::new(), |w| {
@@ -727,6 +858,19 @@ fn compile_bundle<'i, 'o, P: SourceProvider, F: FnOnce(&mut StyleSheet<'i, 'o>)
               Default::default()
             },
             dashed_idents: c.dashed_idents.unwrap_or_default(),
+            custom_properties: c.custom_properties.unwrap_or_default(),
+            hash_length: c.hash_length,
+            hash_encoding: c.hash_encoding.unwrap_or_default(),
+            classes: c.classes.unwrap_or(true),
+            ids: c.ids.unwrap_or(true),
+            animation: c.animation.unwrap_or(true),
+            counter_style: c.counter_style.unwrap_or(true),
+            container: c.container.unwrap_or(true),
+            grid: c.grid.unwrap_or(true),
+            timeline: c.timeline.unwrap_or(true),
+            values: c.values.unwrap_or_default(),
+            pure: c.pure.unwrap_or_default(),
+            name_allocator: None,
           }),
         }
       } else {
@@ -734,6 +878,7 @@ fn compile_bundle<'i, 'o, P: SourceProvider, F: FnOnce(&mut StyleSheet<'i, 'o>)
       },
       error_recovery: config.error_recovery.unwrap_or_default(),
       warnings: warnings.clone(),
+      preserve_comments: config.preserve_comments.clone().unwrap_or_default(),
       ..ParserOptions::default()
     };
 
@@ -744,14 +889,36 @@ fn compile_bundle<'i, 'o, P: SourceProvider, F: FnOnce(&mut StyleSheet<'i, 'o>)
       visit(&mut stylesheet).map_err(CompileError::JsError)?;
     }
 
+    let preset = MinifyOptions::level(config.level.unwrap_or_default());
     stylesheet.minify(MinifyOptions {
       targets: config.targets,
       unused_symbols: config.unused_symbols.clone().unwrap_or_default(),
+      direction: config.direction,
+      remove_unused_will_change: config.remove_unused_will_change.unwrap_or(preset.remove_unused_will_change),
+      document_rules: config.document_rules.unwrap_or_default(),
+      remove_unused_keyframes: config.remove_unused_keyframes.unwrap_or(preset.remove_unused_keyframes),
+      remove_unused_font_faces: config.remove_unused_font_faces.unwrap_or(preset.remove_unused_font_faces),
+      preserved_font_families: config.preserved_font_families.clone().unwrap_or_default(),
+      remove_unused_custom_properties: config
+        .remove_unused_custom_properties
+        .unwrap_or(preset.remove_unused_custom_properties),
+      preserved_custom_properties: config.preserved_custom_properties.clone().unwrap_or_default(),
+      preserve_root_custom_properties: config.preserve_root_custom_properties.unwrap_or_default(),
+      purge: config.purge.clone(),
+      inline_registered_properties: config
+        .inline_registered_properties
+        .unwrap_or(preset.inline_registered_properties),
+      merge_duplicate_keyframes: config.merge_duplicate_keyframes.unwrap_or(preset.merge_duplicate_keyframes),
+      preserve_rule_structure: config.preserve_rule_structure.unwrap_or(preset.preserve_rule_structure),
+      canonical_order: config.canonical_order.unwrap_or(preset.canonical_order),
+      restructure_rules: config.restructure_rules.unwrap_or(preset.restructure_rules),
+      legacy_scroll_snap: config.legacy_scroll_snap.unwrap_or_default(),
     })?;
 
     stylesheet.to_css(PrinterOptions {
       minify: config.minify.unwrap_or_default(),
       source_map: source_map.as_mut(),
+      source_map_options: Default::default(),
       project_root,
       targets: config.targets,
       analyze_dependencies: if let Some(d) = &config.analyze_dependencies {
@@ -766,11 +933,23 @@ fn compile_bundle<'i, 'o, P: SourceProvider, F: FnOnce(&mut StyleSheet<'i, 'o>)
         None
       },
       pseudo_classes: config.pseudo_classes.as_ref().map(|p| p.into()),
+      preserve_position_keywords: config.preserve_position_keywords.unwrap_or_default(),
+      shortest_units: config.shortest_units.unwrap_or_default(),
+      precision: config.precision.unwrap_or_default(),
+      compression: config.compression.unwrap_or_default(),
+      urls: UrlOptions {
+        output_path: config.output_path.as_deref().map(Path::new),
+        rewrite: None,
+      },
+      banner: config.banner.as_deref(),
+      footer: config.footer.as_deref(),
+      source_comments: config.source_comments.unwrap_or_default(),
+      icss: config.icss.unwrap_or_default(),
     })?
   };
 
   let map = if let Some(source_map) = &mut source_map {
-    source_map.to_json(None).ok()
+    source_map.to_json(config.source_root.as_deref()).ok()
   } else {
     None
   };
@@ -779,6 +958,7 @@ fn compile_bundle<'i, 'o, P: SourceProvider, F: FnOnce(&mut StyleSheet<'i, 'o>)
     code: res.code.into_bytes(),
     map: map.map(|m| m.into_bytes()),
     exports: res.exports,
+    removed_exports: None,
     references: res.references,
     dependencies: res.dependencies,
     warnings: warnings.map_or(Vec::new(), |w| {
@@ -806,6 +986,14 @@ struct AttrConfig {
   pub analyze_dependencies: bool,
   #[serde(default)]
   pub error_recovery: bool,
+  #[serde(default)]
+  pub preserve_position_keywords: bool,
+  #[serde(default)]
+  pub shortest_units: bool,
+  #[serde(default)]
+  pub precision: Precision,
+  #[serde(default)]
+  pub compression: Compression,
 }
 
 #[derive(Serialize)]
@@ -863,6 +1051,7 @@ fn compile_attr<'i>(
     attr.to_css(PrinterOptions {
       minify: config.minify,
       source_map: None,
+      source_map_options: Default::default(),
       project_root: None,
       targets: config.targets,
       analyze_dependencies: if config.analyze_dependencies {
@@ -871,6 +1060,15 @@ fn compile_attr<'i>(
         None
       },
       pseudo_classes: None,
+      preserve_position_keywords: config.preserve_position_keywords,
+      shortest_units: config.shortest_units,
+      precision: config.precision,
+      compression: config.compression,
+      urls: UrlOptions::default(),
+      banner: None,
+      footer: None,
+      source_comments: false,
+      icss: false,
     })?
   };
   Ok(AttrResult {
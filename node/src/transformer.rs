@@ -288,6 +288,9 @@ impl<'i> Visitor<'i> for JsVisitor {
               CssRule::MozDocument(..) => "moz-document",
               CssRule::Nesting(..) => "nesting",
               CssRule::Viewport(..) => "viewport",
+              CssRule::ViewTransition(..) => "view-transition",
+              CssRule::Comment(..) => "comment",
+              CssRule::Value(..) => "value",
               CssRule::Unknown(v) => {
                 let name = v.name.as_ref();
                 if let Some(visit) = rule_map.custom(stage, "unknown", name) {
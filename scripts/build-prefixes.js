@@ -225,6 +225,12 @@ let mdnFeatures = {
   placeContent: mdn.css.properties['place-content'].__compat.support,
   placeItems: mdn.css.properties['place-items'].__compat.support,
   overflowShorthand: mdn.css.properties['overflow'].multiple_keywords.__compat.support,
+  overflowClip: mdn.css.properties['overflow'].clip.__compat.support,
+  overflowClipMargin: mdn.css.properties['overflow-clip-margin'].__compat.support,
+  fieldSizing: mdn.css.properties['field-sizing'].__compat.support,
+  interpolateSize: mdn.css.properties['interpolate-size'].__compat.support,
+  readingFlow: mdn.css.properties['reading-flow'].__compat.support,
+  readingOrder: mdn.css.properties['reading-order'].__compat.support,
   mediaRangeSyntax: mdn.css['at-rules'].media.range_syntax.__compat.support,
   mediaIntervalSyntax: {}, // currently no browsers
   logicalBorders: mdn.css.properties['border-inline-start'].__compat.support,
@@ -236,6 +242,7 @@ let mdnFeatures = {
   logicalPaddingShorthand: mdn.css.properties['padding-inline'].__compat.support,
   logicalInset: mdn.css.properties['inset-inline-start'].__compat.support,
   logicalSize: mdn.css.properties['inline-size'].__compat.support,
+  logicalContainIntrinsicSize: mdn.css.properties['contain-intrinsic-inline-size'].__compat.support,
   logicalTextAlign: mdn.css.properties['text-align']['flow_relative_values_start_and_end'].__compat.support,
   labColors: mdn.css.types.color.lab.__compat.support,
   oklabColors: {},
@@ -262,7 +269,16 @@ let mdnFeatures = {
       })
   ),
   imageSet: mdn.css.types.image['image-set'].__compat.support,
-  xResolutionUnit: mdn.css.types.resolution.x.__compat.support
+  fontSizeAdjustTwoValue: mdn.css.properties['font-size-adjust']['two-values'].__compat.support,
+  xResolutionUnit: mdn.css.types.resolution.x.__compat.support,
+  siblingIndex: mdn.css.types['sibling-index'].__compat.support,
+  siblingCount: mdn.css.types['sibling-count'].__compat.support,
+  subgrid: mdn.css.properties['grid-template-columns'].subgrid.__compat.support,
+  // toggle() is not yet implemented by any browser.
+  toggle: {},
+  transitionBehavior: mdn.css.properties['transition-behavior'].__compat.support,
+  textWrapStyle: mdn.css.properties['text-wrap-style'].__compat.support,
+  whiteSpaceCollapse: mdn.css.properties['white-space-collapse'].__compat.support
 };
 
 for (let feature in mdnFeatures) {
@@ -315,14 +331,76 @@ let prefixMapping = {
   o: 'O'
 };
 
+let kebabify = (f) => f.replace(/([A-Z])([A-Z][a-z])/g, '$1-$2').replace(/([a-z0-9])([A-Z])/g, '$1-$2').toLowerCase();
 let enumify = (f) => f.replace(/^@([a-z])/, (_, x) => 'At' + x.toUpperCase()).replace(/^::([a-z])/, (_, x) => 'PseudoElement' + x.toUpperCase()).replace(/^:([a-z])/, (_, x) => 'PseudoClass' + x.toUpperCase()).replace(/(^|-)([a-z])/g, (_, a, x) => x.toUpperCase())
 
 let allBrowsers = Object.keys(browsers).filter(b => !(b in BROWSER_MAPPING)).sort();
 let targets = fs.readFileSync('src/targets.rs', 'utf8')
   .replace(/pub struct Browsers \{((?:.|\n)+?)\}/, `pub struct Browsers {
-  pub ${allBrowsers.join(': Option<u32>,\n  pub ')}: Option<u32>
+  pub ${allBrowsers.join(': Option<u32>,\n  pub ')}: Option<u32>,
+  /// Compatibility data that overrides the data built into Lightning CSS.
+  /// See [with_feature_data](Browsers::with_feature_data).
+  #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))]
+  pub feature_data: Option<&'static FeatureData>,
+  /// Features that are always lowered, regardless of browser support.
+  #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))]
+  pub include: Features,
+  /// Features that are never lowered, regardless of browser support.
+  /// These take precedence over \`include\`.
+  #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))]
+  pub exclude: Features
 }`);
 
+// Named target presets, resolved from browserslist queries using the bundled caniuse data.
+const TARGET_PRESETS = [
+  {
+    name: 'defaults',
+    query: 'defaults',
+    doc: 'Browsers matching the browserslist `defaults` query, i.e. `> 0.5%, last 2 versions, Firefox ESR, not dead`.\nThis is a sensible default for most websites.'
+  },
+  {
+    name: 'modern',
+    query: 'chrome >= 61, edge >= 79, firefox >= 60, safari >= 11, ios_saf >= 11, opera >= 48, samsung >= 8.2, android >= 61',
+    doc: 'Browsers that support ES modules and CSS custom properties, which is a common baseline for\napplications that ship a modern build, e.g. Chrome 61, Firefox 60, and Safari 11.'
+  },
+  {
+    name: 'legacy',
+    query: 'ie >= 11, edge >= 12, chrome >= 49, firefox >= 52, safari >= 9, ios_saf >= 9, opera >= 36, samsung >= 5, android >= 4.4',
+    doc: 'Older browsers that are still found in the wild, including Internet Explorer 11, Android 4.4 and Safari 9.\nThis enables most of the lowering and prefixing that Lightning CSS supports.'
+  },
+  {
+    name: 'fully_supported',
+    query: 'last 1 version, not dead',
+    doc: 'The latest version of each browser that is not dead. Only features that are not yet supported by\ncurrent browsers are lowered.'
+  }
+];
+
+let presets = TARGET_PRESETS.map(({name, query, doc}) => {
+  let versions = {};
+  for (let entry of require('browserslist')(query)) {
+    let [browser, version] = entry.split(' ');
+    browser = BROWSER_MAPPING[browser] === undefined ? browser : BROWSER_MAPPING[browser];
+    let v = parseVersion(version);
+    if (browser && v != null && (versions[browser] == null || v < versions[browser])) {
+      versions[browser] = v;
+    }
+  }
+
+  return `  ${doc.split('\n').map(l => '/// ' + l).join('\n  ')}
+  pub fn ${name}() -> Browsers {
+    Browsers {
+      ${Object.keys(versions).sort().map(b => `${b}: Some(${versions[b]}),`).join('\n      ')}
+      ..Browsers::default()
+    }
+  }`;
+});
+
+targets = targets.replace(/\/\/ Target presets\.(?:.|\n)+?\n\}\n/, `// Target presets. These are generated by build-prefixes.js from the bundled browserslist and caniuse data.
+impl Browsers {
+${presets.join('\n\n')}
+}
+`);
+
 fs.writeFileSync('src/targets.rs', targets);
 execSync('rustfmt src/targets.rs');
 
@@ -341,12 +419,17 @@ use crate::vendor_prefix::VendorPrefix;
 use crate::targets::Browsers;
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Feature {
   ${[...p.keys()].flat().map(enumify).sort().join(',\n  ')}
 }
 
 impl Feature {
   pub fn prefixes_for(&self, browsers: Browsers) -> VendorPrefix {
+    if let Some(prefixes) = browsers.feature_data.and_then(|data| data.prefixes_for(*self, browsers)) {
+      return prefixes;
+    }
+
     let mut prefixes = VendorPrefix::None;
     match self {
       ${[...p].map(([features, versions]) => {
@@ -363,7 +446,7 @@ impl Feature {
             } else if (min == max) {
               condition = `version == ${min}`;
             } else {
-              condition = `version >= ${min} && version <= ${max}`;
+              condition = `(${min}..=${max}).contains(&version)`;
             }
 
               return `if ${condition} {
@@ -377,12 +460,19 @@ impl Feature {
     }
     prefixes
   }
+
+  pub fn from_name(name: &str) -> Option<Feature> {
+    Some(match name {
+      ${[...p.keys()].flat().sort().map(name => `${JSON.stringify(name)} => Feature::${enumify(name)}`).join(',\n      ')},
+      _ => return None
+    })
+  }
 }
 
 pub fn is_flex_2009(browsers: Browsers) -> bool {
   ${Object.entries(flexSpec).map(([name, [min, max]]) => {
     return `if let Some(version) = browsers.${name} {
-    if version >= ${min} && version <= ${max} {
+    if (${min}..=${max}).contains(&version) {
       return true;
     }
   }`;
@@ -393,7 +483,7 @@ pub fn is_flex_2009(browsers: Browsers) -> bool {
 pub fn is_webkit_gradient(browsers: Browsers) -> bool {
   ${Object.entries(oldGradient).map(([name, [min, max]]) => {
     return `if let Some(version) = browsers.${name} {
-    if version >= ${min} && version <= ${max} {
+    if (${min}..=${max}).contains(&version) {
       return true;
     }
   }`;
@@ -409,13 +499,17 @@ let c = `// This file is autogenerated by build-prefixes.js. DO NOT EDIT!
 
 use crate::targets::Browsers;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Feature {
   ${[...compat.keys()].flat().map(enumify).sort().join(',\n  ')}
 }
 
 impl Feature {
   pub fn is_compatible(&self, browsers: Browsers) -> bool {
+    if let Some(compatible) = browsers.compatibility_override(*self) {
+      return compatible;
+    }
+
     match self {
       ${[...compat].map(([features, supportedBrowsers]) =>
         `${features.map(name => `Feature::${enumify(name)}`).join(' |\n      ')} => {` + (Object.entries(supportedBrowsers).length === 0 ? '\n        return false\n      }' : `
@@ -434,7 +528,12 @@ impl Feature {
   }
 
   pub fn is_partially_compatible(&self, targets: Browsers) -> bool {
-    let mut browsers = Browsers::default();
+    let mut browsers = Browsers {
+      feature_data: targets.feature_data,
+      include: targets.include,
+      exclude: targets.exclude,
+      ..Browsers::default()
+    };
     ${allBrowsers.map(browser => `if targets.${browser}.is_some() {
       browsers.${browser} = targets.${browser};
       if self.is_compatible(browsers) {
@@ -444,6 +543,19 @@ impl Feature {
     }\n`).join('    ')}
     false
   }
+
+  pub fn from_name(name: &str) -> Option<Feature> {
+    Some(match name {
+      ${[...compat.keys()].flat().map(enumify).sort().map(name => `"${kebabify(name)}" => Feature::${name}`).join(',\n      ')},
+      _ => return None
+    })
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      ${[...compat.keys()].flat().map(enumify).sort().map(name => `Feature::${name} => "${kebabify(name)}"`).join(',\n      ')}
+    }
+  }
 }
 `;
 
@@ -7,7 +7,23 @@
 //! of the dependencies of the entry file, recursively.
 //!
 //! Rules are bundled following `@import` order, and wrapped in the necessary `@media`, `@supports`,
-//! and `@layer` rules as appropriate to preserve the authored behavior.
+//! and `@layer` rules as appropriate to preserve the authored behavior. The conditions of nested
+//! imports are intersected, e.g. `print` and `(min-width: 500px)` become `print and (min-width: 500px)`.
+//! Conditions that cannot be intersected, such as `(color)` and `not print`, or nested anonymous
+//! layers, result in nested rules instead.
+//!
+//! Import specifiers may contain glob patterns, e.g. `@import "components/**/*.css"`. These are
+//! resolved to a list of files by [SourceProvider::resolve_glob](SourceProvider::resolve_glob),
+//! and each matching file is imported in sorted path order, as if it had its own `@import` rule.
+//!
+//! Files that are imported via different paths but have identical contents, e.g. copies of a vendored
+//! file or packages symlinked into several workspaces, can be emitted once with
+//! [Bundler::set_duplicate_imports](Bundler::set_duplicate_imports).
+//!
+//! When printing a bundle, the [banner](crate::stylesheet::PrinterOptions::banner) and
+//! [footer](crate::stylesheet::PrinterOptions::footer) options add e.g. a license header to the output,
+//! and [source_comments](crate::stylesheet::PrinterOptions::source_comments) marks where the rules from
+//! each file begin with a `/* source: path.css */` comment.
 //!
 //! # Example
 //!
@@ -22,9 +38,69 @@
 //! let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
 //! let stylesheet = bundler.bundle(Path::new("style.css")).unwrap();
 //! ```
+//!
+//! # Multiple entries
+//!
+//! [Bundler::bundle_entries](Bundler::bundle_entries) bundles several entry files at once into a
+//! [ChunkGraph](ChunkGraph). Files imported by more than one entry are emitted into shared chunks,
+//! and a manifest lists the chunks each entry needs, in the order they must be loaded.
+//!
+//! # Watch mode
+//!
+//! [Bundler::rebundle](Bundler::rebundle) keeps the parsed style sheets between builds. Given the files
+//! that changed since the last build, only those are parsed and resolved again, and
+//! [Bundler::import_graph](Bundler::import_graph) describes the files to watch.
+//!
+//! # Async source providers
+//!
+//! Build tools that read files asynchronously, e.g. over the network or from a virtual file system,
+//! can implement [AsyncSourceProvider](AsyncSourceProvider) instead, and wrap it in a
+//! [PrefetchProvider](PrefetchProvider). Awaiting [PrefetchProvider::load](PrefetchProvider::load)
+//! reads and resolves the entry file and its dependencies concurrently, after which the bundler
+//! reads them from memory without blocking.
+//!
+//! ```no_run
+//! # use std::path::Path;
+//! # use lightningcss::{bundler::{AsyncSourceProvider, Bundler, PrefetchProvider}, stylesheet::ParserOptions};
+//! # async fn bundle<P: AsyncSourceProvider>(fs: &P) {
+//! let provider = PrefetchProvider::new(fs);
+//! provider.load(Path::new("style.css")).await;
+//! let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+//! let stylesheet = bundler.bundle(Path::new("style.css")).unwrap();
+//! # }
+//! ```
+//!
+//! # Virtual modules
+//!
+//! Generated CSS can be injected into the import graph without a file on disk by registering it with
+//! [Bundler::add_virtual_module](Bundler::add_virtual_module), e.g. under a `virtual:theme.css`
+//! specifier. Virtual modules are resolved before the source provider is consulted.
+//!
+//! # Remote imports
+//!
+//! By default, `http://` and `https://` imports are passed to the source provider like any other
+//! specifier. A [RemoteProvider](RemoteProvider) wraps another provider, and fetches remote imports
+//! using a [RemoteFetcher](RemoteFetcher) instead, so that style sheets hosted on a CDN can be
+//! bundled at build time, optionally verified against integrity hashes.
+//!
+//! # Packages
+//!
+//! A [PackageProvider](PackageProvider) wraps another provider to resolve imports of npm packages,
+//! such as `@import "normalize.css"` or `@import "~bootstrap/dist/css/bootstrap.css"`, from
+//! `node_modules` directories using the `exports` and `style` fields of `package.json`.
+//!
+//! # Inlining assets
+//!
+//! An [AssetInliner](AssetInliner) can be used when printing a bundled style sheet to replace
+//! references to small images and fonts with `data:` URIs, and report which assets were inlined.
+//! Similarly, an [AssetHasher](AssetHasher) rewrites references to assets to file names containing
+//! a hash of their contents, and returns a manifest of the files to copy.
 
 use crate::{
-  error::ErrorLocation,
+  css_modules::{self, value_placeholder, CssModuleExports},
+  declaration::DeclarationBlock,
+  error::{ErrorLocation, PrinterErrorKind},
+  printer::{Printer, PrinterOptions, UrlKind, UrlReference},
   properties::{
     css_modules::Specifier,
     custom::{
@@ -34,10 +110,13 @@ use crate::{
   },
   rules::{
     layer::{LayerBlockRule, LayerName},
+    value::{ModuleValue, ValueRule},
     Location,
   },
+  sourcemap::source_map_from_json,
   traits::ToCss,
-  values::ident::DashedIdentReference,
+  unused_symbols::matches_pattern,
+  values::{ident::DashedIdentReference, string::CowArcStr, url::is_absolute_url},
 };
 use crate::{
   error::{Error, ParserError},
@@ -50,15 +129,19 @@ use crate::{
   },
   stylesheet::{ParserOptions, StyleSheet},
 };
-use cssparser::AtRuleParser;
+use cssparser::{AtRuleParser, ParseError, Parser, ParserInput, Token};
 use dashmap::DashMap;
+use data_encoding::{BASE64, HEXLOWER};
 use parcel_sourcemap::SourceMap;
 use rayon::prelude::*;
 use std::{
-  collections::HashSet,
+  collections::{HashMap, HashSet},
   fs,
+  future::Future,
   path::{Path, PathBuf},
+  pin::Pin,
   sync::Mutex,
+  task::{Context, Poll},
 };
 
 /// A Bundler combines a CSS file and all imported dependencies together into
@@ -69,18 +152,150 @@ pub struct Bundler<'a, 'o, 's, P, T: AtRuleParser<'a>> {
   source_indexes: DashMap<PathBuf, u32>,
   stylesheets: Mutex<Vec<BundleStyleSheet<'a, 'o, T>>>,
   options: ParserOptions<'o, 'a, T>,
+  circular_imports: CircularImports,
+  cycles: Vec<Vec<ErrorLocation>>,
+  external: Vec<String>,
+  virtual_modules: HashMap<String, &'a str>,
+  /// Whether the bundler is used for [rebundle](Bundler::rebundle). The conditions of each file are then
+  /// computed when emitting the bundle rather than while loading, since files are loaded across builds.
+  incremental: bool,
+  duplicate_imports: DuplicateImports,
+  content_hashes: DashMap<String, u32>,
+  /// The values imported by the `@value` rules of each file, keyed by the placeholders that
+  /// references to them are replaced with while parsing.
+  imported_values: HashMap<u32, ModuleValueMap<'a>>,
+}
+
+/// A map of CSS modules values to the tokens they are replaced with.
+type ModuleValueMap<'i> = HashMap<CowArcStr<'i>, TokenList<'i>>;
+
+/// The leading `@layer` statements and wrapped rules of a file in a chunk.
+type ChunkRules<'i, R> = (Vec<CssRule<'i, R>>, Vec<CssRule<'i, R>>);
+
+/// The result of bundling multiple entries with [Bundler::bundle_entries](Bundler::bundle_entries).
+pub struct ChunkGraph<'i, 'o, T: AtRuleParser<'i>> {
+  /// The chunks, in order of first use by the entries.
+  pub chunks: Vec<Chunk<'i, 'o, T>>,
+  /// For each entry, in the order they were given, the indexes of the chunks it needs
+  /// in the order they must be loaded.
+  pub manifest: Vec<Vec<usize>>,
+}
+
+/// A chunk in a [ChunkGraph](ChunkGraph).
+pub struct Chunk<'i, 'o, T: AtRuleParser<'i>> {
+  /// The indexes of the entries that need the chunk. A chunk needed by multiple entries
+  /// is shared, and contains the files imported by all of them.
+  pub entries: Vec<usize>,
+  /// The paths of the files included in the chunk, in the order they are concatenated.
+  pub files: Vec<PathBuf>,
+  /// The style sheet for the chunk.
+  pub stylesheet: StyleSheet<'i, 'o, T>,
+}
+
+/// Determines how the bundler handles circular `@import` rules.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CircularImports {
+  /// Ignore the `@import` rule that closes a cycle, as browsers do. The cycles that were
+  /// found are available from [Bundler::circular_imports](Bundler::circular_imports).
+  #[default]
+  Ignore,
+  /// Fail with a [CircularImport](BundleErrorKind::CircularImport) error.
+  Error,
+}
+
+/// Determines how the bundler handles files with identical contents that are imported from
+/// different paths, e.g. via symlinked workspace packages or copies of vendored files.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DuplicateImports {
+  /// Bundle each path separately.
+  #[default]
+  Keep,
+  /// Emit the rules of files with identical contents once, as if they were imported from the same path.
+  Dedupe,
+  /// Deduplicate the files, and add a [DuplicateImport](ParserError::DuplicateImport) warning
+  /// for each duplicate to the [warnings](ParserOptions::warnings) of the parser options.
+  Warn,
 }
 
 struct BundleStyleSheet<'i, 'o, T: AtRuleParser<'i>> {
   stylesheet: Option<StyleSheet<'i, 'o, T>>,
-  dependencies: Vec<u32>,
+  dependencies: Vec<ImportDependency<'i>>,
   css_modules_deps: Vec<u32>,
+  /// The source indexes of the files that values are imported from by `@value` rules, keyed by specifier.
+  value_deps: HashMap<String, u32>,
   parent_source_index: u32,
   parent_dep_index: u32,
   layer: Option<Option<LayerName<'i>>>,
   supports: Option<SupportsCondition<'i>>,
   media: MediaList<'i>,
+  outer: Vec<ImportRule<'i>>,
+  loc: Location,
+  content_hash: String,
+  /// The source index of a file with the same contents that this file was deduplicated into.
+  duplicate_of: Option<u32>,
+}
+
+/// The conditions that the rules of a file are imported with.
+#[derive(Clone)]
+struct ImportConditions<'i> {
+  /// Conditions of ancestor `@import` rules that cannot be intersected with the ones below them,
+  /// e.g. nested anonymous layers. These are kept as separate wrapper rules, outermost first.
+  outer: Vec<ImportRule<'i>>,
+  /// The combined conditions of the remaining `@import` rules.
+  rule: ImportRule<'i>,
+}
+
+impl<'i> ImportConditions<'i> {
+  fn new(rule: ImportRule<'i>) -> Self {
+    ImportConditions {
+      outer: Vec::new(),
+      rule,
+    }
+  }
+
+  /// Returns whether the conditions are the same, ignoring locations.
+  fn is_equivalent(&self, other: &ImportConditions<'i>) -> bool {
+    same_conditions(&self.rule, &other.rule) && same_outer_conditions(&self.outer, &other.outer)
+  }
+
+  /// Serializes the conditions of each level, outermost first, skipping empty levels.
+  fn to_strings(&self) -> Vec<String> {
+    self
+      .outer
+      .iter()
+      .chain(std::iter::once(&self.rule))
+      .filter_map(|rule| {
+        let mut s = String::new();
+        let mut printer = Printer::new(&mut s, PrinterOptions::default());
+        rule.conditions_to_css(&mut printer).ok()?;
+        let s = s.trim_start();
+        (!s.is_empty()).then(|| s.to_owned())
+      })
+      .collect()
+  }
+}
+
+/// Returns whether two `@import` rules have the same conditions, ignoring their urls and locations.
+fn same_conditions<'i>(a: &ImportRule<'i>, b: &ImportRule<'i>) -> bool {
+  a.media == b.media && a.supports == b.supports && a.layer == b.layer
+}
+
+fn same_outer_conditions<'i>(a: &[ImportRule<'i>], b: &[ImportRule<'i>]) -> bool {
+  a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_conditions(a, b))
+}
+
+/// The files loaded for an `@import` rule.
+struct ImportDependency<'i> {
+  /// The location of the `@import` rule.
   loc: Location,
+  /// The source indexes of the imported files, or empty if the import is external.
+  source_indexes: Vec<u32>,
+  /// The `@import` rule, with its own conditions.
+  import: ImportRule<'i>,
+  /// The conditions of the `@import` rule combined with those of its parents.
+  conditions: ImportConditions<'i>,
+  /// An external import to preserve in the output, with the conditions of its parents.
+  external: Option<ImportRule<'i>>,
 }
 
 /// A trait to provide the contents of files to a Bundler.
@@ -97,6 +312,30 @@ pub trait SourceProvider: Send + Sync {
   /// Resolves the given import specifier to a file path given the file
   /// which the import originated from.
   fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error>;
+
+  /// Reads the contents of the given file path as bytes. This is used to read assets such as
+  /// images and fonts, e.g. by an [AssetInliner](AssetInliner). By default, the file is read
+  /// as a string using [read](SourceProvider::read).
+  fn read_bytes(&self, file: &Path) -> Result<Vec<u8>, Self::Error> {
+    Ok(self.read(file)?.as_bytes().to_vec())
+  }
+
+  /// Resolves an import specifier containing a glob pattern to the paths of the matching files,
+  /// given the file which the import originated from. `*` matches any characters except `/`, `?`
+  /// matches a single character, `[...]` matches a character class, and a `**` path segment
+  /// matches any number of directories. See [glob_match](glob_match).
+  ///
+  /// The bundler sorts the returned paths, so they may be returned in any order. By default,
+  /// the pattern is resolved as a single file using [resolve](SourceProvider::resolve).
+  fn resolve_glob(&self, pattern: &str, originating_file: &Path) -> Result<Vec<PathBuf>, Self::Error> {
+    Ok(vec![self.resolve(pattern, originating_file)?])
+  }
+
+  /// Returns whether the given file exists. This is used to look up packages, e.g. by a
+  /// [PackageProvider](PackageProvider). By default, the file is read using [read](SourceProvider::read).
+  fn exists(&self, file: &Path) -> bool {
+    self.read(file).is_ok()
+  }
 }
 
 /// Provides an implementation of [SourceProvider](SourceProvider)
@@ -134,6 +373,123 @@ impl SourceProvider for FileProvider {
     // Assume the specifier is a releative file path and join it with current path.
     Ok(originating_file.with_file_name(specifier))
   }
+
+  fn read_bytes(&self, file: &Path) -> Result<Vec<u8>, Self::Error> {
+    fs::read(file)
+  }
+
+  fn exists(&self, file: &Path) -> bool {
+    file.is_file()
+  }
+
+  fn resolve_glob(&self, pattern: &str, originating_file: &Path) -> Result<Vec<PathBuf>, Self::Error> {
+    // Walk from the deepest directory in the pattern without glob characters.
+    let segments: Vec<&str> = pattern.split('/').collect();
+    let base_len = segments.iter().position(|segment| is_glob(segment)).unwrap_or(segments.len());
+    let dir = originating_file.with_file_name(segments[..base_len].join("/"));
+    let pattern = segments[base_len..].join("/");
+    let max_depth = if segments[base_len..].contains(&"**") {
+      usize::MAX
+    } else {
+      segments.len() - base_len
+    };
+
+    let mut files = Vec::new();
+    walk_dir(&dir, max_depth, &mut files)?;
+    files.retain(|file| {
+      let relative = file.strip_prefix(&dir).unwrap_or(file);
+      let relative = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+      glob_match(&pattern, &relative)
+    });
+    Ok(files)
+  }
+}
+
+/// Collects the files in a directory and its subdirectories, up to the given depth.
+fn walk_dir(dir: &Path, max_depth: usize, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+  if max_depth == 0 {
+    return Ok(());
+  }
+
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      walk_dir(&path, max_depth - 1, files)?;
+    } else {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+/// Returns whether an import specifier contains glob characters.
+fn is_glob(specifier: &str) -> bool {
+  specifier.contains(['*', '?', '['])
+}
+
+/// Returns whether a `/` separated path matches a glob pattern, as used by
+/// [SourceProvider::resolve_glob](SourceProvider::resolve_glob).
+///
+/// * `*` matches any sequence of characters except `/`.
+/// * `?` matches any single character except `/`.
+/// * `[abc]` and `[a-z]` match any character in the class, and `[!abc]` any character not in it.
+/// * `**` as a whole path segment matches zero or more path segments.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+  fn match_segments(patterns: &[&str], segments: &[&str]) -> bool {
+    match patterns.split_first() {
+      None => segments.is_empty(),
+      Some((&"**", rest)) => (0..=segments.len()).any(|i| match_segments(rest, &segments[i..])),
+      Some((pattern, rest)) => match segments.split_first() {
+        Some((segment, segments)) => {
+          let pattern: Vec<char> = pattern.chars().collect();
+          let segment: Vec<char> = segment.chars().collect();
+          match_segment(&pattern, &segment) && match_segments(rest, segments)
+        }
+        None => false,
+      },
+    }
+  }
+
+  fn match_segment(pattern: &[char], segment: &[char]) -> bool {
+    match pattern.split_first() {
+      None => segment.is_empty(),
+      Some(('*', rest)) => (0..=segment.len()).any(|i| match_segment(rest, &segment[i..])),
+      Some(('?', rest)) => !segment.is_empty() && match_segment(rest, &segment[1..]),
+      Some(('[', rest)) if rest.contains(&']') => {
+        let end = rest.iter().skip(1).position(|c| *c == ']').map_or(0, |i| i + 1);
+        let (class, negated) = match rest[..end].split_first() {
+          Some(('!', class)) => (class, true),
+          _ => (&rest[..end], false),
+        };
+        match segment.split_first() {
+          Some((c, segment)) => {
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+              if i + 2 < class.len() && class[i + 1] == '-' {
+                matched |= (class[i]..=class[i + 2]).contains(c);
+                i += 3;
+              } else {
+                matched |= class[i] == *c;
+                i += 1;
+              }
+            }
+            matched != negated && match_segment(&rest[end + 1..], segment)
+          }
+          None => false,
+        }
+      }
+      Some((c, rest)) => segment.first() == Some(c) && match_segment(rest, &segment[1..]),
+    }
+  }
+
+  let patterns: Vec<&str> = pattern.split('/').collect();
+  let segments: Vec<&str> = path.split('/').collect();
+  match_segments(&patterns, &segments)
 }
 
 impl Drop for FileProvider {
@@ -144,725 +500,2903 @@ impl Drop for FileProvider {
   }
 }
 
-/// An error that could occur during bundling.
-#[derive(Debug)]
-#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(serde::Serialize))]
-pub enum BundleErrorKind<'i, T: std::error::Error> {
-  /// A parser error occurred.
-  ParserError(ParserError<'i>),
-  /// An unsupported `@import` condition was encountered.
-  UnsupportedImportCondition,
-  /// An unsupported cascade layer combination was encountered.
-  UnsupportedLayerCombination,
-  /// Unsupported media query boolean logic was encountered.
-  UnsupportedMediaBooleanLogic,
-  /// A custom resolver error.
-  ResolverError(#[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))] T),
+/// A future returned by an [AsyncSourceProvider](AsyncSourceProvider).
+pub type SourceFuture<'a, T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'a>>;
+
+/// A trait to asynchronously provide the contents of files to a Bundler, e.g. by reading
+/// them over the network or from a virtual file system. It is used via a
+/// [PrefetchProvider](PrefetchProvider), which loads all files before bundling.
+///
+/// The returned futures may only borrow the provider, not the arguments.
+pub trait AsyncSourceProvider: Send + Sync {
+  /// A custom error.
+  type Error: std::error::Error + Send + Sync;
+
+  /// Reads the contents of the given file path to a string.
+  fn read<'a>(&'a self, file: &Path) -> SourceFuture<'a, &'a str, Self::Error>;
+
+  /// Resolves the given import specifier to a file path given the file
+  /// which the import originated from.
+  fn resolve<'a>(&'a self, specifier: &str, originating_file: &Path) -> SourceFuture<'a, PathBuf, Self::Error>;
 }
 
-impl<'i, T: std::error::Error> From<Error<ParserError<'i>>> for Error<BundleErrorKind<'i, T>> {
-  fn from(err: Error<ParserError<'i>>) -> Self {
-    Error {
-      kind: BundleErrorKind::ParserError(err.kind),
-      loc: err.loc,
-    }
-  }
+/// Provides an implementation of [SourceProvider](SourceProvider) on top of an
+/// [AsyncSourceProvider](AsyncSourceProvider).
+///
+/// Before bundling, [load](PrefetchProvider::load) must be awaited for the entry file. This reads the
+/// file, resolves the specifiers of its `@import` rules and CSS module references, and loads the
+/// resolved files recursively, running the operations for each level of the import graph concurrently.
+/// The bundler then reads the files and resolutions from memory. Errors are reported by the bundler
+/// with the location of the import that caused them.
+pub struct PrefetchProvider<'a, P: AsyncSourceProvider> {
+  fs: &'a P,
+  sources: DashMap<PathBuf, &'a str>,
+  resolved: DashMap<(PathBuf, String), PathBuf>,
+  errors: DashMap<(PathBuf, Option<String>), P::Error>,
 }
 
-impl<'i, T: std::error::Error> std::fmt::Display for BundleErrorKind<'i, T> {
+/// An error returned by a [PrefetchProvider](PrefetchProvider).
+#[derive(Debug)]
+pub enum PrefetchError<E> {
+  /// An error returned by the underlying provider.
+  Provider(E),
+  /// The file was not loaded before bundling.
+  NotLoaded(PathBuf),
+  /// The specifier was not resolved before bundling.
+  NotResolved(String),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for PrefetchError<E> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    use BundleErrorKind::*;
     match self {
-      ParserError(err) => err.fmt(f),
-      UnsupportedImportCondition => write!(f, "Unsupported import condition"),
-      UnsupportedLayerCombination => write!(f, "Unsupported layer combination in @import"),
-      UnsupportedMediaBooleanLogic => write!(f, "Unsupported boolean logic in @import media query"),
-      ResolverError(err) => std::fmt::Display::fmt(&err, f),
+      PrefetchError::Provider(err) => err.fmt(f),
+      PrefetchError::NotLoaded(file) => write!(f, "File was not loaded: {}", file.display()),
+      PrefetchError::NotResolved(specifier) => write!(f, "Specifier was not resolved: {}", specifier),
     }
   }
 }
 
-impl<'i, T: std::error::Error> BundleErrorKind<'i, T> {
-  #[deprecated(note = "use `BundleErrorKind::to_string()` or `std::fmt::Display` instead")]
-  #[allow(missing_docs)]
-  pub fn reason(&self) -> String {
-    self.to_string()
-  }
-}
+impl<E: std::error::Error> std::error::Error for PrefetchError<E> {}
 
-impl<'a, 'o, 's, P: SourceProvider, T: AtRuleParser<'a> + Clone + Sync + Send> Bundler<'a, 'o, 's, P, T>
-where
-  T::AtRule: Sync + Send + ToCss,
-{
-  /// Creates a new Bundler using the given source provider.
-  /// If a source map is given, the content of each source file included in the bundle will
-  /// be added accordingly.
-  pub fn new(fs: &'a P, source_map: Option<&'s mut SourceMap>, options: ParserOptions<'o, 'a, T>) -> Self {
-    Bundler {
-      source_map: source_map.map(Mutex::new),
+impl<'a, P: AsyncSourceProvider> PrefetchProvider<'a, P> {
+  /// Creates a new PrefetchProvider.
+  pub fn new(fs: &'a P) -> Self {
+    PrefetchProvider {
       fs,
-      source_indexes: DashMap::new(),
-      stylesheets: Mutex::new(Vec::new()),
-      options,
+      sources: DashMap::new(),
+      resolved: DashMap::new(),
+      errors: DashMap::new(),
     }
   }
 
-  /// Bundles the given entry file and all dependencies into a single style sheet.
-  pub fn bundle<'e>(
-    &mut self,
-    entry: &'e Path,
-  ) -> Result<StyleSheet<'a, 'o, T>, Error<BundleErrorKind<'a, P::Error>>> {
-    // Phase 1: load and parse all files. This is done in parallel.
-    self.load_file(
-      &entry,
-      ImportRule {
-        url: "".into(),
-        layer: None,
-        supports: None,
-        media: MediaList::new(),
-        loc: Location {
-          source_index: 0,
-          line: 0,
-          column: 1,
-        },
-      },
-    )?;
-
-    // Phase 2: determine the order that the files should be concatenated.
-    self.order();
+  /// Loads the given entry file and all of its dependencies, recursively. Files that were
+  /// already loaded are skipped, so this can be called for multiple entries.
+  pub async fn load(&self, entry: &Path) {
+    let mut pending = vec![entry.to_owned()];
+    while !pending.is_empty() {
+      let futures = pending
+        .drain(..)
+        .filter(|file| !self.sources.contains_key(file))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .map(|file| Box::pin(self.load_file(file)))
+        .collect();
+
+      for files in JoinAll::new(futures).await {
+        pending.extend(files);
+      }
+    }
+  }
 
-    // Phase 3: concatenate.
-    let mut rules: Vec<CssRule<'a, T::AtRule>> = Vec::new();
-    self.inline(&mut rules);
+  /// Reads a file and resolves its dependencies, returning the resolved paths.
+  async fn load_file(&self, file: PathBuf) -> Vec<PathBuf> {
+    let code = match self.fs.read(&file).await {
+      Ok(code) => code,
+      Err(err) => {
+        self.errors.insert((file, None), err);
+        return Vec::new();
+      }
+    };
+    self.sources.insert(file.clone(), code);
+
+    // The parser is not `Send`, so it must be dropped before awaiting.
+    let specifiers = {
+      let mut input = ParserInput::new(code);
+      let mut parser = Parser::new(&mut input);
+      let mut specifiers = Vec::new();
+      scan_dependencies(&mut parser, true, &mut specifiers);
+      specifiers
+    };
 
-    let sources = self
-      .stylesheets
-      .get_mut()
-      .unwrap()
-      .iter()
-      .flat_map(|s| s.stylesheet.as_ref().unwrap().sources.iter().cloned())
+    let futures = specifiers
+      .into_iter()
+      .filter(|specifier| !self.resolved.contains_key(&(file.clone(), specifier.clone())))
+      .map(|specifier| {
+        let future = self.fs.resolve(&specifier, &file);
+        Box::pin(async move { (specifier, future.await) })
+      })
       .collect();
 
-    let mut stylesheet = StyleSheet::new(sources, CssRuleList(rules), self.options.clone());
+    let mut files = Vec::new();
+    for (specifier, result) in JoinAll::new(futures).await {
+      match result {
+        Ok(path) => {
+          files.push(path.clone());
+          self.resolved.insert((file.clone(), specifier), path);
+        }
+        Err(err) => {
+          self.errors.insert((file.clone(), Some(specifier)), err);
+        }
+      }
+    }
+    files
+  }
+}
 
-    stylesheet.source_map_urls = self
-      .stylesheets
-      .get_mut()
-      .unwrap()
-      .iter()
-      .flat_map(|s| s.stylesheet.as_ref().unwrap().source_map_urls.iter().cloned())
-      .collect();
+impl<'a, P: AsyncSourceProvider> SourceProvider for PrefetchProvider<'a, P> {
+  type Error = PrefetchError<P::Error>;
 
-    Ok(stylesheet)
-  }
+  fn read<'b>(&'b self, file: &Path) -> Result<&'b str, Self::Error> {
+    if let Some(code) = self.sources.get(file) {
+      return Ok(*code);
+    }
 
-  fn find_filename(&self, source_index: u32) -> String {
-    // This function is only used for error handling, so it's ok if this is a bit slow.
-    let entry = self.source_indexes.iter().find(|x| *x.value() == source_index).unwrap();
-    entry.key().to_str().unwrap().into()
+    match self.errors.remove(&(file.to_owned(), None)) {
+      Some((_, err)) => Err(PrefetchError::Provider(err)),
+      None => Err(PrefetchError::NotLoaded(file.to_owned())),
+    }
   }
 
-  fn load_file(&self, file: &Path, rule: ImportRule<'a>) -> Result<u32, Error<BundleErrorKind<'a, P::Error>>> {
-    // Check if we already loaded this file.
-    let mut stylesheets = self.stylesheets.lock().unwrap();
-    let source_index = match self.source_indexes.get(file) {
-      Some(source_index) => {
-        // If we already loaded this file, combine the media queries and supports conditions
-        // from this import rule with the existing ones using a logical or operator.
-        let entry = &mut stylesheets[*source_index as usize];
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error> {
+    let key = (originating_file.to_owned(), specifier.to_owned());
+    if let Some(path) = self.resolved.get(&key) {
+      return Ok(path.clone());
+    }
 
-        // We cannot combine a media query and a supports query from different @import rules.
-        // e.g. @import "a.css" print; @import "a.css" supports(color: red);
-        // This would require duplicating the actual rules in the file.
-        if (!rule.media.media_queries.is_empty() && !entry.supports.is_none())
-          || (!entry.media.media_queries.is_empty() && !rule.supports.is_none())
-        {
-          return Err(Error {
-            kind: BundleErrorKind::UnsupportedImportCondition,
-            loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
-          });
-        }
+    match self.errors.remove(&(key.0, Some(key.1))) {
+      Some((_, err)) => Err(PrefetchError::Provider(err)),
+      None => Err(PrefetchError::NotResolved(specifier.to_owned())),
+    }
+  }
+}
 
-        if rule.media.media_queries.is_empty() {
-          entry.media.media_queries.clear();
-        } else if !entry.media.media_queries.is_empty() {
-          entry.media.or(&rule.media);
-        }
+/// Collects the specifiers that a file may depend on: the urls of `@import` rules at the top level,
+/// and strings following a `from` keyword, as in `composes: a from "a.css"` and `var(--a from "a.css")`.
+/// This may include specifiers that the bundler never resolves, e.g. in invalid rules.
+fn scan_dependencies<'i, 't>(input: &mut Parser<'i, 't>, top_level: bool, specifiers: &mut Vec<String>) {
+  let mut push = |specifier: &str| {
+    if !specifiers.iter().any(|s| s == specifier) {
+      specifiers.push(specifier.to_owned());
+    }
+  };
 
-        if let Some(supports) = rule.supports {
-          if let Some(existing_supports) = &mut entry.supports {
-            existing_supports.or(&supports)
+  while let Ok(token) = input.next() {
+    match token.clone() {
+      Token::AtKeyword(name) if top_level && name.eq_ignore_ascii_case("import") => match input.next() {
+        Ok(Token::QuotedString(url)) | Ok(Token::UnquotedUrl(url)) => push(&url.clone()),
+        Ok(Token::Function(f)) if f.eq_ignore_ascii_case("url") => {
+          if let Ok(url) =
+            input.parse_nested_block(|input| input.expect_string_cloned().map_err(ParseError::<()>::from))
+          {
+            push(&url);
           }
-        } else {
-          entry.supports = None;
         }
-
-        if let Some(layer) = &rule.layer {
-          if let Some(existing_layer) = &entry.layer {
-            // We can't OR layer names without duplicating all of the nested rules, so error for now.
-            if layer != existing_layer || (layer.is_none() && existing_layer.is_none()) {
-              return Err(Error {
-                kind: BundleErrorKind::UnsupportedLayerCombination,
-                loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
-              });
-            }
-          } else {
-            entry.layer = rule.layer;
-          }
+        _ => {}
+      },
+      Token::Ident(name) if name.eq_ignore_ascii_case("from") => {
+        if let Ok(specifier) = input.try_parse(|input| input.expect_string_cloned()) {
+          push(&specifier);
         }
-
-        return Ok(*source_index);
       }
-      None => {
-        let source_index = stylesheets.len() as u32;
-        self.source_indexes.insert(file.to_owned(), source_index);
-
-        stylesheets.push(BundleStyleSheet {
-          stylesheet: None,
-          layer: rule.layer.clone(),
-          media: rule.media.clone(),
-          supports: rule.supports.clone(),
-          loc: rule.loc.clone(),
-          dependencies: Vec::new(),
-          css_modules_deps: Vec::new(),
-          parent_source_index: 0,
-          parent_dep_index: 0,
+      Token::Function(_) | Token::ParenthesisBlock | Token::SquareBracketBlock | Token::CurlyBracketBlock => {
+        let mut nested = Vec::new();
+        let _ = input.parse_nested_block(|input| {
+          scan_dependencies(input, false, &mut nested);
+          Ok::<_, ParseError<()>>(())
         });
-
-        source_index
+        for specifier in nested {
+          push(&specifier);
+        }
       }
-    };
-
-    drop(stylesheets); // ensure we aren't holding the lock anymore
+      _ => {}
+    }
+  }
+}
 
-    let code = self.fs.read(file).map_err(|e| Error {
-      kind: BundleErrorKind::ResolverError(e),
-      loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
-    })?;
+/// A future that polls a list of futures concurrently, and resolves to their outputs in order.
+struct JoinAll<F: Future> {
+  futures: Vec<Option<F>>,
+  outputs: Vec<Option<F::Output>>,
+}
 
-    let mut opts = self.options.clone();
-    let filename = file.to_str().unwrap();
-    opts.filename = filename.to_owned();
-    opts.source_index = source_index;
+impl<F: Future> JoinAll<F> {
+  fn new(futures: Vec<F>) -> Self {
+    let outputs = futures.iter().map(|_| None).collect();
+    JoinAll {
+      futures: futures.into_iter().map(Some).collect(),
+      outputs,
+    }
+  }
+}
 
-    let mut stylesheet = StyleSheet::parse(code, opts)?;
-
-    if let Some(source_map) = &self.source_map {
-      // Only add source if we don't have an input source map.
-      // If we do, this will be handled by the printer when remapping locations.
-      let sm = stylesheet.source_map_url(0);
-      if sm.is_none() || !sm.unwrap().starts_with("data") {
-        let mut source_map = source_map.lock().unwrap();
-        let source_index = source_map.add_source(filename);
-        let _ = source_map.set_source_content(source_index as usize, code);
+// The futures are never pinned in place, since they are required to be `Unpin` when polled.
+impl<F: Future> Unpin for JoinAll<F> {}
+
+impl<F: Future + Unpin> Future for JoinAll<F> {
+  type Output = Vec<F::Output>;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let this = &mut *self;
+    let mut done = true;
+    for (future, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+      if let Some(f) = future {
+        match Pin::new(f).poll(cx) {
+          Poll::Ready(value) => {
+            *output = Some(value);
+            *future = None;
+          }
+          Poll::Pending => done = false,
+        }
       }
     }
 
-    // Collect and load dependencies for this stylesheet in parallel.
-    let dependencies: Result<Vec<u32>, _> = stylesheet
-      .rules
-      .0
-      .par_iter_mut()
-      .filter_map(|r| {
-        // Prepend parent layer name to @layer statements.
-        if let CssRule::LayerStatement(layer) = r {
-          if let Some(Some(parent_layer)) = &rule.layer {
-            for name in &mut layer.names {
-              name.0.insert_many(0, parent_layer.0.iter().cloned())
-            }
-          }
-        }
+    if done {
+      Poll::Ready(this.outputs.iter_mut().filter_map(Option::take).collect())
+    } else {
+      Poll::Pending
+    }
+  }
+}
 
-        if let CssRule::Import(import) = r {
-          let specifier = &import.url;
+/// A function to customize the resolution of import specifiers by a [PackageProvider](PackageProvider).
+/// It receives the specifier and the file it originated from, and returns `None` to fall back to
+/// the default resolution.
+pub type ResolveHook<'a> = dyn Fn(&str, &Path) -> Option<PathBuf> + Send + Sync + 'a;
 
-          // Combine media queries and supports conditions from parent
-          // stylesheet with @import rule using a logical and operator.
-          let mut media = rule.media.clone();
-          let result = media.and(&import.media).map_err(|_| Error {
-            kind: BundleErrorKind::UnsupportedMediaBooleanLogic,
-            loc: Some(ErrorLocation::new(
-              import.loc,
-              self.find_filename(import.loc.source_index),
-            )),
-          });
+/// A [SourceProvider](SourceProvider) that resolves imports of npm packages, and delegates everything
+/// else to another provider, e.g. a [FileProvider](FileProvider).
+///
+/// Imports are resolved as follows:
+///
+/// * Specifiers starting with `~` are always resolved as packages, as in webpack's `css-loader`, e.g.
+///   `~normalize.css` or `~@fontsource/inter/400.css`.
+/// * Other bare specifiers, e.g. `normalize.css`, are resolved relative to the importing file as
+///   usual if that file exists, and as a package otherwise.
+/// * Relative and absolute paths are passed to the wrapped provider.
+///
+/// Packages are looked up in the `node_modules` directories of the importing file and its ancestors.
+/// The `exports` field of `package.json` is used if present, matching the `style` and `default`
+/// conditions by default. Otherwise, the entry point of a package is its `style` field, its `main`
+/// field if it is a CSS file, or `index.css`. Specifiers that cannot be resolved as a package are
+/// passed to the wrapped provider.
+pub struct PackageProvider<'a, P> {
+  fs: &'a P,
+  conditions: Vec<String>,
+  hook: Option<&'a ResolveHook<'a>>,
+}
 
-          if let Err(e) = result {
-            return Some(Err(e));
-          }
+impl<'a, P: SourceProvider> PackageProvider<'a, P> {
+  /// Creates a new PackageProvider that wraps the given source provider.
+  pub fn new(fs: &'a P) -> Self {
+    PackageProvider {
+      fs,
+      conditions: vec!["style".into()],
+      hook: None,
+    }
+  }
 
-          let layer = if (rule.layer == Some(None) && import.layer.is_some())
-            || (import.layer == Some(None) && rule.layer.is_some())
-          {
-            // Cannot combine anonymous layers
-            return Some(Err(Error {
-              kind: BundleErrorKind::UnsupportedLayerCombination,
-              loc: Some(ErrorLocation::new(
-                import.loc,
-                self.find_filename(import.loc.source_index),
-              )),
-            }));
-          } else if let Some(Some(a)) = &rule.layer {
-            if let Some(Some(b)) = &import.layer {
-              let mut name = a.clone();
-              name.0.extend(b.0.iter().cloned());
-              Some(Some(name))
-            } else {
-              Some(Some(a.clone()))
-            }
-          } else {
-            import.layer.clone()
-          };
+  /// Sets the conditions matched in the `exports` field of `package.json` in order of preference,
+  /// followed by `default`. The default is `style`.
+  pub fn set_conditions(&mut self, conditions: Vec<String>) {
+    self.conditions = conditions;
+  }
 
-          let result = match self.fs.resolve(&specifier, file) {
-            Ok(path) => self.load_file(
-              &path,
-              ImportRule {
-                layer,
-                media,
-                supports: combine_supports(rule.supports.clone(), &import.supports),
-                url: "".into(),
-                loc: import.loc,
-              },
-            ),
-            Err(err) => Err(Error {
-              kind: BundleErrorKind::ResolverError(err),
-              loc: Some(ErrorLocation::new(
-                import.loc,
-                self.find_filename(import.loc.source_index),
-              )),
-            }),
-          };
+  /// Sets a function that is called before resolving each specifier, e.g. to implement aliases.
+  pub fn set_hook(&mut self, hook: &'a ResolveHook<'a>) {
+    self.hook = Some(hook);
+  }
 
-          Some(result)
-        } else {
-          None
+  fn resolve_package(&self, specifier: &str, originating_file: &Path) -> Option<PathBuf> {
+    // Scoped packages have two path segments, e.g. `@scope/name`.
+    let name_len = if specifier.starts_with('@') { 2 } else { 1 };
+    let mut segments = specifier.splitn(name_len + 1, '/');
+    let name: Vec<&str> = segments.by_ref().take(name_len).collect();
+    let subpath = segments.next();
+
+    for dir in originating_file.ancestors().skip(1) {
+      let package_dir = dir.join("node_modules").join(name.join("/"));
+      let package_json = package_dir.join("package.json");
+      if !self.fs.exists(&package_json) {
+        match subpath {
+          Some(subpath) if self.fs.exists(&package_dir.join(subpath)) => return Some(package_dir.join(subpath)),
+          _ => continue,
         }
-      })
-      .collect();
-
-    // Collect CSS modules dependencies from the `composes` property.
-    let css_modules_deps: Result<Vec<u32>, _> = if self.options.css_modules.is_some() {
-      stylesheet
-        .rules
-        .0
-        .par_iter_mut()
-        .filter_map(|r| {
-          if let CssRule::Style(style) = r {
-            Some(
-              style
-                .declarations
-                .declarations
-                .par_iter_mut()
-                .chain(style.declarations.important_declarations.par_iter_mut())
-                .filter_map(|d| match d {
-                  Property::Composes(composes) => self
-                    .add_css_module_dep(file, &rule, style.loc, composes.loc, &mut composes.from)
-                    .map(|result| rayon::iter::Either::Left(rayon::iter::once(result))),
+      }
 
-                  // Handle variable references if the dashed_idents option is present.
-                  Property::Custom(CustomProperty { value, .. })
-                  | Property::Unparsed(UnparsedProperty { value, .. })
-                    if matches!(&self.options.css_modules, Some(css_modules) if css_modules.dashed_idents) =>
-                  {
-                    Some(rayon::iter::Either::Right(visit_vars(value).filter_map(|name| {
-                      self.add_css_module_dep(
-                        file,
-                        &rule,
-                        style.loc,
-                        // TODO: store loc in variable reference?
-                        crate::dependencies::Location {
-                          line: style.loc.line,
-                          column: style.loc.column,
-                        },
-                        &mut name.from,
-                      )
-                    })))
-                  }
-                  _ => None,
-                })
-                .flatten(),
-            )
-          } else {
-            None
-          }
-        })
-        .flatten()
-        .collect()
-    } else {
-      Ok(vec![])
-    };
+      let package: serde_json::Value = serde_json::from_str(self.fs.read(&package_json).ok()?).ok()?;
+      if let Some(exports) = package.get("exports") {
+        let subpath = match subpath {
+          Some(subpath) => format!("./{}", subpath),
+          None => ".".into(),
+        };
+        let target = resolve_exports(exports, &subpath, &self.conditions)?;
+        return Some(package_dir.join(target.trim_start_matches("./")));
+      }
 
-    let entry = &mut self.stylesheets.lock().unwrap()[source_index as usize];
-    entry.stylesheet = Some(stylesheet);
-    entry.dependencies = dependencies?;
-    entry.css_modules_deps = css_modules_deps?;
+      let entry = match subpath {
+        Some(subpath) => subpath,
+        None => package
+          .get("style")
+          .and_then(|style| style.as_str())
+          .or_else(|| {
+            package
+              .get("main")
+              .and_then(|main| main.as_str())
+              .filter(|main| main.ends_with(".css"))
+          })
+          .unwrap_or("index.css"),
+      };
+      return Some(package_dir.join(entry.trim_start_matches("./")));
+    }
 
-    Ok(source_index)
+    None
   }
+}
 
-  fn add_css_module_dep(
-    &self,
-    file: &Path,
-    rule: &ImportRule<'a>,
-    style_loc: Location,
-    loc: crate::dependencies::Location,
-    specifier: &mut Option<Specifier>,
-  ) -> Option<Result<u32, Error<BundleErrorKind<'a, P::Error>>>> {
-    if let Some(Specifier::File(f)) = specifier {
-      let result = match self.fs.resolve(&f, file) {
-        Ok(path) => {
-          let res = self.load_file(
-            &path,
-            ImportRule {
-              layer: rule.layer.clone(),
-              media: rule.media.clone(),
-              supports: rule.supports.clone(),
-              url: "".into(),
-              loc: Location {
-                source_index: style_loc.source_index,
-                line: loc.line,
-                column: loc.column,
-              },
-            },
-          );
+impl<'a, P: SourceProvider> SourceProvider for PackageProvider<'a, P> {
+  type Error = P::Error;
 
-          if let Ok(source_index) = res {
-            *specifier = Some(Specifier::SourceIndex(source_index));
-          }
+  fn read<'b>(&'b self, file: &Path) -> Result<&'b str, Self::Error> {
+    self.fs.read(file)
+  }
 
-          res
-        }
-        Err(err) => Err(Error {
-          kind: BundleErrorKind::ResolverError(err),
-          loc: Some(ErrorLocation::new(
-            style_loc,
-            self.find_filename(style_loc.source_index),
-          )),
-        }),
-      };
-      Some(result)
-    } else {
-      None
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error> {
+    if let Some(path) = self.hook.and_then(|hook| hook(specifier, originating_file)) {
+      return Ok(path);
     }
-  }
 
-  fn order(&mut self) {
-    process(self.stylesheets.get_mut().unwrap(), 0, &mut HashSet::new());
+    if let Some(specifier) = specifier.strip_prefix('~') {
+      return match self.resolve_package(specifier, originating_file) {
+        Some(path) => Ok(path),
+        None => self.fs.resolve(specifier, originating_file),
+      };
+    }
 
-    fn process<'i, T: AtRuleParser<'i>>(
-      stylesheets: &mut Vec<BundleStyleSheet<'i, '_, T>>,
-      source_index: u32,
-      visited: &mut HashSet<u32>,
-    ) {
-      if visited.contains(&source_index) {
-        return;
+    let path = self.fs.resolve(specifier, originating_file)?;
+    if is_bare_specifier(specifier) && !self.fs.exists(&path) {
+      if let Some(path) = self.resolve_package(specifier, originating_file) {
+        return Ok(path);
       }
+    }
 
-      visited.insert(source_index);
+    Ok(path)
+  }
 
-      let mut dep_index = 0;
-      for i in 0..stylesheets[source_index as usize].css_modules_deps.len() {
-        let dep_source_index = stylesheets[source_index as usize].css_modules_deps[i];
-        let resolved = &mut stylesheets[dep_source_index as usize];
+  fn read_bytes(&self, file: &Path) -> Result<Vec<u8>, Self::Error> {
+    self.fs.read_bytes(file)
+  }
 
-        // CSS modules preserve the first instance of composed stylesheets.
-        if !visited.contains(&dep_source_index) {
-          resolved.parent_dep_index = dep_index;
-          resolved.parent_source_index = source_index;
-          process(stylesheets, dep_source_index, visited);
-        }
+  fn resolve_glob(&self, pattern: &str, originating_file: &Path) -> Result<Vec<PathBuf>, Self::Error> {
+    self.fs.resolve_glob(pattern, originating_file)
+  }
 
-        dep_index += 1;
-      }
+  fn exists(&self, file: &Path) -> bool {
+    self.fs.exists(file)
+  }
+}
 
-      for i in 0..stylesheets[source_index as usize].dependencies.len() {
-        let dep_source_index = stylesheets[source_index as usize].dependencies[i];
-        let resolved = &mut stylesheets[dep_source_index as usize];
+/// Returns whether an import specifier may refer to a package, i.e. it is not a relative or
+/// absolute path or url.
+fn is_bare_specifier(specifier: &str) -> bool {
+  !specifier.is_empty()
+    && !specifier.starts_with(['.', '/', '\\'])
+    && !specifier.contains(':')
+    && !Path::new(specifier).is_absolute()
+}
 
-        // In browsers, every instance of an @import is evaluated, so we preserve the last.
-        resolved.parent_dep_index = dep_index;
-        resolved.parent_source_index = source_index;
+/// Resolves a subpath, e.g. `.` or `./theme.css`, using the `exports` field of a `package.json`.
+fn resolve_exports(exports: &serde_json::Value, subpath: &str, conditions: &[String]) -> Option<String> {
+  let map = match exports.as_object() {
+    Some(map) if map.keys().all(|key| key.starts_with('.')) => map,
+    // A string, array or map of conditions is the export of the main entry point.
+    _ if subpath == "." => return resolve_export_target(exports, None, conditions),
+    _ => return None,
+  };
 
-        process(stylesheets, dep_source_index, visited);
-        dep_index += 1;
-      }
-    }
+  if let Some(target) = map.get(subpath) {
+    return resolve_export_target(target, None, conditions);
   }
 
-  fn inline(&mut self, dest: &mut Vec<CssRule<'a, T::AtRule>>) {
-    process(self.stylesheets.get_mut().unwrap(), 0, dest);
+  // Find the most specific pattern, e.g. `./themes/*.css`.
+  let (key, star) = map
+    .keys()
+    .filter_map(|key| {
+      let (prefix, suffix) = key.split_once('*')?;
+      let star = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+      Some((key, star))
+    })
+    .max_by_key(|(key, _)| key.len())?;
+  resolve_export_target(&map[key], Some(star), conditions)
+}
 
-    fn process<'a, T: AtRuleParser<'a>>(
-      stylesheets: &mut Vec<BundleStyleSheet<'a, '_, T>>,
-      source_index: u32,
-      dest: &mut Vec<CssRule<'a, T::AtRule>>,
-    ) {
-      let stylesheet = &mut stylesheets[source_index as usize];
-      let mut rules = std::mem::take(&mut stylesheet.stylesheet.as_mut().unwrap().rules.0);
+fn resolve_export_target(target: &serde_json::Value, star: Option<&str>, conditions: &[String]) -> Option<String> {
+  match target {
+    serde_json::Value::String(target) => Some(match star {
+      Some(star) => target.replace('*', star),
+      None => target.clone(),
+    }),
+    serde_json::Value::Array(targets) => targets
+      .iter()
+      .find_map(|target| resolve_export_target(target, star, conditions)),
+    // Conditions are matched in order of preference rather than in the order of the keys.
+    serde_json::Value::Object(map) => conditions
+      .iter()
+      .map(|condition| condition.as_str())
+      .chain(std::iter::once("default"))
+      .filter_map(|condition| map.get(condition))
+      .find_map(|target| resolve_export_target(target, star, conditions)),
+    _ => None,
+  }
+}
 
-      // Hoist css modules deps
-      let mut dep_index = 0;
-      for i in 0..stylesheet.css_modules_deps.len() {
-        let dep_source_index = stylesheets[source_index as usize].css_modules_deps[i];
-        let resolved = &stylesheets[dep_source_index as usize];
+/// A trait to fetch the contents of remote style sheets, as used by a [RemoteProvider](RemoteProvider).
+pub trait RemoteFetcher: Send + Sync {
+  /// A custom error.
+  type Error: std::error::Error + Send + Sync;
 
-        // Include the dependency if this is the first instance as computed earlier.
-        if resolved.parent_source_index == source_index && resolved.parent_dep_index == dep_index as u32 {
-          process(stylesheets, dep_source_index, dest);
-        }
+  /// Fetches the contents of the given `http://` or `https://` url.
+  fn fetch(&self, url: &str) -> Result<String, Self::Error>;
+}
 
-        dep_index += 1;
-      }
+/// A [SourceProvider](SourceProvider) that fetches `http://` and `https://` imports using a
+/// [RemoteFetcher](RemoteFetcher), and reads and resolves all other files using another provider.
+///
+/// Each url is fetched once, and cached for the lifetime of the provider. Imports within a remote
+/// style sheet are resolved relative to its url. If an integrity hash is registered for a url with
+/// [set_integrity](RemoteProvider::set_integrity), the fetched contents are verified against it.
+/// Other urls within remote style sheets, e.g. of fonts, are left as is.
+pub struct RemoteProvider<'a, P, F> {
+  fs: &'a P,
+  fetcher: F,
+  integrity: HashMap<String, String>,
+  fetched: DashMap<String, *mut String>,
+}
 
-      let mut import_index = 0;
-      for rule in &mut rules {
-        match rule {
-          CssRule::Import(_) => {
-            let dep_source_index = stylesheets[source_index as usize].dependencies[import_index];
-            let resolved = &stylesheets[dep_source_index as usize];
+unsafe impl<'a, P: Sync, F: Sync> Sync for RemoteProvider<'a, P, F> {}
+unsafe impl<'a, P: Sync, F: Send> Send for RemoteProvider<'a, P, F> {}
 
-            // Include the dependency if this is the last instance as computed earlier.
-            if resolved.parent_source_index == source_index && resolved.parent_dep_index == dep_index {
-              process(stylesheets, dep_source_index, dest);
-            }
+/// An error returned by a [RemoteProvider](RemoteProvider).
+#[derive(Debug)]
+pub enum RemoteError<P, F> {
+  /// An error from the provider used for local files.
+  Provider(P),
+  /// An error fetching a remote url.
+  Fetch {
+    /// The url that failed to fetch.
+    url: String,
+    /// The error returned by the fetcher.
+    error: F,
+  },
+  /// The contents of a remote url did not match its registered integrity hash.
+  IntegrityMismatch {
+    /// The url that was fetched.
+    url: String,
+    /// The registered integrity hash.
+    expected: String,
+    /// The integrity hash of the fetched contents.
+    actual: String,
+  },
+}
 
-            *rule = CssRule::Ignored;
-            dep_index += 1;
-            import_index += 1;
-          }
-          CssRule::LayerStatement(_) => {
-            // @layer rules are the only rules that may appear before an @import.
-            // We must preserve this order to ensure correctness.
-            let layer = std::mem::replace(rule, CssRule::Ignored);
-            dest.push(layer);
-          }
-          CssRule::Ignored => {}
-          _ => break,
-        }
-      }
+impl<P: std::fmt::Display, F: std::fmt::Display> std::fmt::Display for RemoteError<P, F> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RemoteError::Provider(err) => err.fmt(f),
+      RemoteError::Fetch { url, error } => write!(f, "Failed to fetch {}: {}", url, error),
+      RemoteError::IntegrityMismatch { url, expected, actual } => write!(
+        f,
+        "Integrity check failed for {}: expected {}, but got {}",
+        url, expected, actual
+      ),
+    }
+  }
+}
 
-      // Wrap rules in the appropriate @layer, @media, and @supports rules.
-      let stylesheet = &mut stylesheets[source_index as usize];
+impl<P: std::error::Error, F: std::error::Error> std::error::Error for RemoteError<P, F> {}
 
-      if stylesheet.layer.is_some() {
-        rules = vec![CssRule::LayerBlock(LayerBlockRule {
-          name: stylesheet.layer.take().unwrap(),
-          rules: CssRuleList(rules),
-          loc: stylesheet.loc,
-        })]
-      }
+impl<'a, P: SourceProvider, F: RemoteFetcher> RemoteProvider<'a, P, F> {
+  /// Creates a new RemoteProvider that fetches remote urls with the given fetcher,
+  /// and reads all other files using the given source provider.
+  pub fn new(fs: &'a P, fetcher: F) -> Self {
+    RemoteProvider {
+      fs,
+      fetcher,
+      integrity: HashMap::new(),
+      fetched: DashMap::new(),
+    }
+  }
 
-      if !stylesheet.media.media_queries.is_empty() {
-        rules = vec![CssRule::Media(MediaRule {
-          query: std::mem::replace(&mut stylesheet.media, MediaList::new()),
-          rules: CssRuleList(rules),
-          loc: stylesheet.loc,
-        })]
-      }
+  /// Registers a [subresource integrity](https://www.w3.org/TR/SRI/) hash that the contents of
+  /// the given url must match, e.g. `sha384-...`. Multiple space separated hashes may be given,
+  /// in which case the contents must match one of them. `sha256`, `sha384` and `sha512` are supported.
+  pub fn set_integrity(&mut self, url: &str, integrity: &str) {
+    self.integrity.insert(url.to_owned(), integrity.to_owned());
+  }
+
+  /// Returns the `sha384` integrity hash of a url that has been fetched, e.g. to pin
+  /// it with [set_integrity](RemoteProvider::set_integrity).
+  pub fn integrity(&self, url: &str) -> Option<String> {
+    let ptr = *self.fetched.get(url)?;
+    // SAFETY: fetched contents are only dropped with the provider.
+    integrity_hash("sha384", unsafe { (*ptr).as_bytes() })
+  }
+
+  fn fetch(&self, url: &str) -> Result<&str, RemoteError<P::Error, F::Error>> {
+    if let Some(ptr) = self.fetched.get(url) {
+      // SAFETY: fetched contents are only dropped with the provider.
+      return Ok(unsafe { &**ptr });
+    }
+
+    let code = self.fetcher.fetch(url).map_err(|error| RemoteError::Fetch {
+      url: url.to_owned(),
+      error,
+    })?;
 
-      if stylesheet.supports.is_some() {
-        rules = vec![CssRule::Supports(SupportsRule {
-          condition: stylesheet.supports.take().unwrap(),
-          rules: CssRuleList(rules),
-          loc: stylesheet.loc,
-        })]
+    if let Some(expected) = self.integrity.get(url) {
+      let matches = expected.split_whitespace().any(|hash| {
+        let (algorithm, digest) = hash.split_once('-').unwrap_or_default();
+        let digest = digest.split('?').next().unwrap_or_default();
+        integrity_hash(algorithm, code.as_bytes()) == Some(format!("{}-{}", algorithm, digest))
+      });
+
+      if !matches {
+        let algorithm = expected.split('-').next().unwrap_or_default();
+        return Err(RemoteError::IntegrityMismatch {
+          url: url.to_owned(),
+          expected: expected.clone(),
+          actual: integrity_hash(algorithm, code.as_bytes())
+            .or_else(|| integrity_hash("sha384", code.as_bytes()))
+            .unwrap_or_default(),
+        });
       }
+    }
+
+    // If another thread fetched the same url in the meantime, keep the first result.
+    let ptr = *self
+      .fetched
+      .entry(url.to_owned())
+      .or_insert_with(|| Box::into_raw(Box::new(code)));
+    // SAFETY: fetched contents are only dropped with the provider.
+    Ok(unsafe { &*ptr })
+  }
+}
+
+impl<'a, P: SourceProvider, F: RemoteFetcher> SourceProvider for RemoteProvider<'a, P, F> {
+  type Error = RemoteError<P::Error, F::Error>;
+
+  fn read<'b>(&'b self, file: &Path) -> Result<&'b str, Self::Error> {
+    match file.to_str().filter(|file| is_remote_url(file)) {
+      Some(url) => self.fetch(url),
+      None => self.fs.read(file).map_err(RemoteError::Provider),
+    }
+  }
+
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error> {
+    if is_remote_url(specifier) {
+      return Ok(PathBuf::from(specifier));
+    }
+
+    match originating_file.to_str().filter(|file| is_remote_url(file)) {
+      Some(base) => Ok(PathBuf::from(join_url(base, specifier))),
+      None => self.fs.resolve(specifier, originating_file).map_err(RemoteError::Provider),
+    }
+  }
 
-      dest.extend(rules);
+  fn read_bytes(&self, file: &Path) -> Result<Vec<u8>, Self::Error> {
+    match file.to_str().filter(|file| is_remote_url(file)) {
+      Some(url) => Ok(self.fetch(url)?.as_bytes().to_vec()),
+      None => self.fs.read_bytes(file).map_err(RemoteError::Provider),
     }
   }
+
+  fn exists(&self, file: &Path) -> bool {
+    match file.to_str().filter(|file| is_remote_url(file)) {
+      Some(url) => self.fetch(url).is_ok(),
+      None => self.fs.exists(file),
+    }
+  }
+
+  fn resolve_glob(&self, pattern: &str, originating_file: &Path) -> Result<Vec<PathBuf>, Self::Error> {
+    if is_remote_url(pattern) || matches!(originating_file.to_str(), Some(file) if is_remote_url(file)) {
+      // Remote urls cannot be listed, so patterns are fetched as is.
+      return Ok(vec![self.resolve(pattern, originating_file)?]);
+    }
+
+    self.fs.resolve_glob(pattern, originating_file).map_err(RemoteError::Provider)
+  }
 }
 
-fn combine_supports<'a>(
-  a: Option<SupportsCondition<'a>>,
-  b: &Option<SupportsCondition<'a>>,
-) -> Option<SupportsCondition<'a>> {
-  if let Some(mut a) = a {
-    if let Some(b) = b {
-      a.and(b)
+impl<'a, P, F> Drop for RemoteProvider<'a, P, F> {
+  fn drop(&mut self) {
+    for entry in self.fetched.iter() {
+      std::mem::drop(unsafe { Box::from_raw(*entry.value()) })
     }
-    Some(a)
-  } else {
-    b.clone()
   }
 }
 
-fn visit_vars<'a, 'b>(
-  token_list: &'b mut TokenList<'a>,
-) -> impl ParallelIterator<Item = &'b mut DashedIdentReference<'a>> {
-  let mut stack = vec![token_list.0.iter_mut()];
-  std::iter::from_fn(move || {
-    while !stack.is_empty() {
-      let iter = stack.last_mut().unwrap();
-      match iter.next() {
-        Some(TokenOrValue::Var(var)) => {
-          if let Some(fallback) = &mut var.fallback {
-            stack.push(fallback.0.iter_mut());
-          }
-          return Some(&mut var.name);
-        }
-        Some(TokenOrValue::Env(env)) => {
-          if let Some(fallback) = &mut env.fallback {
-            stack.push(fallback.0.iter_mut());
-          }
-          if let EnvironmentVariableName::Custom(name) = &mut env.name {
-            return Some(name);
-          }
-        }
-        Some(TokenOrValue::UnresolvedColor(color)) => match color {
-          UnresolvedColor::RGB { alpha, .. } | UnresolvedColor::HSL { alpha, .. } => {
-            stack.push(alpha.0.iter_mut());
-          }
-        },
-        None => {
-          stack.pop();
+/// Returns whether an import specifier is an `http://` or `https://` url.
+fn is_remote_url(specifier: &str) -> bool {
+  let scheme = specifier.split_once("://").map_or("", |(scheme, _)| scheme);
+  scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+/// Resolves a relative url against a base url.
+fn join_url(base: &str, specifier: &str) -> String {
+  let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+  if let Some(specifier) = specifier.strip_prefix("//") {
+    return format!("{}://{}", scheme, specifier);
+  }
+
+  let path_start = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+  let origin = &base[..scheme.len() + 3 + path_start];
+  if specifier.starts_with('/') {
+    return format!("{}{}", origin, specifier);
+  }
+
+  let base_path = &rest[path_start..];
+  let base_path = &base_path[..base_path.find(['?', '#']).unwrap_or(base_path.len())];
+  let (path, suffix) = specifier.split_at(specifier.find(['?', '#']).unwrap_or(specifier.len()));
+
+  // Start from the directory of the base url, keeping the empty segment for the leading slash.
+  let mut segments: Vec<&str> = if base_path.is_empty() {
+    vec![""]
+  } else {
+    base_path.split('/').collect()
+  };
+  segments.pop();
+  if segments.is_empty() {
+    segments.push("");
+  }
+
+  for segment in path.split('/') {
+    match segment {
+      "." => {}
+      ".." => {
+        if segments.len() > 1 {
+          segments.pop();
         }
-        _ => {}
       }
+      segment => segments.push(segment),
     }
-    None
-  })
-  .par_bridge()
+  }
+
+  format!("{}{}{}", origin, segments.join("/"), suffix)
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use crate::{
-    css_modules::{self, CssModuleExports, CssModuleReference},
-    stylesheet::{MinifyOptions, PrinterOptions},
-    targets::Browsers,
+/// Computes a subresource integrity hash with the given algorithm.
+fn integrity_hash(algorithm: &str, bytes: &[u8]) -> Option<String> {
+  use sha2::{Digest, Sha256, Sha384, Sha512};
+  let digest = match algorithm {
+    "sha256" => BASE64.encode(&Sha256::digest(bytes)),
+    "sha384" => BASE64.encode(&Sha384::digest(bytes)),
+    "sha512" => BASE64.encode(&Sha512::digest(bytes)),
+    _ => return None,
   };
-  use indoc::indoc;
-  use std::collections::HashMap;
+  Some(format!("{}-{}", algorithm, digest))
+}
 
-  #[derive(Clone)]
-  struct TestProvider {
-    map: HashMap<PathBuf, String>,
+/// Options for inlining assets referenced by `url()` as `data:` URIs with an [AssetInliner](AssetInliner).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetInlineOptions {
+  /// The maximum size of an asset to inline, in bytes. Larger assets are left as references.
+  pub max_size: usize,
+  /// The file extensions of assets to inline, without the leading `.`, e.g. `svg` or `woff2`.
+  /// If empty, all assets with a known MIME type are inlined.
+  pub extensions: Vec<String>,
+}
+
+impl Default for AssetInlineOptions {
+  fn default() -> Self {
+    AssetInlineOptions {
+      max_size: 4096,
+      extensions: Vec::new(),
+    }
   }
+}
 
-  impl SourceProvider for TestProvider {
-    type Error = std::io::Error;
+/// The dependency graph of the files loaded by a bundler, as returned by
+/// [Bundler::import_graph](Bundler::import_graph).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(serde::Serialize))]
+pub struct ImportGraph {
+  /// The files, in the order they were loaded. Entries are loaded first.
+  pub files: Vec<ImportGraphFile>,
+}
 
-    fn read<'a>(&'a self, file: &Path) -> Result<&'a str, Self::Error> {
-      Ok(self.map.get(file).unwrap())
+/// A file in an [ImportGraph](ImportGraph).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ImportGraphFile {
+  /// The resolved path of the file.
+  pub path: PathBuf,
+  /// A hash of the contents of the file, e.g. `sha256-<base64>`.
+  pub content_hash: String,
+  /// The `@import` rules in the file, in source order.
+  pub imports: Vec<ImportGraphEdge>,
+  /// The indexes of the files referenced by CSS modules `composes` and dashed ident references.
+  pub composes: Vec<usize>,
+}
+
+/// An `@import` rule in an [ImportGraph](ImportGraph).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(serde::Serialize))]
+pub struct ImportGraphEdge {
+  /// The import specifier, as written in the source.
+  pub specifier: String,
+  /// The location of the `@import` rule.
+  pub loc: ErrorLocation,
+  /// The indexes of the files the import resolved to, or empty if it is external.
+  pub files: Vec<usize>,
+  /// Whether the import was preserved in the output as an external import.
+  pub external: bool,
+  /// The conditions the imported files were included under, combined with those of the files
+  /// importing them, e.g. `layer(base) print`. Conditions that could not be intersected are
+  /// listed separately, outermost first.
+  pub conditions: Vec<String>,
+}
+
+/// An asset that was inlined by an [AssetInliner](AssetInliner).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(serde::Serialize))]
+pub struct InlinedAsset {
+  /// The resolved path of the asset.
+  pub path: PathBuf,
+  /// The detected MIME type of the asset.
+  pub mime_type: &'static str,
+  /// The size of the asset, in bytes.
+  pub size: usize,
+  /// The size of the `data:` URI the asset was replaced with, in bytes.
+  pub encoded_size: usize,
+}
+
+/// Inlines small assets referenced by `url()` in a bundled style sheet as `data:` URIs.
+///
+/// Relative urls are resolved against the file they appear in using the [SourceProvider](SourceProvider),
+/// and read with [read_bytes](SourceProvider::read_bytes). Assets within the size limit whose MIME type
+/// can be detected from their contents or extension are base64 encoded, except for SVG images, which are
+/// percent encoded when that is shorter. Urls with a query string or fragment, and urls in `@import`
+/// rules, are left as is, as are assets that fail to resolve or read.
+///
+/// The inliner is used as the [rewrite](crate::printer::UrlOptions::rewrite) hook when printing the
+/// style sheet. The `sourcemap` feature must be enabled, so that the printer knows which file each url
+/// appears in.
+///
+/// ```no_run
+/// # use std::path::Path;
+/// # use lightningcss::{bundler::*, printer::{PrinterOptions, UrlOptions, UrlReference}, stylesheet::ParserOptions};
+/// let fs = FileProvider::new();
+/// let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+/// let stylesheet = bundler.bundle(Path::new("style.css")).unwrap();
+///
+/// let inliner = AssetInliner::new(&fs, AssetInlineOptions::default());
+/// let rewrite = |url: &UrlReference| inliner.rewrite(url);
+/// let res = stylesheet.to_css(PrinterOptions {
+///   urls: UrlOptions { rewrite: Some(&rewrite), ..UrlOptions::default() },
+///   ..PrinterOptions::default()
+/// }).unwrap();
+/// let inlined = inliner.inlined();
+/// ```
+pub struct AssetInliner<'a, P> {
+  fs: &'a P,
+  options: AssetInlineOptions,
+  data_urls: DashMap<PathBuf, Option<String>>,
+  inlined: Mutex<Vec<InlinedAsset>>,
+}
+
+impl<'a, P: SourceProvider> AssetInliner<'a, P> {
+  /// Creates a new AssetInliner that reads assets using the given source provider.
+  pub fn new(fs: &'a P, options: AssetInlineOptions) -> Self {
+    AssetInliner {
+      fs,
+      options,
+      data_urls: DashMap::new(),
+      inlined: Mutex::new(Vec::new()),
     }
+  }
 
-    fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error> {
-      Ok(originating_file.with_file_name(specifier))
+  /// Returns a `data:` URI for the asset referenced by the given url, if it should be inlined.
+  pub fn rewrite(&self, url: &UrlReference) -> Option<String> {
+    if url.kind != UrlKind::Url
+      || url.original.is_empty()
+      || url.original.contains(['?', '#'])
+      || is_absolute_url(url.original)
+    {
+      return None;
+    }
+
+    let path = self.fs.resolve(url.original, Path::new(url.filename)).ok()?;
+    if let Some(data_url) = self.data_urls.get(&path) {
+      return data_url.clone();
     }
+
+    let data_url = self.load(&path);
+    self.data_urls.insert(path, data_url.clone());
+    data_url
   }
 
-  /// Stand-in for a user-authored `SourceProvider` with application-specific logic.
-  struct CustomProvider {
-    map: HashMap<PathBuf, String>,
+  fn load(&self, path: &Path) -> Option<String> {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    if !self.options.extensions.is_empty()
+      && !self.options.extensions.iter().any(|ext| ext.eq_ignore_ascii_case(extension))
+    {
+      return None;
+    }
+
+    let bytes = self.fs.read_bytes(path).ok()?;
+    if bytes.len() > self.options.max_size {
+      return None;
+    }
+
+    let mime_type = sniff_mime_type(&bytes).or_else(|| mime_type_for_extension(extension))?;
+    let data_url = data_url(mime_type, &bytes);
+    self.inlined.lock().unwrap().push(InlinedAsset {
+      path: path.to_owned(),
+      mime_type,
+      size: bytes.len(),
+      encoded_size: data_url.len(),
+    });
+    Some(data_url)
   }
 
-  impl SourceProvider for CustomProvider {
-    type Error = std::io::Error;
+  /// Returns the assets that have been inlined, in the order they were first referenced.
+  pub fn inlined(&self) -> Vec<InlinedAsset> {
+    self.inlined.lock().unwrap().clone()
+  }
+}
 
-    /// Read files from in-memory map.
-    fn read<'a>(&'a self, file: &Path) -> Result<&'a str, Self::Error> {
-      Ok(self.map.get(file).unwrap())
+/// Options for renaming assets referenced by `url()` with a hash of their contents with an
+/// [AssetHasher](AssetHasher).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetHashOptions {
+  /// The template for the hashed file names. `[name]` is replaced with the file stem, `[hash]` with
+  /// the content hash, and `[ext]` with the extension, without the leading `.`.
+  pub file_name: String,
+  /// The number of hexadecimal characters of the content hash to use.
+  pub hash_length: usize,
+  /// A prefix for the rewritten urls, e.g. `/assets/` or the url of a CDN. If empty, the file name
+  /// in the original url is replaced, so the hashed asset is expected next to the original one.
+  pub public_path: String,
+}
+
+impl Default for AssetHashOptions {
+  fn default() -> Self {
+    AssetHashOptions {
+      file_name: "[name]-[hash].[ext]".into(),
+      hash_length: 8,
+      public_path: String::new(),
     }
+  }
+}
 
-    /// Resolve by stripping a `foo:` prefix off any import. Specifiers without
-    /// this prefix fail with an error.
-    fn resolve(&self, specifier: &str, _originating_file: &Path) -> Result<PathBuf, Self::Error> {
-      if specifier.starts_with("foo:") {
-        Ok(Path::new(&specifier["foo:".len()..]).to_path_buf())
-      } else {
-        let err = std::io::Error::new(
-          std::io::ErrorKind::NotFound,
-          format!(
-            "Failed to resolve `{}`, specifier does not start with `foo:`.",
-            &specifier
-          ),
-        );
+/// An asset that was renamed by an [AssetHasher](AssetHasher).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct HashedAsset {
+  /// The resolved path of the asset.
+  pub path: PathBuf,
+  /// The hashed file name of the asset, which the build tool should copy it to.
+  pub hashed_file_name: String,
+  /// The hexadecimal content hash of the asset.
+  pub hash: String,
+}
 
-        Err(err)
-      }
+/// Rewrites `url()` references in a bundled style sheet to file names containing a hash of the
+/// referenced asset's contents, for long term caching.
+///
+/// Relative urls are resolved against the file they appear in using the [SourceProvider](SourceProvider),
+/// and read with [read_bytes](SourceProvider::read_bytes). Any query string or fragment is preserved.
+/// Absolute urls, urls in `@import` rules, and assets that fail to resolve or read are left as is.
+/// The [manifest](AssetHasher::manifest) lists the assets that were renamed, so that the build tool
+/// can copy them to their hashed file names.
+///
+/// Like an [AssetInliner](AssetInliner), the hasher is used as the [rewrite](crate::printer::UrlOptions::rewrite)
+/// hook when printing the style sheet, and the two can be combined to inline small assets and hash the rest.
+///
+/// ```no_run
+/// # use std::path::Path;
+/// # use lightningcss::{bundler::*, printer::{PrinterOptions, UrlOptions, UrlReference}, stylesheet::ParserOptions};
+/// let fs = FileProvider::new();
+/// let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+/// let stylesheet = bundler.bundle(Path::new("style.css")).unwrap();
+///
+/// let inliner = AssetInliner::new(&fs, AssetInlineOptions::default());
+/// let hasher = AssetHasher::new(&fs, AssetHashOptions::default());
+/// let rewrite = |url: &UrlReference| inliner.rewrite(url).or_else(|| hasher.rewrite(url));
+/// let res = stylesheet.to_css(PrinterOptions {
+///   urls: UrlOptions { rewrite: Some(&rewrite), ..UrlOptions::default() },
+///   ..PrinterOptions::default()
+/// }).unwrap();
+/// let manifest = hasher.manifest();
+/// ```
+pub struct AssetHasher<'a, P> {
+  fs: &'a P,
+  options: AssetHashOptions,
+  file_names: DashMap<PathBuf, Option<String>>,
+  manifest: Mutex<Vec<HashedAsset>>,
+}
+
+impl<'a, P: SourceProvider> AssetHasher<'a, P> {
+  /// Creates a new AssetHasher that reads assets using the given source provider.
+  pub fn new(fs: &'a P, options: AssetHashOptions) -> Self {
+    AssetHasher {
+      fs,
+      options,
+      file_names: DashMap::new(),
+      manifest: Mutex::new(Vec::new()),
     }
   }
 
-  macro_rules! fs(
-    { $($key:literal: $value:expr),* } => {
-      {
-        #[allow(unused_mut)]
-        let mut m = HashMap::new();
-        $(
-          m.insert(PathBuf::from($key), $value.to_owned());
-        )*
-        m
+  /// Returns the hashed url for the asset referenced by the given url.
+  pub fn rewrite(&self, url: &UrlReference) -> Option<String> {
+    if url.kind != UrlKind::Url || url.original.is_empty() || is_absolute_url(url.original) {
+      return None;
+    }
+
+    let (original, _) = split_url_suffix(url.original);
+    if original.is_empty() {
+      return None;
+    }
+
+    let path = self.fs.resolve(original, Path::new(url.filename)).ok()?;
+    let file_name = match self.file_names.get(&path) {
+      Some(file_name) => file_name.clone(),
+      None => {
+        let file_name = self.hash(&path);
+        self.file_names.insert(path, file_name.clone());
+        file_name
       }
-    };
-  );
+    }?;
 
-  fn bundle<P: SourceProvider>(fs: P, entry: &str) -> String {
-    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
-    let stylesheet = bundler.bundle(Path::new(entry)).unwrap();
-    stylesheet.to_css(PrinterOptions::default()).unwrap().code
+    let (rebased, suffix) = split_url_suffix(url.url);
+    if self.options.public_path.is_empty() {
+      let dir = match rebased.rfind('/') {
+        Some(index) => &rebased[..index + 1],
+        None => "",
+      };
+      Some(format!("{}{}{}", dir, file_name, suffix))
+    } else {
+      Some(format!("{}{}{}", self.options.public_path, file_name, suffix))
+    }
   }
 
-  fn bundle_css_module<P: SourceProvider>(
-    fs: P,
-    entry: &str,
-    project_root: Option<&str>,
-  ) -> (String, CssModuleExports) {
-    let mut bundler = Bundler::new(
-      &fs,
-      None,
-      ParserOptions {
-        css_modules: Some(css_modules::Config {
-          dashed_idents: true,
-          ..Default::default()
-        }),
-        ..ParserOptions::default()
-      },
-    );
-    let mut stylesheet = bundler.bundle(Path::new(entry)).unwrap();
-    stylesheet.minify(MinifyOptions::default()).unwrap();
-    let res = stylesheet
-      .to_css(PrinterOptions {
-        project_root,
-        ..PrinterOptions::default()
-      })
-      .unwrap();
-    (res.code, res.exports.unwrap())
-  }
+  fn hash(&self, path: &Path) -> Option<String> {
+    use sha2::{Digest, Sha256};
+    let bytes = self.fs.read_bytes(path).ok()?;
+    let mut hash = HEXLOWER.encode(&Sha256::digest(&bytes));
+    hash.truncate(self.options.hash_length);
+
+    let name = path.file_stem().and_then(|name| name.to_str()).unwrap_or_default();
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    let mut file_name = self
+      .options
+      .file_name
+      .replace("[name]", name)
+      .replace("[hash]", &hash)
+      .replace("[ext]", ext);
+    if ext.is_empty() && file_name.ends_with('.') {
+      file_name.pop();
+    }
 
-  fn bundle_custom_media<P: SourceProvider>(fs: P, entry: &str) -> String {
-    let mut bundler = Bundler::new(
-      &fs,
-      None,
-      ParserOptions {
-        custom_media: true,
-        ..ParserOptions::default()
-      },
-    );
-    let mut stylesheet = bundler.bundle(Path::new(entry)).unwrap();
-    let targets = Some(Browsers {
-      safari: Some(13 << 16),
-      ..Browsers::default()
+    self.manifest.lock().unwrap().push(HashedAsset {
+      path: path.to_owned(),
+      hashed_file_name: file_name.clone(),
+      hash,
     });
-    stylesheet
-      .minify(MinifyOptions {
-        targets,
-        ..MinifyOptions::default()
-      })
-      .unwrap();
-    stylesheet
-      .to_css(PrinterOptions {
-        targets,
-        ..PrinterOptions::default()
-      })
-      .unwrap()
-      .code
+    Some(file_name)
   }
 
-  fn error_test<P: SourceProvider>(
-    fs: P,
+  /// Returns the assets that have been renamed, in the order they were first referenced.
+  pub fn manifest(&self) -> Vec<HashedAsset> {
+    self.manifest.lock().unwrap().clone()
+  }
+}
+
+/// Splits a url into its path and its query string and fragment, if any.
+fn split_url_suffix(url: &str) -> (&str, &str) {
+  match url.find(['?', '#']) {
+    Some(index) => url.split_at(index),
+    None => (url, ""),
+  }
+}
+
+/// Detects the MIME type of an asset from the signature at the start of its contents.
+fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+  let mime_type = match bytes {
+    [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => "image/png",
+    [0xff, 0xd8, 0xff, ..] => "image/jpeg",
+    [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "image/gif",
+    [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+    [_, _, _, _, b'f', b't', b'y', b'p', b'a', b'v', b'i', b'f', ..] => "image/avif",
+    [b'w', b'O', b'F', b'F', ..] => "font/woff",
+    [b'w', b'O', b'F', b'2', ..] => "font/woff2",
+    [b'O', b'T', b'T', b'O', ..] => "font/otf",
+    [0, 1, 0, 0, ..] | [b't', b'r', b'u', b'e', ..] => "font/ttf",
+    _ => {
+      let text = std::str::from_utf8(bytes).ok()?.trim_start_matches('\u{feff}').trim_start();
+      if text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")) {
+        "image/svg+xml"
+      } else {
+        return None;
+      }
+    }
+  };
+  Some(mime_type)
+}
+
+/// Returns the MIME type of an asset with the given file extension.
+fn mime_type_for_extension(extension: &str) -> Option<&'static str> {
+  let mime_type = match extension.to_ascii_lowercase().as_str() {
+    "png" => "image/png",
+    "apng" => "image/apng",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "svg" => "image/svg+xml",
+    "bmp" => "image/bmp",
+    "ico" | "cur" => "image/x-icon",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+    "eot" => "application/vnd.ms-fontobject",
+    _ => return None,
+  };
+  Some(mime_type)
+}
+
+/// Encodes an asset as a `data:` URI. SVG images are percent encoded if that is shorter than base64.
+fn data_url(mime_type: &str, bytes: &[u8]) -> String {
+  let base64 = format!("data:{};base64,{}", mime_type, BASE64.encode(bytes));
+  if mime_type != "image/svg+xml" {
+    return base64;
+  }
+
+  let mut percent = format!("data:{},", mime_type);
+  for &b in bytes {
+    if b.is_ascii_alphanumeric() || b"-._~!$&*+,/:;=?@[]".contains(&b) {
+      percent.push(b as char);
+    } else {
+      percent.push_str(&format!("%{:02X}", b));
+    }
+  }
+
+  if percent.len() < base64.len() {
+    percent
+  } else {
+    base64
+  }
+}
+
+/// An error that could occur during bundling.
+#[derive(Debug)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(serde::Serialize))]
+pub enum BundleErrorKind<'i, T: std::error::Error> {
+  /// A parser error occurred.
+  ParserError(ParserError<'i>),
+  /// An unsupported `@import` condition was encountered.
+  UnsupportedImportCondition,
+  /// An unsupported cascade layer combination was encountered.
+  UnsupportedLayerCombination,
+  /// Unsupported media query boolean logic was encountered.
+  UnsupportedMediaBooleanLogic,
+  /// A custom resolver error.
+  ResolverError(#[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))] T),
+  /// A circular `@import` was encountered. Contains the locations of the `@import` rules
+  /// that form the cycle, starting from the first file in the cycle.
+  CircularImport(Vec<ErrorLocation>),
+  /// A printer error occurred while compiling a CSS module.
+  PrinterError(PrinterErrorKind),
+}
+
+impl<'i, T: std::error::Error> From<Error<ParserError<'i>>> for Error<BundleErrorKind<'i, T>> {
+  fn from(err: Error<ParserError<'i>>) -> Self {
+    Error {
+      kind: BundleErrorKind::ParserError(err.kind),
+      loc: err.loc,
+    }
+  }
+}
+
+impl<'i, T: std::error::Error> std::fmt::Display for BundleErrorKind<'i, T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    use BundleErrorKind::*;
+    match self {
+      ParserError(err) => err.fmt(f),
+      UnsupportedImportCondition => write!(f, "Unsupported import condition"),
+      UnsupportedLayerCombination => write!(f, "Unsupported layer combination in @import"),
+      UnsupportedMediaBooleanLogic => write!(f, "Unsupported boolean logic in @import media query"),
+      ResolverError(err) => std::fmt::Display::fmt(&err, f),
+      PrinterError(err) => err.fmt(f),
+      CircularImport(cycle) => {
+        write!(f, "Circular @import: ")?;
+        for loc in cycle {
+          write!(f, "{} -> ", loc)?;
+        }
+        match cycle.first() {
+          Some(loc) => write!(f, "{}", loc.filename),
+          None => Ok(()),
+        }
+      }
+    }
+  }
+}
+
+impl<'i, T: std::error::Error> BundleErrorKind<'i, T> {
+  #[deprecated(note = "use `BundleErrorKind::to_string()` or `std::fmt::Display` instead")]
+  #[allow(missing_docs)]
+  pub fn reason(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl<'a, 'o, 's, P: SourceProvider, T: AtRuleParser<'a> + Clone + Sync + Send> Bundler<'a, 'o, 's, P, T>
+where
+  T::AtRule: Sync + Send + ToCss,
+{
+  /// Creates a new Bundler using the given source provider.
+  /// If a source map is given, the content of each source file included in the bundle will
+  /// be added accordingly.
+  pub fn new(fs: &'a P, source_map: Option<&'s mut SourceMap>, options: ParserOptions<'o, 'a, T>) -> Self {
+    Bundler {
+      source_map: source_map.map(Mutex::new),
+      fs,
+      source_indexes: DashMap::new(),
+      stylesheets: Mutex::new(Vec::new()),
+      options,
+      circular_imports: CircularImports::default(),
+      cycles: Vec::new(),
+      external: Vec::new(),
+      virtual_modules: HashMap::new(),
+      incremental: false,
+      duplicate_imports: DuplicateImports::default(),
+      content_hashes: DashMap::new(),
+      imported_values: HashMap::new(),
+    }
+  }
+
+  /// Sets how files with identical contents imported from different paths are handled.
+  /// By default, each path is bundled separately.
+  pub fn set_duplicate_imports(&mut self, duplicate_imports: DuplicateImports) {
+    self.duplicate_imports = duplicate_imports;
+  }
+
+  /// Sets how circular `@import` rules are handled. By default, they are ignored.
+  pub fn set_circular_imports(&mut self, circular_imports: CircularImports) {
+    self.circular_imports = circular_imports;
+  }
+
+  /// Sets the import specifiers that are preserved as `@import` rules in the output rather than being
+  /// bundled, e.g. `https://fonts.googleapis.com/*`. A `*` wildcard matches any sequence of characters.
+  ///
+  /// External imports are hoisted to the top of the bundle, combined with the media queries, supports
+  /// conditions and cascade layers of the files that import them.
+  pub fn set_external(&mut self, external: Vec<String>) {
+    self.external = external;
+  }
+
+  /// Registers an in-memory style sheet under a virtual specifier, e.g. `virtual:theme.css`.
+  /// An `@import` of the exact specifier, or an entry with it as its path, loads the given code
+  /// instead of asking the [SourceProvider](SourceProvider). This allows plugins to inject generated
+  /// CSS, such as design tokens, into the import graph.
+  ///
+  /// Imports within a virtual module are resolved by the source provider as if the module were
+  /// located at the path given by its specifier.
+  pub fn add_virtual_module(&mut self, specifier: &str, code: &'a str) {
+    self.virtual_modules.insert(specifier.to_owned(), code);
+  }
+
+  /// Returns the circular imports found by the last call to [bundle](Bundler::bundle). Each cycle
+  /// contains the locations of the `@import` rules that form it, starting from the first file in the cycle.
+  pub fn circular_imports(&self) -> &[Vec<ErrorLocation>] {
+    &self.cycles
+  }
+
+  /// Returns the dependency graph of the files loaded by this bundler, including the conditions
+  /// each `@import` was included under. This can be serialized and used by build tools to implement
+  /// caching and invalidation without parsing the files again.
+  pub fn import_graph(&self) -> ImportGraph {
+    let mut paths = vec![PathBuf::new(); self.source_indexes.len()];
+    for entry in self.source_indexes.iter() {
+      paths[*entry.value() as usize] = entry.key().clone();
+    }
+
+    let stylesheets = self.stylesheets.lock().unwrap();
+    let files = stylesheets
+      .iter()
+      .zip(&paths)
+      .map(|(stylesheet, path)| ImportGraphFile {
+        path: path.clone(),
+        content_hash: stylesheet.content_hash.clone(),
+        imports: stylesheet
+          .dependencies
+          .iter()
+          .map(|dep| ImportGraphEdge {
+            specifier: dep.import.url.to_string(),
+            loc: ErrorLocation::new(
+              dep.loc,
+              paths[dep.loc.source_index as usize].to_string_lossy().into_owned(),
+            ),
+            files: dep.source_indexes.iter().map(|index| *index as usize).collect(),
+            external: dep.external.is_some(),
+            conditions: dep.conditions.to_strings(),
+          })
+          .collect(),
+        composes: stylesheet.css_modules_deps.iter().map(|index| *index as usize).collect(),
+      })
+      .collect();
+
+    ImportGraph { files }
+  }
+
+  /// Bundles the given entry file and all dependencies into a single style sheet.
+  pub fn bundle<'e>(
+    &mut self,
+    entry: &'e Path,
+  ) -> Result<StyleSheet<'a, 'o, T>, Error<BundleErrorKind<'a, P::Error>>> {
+    // Phase 1: load and parse all files. This is done in parallel.
+    let entry = self.load_file(entry, ImportConditions::new(entry_import()))?;
+    self.resolve_values();
+
+    // Phase 2: determine the order that the files should be concatenated.
+    let cycles = self.order(entry);
+    self.set_cycles(cycles)?;
+
+    // Phase 3: concatenate.
+    let mut rules: Vec<CssRule<'a, T::AtRule>> = Vec::new();
+    let mut externals = Vec::new();
+    self.inline(&mut rules, &mut externals);
+
+    // Hoist external imports above all other rules, except the leading @layer statements.
+    let index = rules
+      .iter()
+      .position(|rule| !matches!(rule, CssRule::LayerStatement(..)))
+      .unwrap_or(rules.len());
+    rules.splice(index..index, externals.into_iter().map(CssRule::Import));
+
+    Ok(self.new_stylesheet(rules))
+  }
+
+  /// Bundles the given entry again after the given files changed, e.g. in watch mode. The parsed
+  /// style sheets of all other files are reused from previous calls, so only the changed files are
+  /// read, parsed and resolved again, along with any files they newly import.
+  ///
+  /// The first call bundles the entry from scratch. Unlike [bundle](Bundler::bundle), the parsed style
+  /// sheets are kept for later calls, so the same bundler should not also be used with `bundle`.
+  /// Files containing glob imports must be included in the changed files when files matching
+  /// the pattern are added or removed.
+  pub fn rebundle(
+    &mut self,
+    entry: &Path,
+    changed: &[&Path],
+  ) -> Result<StyleSheet<'a, 'o, T>, Error<BundleErrorKind<'a, P::Error>>>
+  where
+    T::AtRule: Clone,
+  {
+    self.incremental = true;
+    for file in changed {
+      let source_index = match self.source_indexes.get(*file) {
+        Some(source_index) => *source_index,
+        None => continue,
+      };
+
+      let stylesheet = &self.stylesheets.get_mut().unwrap()[source_index as usize];
+      let outer = stylesheet.outer.clone();
+      let rule = ImportRule {
+        layer: stylesheet.layer.clone(),
+        media: stylesheet.media.clone(),
+        supports: stylesheet.supports.clone(),
+        url: "".into(),
+        loc: stylesheet.loc,
+      };
+      let code = self.read_file(file, &rule)?;
+      self.parse_file(file, source_index, code, &outer, &rule)?;
+    }
+
+    let mut chunk_graph = self.bundle_entries(&[entry])?;
+    Ok(chunk_graph.chunks.remove(0).stylesheet)
+  }
+
+  /// Compiles the given file on its own as a CSS module, and returns its exports with the `composes`
+  /// references to other files resolved, as described in [resolve_composes](css_modules::resolve_composes).
+  /// The referenced files are read and compiled using the source provider, but unlike [bundle](Bundler::bundle),
+  /// their rules are not included in the output of the file. This requires the `css_modules` parser option,
+  /// and the project root must be the same as the one passed to the printer.
+  pub fn module_exports(
+    &self,
+    file: &Path,
+    project_root: Option<&str>,
+  ) -> Result<CssModuleExports, Error<BundleErrorKind<'a, P::Error>>> {
+    let mut exports = self.compile_module(file, project_root)?;
+    css_modules::resolve_composes(
+      &mut exports,
+      file.to_str().unwrap(),
+      |specifier, from| -> Result<_, Error<BundleErrorKind<'a, P::Error>>> {
+        let path = self.resolve(specifier, Path::new(from)).map_err(|err| Error {
+          kind: BundleErrorKind::ResolverError(err),
+          loc: None,
+        })?;
+        let exports = self.compile_module(&path, project_root)?;
+        Ok((path.to_str().unwrap().to_owned(), exports))
+      },
+    )?;
+    Ok(exports)
+  }
+
+  /// Parses and prints a single file as a CSS module, and returns its exports.
+  fn compile_module(
+    &self,
+    file: &Path,
+    project_root: Option<&str>,
+  ) -> Result<CssModuleExports, Error<BundleErrorKind<'a, P::Error>>> {
+    let code = match file.to_str().and_then(|file| self.virtual_modules.get(file)) {
+      Some(code) => *code,
+      None => self.fs.read(file).map_err(|err| Error {
+        kind: BundleErrorKind::ResolverError(err),
+        loc: None,
+      })?,
+    };
+
+    let mut opts = self.options.clone();
+    opts.filename = file.to_str().unwrap().to_owned();
+    opts.source_index = 0;
+    let stylesheet = StyleSheet::parse(code, opts)?;
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        project_root,
+        ..PrinterOptions::default()
+      })
+      .map_err(|err| Error {
+        kind: BundleErrorKind::PrinterError(err.kind),
+        loc: err.loc,
+      })?;
+    Ok(res.exports.unwrap_or_default())
+  }
+
+  /// Bundles multiple entry files into a graph of chunks. Files imported by more than one entry are
+  /// emitted into shared chunks, grouped by the set of entries that import them, and the files only
+  /// imported by a single entry into a chunk for that entry. The returned manifest lists the chunks
+  /// each entry needs, in the order they must be loaded.
+  ///
+  /// Each file is wrapped in the `@media`, `@supports` and `@layer` rules it was imported with, and the
+  /// files within a chunk are concatenated in the same order as when bundling the entries individually.
+  /// The `@layer` statements at the top of a file are emitted at the top of the chunk containing it.
+  pub fn bundle_entries(
+    &mut self,
+    entries: &[&Path],
+  ) -> Result<ChunkGraph<'a, 'o, T>, Error<BundleErrorKind<'a, P::Error>>>
+  where
+    T::AtRule: Clone,
+  {
+    // Phase 1: load and parse all files.
+    let entries = entries
+      .iter()
+      .map(|entry| self.load_file(entry, ImportConditions::new(entry_import())))
+      .collect::<Result<Vec<_>, _>>()?;
+    self.resolve_values();
+
+    // Phase 2: determine the files each entry includes, and the order they are concatenated in.
+    let mut cycles = Vec::new();
+    let mut orders = Vec::new();
+    for &entry in &entries {
+      for cycle in self.order(entry) {
+        if !cycles.contains(&cycle) {
+          cycles.push(cycle);
+        }
+      }
+      orders.push(concat_order(self.stylesheets.get_mut().unwrap(), entry));
+    }
+    self.set_cycles(cycles)?;
+
+    // Phase 3: group files into chunks by the set of entries that include them,
+    // in order of first use.
+    let conditions = entries
+      .iter()
+      .map(|entry| self.entry_conditions(*entry))
+      .collect::<Result<Vec<_>, _>>()?;
+    let mut file_entries: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (entry, order) in orders.iter().enumerate() {
+      for source_index in order {
+        file_entries.entry(*source_index).or_default().push(entry);
+      }
+    }
+
+    let mut chunks: Vec<(Vec<usize>, Vec<u32>)> = Vec::new();
+    let mut manifest = vec![Vec::new(); entries.len()];
+    for (entry, order) in orders.iter().enumerate() {
+      for source_index in order {
+        // A file imported with different conditions by different entries cannot be shared,
+        // so each entry includes its own copy.
+        let mut key = file_entries[source_index].clone();
+        if !key
+          .iter()
+          .all(|other| conditions[*other][source_index].is_equivalent(&conditions[entry][source_index]))
+        {
+          key = vec![entry];
+        }
+
+        let chunk = match chunks.iter().position(|(entries, _)| *entries == key) {
+          Some(chunk) => chunk,
+          None => {
+            chunks.push((key, Vec::new()));
+            chunks.len() - 1
+          }
+        };
+
+        if !chunks[chunk].1.contains(source_index) {
+          chunks[chunk].1.push(*source_index);
+        }
+
+        if !manifest[entry].contains(&chunk) {
+          manifest[entry].push(chunk);
+        }
+      }
+    }
+
+    // Phase 4: concatenate the files in each chunk.
+    let mut chunk_graph = ChunkGraph {
+      chunks: Vec::new(),
+      manifest,
+    };
+    for (entries, files) in chunks {
+      let mut statements = Vec::new();
+      let mut externals = Vec::new();
+      let mut rules = Vec::new();
+      for source_index in &files {
+        let conditions = &conditions[entries[0]][source_index];
+        let (file_statements, file_rules) = self.chunk_rules(*source_index, conditions, &mut externals)?;
+        statements.extend(file_statements);
+        rules.extend(file_rules);
+      }
+
+      statements.extend(externals.into_iter().map(CssRule::Import));
+      statements.extend(rules);
+      chunk_graph.chunks.push(Chunk {
+        entries,
+        files: files
+          .iter()
+          .map(|source_index| PathBuf::from(self.find_filename(*source_index)))
+          .collect(),
+        stylesheet: self.new_stylesheet(statements),
+      });
+    }
+
+    Ok(chunk_graph)
+  }
+
+  /// Creates a style sheet with the given rules, and the sources of all loaded files.
+  fn new_stylesheet(&mut self, rules: Vec<CssRule<'a, T::AtRule>>) -> StyleSheet<'a, 'o, T> {
+    let mut sources = Vec::new();
+    let mut source_map_urls = Vec::new();
+    let mut content_hashes = Vec::new();
+    let mut input_source_maps = Vec::new();
+    for s in self.stylesheets.get_mut().unwrap().iter() {
+      // Style sheets of duplicate files are empty, and have no source map url or content hash.
+      let stylesheet = s.stylesheet.as_ref().unwrap();
+      for i in 0..stylesheet.sources.len() {
+        sources.push(stylesheet.sources[i].clone());
+        source_map_urls.push(stylesheet.source_map_urls.get(i).cloned().flatten());
+        content_hashes.push(stylesheet.content_hashes.get(i).copied().flatten());
+        input_source_maps.push(stylesheet.input_source_maps.get(i).cloned().flatten());
+      }
+    }
+
+    let mut stylesheet = StyleSheet::new(sources, CssRuleList(rules), self.options.clone());
+    stylesheet.source_map_urls = source_map_urls;
+    stylesheet.content_hashes = content_hashes;
+    stylesheet.input_source_maps = input_source_maps;
+    stylesheet
+  }
+
+  /// Records the circular imports that were found, and returns an error for the first one
+  /// if they are not allowed.
+  fn set_cycles(&mut self, cycles: Vec<Vec<Location>>) -> Result<(), Error<BundleErrorKind<'a, P::Error>>> {
+    self.cycles = cycles
+      .iter()
+      .map(|cycle| {
+        cycle
+          .iter()
+          .map(|loc| ErrorLocation::new(*loc, self.find_filename(loc.source_index)))
+          .collect()
+      })
+      .collect();
+
+    match (self.circular_imports, self.cycles.first()) {
+      (CircularImports::Error, Some(cycle)) => Err(Error {
+        loc: cycle.last().cloned(),
+        kind: BundleErrorKind::CircularImport(cycle.clone()),
+      }),
+      _ => Ok(()),
+    }
+  }
+
+  /// Resolves an import specifier, checking the virtual modules before the source provider.
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, P::Error> {
+    if self.virtual_modules.contains_key(specifier) {
+      return Ok(PathBuf::from(specifier));
+    }
+
+    self.fs.resolve(specifier, originating_file)
+  }
+
+  fn find_filename(&self, source_index: u32) -> String {
+    // This function is only used for error handling, so it's ok if this is a bit slow.
+    let entry = self.source_indexes.iter().find(|x| *x.value() == source_index).unwrap();
+    entry.key().to_str().unwrap().into()
+  }
+
+  fn load_file(
+    &self,
+    file: &Path,
+    conditions: ImportConditions<'a>,
+  ) -> Result<u32, Error<BundleErrorKind<'a, P::Error>>> {
+    let ImportConditions { outer, rule } = conditions;
+
+    // Check if we already loaded this file.
+    let mut stylesheets = self.stylesheets.lock().unwrap();
+    let source_index = match self.source_indexes.get(file) {
+      Some(source_index) => {
+        // If we already loaded this file, combine the media queries and supports conditions
+        // from this import rule with the existing ones using a logical or operator.
+        if self.incremental {
+          return Ok(*source_index);
+        }
+
+        let entry = &mut stylesheets[*source_index as usize];
+        if let Some(original) = entry.duplicate_of {
+          drop(stylesheets);
+          let original_filename = self.find_filename(original);
+          return self.load_file(Path::new(&original_filename), ImportConditions { outer, rule });
+        }
+
+        self.merge_outer_conditions(&entry.outer, &outer, &rule)?;
+        self.merge_import_conditions(&mut entry.media, &mut entry.supports, &mut entry.layer, &rule)?;
+        return Ok(*source_index);
+      }
+      None => {
+        let source_index = stylesheets.len() as u32;
+        self.source_indexes.insert(file.to_owned(), source_index);
+
+        stylesheets.push(BundleStyleSheet {
+          stylesheet: None,
+          layer: rule.layer.clone(),
+          media: rule.media.clone(),
+          supports: rule.supports.clone(),
+          outer: outer.clone(),
+          loc: rule.loc,
+          dependencies: Vec::new(),
+          css_modules_deps: Vec::new(),
+          value_deps: HashMap::new(),
+          parent_source_index: 0,
+          parent_dep_index: 0,
+          content_hash: String::new(),
+          duplicate_of: None,
+        });
+
+        source_index
+      }
+    };
+
+    drop(stylesheets); // ensure we aren't holding the lock anymore
+
+    let code = self.read_file(file, &rule)?;
+    if self.duplicate_imports != DuplicateImports::Keep {
+      let content_hash = integrity_hash("sha256", code.as_bytes()).unwrap();
+      let original = *self.content_hashes.entry(content_hash.clone()).or_insert(source_index);
+      if original != source_index {
+        return self.load_duplicate(
+          file,
+          source_index,
+          original,
+          content_hash,
+          ImportConditions { outer, rule },
+        );
+      }
+    }
+
+    self.parse_file(file, source_index, code, &outer, &rule)?;
+    Ok(source_index)
+  }
+
+  /// Loads a file with the same contents as an already loaded file by importing the original
+  /// file instead. The duplicate is left as an empty style sheet.
+  fn load_duplicate(
+    &self,
+    file: &Path,
+    source_index: u32,
+    original: u32,
+    content_hash: String,
+    conditions: ImportConditions<'a>,
+  ) -> Result<u32, Error<BundleErrorKind<'a, P::Error>>> {
+    let filename = file.to_str().unwrap();
+    let original_filename = self.find_filename(original);
+
+    {
+      let mut stylesheets = self.stylesheets.lock().unwrap();
+      let entry = &mut stylesheets[source_index as usize];
+      let mut opts = self.options.clone();
+      opts.filename = filename.to_owned();
+      opts.source_index = source_index;
+      entry.stylesheet = Some(StyleSheet::new(
+        vec![filename.to_owned()],
+        CssRuleList(Vec::new()),
+        opts,
+      ));
+      entry.content_hash = content_hash;
+      entry.duplicate_of = Some(original);
+    }
+
+    if self.duplicate_imports == DuplicateImports::Warn {
+      if let Some(warnings) = &self.options.warnings {
+        if let Ok(mut warnings) = warnings.write() {
+          let loc = conditions.rule.loc;
+          warnings.push(Error {
+            kind: ParserError::DuplicateImport(original_filename.clone().into()),
+            loc: Some(ErrorLocation::new(loc, self.find_filename(loc.source_index))),
+          });
+        }
+      }
+    }
+
+    self.load_file(Path::new(&original_filename), conditions)
+  }
+
+  /// Reads the contents of a file, from the virtual modules or the source provider.
+  fn read_file(
+    &self,
+    file: &Path,
+    rule: &ImportRule<'a>,
+  ) -> Result<&'a str, Error<BundleErrorKind<'a, P::Error>>> {
+    match file.to_str().and_then(|file| self.virtual_modules.get(file)) {
+      Some(code) => Ok(*code),
+      None => self.fs.read(file).map_err(|e| Error {
+        kind: BundleErrorKind::ResolverError(e),
+        loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
+      }),
+    }
+  }
+
+  /// Parses a file, and loads its dependencies.
+  fn parse_file(
+    &self,
+    file: &Path,
+    source_index: u32,
+    code: &'a str,
+    outer: &[ImportRule<'a>],
+    rule: &ImportRule<'a>,
+  ) -> Result<(), Error<BundleErrorKind<'a, P::Error>>> {
+    let mut opts = self.options.clone();
+    let filename = file.to_str().unwrap();
+    opts.filename = filename.to_owned();
+    opts.source_index = source_index;
+
+    let mut stylesheet = StyleSheet::parse(code, opts)?;
+
+    if let Some(source_map) = &self.source_map {
+      // Load a source map file referenced by a `sourceMappingURL` comment, e.g. written by Sass
+      // alongside the compiled CSS. Inline source maps are loaded by the printer.
+      if let Some(url) = stylesheet.source_map_url(0) {
+        if !url.starts_with("data") && !is_absolute_url(url) {
+          let path = file.parent().map_or_else(|| PathBuf::from(url), |dir| dir.join(url));
+          if let Some(sm) = self.fs.read(&path).ok().and_then(|json| source_map_from_json("/", json).ok()) {
+            stylesheet.set_input_source_map(0, sm);
+          }
+        }
+      }
+
+      // Only add source if we don't have an input source map.
+      // If we do, this will be handled by the printer when remapping locations.
+      if !stylesheet.has_input_source_map(0) {
+        let mut source_map = source_map.lock().unwrap();
+        let source_index = source_map.add_source(filename);
+        let _ = source_map.set_source_content(source_index as usize, code);
+      }
+    }
+
+    // Collect and load dependencies for this stylesheet in parallel.
+    let dependencies: Result<Vec<ImportDependency<'a>>, _> = stylesheet
+      .rules
+      .0
+      .par_iter_mut()
+      .filter_map(|r| {
+        // Prepend parent layer name to @layer statements.
+        if let CssRule::LayerStatement(layer) = r {
+          if let Some(Some(parent_layer)) = &rule.layer {
+            for name in &mut layer.names {
+              name.0.insert_many(0, parent_layer.0.iter().cloned())
+            }
+          }
+        }
+
+        if let CssRule::Import(import) = r {
+          let specifier = &import.url;
+          let combined = match self.combine_import_conditions(outer, rule, import) {
+            Ok(combined) => combined,
+            Err(err) => return Some(Err(err)),
+          };
+
+          if !self.virtual_modules.contains_key(specifier.as_ref())
+            && self.external.iter().any(|pattern| matches_pattern(pattern, specifier))
+          {
+            return Some(
+              self
+                .external_import(specifier, combined.clone())
+                .map(|external| ImportDependency {
+                  loc: import.loc,
+                  source_indexes: Vec::new(),
+                  import: import.clone(),
+                  conditions: combined,
+                  external: Some(external),
+                }),
+            );
+          }
+
+          let paths = if is_glob(specifier) && !self.virtual_modules.contains_key(specifier.as_ref()) {
+            self.fs.resolve_glob(specifier, file).map(|mut paths| {
+              // Sort the matches so the bundle is deterministic, and skip the importing file itself.
+              paths.retain(|path| path != file);
+              paths.sort();
+              paths.dedup();
+              paths
+            })
+          } else {
+            self.resolve(specifier, file).map(|path| vec![path])
+          };
+
+          let result = match paths {
+            Ok(paths) => paths
+              .iter()
+              .map(|path| self.load_file(path, combined.clone()))
+              .collect::<Result<_, _>>()
+              .map(|source_indexes| ImportDependency {
+                loc: import.loc,
+                source_indexes,
+                import: import.clone(),
+                conditions: combined,
+                external: None,
+              }),
+            Err(err) => Err(Error {
+              kind: BundleErrorKind::ResolverError(err),
+              loc: Some(ErrorLocation::new(
+                import.loc,
+                self.find_filename(import.loc.source_index),
+              )),
+            }),
+          };
+
+          Some(result)
+        } else {
+          None
+        }
+      })
+      .collect();
+
+    // Collect CSS modules dependencies from the `composes` property.
+    let css_modules_deps: Result<Vec<u32>, _> = if self.options.css_modules.is_some() {
+      stylesheet
+        .rules
+        .0
+        .par_iter_mut()
+        .filter_map(|r| {
+          if let CssRule::Style(style) = r {
+            Some(
+              style
+                .declarations
+                .declarations
+                .par_iter_mut()
+                .chain(style.declarations.important_declarations.par_iter_mut())
+                .filter_map(|d| match d {
+                  Property::Composes(composes) => self
+                    .add_css_module_dep(file, outer, rule, style.loc, composes.loc, &mut composes.from)
+                    .map(|result| rayon::iter::Either::Left(rayon::iter::once(result))),
+
+                  // Handle variable references if the dashed_idents option is present.
+                  Property::Custom(CustomProperty { value, .. })
+                  | Property::Unparsed(UnparsedProperty { value, .. })
+                    if matches!(&self.options.css_modules, Some(css_modules) if css_modules.dashed_idents) =>
+                  {
+                    Some(rayon::iter::Either::Right(visit_vars(value).filter_map(|name| {
+                      self.add_css_module_dep(
+                        file,
+                        outer,
+                        rule,
+                        style.loc,
+                        // TODO: store loc in variable reference?
+                        crate::dependencies::Location {
+                          line: style.loc.line,
+                          column: style.loc.column,
+                        },
+                        &mut name.from,
+                      )
+                    })))
+                  }
+                  _ => None,
+                })
+                .flatten(),
+            )
+          } else {
+            None
+          }
+        })
+        .flatten()
+        .collect()
+    } else {
+      Ok(vec![])
+    };
+
+    // Load the files that values are imported from by `@value` rules. Like the files referenced
+    // by `composes`, they are included in the bundle.
+    let mut css_modules_deps = css_modules_deps?;
+    let mut value_deps = HashMap::new();
+    for r in &stylesheet.rules.0 {
+      if let CssRule::Value(ValueRule {
+        value: ModuleValue::Import { specifier, .. },
+        loc,
+      }) = r
+      {
+        let dep_loc = crate::dependencies::Location {
+          line: loc.line,
+          column: loc.column,
+        };
+        let mut dep = Some(Specifier::File(specifier.clone()));
+        if let Some(result) = self.add_css_module_dep(file, outer, rule, *loc, dep_loc, &mut dep) {
+          let dep_source_index = result?;
+          css_modules_deps.push(dep_source_index);
+          value_deps.insert(specifier.to_string(), dep_source_index);
+        }
+      }
+    }
+
+    let entry = &mut self.stylesheets.lock().unwrap()[source_index as usize];
+    entry.stylesheet = Some(stylesheet);
+    entry.dependencies = dependencies?;
+    entry.css_modules_deps = css_modules_deps;
+    entry.value_deps = value_deps;
+    entry.content_hash = integrity_hash("sha256", code.as_bytes()).unwrap();
+
+    Ok(())
+  }
+
+  /// Combines the media queries, supports conditions and layer of a file with those of an
+  /// `@import` rule within it, returning the conditions for the imported file.
+  ///
+  /// Conditions are intersected where possible. Media queries that cannot be intersected, and
+  /// anonymous layers, which are distinct from any layer nested inside them, are instead kept
+  /// as an outer level, so the imported rules are wrapped in nested rules.
+  fn combine_import_conditions(
+    &self,
+    outer: &[ImportRule<'a>],
+    parent: &ImportRule<'a>,
+    import: &ImportRule<'a>,
+  ) -> Result<ImportConditions<'a>, Error<BundleErrorKind<'a, P::Error>>> {
+    let mut level = ImportRule {
+      layer: None,
+      media: MediaList::new(),
+      supports: None,
+      url: "".into(),
+      loc: parent.loc,
+    };
+
+    // Combine media queries and supports conditions from parent
+    // stylesheet with @import rule using a logical and operator.
+    let mut media = parent.media.clone();
+    if media.and(&import.media).is_err() {
+      level.media = parent.media.clone();
+      media = import.media.clone();
+    }
+
+    let layer = if (parent.layer == Some(None) && import.layer.is_some())
+      || (import.layer == Some(None) && parent.layer.is_some())
+    {
+      level.layer = parent.layer.clone();
+      import.layer.clone()
+    } else if let Some(Some(a)) = &parent.layer {
+      if let Some(Some(b)) = &import.layer {
+        let mut name = a.clone();
+        name.0.extend(b.0.iter().cloned());
+        Some(Some(name))
+      } else {
+        Some(Some(a.clone()))
+      }
+    } else {
+      import.layer.clone()
+    };
+
+    let mut outer = outer.to_vec();
+    if level.layer.is_some() || !level.media.media_queries.is_empty() {
+      outer.push(level);
+    }
+
+    Ok(ImportConditions {
+      outer,
+      rule: ImportRule {
+        layer,
+        media,
+        supports: combine_supports(parent.supports.clone(), &import.supports),
+        url: "".into(),
+        loc: import.loc,
+      },
+    })
+  }
+
+  /// Returns an external `@import` rule for the given url with the given combined conditions.
+  /// Conditions that require nested rules cannot be expressed by an `@import` rule.
+  fn external_import(
+    &self,
+    url: &CowArcStr<'a>,
+    conditions: ImportConditions<'a>,
+  ) -> Result<ImportRule<'a>, Error<BundleErrorKind<'a, P::Error>>> {
+    if !conditions.outer.is_empty() {
+      return Err(Error {
+        kind: BundleErrorKind::UnsupportedImportCondition,
+        loc: Some(ErrorLocation::new(
+          conditions.rule.loc,
+          self.find_filename(conditions.rule.loc.source_index),
+        )),
+      });
+    }
+
+    Ok(ImportRule {
+      url: url.clone(),
+      ..conditions.rule
+    })
+  }
+
+  /// Checks that another `@import` of an already loaded file is nested in the same outer
+  /// conditions. Different outer conditions cannot be merged without duplicating the rules.
+  fn merge_outer_conditions(
+    &self,
+    existing: &[ImportRule<'a>],
+    outer: &[ImportRule<'a>],
+    rule: &ImportRule<'a>,
+  ) -> Result<(), Error<BundleErrorKind<'a, P::Error>>> {
+    if same_outer_conditions(existing, outer) {
+      return Ok(());
+    }
+
+    Err(Error {
+      kind: BundleErrorKind::UnsupportedImportCondition,
+      loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
+    })
+  }
+
+  /// Merges the conditions of another `@import` of an already loaded file into its existing
+  /// conditions using a logical or operator.
+  fn merge_import_conditions(
+    &self,
+    media: &mut MediaList<'a>,
+    supports: &mut Option<SupportsCondition<'a>>,
+    layer: &mut Option<Option<LayerName<'a>>>,
+    rule: &ImportRule<'a>,
+  ) -> Result<(), Error<BundleErrorKind<'a, P::Error>>> {
+    // We cannot combine a media query and a supports query from different @import rules.
+    // e.g. @import "a.css" print; @import "a.css" supports(color: red);
+    // This would require duplicating the actual rules in the file.
+    if (!rule.media.media_queries.is_empty() && supports.is_some())
+      || (!media.media_queries.is_empty() && rule.supports.is_some())
+    {
+      return Err(Error {
+        kind: BundleErrorKind::UnsupportedImportCondition,
+        loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
+      });
+    }
+
+    if rule.media.media_queries.is_empty() {
+      media.media_queries.clear();
+    } else if !media.media_queries.is_empty() {
+      media.or(&rule.media);
+    }
+
+    if let Some(rule_supports) = &rule.supports {
+      if let Some(existing_supports) = supports {
+        existing_supports.or(rule_supports)
+      }
+    } else {
+      *supports = None;
+    }
+
+    if let Some(rule_layer) = &rule.layer {
+      if let Some(existing_layer) = layer {
+        // We can't OR layer names without duplicating all of the nested rules, so error for now.
+        if rule_layer != existing_layer || (rule_layer.is_none() && existing_layer.is_none()) {
+          return Err(Error {
+            kind: BundleErrorKind::UnsupportedLayerCombination,
+            loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
+          });
+        }
+      } else {
+        *layer = rule.layer.clone();
+      }
+    }
+
+    Ok(())
+  }
+
+  fn add_css_module_dep(
+    &self,
+    file: &Path,
+    outer: &[ImportRule<'a>],
+    rule: &ImportRule<'a>,
+    style_loc: Location,
+    loc: crate::dependencies::Location,
+    specifier: &mut Option<Specifier>,
+  ) -> Option<Result<u32, Error<BundleErrorKind<'a, P::Error>>>> {
+    if let Some(Specifier::File(f)) = specifier {
+      let result = match self.resolve(f, file) {
+        Ok(path) => {
+          let res = self.load_file(
+            &path,
+            ImportConditions {
+              outer: outer.to_vec(),
+              rule: ImportRule {
+                layer: rule.layer.clone(),
+                media: rule.media.clone(),
+                supports: rule.supports.clone(),
+                url: "".into(),
+                loc: Location {
+                  source_index: style_loc.source_index,
+                  line: loc.line,
+                  column: loc.column,
+                },
+              },
+            },
+          );
+
+          if let Ok(source_index) = res {
+            *specifier = Some(Specifier::SourceIndex(source_index));
+          }
+
+          res
+        }
+        Err(err) => Err(Error {
+          kind: BundleErrorKind::ResolverError(err),
+          loc: Some(ErrorLocation::new(
+            style_loc,
+            self.find_filename(style_loc.source_index),
+          )),
+        }),
+      };
+      Some(result)
+    } else {
+      None
+    }
+  }
+
+  /// Resolves the values imported by the `@value` rules of each loaded file from the files they
+  /// are imported from. Values imported from a file that circularly imports the importing file
+  /// are left unresolved.
+  fn resolve_values(&mut self) {
+    let stylesheets = self.stylesheets.get_mut().unwrap();
+    let mut exports = HashMap::new();
+    let mut imported_values = HashMap::new();
+    for source_index in 0..stylesheets.len() as u32 {
+      process(stylesheets, source_index, &mut exports, &mut imported_values);
+    }
+    self.imported_values = imported_values;
+
+    /// Returns the values exported by a file, with their own imported values resolved.
+    fn process<'a, T: AtRuleParser<'a>>(
+      stylesheets: &[BundleStyleSheet<'a, '_, T>],
+      source_index: u32,
+      exports: &mut HashMap<u32, Option<ModuleValueMap<'a>>>,
+      imported_values: &mut HashMap<u32, ModuleValueMap<'a>>,
+    ) -> ModuleValueMap<'a> {
+      // A file that is still being resolved is part of a cycle.
+      if let Some(values) = exports.get(&source_index) {
+        return values.clone().unwrap_or_default();
+      }
+
+      let entry = &stylesheets[source_index as usize];
+      let stylesheet = match &entry.stylesheet {
+        Some(stylesheet) => stylesheet,
+        None => return ModuleValueMap::new(),
+      };
+
+      exports.insert(source_index, None);
+      let filename = &stylesheet.sources[0];
+      let mut imported = ModuleValueMap::new();
+      let mut values = ModuleValueMap::new();
+      for rule in &stylesheet.rules.0 {
+        match rule {
+          CssRule::Value(ValueRule {
+            value: ModuleValue::Import { names, specifier },
+            ..
+          }) => {
+            let dep_values = match entry.value_deps.get(specifier.as_ref()) {
+              Some(dep_source_index) => process(stylesheets, *dep_source_index, exports, imported_values),
+              None => continue,
+            };
+
+            for import in names {
+              if let Some(value) = dep_values.get(&import.name.0) {
+                let placeholder = value_placeholder(filename, &import.name.0, specifier);
+                imported.insert(placeholder.into(), value.clone());
+                values.insert(import.local_name().0.clone(), value.clone());
+              }
+            }
+          }
+          CssRule::Value(ValueRule {
+            value: ModuleValue::Definition { name, value },
+            ..
+          }) => {
+            let mut value = value.clone();
+            substitute_values(&mut value, &imported);
+            values.insert(name.0.clone(), value);
+          }
+          _ => {}
+        }
+      }
+
+      if !imported.is_empty() {
+        imported_values.insert(source_index, imported);
+      }
+      exports.insert(source_index, Some(values.clone()));
+      values
+    }
+  }
+
+  /// Determines the order of the files, and returns the locations of the `@import` rules
+  /// forming each circular import that was found and ignored.
+  fn order(&mut self, entry: u32) -> Vec<Vec<Location>> {
+    let mut cycles = Vec::new();
+    process(
+      self.stylesheets.get_mut().unwrap(),
+      entry,
+      None,
+      &mut HashSet::new(),
+      &mut Vec::new(),
+      &mut cycles,
+    );
+    return cycles;
+
+    fn process<'i, T: AtRuleParser<'i>>(
+      stylesheets: &mut Vec<BundleStyleSheet<'i, '_, T>>,
+      source_index: u32,
+      import_loc: Option<Location>,
+      visited: &mut HashSet<u32>,
+      stack: &mut Vec<(u32, Option<Location>)>,
+      cycles: &mut Vec<Vec<Location>>,
+    ) {
+      if visited.contains(&source_index) {
+        return;
+      }
+
+      visited.insert(source_index);
+      stack.push((source_index, import_loc));
+
+      let mut dep_index = 0;
+      for i in 0..stylesheets[source_index as usize].css_modules_deps.len() {
+        let dep_source_index = stylesheets[source_index as usize].css_modules_deps[i];
+        let resolved = &mut stylesheets[dep_source_index as usize];
+
+        // CSS modules preserve the first instance of composed stylesheets.
+        if !visited.contains(&dep_source_index) {
+          resolved.parent_dep_index = dep_index;
+          resolved.parent_source_index = source_index;
+          process(stylesheets, dep_source_index, None, visited, stack, cycles);
+        }
+
+        dep_index += 1;
+      }
+
+      let dependencies: Vec<(Location, u32)> = stylesheets[source_index as usize]
+        .dependencies
+        .iter()
+        .flat_map(|dep| dep.source_indexes.iter().map(move |index| (dep.loc, *index)))
+        .collect();
+      for (loc, dep_source_index) in dependencies {
+        // Browsers ignore an @import of a style sheet that is already being imported.
+        if let Some(pos) = stack.iter().position(|(index, _)| *index == dep_source_index) {
+          // Only report cycles formed entirely by @import rules, and not by composes.
+          let cycle: Option<Vec<Location>> = stack[pos + 1..]
+            .iter()
+            .map(|(_, loc)| *loc)
+            .chain(std::iter::once(Some(loc)))
+            .collect();
+          cycles.extend(cycle);
+          dep_index += 1;
+          continue;
+        }
+
+        let resolved = &mut stylesheets[dep_source_index as usize];
+
+        // In browsers, every instance of an @import is evaluated, so we preserve the last.
+        resolved.parent_dep_index = dep_index;
+        resolved.parent_source_index = source_index;
+
+        process(stylesheets, dep_source_index, Some(loc), visited, stack, cycles);
+        dep_index += 1;
+      }
+
+      stack.pop();
+    }
+  }
+
+  fn inline(&mut self, dest: &mut Vec<CssRule<'a, T::AtRule>>, externals: &mut Vec<ImportRule<'a>>) {
+    process(
+      self.stylesheets.get_mut().unwrap(),
+      &self.imported_values,
+      0,
+      dest,
+      externals,
+    );
+
+    fn process<'a, T: AtRuleParser<'a>>(
+      stylesheets: &mut Vec<BundleStyleSheet<'a, '_, T>>,
+      imported_values: &HashMap<u32, ModuleValueMap<'a>>,
+      source_index: u32,
+      dest: &mut Vec<CssRule<'a, T::AtRule>>,
+      externals: &mut Vec<ImportRule<'a>>,
+    ) {
+      let stylesheet = &mut stylesheets[source_index as usize];
+      let mut rules = std::mem::take(&mut stylesheet.stylesheet.as_mut().unwrap().rules.0);
+      if let Some(values) = imported_values.get(&source_index) {
+        let filename = &stylesheet.stylesheet.as_ref().unwrap().sources[0];
+        resolve_imported_values(&mut rules, values, filename);
+      }
+
+      // Hoist css modules deps
+      let mut dep_index = 0;
+      for i in 0..stylesheet.css_modules_deps.len() {
+        let dep_source_index = stylesheets[source_index as usize].css_modules_deps[i];
+        let resolved = &stylesheets[dep_source_index as usize];
+
+        // Include the dependency if this is the first instance as computed earlier.
+        if resolved.parent_source_index == source_index && resolved.parent_dep_index == dep_index {
+          process(stylesheets, imported_values, dep_source_index, dest, externals);
+        }
+
+        dep_index += 1;
+      }
+
+      let mut import_index = 0;
+      for rule in &mut rules {
+        match rule {
+          CssRule::Import(_) => {
+            let dependency = &stylesheets[source_index as usize].dependencies[import_index];
+            if let Some(external) = &dependency.external {
+              add_external_import(externals, external);
+            }
+
+            for dep_source_index in dependency.source_indexes.clone() {
+              let resolved = &stylesheets[dep_source_index as usize];
+
+              // Include the dependency if this is the last instance as computed earlier.
+              if resolved.parent_source_index == source_index && resolved.parent_dep_index == dep_index {
+                process(stylesheets, imported_values, dep_source_index, dest, externals);
+              }
+
+              dep_index += 1;
+            }
+
+            *rule = CssRule::Ignored;
+            import_index += 1;
+          }
+          CssRule::LayerStatement(_) => {
+            // @layer rules are the only rules that may appear before an @import.
+            // We must preserve this order to ensure correctness.
+            let layer = std::mem::replace(rule, CssRule::Ignored);
+            dest.push(layer);
+          }
+          CssRule::Ignored => {}
+          _ => break,
+        }
+      }
+
+      let stylesheet = &mut stylesheets[source_index as usize];
+      dest.extend(wrap_rules(
+        rules,
+        stylesheet.layer.take(),
+        std::mem::replace(&mut stylesheet.media, MediaList::new()),
+        stylesheet.supports.take(),
+        std::mem::take(&mut stylesheet.outer),
+        stylesheet.loc,
+      ));
+    }
+  }
+
+  /// Returns the rules of a file for a chunk: its leading `@layer` statements, and its remaining
+  /// rules wrapped in the given conditions. External imports are added to the given list.
+  fn chunk_rules(
+    &self,
+    source_index: u32,
+    conditions: &ImportConditions<'a>,
+    externals: &mut Vec<ImportRule<'a>>,
+  ) -> Result<ChunkRules<'a, T::AtRule>, Error<BundleErrorKind<'a, P::Error>>>
+  where
+    T::AtRule: Clone,
+  {
+    let stylesheets = self.stylesheets.lock().unwrap();
+    let stylesheet = &stylesheets[source_index as usize];
+    let mut rules = stylesheet.stylesheet.as_ref().unwrap().rules.0.clone();
+    if let Some(values) = self.imported_values.get(&source_index) {
+      let filename = &stylesheet.stylesheet.as_ref().unwrap().sources[0];
+      resolve_imported_values(&mut rules, values, filename);
+    }
+    let mut statements = Vec::new();
+    let mut import_index = 0;
+    for rule in &mut rules {
+      match rule {
+        CssRule::Import(_) => {
+          let dependency = &stylesheet.dependencies[import_index];
+          if dependency.external.is_some() {
+            let combined =
+              self.combine_import_conditions(&conditions.outer, &conditions.rule, &dependency.import)?;
+            add_external_import(externals, &self.external_import(&dependency.import.url, combined)?);
+          }
+          *rule = CssRule::Ignored;
+          import_index += 1;
+        }
+        CssRule::LayerStatement(_) => statements.push(std::mem::replace(rule, CssRule::Ignored)),
+        CssRule::Ignored => {}
+        _ => break,
+      }
+    }
+
+    let ImportConditions { outer, rule } = conditions.clone();
+    let rules = wrap_rules(rules, rule.layer, rule.media, rule.supports, outer, stylesheet.loc);
+    Ok((statements, rules))
+  }
+
+  /// Computes the conditions that each file is imported with when bundling the given entry
+  /// on its own, combining and merging them as `load_file` does.
+  fn entry_conditions(
+    &self,
+    entry: u32,
+  ) -> Result<HashMap<u32, ImportConditions<'a>>, Error<BundleErrorKind<'a, P::Error>>> {
+    let mut conditions = HashMap::new();
+    self.visit_conditions(entry, ImportConditions::new(entry_import()), &mut conditions)?;
+    Ok(conditions)
+  }
+
+  fn visit_conditions(
+    &self,
+    source_index: u32,
+    import_conditions: ImportConditions<'a>,
+    conditions: &mut HashMap<u32, ImportConditions<'a>>,
+  ) -> Result<(), Error<BundleErrorKind<'a, P::Error>>> {
+    let ImportConditions { outer, rule } = &import_conditions;
+    if let Some(existing) = conditions.get_mut(&source_index) {
+      self.merge_outer_conditions(&existing.outer, outer, rule)?;
+      let ImportRule {
+        media, supports, layer, ..
+      } = &mut existing.rule;
+      return self.merge_import_conditions(media, supports, layer, rule);
+    }
+
+    conditions.insert(source_index, import_conditions.clone());
+    let dependencies: Vec<(ImportRule<'a>, Vec<u32>)> = self.stylesheets.lock().unwrap()[source_index as usize]
+      .dependencies
+      .iter()
+      .map(|dep| (dep.import.clone(), dep.source_indexes.clone()))
+      .collect();
+
+    for (import, source_indexes) in dependencies {
+      let combined = self.combine_import_conditions(outer, rule, &import)?;
+      for dep_source_index in source_indexes {
+        self.visit_conditions(dep_source_index, combined.clone(), conditions)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Returns the files included in the bundle for an entry, in the order their rules are
+/// concatenated, following the parents computed by `Bundler::order`.
+fn concat_order<'i, T: AtRuleParser<'i>>(stylesheets: &[BundleStyleSheet<'i, '_, T>], entry: u32) -> Vec<u32> {
+  let mut files = Vec::new();
+  process(stylesheets, entry, &mut files);
+  return files;
+
+  fn process<'i, T: AtRuleParser<'i>>(
+    stylesheets: &[BundleStyleSheet<'i, '_, T>],
+    source_index: u32,
+    files: &mut Vec<u32>,
+  ) {
+    if files.contains(&source_index) {
+      return;
+    }
+
+    let stylesheet = &stylesheets[source_index as usize];
+    let deps = stylesheet
+      .css_modules_deps
+      .iter()
+      .chain(stylesheet.dependencies.iter().flat_map(|dep| dep.source_indexes.iter()));
+    for (dep_index, dep_source_index) in deps.enumerate() {
+      let resolved = &stylesheets[*dep_source_index as usize];
+      if resolved.parent_source_index == source_index && resolved.parent_dep_index == dep_index as u32 {
+        process(stylesheets, *dep_source_index, files);
+      }
+    }
+
+    files.push(source_index);
+  }
+}
+
+/// Wraps the rules of a file in the appropriate @layer, @media, and @supports rules,
+/// nested within the rules for each of the outer conditions.
+fn wrap_rules<'a, R>(
+  mut rules: Vec<CssRule<'a, R>>,
+  layer: Option<Option<LayerName<'a>>>,
+  media: MediaList<'a>,
+  supports: Option<SupportsCondition<'a>>,
+  outer: Vec<ImportRule<'a>>,
+  loc: Location,
+) -> Vec<CssRule<'a, R>> {
+  if let Some(name) = layer {
+    rules = vec![CssRule::LayerBlock(LayerBlockRule {
+      name,
+      rules: CssRuleList(rules),
+      loc,
+    })]
+  }
+
+  if !media.media_queries.is_empty() {
+    rules = vec![CssRule::Media(MediaRule {
+      query: media,
+      rules: CssRuleList(rules),
+      loc,
+    })]
+  }
+
+  if let Some(condition) = supports {
+    rules = vec![CssRule::Supports(SupportsRule {
+      condition,
+      rules: CssRuleList(rules),
+      loc,
+    })]
+  }
+
+  for rule in outer.into_iter().rev() {
+    rules = wrap_rules(rules, rule.layer, rule.media, rule.supports, Vec::new(), rule.loc);
+  }
+
+  rules
+}
+
+/// Returns the implicit import rule for an entry file.
+fn entry_import<'a>() -> ImportRule<'a> {
+  ImportRule {
+    url: "".into(),
+    layer: None,
+    supports: None,
+    media: MediaList::new(),
+    loc: Location {
+      source_index: 0,
+      line: 0,
+      column: 1,
+    },
+  }
+}
+
+/// Adds an external import to the list, merging it with an existing import of the same url
+/// with the same supports condition and layer by combining their media queries.
+fn add_external_import<'a>(externals: &mut Vec<ImportRule<'a>>, import: &ImportRule<'a>) {
+  let existing = externals.iter_mut().find(|existing| {
+    existing.url == import.url && existing.supports == import.supports && existing.layer == import.layer
+  });
+
+  match existing {
+    Some(existing) => {
+      if import.media.media_queries.is_empty() {
+        existing.media.media_queries.clear();
+      } else if !existing.media.media_queries.is_empty() {
+        existing.media.or(&import.media);
+      }
+    }
+    None => externals.push(import.clone()),
+  }
+}
+
+fn combine_supports<'a>(
+  a: Option<SupportsCondition<'a>>,
+  b: &Option<SupportsCondition<'a>>,
+) -> Option<SupportsCondition<'a>> {
+  if let Some(mut a) = a {
+    if let Some(b) = b {
+      a.and(b)
+    }
+    Some(a)
+  } else {
+    b.clone()
+  }
+}
+
+/// Replaces references to values imported by `@value` rules with the imported values, given the
+/// values keyed by placeholder. The resolved imports are replaced with definitions of the values,
+/// so that they are exported with their values rather than as references to the imported file.
+fn resolve_imported_values<'a, R>(rules: &mut Vec<CssRule<'a, R>>, values: &ModuleValueMap<'a>, filename: &str) {
+  let mut result = Vec::with_capacity(rules.len());
+  for mut rule in std::mem::take(rules) {
+    if let CssRule::Value(ValueRule {
+      value: ModuleValue::Import { names, specifier },
+      loc,
+    }) = &mut rule
+    {
+      names.retain(
+        |import| match values.get(value_placeholder(filename, &import.name.0, specifier).as_str()) {
+          Some(value) => {
+            result.push(CssRule::Value(ValueRule {
+              value: ModuleValue::Definition {
+                name: import.local_name().clone(),
+                value: value.clone(),
+              },
+              loc: *loc,
+            }));
+            false
+          }
+          None => true,
+        },
+      );
+
+      if names.is_empty() {
+        continue;
+      }
+    }
+
+    substitute_rule_values(&mut rule, values);
+    result.push(rule);
+  }
+
+  *rules = result;
+
+  fn substitute_rule_values<'a, R>(rule: &mut CssRule<'a, R>, values: &ModuleValueMap<'a>) {
+    let rules = match rule {
+      CssRule::Style(style) => {
+        substitute_declaration_values(&mut style.declarations, values);
+        &mut style.rules
+      }
+      CssRule::Nesting(nesting) => {
+        substitute_declaration_values(&mut nesting.style.declarations, values);
+        &mut nesting.style.rules
+      }
+      CssRule::Media(media) => &mut media.rules,
+      CssRule::Supports(supports) => &mut supports.rules,
+      CssRule::Container(container) => &mut container.rules,
+      CssRule::LayerBlock(layer) => &mut layer.rules,
+      CssRule::MozDocument(document) => &mut document.rules,
+      CssRule::Keyframes(keyframes) => {
+        for keyframe in &mut keyframes.keyframes {
+          substitute_declaration_values(&mut keyframe.declarations, values);
+        }
+        return;
+      }
+      CssRule::Value(ValueRule {
+        value: ModuleValue::Definition { value, .. },
+        ..
+      }) => {
+        substitute_values(value, values);
+        return;
+      }
+      _ => return,
+    };
+
+    for rule in &mut rules.0 {
+      substitute_rule_values(rule, values);
+    }
+  }
+
+  fn substitute_declaration_values<'a>(declarations: &mut DeclarationBlock<'a>, values: &ModuleValueMap<'a>) {
+    for property in declarations
+      .declarations
+      .iter_mut()
+      .chain(declarations.important_declarations.iter_mut())
+    {
+      match property {
+        Property::Custom(CustomProperty { value, .. }) | Property::Unparsed(UnparsedProperty { value, .. }) => {
+          substitute_values(value, values)
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Replaces the identifiers in a token list that are keys of the given values, including within
+/// function arguments.
+fn substitute_values<'a>(tokens: &mut TokenList<'a>, values: &ModuleValueMap<'a>) {
+  let mut result = Vec::with_capacity(tokens.0.len());
+  for mut token in std::mem::take(&mut tokens.0) {
+    if let TokenOrValue::Token(crate::properties::custom::Token::Ident(name)) = &token {
+      if let Some(value) = values.get(name) {
+        result.extend(value.0.iter().cloned());
+        continue;
+      }
+    }
+
+    if let TokenOrValue::Function(function) = &mut token {
+      substitute_values(&mut function.arguments, values);
+    }
+    result.push(token);
+  }
+
+  tokens.0 = result;
+}
+
+fn visit_vars<'a, 'b>(
+  token_list: &'b mut TokenList<'a>,
+) -> impl ParallelIterator<Item = &'b mut DashedIdentReference<'a>> {
+  let mut stack = vec![token_list.0.iter_mut()];
+  std::iter::from_fn(move || {
+    while !stack.is_empty() {
+      let iter = stack.last_mut().unwrap();
+      match iter.next() {
+        Some(TokenOrValue::Var(var)) => {
+          if let Some(fallback) = &mut var.fallback {
+            stack.push(fallback.0.iter_mut());
+          }
+          return Some(&mut var.name);
+        }
+        Some(TokenOrValue::Env(env)) => {
+          if let Some(fallback) = &mut env.fallback {
+            stack.push(fallback.0.iter_mut());
+          }
+          if let EnvironmentVariableName::Custom(name) = &mut env.name {
+            return Some(name);
+          }
+        }
+        Some(TokenOrValue::Attr(attr)) => {
+          if let Some(fallback) = &mut attr.fallback {
+            stack.push(fallback.0.iter_mut());
+          }
+        }
+        Some(TokenOrValue::Toggle(toggle)) => {
+          for value in &mut toggle.values {
+            stack.push(value.0.iter_mut());
+          }
+        }
+        Some(TokenOrValue::UnresolvedColor(color)) => match color {
+          UnresolvedColor::RGB { alpha, .. } | UnresolvedColor::HSL { alpha, .. } => {
+            stack.push(alpha.0.iter_mut());
+          }
+        },
+        None => {
+          stack.pop();
+        }
+        _ => {}
+      }
+    }
+    None
+  })
+  .par_bridge()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    css_modules::{self, CssModuleExports, CssModuleReference},
+    stylesheet::{MinifyOptions, PrinterOptions},
+    targets::Browsers,
+  };
+  use indoc::indoc;
+  use std::collections::HashMap;
+  use std::sync::{Arc, RwLock};
+
+  #[derive(Clone)]
+  struct TestProvider {
+    map: HashMap<PathBuf, String>,
+  }
+
+  impl SourceProvider for TestProvider {
+    type Error = std::io::Error;
+
+    fn read<'a>(&'a self, file: &Path) -> Result<&'a str, Self::Error> {
+      Ok(self.map.get(file).unwrap())
+    }
+
+    fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error> {
+      Ok(originating_file.with_file_name(specifier))
+    }
+
+    fn exists(&self, file: &Path) -> bool {
+      self.map.contains_key(file)
+    }
+
+    fn resolve_glob(&self, pattern: &str, originating_file: &Path) -> Result<Vec<PathBuf>, Self::Error> {
+      let pattern = originating_file.with_file_name(pattern);
+      Ok(
+        self
+          .map
+          .keys()
+          .filter(|file| glob_match(pattern.to_str().unwrap(), file.to_str().unwrap()))
+          .cloned()
+          .collect(),
+      )
+    }
+  }
+
+  /// Stand-in for a user-authored `SourceProvider` with application-specific logic.
+  struct CustomProvider {
+    map: HashMap<PathBuf, String>,
+  }
+
+  impl SourceProvider for CustomProvider {
+    type Error = std::io::Error;
+
+    /// Read files from in-memory map.
+    fn read<'a>(&'a self, file: &Path) -> Result<&'a str, Self::Error> {
+      Ok(self.map.get(file).unwrap())
+    }
+
+    /// Resolve by stripping a `foo:` prefix off any import. Specifiers without
+    /// this prefix fail with an error.
+    fn resolve(&self, specifier: &str, _originating_file: &Path) -> Result<PathBuf, Self::Error> {
+      if specifier.starts_with("foo:") {
+        Ok(Path::new(&specifier["foo:".len()..]).to_path_buf())
+      } else {
+        let err = std::io::Error::new(
+          std::io::ErrorKind::NotFound,
+          format!(
+            "Failed to resolve `{}`, specifier does not start with `foo:`.",
+            &specifier
+          ),
+        );
+
+        Err(err)
+      }
+    }
+  }
+
+  /// An async provider whose reads complete after yielding once to the executor.
+  struct AsyncTestProvider {
+    map: HashMap<PathBuf, String>,
+  }
+
+  impl AsyncSourceProvider for AsyncTestProvider {
+    type Error = std::io::Error;
+
+    fn read<'a>(&'a self, file: &Path) -> SourceFuture<'a, &'a str, Self::Error> {
+      let file = file.to_owned();
+      Box::pin(async move {
+        YieldOnce(false).await;
+        match self.map.get(&file) {
+          Some(code) => Ok(code.as_str()),
+          None => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found")),
+        }
+      })
+    }
+
+    fn resolve<'a>(&'a self, specifier: &str, originating_file: &Path) -> SourceFuture<'a, PathBuf, Self::Error> {
+      let path = originating_file.with_file_name(specifier);
+      Box::pin(async move { Ok(path) })
+    }
+  }
+
+  struct YieldOnce(bool);
+
+  impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+      if self.0 {
+        return Poll::Ready(());
+      }
+
+      self.0 = true;
+      cx.waker().wake_by_ref();
+      Poll::Pending
+    }
+  }
+
+  fn block_on<F: Future>(future: F) -> F::Output {
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+
+    fn raw_waker() -> RawWaker {
+      fn clone(_: *const ()) -> RawWaker {
+        raw_waker()
+      }
+      fn noop(_: *const ()) {}
+      static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+      RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    let waker = unsafe { Waker::from_raw(raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+      if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+        return output;
+      }
+    }
+  }
+
+  macro_rules! fs(
+    { $($key:literal: $value:expr),* } => {
+      {
+        #[allow(unused_mut)]
+        let mut m = HashMap::new();
+        $(
+          m.insert(PathBuf::from($key), $value.to_owned());
+        )*
+        m
+      }
+    };
+  );
+
+  fn bundle<P: SourceProvider>(fs: P, entry: &str) -> String {
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new(entry)).unwrap();
+    stylesheet.to_css(PrinterOptions::default()).unwrap().code
+  }
+
+  fn bundle_css_module<P: SourceProvider>(
+    fs: P,
+    entry: &str,
+    project_root: Option<&str>,
+  ) -> (String, CssModuleExports) {
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(css_modules::Config {
+          dashed_idents: true,
+          ..Default::default()
+        }),
+        ..ParserOptions::default()
+      },
+    );
+    let mut stylesheet = bundler.bundle(Path::new(entry)).unwrap();
+    stylesheet.minify(MinifyOptions::default()).unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        project_root,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    (res.code, res.exports.unwrap())
+  }
+
+  fn bundle_custom_media<P: SourceProvider>(fs: P, entry: &str) -> String {
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        custom_media: true,
+        ..ParserOptions::default()
+      },
+    );
+    let mut stylesheet = bundler.bundle(Path::new(entry)).unwrap();
+    let targets = Some(Browsers {
+      safari: Some(13 << 16),
+      ..Browsers::default()
+    });
+    stylesheet
+      .minify(MinifyOptions {
+        targets,
+        ..MinifyOptions::default()
+      })
+      .unwrap();
+    stylesheet
+      .to_css(PrinterOptions {
+        targets,
+        ..PrinterOptions::default()
+      })
+      .unwrap()
+      .code
+  }
+
+  fn error_test<P: SourceProvider>(
+    fs: P,
     entry: &str,
     maybe_cb: Option<Box<dyn FnOnce(BundleErrorKind<P::Error>) -> ()>>,
   ) {
@@ -875,36 +3409,282 @@ mod tests {
           cb(e.kind);
         }
       }
-    }
-  }
+    }
+  }
+
+  fn flatten_exports(exports: CssModuleExports) -> HashMap<String, String> {
+    let mut res = HashMap::new();
+    for (name, export) in &exports {
+      let mut classes = export.name.clone();
+      for composes in &export.composes {
+        classes.push(' ');
+        classes.push_str(match composes {
+          CssModuleReference::Local { name } => name,
+          CssModuleReference::Global { name } => name,
+          _ => unreachable!(),
+        })
+      }
+      res.insert(name.clone(), classes);
+    }
+    res
+  }
+
+  #[test]
+  fn test_bundle() {
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css";
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          .b { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      .b {
+        color: green;
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" print;
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          .b { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media print {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" supports(color: green);
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          .b { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @supports (color: green) {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" supports(color: green) print;
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          .b { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @supports (color: green) {
+        @media print {
+          .b {
+            color: green;
+          }
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" print;
+          @import "b.css" screen;
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          .b { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media print, screen {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" supports(color: red);
+          @import "b.css" supports(foo: bar);
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          .b { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @supports (color: red) or (foo: bar) {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" print;
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          @import "c.css" (color);
+          .b { color: yellow }
+        "#,
+          "/c.css": r#"
+          .c { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media print and (color) {
+        .c {
+          color: green;
+        }
+      }
+      
+      @media print {
+        .b {
+          color: #ff0;
+        }
+      }
+
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css";
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          @import "c.css";
+        "#,
+          "/c.css": r#"
+          @import "a.css";
+          .c { color: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      .c {
+        color: green;
+      }
 
-  fn flatten_exports(exports: CssModuleExports) -> HashMap<String, String> {
-    let mut res = HashMap::new();
-    for (name, export) in &exports {
-      let mut classes = export.name.clone();
-      for composes in &export.composes {
-        classes.push(' ');
-        classes.push_str(match composes {
-          CssModuleReference::Local { name } => name,
-          CssModuleReference::Global { name } => name,
-          _ => unreachable!(),
-        })
+      .a {
+        color: red;
       }
-      res.insert(name.clone(), classes);
-    }
-    res
-  }
+    "#}
+    );
 
-  #[test]
-  fn test_bundle() {
     let res = bundle(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css";
+          @import "b/c.css";
           .a { color: red }
         "#,
-          "/b.css": r#"
+          "/b/c.css": r#"
           .b { color: green }
         "#
         },
@@ -928,25 +3708,58 @@ mod tests {
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" print;
+          @import "./b/c.css";
           .a { color: red }
         "#,
-          "/b.css": r#"
+          "/b/c.css": r#"
           .b { color: green }
         "#
         },
       },
       "/a.css",
     );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      .b {
+        color: green;
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle_custom_media(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "media.css";
+          @import "b.css";
+          .a { color: red }
+        "#,
+          "/media.css": r#"
+          @custom-media --foo print;
+        "#,
+          "/b.css": r#"
+          @media (--foo) {
+            .a { color: green }
+          }
+        "#
+        },
+      },
+      "/a.css",
+    );
     assert_eq!(
       res,
       indoc! { r#"
       @media print {
-        .b {
+        .a {
           color: green;
         }
       }
-      
+
       .a {
         color: red;
       }
@@ -957,7 +3770,7 @@ mod tests {
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" supports(color: green);
+          @import "b.css" layer(foo);
           .a { color: red }
         "#,
           "/b.css": r#"
@@ -970,7 +3783,7 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @supports (color: green) {
+      @layer foo {
         .b {
           color: green;
         }
@@ -986,7 +3799,7 @@ mod tests {
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" supports(color: green) print;
+          @import "b.css" layer;
           .a { color: red }
         "#,
           "/b.css": r#"
@@ -999,11 +3812,9 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @supports (color: green) {
-        @media print {
-          .b {
-            color: green;
-          }
+      @layer {
+        .b {
+          color: green;
         }
       }
       
@@ -1017,12 +3828,15 @@ mod tests {
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" print;
-          @import "b.css" screen;
+          @import "b.css" layer(foo);
           .a { color: red }
         "#,
           "/b.css": r#"
+          @import "c.css" layer(bar);
           .b { color: green }
+        "#,
+          "/c.css": r#"
+          .c { color: green }
         "#
         },
       },
@@ -1031,7 +3845,13 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @media print, screen {
+      @layer foo.bar {
+        .c {
+          color: green;
+        }
+      }
+
+      @layer foo {
         .b {
           color: green;
         }
@@ -1047,9 +3867,8 @@ mod tests {
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" supports(color: red);
-          @import "b.css" supports(foo: bar);
-          .a { color: red }
+          @import "b.css" layer(foo);
+          @import "b.css" layer(foo);
         "#,
           "/b.css": r#"
           .b { color: green }
@@ -1061,28 +3880,167 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @supports (color: red) or (foo: bar) {
+      @layer foo {
         .b {
           color: green;
         }
       }
+    "#}
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @layer bar, foo;
+          @import "b.css" layer(foo);
+          
+          @layer bar {
+            div {
+              background: red;
+            }
+          }
+        "#,
+          "/b.css": r#"
+          @layer qux, baz;
+          @import "c.css" layer(baz);
+          
+          @layer qux {
+            div {
+              background: green;
+            }
+          }
+        "#,
+          "/c.css": r#"
+          div {
+            background: yellow;
+          }      
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @layer bar, foo;
+      @layer foo.qux, foo.baz;
+
+      @layer foo.baz {
+        div {
+          background: #ff0;
+        }
+      }
+
+      @layer foo {
+        @layer qux {
+          div {
+            background: green;
+          }
+        }
+      }
       
-      .a {
-        color: red;
+      @layer bar {
+        div {
+          background: red;
+        }
       }
     "#}
     );
 
+    // Layer order depends on @import conditions.
     let res = bundle(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" print;
-          .a { color: red }
+          @import "b.css" layer(bar) (min-width: 1000px);
+
+          @layer baz {
+            #box { background: purple }
+          }
+
+          @layer bar {
+            #box { background: yellow }
+          }
         "#,
           "/b.css": r#"
-          @import "c.css" (color);
-          .b { color: yellow }
+          #box { background: green }
+        "#
+        },
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media (min-width: 1000px) {
+        @layer bar {
+          #box {
+            background: green;
+          }
+        }
+      }
+      
+      @layer baz {
+        #box {
+          background: purple;
+        }
+      }
+      
+      @layer bar {
+        #box {
+          background: #ff0;
+        }
+      }
+    "#}
+    );
+
+    error_test(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" layer(foo);
+          @import "b.css" layer(bar);
+        "#,
+          "/b.css": r#"
+          .b { color: red }
+        "#
+        },
+      },
+      "/a.css",
+      Some(Box::new(|err| {
+        assert!(matches!(err, BundleErrorKind::UnsupportedLayerCombination));
+      })),
+    );
+
+    error_test(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" layer;
+          @import "b.css" layer;
+        "#,
+          "/b.css": r#"
+          .b { color: red }
+        "#
+        },
+      },
+      "/a.css",
+      Some(Box::new(|err| {
+        assert!(matches!(err, BundleErrorKind::UnsupportedLayerCombination));
+      })),
+    );
+
+    let res = bundle(
+      TestProvider {
+        map: fs! {
+          "/a.css": r#"
+          @import "b.css" layer;
+          .a { color: red }
+        "#,
+          "/b.css": r#"
+          @import "c.css" layer;
+          .b { color: green }
         "#,
           "/c.css": r#"
           .c { color: green }
@@ -1094,15 +4052,17 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @media print and (color) {
-        .c {
-          color: green;
+      @layer {
+        @layer {
+          .c {
+            color: green;
+          }
         }
       }
-      
-      @media print {
+
+      @layer {
         .b {
-          color: #ff0;
+          color: green;
         }
       }
 
@@ -1116,14 +4076,14 @@ mod tests {
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css";
+          @import "b.css" layer;
           .a { color: red }
         "#,
           "/b.css": r#"
-          @import "c.css";
+          @import "c.css" layer(foo);
+          .b { color: green }
         "#,
           "/c.css": r#"
-          @import "a.css";
           .c { color: green }
         "#
         },
@@ -1133,8 +4093,18 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      .c {
-        color: green;
+      @layer {
+        @layer foo {
+          .c {
+            color: green;
+          }
+        }
+      }
+
+      @layer {
+        .b {
+          color: green;
+        }
       }
 
       .a {
@@ -1146,25 +4116,41 @@ mod tests {
     let res = bundle(
       TestProvider {
         map: fs! {
+          "/index.css": r#"
+          @import "a.css";
+          @import "b.css";
+        "#,
           "/a.css": r#"
-          @import "b/c.css";
-          .a { color: red }
+          @import "./c.css";
+          body { background: red; }
         "#,
-          "/b/c.css": r#"
-          .b { color: green }
+          "/b.css": r#"
+          @import "./c.css";
+          body { color: red; }
+        "#,
+          "/c.css": r#"
+          body {
+            background: white;
+            color: black; 
+          }
         "#
         },
       },
-      "/a.css",
+      "/index.css",
     );
     assert_eq!(
       res,
       indoc! { r#"
-      .b {
-        color: green;
+      body {
+        background: red;
       }
-      
-      .a {
+
+      body {
+        background: #fff;
+        color: #000;
+      }
+
+      body {
         color: red;
       }
     "#}
@@ -1173,46 +4159,42 @@ mod tests {
     let res = bundle(
       TestProvider {
         map: fs! {
+          "/index.css": r#"
+          @import "a.css";
+          @import "b.css";
+          @import "a.css";
+        "#,
           "/a.css": r#"
-          @import "./b/c.css";
-          .a { color: red }
+          body { background: green; }
         "#,
-          "/b/c.css": r#"
-          .b { color: green }
+          "/b.css": r#"
+          body { background: red; }
         "#
         },
       },
-      "/a.css",
+      "/index.css",
     );
     assert_eq!(
       res,
       indoc! { r#"
-      .b {
-        color: green;
+      body {
+        background: red;
       }
-      
-      .a {
-        color: red;
+
+      body {
+        background: green;
       }
     "#}
     );
 
-    let res = bundle_custom_media(
-      TestProvider {
+    let res = bundle(
+      CustomProvider {
         map: fs! {
           "/a.css": r#"
-          @import "media.css";
-          @import "b.css";
-          .a { color: red }
-        "#,
-          "/media.css": r#"
-          @custom-media --foo print;
-        "#,
-          "/b.css": r#"
-          @media (--foo) {
-            .a { color: green }
-          }
-        "#
+            @import "foo:/b.css";
+            .a { color: red; }
+          "#,
+          "/b.css": ".b { color: green; }"
         },
       },
       "/a.css",
@@ -1220,704 +4202,1480 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @media print {
-        .a {
+        .b {
           color: green;
         }
-      }
 
-      .a {
-        color: red;
-      }
-    "#}
+        .a {
+          color: red;
+        }
+      "# }
     );
 
-    let res = bundle(
-      TestProvider {
+    error_test(
+      CustomProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" layer(foo);
-          .a { color: red }
-        "#,
-          "/b.css": r#"
-          .b { color: green }
-        "#
+            /* Forgot to prefix with `foo:`. */
+            @import "/b.css";
+            .a { color: red; }
+          "#,
+          "/b.css": ".b { color: green; }"
         },
       },
       "/a.css",
-    );
-    assert_eq!(
-      res,
-      indoc! { r#"
-      @layer foo {
-        .b {
-          color: green;
-        }
-      }
-      
-      .a {
-        color: red;
-      }
-    "#}
+      Some(Box::new(|err| {
+        let kind = match err {
+          BundleErrorKind::ResolverError(ref error) => error.kind(),
+          _ => unreachable!(),
+        };
+        assert!(matches!(kind, std::io::ErrorKind::NotFound));
+        assert!(err
+          .to_string()
+          .contains("Failed to resolve `/b.css`, specifier does not start with `foo:`."));
+      })),
     );
 
-    let res = bundle(
+    // let res = bundle(fs! {
+    //   "/a.css": r#"
+    //     @import "b.css" supports(color: red) (color);
+    //     @import "b.css" supports(foo: bar) (orientation: horizontal);
+    //     .a { color: red }
+    //   "#,
+    //   "/b.css": r#"
+    //     .b { color: green }
+    //   "#
+    // }, "/a.css");
+
+    // let res = bundle(fs! {
+    //   "/a.css": r#"
+    //     @import "b.css" not print;
+    //     .a { color: red }
+    //   "#,
+    //   "/b.css": r#"
+    //     @import "c.css" not screen;
+    //     .b { color: green }
+    //   "#,
+    //   "/c.css": r#"
+    //     .c { color: yellow }
+    //   "#
+    // }, "/a.css");
+  }
+
+  #[test]
+  fn test_css_module() {
+    macro_rules! map {
+      { $($key:expr => $val:expr),* } => {
+        HashMap::from([
+          $(($key.to_owned(), $val.to_owned()),)*
+        ])
+      };
+    }
+
+    let (code, exports) = bundle_css_module(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" layer;
+          @import "b.css";
           .a { color: red }
         "#,
           "/b.css": r#"
-          .b { color: green }
+          .a { color: green }
         "#
         },
       },
       "/a.css",
+      None,
     );
     assert_eq!(
-      res,
+      code,
       indoc! { r#"
-      @layer {
-        .b {
-          color: green;
-        }
+      ._9z6RGq_a {
+        color: green;
       }
-      
-      .a {
+
+      ._6lixEq_a {
         color: red;
       }
     "#}
     );
+    assert_eq!(
+      flatten_exports(exports),
+      map! {
+        "a" => "_6lixEq_a"
+      }
+    );
 
-    let res = bundle(
+    let (code, exports) = bundle_css_module(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" layer(foo);
-          .a { color: red }
+          .a { composes: x from './b.css'; color: red; }
+          .b { color: yellow }
         "#,
           "/b.css": r#"
-          @import "c.css" layer(bar);
-          .b { color: green }
-        "#,
-          "/c.css": r#"
-          .c { color: green }
+          .x { composes: y; background: green }
+          .y { font: Helvetica }
         "#
         },
       },
       "/a.css",
+      None,
     );
     assert_eq!(
-      res,
+      code,
       indoc! { r#"
-      @layer foo.bar {
-        .c {
-          color: green;
-        }
+      ._8Cs9ZG_x {
+        background: green;
       }
 
-      @layer foo {
-        .b {
-          color: green;
-        }
+      ._8Cs9ZG_y {
+        font: Helvetica;
       }
-      
-      .a {
+
+      ._6lixEq_a {
         color: red;
       }
+
+      ._6lixEq_b {
+        color: #ff0;
+      }
     "#}
     );
+    assert_eq!(
+      flatten_exports(exports),
+      map! {
+        "a" => "_6lixEq_a _8Cs9ZG_x _8Cs9ZG_y",
+        "b" => "_6lixEq_b"
+      }
+    );
 
-    let res = bundle(
+    let (code, exports) = bundle_css_module(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @import "b.css" layer(foo);
-          @import "b.css" layer(foo);
+          .a { composes: x from './b.css'; background: red; }
         "#,
           "/b.css": r#"
-          .b { color: green }
+          .a { background: red }
         "#
         },
       },
       "/a.css",
+      None,
     );
     assert_eq!(
-      res,
+      code,
       indoc! { r#"
-      @layer foo {
-        .b {
-          color: green;
-        }
+      ._8Cs9ZG_a {
+        background: red;
+      }
+
+      ._6lixEq_a {
+        background: red;
       }
     "#}
     );
+    assert_eq!(
+      flatten_exports(exports),
+      map! {
+        "a" => "_6lixEq_a"
+      }
+    );
 
-    let res = bundle(
+    let (code, exports) = bundle_css_module(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          @layer bar, foo;
-          @import "b.css" layer(foo);
-          
-          @layer bar {
-            div {
-              background: red;
-            }
+          .a {
+            background: var(--bg from "./b.css", var(--fallback from "./b.css"));
+            color: rgb(255 255 255 / var(--opacity from "./b.css"));
+            width: env(--env, var(--env-fallback from "./env.css"));
           }
         "#,
           "/b.css": r#"
-          @layer qux, baz;
-          @import "c.css" layer(baz);
-          
-          @layer qux {
-            div {
-              background: green;
-            }
+          .b {
+            --bg: red;
+            --fallback: yellow;
+            --opacity: 0.5;
           }
         "#,
-          "/c.css": r#"
-          div {
-            background: yellow;
-          }      
+          "/env.css": r#"
+          .env {
+            --env-fallback: 20px;
+          }
         "#
         },
       },
       "/a.css",
+      None,
     );
     assert_eq!(
-      res,
+      code,
       indoc! { r#"
-      @layer bar, foo;
-      @layer foo.qux, foo.baz;
-
-      @layer foo.baz {
-        div {
-          background: #ff0;
-        }
-      }
-
-      @layer foo {
-        @layer qux {
-          div {
-            background: green;
-          }
-        }
+      ._8Cs9ZG_b {
+        --_8Cs9ZG_bg: red;
+        --_8Cs9ZG_fallback: yellow;
+        --_8Cs9ZG_opacity: .5;
       }
       
-      @layer bar {
-        div {
-          background: red;
-        }
+      .GbJUva_env {
+        --GbJUva_env-fallback: 20px;
+      }
+
+      ._6lixEq_a {
+        background: var(--_8Cs9ZG_bg, var(--_8Cs9ZG_fallback));
+        color: rgb(255 255 255 / var(--_8Cs9ZG_opacity));
+        width: env(--_6lixEq_env, var(--GbJUva_env-fallback));
       }
     "#}
     );
+    assert_eq!(
+      flatten_exports(exports),
+      map! {
+        "a" => "_6lixEq_a",
+        "--env" => "--_6lixEq_env"
+      }
+    );
 
-    // Layer order depends on @import conditions.
-    let res = bundle(
+    // Hashes are stable between project roots.
+    let expected = indoc! { r#"
+    .dyGcAa_b {
+      background: #ff0;
+    }
+
+    .CK9avG_a {
+      background: #fff;
+    }
+  "#};
+
+    let (code, _) = bundle_css_module(
       TestProvider {
         map: fs! {
-          "/a.css": r#"
-          @import "b.css" layer(bar) (min-width: 1000px);
+          "/foo/bar/a.css": r#"
+        @import "b.css";
+        .a {
+          background: white;
+        }
+      "#,
+          "/foo/bar/b.css": r#"
+        .b {
+          background: yellow;
+        }
+      "#
+        },
+      },
+      "/foo/bar/a.css",
+      Some("/foo/bar"),
+    );
+    assert_eq!(code, expected);
 
-          @layer baz {
-            #box { background: purple }
-          }
+    let (code, _) = bundle_css_module(
+      TestProvider {
+        map: fs! {
+          "/x/y/z/a.css": r#"
+      @import "b.css";
+      .a {
+        background: white;
+      }
+    "#,
+          "/x/y/z/b.css": r#"
+      .b {
+        background: yellow;
+      }
+    "#
+        },
+      },
+      "/x/y/z/a.css",
+      Some("/x/y/z"),
+    );
+    assert_eq!(code, expected);
+  }
 
-          @layer bar {
-            #box { background: yellow }
-          }
+  #[test]
+  fn test_css_module_values() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @value primary, secondary as accent from "./b.css";
+          @value border: 1px solid accent;
+          .a { color: primary; border: border; }
         "#,
           "/b.css": r#"
-          #box { background: green }
+          @value base from "./c.css";
+          @value primary: base;
+          @value secondary: rgb(0 0 255);
+          .b { color: secondary }
+        "#,
+          "/c.css": r#"
+          @value base: red;
         "#
-        },
       },
-      "/a.css",
+    };
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(css_modules::Config {
+          values: true,
+          ..Default::default()
+        }),
+        ..ParserOptions::default()
+      },
     );
+    let mut stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+    stylesheet.minify(MinifyOptions::default()).unwrap();
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-      @media (min-width: 1000px) {
-        @layer bar {
-          #box {
-            background: green;
-          }
-        }
+      ._8Cs9ZG_b {
+        color: #00f;
       }
-      
-      @layer baz {
-        #box {
-          background: purple;
+
+      ._6lixEq_a {
+        color: red;
+        border: 1px solid #00f;
+      }
+    "#}
+    );
+
+    let exports = flatten_exports(res.exports.unwrap());
+    assert_eq!(exports["primary"], "red");
+    assert_eq!(exports["accent"], "#00f");
+    assert_eq!(exports["border"], "1px solid #00f");
+  }
+
+  #[test]
+  fn test_css_module_name_allocator() {
+    let fs = TestProvider {
+      map: fs! {
+        "/index.css": r#"
+          @import "c.css";
+          @import "b.css";
+          .main { color: red }
+        "#,
+          "/b.css": r#"
+          .foo { color: green }
+          .bar { color: blue }
+        "#,
+          "/c.css": r#"
+          .foo { color: yellow }
+        "#
+      },
+    };
+
+    // Files are loaded in parallel, but names are allocated sorted by path and local name,
+    // so every build with a fresh allocator produces the same output.
+    for _ in 0..10 {
+      let mut bundler = Bundler::new(
+        &fs,
+        None,
+        ParserOptions {
+          css_modules: Some(css_modules::Config {
+            name_allocator: Some(Arc::new(css_modules::MinimalNameAllocator::new())),
+            ..Default::default()
+          }),
+          ..ParserOptions::default()
+        },
+      );
+      let stylesheet = bundler.bundle(Path::new("/index.css")).unwrap();
+      let res = stylesheet
+        .to_css(PrinterOptions {
+          project_root: Some("/"),
+          ..PrinterOptions::default()
+        })
+        .unwrap();
+      assert_eq!(
+        res.code,
+        indoc! { r#"
+        .c {
+          color: #ff0;
+        }
+
+        .b {
+          color: green;
         }
+
+        .a {
+          color: #00f;
+        }
+
+        .d {
+          color: red;
+        }
+      "#}
+      );
+      assert_eq!(flatten_exports(res.exports.unwrap())["main"], "d");
+    }
+  }
+
+  #[test]
+  fn test_prefetch_provider() {
+    let fs = AsyncTestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "b.css";
+          @import url(c.css) print;
+          .a { color: red }
+        "#,
+        "/b.css": r#"
+          @import url("c.css") print;
+          .b { color: green }
+        "#,
+        "/c.css": r#"
+          .c { color: blue }
+        "#
+      },
+    };
+    fn assert_send<T: Send>(value: T) -> T {
+      value
+    }
+
+    let provider = PrefetchProvider::new(&fs);
+    block_on(assert_send(provider.load(Path::new("/a.css"))));
+    assert_eq!(provider.sources.len(), 3);
+
+    let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+    assert_eq!(
+      stylesheet.to_css(PrinterOptions::default()).unwrap().code,
+      indoc! { r#"
+      .b {
+        color: green;
       }
-      
-      @layer bar {
-        #box {
-          background: #ff0;
+
+      @media print {
+        .c {
+          color: #00f;
         }
       }
+
+      .a {
+        color: red;
+      }
     "#}
     );
 
-    error_test(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          @import "b.css" layer(foo);
-          @import "b.css" layer(bar);
+    let fs = AsyncTestProvider {
+      map: fs! {
+        "/a.css": r#"
+          .a { composes: b from "b.css"; color: red }
         "#,
-          "/b.css": r#"
-          .b { color: red }
+        "/b.css": r#"
+          .b { color: green }
         "#
-        },
       },
-      "/a.css",
-      Some(Box::new(|err| {
-        assert!(matches!(err, BundleErrorKind::UnsupportedLayerCombination));
-      })),
-    );
+    };
+    let provider = PrefetchProvider::new(&fs);
+    block_on(provider.load(Path::new("/a.css")));
+    assert_eq!(provider.sources.len(), 2);
 
-    error_test(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          @import "b.css" layer;
-          @import "b.css" layer;
+    let fs = AsyncTestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "missing.css";
+        "#
+      },
+    };
+    let provider = PrefetchProvider::new(&fs);
+    block_on(provider.load(Path::new("/a.css")));
+    let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+    let err = match bundler.bundle(Path::new("/a.css")) {
+      Ok(_) => unreachable!(),
+      Err(err) => err,
+    };
+    assert!(matches!(
+      err.kind,
+      BundleErrorKind::ResolverError(PrefetchError::Provider(..))
+    ));
+    assert_eq!(err.loc.unwrap().line, 1);
+
+    let provider = PrefetchProvider::new(&fs);
+    let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+    let err = match bundler.bundle(Path::new("/a.css")) {
+      Ok(_) => unreachable!(),
+      Err(err) => err,
+    };
+    assert!(matches!(
+      err.kind,
+      BundleErrorKind::ResolverError(PrefetchError::NotLoaded(..))
+    ));
+  }
+
+  #[test]
+  fn test_rebase_urls() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "sub/b.css";
+          .a { background: url(a.png) }
         "#,
-          "/b.css": r#"
-          .b { color: red }
+        "/sub/b.css": r#"
+          .b { background: url(../img/b.png) }
         "#
-        },
       },
-      "/a.css",
-      Some(Box::new(|err| {
-        assert!(matches!(err, BundleErrorKind::UnsupportedLayerCombination));
-      })),
-    );
+    };
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        urls: crate::printer::UrlOptions {
+          output_path: Some(Path::new("/dist/out.css")),
+          rewrite: None,
+        },
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.code, ".b{background:url(../img/b.png)}.a{background:url(../a.png)}");
+  }
 
-    error_test(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          @import "b.css" layer;
+  #[test]
+  fn test_circular_imports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "b.css";
           .a { color: red }
         "#,
-          "/b.css": r#"
-          @import "c.css" layer;
+        "/b.css": r#"
+          @import "c.css";
           .b { color: green }
         "#,
-          "/c.css": r#"
-          .c { color: green }
+        "/c.css": r#"
+          @import "b.css";
+          .c { color: blue }
         "#
-        },
       },
-      "/a.css",
-      Some(Box::new(|err| {
-        assert!(matches!(err, BundleErrorKind::UnsupportedLayerCombination));
-      })),
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let res = bundler
+      .bundle(Path::new("/a.css"))
+      .unwrap()
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.code, ".c{color:#00f}.b{color:green}.a{color:red}");
+    let location = |filename: &str| ErrorLocation {
+      filename: filename.into(),
+      line: 1,
+      column: 11,
+    };
+    assert_eq!(
+      bundler.circular_imports(),
+      &[vec![location("/b.css"), location("/c.css")]]
     );
 
-    error_test(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          @import "b.css" layer;
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    bundler.set_circular_imports(CircularImports::Error);
+    let res = bundler.bundle(Path::new("/a.css"));
+    match res {
+      Err(Error { kind, loc }) => {
+        assert_eq!(
+          kind.to_string(),
+          "Circular @import: /b.css:1:11 -> /c.css:1:11 -> /b.css"
+        );
+        assert_eq!(loc, Some(location("/c.css")));
+      }
+      Ok(..) => panic!("expected a circular import error"),
+    }
+  }
+
+  #[test]
+  fn test_conditional_imports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "b.css" layer(x) supports(display: grid) print, screen;
+          @import "e.css" (color);
           .a { color: red }
         "#,
-          "/b.css": r#"
-          @import "c.css" layer(foo);
+        "/b.css": r#"
+          @import "c.css" layer(y) (min-width: 500px);
+          @import "d.css" not print;
           .b { color: green }
         "#,
-          "/c.css": r#"
-          .c { color: green }
+        "/c.css": r#"
+          .c { color: blue }
+        "#,
+        "/d.css": r#"
+          .d { color: yellow }
+        "#,
+        "/e.css": r#"
+          @import "f.css" not print;
+          .e { color: purple }
+        "#,
+        "/f.css": r#"
+          .f { color: pink }
         "#
-        },
       },
-      "/a.css",
-      Some(Box::new(|err| {
-        assert!(matches!(err, BundleErrorKind::UnsupportedLayerCombination));
-      })),
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let res = bundler
+      .bundle(Path::new("/a.css"))
+      .unwrap()
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(
+      res.code,
+      "@supports (display:grid){@media print and (min-width:500px),screen and (min-width:500px){@layer x.y{.c{color:#00f}}}}\
+       @supports (display:grid){@media screen{@layer x{.d{color:#ff0}}}}\
+       @supports (display:grid){@media print,screen{@layer x{.b{color:green}}}}\
+       @media (color){@media not print{.f{color:pink}}}\
+       @media (color){.e{color:purple}}\
+       .a{color:red}"
     );
+  }
 
-    let res = bundle(
-      TestProvider {
-        map: fs! {
-          "/index.css": r#"
-          @import "a.css";
+  #[test]
+  fn test_virtual_modules() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "virtual:theme.css" layer(theme);
           @import "b.css";
+          .a { color: var(--primary) }
         "#,
-          "/a.css": r#"
-          @import "./c.css";
-          body { background: red; }
+        "/b.css": r#"
+          @import "virtual:theme.css" layer(theme);
+          .b { color: green }
         "#,
-          "/b.css": r#"
-          @import "./c.css";
-          body { color: red; }
+        "/c.css": r#"
+          .c { color: yellow }
+        "#
+      },
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    bundler.add_virtual_module(
+      "virtual:theme.css",
+      "@import 'virtual:tokens.css'; .theme { color: red }",
+    );
+    bundler.add_virtual_module("virtual:tokens.css", ":root { --primary: blue }");
+    bundler.set_external(vec!["virtual:*".into()]);
+    let res = bundler
+      .bundle(Path::new("/a.css"))
+      .unwrap()
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(
+      res.code,
+      "@layer theme{:root{--primary:blue}}@layer theme{.theme{color:red}}.b{color:green}.a{color:var(--primary)}"
+    );
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    bundler.add_virtual_module("virtual:entry.css", "@import '/c.css'; .entry { color: blue }");
+    let res = bundler
+      .bundle(Path::new("virtual:entry.css"))
+      .unwrap()
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.code, ".c{color:#ff0}.entry{color:#00f}");
+  }
+
+  #[test]
+  fn test_import_graph() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "b.css" print;
+          @import "https://fonts.example.com/inter.css";
+          .a { color: red }
         "#,
-          "/c.css": r#"
-          body {
-            background: white;
-            color: black; 
-          }
+        "/b.css": r#"
+          @import "c.css" layer(base) (min-width: 500px);
+          .b { color: green }
+        "#,
+        "/c.css": r#"
+          .c { color: blue }
         "#
-        },
       },
-      "/index.css",
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    bundler.set_external(vec!["https://*".into()]);
+    bundler.bundle(Path::new("/a.css")).unwrap();
+    let graph = bundler.import_graph();
+    let paths: Vec<_> = graph.files.iter().map(|file| file.path.to_str().unwrap()).collect();
+    assert_eq!(paths, ["/a.css", "/b.css", "/c.css"]);
+    assert_eq!(
+      graph.files[2].content_hash,
+      integrity_hash("sha256", fs.read(Path::new("/c.css")).unwrap().as_bytes()).unwrap()
     );
+
+    let imports: Vec<_> = graph.files[0]
+      .imports
+      .iter()
+      .map(|import| (import.specifier.as_str(), import.files.clone(), import.external))
+      .collect();
     assert_eq!(
-      res,
-      indoc! { r#"
-      body {
-        background: red;
-      }
+      imports,
+      [
+        ("b.css", vec![1], false),
+        ("https://fonts.example.com/inter.css", vec![], true)
+      ]
+    );
+    assert_eq!(graph.files[0].imports[0].conditions, ["print"]);
+    assert_eq!(graph.files[0].imports[1].conditions, Vec::<String>::new());
+    assert_eq!(
+      graph.files[1].imports[0].conditions,
+      ["layer(base) print and (min-width: 500px)"]
+    );
+    assert_eq!(
+      graph.files[1].imports[0].loc,
+      ErrorLocation::new(
+        Location {
+          source_index: 1,
+          line: 1,
+          column: 11,
+        },
+        "/b.css".into(),
+      )
+    );
+    assert!(graph.files[2].imports.is_empty());
+  }
+
+  /// A provider whose files can be changed between builds, and which records the files it reads.
+  #[derive(Default)]
+  struct WatchProvider {
+    map: Mutex<HashMap<PathBuf, &'static str>>,
+    reads: Mutex<Vec<PathBuf>>,
+  }
+
+  impl WatchProvider {
+    fn write(&self, file: &str, code: &str) {
+      let code: &'static str = Box::leak(code.to_owned().into_boxed_str());
+      self.map.lock().unwrap().insert(PathBuf::from(file), code);
+    }
+
+    fn take_reads(&self) -> Vec<PathBuf> {
+      let mut reads = std::mem::take(&mut *self.reads.lock().unwrap());
+      reads.sort();
+      reads
+    }
+  }
+
+  impl SourceProvider for WatchProvider {
+    type Error = std::io::Error;
+
+    fn read<'a>(&'a self, file: &Path) -> Result<&'a str, Self::Error> {
+      self.reads.lock().unwrap().push(file.to_owned());
+      Ok(self.map.lock().unwrap().get(file).unwrap())
+    }
+
+    fn resolve(&self, specifier: &str, originating_file: &Path) -> Result<PathBuf, Self::Error> {
+      Ok(originating_file.with_file_name(specifier))
+    }
+  }
+
+  #[test]
+  fn test_rebundle() {
+    let fs = WatchProvider::default();
+    fs.write("/a.css", "@import 'b.css' print; @import 'c.css'; .a { color: red }");
+    fs.write("/b.css", ".b { color: green }");
+    fs.write("/c.css", ".c { color: blue }");
+    fs.write("/d.css", ".d { color: yellow }");
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let rebundle = |bundler: &mut Bundler<'_, '_, '_, _, _>, changed: &[&Path]| {
+      bundler
+        .rebundle(Path::new("/a.css"), changed)
+        .unwrap()
+        .to_css(PrinterOptions {
+          minify: true,
+          ..PrinterOptions::default()
+        })
+        .unwrap()
+        .code
+    };
+
+    assert_eq!(
+      rebundle(&mut bundler, &[]),
+      "@media print{.b{color:green}}.c{color:#00f}.a{color:red}"
+    );
+    assert_eq!(
+      fs.take_reads(),
+      [
+        PathBuf::from("/a.css"),
+        PathBuf::from("/b.css"),
+        PathBuf::from("/c.css")
+      ]
+    );
+
+    fs.write("/c.css", "@import 'd.css' layer(d); .c { color: purple }");
+    assert_eq!(
+      rebundle(&mut bundler, &[Path::new("/c.css")]),
+      "@media print{.b{color:green}}@layer d{.d{color:#ff0}}.c{color:purple}.a{color:red}"
+    );
+    assert_eq!(fs.take_reads(), [PathBuf::from("/c.css"), PathBuf::from("/d.css")]);
+
+    fs.write("/a.css", "@import 'c.css'; .a { color: orange }");
+    assert_eq!(
+      rebundle(&mut bundler, &[Path::new("/a.css")]),
+      "@layer d{.d{color:#ff0}}.c{color:purple}.a{color:orange}"
+    );
+    assert_eq!(fs.take_reads(), [PathBuf::from("/a.css")]);
+  }
+
+  #[test]
+  fn test_package_imports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/src/a.css": r#"
+          @import "normalize.css";
+          @import "~@scope/ui/button.css";
+          @import "theme";
+          @import "local.css";
+          @import "~alias/x.css";
+          .a { color: red }
+        "#,
+        "/src/local.css": ".local { color: green }",
+        "/src/node_modules/theme/package.json": r#"{ "exports": { "default": "./default.css", "style": "./theme.css" } }"#,
+        "/src/node_modules/theme/theme.css": ".theme { color: blue }",
+        "/node_modules/normalize.css/package.json": r#"{ "main": "index.js", "style": "normalize.css" }"#,
+        "/node_modules/normalize.css/normalize.css": "html { line-height: 1.15 }",
+        "/node_modules/@scope/ui/package.json": r#"{ "exports": { ".": "./index.css", "./*.css": { "style": "./dist/*.css" } } }"#,
+        "/node_modules/@scope/ui/dist/button.css": ".button { color: purple }",
+        "/aliases/x.css": ".x { color: pink }"
+      },
+    };
 
-      body {
-        background: #fff;
-        color: #000;
-      }
+    let hook = |specifier: &str, _: &Path| {
+      specifier
+        .strip_prefix("~alias/")
+        .map(|specifier| Path::new("/aliases").join(specifier))
+    };
+    let mut provider = PackageProvider::new(&fs);
+    provider.set_hook(&hook);
+    {
+      let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+      let res = bundler
+        .bundle(Path::new("/src/a.css"))
+        .unwrap()
+        .to_css(PrinterOptions {
+          minify: true,
+          ..PrinterOptions::default()
+        })
+        .unwrap();
+      assert_eq!(
+        res.code,
+        "html{line-height:1.15}.button{color:purple}.theme{color:#00f}.local{color:green}.x{color:pink}.a{color:red}"
+      );
+    }
 
-      body {
-        color: red;
-      }
-    "#}
+    let entry = Path::new("/src/a.css");
+    assert_eq!(
+      provider.resolve("~@scope/ui", entry).unwrap(),
+      Path::new("/node_modules/@scope/ui/index.css")
+    );
+    provider.set_conditions(vec![]);
+    assert_eq!(
+      provider.resolve("theme", entry).unwrap(),
+      Path::new("/src/node_modules/theme/default.css")
+    );
+    assert_eq!(
+      provider.resolve("missing.css", entry).unwrap(),
+      Path::new("/src/missing.css")
     );
+  }
 
-    let res = bundle(
-      TestProvider {
-        map: fs! {
-          "/index.css": r#"
-          @import "a.css";
+  #[test]
+  fn test_duplicate_imports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "vendor/reset.css";
           @import "b.css";
-          @import "a.css";
+          @import "copy/reset.css";
+          .a { color: red }
         "#,
-          "/a.css": r#"
-          body { background: green; }
+        "/b.css": r#"
+          @import "copy/reset.css";
+          .b { color: green }
         "#,
-          "/b.css": r#"
-          body { background: red; }
-        "#
-        },
+        "/vendor/reset.css": "html { margin: 0 }",
+        "/copy/reset.css": "html { margin: 0 }"
       },
-      "/index.css",
+    };
+
+    let bundle = |duplicate_imports, warnings| {
+      let mut bundler = Bundler::new(
+        &fs,
+        None,
+        ParserOptions {
+          warnings,
+          ..ParserOptions::default()
+        },
+      );
+      bundler.set_duplicate_imports(duplicate_imports);
+      let res = bundler
+        .bundle(Path::new("/a.css"))
+        .unwrap()
+        .to_css(PrinterOptions {
+          minify: true,
+          ..PrinterOptions::default()
+        })
+        .unwrap();
+      res.code
+    };
+
+    assert_eq!(
+      bundle(DuplicateImports::Keep, None),
+      "html{margin:0}.b{color:green}html{margin:0}.a{color:red}"
     );
     assert_eq!(
-      res,
-      indoc! { r#"
-      body {
-        background: red;
-      }
+      bundle(DuplicateImports::Dedupe, None),
+      ".b{color:green}html{margin:0}.a{color:red}"
+    );
 
-      body {
-        background: green;
-      }
-    "#}
+    let warnings = Arc::new(RwLock::new(Vec::new()));
+    assert_eq!(
+      bundle(DuplicateImports::Warn, Some(warnings.clone())),
+      ".b{color:green}html{margin:0}.a{color:red}"
     );
+    let warnings = warnings.read().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(matches!(warnings[0].kind, ParserError::DuplicateImport(..)));
+  }
 
-    let res = bundle(
-      CustomProvider {
-        map: fs! {
-          "/a.css": r#"
-            @import "foo:/b.css";
-            .a { color: red; }
-          "#,
-          "/b.css": ".b { color: green; }"
-        },
+  #[test]
+  fn test_banner_and_source_comments() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "b.css" print;
+          @import "c.css";
+          .a { color: red }
+        "#,
+        "/b.css": r#"
+          .b { color: green }
+        "#,
+        "/c.css": r#"
+          .c { color: blue }
+        "#
       },
-      "/a.css",
-    );
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        banner: Some("/*! license */"),
+        footer: Some("/* footer */"),
+        source_comments: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-        .b {
-          color: green;
+        /*! license */
+        /* source: /b.css */
+        @media print {
+          .b {
+            color: green;
+          }
+        }
+
+        /* source: /c.css */
+        .c {
+          color: #00f;
         }
 
+        /* source: /a.css */
         .a {
           color: red;
         }
-      "# }
+        /* footer */
+      "#}
     );
 
-    error_test(
-      CustomProvider {
-        map: fs! {
-          "/a.css": r#"
-            /* Forgot to prefix with `foo:`. */
-            @import "/b.css";
-            .a { color: red; }
-          "#,
-          "/b.css": ".b { color: green; }"
-        },
-      },
-      "/a.css",
-      Some(Box::new(|err| {
-        let kind = match err {
-          BundleErrorKind::ResolverError(ref error) => error.kind(),
-          _ => unreachable!(),
-        };
-        assert!(matches!(kind, std::io::ErrorKind::NotFound));
-        assert!(err
-          .to_string()
-          .contains("Failed to resolve `/b.css`, specifier does not start with `foo:`."));
-      })),
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        banner: Some("/*! license */"),
+        footer: Some("/* footer */"),
+        source_comments: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(
+      res.code,
+      "/*! license */\n@media print{.b{color:green}}.c{color:#00f}.a{color:red}\n/* footer */"
     );
+  }
 
-    // let res = bundle(fs! {
-    //   "/a.css": r#"
-    //     @import "b.css" supports(color: red) (color);
-    //     @import "b.css" supports(foo: bar) (orientation: horizontal);
-    //     .a { color: red }
-    //   "#,
-    //   "/b.css": r#"
-    //     .b { color: green }
-    //   "#
-    // }, "/a.css");
+  #[test]
+  fn test_module_exports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          .a { composes: x from './b.css'; color: red; }
+          .b { composes: z from './c.css' }
+          .c { composes: missing from './c.css' }
+        "#,
+        "/b.css": r#"
+          .x { composes: y; composes: z from './c.css'; background: green }
+          .y { font: Helvetica }
+        "#,
+        "/c.css": r#"
+          .z { color: yellow }
+        "#
+      },
+    };
 
-    // let res = bundle(fs! {
-    //   "/a.css": r#"
-    //     @import "b.css" not print;
-    //     .a { color: red }
-    //   "#,
-    //   "/b.css": r#"
-    //     @import "c.css" not screen;
-    //     .b { color: green }
-    //   "#,
-    //   "/c.css": r#"
-    //     .c { color: yellow }
-    //   "#
-    // }, "/a.css");
+    let bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(css_modules::Config::default()),
+        ..ParserOptions::default()
+      },
+    );
+    let exports = bundler.module_exports(Path::new("/a.css"), None).unwrap();
+    assert_eq!(
+      exports["c"].composes,
+      vec![CssModuleReference::Dependency {
+        name: "missing".into(),
+        specifier: "./c.css".into()
+      }]
+    );
+
+    let exports = exports.into_iter().filter(|(name, _)| name != "c").collect();
+    assert_eq!(
+      flatten_exports(exports),
+      HashMap::from([
+        ("a".into(), "_6lixEq_a _8Cs9ZG_x _8Cs9ZG_y F8Il3q_z".into()),
+        ("b".into(), "_6lixEq_b F8Il3q_z".into())
+      ])
+    );
   }
 
   #[test]
-  fn test_css_module() {
-    macro_rules! map {
-      { $($key:expr => $val:expr),* } => {
-        HashMap::from([
-          $(($key.to_owned(), $val.to_owned()),)*
-        ])
-      };
-    }
-
-    let (code, exports) = bundle_css_module(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          @import "b.css";
+  fn test_external_imports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @layer base, components;
+          @import "b.css" print;
+          @import "c.css" layer(components);
+          @import "https://fonts.example.com/inter.css";
           .a { color: red }
         "#,
-          "/b.css": r#"
-          .a { color: green }
+        "/b.css": r#"
+          @import "https://fonts.example.com/mono.css" (min-width: 500px);
+          @import "theme.css";
+          .b { color: green }
+        "#,
+        "/c.css": r#"
+          @import "https://fonts.example.com/inter.css" supports(display: grid);
+          .c { color: blue }
         "#
-        },
       },
-      "/a.css",
-      None,
-    );
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    bundler.set_external(vec!["https://fonts.example.com/*".into(), "theme.css".into()]);
+    let res = bundler
+      .bundle(Path::new("/a.css"))
+      .unwrap()
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
     assert_eq!(
-      code,
-      indoc! { r#"
-      ._9z6RGq_a {
-        color: green;
-      }
+      res.code,
+      "@layer base,components;\
+       @import \"https://fonts.example.com/mono.css\" print and (min-width:500px);\
+       @import \"theme.css\" print;\
+       @import \"https://fonts.example.com/inter.css\" layer(components) supports(display:grid);\
+       @import \"https://fonts.example.com/inter.css\";\
+       @media print{.b{color:green}}@layer components{.c{color:#00f}}.a{color:red}"
+    );
+  }
 
-      ._6lixEq_a {
-        color: red;
-      }
-    "#}
+  #[test]
+  fn test_bundle_entries() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "reset.css" layer(base);
+          @import "button.css";
+          @import "a-only.css";
+          .a { color: red }
+        "#,
+        "/b.css": r#"
+          @import "reset.css" layer(base);
+          @import "button.css" print;
+          .b { color: green }
+        "#,
+        "/c.css": r#"
+          @import "a-only.css";
+        "#,
+        "/reset.css": "* { margin: 0 }",
+        "/button.css": ".button { color: blue }",
+        "/a-only.css": ".a-only { color: yellow }"
+      },
+    };
+
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let graph = bundler
+      .bundle_entries(&[Path::new("/a.css"), Path::new("/b.css"), Path::new("/c.css")])
+      .unwrap();
+    let chunks: Vec<(Vec<usize>, String)> = graph
+      .chunks
+      .iter()
+      .map(|chunk| {
+        let res = chunk
+          .stylesheet
+          .to_css(PrinterOptions {
+            minify: true,
+            ..PrinterOptions::default()
+          })
+          .unwrap();
+        (chunk.entries.clone(), res.code)
+      })
+      .collect();
+
+    assert_eq!(
+      chunks,
+      vec![
+        (vec![0, 1], "@layer base{*{margin:0}}".into()),
+        // button.css is imported with different media queries, so each entry has its own copy.
+        (vec![0], ".button{color:#00f}.a{color:red}".into()),
+        (vec![0, 2], ".a-only{color:#ff0}".into()),
+        (vec![1], "@media print{.button{color:#00f}}.b{color:green}".into()),
+        (vec![2], "".into()),
+      ]
     );
+    assert_eq!(graph.manifest, vec![vec![0, 1, 2], vec![0, 3], vec![2, 4]]);
     assert_eq!(
-      flatten_exports(exports),
-      map! {
-        "a" => "_6lixEq_a"
-      }
+      graph.chunks[1].files,
+      vec![PathBuf::from("/button.css"), PathBuf::from("/a.css")]
     );
+  }
 
-    let (code, exports) = bundle_css_module(
+  #[test]
+  fn test_glob_imports() {
+    let res = bundle(
       TestProvider {
         map: fs! {
           "/a.css": r#"
-          .a { composes: x from './b.css'; color: red; }
-          .b { color: yellow }
-        "#,
-          "/b.css": r#"
-          .x { composes: y; background: green }
-          .y { font: Helvetica }
-        "#
+            @import "components/**/*.css" layer(components);
+            @import "*.css";
+            .a { color: red }
+          "#,
+          "/b.css": ".b { color: green }",
+          "/components/card.css": ".card { color: blue }",
+          "/components/button/button.css": ".button { color: yellow }",
+          "/components/button/button.md": "Button",
+          "/components/alert.css": ".alert { color: purple }"
         },
       },
       "/a.css",
-      None,
     );
     assert_eq!(
-      code,
+      res,
       indoc! { r#"
-      ._8Cs9ZG_x {
-        background: green;
-      }
+        @layer components {
+          .alert {
+            color: purple;
+          }
+        }
 
-      ._8Cs9ZG_y {
-        font: Helvetica;
-      }
+        @layer components {
+          .button {
+            color: #ff0;
+          }
+        }
 
-      ._6lixEq_a {
-        color: red;
-      }
+        @layer components {
+          .card {
+            color: #00f;
+          }
+        }
 
-      ._6lixEq_b {
-        color: #ff0;
-      }
-    "#}
+        .b {
+          color: green;
+        }
+
+        .a {
+          color: red;
+        }
+      "#}
     );
-    assert_eq!(
-      flatten_exports(exports),
-      map! {
-        "a" => "_6lixEq_a _8Cs9ZG_x _8Cs9ZG_y",
-        "b" => "_6lixEq_b"
+
+    assert!(glob_match("**/*.css", "a.css"));
+    assert!(glob_match("a/**/b/*.css", "a/x/y/b/c.css"));
+    assert!(glob_match("icon-[a-c]?.css", "icon-b1.css"));
+    assert!(!glob_match("icon-[!a-c]?.css", "icon-b1.css"));
+    assert!(!glob_match("*.css", "a/b.css"));
+  }
+
+  /// A fetcher that serves remote style sheets from memory, and counts the fetches.
+  struct TestFetcher {
+    map: HashMap<String, String>,
+    fetches: std::sync::atomic::AtomicUsize,
+  }
+
+  impl RemoteFetcher for TestFetcher {
+    type Error = std::io::Error;
+
+    fn fetch(&self, url: &str) -> Result<String, Self::Error> {
+      self.fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+      match self.map.get(url) {
+        Some(code) => Ok(code.clone()),
+        None => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "404")),
       }
-    );
+    }
+  }
 
-    let (code, exports) = bundle_css_module(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          .a { composes: x from './b.css'; background: red; }
+  #[test]
+  fn test_remote_imports() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "https://cdn.example.com/css/tokens.css";
+          @import "b.css";
+          .a { color: var(--brand) }
         "#,
-          "/b.css": r#"
-          .a { background: red }
+        "/b.css": r#"
+          @import "https://cdn.example.com/css/tokens.css";
+          .b { color: green }
         "#
-        },
       },
-      "/a.css",
-      None,
-    );
+    };
+    let fetcher = || TestFetcher {
+      map: [
+        (
+          "https://cdn.example.com/css/tokens.css",
+          r#"@import "../base/colors.css"; :root { --brand: var(--blue) }"#,
+        ),
+        ("https://cdn.example.com/base/colors.css", ":root { --blue: #00f }"),
+      ]
+      .into_iter()
+      .map(|(url, code)| (url.to_owned(), code.to_owned()))
+      .collect(),
+      fetches: Default::default(),
+    };
+
+    let provider = RemoteProvider::new(&fs, fetcher());
+    let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+    let res = bundler
+      .bundle(Path::new("/a.css"))
+      .unwrap()
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
     assert_eq!(
-      code,
-      indoc! { r#"
-      ._8Cs9ZG_a {
-        background: red;
+      res.code,
+      ":root{--blue:#00f}:root{--brand:var(--blue)}.b{color:green}.a{color:var(--brand)}"
+    );
+    assert_eq!(provider.fetcher.fetches.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    // Pinning the integrity hash of a fetched url accepts the same contents.
+    let integrity = provider.integrity("https://cdn.example.com/css/tokens.css").unwrap();
+    assert!(integrity.starts_with("sha384-"));
+    let mut provider = RemoteProvider::new(&fs, fetcher());
+    provider.set_integrity("https://cdn.example.com/css/tokens.css", &integrity);
+    let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+    assert!(bundler.bundle(Path::new("/a.css")).is_ok());
+
+    let mut provider = RemoteProvider::new(&fs, fetcher());
+    provider.set_integrity(
+      "https://cdn.example.com/css/tokens.css",
+      "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=",
+    );
+    let mut bundler = Bundler::new(&provider, None, ParserOptions::default());
+    match bundler.bundle(Path::new("/a.css")) {
+      Err(Error {
+        kind: BundleErrorKind::ResolverError(RemoteError::IntegrityMismatch { url, actual, .. }),
+        ..
+      }) => {
+        assert_eq!(url, "https://cdn.example.com/css/tokens.css");
+        assert!(actual.starts_with("sha256-"));
       }
+      _ => panic!("expected an integrity error"),
+    }
 
-      ._6lixEq_a {
-        background: red;
-      }
-    "#}
+    assert_eq!(
+      join_url("https://example.com", "a.css?v=1"),
+      "https://example.com/a.css?v=1"
     );
     assert_eq!(
-      flatten_exports(exports),
-      map! {
-        "a" => "_6lixEq_a"
-      }
+      join_url("https://example.com/a/b.css", "//other.com/c.css"),
+      "https://other.com/c.css"
+    );
+    assert_eq!(
+      join_url("https://example.com/a/b.css?x", "/c.css"),
+      "https://example.com/c.css"
     );
+    assert_eq!(
+      join_url("https://example.com/a/b.css", "../../c.css"),
+      "https://example.com/c.css"
+    );
+  }
 
-    let (code, exports) = bundle_css_module(
-      TestProvider {
-        map: fs! {
-          "/a.css": r#"
-          .a {
-            background: var(--bg from "./b.css", var(--fallback from "./b.css"));
-            color: rgb(255 255 255 / var(--opacity from "./b.css"));
-            width: env(--env, var(--env-fallback from "./env.css"));
-          }
+  #[test]
+  fn test_inline_assets() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "sub/b.css";
+          .a { background: url(img/icon.svg) }
+          .c { background: url(big.png) }
+          .d { background: url(notes.txt) }
+          .e { background: url(img/icon.svg#a) }
         "#,
-          "/b.css": r#"
-          .b {
-            --bg: red;
-            --fallback: yellow;
-            --opacity: 0.5;
-          }
+        "/sub/b.css": r#"
+          @font-face { font-family: A; src: url(font.woff2) }
         "#,
-          "/env.css": r#"
-          .env {
-            --env-fallback: 20px;
-          }
-        "#
-        },
+        "/img/icon.svg": r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#,
+        "/sub/font.woff2": "wOF2data",
+        "/big.png": "x".repeat(5000),
+        "/notes.txt": "hello"
       },
-      "/a.css",
-      None,
-    );
+    };
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+
+    let print = |options: AssetInlineOptions| {
+      let inliner = AssetInliner::new(&fs, options);
+      let rewrite = |url: &UrlReference| inliner.rewrite(url);
+      let res = stylesheet
+        .to_css(PrinterOptions {
+          minify: true,
+          urls: crate::printer::UrlOptions {
+            output_path: None,
+            rewrite: Some(&rewrite),
+          },
+          ..PrinterOptions::default()
+        })
+        .unwrap();
+      (res.code, inliner.inlined())
+    };
+
+    let (code, inlined) = print(AssetInlineOptions::default());
     assert_eq!(
       code,
-      indoc! { r#"
-      ._8Cs9ZG_b {
-        --_8Cs9ZG_bg: red;
-        --_8Cs9ZG_fallback: yellow;
-        --_8Cs9ZG_opacity: .5;
-      }
-      
-      .GbJUva_env {
-        --GbJUva_env-fallback: 20px;
-      }
-
-      ._6lixEq_a {
-        background: var(--_8Cs9ZG_bg, var(--_8Cs9ZG_fallback));
-        color: rgb(255 255 255 / var(--_8Cs9ZG_opacity));
-        width: env(--_6lixEq_env, var(--GbJUva_env-fallback));
-      }
-    "#}
+      "@font-face{font-family:A;src:url(data:font/woff2;base64,d09GMmRhdGE=)}\
+       .a{background:url(data:image/svg+xml,%3Csvg%20xmlns=%22http://www.w3.org/2000/svg%22/%3E)}\
+       .c{background:url(big.png)}.d{background:url(notes.txt)}.e{background:url(img/icon.svg#a)}"
     );
     assert_eq!(
-      flatten_exports(exports),
-      map! {
-        "a" => "_6lixEq_a",
-        "--env" => "--_6lixEq_env"
-      }
+      inlined,
+      vec![
+        InlinedAsset {
+          path: PathBuf::from("/sub/font.woff2"),
+          mime_type: "font/woff2",
+          size: 8,
+          encoded_size: 35,
+        },
+        InlinedAsset {
+          path: PathBuf::from("/img/icon.svg"),
+          mime_type: "image/svg+xml",
+          size: 41,
+          encoded_size: 70,
+        },
+      ]
     );
 
-    // Hashes are stable between project roots.
-    let expected = indoc! { r#"
-    .dyGcAa_b {
-      background: #ff0;
-    }
+    let (code, inlined) = print(AssetInlineOptions {
+      extensions: vec!["svg".into()],
+      ..AssetInlineOptions::default()
+    });
+    assert!(code.starts_with("@font-face{font-family:A;src:url(font.woff2)}"));
+    assert_eq!(inlined.len(), 1);
+    assert_eq!(inlined[0].path, PathBuf::from("/img/icon.svg"));
+  }
 
-    .CK9avG_a {
-      background: #fff;
-    }
-  "#};
+  #[test]
+  fn test_hash_assets() {
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+          @import "sub/b.css";
+          .a { background: url(img/logo.png) }
+          .b { background: url(img/logo.png?v=1) }
+          .c { background: url(https://example.com/logo.png) }
+        "#,
+        "/sub/b.css": r#"
+          @font-face { font-family: A; src: url(font.woff2#iefix) }
+        "#,
+        "/img/logo.png": "logo",
+        "/sub/font.woff2": "font"
+      },
+    };
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+
+    let print = |options: AssetHashOptions| {
+      let hasher = AssetHasher::new(&fs, options);
+      let rewrite = |url: &UrlReference| hasher.rewrite(url);
+      let res = stylesheet
+        .to_css(PrinterOptions {
+          minify: true,
+          urls: crate::printer::UrlOptions {
+            output_path: None,
+            rewrite: Some(&rewrite),
+          },
+          ..PrinterOptions::default()
+        })
+        .unwrap();
+      (res.code, hasher.manifest())
+    };
 
-    let (code, _) = bundle_css_module(
-      TestProvider {
-        map: fs! {
-          "/foo/bar/a.css": r#"
-        @import "b.css";
-        .a {
-          background: white;
-        }
-      "#,
-          "/foo/bar/b.css": r#"
-        .b {
-          background: yellow;
-        }
-      "#
+    let (code, manifest) = print(AssetHashOptions::default());
+    assert_eq!(
+      code,
+      "@font-face{font-family:A;src:url(font-795ea3ef.woff2#iefix)}\
+       .a{background:url(img/logo-3598ce6f.png)}.b{background:url(img/logo-3598ce6f.png?v=1)}\
+       .c{background:url(https://example.com/logo.png)}"
+    );
+    assert_eq!(
+      manifest,
+      vec![
+        HashedAsset {
+          path: PathBuf::from("/sub/font.woff2"),
+          hashed_file_name: "font-795ea3ef.woff2".into(),
+          hash: "795ea3ef".into(),
         },
-      },
-      "/foo/bar/a.css",
-      Some("/foo/bar"),
+        HashedAsset {
+          path: PathBuf::from("/img/logo.png"),
+          hashed_file_name: "logo-3598ce6f.png".into(),
+          hash: "3598ce6f".into(),
+        },
+      ]
     );
-    assert_eq!(code, expected);
 
-    let (code, _) = bundle_css_module(
-      TestProvider {
-        map: fs! {
-          "/x/y/z/a.css": r#"
-      @import "b.css";
-      .a {
-        background: white;
-      }
-    "#,
-          "/x/y/z/b.css": r#"
-      .b {
-        background: yellow;
-      }
-    "#
-        },
-      },
-      "/x/y/z/a.css",
-      Some("/x/y/z"),
+    let (code, _) = print(AssetHashOptions {
+      file_name: "[hash].[ext]".into(),
+      hash_length: 4,
+      public_path: "/assets/".into(),
+    });
+    assert_eq!(
+      code,
+      "@font-face{font-family:A;src:url(/assets/795e.woff2#iefix)}\
+       .a{background:url(/assets/3598.png)}.b{background:url(/assets/3598.png?v=1)}\
+       .c{background:url(https://example.com/logo.png)}"
     );
-    assert_eq!(code, expected);
   }
 
   #[test]
@@ -1961,7 +5719,67 @@ mod tests {
     let map = sm.to_json(None).unwrap();
     assert_eq!(
       map,
-      r#"{"version":3,"sourceRoot":null,"mappings":"ACAA,uCCGA,2CAAA,8BFDQ","sources":["a.css","sass/_demo.scss","stdin"],"sourcesContent":["\n        @import \"/b.css\";\n        .a { color: red; }\n      ",".imported {\n  content: \"yay, file support!\";\n}","@import \"_variables\";\n@import \"_demo\";\n\n.selector {\n  margin: $size;\n  background-color: $brandColor;\n\n  .nested {\n    margin: $size / 2;\n  }\n}"],"names":[]}"#
+      r#"{"version":3,"sourceRoot":null,"mappings":"ACAA,UACW,6BCEX,UCHiB,sBACP,WDEV,kBCFO,YHCC,GAAY","sources":["a.css","sass/_demo.scss","stdin","sass/_variables.scss"],"sourcesContent":["\n        @import \"/b.css\";\n        .a { color: red; }\n      ",".imported {\n  content: \"yay, file support!\";\n}","@import \"_variables\";\n@import \"_demo\";\n\n.selector {\n  margin: $size;\n  background-color: $brandColor;\n\n  .nested {\n    margin: $size / 2;\n  }\n}","$brandColor: #f60;\n$size: 1em;"],"names":[]}"#
+    );
+  }
+
+  #[test]
+  fn test_external_source_map() {
+    let source = r#".imported {
+      content: "yay, file support!";
+    }
+    
+    .selector {
+      margin: 1em;
+      background-color: #f60;
+    }
+    
+    .selector .nested {
+      margin: 0.5em;
+    }
+    
+    /*# sourceMappingURL=b.css.map */"#;
+
+    let map = r#"{
+      "version": 3,
+      "sourceRoot": "root",
+      "file": "stdout",
+      "sources": ["stdin", "sass/_variables.scss", "sass/_demo.scss"],
+      "sourcesContent": [
+        "@import \"_variables\";\n@import \"_demo\";\n\n.selector {\n  margin: $size;\n  background-color: $brandColor;\n\n  .nested {\n    margin: $size / 2;\n  }\n}",
+        "$brandColor: #f60;\n$size: 1em;",
+        ".imported {\n  content: \"yay, file support!\";\n}"
+      ],
+      "mappings": "AEAA,SAAS,CAAC;EACR,OAAO,EAAE,oBAAqB;CAC/B;;AFCD,SAAS,CAAC;EACR,MAAM,ECHD,GAAG;EDIR,gBAAgB,ECLL,IAAI;CDUhB;;AAPD,SAAS,CAIP,OAAO,CAAC;EACN,MAAM,ECPH,KAAG;CDQP",
+      "names": []
+    }"#;
+
+    let fs = TestProvider {
+      map: fs! {
+        "/a.css": r#"
+        @import "/b.css";
+        .a { color: red; }
+      "#,
+        "/b.css": source,
+        "/b.css.map": map
+      },
+    };
+
+    let mut sm = parcel_sourcemap::SourceMap::new("/");
+    let mut bundler = Bundler::new(&fs, Some(&mut sm), ParserOptions::default());
+    let mut stylesheet = bundler.bundle(Path::new("/a.css")).unwrap();
+    stylesheet.minify(MinifyOptions::default()).unwrap();
+    stylesheet
+      .to_css(PrinterOptions {
+        source_map: Some(&mut sm),
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    let map = sm.to_json(None).unwrap();
+    assert_eq!(
+      map,
+      r#"{"version":3,"sourceRoot":null,"mappings":"ACAA,UACW,6BCEX,UCHiB,sBACP,WDEV,kBCFO,YHCC,GAAY","sources":["a.css","sass/_demo.scss","stdin","sass/_variables.scss"],"sourcesContent":["\n        @import \"/b.css\";\n        .a { color: red; }\n      ",".imported {\n  content: \"yay, file support!\";\n}","@import \"_variables\";\n@import \"_demo\";\n\n.selector {\n  margin: $size;\n  background-color: $brandColor;\n\n  .nested {\n    margin: $size / 2;\n  }\n}","$brandColor: #f60;\n$size: 1em;"],"names":[]}"#
     );
   }
 }
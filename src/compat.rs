@@ -2,7 +2,7 @@
 
 use crate::targets::Browsers;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Feature {
   AnyPseudo,
   Clamp,
@@ -38,15 +38,19 @@ pub enum Feature {
   CustomMediaQueries,
   Dialog,
   DoublePositionGradients,
+  FieldSizing,
   FontFamilySystemUi,
+  FontSizeAdjustTwoValue,
   FormValidation,
   Fullscreen,
   ImageSet,
+  InterpolateSize,
   LabColors,
   LangList,
   LogicalBorderRadius,
   LogicalBorderShorthand,
   LogicalBorders,
+  LogicalContainIntrinsicSize,
   LogicalInset,
   LogicalMargin,
   LogicalMarginShorthand,
@@ -57,20 +61,35 @@ pub enum Feature {
   MediaIntervalSyntax,
   MediaRangeSyntax,
   OklabColors,
+  OverflowClip,
+  OverflowClipMargin,
   OverflowShorthand,
   P3Colors,
   PlaceContent,
   PlaceItems,
   PlaceSelf,
+  ReadingFlow,
+  ReadingOrder,
   Shadowdomv1,
+  SiblingCount,
+  SiblingIndex,
   SpaceSeparatedColorFunction,
+  Subgrid,
   TextDecorationThicknessPercent,
   TextDecorationThicknessShorthand,
+  TextWrapStyle,
+  Toggle,
+  TransitionBehavior,
+  WhiteSpaceCollapse,
   XResolutionUnit,
 }
 
 impl Feature {
   pub fn is_compatible(&self, browsers: Browsers) -> bool {
+    if let Some(compatible) = browsers.compatibility_override(*self) {
+      return compatible;
+    }
+
     match self {
       Feature::CssSel2 => {
         if let Some(version) = browsers.ie {
@@ -2213,6 +2232,547 @@ impl Feature {
           return false;
         }
       }
+      Feature::SiblingIndex | Feature::SiblingCount => {
+        if let Some(version) = browsers.chrome {
+          if version < 9043968 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 9043968 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7995392 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 9043968 {
+            return false;
+          }
+        }
+        if browsers.firefox.is_some()
+          || browsers.ie.is_some()
+          || browsers.ios_saf.is_some()
+          || browsers.safari.is_some()
+          || browsers.samsung.is_some()
+        {
+          return false;
+        }
+      }
+      Feature::Toggle => {
+        return false;
+      }
+      Feature::FontSizeAdjustTwoValue => {
+        if let Some(version) = browsers.chrome {
+          if version < 8323072 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 8323072 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 7733248 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7405568 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1114112 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1114112 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1835008 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 8323072 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::LogicalContainIntrinsicSize => {
+        if let Some(version) = browsers.chrome {
+          if version < 6225920 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 6225920 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 7012352 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 5308416 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1114112 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1114112 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1114112 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 6225920 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::OverflowClip => {
+        if let Some(version) = browsers.chrome {
+          if version < 5898240 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 5898240 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 5308416 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 4980736 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1048576 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1048576 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 983040 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 5898240 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::OverflowClipMargin => {
+        if let Some(version) = browsers.chrome {
+          if version < 5898240 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 5898240 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 6684672 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 4980736 {
+            return false;
+          }
+        }
+        if browsers.safari.is_some() {
+          return false;
+        }
+        if browsers.ios_saf.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 983040 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 5898240 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::FieldSizing => {
+        if let Some(version) = browsers.chrome {
+          if version < 8060928 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 8060928 {
+            return false;
+          }
+        }
+        if browsers.firefox.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7143424 {
+            return false;
+          }
+        }
+        if browsers.safari.is_some() {
+          return false;
+        }
+        if browsers.ios_saf.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1769472 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 8060928 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::InterpolateSize => {
+        if let Some(version) = browsers.chrome {
+          if version < 8454144 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 8454144 {
+            return false;
+          }
+        }
+        if browsers.firefox.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7536640 {
+            return false;
+          }
+        }
+        if browsers.safari.is_some() {
+          return false;
+        }
+        if browsers.ios_saf.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1835008 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 8454144 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::ReadingFlow => {
+        if let Some(version) = browsers.chrome {
+          if version < 8978432 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 8978432 {
+            return false;
+          }
+        }
+        if browsers.firefox.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7929856 {
+            return false;
+          }
+        }
+        if browsers.safari.is_some() {
+          return false;
+        }
+        if browsers.ios_saf.is_some() {
+          return false;
+        }
+        if browsers.samsung.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.android {
+          if version < 8978432 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::ReadingOrder => {
+        if let Some(version) = browsers.chrome {
+          if version < 8978432 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 8978432 {
+            return false;
+          }
+        }
+        if browsers.firefox.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7929856 {
+            return false;
+          }
+        }
+        if browsers.safari.is_some() {
+          return false;
+        }
+        if browsers.ios_saf.is_some() {
+          return false;
+        }
+        if browsers.samsung.is_some() {
+          return false;
+        }
+        if let Some(version) = browsers.android {
+          if version < 8978432 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::Subgrid => {
+        if let Some(version) = browsers.chrome {
+          if version < 7667712 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 7667712 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 4653056 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 6750208 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1048576 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1048576 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1572864 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 7667712 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::TransitionBehavior => {
+        if let Some(version) = browsers.chrome {
+          if version < 7667712 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 7667712 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 8454144 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 6750208 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1115136 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1115136 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1572864 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 7667712 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::WhiteSpaceCollapse => {
+        if let Some(version) = browsers.chrome {
+          if version < 7471104 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 7471104 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 8126464 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 6553600 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1115136 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1115136 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1507328 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 7471104 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
+      Feature::TextWrapStyle => {
+        if let Some(version) = browsers.chrome {
+          if version < 8519680 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.edge {
+          if version < 8519680 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.firefox {
+          if version < 8126464 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.opera {
+          if version < 7536640 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.safari {
+          if version < 1115392 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.ios_saf {
+          if version < 1115392 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.samsung {
+          if version < 1835008 {
+            return false;
+          }
+        }
+        if let Some(version) = browsers.android {
+          if version < 8519680 {
+            return false;
+          }
+        }
+        if browsers.ie.is_some() {
+          return false;
+        }
+      }
       Feature::P3Colors | Feature::LangList => {
         if let Some(version) = browsers.safari {
           if version < 655616 {
@@ -2240,7 +2800,12 @@ impl Feature {
   }
 
   pub fn is_partially_compatible(&self, targets: Browsers) -> bool {
-    let mut browsers = Browsers::default();
+    let mut browsers = Browsers {
+      feature_data: targets.feature_data,
+      include: targets.include,
+      exclude: targets.exclude,
+      ..Browsers::default()
+    };
     if targets.android.is_some() {
       browsers.android = targets.android;
       if self.is_compatible(browsers) {
@@ -2307,4 +2872,170 @@ impl Feature {
 
     false
   }
+
+  pub fn from_name(name: &str) -> Option<Feature> {
+    Some(match name {
+      "any-pseudo" => Feature::AnyPseudo,
+      "clamp" => Feature::Clamp,
+      "color-function" => Feature::ColorFunction,
+      "css-any-link" => Feature::CssAnyLink,
+      "css-autofill" => Feature::CssAutofill,
+      "css-case-insensitive" => Feature::CssCaseInsensitive,
+      "css-default-pseudo" => Feature::CssDefaultPseudo,
+      "css-dir-pseudo" => Feature::CssDirPseudo,
+      "css-first-letter" => Feature::CssFirstLetter,
+      "css-first-line" => Feature::CssFirstLine,
+      "css-focus-visible" => Feature::CssFocusVisible,
+      "css-focus-within" => Feature::CssFocusWithin,
+      "css-gencontent" => Feature::CssGencontent,
+      "css-has" => Feature::CssHas,
+      "css-in-out-of-range" => Feature::CssInOutOfRange,
+      "css-indeterminate-pseudo" => Feature::CssIndeterminatePseudo,
+      "css-marker-pseudo" => Feature::CssMarkerPseudo,
+      "css-matches-pseudo" => Feature::CssMatchesPseudo,
+      "css-namespaces" => Feature::CssNamespaces,
+      "css-nesting" => Feature::CssNesting,
+      "css-not-sel-list" => Feature::CssNotSelList,
+      "css-optional-pseudo" => Feature::CssOptionalPseudo,
+      "css-placeholder" => Feature::CssPlaceholder,
+      "css-placeholder-shown" => Feature::CssPlaceholderShown,
+      "css-read-only-write" => Feature::CssReadOnlyWrite,
+      "css-rrggbbaa" => Feature::CssRrggbbaa,
+      "css-sel2" => Feature::CssSel2,
+      "css-sel3" => Feature::CssSel3,
+      "css-selection" => Feature::CssSelection,
+      "cue" => Feature::Cue,
+      "cue-function" => Feature::CueFunction,
+      "custom-media-queries" => Feature::CustomMediaQueries,
+      "dialog" => Feature::Dialog,
+      "double-position-gradients" => Feature::DoublePositionGradients,
+      "field-sizing" => Feature::FieldSizing,
+      "font-family-system-ui" => Feature::FontFamilySystemUi,
+      "font-size-adjust-two-value" => Feature::FontSizeAdjustTwoValue,
+      "form-validation" => Feature::FormValidation,
+      "fullscreen" => Feature::Fullscreen,
+      "image-set" => Feature::ImageSet,
+      "interpolate-size" => Feature::InterpolateSize,
+      "lab-colors" => Feature::LabColors,
+      "lang-list" => Feature::LangList,
+      "logical-border-radius" => Feature::LogicalBorderRadius,
+      "logical-border-shorthand" => Feature::LogicalBorderShorthand,
+      "logical-borders" => Feature::LogicalBorders,
+      "logical-contain-intrinsic-size" => Feature::LogicalContainIntrinsicSize,
+      "logical-inset" => Feature::LogicalInset,
+      "logical-margin" => Feature::LogicalMargin,
+      "logical-margin-shorthand" => Feature::LogicalMarginShorthand,
+      "logical-padding" => Feature::LogicalPadding,
+      "logical-padding-shorthand" => Feature::LogicalPaddingShorthand,
+      "logical-size" => Feature::LogicalSize,
+      "logical-text-align" => Feature::LogicalTextAlign,
+      "media-interval-syntax" => Feature::MediaIntervalSyntax,
+      "media-range-syntax" => Feature::MediaRangeSyntax,
+      "oklab-colors" => Feature::OklabColors,
+      "overflow-clip" => Feature::OverflowClip,
+      "overflow-clip-margin" => Feature::OverflowClipMargin,
+      "overflow-shorthand" => Feature::OverflowShorthand,
+      "p3-colors" => Feature::P3Colors,
+      "place-content" => Feature::PlaceContent,
+      "place-items" => Feature::PlaceItems,
+      "place-self" => Feature::PlaceSelf,
+      "reading-flow" => Feature::ReadingFlow,
+      "reading-order" => Feature::ReadingOrder,
+      "shadowdomv1" => Feature::Shadowdomv1,
+      "sibling-count" => Feature::SiblingCount,
+      "sibling-index" => Feature::SiblingIndex,
+      "space-separated-color-function" => Feature::SpaceSeparatedColorFunction,
+      "subgrid" => Feature::Subgrid,
+      "text-decoration-thickness-percent" => Feature::TextDecorationThicknessPercent,
+      "text-decoration-thickness-shorthand" => Feature::TextDecorationThicknessShorthand,
+      "text-wrap-style" => Feature::TextWrapStyle,
+      "toggle" => Feature::Toggle,
+      "transition-behavior" => Feature::TransitionBehavior,
+      "white-space-collapse" => Feature::WhiteSpaceCollapse,
+      "x-resolution-unit" => Feature::XResolutionUnit,
+      _ => return None,
+    })
+  }
+  pub fn name(&self) -> &'static str {
+    match self {
+      Feature::AnyPseudo => "any-pseudo",
+      Feature::Clamp => "clamp",
+      Feature::ColorFunction => "color-function",
+      Feature::CssAnyLink => "css-any-link",
+      Feature::CssAutofill => "css-autofill",
+      Feature::CssCaseInsensitive => "css-case-insensitive",
+      Feature::CssDefaultPseudo => "css-default-pseudo",
+      Feature::CssDirPseudo => "css-dir-pseudo",
+      Feature::CssFirstLetter => "css-first-letter",
+      Feature::CssFirstLine => "css-first-line",
+      Feature::CssFocusVisible => "css-focus-visible",
+      Feature::CssFocusWithin => "css-focus-within",
+      Feature::CssGencontent => "css-gencontent",
+      Feature::CssHas => "css-has",
+      Feature::CssInOutOfRange => "css-in-out-of-range",
+      Feature::CssIndeterminatePseudo => "css-indeterminate-pseudo",
+      Feature::CssMarkerPseudo => "css-marker-pseudo",
+      Feature::CssMatchesPseudo => "css-matches-pseudo",
+      Feature::CssNamespaces => "css-namespaces",
+      Feature::CssNesting => "css-nesting",
+      Feature::CssNotSelList => "css-not-sel-list",
+      Feature::CssOptionalPseudo => "css-optional-pseudo",
+      Feature::CssPlaceholder => "css-placeholder",
+      Feature::CssPlaceholderShown => "css-placeholder-shown",
+      Feature::CssReadOnlyWrite => "css-read-only-write",
+      Feature::CssRrggbbaa => "css-rrggbbaa",
+      Feature::CssSel2 => "css-sel2",
+      Feature::CssSel3 => "css-sel3",
+      Feature::CssSelection => "css-selection",
+      Feature::Cue => "cue",
+      Feature::CueFunction => "cue-function",
+      Feature::CustomMediaQueries => "custom-media-queries",
+      Feature::Dialog => "dialog",
+      Feature::DoublePositionGradients => "double-position-gradients",
+      Feature::FieldSizing => "field-sizing",
+      Feature::FontFamilySystemUi => "font-family-system-ui",
+      Feature::FontSizeAdjustTwoValue => "font-size-adjust-two-value",
+      Feature::FormValidation => "form-validation",
+      Feature::Fullscreen => "fullscreen",
+      Feature::ImageSet => "image-set",
+      Feature::InterpolateSize => "interpolate-size",
+      Feature::LabColors => "lab-colors",
+      Feature::LangList => "lang-list",
+      Feature::LogicalBorderRadius => "logical-border-radius",
+      Feature::LogicalBorderShorthand => "logical-border-shorthand",
+      Feature::LogicalBorders => "logical-borders",
+      Feature::LogicalContainIntrinsicSize => "logical-contain-intrinsic-size",
+      Feature::LogicalInset => "logical-inset",
+      Feature::LogicalMargin => "logical-margin",
+      Feature::LogicalMarginShorthand => "logical-margin-shorthand",
+      Feature::LogicalPadding => "logical-padding",
+      Feature::LogicalPaddingShorthand => "logical-padding-shorthand",
+      Feature::LogicalSize => "logical-size",
+      Feature::LogicalTextAlign => "logical-text-align",
+      Feature::MediaIntervalSyntax => "media-interval-syntax",
+      Feature::MediaRangeSyntax => "media-range-syntax",
+      Feature::OklabColors => "oklab-colors",
+      Feature::OverflowClip => "overflow-clip",
+      Feature::OverflowClipMargin => "overflow-clip-margin",
+      Feature::OverflowShorthand => "overflow-shorthand",
+      Feature::P3Colors => "p3-colors",
+      Feature::PlaceContent => "place-content",
+      Feature::PlaceItems => "place-items",
+      Feature::PlaceSelf => "place-self",
+      Feature::ReadingFlow => "reading-flow",
+      Feature::ReadingOrder => "reading-order",
+      Feature::Shadowdomv1 => "shadowdomv1",
+      Feature::SiblingCount => "sibling-count",
+      Feature::SiblingIndex => "sibling-index",
+      Feature::SpaceSeparatedColorFunction => "space-separated-color-function",
+      Feature::Subgrid => "subgrid",
+      Feature::TextDecorationThicknessPercent => "text-decoration-thickness-percent",
+      Feature::TextDecorationThicknessShorthand => "text-decoration-thickness-shorthand",
+      Feature::TextWrapStyle => "text-wrap-style",
+      Feature::Toggle => "toggle",
+      Feature::TransitionBehavior => "transition-behavior",
+      Feature::WhiteSpaceCollapse => "white-space-collapse",
+      Feature::XResolutionUnit => "x-resolution-unit",
+    }
+  }
 }
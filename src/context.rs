@@ -1,13 +1,16 @@
 use std::collections::HashSet;
 
 use crate::compat::Feature;
-use crate::declaration::DeclarationBlock;
-use crate::properties::custom::UnparsedProperty;
+use crate::declaration::{DeclarationBlock, DeclarationList};
+use crate::error::{ErrorWithLocation, ParserError};
+use crate::logical::{LogicalGroup, PropertyCategory};
+use crate::properties::custom::{Attr, AttrProperty, UnparsedProperty};
 use crate::properties::Property;
 use crate::rules::supports::{SupportsCondition, SupportsRule};
-use crate::rules::{style::StyleRule, CssRule, CssRuleList};
+use crate::rules::{style::StyleRule, CssRule, CssRuleList, Location};
 use crate::selector::{Direction, PseudoClass};
 use crate::targets::Browsers;
+use crate::unused_symbols::UnusedSymbols;
 use crate::vendor_prefix::VendorPrefix;
 use parcel_selectors::parser::Component;
 
@@ -33,20 +36,38 @@ pub(crate) struct PropertyHandlerContext<'i, 'o> {
   supports: Vec<SupportsEntry<'i>>,
   ltr: Vec<Property<'i>>,
   rtl: Vec<Property<'i>>,
+  pub direction: Option<Direction>,
+  pub legacy_scroll_snap: bool,
   pub context: DeclarationContext,
-  pub unused_symbols: &'o HashSet<String>,
+  pub unused_symbols: &'o UnusedSymbols,
+  pub used_properties: Option<&'o HashSet<String>>,
+  pub loc: Location,
+  pub warnings: Vec<ErrorWithLocation<ParserError<'i>>>,
+  pub lowered_features: Vec<(Feature, Location)>,
+  pub unsupported_features: Vec<(Feature, Location)>,
 }
 
 impl<'i, 'o> PropertyHandlerContext<'i, 'o> {
-  pub fn new(targets: Option<Browsers>, unused_symbols: &'o HashSet<String>) -> Self {
+  pub fn new(targets: Option<Browsers>, unused_symbols: &'o UnusedSymbols) -> Self {
     PropertyHandlerContext {
       targets,
       is_important: false,
       supports: Vec::new(),
       ltr: Vec::new(),
       rtl: Vec::new(),
+      direction: None,
+      legacy_scroll_snap: false,
       context: DeclarationContext::None,
       unused_symbols,
+      used_properties: None,
+      loc: Location {
+        source_index: 0,
+        line: 0,
+        column: 1,
+      },
+      warnings: Vec::new(),
+      lowered_features: Vec::new(),
+      unsupported_features: Vec::new(),
     }
   }
 
@@ -64,9 +85,103 @@ impl<'i, 'o> PropertyHandlerContext<'i, 'o> {
     }
   }
 
-  pub fn add_logical_rule(&mut self, ltr: Property<'i>, rtl: Property<'i>) {
-    self.ltr.push(ltr);
-    self.rtl.push(rtl);
+  pub fn add_unsupported_function_warnings(&mut self, property: &Property<'i>) {
+    let targets = match self.targets {
+      Some(targets) => targets,
+      None => return,
+    };
+
+    let tokens = match property {
+      Property::Unparsed(unparsed) => &unparsed.value,
+      Property::Custom(custom) => &custom.value,
+      _ => return,
+    };
+
+    let mut functions = Vec::new();
+    tokens.get_unsupported_functions(targets, &mut functions);
+    for (name, feature) in functions {
+      self.warnings.push(ErrorWithLocation {
+        kind: ParserError::UnsupportedFunction(name.into()),
+        loc: self.loc,
+      });
+      self.unsupported_features.push((feature, self.loc));
+    }
+  }
+
+  pub fn add_unsupported_property_warnings(&mut self, property: &Property<'i>) {
+    let feature = match property_feature(property) {
+      Some(feature) => feature,
+      None => return,
+    };
+
+    if !self.is_supported(feature) {
+      self.warnings.push(ErrorWithLocation {
+        kind: ParserError::UnsupportedProperty(property.property_id().name().to_owned().into()),
+        loc: self.loc,
+      });
+      self.unsupported_features.push((feature, self.loc));
+    }
+  }
+
+  /// Records features used by a declaration that will be down-leveled for the targets.
+  pub fn add_lowered_features(&mut self, property: &Property<'i>) {
+    let targets = match self.targets {
+      Some(targets) => targets,
+      None => return,
+    };
+
+    let property_id = property.property_id();
+    if property_id.category() == Some(PropertyCategory::Logical) {
+      let feature = match property_id.logical_group() {
+        Some(LogicalGroup::Margin) => Some(Feature::LogicalMargin),
+        Some(LogicalGroup::Padding) => Some(Feature::LogicalPadding),
+        Some(LogicalGroup::Inset) => Some(Feature::LogicalInset),
+        Some(LogicalGroup::Size | LogicalGroup::MinSize | LogicalGroup::MaxSize) => Some(Feature::LogicalSize),
+        Some(LogicalGroup::BorderRadius) => Some(Feature::LogicalBorderRadius),
+        Some(LogicalGroup::BorderColor | LogicalGroup::BorderStyle | LogicalGroup::BorderWidth) => {
+          Some(Feature::LogicalBorders)
+        }
+        Some(LogicalGroup::ContainIntrinsicSize) => Some(Feature::LogicalContainIntrinsicSize),
+        _ => None,
+      };
+
+      if let Some(feature) = feature {
+        if !self.is_supported(feature) {
+          self.lowered_features.push((feature, self.loc));
+        }
+      }
+    }
+
+    let color = match property {
+      Property::Color(color)
+      | Property::BackgroundColor(color)
+      | Property::BorderTopColor(color)
+      | Property::BorderRightColor(color)
+      | Property::BorderBottomColor(color)
+      | Property::BorderLeftColor(color)
+      | Property::OutlineColor(color)
+      | Property::TextDecorationColor(color, _) => color,
+      _ => return,
+    };
+
+    if let Some(feature) = color.get_feature() {
+      if !feature.is_compatible(targets) {
+        self.lowered_features.push((feature, self.loc));
+      }
+    }
+  }
+
+  pub fn add_logical_rule(&mut self, dest: &mut DeclarationList<'i>, ltr: Property<'i>, rtl: Property<'i>) {
+    // If the document direction is known up front, emit the physical property
+    // directly rather than duplicating the rule for each direction.
+    match self.direction {
+      Some(Direction::Ltr) => dest.push(ltr),
+      Some(Direction::Rtl) => dest.push(rtl),
+      None => {
+        self.ltr.push(ltr);
+        self.rtl.push(rtl);
+      }
+    }
   }
 
   pub fn get_logical_rules<T>(&mut self, style_rule: &StyleRule<'i, T>) -> Vec<CssRule<'i, T>> {
@@ -87,7 +202,7 @@ impl<'i, 'o> PropertyHandlerContext<'i, 'o> {
           vendor_prefix: VendorPrefix::None,
           declarations: DeclarationBlock {
             declarations: std::mem::take(&mut self.$decls),
-            important_declarations: vec![],
+            ..DeclarationBlock::new()
           },
           rules: CssRuleList(vec![]),
           loc: style_rule.loc.clone(),
@@ -154,6 +269,30 @@ impl<'i, 'o> PropertyHandlerContext<'i, 'o> {
     }
   }
 
+  /// Adds conditional fallbacks for the fallback value of a typed `attr()` function, e.g. for colors.
+  pub fn add_attr_fallbacks(&mut self, attr: &mut AttrProperty<'i>) {
+    if self.context != DeclarationContext::StyleRule && self.context != DeclarationContext::StyleAttribute {
+      return;
+    }
+
+    let fallbacks = match (self.targets, &mut attr.value.fallback) {
+      (Some(targets), Some(fallback)) => fallback.get_fallbacks(targets),
+      _ => return,
+    };
+    for (condition, fallback) in fallbacks {
+      self.add_conditional_property(
+        condition,
+        Property::Attr(AttrProperty {
+          property_id: attr.property_id.clone(),
+          value: Attr {
+            fallback: Some(fallback),
+            ..attr.value.clone()
+          },
+        }),
+      );
+    }
+  }
+
   pub fn get_supports_rules<T>(&mut self, style_rule: &StyleRule<'i, T>) -> Vec<CssRule<'i, T>> {
     if self.supports.is_empty() {
       return Vec::new();
@@ -170,6 +309,7 @@ impl<'i, 'o> PropertyHandlerContext<'i, 'o> {
           declarations: DeclarationBlock {
             declarations: entry.declarations,
             important_declarations: entry.important_declarations,
+            ..DeclarationBlock::new()
           },
           rules: CssRuleList(vec![]),
           loc: style_rule.loc.clone(),
@@ -181,3 +321,14 @@ impl<'i, 'o> PropertyHandlerContext<'i, 'o> {
     dest
   }
 }
+
+/// Returns the feature required by a property that cannot be transpiled, if any.
+pub(crate) fn property_feature(property: &Property) -> Option<Feature> {
+  match property {
+    Property::FieldSizing(_) => Some(Feature::FieldSizing),
+    Property::InterpolateSize(_) => Some(Feature::InterpolateSize),
+    Property::ReadingFlow(_) => Some(Feature::ReadingFlow),
+    Property::ReadingOrder(_) => Some(Feature::ReadingOrder),
+    _ => None,
+  }
+}
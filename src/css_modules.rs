@@ -7,31 +7,141 @@
 //! CSS modules can be enabled using the `css_modules` option when parsing a style sheet. When the
 //! style sheet is printed, hashes will be added to any declared names, and references to those names
 //! will be updated accordingly. A map of the original names to compiled (hashed) names will be returned.
+//!
+//! Selectors wrapped in `:global()` are left as is. Keyframes and counter styles defined globally can
+//! be referenced from a module using the `global()` function, e.g. `animation: global(fade) 1s`.
+//!
+//! When the `values` option is enabled, [@value](crate::rules::value::ValueRule) rules define named values
+//! that are substituted into declarations and media queries, and exported along with the other names.
+//!
+//! Instead of the pattern, a [NameAllocator](NameAllocator) may assign the names, e.g. the
+//! [MinimalNameAllocator](MinimalNameAllocator), which assigns the shortest possible names across all
+//! files in a build.
+//!
+//! Names composed from other files with `composes: a from "a.css"` are returned as dependency references
+//! for the host to resolve, unless the files are bundled together. Alternatively, [resolve_composes](resolve_composes)
+//! or [Bundler::module_exports](crate::bundler::Bundler::module_exports) resolve them to the compiled names.
 
 use crate::error::PrinterErrorKind;
 use crate::properties::css_modules::{Composes, Specifier};
+use crate::rules::Location;
 use crate::selector::SelectorList;
+use cssparser::{serialize_identifier, serialize_string};
 use data_encoding::{Encoding, Specification};
 use lazy_static::lazy_static;
 use pathdiff::diff_paths;
 #[cfg(any(feature = "serde", feature = "nodejs"))]
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use smallvec::{smallvec, SmallVec};
 use std::borrow::Cow;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 /// Configuration for CSS modules.
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Config<'i> {
   /// The name pattern to use when renaming class names and other identifiers.
   /// Default is `[hash]_[local]`.
   pub pattern: Pattern<'i>,
   /// Whether to rename dashed identifiers, e.g. custom properties.
   pub dashed_idents: bool,
+  /// Whether to rename the custom properties declared in the module, including those registered with
+  /// `@property`, and the `var()` references to them. Unlike `dashed_idents`, references to custom
+  /// properties that are not declared in the module, e.g. global design tokens, are left as is, and
+  /// other dashed identifiers are not renamed. Has no effect when `dashed_idents` is enabled.
+  /// Default is `false`.
+  pub custom_properties: bool,
+  /// The number of characters to shorten the `[hash]` and `[contenthash]` placeholders to.
+  /// By default, the full hash is used.
+  pub hash_length: Option<usize>,
+  /// The alphabet to encode the `[hash]` and `[contenthash]` placeholders with.
+  pub hash_encoding: HashEncoding,
+  /// Whether to scope class names in selectors. Default is `true`.
+  pub classes: bool,
+  /// Whether to scope ids in selectors. Default is `true`.
+  pub ids: bool,
+  /// Whether to scope `@keyframes` names, and the references to them in animation properties.
+  /// Default is `true`.
+  pub animation: bool,
+  /// Whether to scope `@counter-style` names, and the references to them in e.g. `list-style-type`.
+  /// Default is `true`.
+  pub counter_style: bool,
+  /// Whether to scope container names in `@container` rules and the `container-name` property.
+  /// Default is `true`.
+  pub container: bool,
+  /// Whether to scope grid line and area names. Default is `true`.
+  pub grid: bool,
+  /// Whether to scope scroll and view timeline names declared with `scroll-timeline-name`, `view-timeline-name`
+  /// and their shorthands, and the references to them in `animation-timeline` and `timeline-scope`.
+  /// Timeline names are always scoped when `dashed_idents` is enabled. Default is `true`.
+  pub timeline: bool,
+  /// Whether to support the [@value](crate::rules::value::ValueRule) rule, as implemented by
+  /// [postcss-modules-values](https://github.com/css-modules/postcss-modules-values). Default is `false`.
+  pub values: bool,
+  /// Whether to require each top-level selector to contain at least one local class or id, so that
+  /// the module cannot style elements globally. This matches the `pure` mode of css-loader.
+  /// Selectors that do not are an error, or a warning when parsing with `error_recovery`.
+  /// Default is `false`.
+  pub pure: bool,
+  /// Assigns the scoped names instead of the `pattern`, e.g. to share short names across all files in a build.
+  pub name_allocator: Option<Arc<dyn NameAllocator>>,
+}
+
+impl<'i> Default for Config<'i> {
+  fn default() -> Self {
+    Config {
+      pattern: Pattern::default(),
+      dashed_idents: false,
+      custom_properties: false,
+      hash_length: None,
+      hash_encoding: HashEncoding::default(),
+      classes: true,
+      ids: true,
+      animation: true,
+      counter_style: true,
+      container: true,
+      grid: true,
+      timeline: true,
+      values: false,
+      pure: false,
+      name_allocator: None,
+    }
+  }
+}
+
+/// A kind of name that may be scoped by CSS modules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum NameKind {
+  Class,
+  Id,
+  Keyframes,
+  CounterStyle,
+  Container,
+  Grid,
+  /// Any other custom identifier, e.g. a `view-transition-name`. These are always scoped.
+  Other,
+}
+
+/// The alphabet used to encode hashes in CSS modules names.
+///
+/// See [Config](Config).
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Deserialize),
+  serde(rename_all = "lowercase")
+)]
+pub enum HashEncoding {
+  /// URL safe base64, using letters, digits, `_` and `-`.
+  #[default]
+  Base64Url,
+  /// Base 36, using lowercase letters and digits.
+  Base36,
 }
 
 /// A CSS modules class name pattern.
@@ -86,6 +196,7 @@ impl<'i> Pattern<'i> {
             "[name]" => Segment::Name,
             "[local]" => Segment::Local,
             "[hash]" => Segment::Hash,
+            "[contenthash]" => Segment::ContentHash,
             s => return Err(PatternParseError::UnknownPlaceholder(s.into(), start_idx)),
           };
           segments.push(segment);
@@ -105,8 +216,20 @@ impl<'i> Pattern<'i> {
     Ok(Pattern { segments })
   }
 
+  /// Returns whether the pattern contains a `[contenthash]` placeholder.
+  pub fn has_content_hash(&self) -> bool {
+    self.segments.iter().any(|segment| matches!(segment, Segment::ContentHash))
+  }
+
   /// Write the substituted pattern to a destination.
-  pub fn write<W, E>(&self, hash: &str, path: &Path, local: &str, mut write: W) -> Result<(), E>
+  pub fn write<W, E>(
+    &self,
+    hash: &str,
+    content_hash: &str,
+    path: &Path,
+    local: &str,
+    mut write: W,
+  ) -> Result<(), E>
   where
     W: FnMut(&str) -> Result<(), E>,
   {
@@ -129,22 +252,13 @@ impl<'i> Pattern<'i> {
         Segment::Hash => {
           write(hash)?;
         }
+        Segment::ContentHash => {
+          write(content_hash)?;
+        }
       }
     }
     Ok(())
   }
-
-  #[inline]
-  fn write_to_string(
-    &self,
-    mut res: String,
-    hash: &str,
-    path: &Path,
-    local: &str,
-  ) -> Result<String, std::fmt::Error> {
-    self.write(hash, path, local, |s| res.write_str(s))?;
-    Ok(res)
-  }
 }
 
 /// A segment in a CSS modules class name pattern.
@@ -160,6 +274,135 @@ pub enum Segment<'i> {
   Local,
   /// A hash of the file name.
   Hash,
+  /// A hash of the file contents, so that names change when the file changes.
+  /// When the contents are not known, e.g. for style sheets created with
+  /// [StyleSheet::new](crate::stylesheet::StyleSheet::new), the hash of the file name is used instead.
+  ContentHash,
+}
+
+/// Assigns the scoped names of identifiers in CSS modules, instead of a [Pattern](Pattern).
+///
+/// An allocator may be shared between all style sheets in a build, so that names are unique
+/// across files. It must return the same name each time it is called with the same arguments.
+pub trait NameAllocator: std::fmt::Debug + Send + Sync {
+  /// Returns the scoped name for a local identifier declared in the given file. The path is relative
+  /// to the project root if one is given. The name of a dashed identifier does not include the `--` prefix.
+  fn allocate(&self, path: &str, local: &str) -> String;
+}
+
+/// A [NameAllocator](NameAllocator) that assigns the shortest possible names, i.e. `a`, `b`, ..., `Z`,
+/// `aa`, `ba`, etc., in the order names are first allocated. Style sheets allocate their names sorted by
+/// file path and then local name, so the names don't depend on the order files are loaded or rules are printed.
+///
+/// The assigned names can be saved with [state](MinimalNameAllocator::state) and restored with
+/// [from_state](MinimalNameAllocator::from_state), so that names remain stable between incremental builds.
+#[derive(Debug, Default)]
+pub struct MinimalNameAllocator {
+  names: Mutex<MinimalNames>,
+}
+
+#[derive(Debug, Default)]
+struct MinimalNames {
+  by_local: HashMap<(String, String), String>,
+  used: HashSet<String>,
+  next: usize,
+}
+
+impl MinimalNameAllocator {
+  /// Creates an allocator with no names assigned.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Restores an allocator from a state previously returned by [state](MinimalNameAllocator::state).
+  /// Each line of the state contains a path, a local name, and the assigned name, separated by tabs.
+  /// Malformed lines are ignored.
+  pub fn from_state(state: &str) -> Self {
+    let mut names = MinimalNames::default();
+    for line in state.lines() {
+      let mut parts = line.split('\t');
+      if let (Some(path), Some(local), Some(name), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+      {
+        names.used.insert(name.into());
+        names.by_local.insert((path.into(), local.into()), name.into());
+      }
+    }
+
+    MinimalNameAllocator {
+      names: Mutex::new(names),
+    }
+  }
+
+  /// Returns the assigned names in a format that can be persisted, e.g. to a file, and restored
+  /// with [from_state](MinimalNameAllocator::from_state). Lines are sorted so the output is deterministic.
+  pub fn state(&self) -> String {
+    let names = self.names.lock().unwrap();
+    let mut lines: Vec<String> = names
+      .by_local
+      .iter()
+      .map(|((path, local), name)| format!("{}\t{}\t{}\n", path, local, name))
+      .collect();
+    lines.sort();
+    lines.concat()
+  }
+}
+
+impl NameAllocator for MinimalNameAllocator {
+  fn allocate(&self, path: &str, local: &str) -> String {
+    let mut names = self.names.lock().unwrap();
+    if let Some(name) = names.by_local.get(&(path.to_owned(), local.to_owned())) {
+      return name.clone();
+    }
+
+    let name = loop {
+      let name = minimal_name(names.next);
+      names.next += 1;
+      if !names.used.contains(&name) {
+        break name;
+      }
+    };
+
+    names.used.insert(name.clone());
+    names.by_local.insert((path.into(), local.into()), name.clone());
+    name
+  }
+}
+
+/// A [NameAllocator](NameAllocator) that records the names requested from it, without assigning them.
+/// Used to allocate the names of a style sheet with the real allocator in a deterministic order.
+#[derive(Debug, Default)]
+pub(crate) struct NameRecorder {
+  names: Mutex<BTreeSet<(String, String)>>,
+}
+
+impl NameRecorder {
+  /// Returns the recorded paths and local names, sorted by path and then local name.
+  pub fn take_names(&self) -> BTreeSet<(String, String)> {
+    std::mem::take(&mut self.names.lock().unwrap())
+  }
+}
+
+impl NameAllocator for NameRecorder {
+  fn allocate(&self, path: &str, local: &str) -> String {
+    self.names.lock().unwrap().insert((path.into(), local.into()));
+    local.into()
+  }
+}
+
+/// Returns the name at the given index in the sequence `a`, ..., `Z`, `aa`, `ba`, ..., `Za`, `ab`, etc.
+/// Names always start with a letter, so they are valid identifiers.
+fn minimal_name(mut index: usize) -> String {
+  const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+  const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-";
+  let mut name = String::new();
+  name.push(FIRST[index % FIRST.len()] as char);
+  index /= FIRST.len();
+  while index > 0 {
+    index -= 1;
+    name.push(REST[index % REST.len()] as char);
+    index /= REST.len();
+  }
+  name
 }
 
 /// A referenced name within a CSS module, e.g. via the `composes` property.
@@ -202,11 +445,155 @@ pub struct CssModuleExport {
   pub composes: Vec<CssModuleReference>,
   /// Whether the export is referenced in this file.
   pub is_referenced: bool,
+  /// The location of the rule where the name first appears in the source file, if known.
+  #[cfg_attr(
+    any(feature = "serde", feature = "nodejs"),
+    serde(skip_serializing_if = "Option::is_none")
+  )]
+  pub loc: Option<Location>,
 }
 
 /// A map of exported names to values.
 pub type CssModuleExports = HashMap<String, CssModuleExport>;
 
+/// An export from a CSS module, with the metadata needed to generate type declarations
+/// such as a `.d.ts` file.
+///
+/// See [export_entries](export_entries).
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(rename_all = "camelCase"))]
+pub struct CssModuleExportEntry {
+  /// The key to export the name under. This is the camelCase name if requested,
+  /// and the original name otherwise.
+  pub key: String,
+  /// The original name in the source file.
+  pub original_name: String,
+  /// The camelCase form of the original name, e.g. `fooBar` for `foo-bar`.
+  pub camel_case_name: String,
+  /// The local (compiled) name for this export.
+  pub scoped_name: String,
+  /// Whether the export composes other names.
+  pub is_composed: bool,
+  /// The location of the rule where the name first appears in the source file, if known.
+  pub loc: Option<Location>,
+}
+
+/// Returns the exports of a CSS module as a list sorted by key, so that type declarations
+/// generated from it are deterministic. When `camel_case` is true, the keys are converted
+/// to camelCase, e.g. `foo-bar` becomes `fooBar`.
+pub fn export_entries(exports: &CssModuleExports, camel_case: bool) -> Vec<CssModuleExportEntry> {
+  let mut entries: Vec<CssModuleExportEntry> = exports
+    .iter()
+    .map(|(name, export)| {
+      let camel_case_name = to_camel_case(name);
+      CssModuleExportEntry {
+        key: if camel_case {
+          camel_case_name.clone()
+        } else {
+          name.clone()
+        },
+        original_name: name.clone(),
+        camel_case_name,
+        scoped_name: export.name.clone(),
+        is_composed: !export.composes.is_empty(),
+        loc: export.loc,
+      }
+    })
+    .collect();
+  entries.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.original_name.cmp(&b.original_name)));
+  entries
+}
+
+/// Converts a dash separated name to camelCase. Leading dashes, e.g. of dashed idents, are removed.
+fn to_camel_case(name: &str) -> String {
+  let mut result = String::with_capacity(name.len());
+  for (i, part) in name.split('-').filter(|part| !part.is_empty()).enumerate() {
+    let mut chars = part.chars();
+    if i > 0 {
+      if let Some(c) = chars.next() {
+        result.extend(c.to_uppercase());
+      }
+    }
+    result.push_str(chars.as_str());
+  }
+  result
+}
+
+/// Serializes the exports and references of a CSS module as [ICSS](https://github.com/css-modules/icss)
+/// `:import` and `:export` rules, as exchanged by css-loader and other webpack loaders.
+///
+/// Each reference to another file is imported under its placeholder, which appears in place of the
+/// referenced name in the compiled CSS. Names composed from other files are imported under generated
+/// aliases. Rules and declarations are sorted so that the output is deterministic.
+pub fn to_icss(exports: &CssModuleExports, references: &CssModuleReferences, minify: bool) -> String {
+  // Imported names by specifier, keyed by their local alias.
+  let mut imports: BTreeMap<&str, BTreeMap<String, &str>> = BTreeMap::new();
+  for (placeholder, reference) in references {
+    if let CssModuleReference::Dependency { name, specifier } = reference {
+      imports.entry(specifier).or_default().insert(placeholder.clone(), name);
+    }
+  }
+
+  let mut values: BTreeMap<&str, String> = BTreeMap::new();
+  for (key, export) in exports {
+    let mut value = export.name.clone();
+    for reference in &export.composes {
+      value.push(' ');
+      match reference {
+        CssModuleReference::Local { name } | CssModuleReference::Global { name } => value.push_str(name),
+        CssModuleReference::Dependency { name, specifier } => {
+          let alias = format!("i__{}", hash(&format!("{}_{}", specifier, name), false));
+          value.push_str(&alias);
+          imports.entry(specifier).or_default().insert(alias, name);
+        }
+      }
+    }
+    values.insert(key, value);
+  }
+
+  let mut dest = String::new();
+  let mut write_rule = |selector: &str, declarations: &mut dyn Iterator<Item = (&str, &str)>| {
+    if !dest.is_empty() && !minify {
+      dest.push('\n');
+    }
+    dest.push_str(selector);
+    dest.push_str(if minify { "{" } else { " {\n" });
+    let mut first = true;
+    for (key, value) in declarations {
+      if minify && !first {
+        dest.push(';');
+      }
+      first = false;
+      if !minify {
+        dest.push_str("  ");
+      }
+      let _ = serialize_identifier(key, &mut dest);
+      dest.push_str(if minify { ":" } else { ": " });
+      dest.push_str(value);
+      if !minify {
+        dest.push_str(";\n");
+      }
+    }
+    dest.push_str(if minify { "}" } else { "}\n" });
+  };
+
+  for (specifier, names) in &imports {
+    let mut selector = String::from(":import(");
+    let _ = serialize_string(specifier, &mut selector);
+    selector.push(')');
+    let mut names = names.iter().map(|(alias, name)| (alias.as_str(), *name));
+    write_rule(&selector, &mut names);
+  }
+
+  if !values.is_empty() {
+    let mut values = values.iter().map(|(key, value)| (*key, value.as_str()));
+    write_rule(":export", &mut values);
+  }
+
+  dest
+}
+
 /// A map of placeholders to references.
 pub type CssModuleReferences = HashMap<String, CssModuleReference>;
 
@@ -224,20 +611,27 @@ pub(crate) struct CssModule<'a, 'b, 'c> {
   pub config: &'a Config<'b>,
   pub sources: Vec<&'c Path>,
   pub hashes: Vec<String>,
+  pub content_hashes: Vec<String>,
   pub exports_by_source_index: Vec<CssModuleExports>,
+  /// The paths of the source files relative to the project root, used as keys for the name allocator.
+  pub relative_paths: Vec<String>,
   pub references: &'a mut HashMap<String, CssModuleReference>,
+  /// The custom properties declared in each source file, which are renamed when the
+  /// `custom_properties` option is enabled.
+  pub declared_custom_properties: Vec<HashSet<String>>,
 }
 
 impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
   pub fn new(
     config: &'a Config<'b>,
     sources: &'c Vec<String>,
+    content_hashes: &[Option<u64>],
     project_root: Option<&'c str>,
     references: &'a mut HashMap<String, CssModuleReference>,
   ) -> Self {
     let project_root = project_root.map(|p| Path::new(p));
     let sources: Vec<&Path> = sources.iter().map(|filename| Path::new(filename)).collect();
-    let hashes = sources
+    let relative_paths: Vec<String> = sources
       .iter()
       .map(|path| {
         // Make paths relative to project root so hashes are stable.
@@ -247,84 +641,144 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
           }
           _ => Cow::Borrowed(*path),
         };
-        hash(
-          &source.to_string_lossy(),
-          matches!(config.pattern.segments[0], Segment::Hash),
-        )
+        source.to_string_lossy().into_owned()
+      })
+      .collect();
+    let path_hashes: Vec<u32> = relative_paths.iter().map(|path| hash_u32(path)).collect();
+    let hashes = path_hashes
+      .iter()
+      .map(|hash| config.encode_hash(&hash.to_le_bytes(), matches!(config.pattern.segments[0], Segment::Hash)))
+      .collect();
+    let content_hashes = path_hashes
+      .iter()
+      .enumerate()
+      .map(|(i, hash)| {
+        let at_start = matches!(config.pattern.segments[0], Segment::ContentHash);
+        match content_hashes.get(i).copied().flatten() {
+          Some(content_hash) => config.encode_hash(&content_hash.to_le_bytes(), at_start),
+          None => config.encode_hash(&hash.to_le_bytes(), at_start),
+        }
       })
       .collect();
     Self {
       config,
       exports_by_source_index: sources.iter().map(|_| HashMap::new()).collect(),
+      declared_custom_properties: sources.iter().map(|_| HashSet::new()).collect(),
       sources,
+      relative_paths,
       hashes,
+      content_hashes,
       references,
     }
   }
 
-  pub fn add_local(&mut self, exported: &str, local: &str, source_index: u32) {
+  pub fn add_local(&mut self, exported: &str, local: &str, loc: Location) {
+    let source_index = loc.source_index;
     self.exports_by_source_index[source_index as usize]
       .entry(exported.into())
       .or_insert_with(|| CssModuleExport {
-        name: self
-          .config
-          .pattern
-          .write_to_string(
-            String::new(),
-            &self.hashes[source_index as usize],
-            &self.sources[source_index as usize],
-            local,
-          )
-          .unwrap(),
+        name: self.config.name_to_string(
+          "",
+          &self.hashes[source_index as usize],
+          &self.content_hashes[source_index as usize],
+          self.sources[source_index as usize],
+          &self.relative_paths[source_index as usize],
+          local,
+        ),
         composes: vec![],
         is_referenced: false,
+        loc: Some(loc),
       });
   }
 
-  pub fn add_dashed(&mut self, local: &str, source_index: u32) {
+  /// Returns whether a custom property is renamed by the `custom_properties` option.
+  pub fn is_scoped_custom_property(&self, name: &str, source_index: u32) -> bool {
+    self.config.custom_properties
+      && !self.config.dashed_idents
+      && self.declared_custom_properties[source_index as usize].contains(name)
+  }
+
+  pub fn add_dashed(&mut self, local: &str, loc: Location) {
+    let source_index = loc.source_index;
     self.exports_by_source_index[source_index as usize]
       .entry(local.into())
       .or_insert_with(|| CssModuleExport {
-        name: self
-          .config
-          .pattern
-          .write_to_string(
-            "--".into(),
-            &self.hashes[source_index as usize],
-            &self.sources[source_index as usize],
-            &local[2..],
-          )
-          .unwrap(),
+        name: self.config.name_to_string(
+          "--",
+          &self.hashes[source_index as usize],
+          &self.content_hashes[source_index as usize],
+          self.sources[source_index as usize],
+          &self.relative_paths[source_index as usize],
+          &local[2..],
+        ),
         composes: vec![],
         is_referenced: false,
+        loc: Some(loc),
       });
   }
 
-  pub fn reference(&mut self, name: &str, source_index: u32) {
+  /// Exports a value defined by a `@value` rule.
+  pub fn add_value(&mut self, name: &str, value: String, loc: Location) {
+    self.exports_by_source_index[loc.source_index as usize].insert(
+      name.into(),
+      CssModuleExport {
+        name: value,
+        composes: vec![],
+        is_referenced: false,
+        loc: Some(loc),
+      },
+    );
+  }
+
+  /// Exports a value imported by a `@value` rule as a placeholder, and adds a reference
+  /// to the imported module for the placeholder.
+  pub fn add_value_import(&mut self, local: &str, name: &str, specifier: &str, loc: Location) {
+    let filename = self.sources[loc.source_index as usize].to_string_lossy();
+    let placeholder = value_placeholder(&filename, name, specifier);
+    self.references.insert(
+      placeholder.clone(),
+      CssModuleReference::Dependency {
+        name: name.into(),
+        specifier: specifier.into(),
+      },
+    );
+    self.exports_by_source_index[loc.source_index as usize].insert(
+      local.into(),
+      CssModuleExport {
+        name: placeholder,
+        composes: vec![],
+        is_referenced: false,
+        loc: Some(loc),
+      },
+    );
+  }
+
+  pub fn reference(&mut self, name: &str, loc: Location) {
+    let source_index = loc.source_index;
     match self.exports_by_source_index[source_index as usize].entry(name.into()) {
       std::collections::hash_map::Entry::Occupied(mut entry) => {
         entry.get_mut().is_referenced = true;
       }
       std::collections::hash_map::Entry::Vacant(entry) => {
         entry.insert(CssModuleExport {
-          name: self
-            .config
-            .pattern
-            .write_to_string(
-              String::new(),
-              &self.hashes[source_index as usize],
-              &self.sources[source_index as usize],
-              name,
-            )
-            .unwrap(),
+          name: self.config.name_to_string(
+            "",
+            &self.hashes[source_index as usize],
+            &self.content_hashes[source_index as usize],
+            self.sources[source_index as usize],
+            &self.relative_paths[source_index as usize],
+            name,
+          ),
           composes: vec![],
           is_referenced: true,
+          loc: Some(loc),
         });
       }
     }
   }
 
-  pub fn reference_dashed(&mut self, name: &str, from: &Option<Specifier>, source_index: u32) -> Option<String> {
+  pub fn reference_dashed(&mut self, name: &str, from: &Option<Specifier>, loc: Location) -> Option<String> {
+    let source_index = loc.source_index;
     let (reference, key) = match from {
       Some(Specifier::Global) => return Some(name[2..].into()),
       Some(Specifier::File(file)) => (
@@ -335,18 +789,14 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
         file.as_ref(),
       ),
       Some(Specifier::SourceIndex(source_index)) => {
-        return Some(
-          self
-            .config
-            .pattern
-            .write_to_string(
-              String::new(),
-              &self.hashes[*source_index as usize],
-              &self.sources[*source_index as usize],
-              &name[2..],
-            )
-            .unwrap(),
-        )
+        return Some(self.config.name_to_string(
+          "",
+          &self.hashes[*source_index as usize],
+          &self.content_hashes[*source_index as usize],
+          self.sources[*source_index as usize],
+          &self.relative_paths[*source_index as usize],
+          &name[2..],
+        ))
       }
       None => {
         // Local export. Mark as used.
@@ -356,18 +806,17 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
           }
           std::collections::hash_map::Entry::Vacant(entry) => {
             entry.insert(CssModuleExport {
-              name: self
-                .config
-                .pattern
-                .write_to_string(
-                  "--".into(),
-                  &self.hashes[source_index as usize],
-                  &self.sources[source_index as usize],
-                  &name[2..],
-                )
-                .unwrap(),
+              name: self.config.name_to_string(
+                "--",
+                &self.hashes[source_index as usize],
+                &self.content_hashes[source_index as usize],
+                self.sources[source_index as usize],
+                &self.relative_paths[source_index as usize],
+                &name[2..],
+              ),
               composes: vec![],
               is_referenced: true,
+              loc: Some(loc),
             });
           }
         }
@@ -385,29 +834,43 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
     Some(hash)
   }
 
+  /// Returns the export for a class name. When classes are not scoped, the class is exported as is.
+  fn class_export(&mut self, name: &str, loc: Location) -> &mut CssModuleExport {
+    self.exports_by_source_index[loc.source_index as usize]
+      .entry(name.into())
+      .or_insert_with(|| CssModuleExport {
+        name: name.into(),
+        composes: vec![],
+        is_referenced: false,
+        loc: Some(loc),
+      })
+  }
+
   pub fn handle_composes(
     &mut self,
     selectors: &SelectorList,
     composes: &Composes,
-    source_index: u32,
+    loc: Location,
   ) -> Result<(), PrinterErrorKind> {
+    let source_index = loc.source_index;
     for sel in &selectors.0 {
       if sel.len() == 1 {
         match sel.iter_raw_match_order().next().unwrap() {
           parcel_selectors::parser::Component::Class(ref id) => {
             for name in &composes.names {
               let reference = match &composes.from {
+                None if !self.config.classes => CssModuleReference::Local {
+                  name: name.0.as_ref().into(),
+                },
                 None => CssModuleReference::Local {
-                  name: self
-                    .config
-                    .pattern
-                    .write_to_string(
-                      String::new(),
-                      &self.hashes[source_index as usize],
-                      &self.sources[source_index as usize],
-                      name.0.as_ref(),
-                    )
-                    .unwrap(),
+                  name: self.config.name_to_string(
+                    "",
+                    &self.hashes[source_index as usize],
+                    &self.content_hashes[source_index as usize],
+                    self.sources[source_index as usize],
+                    &self.relative_paths[source_index as usize],
+                    name.0.as_ref(),
+                  ),
                 },
                 Some(Specifier::SourceIndex(dep_source_index)) => {
                   if let Some(entry) =
@@ -415,9 +878,7 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
                   {
                     let name = entry.name.clone();
                     let composes = entry.composes.clone();
-                    let export = self.exports_by_source_index[source_index as usize]
-                      .get_mut(&id.0.as_ref().to_owned())
-                      .unwrap();
+                    let export = self.class_export(id.0.as_ref(), loc);
 
                     export.composes.push(CssModuleReference::Local { name });
                     export.composes.extend(composes);
@@ -433,9 +894,7 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
                 },
               };
 
-              let export = self.exports_by_source_index[source_index as usize]
-                .get_mut(&id.0.as_ref().to_owned())
-                .unwrap();
+              let export = self.class_export(id.0.as_ref(), loc);
               if !export.composes.contains(&reference) {
                 export.composes.push(reference);
               }
@@ -454,15 +913,207 @@ impl<'a, 'b, 'c> CssModule<'a, 'b, 'c> {
   }
 }
 
-pub(crate) fn hash(s: &str, at_start: bool) -> String {
+/// Resolves the references to other files in the `composes` lists of a CSS module's exports.
+///
+/// Each [Dependency](CssModuleReference::Dependency) reference is replaced with the compiled name of
+/// the referenced export, followed by the names that export composes in turn, so that each export
+/// lists all of the class names to apply. The `load` callback is called with a specifier and the
+/// filename of the module it appears in, and returns the resolved filename and the exports of the
+/// referenced module. References to names that are not exported, or that form a cycle between
+/// modules, are left unresolved.
+///
+/// When bundling, references are resolved by the [Bundler](crate::bundler::Bundler) instead.
+pub fn resolve_composes<E, F>(exports: &mut CssModuleExports, filename: &str, mut load: F) -> Result<(), E>
+where
+  F: FnMut(&str, &str) -> Result<(String, CssModuleExports), E>,
+{
+  let mut resolver = ComposesResolver {
+    load: &mut load,
+    filenames: HashMap::new(),
+    modules: HashMap::new(),
+    resolving: vec![filename.to_owned()],
+  };
+  resolver.resolve_exports(exports, filename)
+}
+
+type LoadModule<'a, E> = dyn FnMut(&str, &str) -> Result<(String, CssModuleExports), E> + 'a;
+
+struct ComposesResolver<'a, E> {
+  load: &'a mut LoadModule<'a, E>,
+  /// The resolved filenames of each specifier, keyed by the filename it appears in and the specifier.
+  filenames: HashMap<(String, String), String>,
+  /// The exports of each loaded module, with their references resolved.
+  modules: HashMap<String, CssModuleExports>,
+  /// The filenames of the modules being resolved, to detect cycles.
+  resolving: Vec<String>,
+}
+
+impl<'a, E> ComposesResolver<'a, E> {
+  fn resolve_exports(&mut self, exports: &mut CssModuleExports, filename: &str) -> Result<(), E> {
+    for export in exports.values_mut() {
+      let mut composes = Vec::with_capacity(export.composes.len());
+      for reference in std::mem::take(&mut export.composes) {
+        let resolved = match &reference {
+          CssModuleReference::Dependency { name, specifier } => {
+            self.resolve_reference(name, specifier, filename)?
+          }
+          _ => None,
+        };
+
+        for reference in resolved.unwrap_or_else(|| vec![reference]) {
+          if !composes.contains(&reference) {
+            composes.push(reference);
+          }
+        }
+      }
+      export.composes = composes;
+    }
+
+    Ok(())
+  }
+
+  fn resolve_reference(
+    &mut self,
+    name: &str,
+    specifier: &str,
+    from: &str,
+  ) -> Result<Option<Vec<CssModuleReference>>, E> {
+    let key = (from.to_owned(), specifier.to_owned());
+    let filename = match self.filenames.get(&key) {
+      Some(filename) => filename.clone(),
+      None => {
+        let (filename, mut exports) = (self.load)(specifier, from)?;
+        self.filenames.insert(key, filename.clone());
+        if !self.resolving.contains(&filename) && !self.modules.contains_key(&filename) {
+          self.resolving.push(filename.clone());
+          self.resolve_exports(&mut exports, &filename)?;
+          self.resolving.pop();
+          self.modules.insert(filename.clone(), exports);
+        }
+        filename
+      }
+    };
+
+    let export = match self.modules.get(&filename).and_then(|exports| exports.get(name)) {
+      Some(export) => export,
+      None => return Ok(None),
+    };
+
+    let mut resolved = vec![CssModuleReference::Local {
+      name: export.name.clone(),
+    }];
+    resolved.extend(export.composes.iter().cloned());
+    Ok(Some(resolved))
+  }
+}
+
+impl<'i> Config<'i> {
+  /// Returns whether the given kind of name is scoped.
+  pub(crate) fn scopes(&self, kind: NameKind) -> bool {
+    match kind {
+      NameKind::Class => self.classes,
+      NameKind::Id => self.ids,
+      NameKind::Keyframes => self.animation,
+      NameKind::CounterStyle => self.counter_style,
+      NameKind::Container => self.container,
+      NameKind::Grid => self.grid,
+      NameKind::Other => true,
+    }
+  }
+
+  /// Writes the scoped name of a local identifier declared in the given file. The name is assigned
+  /// by the name allocator if one is configured, and generated from the pattern otherwise.
+  pub(crate) fn write_name<W, E>(
+    &self,
+    hash: &str,
+    content_hash: &str,
+    path: &Path,
+    relative_path: &str,
+    local: &str,
+    mut write: W,
+  ) -> Result<(), E>
+  where
+    W: FnMut(&str) -> Result<(), E>,
+  {
+    match &self.name_allocator {
+      Some(allocator) => write(&allocator.allocate(relative_path, local)),
+      None => self.pattern.write(hash, content_hash, path, local, write),
+    }
+  }
+
+  fn name_to_string(
+    &self,
+    prefix: &str,
+    hash: &str,
+    content_hash: &str,
+    path: &Path,
+    relative_path: &str,
+    local: &str,
+  ) -> String {
+    let mut res = String::from(prefix);
+    let _ = self.write_name(hash, content_hash, path, relative_path, local, |s| res.write_str(s));
+    res
+  }
+
+  /// Encodes a hash with the configured alphabet and length. Names cannot start with a digit,
+  /// so an underscore is prepended in this case when the hash is at the start.
+  fn encode_hash(&self, bytes: &[u8], at_start: bool) -> String {
+    let mut hash = match self.hash_encoding {
+      HashEncoding::Base64Url => ENCODER.encode(bytes),
+      HashEncoding::Base36 => encode_base36(bytes),
+    };
+    if let Some(length) = self.hash_length {
+      hash.truncate(length.max(1));
+    }
+    prefix_digit(hash, at_start)
+  }
+}
+
+/// Returns whether the pattern of the CSS modules config, if any, needs the hashes of file contents.
+pub(crate) fn needs_content_hash(config: &Option<Config>) -> bool {
+  matches!(config, Some(config) if config.pattern.has_content_hash())
+}
+
+/// Hashes the contents of a file, for the `[contenthash]` placeholder.
+pub(crate) fn content_hash(code: &str) -> u64 {
+  // Use a stable hash so that names stay the same across builds and platforms.
+  let digest = Sha256::digest(code.as_bytes());
+  u64::from_le_bytes(digest[..8].try_into().unwrap())
+}
+
+fn hash_u32(s: &str) -> u32 {
   let mut hasher = DefaultHasher::new();
   s.hash(&mut hasher);
-  let hash = hasher.finish() as u32;
+  hasher.finish() as u32
+}
 
-  let hash = ENCODER.encode(&hash.to_le_bytes());
+/// Encodes little endian bytes as a base 36 number.
+fn encode_base36(bytes: &[u8]) -> String {
+  let mut n = bytes.iter().rev().fold(0u128, |n, b| n << 8 | *b as u128);
+  let mut digits = Vec::new();
+  loop {
+    digits.push(char::from_digit((n % 36) as u32, 36).unwrap());
+    n /= 36;
+    if n == 0 {
+      break;
+    }
+  }
+  digits.iter().rev().collect()
+}
+
+fn prefix_digit(hash: String, at_start: bool) -> String {
   if at_start && matches!(hash.as_bytes()[0], b'0'..=b'9') {
     format!("_{}", hash)
   } else {
     hash
   }
 }
+
+pub(crate) fn hash(s: &str, at_start: bool) -> String {
+  prefix_digit(ENCODER.encode(&hash_u32(s).to_le_bytes()), at_start)
+}
+
+/// Returns the placeholder that references to a value imported by a `@value` rule are replaced with.
+pub(crate) fn value_placeholder(filename: &str, name: &str, specifier: &str) -> String {
+  hash(&format!("{}_{}_{}", filename, name, specifier), true)
+}
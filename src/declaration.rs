@@ -21,18 +21,25 @@ use crate::properties::{
   grid::GridHandler,
   list::ListStyleHandler,
   margin_padding::*,
+  motion::OffsetHandler,
   outline::OutlineHandler,
   overflow::OverflowHandler,
   position::PositionHandler,
   prefix_handler::{FallbackHandler, PrefixHandler},
-  size::SizeHandler,
-  text::TextDecorationHandler,
+  scroll_snap::ScrollSnapHandler,
+  size::{ContainIntrinsicSizeHandler, SizeHandler},
+  text::{TextDecorationHandler, WhiteSpaceHandler},
   transform::TransformHandler,
   transition::TransitionHandler,
+  will_change::WillChangeHandler,
 };
 use crate::properties::{Property, PropertyId};
+use crate::rules::comment::CommentRule;
+use crate::rules::value::ModuleValues;
+use crate::rules::Location;
 use crate::targets::Browsers;
 use crate::traits::{PropertyHandler, ToCss};
+use crate::unused_symbols::SymbolKind;
 use crate::values::string::CowArcStr;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
@@ -43,7 +50,7 @@ use cssparser::*;
 /// Properties are separated into a list of `!important` declararations,
 /// and a list of normal declarations. This reduces memory usage compared
 /// with storing a boolean along with each property.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "visitor", derive(Visit), visit(visit_declaration_block, PROPERTIES))]
 #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
 #[cfg_attr(
@@ -59,6 +66,80 @@ pub struct DeclarationBlock<'i> {
   /// A list of normal declarations in the block.
   #[cfg_attr(feature = "serde", serde(default))]
   pub declarations: Vec<Property<'i>>,
+  /// The source locations of the declarations, used to add a source map mapping for each declaration.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub locations: DeclarationLocations,
+  /// Comments preserved within the block, according to the comment policy the style sheet was parsed with.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub comments: Vec<DeclarationComment<'i>>,
+}
+
+impl<'i> PartialEq for DeclarationBlock<'i> {
+  fn eq(&self, other: &Self) -> bool {
+    // Source locations are not significant when comparing declarations.
+    self.important_declarations == other.important_declarations
+      && self.declarations == other.declarations
+      && self.comments == other.comments
+  }
+}
+
+/// The source locations of the declarations in a [DeclarationBlock](DeclarationBlock), i.e. the start of
+/// each declaration's value.
+///
+/// Each list has one location per declaration, in the same order. When the number of locations does
+/// not match the number of declarations, e.g. after declarations are added by a visitor, the locations
+/// are ignored, and the declarations are mapped to the location of the enclosing rule instead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeclarationLocations {
+  /// The locations of the `!important` declarations.
+  pub important_declarations: Vec<Location>,
+  /// The locations of the normal declarations.
+  pub declarations: Vec<Location>,
+}
+
+/// A comment preserved within a [DeclarationBlock](DeclarationBlock), attached to the declaration that
+/// follows it.
+///
+/// The comment is printed before the first declaration of the same property, or of a related shorthand or
+/// longhand, e.g. after minification merges declarations. Comments at the end of the block, or whose
+/// declaration was removed, are printed after the last declaration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct DeclarationComment<'i> {
+  /// The comment.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub comment: CommentRule<'i>,
+  /// The property of the declaration following the comment, or `None` at the end of the block.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub property_id: Option<PropertyId<'i>>,
+  /// Whether the declaration following the comment is `!important`.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub important: bool,
+}
+
+impl DeclarationLocations {
+  /// Returns the location of the declaration at the given index, if known.
+  fn get(&self, block: &DeclarationBlock, index: usize, important: bool) -> Option<Location> {
+    let (locations, len) = if important {
+      (&self.important_declarations, block.important_declarations.len())
+    } else {
+      (&self.declarations, block.declarations.len())
+    };
+
+    if locations.len() == len {
+      locations.get(index).copied()
+    } else {
+      None
+    }
+  }
 }
 
 impl<'i> DeclarationBlock<'i> {
@@ -67,14 +148,24 @@ impl<'i> DeclarationBlock<'i> {
     input: &mut Parser<'i, 't>,
     options: &'a ParserOptions<'o, 'i, T>,
   ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
-    let mut important_declarations = DeclarationList::new();
-    let mut declarations = DeclarationList::new();
+    Self::parse_with_values(input, options, None)
+  }
+
+  /// Parses a declaration block, substituting references to CSS modules `@value` rules.
+  pub(crate) fn parse_with_values<'a, 'o, 't, T>(
+    input: &mut Parser<'i, 't>,
+    options: &'a ParserOptions<'o, 'i, T>,
+    values: Option<&'a ModuleValues<'i>>,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    // Comments are skipped by the declaration list parser, so find the preserved comments up front.
+    let comments = options.find_comments(input);
+    let mut declarations = DeclarationBlock::new();
     let mut parser = DeclarationListParser::new(
       input,
       PropertyDeclarationParser {
-        important_declarations: &mut important_declarations,
         declarations: &mut declarations,
         options,
+        values,
       },
     );
     while let Some(res) = parser.next() {
@@ -87,10 +178,11 @@ impl<'i> DeclarationBlock<'i> {
       }
     }
 
-    Ok(DeclarationBlock {
-      important_declarations,
-      declarations,
-    })
+    for comment in comments {
+      declarations.add_comment(comment);
+    }
+
+    Ok(declarations)
   }
 
   /// Parses a declaration block from a string.
@@ -110,6 +202,8 @@ impl<'i> DeclarationBlock<'i> {
     Self {
       declarations: vec![],
       important_declarations: vec![],
+      locations: DeclarationLocations::default(),
+      comments: vec![],
     }
   }
 
@@ -129,7 +223,9 @@ impl<'i> ToCss for DeclarationBlock<'i> {
 
     macro_rules! write {
       ($decls: expr, $important: literal) => {
-        for decl in &$decls {
+        for (index, decl) in $decls.iter().enumerate() {
+          #[cfg(feature = "sourcemap")]
+          self.add_mapping(dest, index, $important);
           decl.to_css(dest, $important)?;
           if i != len - 1 {
             dest.write_char(';')?;
@@ -161,8 +257,11 @@ impl<'i> DeclarationBlock<'i> {
 
     macro_rules! write {
       ($decls: expr, $important: literal) => {
-        for decl in &$decls {
+        for (index, decl) in $decls.iter().enumerate() {
+          self.write_comments(dest, Some(index), $important)?;
           dest.newline()?;
+          #[cfg(feature = "sourcemap")]
+          self.add_mapping(dest, index, $important);
           decl.to_css(dest, $important)?;
           if i != len - 1 || !dest.minify {
             dest.write_char(';')?;
@@ -174,11 +273,92 @@ impl<'i> DeclarationBlock<'i> {
 
     write!(self.declarations, false);
     write!(self.important_declarations, true);
+    self.write_comments(dest, None, false)?;
 
     dest.dedent();
     dest.newline()?;
     dest.write_char('}')
   }
+
+  /// Attaches a comment to the declaration that follows it in the source, based on the declaration locations.
+  pub(crate) fn add_comment(&mut self, comment: CommentRule<'i>) {
+    let is_after = |loc: &&Location| (loc.line, loc.column) > (comment.loc.line, comment.loc.column);
+    let next = self
+      .locations
+      .declarations
+      .iter()
+      .zip(&self.declarations)
+      .map(|(loc, property)| (loc, property, false))
+      .chain(
+        self
+          .locations
+          .important_declarations
+          .iter()
+          .zip(&self.important_declarations)
+          .map(|(loc, property)| (loc, property, true)),
+      )
+      .filter(|(loc, ..)| is_after(loc))
+      .min_by_key(|(loc, ..)| (loc.line, loc.column));
+
+    self.comments.push(DeclarationComment {
+      property_id: next.map(|(_, property, _)| property.property_id()),
+      important: matches!(next, Some((.., true))),
+      comment,
+    });
+  }
+
+  /// Writes the comments attached to the declaration at the given index, or when `index` is `None`,
+  /// the comments at the end of the block.
+  pub(crate) fn write_comments<W>(
+    &self,
+    dest: &mut Printer<W>,
+    index: Option<usize>,
+    important: bool,
+  ) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    for comment in &self.comments {
+      if self.comment_index(comment) == index && (index.is_none() || comment.important == important) {
+        dest.newline()?;
+        comment.comment.to_css(dest)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Returns the index of the declaration a comment is printed before, or `None` if it is printed after
+  /// the last declaration.
+  fn comment_index(&self, comment: &DeclarationComment<'i>) -> Option<usize> {
+    let property_id = comment.property_id.as_ref()?;
+    let declarations = if comment.important {
+      &self.important_declarations
+    } else {
+      &self.declarations
+    };
+
+    declarations
+      .iter()
+      .position(|property| property.property_id().name() == property_id.name())
+      .or_else(|| {
+        declarations.iter().position(|property| {
+          let id = property.property_id();
+          is_longhand_of(&id, property_id) || is_longhand_of(property_id, &id)
+        })
+      })
+  }
+
+  /// Adds a source map mapping for the declaration at the given index, if its location is known.
+  #[cfg(feature = "sourcemap")]
+  pub(crate) fn add_mapping<W>(&self, dest: &mut Printer<W>, index: usize, important: bool)
+  where
+    W: std::fmt::Write,
+  {
+    if let Some(loc) = self.locations.get(self, index, important) {
+      dest.add_declaration_mapping(loc);
+    }
+  }
 }
 
 impl<'i> DeclarationBlock<'i> {
@@ -192,6 +372,9 @@ impl<'i> DeclarationBlock<'i> {
       ($decls: expr, $handler: expr, $important: literal) => {
         for decl in $decls.iter() {
           context.is_important = $important;
+          context.add_unsupported_function_warnings(decl);
+          context.add_unsupported_property_warnings(decl);
+          context.add_lowered_features(decl);
           let handled = $handler.handle_property(decl, context);
 
           if !handled {
@@ -206,8 +389,81 @@ impl<'i> DeclarationBlock<'i> {
 
     handler.finalize(context);
     important_handler.finalize(context);
-    self.important_declarations = std::mem::take(&mut important_handler.decls);
-    self.declarations = std::mem::take(&mut handler.decls);
+    let important_declarations = std::mem::take(&mut important_handler.decls);
+    let declarations = std::mem::take(&mut handler.decls);
+    self.locations = DeclarationLocations {
+      important_declarations: remap_locations(
+        &self.important_declarations,
+        &self.locations.important_declarations,
+        &important_declarations,
+      ),
+      declarations: remap_locations(&self.declarations, &self.locations.declarations, &declarations),
+    };
+    self.important_declarations = important_declarations;
+    self.declarations = declarations;
+  }
+
+  /// Retains only the declarations for which the predicate returns true, along with their locations.
+  /// The predicate is called with each declaration, and whether it is `!important`.
+  pub(crate) fn retain<F: FnMut(&Property<'i>, bool) -> bool>(&mut self, mut f: F) {
+    fn retain<'i, F: FnMut(&Property<'i>) -> bool>(
+      declarations: &mut Vec<Property<'i>>,
+      locations: &mut Vec<Location>,
+      mut f: F,
+    ) {
+      if locations.len() != declarations.len() {
+        locations.clear();
+        declarations.retain(f);
+        return;
+      }
+
+      let keep: Vec<bool> = declarations.iter().map(&mut f).collect();
+      let mut keep_location = keep.iter();
+      locations.retain(|_| *keep_location.next().unwrap());
+      let mut keep_declaration = keep.iter();
+      declarations.retain(|_| *keep_declaration.next().unwrap());
+    }
+
+    retain(&mut self.declarations, &mut self.locations.declarations, |property| {
+      f(property, false)
+    });
+    retain(
+      &mut self.important_declarations,
+      &mut self.locations.important_declarations,
+      |property| f(property, true),
+    );
+  }
+
+  /// Appends the declarations of another block to this one, along with their locations and comments.
+  pub(crate) fn append(&mut self, other: &mut DeclarationBlock<'i>) {
+    fn append<'i>(
+      declarations: &mut Vec<Property<'i>>,
+      locations: &mut Vec<Location>,
+      other_declarations: &mut Vec<Property<'i>>,
+      other_locations: &mut Vec<Location>,
+    ) {
+      if locations.len() == declarations.len() && other_locations.len() == other_declarations.len() {
+        locations.append(other_locations);
+      } else {
+        locations.clear();
+        other_locations.clear();
+      }
+      declarations.append(other_declarations);
+    }
+
+    append(
+      &mut self.declarations,
+      &mut self.locations.declarations,
+      &mut other.declarations,
+      &mut other.locations.declarations,
+    );
+    append(
+      &mut self.important_declarations,
+      &mut self.locations.important_declarations,
+      &mut other.important_declarations,
+      &mut other.locations.important_declarations,
+    );
+    self.comments.append(&mut other.comments);
   }
 
   /// Returns whether the declaration block is empty.
@@ -300,14 +556,15 @@ impl<'i> DeclarationBlock<'i> {
   /// includes the longhand, the shorthand will be updated rather than appending a new declaration.
   pub fn set(&mut self, property: Property<'i>, important: bool) {
     let property_id = property.property_id();
-    let declarations = if important {
-      // Remove any non-important properties with this id.
-      self.declarations.retain(|decl| decl.property_id() != property_id);
-      &mut self.important_declarations
+    // Remove any properties with this id and the opposite importance.
+    self.retain(|decl, is_important| is_important == important || decl.property_id() != property_id);
+    let (declarations, locations) = if important {
+      (
+        &mut self.important_declarations,
+        &mut self.locations.important_declarations,
+      )
     } else {
-      // Remove any important properties with this id.
-      self.important_declarations.retain(|decl| decl.property_id() != property_id);
-      &mut self.declarations
+      (&mut self.declarations, &mut self.locations.declarations)
     };
 
     let longhands = property_id.longhands().unwrap_or_else(|| vec![property.property_id()]);
@@ -343,6 +600,8 @@ impl<'i> DeclarationBlock<'i> {
       }
     }
 
+    // The location of the new declaration is unknown.
+    locations.clear();
     declarations.push(property)
   }
 
@@ -398,15 +657,17 @@ impl<'i> DeclarationBlock<'i> {
       }
     }
 
+    // The locations of split shorthands are unknown.
+    self.locations = DeclarationLocations::default();
     remove(&mut self.declarations, property_id);
     remove(&mut self.important_declarations, property_id);
   }
 }
 
 struct PropertyDeclarationParser<'a, 'o, 'i, T> {
-  important_declarations: &'a mut Vec<Property<'i>>,
-  declarations: &'a mut Vec<Property<'i>>,
+  declarations: &'a mut DeclarationBlock<'i>,
   options: &'a ParserOptions<'o, 'i, T>,
+  values: Option<&'a ModuleValues<'i>>,
 }
 
 /// Parse a declaration within {} block: `color: blue`
@@ -419,13 +680,7 @@ impl<'a, 'o, 'i, T> cssparser::DeclarationParser<'i> for PropertyDeclarationPars
     name: CowRcStr<'i>,
     input: &mut cssparser::Parser<'i, 't>,
   ) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
-    parse_declaration(
-      name,
-      input,
-      &mut self.declarations,
-      &mut self.important_declarations,
-      &self.options,
-    )
+    parse_declaration(name, input, self.declarations, self.options, self.values)
   }
 }
 
@@ -439,12 +694,29 @@ impl<'a, 'o, 'i, T> AtRuleParser<'i> for PropertyDeclarationParser<'a, 'o, 'i, T
 pub(crate) fn parse_declaration<'i, 't, T>(
   name: CowRcStr<'i>,
   input: &mut cssparser::Parser<'i, 't>,
-  declarations: &mut DeclarationList<'i>,
-  important_declarations: &mut DeclarationList<'i>,
-  options: &ParserOptions<T>,
+  declarations: &mut DeclarationBlock<'i>,
+  options: &ParserOptions<'_, 'i, T>,
+  values: Option<&ModuleValues<'i>>,
 ) -> Result<(), cssparser::ParseError<'i, ParserError<'i>>> {
+  // The location of the value, after any leading whitespace.
+  let state = input.state();
+  input.skip_whitespace();
+  let location = input.current_source_location();
+  let loc = Location {
+    source_index: options.source_index,
+    line: location.line,
+    column: location.column,
+  };
+  input.reset(&state);
+
   let property = input.parse_until_before(Delimiter::Bang, |input| {
-    Property::parse(PropertyId::from(CowArcStr::from(name)), input, options)
+    let property_id = PropertyId::from(CowArcStr::from(name));
+    if let Some(values) = values {
+      if let Some(property) = values.parse_property(&property_id, input, options)? {
+        return Ok(property);
+      }
+    }
+    Property::parse(property_id, input, options)
   })?;
   let important = input
     .try_parse(|input| {
@@ -453,13 +725,61 @@ pub(crate) fn parse_declaration<'i, 't, T>(
     })
     .is_ok();
   if important {
-    important_declarations.push(property);
+    declarations.important_declarations.push(property);
+    declarations.locations.important_declarations.push(loc);
   } else {
-    declarations.push(property);
+    declarations.declarations.push(property);
+    declarations.locations.declarations.push(loc);
   }
   Ok(())
 }
 
+/// Assigns locations to the declarations produced by minifying a list of declarations. A declaration uses the
+/// location of the last original declaration of the same property. Shorthands that were merged from longhands,
+/// and longhands that were split from a shorthand, use the location of the earliest original declaration that
+/// contributed to them. Declarations that were generated, e.g. vendor prefixed fallbacks, use the location of
+/// the first original declaration.
+fn remap_locations(original: &[Property], locations: &[Location], minified: &[Property]) -> Vec<Location> {
+  if locations.is_empty() || locations.len() != original.len() {
+    return Vec::new();
+  }
+
+  minified
+    .iter()
+    .map(|property| {
+      let property_id = property.property_id();
+      let same = original
+        .iter()
+        .zip(locations)
+        .rev()
+        .find(|(original, _)| original.property_id().name() == property_id.name());
+      if let Some((_, loc)) = same {
+        return *loc;
+      }
+
+      original
+        .iter()
+        .zip(locations)
+        .find(|(original, _)| {
+          let original_id = original.property_id();
+          is_longhand_of(&original_id, &property_id) || is_longhand_of(&property_id, &original_id)
+        })
+        .map_or(locations[0], |(_, loc)| *loc)
+    })
+    .collect()
+}
+
+/// Returns whether a property is a longhand of a shorthand, directly or via an intermediate shorthand,
+/// e.g. `border-top-width` of `border`. Vendor prefixes are ignored.
+fn is_longhand_of(longhand: &PropertyId, shorthand: &PropertyId) -> bool {
+  match shorthand.longhands() {
+    Some(longhands) => longhands
+      .iter()
+      .any(|id| id.name() == longhand.name() || is_longhand_of(longhand, id)),
+    None => false,
+  }
+}
+
 pub(crate) type DeclarationList<'i> = Vec<Property<'i>>;
 
 pub(crate) struct DeclarationHandler<'i> {
@@ -470,12 +790,17 @@ pub(crate) struct DeclarationHandler<'i> {
   grid: GridHandler<'i>,
   align: AlignHandler,
   size: SizeHandler,
+  contain_intrinsic_size: ContainIntrinsicSizeHandler,
   margin: MarginHandler<'i>,
   padding: PaddingHandler<'i>,
   scroll_margin: ScrollMarginHandler<'i>,
   scroll_padding: ScrollPaddingHandler<'i>,
+  scroll_snap: ScrollSnapHandler,
+  offset: OffsetHandler<'i>,
+  will_change: WillChangeHandler<'i>,
   font: FontHandler<'i>,
   text: TextDecorationHandler<'i>,
+  white_space: WhiteSpaceHandler,
   list: ListStyleHandler<'i>,
   transition: TransitionHandler<'i>,
   animation: AnimationHandler<'i>,
@@ -502,12 +827,17 @@ impl<'i> DeclarationHandler<'i> {
       grid: GridHandler::default(),
       align: AlignHandler::new(targets),
       size: SizeHandler::default(),
+      contain_intrinsic_size: ContainIntrinsicSizeHandler::default(),
       margin: MarginHandler::default(),
       padding: PaddingHandler::default(),
       scroll_margin: ScrollMarginHandler::default(),
       scroll_padding: ScrollPaddingHandler::default(),
+      scroll_snap: ScrollSnapHandler::new(targets),
+      offset: OffsetHandler::new(targets),
+      will_change: WillChangeHandler::default(),
       font: FontHandler::default(),
       text: TextDecorationHandler::new(targets),
+      white_space: WhiteSpaceHandler::new(targets),
       list: ListStyleHandler::new(targets),
       transition: TransitionHandler::new(targets),
       animation: AnimationHandler::new(targets),
@@ -531,11 +861,21 @@ impl<'i> DeclarationHandler<'i> {
     context: &mut PropertyHandlerContext<'i, '_>,
   ) -> bool {
     if !context.unused_symbols.is_empty()
-      && matches!(property, Property::Custom(custom) if context.unused_symbols.contains(custom.name.as_ref()))
+      && matches!(property, Property::Custom(custom) if context.unused_symbols.contains(SymbolKind::Name, custom.name.as_ref()))
     {
       return true;
     }
 
+    // Typed attr() values are not handled by the property handlers, so their pending values are
+    // flushed first to preserve the order of the declarations.
+    if let Property::Attr(attr) = property {
+      self.finalize(context);
+      let mut attr = attr.clone();
+      context.add_attr_fallbacks(&mut attr);
+      self.decls.push(Property::Attr(attr));
+      return true;
+    }
+
     self.background.handle_property(property, &mut self.decls, context)
       || self.border.handle_property(property, &mut self.decls, context)
       || self.outline.handle_property(property, &mut self.decls, context)
@@ -543,12 +883,16 @@ impl<'i> DeclarationHandler<'i> {
       || self.grid.handle_property(property, &mut self.decls, context)
       || self.align.handle_property(property, &mut self.decls, context)
       || self.size.handle_property(property, &mut self.decls, context)
+      || self.contain_intrinsic_size.handle_property(property, &mut self.decls, context)
       || self.margin.handle_property(property, &mut self.decls, context)
       || self.padding.handle_property(property, &mut self.decls, context)
       || self.scroll_margin.handle_property(property, &mut self.decls, context)
       || self.scroll_padding.handle_property(property, &mut self.decls, context)
+      || self.scroll_snap.handle_property(property, &mut self.decls, context)
+      || self.offset.handle_property(property, &mut self.decls, context)
       || self.font.handle_property(property, &mut self.decls, context)
       || self.text.handle_property(property, &mut self.decls, context)
+      || self.white_space.handle_property(property, &mut self.decls, context)
       || self.list.handle_property(property, &mut self.decls, context)
       || self.transition.handle_property(property, &mut self.decls, context)
       || self.animation.handle_property(property, &mut self.decls, context)
@@ -560,6 +904,7 @@ impl<'i> DeclarationHandler<'i> {
       || self.box_shadow.handle_property(property, &mut self.decls, context)
       || self.mask.handle_property(property, &mut self.decls, context)
       || self.container.handle_property(property, &mut self.decls, context)
+      || self.will_change.handle_property(property, &mut self.decls, context)
       || self.fallback.handle_property(property, &mut self.decls, context)
       || self.prefix.handle_property(property, &mut self.decls, context)
   }
@@ -572,12 +917,16 @@ impl<'i> DeclarationHandler<'i> {
     self.grid.finalize(&mut self.decls, context);
     self.align.finalize(&mut self.decls, context);
     self.size.finalize(&mut self.decls, context);
+    self.contain_intrinsic_size.finalize(&mut self.decls, context);
     self.margin.finalize(&mut self.decls, context);
     self.padding.finalize(&mut self.decls, context);
     self.scroll_margin.finalize(&mut self.decls, context);
     self.scroll_padding.finalize(&mut self.decls, context);
+    self.scroll_snap.finalize(&mut self.decls, context);
+    self.offset.finalize(&mut self.decls, context);
     self.font.finalize(&mut self.decls, context);
     self.text.finalize(&mut self.decls, context);
+    self.white_space.finalize(&mut self.decls, context);
     self.list.finalize(&mut self.decls, context);
     self.transition.finalize(&mut self.decls, context);
     self.animation.finalize(&mut self.decls, context);
@@ -589,6 +938,7 @@ impl<'i> DeclarationHandler<'i> {
     self.box_shadow.finalize(&mut self.decls, context);
     self.mask.finalize(&mut self.decls, context);
     self.container.finalize(&mut self.decls, context);
+    self.will_change.finalize(&mut self.decls, context);
     self.fallback.finalize(&mut self.decls, context);
     self.prefix.finalize(&mut self.decls, context);
   }
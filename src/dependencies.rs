@@ -2,7 +2,8 @@
 //!
 //! Dependencies in CSS can be analyzed using the `analyze_dependencies` option
 //! when printing a style sheet. These include other style sheets referenved via
-//! the `@import` rule, as well as `url()` references. See [PrinterOptions](PrinterOptions).
+//! the `@import` rule, as well as `url()` references. Paint worklets referenced via
+//! the `paint()` function are also reported. See [PrinterOptions](PrinterOptions).
 //!
 //! When dependency analysis is enabled, `@import` rules are removed, and `url()`
 //! dependencies are replaced with hashed placeholders that can be substituted with
@@ -12,6 +13,7 @@ use crate::css_modules::hash;
 use crate::printer::PrinterOptions;
 use crate::rules::import::ImportRule;
 use crate::traits::ToCss;
+use crate::values::image::PaintImage;
 use crate::values::url::Url;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
@@ -20,7 +22,7 @@ use cssparser::SourceLocation;
 use serde::Serialize;
 
 /// Options for `analyze_dependencies` in `PrinterOptions`.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct DependencyOptions {
   /// Whether to remove `@import` rules.
   pub remove_imports: bool,
@@ -38,6 +40,8 @@ pub enum Dependency {
   Import(ImportDependency),
   /// A `url()` dependency.
   Url(UrlDependency),
+  /// A `paint()` worklet dependency.
+  Paint(PaintDependency),
 }
 
 /// An `@import` dependency.
@@ -101,6 +105,12 @@ pub struct UrlDependency {
   pub url: String,
   /// The placeholder that the URL was replaced with.
   pub placeholder: String,
+  /// Any url modifiers, e.g. `crossorigin(anonymous)`, serialized as strings.
+  #[cfg_attr(
+    any(feature = "serde", feature = "nodejs"),
+    serde(skip_serializing_if = "Vec::is_empty")
+  )]
+  pub modifiers: Vec<String>,
   /// The location of the dependency in the source file.
   pub loc: SourceRange,
 }
@@ -112,11 +122,37 @@ impl UrlDependency {
     UrlDependency {
       url: url.url.to_string(),
       placeholder,
+      // Modifiers that cannot be serialized on their own are left out rather than failing.
+      modifiers: url
+        .modifiers
+        .iter()
+        .filter_map(|modifier| modifier.to_css_string(PrinterOptions::default()).ok())
+        .collect(),
       loc: SourceRange::new(filename, url.loc, 4, url.url.len()),
     }
   }
 }
 
+/// A `paint()` worklet dependency.
+#[derive(Debug)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+pub struct PaintDependency {
+  /// The name of the paint worklet.
+  pub name: String,
+  /// The location of the dependency in the source file.
+  pub loc: SourceRange,
+}
+
+impl PaintDependency {
+  /// Creates a new paint worklet dependency.
+  pub fn new(paint: &PaintImage, filename: &str) -> PaintDependency {
+    PaintDependency {
+      name: paint.name.0.to_string(),
+      loc: SourceRange::new(filename, paint.loc, 6, paint.name.0.len()),
+    }
+  }
+}
+
 /// Represents the range of source code where a dependency was found.
 #[derive(Debug)]
 #[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
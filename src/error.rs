@@ -99,6 +99,29 @@ pub enum ParserError<'i> {
   UnexpectedToken(#[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))] Token<'i>),
   /// Maximum nesting depth was reached.
   MaximumNestingDepth,
+  /// A function is not supported by the configured browser targets.
+  UnsupportedFunction(CowArcStr<'i>),
+  /// A value is not supported by the configured browser targets.
+  UnsupportedValue(CowArcStr<'i>),
+  /// A property is not supported by the configured browser targets.
+  UnsupportedProperty(CowArcStr<'i>),
+  /// An unknown property name was referenced.
+  UnknownProperty(CowArcStr<'i>),
+  /// A property that cannot be animated was referenced where an animatable property is expected.
+  NonAnimatableProperty(CowArcStr<'i>),
+  /// A selector can never match any element, and was removed.
+  ImpossibleSelector(CowArcStr<'i>),
+  /// The value of a custom property does not match the syntax registered by its `@property` rule.
+  InvalidRegisteredPropertyValue(CowArcStr<'i>),
+  /// The initial value of a custom property registered by an `@property` rule is not valid for a property it is referenced in.
+  IncompatibleRegisteredProperty {
+    /// The name of the custom property.
+    name: CowArcStr<'i>,
+    /// The name of the property referencing it.
+    property: CowArcStr<'i>,
+  },
+  /// An imported file has the same contents as another imported file, and was deduplicated.
+  DuplicateImport(CowArcStr<'i>),
 }
 
 impl<'i> fmt::Display for ParserError<'i> {
@@ -126,6 +149,27 @@ impl<'i> fmt::Display for ParserError<'i> {
       ),
       UnexpectedToken(token) => write!(f, "Unexpected token {:?}", token),
       MaximumNestingDepth => write!(f, "Overflowed the maximum nesting depth"),
+      UnsupportedFunction(name) => write!(f, "The {}() function is not supported by the configured targets", name),
+      UnsupportedValue(value) => write!(f, "The `{}` value is not supported by the configured targets", value),
+      UnsupportedProperty(name) => write!(f, "The `{}` property is not supported by the configured targets", name),
+      UnknownProperty(name) => write!(f, "Unknown property `{}`", name),
+      NonAnimatableProperty(name) => write!(f, "The `{}` property is not animatable", name),
+      ImpossibleSelector(selector) => write!(f, "The selector `{}` can never match, and was removed", selector),
+      InvalidRegisteredPropertyValue(name) => write!(
+        f,
+        "The value of the `{}` custom property does not match the syntax of its @property rule",
+        name
+      ),
+      IncompatibleRegisteredProperty { name, property } => write!(
+        f,
+        "The initial value of the `{}` custom property is not valid for the `{}` property",
+        name, property
+      ),
+      DuplicateImport(original) => write!(
+        f,
+        "Imported file has the same contents as {}, and was deduplicated",
+        original
+      ),
     }
   }
 }
@@ -210,6 +254,8 @@ pub enum SelectorError<'i> {
   PseudoElementExpectedIdent(#[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))] Token<'i>),
   /// An unexpected identifier was encountered.
   UnexpectedIdent(CowArcStr<'i>),
+  /// A selector in a pure CSS module does not contain a local class or id.
+  PureCssModuleClass,
   /// An unexpected token was encountered inside an attribute selector.
   UnexpectedTokenInAttributeSelector(
     #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))] Token<'i>,
@@ -238,6 +284,7 @@ impl<'i> fmt::Display for SelectorError<'i> {
       MissingNestingSelector => write!(f, "A nesting selector (&) is required in each selector of a @nest rule"),
       NoQualifiedNameInAttributeSelector(token) => write!(f, "No qualified name in attribute selector: {:?}.", token),
       PseudoElementExpectedIdent(token) => write!(f, "Invalid token in pseudo element: {:?}", token),
+      PureCssModuleClass => write!(f, "A selector in a pure CSS module must contain at least one local class or id"),
       UnexpectedIdent(name) => write!(f, "Unexpected identifier: {}", name),
       UnexpectedTokenInAttributeSelector(token) => write!(f, "Unexpected token in attribute selector: {:?}", token),
       UnsupportedPseudoClassOrElement(name) => write!(f, "Unsupported pseudo class or element: {}", name),
@@ -366,6 +413,8 @@ pub enum PrinterErrorKind {
   InvalidComposesSelector,
   /// The CSS modules pattern must end with `[local]` for use in CSS grid.
   InvalidCssModulesPatternInGrid,
+  /// The source map could not be serialized.
+  SourceMapError,
 }
 
 impl From<fmt::Error> for PrinterError {
@@ -386,6 +435,7 @@ impl fmt::Display for PrinterErrorKind {
       InvalidComposesNesting => write!(f, "The `composes` property cannot be used within nested rules"),
       InvalidComposesSelector => write!(f, "The `composes` property cannot be used with a simple class selector"),
       InvalidCssModulesPatternInGrid => write!(f, "The CSS modules `pattern` config must end with `[local]` for use in CSS grid line names."),
+      SourceMapError => write!(f, "Failed to serialize the source map"),
     }
   }
 }
@@ -10,7 +10,7 @@ impl Default for PropertyCategory {
   }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Eq, Hash)]
 pub enum LogicalGroup {
   BorderColor,
   BorderStyle,
@@ -24,4 +24,5 @@ pub enum LogicalGroup {
   Size,
   MinSize,
   MaxSize,
+  ContainIntrinsicSize,
 }
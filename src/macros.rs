@@ -723,6 +723,21 @@ macro_rules! define_list_shorthand {
 pub(crate) use define_list_shorthand;
 
 macro_rules! rect_shorthand {
+  (
+    $(#[$meta: meta])*
+    $vis:vis struct $name: ident<$l: lifetime, $t: ty> {
+      $top: ident,
+      $right: ident,
+      $bottom: ident,
+      $left: ident
+    }
+  ) => {
+    rect_shorthand! {
+      @impl
+      $(#[$meta])*
+      $name<$l>($t) { $top, $right, $bottom, $left }
+    }
+  };
   (
     $(#[$meta: meta])*
     $vis:vis struct $name: ident<$t: ty> {
@@ -731,10 +746,26 @@ macro_rules! rect_shorthand {
       $bottom: ident,
       $left: ident
     }
+  ) => {
+    rect_shorthand! {
+      @impl
+      $(#[$meta])*
+      $name($t) { $top, $right, $bottom, $left }
+    }
+  };
+  (
+    @impl
+    $(#[$meta: meta])*
+    $name: ident$(<$l: lifetime>)?($t: ty) {
+      $top: ident,
+      $right: ident,
+      $bottom: ident,
+      $left: ident
+    }
   ) => {
     define_shorthand! {
       $(#[$meta])*
-      pub struct $name {
+      pub struct $name$(<$l>)? {
         /// The top value.
         top: $top($t),
         /// The right value.
@@ -746,7 +777,7 @@ macro_rules! rect_shorthand {
       }
     }
 
-    impl<'i> Parse<'i> for $name {
+    impl<'i> Parse<'i> for $name$(<$l>)? {
       fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
         let rect = Rect::parse(input)?;
         Ok(Self {
@@ -758,7 +789,7 @@ macro_rules! rect_shorthand {
       }
     }
 
-    impl ToCss for $name {
+    impl$(<$l>)? ToCss for $name$(<$l>)? {
       fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
       where
         W: std::fmt::Write,
@@ -772,6 +803,26 @@ macro_rules! rect_shorthand {
 pub(crate) use rect_shorthand;
 
 macro_rules! size_shorthand {
+  (
+    $(#[$outer:meta])*
+    $vis:vis struct $name: ident<$l: lifetime, $t: ty> {
+      $(#[$a_meta: meta])*
+      $a_key: ident: $a_prop: ident,
+      $(#[$b_meta: meta])*
+      $b_key: ident: $b_prop: ident,
+    }
+  ) => {
+    size_shorthand! {
+      @impl
+      $(#[$outer])*
+      $vis $name<$l>($t) {
+        $(#[$a_meta])*
+        $a_key: $a_prop,
+        $(#[$b_meta])*
+        $b_key: $b_prop,
+      }
+    }
+  };
   (
     $(#[$outer:meta])*
     $vis:vis struct $name: ident<$t: ty> {
@@ -780,10 +831,31 @@ macro_rules! size_shorthand {
       $(#[$b_meta: meta])*
       $b_key: ident: $b_prop: ident,
     }
+  ) => {
+    size_shorthand! {
+      @impl
+      $(#[$outer])*
+      $vis $name($t) {
+        $(#[$a_meta])*
+        $a_key: $a_prop,
+        $(#[$b_meta])*
+        $b_key: $b_prop,
+      }
+    }
+  };
+  (
+    @impl
+    $(#[$outer:meta])*
+    $vis:vis $name: ident$(<$l: lifetime>)?($t: ty) {
+      $(#[$a_meta: meta])*
+      $a_key: ident: $a_prop: ident,
+      $(#[$b_meta: meta])*
+      $b_key: ident: $b_prop: ident,
+    }
   ) => {
     define_shorthand! {
       $(#[$outer])*
-      $vis struct $name {
+      $vis struct $name$(<$l>)? {
         $(#[$a_meta])*
         $a_key: $a_prop($t),
         $(#[$b_meta])*
@@ -791,7 +863,7 @@ macro_rules! size_shorthand {
       }
     }
 
-    impl<'i> Parse<'i> for $name {
+    impl<'i> Parse<'i> for $name$(<$l>)? {
       fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
         let size = Size2D::parse(input)?;
         Ok(Self {
@@ -801,7 +873,7 @@ macro_rules! size_shorthand {
       }
     }
 
-    impl ToCss for $name {
+    impl$(<$l>)? ToCss for $name$(<$l>)? {
       fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
       where
         W: std::fmt::Write,
@@ -9,7 +9,7 @@ use crate::rules::custom_media::CustomMediaRule;
 use crate::rules::Location;
 use crate::stylesheet::ParserOptions;
 use crate::targets::Browsers;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Zero};
 use crate::values::ident::Ident;
 use crate::values::number::CSSNumber;
 use crate::values::string::CowArcStr;
@@ -39,6 +39,15 @@ pub struct MediaList<'i> {
 }
 
 impl<'i> MediaList<'i> {
+  /// Adds the media query features used by this list that will be down-leveled for the targets.
+  pub(crate) fn get_lowered_features(&self, targets: Browsers, features: &mut Vec<Feature>) {
+    for query in &self.media_queries {
+      if let Some(condition) = &query.condition {
+        condition.get_lowered_features(targets, features);
+      }
+    }
+  }
+
   /// Creates an empty media query list.
   pub fn new() -> Self {
     MediaList { media_queries: vec![] }
@@ -95,21 +104,35 @@ impl<'i> MediaList<'i> {
   ///
   /// Returns an error if the boolean logic is not possible.
   pub fn and(&mut self, b: &MediaList<'i>) -> Result<(), ()> {
+    if b.media_queries.is_empty() {
+      return Ok(());
+    }
+
     if self.media_queries.is_empty() {
       self.media_queries.extend(b.media_queries.iter().cloned());
       return Ok(());
     }
 
-    for b in &b.media_queries {
-      if self.media_queries.contains(&b) {
-        continue;
+    // Each query in one list must be combined with each query in the other,
+    // e.g. `print, screen` and `(color)` => `print and (color), screen and (color)`.
+    let mut media_queries = Vec::new();
+    for a in &self.media_queries {
+      for b in &b.media_queries {
+        let mut mq = a.clone();
+        mq.and(b)?;
+        if !media_queries.contains(&mq) {
+          media_queries.push(mq);
+        }
       }
+    }
 
-      for a in &mut self.media_queries {
-        a.and(&b)?;
-      }
+    if media_queries.iter().any(|mq| !mq.never_matches()) {
+      media_queries.retain(|mq| !mq.never_matches());
+    } else {
+      media_queries.truncate(1);
     }
 
+    self.media_queries = media_queries;
     Ok(())
   }
 
@@ -122,6 +145,68 @@ impl<'i> MediaList<'i> {
       }
     }
   }
+
+  /// Returns whether this media query list matches whenever the other one matches,
+  /// e.g. `(min-width: 600px)` contains `(min-width: 800px)`.
+  pub(crate) fn contains(&self, other: &MediaList<'i>) -> bool {
+    self.always_matches()
+      || (!other.media_queries.is_empty()
+        && other
+          .media_queries
+          .iter()
+          .all(|b| self.media_queries.iter().any(|a| a.contains(b))))
+  }
+
+  /// Returns whether this media query list and the other one can never match at the same time,
+  /// e.g. `(min-width: 800px)` and `(max-width: 600px)`.
+  pub(crate) fn excludes(&self, other: &MediaList<'i>) -> bool {
+    self.never_matches()
+      || other.never_matches()
+      || (!self.media_queries.is_empty()
+        && !other.media_queries.is_empty()
+        && self
+          .media_queries
+          .iter()
+          .all(|a| other.media_queries.iter().all(|b| a.excludes(b))))
+  }
+
+  /// Simplifies the media queries in the list, and removes queries that are
+  /// already matched by another query in the list.
+  pub(crate) fn minify(&mut self, targets: Option<Browsers>) {
+    for query in &mut self.media_queries {
+      query.minify(targets);
+    }
+
+    if self.media_queries.iter().any(|query| query.always_matches()) {
+      self.media_queries = vec![MediaQuery {
+        qualifier: None,
+        media_type: MediaType::All,
+        condition: None,
+      }];
+      return;
+    }
+
+    if self.media_queries.iter().any(|query| !query.never_matches()) {
+      self.media_queries.retain(|query| !query.never_matches());
+    }
+
+    // Remove queries that are contained in another query. When two queries are
+    // equivalent, the first one is kept.
+    let mut i = 0;
+    while i < self.media_queries.len() {
+      let query = &self.media_queries[i];
+      let redundant = self
+        .media_queries
+        .iter()
+        .enumerate()
+        .any(|(j, other)| j != i && other.contains(query) && (j < i || !query.contains(other)));
+      if redundant {
+        self.media_queries.remove(i);
+      } else {
+        i += 1;
+      }
+    }
+  }
 }
 
 impl<'i> ToCss for MediaList<'i> {
@@ -304,39 +389,142 @@ impl<'i> MediaQuery<'i> {
     self.qualifier == Some(Qualifier::Not) && self.media_type == MediaType::All && self.condition == None
   }
 
+  /// Returns a media query that never matches, i.e. `not all`.
+  fn never() -> Self {
+    MediaQuery {
+      qualifier: Some(Qualifier::Not),
+      media_type: MediaType::All,
+      condition: None,
+    }
+  }
+
+  fn minify(&mut self, targets: Option<Browsers>) {
+    let result = match &mut self.condition {
+      Some(condition) => condition.minify(targets),
+      None => return,
+    };
+
+    match (result, self.qualifier) {
+      (Some(matches), _) => {
+        self.condition = None;
+        // `not screen and <false>` always matches.
+        if !matches && self.qualifier == Some(Qualifier::Not) {
+          self.qualifier = None;
+          self.media_type = MediaType::All;
+        } else if !matches {
+          self.qualifier = Some(Qualifier::Not);
+          self.media_type = MediaType::All;
+        }
+      }
+      // `not all and (cond)` can be written as `not (cond)` in media queries level 4,
+      // which shipped alongside the range syntax.
+      (None, Some(Qualifier::Not))
+        if self.media_type == MediaType::All
+          && matches!(targets, Some(targets) if Feature::MediaRangeSyntax.is_compatible(targets)) =>
+      {
+        self.qualifier = None;
+        let condition = self.condition.take().unwrap();
+        self.condition = Some(match condition {
+          MediaCondition::Not(condition) => *condition,
+          condition => MediaCondition::Not(Box::new(condition)),
+        });
+      }
+      _ => {}
+    }
+  }
+
+  /// Returns whether this query matches whenever the other query matches.
+  fn contains(&self, other: &MediaQuery<'i>) -> bool {
+    if self == other {
+      return true;
+    }
+
+    if self.qualifier.is_some() || other.qualifier.is_some() {
+      return false;
+    }
+
+    if self.media_type != MediaType::All && self.media_type != other.media_type {
+      return false;
+    }
+
+    match (&self.condition, &other.condition) {
+      (None, _) => true,
+      (Some(_), None) => false,
+      (Some(a), Some(b)) => {
+        let b = b.conjuncts();
+        a.conjuncts().iter().all(|a| b.iter().any(|b| b.implies(a)))
+      }
+    }
+  }
+
+  /// Returns whether this query and the other one can never match at the same time.
+  fn excludes(&self, other: &MediaQuery<'i>) -> bool {
+    if self.qualifier == Some(Qualifier::Not) || other.qualifier == Some(Qualifier::Not) {
+      return false;
+    }
+
+    if self.media_type != MediaType::All
+      && other.media_type != MediaType::All
+      && self.media_type != other.media_type
+    {
+      return true;
+    }
+
+    match (&self.condition, &other.condition) {
+      (Some(a), Some(b)) => {
+        let b = b.conjuncts();
+        a.conjuncts().iter().any(|a| b.iter().any(|b| a.excludes(b)))
+      }
+      _ => false,
+    }
+  }
+
   /// Attempts to combine the given media query into this one. The resulting media query
   /// matches if both of the original media queries would have matched.
   ///
   /// Returns an error if the boolean logic is not possible.
   pub fn and<'a>(&mut self, b: &MediaQuery<'i>) -> Result<(), ()> {
+    if self == b || b.always_matches() || self.never_matches() {
+      return Ok(());
+    }
+
+    if self.always_matches() || b.never_matches() {
+      *self = b.clone();
+      return Ok(());
+    }
+
+    // A negated query with a condition negates the whole query, e.g. `not print and (color)`
+    // matches screens without color, so it cannot be combined with anything else.
+    let is_negated = |mq: &MediaQuery| mq.qualifier == Some(Qualifier::Not);
+    if (is_negated(self) && self.condition.is_some()) || (is_negated(b) && b.condition.is_some()) {
+      return Err(());
+    }
+
     let at = (&self.qualifier, &self.media_type);
     let bt = (&b.qualifier, &b.media_type);
     let (qualifier, media_type) = match (at, bt) {
-      // `not all and screen` => not all
-      // `screen and not all` => not all
-      ((&Some(Qualifier::Not), &MediaType::All), _) |
-      (_, (&Some(Qualifier::Not), &MediaType::All)) => (Some(Qualifier::Not), MediaType::All),
       // `not screen and not print` => ERROR
-      // `not screen and not screen` => not screen
-      ((&Some(Qualifier::Not), a), (&Some(Qualifier::Not), b)) => {
-        if a == b {
-          (Some(Qualifier::Not), a.clone())
-        } else {
-          return Err(())
-        }
-      },
-      // `all and print` => print
-      // `print and all` => print
-      // `all and not print` => not print
-      ((_, MediaType::All), (q, t)) |
-      ((q, t), (_, MediaType::All)) |
+      ((&Some(Qualifier::Not), _), (&Some(Qualifier::Not), _)) => return Err(()),
+      // `not print and print` => not all
+      ((&Some(Qualifier::Not), a), (_, b)) | ((_, b), (&Some(Qualifier::Not), a)) if a == b => {
+        *self = MediaQuery::never();
+        return Ok(());
+      }
+      // `not print and (color)` => ERROR
+      ((&Some(Qualifier::Not), _), (_, MediaType::All)) | ((_, MediaType::All), (&Some(Qualifier::Not), _)) => {
+        return Err(())
+      }
       // `not screen and print` => print
       // `print and not screen` => print
-      ((&Some(Qualifier::Not), _), (q, t)) |
-      ((q, t), (&Some(Qualifier::Not), _)) => (q.clone(), t.clone()),
+      ((&Some(Qualifier::Not), _), (q, t)) | ((q, t), (&Some(Qualifier::Not), _)) => (*q, t.clone()),
       // `print and screen` => not all
-      ((_, a), (_, b)) if a != b => (Some(Qualifier::Not), MediaType::All),
-      ((_, a), _) => (None, a.clone())
+      ((_, a), (_, b)) if a != b && *a != MediaType::All && *b != MediaType::All => {
+        *self = MediaQuery::never();
+        return Ok(());
+      }
+      // `all and print` => print
+      // `only screen and screen` => only screen
+      ((qa, a), (qb, b)) => (qa.or(*qb), if *a == MediaType::All { b.clone() } else { a.clone() }),
     };
 
     self.qualifier = qualifier;
@@ -442,6 +630,26 @@ pub enum MediaCondition<'i> {
 }
 
 impl<'i> MediaCondition<'i> {
+  fn get_lowered_features(&self, targets: Browsers, features: &mut Vec<Feature>) {
+    match self {
+      MediaCondition::Feature(MediaFeature::Range { .. }) if !Feature::MediaRangeSyntax.is_compatible(targets) => {
+        features.push(Feature::MediaRangeSyntax)
+      }
+      MediaCondition::Feature(MediaFeature::Interval { .. })
+        if !Feature::MediaIntervalSyntax.is_compatible(targets) =>
+      {
+        features.push(Feature::MediaIntervalSyntax)
+      }
+      MediaCondition::Feature(..) => {}
+      MediaCondition::Not(condition) => condition.get_lowered_features(targets, features),
+      MediaCondition::Operation { conditions, .. } => {
+        for condition in conditions {
+          condition.get_lowered_features(targets, features);
+        }
+      }
+    }
+  }
+
   /// Parse a single media condition.
   pub fn parse<'t>(input: &mut Parser<'i, 't>, allow_or: bool) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -502,6 +710,96 @@ impl<'i> MediaCondition<'i> {
     })
   }
 
+  /// Returns the conditions that must all be true for this condition to be true.
+  fn conjuncts(&self) -> &[MediaCondition<'i>] {
+    match self {
+      MediaCondition::Operation {
+        operator: Operator::And,
+        conditions,
+      } => conditions,
+      condition => std::slice::from_ref(condition),
+    }
+  }
+
+  /// Returns whether this condition is true whenever the other condition is true.
+  fn implies(&self, other: &MediaCondition<'i>) -> bool {
+    if self == other {
+      return true;
+    }
+
+    match (self, other) {
+      (MediaCondition::Feature(a), MediaCondition::Feature(b)) => {
+        let a = a.bounds();
+        let b = b.bounds();
+        !b.is_empty() && b.iter().all(|b| a.iter().any(|a| a.implies(b)))
+      }
+      _ => false,
+    }
+  }
+
+  /// Returns whether this condition and the other one can never be true at the same time.
+  fn excludes(&self, other: &MediaCondition<'i>) -> bool {
+    match (self, other) {
+      (MediaCondition::Feature(a), MediaCondition::Feature(b)) => {
+        let b = b.bounds();
+        a.bounds().iter().any(|a| b.iter().any(|b| a.excludes(b)))
+      }
+      _ => false,
+    }
+  }
+
+  /// Simplifies the condition for the given targets. Returns whether the condition
+  /// always or never matches, if it is known.
+  fn minify(&mut self, targets: Option<Browsers>) -> Option<bool> {
+    match self {
+      MediaCondition::Feature(feature) => {
+        if feature.always_matches() {
+          return Some(true);
+        }
+
+        if let Some(targets) = targets {
+          if Feature::MediaRangeSyntax.is_compatible(targets) {
+            feature.convert_to_range();
+          }
+        }
+        None
+      }
+      MediaCondition::Not(condition) => condition.minify(targets).map(|matches| !matches),
+      MediaCondition::Operation { operator, conditions } => {
+        let operator = *operator;
+        let mut result = None;
+        conditions.retain_mut(|condition| match condition.minify(targets) {
+          Some(matches) if matches == (operator == Operator::Or) => {
+            result = Some(matches);
+            true
+          }
+          Some(_) => false,
+          None => true,
+        });
+
+        if result.is_some() {
+          return result;
+        }
+
+        if operator == Operator::And
+          && matches!(targets, Some(targets) if Feature::MediaIntervalSyntax.is_compatible(targets))
+        {
+          merge_intervals(conditions);
+        }
+
+        match conditions.len() {
+          // All conditions were removed: `and` of true conditions, or `or` of false ones.
+          0 => Some(operator == Operator::And),
+          1 => {
+            *self = conditions.pop().unwrap();
+            None
+          }
+          _ => None,
+        }
+      }
+    }
+  }
+
   fn needs_parens(&self, parent_operator: Option<Operator>, targets: &Option<Browsers>) -> bool {
     match self {
       MediaCondition::Not(_) => true,
@@ -677,7 +975,177 @@ impl<'i> Parse<'i> for MediaFeature<'i> {
   }
 }
 
+/// Range features that may be written with a `min-` or `max-` prefix.
+const RANGE_FEATURES: &[&str] = &[
+  "width",
+  "height",
+  "aspect-ratio",
+  "resolution",
+  "color",
+  "color-index",
+  "monochrome",
+  "device-width",
+  "device-height",
+  "device-aspect-ratio",
+];
+
+/// A lower or upper bound on the value of a range feature.
+struct Bound<'a, 'i> {
+  name: &'a str,
+  lower: bool,
+  inclusive: bool,
+  value: &'a MediaFeatureValue<'i>,
+}
+
+impl<'a, 'i> Bound<'a, 'i> {
+  /// Returns whether every value within this bound is within the other bound.
+  fn implies(&self, other: &Bound) -> bool {
+    if self.name != other.name || self.lower != other.lower {
+      return false;
+    }
+
+    match self.value.compare(other.value) {
+      Some(std::cmp::Ordering::Equal) => other.inclusive || !self.inclusive,
+      Some(std::cmp::Ordering::Greater) => self.lower,
+      Some(std::cmp::Ordering::Less) => !self.lower,
+      None => false,
+    }
+  }
+
+  /// Returns whether no value is within both bounds.
+  fn excludes(&self, other: &Bound) -> bool {
+    if self.name != other.name || self.lower == other.lower {
+      return false;
+    }
+
+    let (lower, upper) = if self.lower { (self, other) } else { (other, self) };
+    match lower.value.compare(upper.value) {
+      Some(std::cmp::Ordering::Greater) => true,
+      Some(std::cmp::Ordering::Equal) => !lower.inclusive || !upper.inclusive,
+      _ => false,
+    }
+  }
+}
+
 impl<'i> MediaFeature<'i> {
+  /// Returns the bounds that the feature places on the value of a range feature.
+  fn bounds(&self) -> Vec<Bound<'_, 'i>> {
+    use MediaFeatureComparison::*;
+    let bound = |name, operator: &MediaFeatureComparison, value, reversed: bool| {
+      let operator = if reversed { operator.opposite() } else { *operator };
+      let (lower, inclusive) = match operator {
+        GreaterThan => (true, false),
+        GreaterThanEqual => (true, true),
+        LessThan => (false, false),
+        LessThanEqual => (false, true),
+        Equal => {
+          return vec![
+            Bound {
+              name,
+              lower: true,
+              inclusive: true,
+              value,
+            },
+            Bound {
+              name,
+              lower: false,
+              inclusive: true,
+              value,
+            },
+          ]
+        }
+      };
+      vec![Bound {
+        name,
+        lower,
+        inclusive,
+        value,
+      }]
+    };
+
+    match self {
+      MediaFeature::Plain { name, value } => {
+        let (operator, name) = if let Some(name) = name.0.strip_prefix("min-") {
+          (GreaterThanEqual, name)
+        } else if let Some(name) = name.0.strip_prefix("max-") {
+          (LessThanEqual, name)
+        } else {
+          return Vec::new();
+        };
+
+        if !RANGE_FEATURES.contains(&name) {
+          return Vec::new();
+        }
+
+        bound(name, &operator, value, false)
+      }
+      MediaFeature::Range { name, operator, value } => bound(name.0.as_ref(), operator, value, false),
+      MediaFeature::Interval {
+        name,
+        start,
+        start_operator,
+        end,
+        end_operator,
+      } => {
+        // The start value is on the left side of the comparison, e.g. `100px < width`.
+        let mut bounds = bound(name.0.as_ref(), start_operator, start, true);
+        bounds.extend(bound(name.0.as_ref(), end_operator, end, false));
+        bounds
+      }
+      MediaFeature::Boolean { .. } => Vec::new(),
+    }
+  }
+
+  /// Returns whether the feature matches in all environments, e.g. `(min-width: 0)`.
+  fn always_matches(&self) -> bool {
+    let (name, value) = match self {
+      MediaFeature::Plain { name, value } => match name.0.strip_prefix("min-") {
+        Some(name) => (name, value),
+        None => return false,
+      },
+      MediaFeature::Range {
+        name,
+        operator: MediaFeatureComparison::GreaterThanEqual,
+        value,
+      } => (name.0.as_ref(), value),
+      _ => return false,
+    };
+
+    if !RANGE_FEATURES.contains(&name) {
+      return false;
+    }
+
+    match value {
+      MediaFeatureValue::Length(length) => length.is_zero(),
+      MediaFeatureValue::Number(number) => *number == 0.0,
+      MediaFeatureValue::Resolution(resolution) => resolution.to_dpi() == 0.0,
+      _ => false,
+    }
+  }
+
+  /// Converts a `min-` or `max-` prefixed feature to the range syntax, which is shorter.
+  fn convert_to_range(&mut self) {
+    if let MediaFeature::Plain { name, value } = self {
+      let (operator, unprefixed) = if let Some(name) = name.0.strip_prefix("min-") {
+        (MediaFeatureComparison::GreaterThanEqual, name)
+      } else if let Some(name) = name.0.strip_prefix("max-") {
+        (MediaFeatureComparison::LessThanEqual, name)
+      } else {
+        return;
+      };
+
+      if !RANGE_FEATURES.contains(&unprefixed) {
+        return;
+      }
+
+      *self = MediaFeature::Range {
+        name: Ident(unprefixed.to_owned().into()),
+        operator,
+        value: value.clone(),
+      };
+    }
+  }
+
   fn parse_name_first<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let name = Ident::parse(input)?;
 
@@ -903,6 +1371,19 @@ impl<'i> ToCss for MediaFeatureValue<'i> {
   }
 }
 
+impl<'i> MediaFeatureValue<'i> {
+  /// Compares two values of the same type, if possible.
+  fn compare(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    match (self, other) {
+      (MediaFeatureValue::Length(a), MediaFeatureValue::Length(b)) => a.partial_cmp(b),
+      (MediaFeatureValue::Number(a), MediaFeatureValue::Number(b)) => a.partial_cmp(b),
+      (MediaFeatureValue::Resolution(a), MediaFeatureValue::Resolution(b)) => a.to_dpi().partial_cmp(&b.to_dpi()),
+      (MediaFeatureValue::Ratio(a), MediaFeatureValue::Ratio(b)) => (a.0 / a.1).partial_cmp(&(b.0 / b.1)),
+      _ => None,
+    }
+  }
+}
+
 impl<'i> std::ops::Add<f32> for MediaFeatureValue<'i> {
   type Output = Self;
 
@@ -918,6 +1399,57 @@ impl<'i> std::ops::Add<f32> for MediaFeatureValue<'i> {
   }
 }
 
+/// Combines lower and upper bounds on the same feature in a list of `and` conditions
+/// into an interval, e.g. `(width >= 100px) and (width < 200px)` becomes `(100px <= width < 200px)`.
+fn merge_intervals<'i>(conditions: &mut Vec<MediaCondition<'i>>) {
+  let is_lower = |operator: &MediaFeatureComparison| {
+    matches!(
+      operator,
+      MediaFeatureComparison::GreaterThan | MediaFeatureComparison::GreaterThanEqual
+    )
+  };
+  let is_upper = |operator: &MediaFeatureComparison| {
+    matches!(
+      operator,
+      MediaFeatureComparison::LessThan | MediaFeatureComparison::LessThanEqual
+    )
+  };
+
+  let mut i = 0;
+  while i < conditions.len() {
+    let (name, start_operator, start) = match &conditions[i] {
+      MediaCondition::Feature(MediaFeature::Range { name, operator, value }) if is_lower(operator) => {
+        (name.clone(), operator.opposite(), value.clone())
+      }
+      _ => {
+        i += 1;
+        continue;
+      }
+    };
+
+    let upper = conditions.iter().position(|condition| {
+      matches!(condition, MediaCondition::Feature(MediaFeature::Range { name: n, operator, .. }) if *n == name && is_upper(operator))
+    });
+
+    if let Some(j) = upper {
+      if let MediaCondition::Feature(MediaFeature::Range { operator, value, .. }) = conditions.remove(j) {
+        if j < i {
+          i -= 1;
+        }
+        conditions[i] = MediaCondition::Feature(MediaFeature::Interval {
+          name,
+          start,
+          start_operator,
+          end: value,
+          end_operator: operator,
+        });
+      }
+    }
+
+    i += 1;
+  }
+}
+
 /// Consumes an operation or a colon, or returns an error.
 fn consume_operation_or_colon<'i, 't>(
   input: &mut Parser<'i, 't>,
@@ -1135,6 +1667,41 @@ mod tests {
     assert_eq!(and("all", "only screen"), "only screen");
     assert_eq!(and("only screen", "all"), "only screen");
     assert_eq!(and("print", "print"), "print");
+    assert_eq!(and("not print", "print"), "not all");
+    assert_eq!(and("not print", "screen and (color)"), "screen and (color)");
+
+    let mut a = parse("not print and (color)");
+    assert!(a.and(&parse("screen")).is_err());
+    let mut a = parse("not print");
+    assert!(a.and(&parse("(color)")).is_err());
+  }
+
+  #[test]
+  fn test_and_list() {
+    fn and_list(a: &str, b: &str) -> Result<String, ()> {
+      fn parse(s: &str) -> MediaList<'_> {
+        let mut input = ParserInput::new(s);
+        let mut parser = Parser::new(&mut input);
+        MediaList::parse(&mut parser).unwrap()
+      }
+
+      let mut a = parse(a);
+      let b = parse(b);
+      a.and(&b)?;
+      Ok(a.to_css_string(PrinterOptions::default()).unwrap())
+    }
+
+    assert_eq!(
+      and_list("print, screen", "(color)").unwrap(),
+      "print and (color), screen and (color)"
+    );
+    assert_eq!(and_list("print, screen", "print").unwrap(), "print");
+    assert_eq!(and_list("print", "screen").unwrap(), "not all");
+    assert_eq!(
+      and_list("(min-width: 100px), (color)", "(max-width: 200px), print").unwrap(),
+      "(min-width: 100px) and (max-width: 200px), print and (min-width: 100px), (color) and (max-width: 200px), print and (color)"
+    );
+    assert!(and_list("not print, screen", "not screen").is_err());
   }
 
   #[test]
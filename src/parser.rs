@@ -1,5 +1,5 @@
-use crate::declaration::{parse_declaration, DeclarationBlock, DeclarationList};
-use crate::error::{Error, ParserError, PrinterError};
+use crate::declaration::{parse_declaration, DeclarationBlock};
+use crate::error::{Error, ParserError, PrinterError, SelectorError};
 use crate::media_query::*;
 use crate::printer::Printer;
 use crate::properties::custom::TokenList;
@@ -7,11 +7,13 @@ use crate::rules::container::{ContainerName, ContainerRule};
 use crate::rules::font_palette_values::FontPaletteValuesRule;
 use crate::rules::layer::{LayerBlockRule, LayerStatementRule};
 use crate::rules::property::PropertyRule;
+use crate::rules::view_transition::ViewTransitionRule;
 use crate::rules::viewport::ViewportRule;
 use crate::rules::{
+  comment::{CommentPolicy, CommentRule},
   counter_style::CounterStyleRule,
   custom_media::CustomMediaRule,
-  document::MozDocumentRule,
+  document::{DocumentCondition, MozDocumentRule},
   font_face::{FontFaceDeclarationParser, FontFaceRule},
   import::ImportRule,
   keyframes::{KeyframeListParser, KeyframesName, KeyframesRule},
@@ -23,9 +25,10 @@ use crate::rules::{
   style::StyleRule,
   supports::{SupportsCondition, SupportsRule},
   unknown::UnknownAtRule,
+  value::{ModuleValue, ModuleValues, ValueRule},
   CssRule, CssRuleList, Location,
 };
-use crate::selector::{Component, SelectorList, SelectorParser};
+use crate::selector::{is_pure_css_modules_selector, Component, SelectorList, SelectorParser};
 use crate::traits::Parse;
 use crate::values::ident::{CustomIdent, DashedIdent};
 use crate::values::string::CowArcStr;
@@ -57,6 +60,8 @@ pub struct ParserOptions<'o, 'i, T = DefaultAtRuleParser> {
   pub warnings: Option<Arc<RwLock<Vec<Error<ParserError<'i>>>>>>,
   /// A custom at rule parser.
   pub at_rule_parser: Option<T>,
+  /// Which comments between rules and declarations to preserve. By default, all comments are discarded.
+  pub preserve_comments: CommentPolicy,
 }
 
 impl<'o, 'i, T> ParserOptions<'o, 'i, T> {
@@ -68,6 +73,67 @@ impl<'o, 'i, T> ParserOptions<'o, 'i, T> {
       }
     }
   }
+
+  /// Consumes the whitespace and comments before the next rule in a rule list,
+  /// and appends the comments preserved by the comment policy to the list.
+  pub(crate) fn parse_comments<'t, R>(&self, input: &mut Parser<'i, 't>, rules: &mut Vec<CssRule<'i, R>>) {
+    if self.preserve_comments.is_empty() {
+      return;
+    }
+
+    loop {
+      let state = input.state();
+      match input.next_including_whitespace_and_comments() {
+        Ok(&Token::WhiteSpace(..)) => {}
+        Ok(&Token::Comment(text)) => {
+          if self.preserve_comments.preserves(text) {
+            rules.push(CssRule::Comment(self.comment(text, &state)));
+          }
+        }
+        _ => {
+          input.reset(&state);
+          return;
+        }
+      }
+    }
+  }
+
+  /// Returns the comments preserved by the comment policy within a block, without consuming any input.
+  pub(crate) fn find_comments<'t>(&self, input: &mut Parser<'i, 't>) -> Vec<CommentRule<'i>> {
+    let mut comments = Vec::new();
+    if self.preserve_comments.is_empty() {
+      return comments;
+    }
+
+    let start = input.state();
+    loop {
+      let state = input.state();
+      match input.next_including_whitespace_and_comments() {
+        Ok(&Token::Comment(text)) => {
+          if self.preserve_comments.preserves(text) {
+            comments.push(self.comment(text, &state));
+          }
+        }
+        Ok(_) => {}
+        Err(_) => break,
+      }
+    }
+
+    input.reset(&start);
+    comments
+  }
+
+  pub(crate) fn comment(&self, text: &'i str, state: &ParserState) -> CommentRule<'i> {
+    let loc = state.source_location();
+    CommentRule {
+      text: text.into(),
+      loc: Location {
+        source_index: self.source_index,
+        line: loc.line,
+        column: loc.column,
+      },
+    }
+  }
 }
 
 impl<'o, 'i> ParserOptions<'o, 'i, DefaultAtRuleParser> {
@@ -82,6 +148,7 @@ impl<'o, 'i> ParserOptions<'o, 'i, DefaultAtRuleParser> {
       error_recovery: false,
       warnings: None,
       at_rule_parser: None,
+      preserve_comments: CommentPolicy::default(),
     }
   }
 
@@ -132,6 +199,7 @@ enum State {
 pub struct TopLevelRuleParser<'a, 'o, 'i, T> {
   default_namespace: Option<CowArcStr<'i>>,
   namespace_prefixes: HashMap<CowArcStr<'i>, CowArcStr<'i>>,
+  values: ModuleValues<'i>,
   pub options: &'a mut ParserOptions<'o, 'i, T>,
   state: State,
 }
@@ -141,6 +209,7 @@ impl<'a, 'o, 'b, 'i, T> TopLevelRuleParser<'a, 'o, 'i, T> {
     TopLevelRuleParser {
       default_namespace: None,
       namespace_prefixes: HashMap::new(),
+      values: ModuleValues::default(),
       options,
       state: State::Start,
     }
@@ -150,6 +219,7 @@ impl<'a, 'o, 'b, 'i, T> TopLevelRuleParser<'a, 'o, 'i, T> {
     NestedRuleParser {
       default_namespace: &mut self.default_namespace,
       namespace_prefixes: &mut self.namespace_prefixes,
+      values: &self.values,
       options: &mut self.options,
     }
   }
@@ -179,8 +249,8 @@ pub enum AtRulePrelude<'i, T> {
   Keyframes(KeyframesName<'i>, VendorPrefix),
   /// A @page rule prelude.
   Page(Vec<PageSelector<'i>>),
-  /// A @-moz-document rule.
-  MozDocument,
+  /// A @document or @-moz-document rule.
+  MozDocument(VendorPrefix, Vec<DocumentCondition<'i>>),
   /// A @import rule prelude.
   Import(
     CowRcStr<'i>,
@@ -202,6 +272,10 @@ pub enum AtRulePrelude<'i, T> {
   Property(DashedIdent<'i>),
   /// A @container prelude.
   Container(Option<ContainerName<'i>>, MediaCondition<'i>),
+  /// A @view-transition prelude.
+  ViewTransition,
+  /// A CSS modules @value prelude, with the source text of a value definition.
+  Value(ModuleValue<'i>, Option<&'i str>),
   /// An unknown prelude.
   Unknown(CowArcStr<'i>, TokenList<'i>),
   /// A custom prelude.
@@ -271,6 +345,10 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for TopLevelRuleParser<'a
         let name = DashedIdent::parse(input)?;
         return Ok(AtRulePrelude::Property(name))
       },
+      "value" if matches!(&self.options.css_modules, Some(css_modules) if css_modules.values) => {
+        let (value, source) = self.values.parse_rule(input, self.options)?;
+        return Ok(AtRulePrelude::Value(value, source))
+      },
       _ => {}
     }
 
@@ -332,6 +410,10 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for TopLevelRuleParser<'a
         self.state = State::Body;
         CssRule::CustomMedia(CustomMediaRule { name, query, loc })
       }
+      AtRulePrelude::Value(value, source) => {
+        self.values.insert(&value, source, &self.options.filename);
+        CssRule::Value(ValueRule { value, loc })
+      }
       AtRulePrelude::Layer(_) => {
         // @layer statements are allowed before @import rules, but cannot be interleaved.
         if self.state <= State::Layers {
@@ -388,6 +470,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> QualifiedRuleParser<'i> for TopLevelRulePa
 struct NestedRuleParser<'a, 'o, 'i, T> {
   default_namespace: &'a Option<CowArcStr<'i>>,
   namespace_prefixes: &'a HashMap<CowArcStr<'i>, CowArcStr<'i>>,
+  values: &'a ModuleValues<'i>,
   options: &'a mut ParserOptions<'o, 'i, T>,
 }
 
@@ -399,12 +482,19 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> NestedRuleParser<'a, 'o, 'i, T> {
     let nested_parser = NestedRuleParser {
       default_namespace: self.default_namespace,
       namespace_prefixes: self.namespace_prefixes,
+      values: self.values,
       options: self.options,
     };
 
     let mut iter = RuleListParser::new_for_nested_rule(input, nested_parser);
     let mut rules = Vec::new();
-    while let Some(result) = iter.next() {
+    loop {
+      iter.parser.options.parse_comments(iter.input, &mut rules);
+      let result = match iter.next() {
+        Some(result) => result,
+        None => break,
+      };
+
       match result {
         Ok(CssRule::Ignored) => {}
         Ok(rule) => rules.push(rule),
@@ -443,7 +533,7 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for NestedRuleParser<
   ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
     match_ignore_ascii_case! { &*name,
       "media" => {
-        let media = MediaList::parse(input)?;
+        let media = self.values.parse_media_list(input)?;
         Ok(AtRulePrelude::Media(media))
       },
       "supports" => {
@@ -497,25 +587,15 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for NestedRuleParser<
         let selectors = input.try_parse(|input| input.parse_comma_separated(PageSelector::parse)).unwrap_or_default();
         Ok(AtRulePrelude::Page(selectors))
       },
-      "-moz-document" => {
-        // Firefox only supports the url-prefix() function with no arguments as a legacy CSS hack.
-        // See https://css-tricks.com/snippets/css/css-hacks-targeting-firefox/
-        input.expect_function_matching("url-prefix")?;
-        input.parse_nested_block(|input| {
-          // Firefox also allows an empty string as an argument...
-          // https://github.com/mozilla/gecko-dev/blob/0077f2248712a1b45bf02f0f866449f663538164/servo/components/style/stylesheets/document_rule.rs#L303
-          let _ = input.try_parse(|input| -> Result<(), ParseError<'i, Self::Error>> {
-            let s = input.expect_string()?;
-            if !s.is_empty() {
-              return Err(input.new_custom_error(ParserError::InvalidValue))
-            }
-            Ok(())
-          });
-          input.expect_exhausted()?;
-          Ok(())
-        })?;
+      "document" | "-moz-document" => {
+        let prefix = if starts_with_ignore_ascii_case(&name, "-moz-") {
+          VendorPrefix::Moz
+        } else {
+          VendorPrefix::None
+        };
 
-        Ok(AtRulePrelude::MozDocument)
+        let conditions = input.parse_comma_separated(DocumentCondition::parse)?;
+        Ok(AtRulePrelude::MozDocument(prefix, conditions))
       },
       "layer" => {
         let names = match Vec::<LayerName>::parse(input) {
@@ -530,6 +610,9 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for NestedRuleParser<
         let condition = MediaCondition::parse(input, true)?;
         Ok(AtRulePrelude::Container(name, condition))
       },
+      "view-transition" => {
+        Ok(AtRulePrelude::ViewTransition)
+      },
       _ => {
         if let Some(at_rule_parser) = &mut self.options.at_rule_parser {
           if let Ok(prelude) = at_rule_parser.parse_prelude(name.clone(), input) {
@@ -619,6 +702,7 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for NestedRuleParser<
           name,
           keyframes: iter.filter_map(Result::ok).collect(),
           vendor_prefix,
+          aliases: Vec::new(),
           loc,
         }))
       }
@@ -626,7 +710,9 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for NestedRuleParser<
         let rule = PageRule::parse(selectors, input, loc, self.options)?;
         Ok(CssRule::Page(rule))
       }
-      AtRulePrelude::MozDocument => Ok(CssRule::MozDocument(MozDocumentRule {
+      AtRulePrelude::MozDocument(vendor_prefix, conditions) => Ok(CssRule::MozDocument(MozDocumentRule {
+        vendor_prefix,
+        conditions,
         rules: self.parse_nested_rules(input)?,
         loc,
       })),
@@ -647,9 +733,11 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for NestedRuleParser<
       }
       AtRulePrelude::LayerBlock(..) => unreachable!(), // only used in nested style rules.
       AtRulePrelude::Property(name) => Ok(CssRule::Property(PropertyRule::parse(name, input, loc)?)),
+      AtRulePrelude::ViewTransition => Ok(CssRule::ViewTransition(ViewTransitionRule::parse(input, loc)?)),
       AtRulePrelude::Import(..)
       | AtRulePrelude::Namespace(..)
       | AtRulePrelude::CustomMedia(..)
+      | AtRulePrelude::Value(..)
       | AtRulePrelude::Charset => {
         // These rules don't have blocks.
         Err(input.new_unexpected_token_error(Token::CurlyBracketBlock))
@@ -724,7 +812,23 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> QualifiedRuleParser<'i> for NestedRule
       is_nesting_allowed: false,
       options: &self.options,
     };
-    SelectorList::parse(&selector_parser, input, NestingRequirement::None)
+    let location = input.current_source_location();
+    let selectors = SelectorList::parse(&selector_parser, input, NestingRequirement::None)?;
+    if let Some(config) = &self.options.css_modules {
+      if config.pure
+        && !selectors
+          .0
+          .iter()
+          .all(|selector| is_pure_css_modules_selector(selector, config))
+      {
+        let err = location.new_custom_error(ParserError::SelectorError(SelectorError::PureCssModuleClass));
+        if !self.options.error_recovery {
+          return Err(err);
+        }
+        self.options.warn(err);
+      }
+    }
+    Ok(selectors)
   }
 
   fn parse_block<'t>(
@@ -735,9 +839,18 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> QualifiedRuleParser<'i> for NestedRule
   ) -> Result<CssRule<'i, T::AtRule>, ParseError<'i, Self::Error>> {
     let loc = self.loc(start);
     let (declarations, rules) = if self.options.nesting {
-      parse_declarations_and_nested_rules(input, self.default_namespace, self.namespace_prefixes, self.options)?
+      parse_declarations_and_nested_rules(
+        input,
+        self.default_namespace,
+        self.namespace_prefixes,
+        self.values,
+        self.options,
+      )?
     } else {
-      (DeclarationBlock::parse(input, self.options)?, CssRuleList(vec![]))
+      (
+        DeclarationBlock::parse_with_values(input, self.options, Some(self.values))?,
+        CssRuleList(vec![]),
+      )
     };
     Ok(CssRule::Style(StyleRule {
       selectors,
@@ -753,27 +866,37 @@ fn parse_declarations_and_nested_rules<'a, 'o, 'i, 't, T: AtRuleParser<'i>>(
   input: &mut Parser<'i, 't>,
   default_namespace: &'a Option<CowArcStr<'i>>,
   namespace_prefixes: &'a HashMap<CowArcStr<'i>, CowArcStr<'i>>,
+  values: &'a ModuleValues<'i>,
   options: &'a mut ParserOptions<'o, 'i, T>,
 ) -> Result<(DeclarationBlock<'i>, CssRuleList<'i, T::AtRule>), ParseError<'i, ParserError<'i>>> {
-  let mut important_declarations = DeclarationList::new();
-  let mut declarations = DeclarationList::new();
+  let mut declarations = DeclarationBlock::new();
   let mut rules = CssRuleList(vec![]);
   let mut parser = StyleRuleParser {
     default_namespace,
     namespace_prefixes,
+    values,
     options,
     declarations: &mut declarations,
-    important_declarations: &mut important_declarations,
     rules: &mut rules,
   };
 
+  // Preserved comments are attached to the declaration or nested rule that follows them.
+  let mut comments = Vec::new();
+  let mut pending_comments = Vec::new();
+
   // In the v2 nesting spec, declarations and nested rules may be mixed.
   // https://drafts.csswg.org/css-syntax/#consume-style-block
   loop {
     let start = input.state();
     match input.next_including_whitespace_and_comments() {
-      Ok(&Token::WhiteSpace(_)) | Ok(&Token::Comment(_)) | Ok(&Token::Semicolon) => continue,
+      Ok(&Token::Comment(text)) => {
+        if parser.options.preserve_comments.preserves(text) {
+          pending_comments.push(parser.options.comment(text, &start));
+        }
+      }
+      Ok(&Token::WhiteSpace(_)) | Ok(&Token::Semicolon) => continue,
       Ok(&Token::Ident(ref name)) => {
+        comments.append(&mut pending_comments);
         let name = name.clone();
         let callback = |input: &mut Parser<'i, '_>| {
           input.expect_colon()?;
@@ -783,6 +906,7 @@ fn parse_declarations_and_nested_rules<'a, 'o, 'i, 't, T: AtRuleParser<'i>>(
       }
       Ok(_) => {
         input.reset(&start);
+        parser.rules.0.extend(pending_comments.drain(..).map(CssRule::Comment));
         let mut iter = RuleListParser::new_for_nested_rule(input, parser);
         if let Some(result) = iter.next() {
           if let Err((err, _)) = result {
@@ -800,21 +924,20 @@ fn parse_declarations_and_nested_rules<'a, 'o, 'i, 't, T: AtRuleParser<'i>>(
     }
   }
 
-  Ok((
-    DeclarationBlock {
-      declarations,
-      important_declarations,
-    },
-    rules,
-  ))
+  comments.append(&mut pending_comments);
+  for comment in comments {
+    declarations.add_comment(comment);
+  }
+
+  Ok((declarations, rules))
 }
 
 pub struct StyleRuleParser<'a, 'o, 'i, T: AtRuleParser<'i>> {
   default_namespace: &'a Option<CowArcStr<'i>>,
   namespace_prefixes: &'a HashMap<CowArcStr<'i>, CowArcStr<'i>>,
+  values: &'a ModuleValues<'i>,
   options: &'a mut ParserOptions<'o, 'i, T>,
-  declarations: &'a mut DeclarationList<'i>,
-  important_declarations: &'a mut DeclarationList<'i>,
+  declarations: &'a mut DeclarationBlock<'i>,
   rules: &'a mut CssRuleList<'i, T::AtRule>,
 }
 
@@ -828,13 +951,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> cssparser::DeclarationParser<'i> for Style
     name: CowRcStr<'i>,
     input: &mut cssparser::Parser<'i, 't>,
   ) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
-    parse_declaration(
-      name,
-      input,
-      &mut self.declarations,
-      &mut self.important_declarations,
-      &self.options,
-    )
+    parse_declaration(name, input, self.declarations, self.options, Some(self.values))
   }
 }
 
@@ -850,7 +967,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for StyleRuleParser<'a, '
   ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
     match_ignore_ascii_case! { &*name,
       "media" => {
-        let media = MediaList::parse(input)?;
+        let media = self.values.parse_media_list(input)?;
         Ok(AtRulePrelude::Media(media))
       },
       "supports" => {
@@ -914,6 +1031,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for StyleRuleParser<'a, '
             self.options.source_index,
             self.default_namespace,
             self.namespace_prefixes,
+            self.values,
             self.options,
           )?,
           loc,
@@ -928,6 +1046,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for StyleRuleParser<'a, '
             self.options.source_index,
             self.default_namespace,
             self.namespace_prefixes,
+            self.values,
             self.options,
           )?,
           loc,
@@ -943,6 +1062,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for StyleRuleParser<'a, '
             self.options.source_index,
             self.default_namespace,
             self.namespace_prefixes,
+            self.values,
             self.options,
           )?,
           loc,
@@ -957,6 +1077,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for StyleRuleParser<'a, '
             self.options.source_index,
             self.default_namespace,
             self.namespace_prefixes,
+            self.values,
             self.options,
           )?,
           loc,
@@ -968,6 +1089,7 @@ impl<'a, 'o, 'i, T: AtRuleParser<'i>> AtRuleParser<'i> for StyleRuleParser<'a, '
           input,
           self.default_namespace,
           self.namespace_prefixes,
+          self.values,
           self.options,
         )?;
         self.rules.0.push(CssRule::Nesting(NestingRule {
@@ -1042,6 +1164,7 @@ fn parse_nested_at_rule<'a, 'o, 'i, 't, T: AtRuleParser<'i>>(
   source_index: u32,
   default_namespace: &'a Option<CowArcStr<'i>>,
   namespace_prefixes: &'a HashMap<CowArcStr<'i>, CowArcStr<'i>>,
+  values: &'a ModuleValues<'i>,
   options: &'a mut ParserOptions<'o, 'i, T>,
 ) -> Result<CssRuleList<'i, T::AtRule>, ParseError<'i, ParserError<'i>>> {
   let loc = input.current_source_location();
@@ -1054,7 +1177,7 @@ fn parse_nested_at_rule<'a, 'o, 'i, 't, T: AtRuleParser<'i>>(
   // Declarations can be immediately within @media and @supports blocks that are nested within a parent style rule.
   // These act the same way as if they were nested within a `& { ... }` block.
   let (declarations, mut rules) =
-    parse_declarations_and_nested_rules(input, default_namespace, namespace_prefixes, options)?;
+    parse_declarations_and_nested_rules(input, default_namespace, namespace_prefixes, values, options)?;
 
   if declarations.len() > 0 {
     rules.0.insert(
@@ -1097,8 +1220,13 @@ impl<'a, 'o, 'b, 'i, T: AtRuleParser<'i>> QualifiedRuleParser<'i> for StyleRuleP
     input: &mut Parser<'i, 't>,
   ) -> Result<(), ParseError<'i, Self::Error>> {
     let loc = start.source_location();
-    let (declarations, rules) =
-      parse_declarations_and_nested_rules(input, self.default_namespace, self.namespace_prefixes, self.options)?;
+    let (declarations, rules) = parse_declarations_and_nested_rules(
+      input,
+      self.default_namespace,
+      self.namespace_prefixes,
+      self.values,
+      self.options,
+    )?;
     self.rules.0.push(CssRule::Style(StyleRule {
       selectors,
       vendor_prefix: VendorPrefix::empty(),
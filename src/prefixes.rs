@@ -4,6 +4,7 @@ use crate::targets::Browsers;
 use crate::vendor_prefix::VendorPrefix;
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Feature {
   AlignContent,
   AlignItems,
@@ -193,6 +194,10 @@ pub enum Feature {
 
 impl Feature {
   pub fn prefixes_for(&self, browsers: Browsers) -> VendorPrefix {
+    if let Some(prefixes) = browsers.feature_data.and_then(|data| data.prefixes_for(*self, browsers)) {
+      return prefixes;
+    }
+
     let mut prefixes = VendorPrefix::None;
     match self {
       Feature::BorderRadius
@@ -211,7 +216,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 198144 {
+          if (131072..=198144).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -221,34 +226,34 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 262144 {
+          if (196864..=262144).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::BoxShadow => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 196608 {
+          if (131328..=196608).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 589824 {
+          if (262144..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 197888 && version <= 198144 {
+          if (197888..=198144).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 262656 {
+          if (197120..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 327680 {
+          if (196864..=327680).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -264,22 +269,22 @@ impl Feature {
       | Feature::AnimationTimingFunction
       | Feature::AtKeyframes => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 2752512 {
+          if (262144..=2752512).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 327680 && version <= 983040 {
+          if (327680..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 524544 {
+          if (197120..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -287,12 +292,12 @@ impl Feature {
           if version == 786432 {
             prefixes |= VendorPrefix::O;
           }
-          if version >= 983040 && version <= 1900544 {
+          if (983040..=1900544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 524288 {
+          if (262144..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -303,49 +308,49 @@ impl Feature {
       | Feature::TransitionDelay
       | Feature::TransitionTimingFunction => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 262656 {
+          if (131328..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 1638400 {
+          if (262144..=1638400).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 262144 && version <= 983040 {
+          if (262144..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 393216 {
+          if (197120..=393216).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 655360 && version <= 786432 {
+          if (655360..=786432).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 393216 {
+          if (196864..=393216).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Transform | Feature::TransformOrigin => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 2293760 {
+          if (262144..=2293760).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 197888 && version <= 983040 {
+          if (197888..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -355,84 +360,84 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 524544 {
+          if (197120..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 656640 && version <= 786432 {
+          if (656640..=786432).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
-          if version >= 983040 && version <= 1441792 {
+          if (983040..=1441792).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 524288 {
+          if (196864..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Perspective | Feature::PerspectiveOrigin | Feature::TransformStyle => {
         if let Some(version) = browsers.android {
-          if version >= 196608 && version <= 263171 {
+          if (196608..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 786432 && version <= 2293760 {
+          if (786432..=2293760).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 655360 && version <= 983040 {
+          if (655360..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 524544 {
+          if (197120..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 1441792 {
+          if (983040..=1441792).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 524288 {
+          if (262144..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::BackfaceVisibility => {
         if let Some(version) = browsers.android {
-          if version >= 196608 && version <= 263171 {
+          if (196608..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 786432 && version <= 2293760 {
+          if (786432..=2293760).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 655360 && version <= 983040 {
+          if (655360..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 983552 {
+          if (197120..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 1441792 {
+          if (983040..=1441792).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 983552 {
+          if (262144..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -442,98 +447,98 @@ impl Feature {
       | Feature::RadialGradient
       | Feature::RepeatingRadialGradient => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 262656 {
+          if (131328..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 1638400 {
+          if (262144..=1638400).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 198144 && version <= 983040 {
+          if (198144..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 393216 {
+          if (197120..=393216).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 721152 && version <= 786432 {
+          if (721152..=786432).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 393216 {
+          if (262144..=393216).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::BoxSizing => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 196608 {
+          if (131328..=196608).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 589824 {
+          if (262144..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 1835008 {
+          if (131072..=1835008).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 262656 {
+          if (197120..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 327680 {
+          if (196864..=327680).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Filter => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1179648 && version <= 3407872 {
+          if (1179648..=3407872).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 393216 && version <= 589824 {
+          if (393216..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2555904 {
+          if (983040..=2555904).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393216 && version <= 589824 {
+          if (393216..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 393728 {
+          if (262144..=393728).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::FilterFunction => {
         if let Some(version) = browsers.ios_saf {
-          if version >= 589824 && version <= 590592 {
+          if (589824..=590592).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -545,7 +550,7 @@ impl Feature {
       }
       Feature::BackdropFilter => {
         if let Some(version) = browsers.edge {
-          if version >= 1114112 && version <= 1179648 {
+          if (1114112..=1179648).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -578,32 +583,32 @@ impl Feature {
       | Feature::ColumnSpan
       | Feature::ColumnFill => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 3211264 {
+          if (262144..=3211264).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 3342336 {
+          if (131072..=3342336).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 524544 {
+          if (197120..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2359296 {
+          if (983040..=2359296).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 524288 {
+          if (196864..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -615,27 +620,27 @@ impl Feature {
       }
       Feature::BreakBefore | Feature::BreakAfter | Feature::BreakInside => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 3211264 {
+          if (262144..=3211264).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 524544 {
+          if (197120..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2359296 {
+          if (983040..=2359296).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 524288 {
+          if (196864..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -647,22 +652,22 @@ impl Feature {
       }
       Feature::UserSelect => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 3473408 {
+          if (262144..=3473408).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 4456448 {
+          if (131072..=4456448).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -677,7 +682,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2621440 {
+          if (983040..=2621440).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -687,7 +692,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 327680 {
+          if (262144..=327680).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -707,17 +712,17 @@ impl Feature {
       | Feature::AlignSelf
       | Feature::AlignContent => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 262656 {
+          if (131328..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 1835008 {
+          if (262144..=1835008).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 1376256 {
+          if (131072..=1376256).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -727,29 +732,29 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 524544 {
+          if (197120..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 1048576 {
+          if (983040..=1048576).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 524288 {
+          if (196864..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Calc => {
         if let Some(version) = browsers.chrome {
-          if version >= 1245184 && version <= 1638400 {
+          if (1245184..=1638400).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 262144 && version <= 983040 {
+          if (262144..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -766,7 +771,7 @@ impl Feature {
       }
       Feature::BackgroundOrigin | Feature::BackgroundSize => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 131840 {
+          if (131328..=131840).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -793,7 +798,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 917504 {
+          if (786432..=917504).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
           if version >= 5177344 {
@@ -801,7 +806,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 393216 && version <= 852992 {
+          if (393216..=852992).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -811,7 +816,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 852224 {
+          if (262144..=852224).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -823,22 +828,22 @@ impl Feature {
       }
       Feature::FontFeatureSettings | Feature::FontVariantLigatures | Feature::FontLanguageOverride => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1048576 && version <= 3080192 {
+          if (1048576..=3080192).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 262144 && version <= 2162688 {
+          if (262144..=2162688).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2228224 {
+          if (983040..=2228224).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -855,83 +860,83 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1900544 && version <= 2097152 {
+          if (1900544..=2097152).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 524288 && version <= 721664 {
+          if (524288..=721664).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 1048576 && version <= 1245184 {
+          if (1048576..=1245184).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 458752 && version <= 589824 {
+          if (458752..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::BorderImage => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 262656 {
+          if (131328..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 917504 {
+          if (262144..=917504).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 197888 && version <= 917504 {
+          if (197888..=917504).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 327680 {
+          if (197120..=327680).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 720896 && version <= 786688 {
+          if (720896..=786688).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 327936 {
+          if (196864..=327936).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::PseudoElementSelection => {
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 3997696 {
+          if (131072..=3997696).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
       }
       Feature::PseudoElementPlaceholder => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 3670016 {
+          if (262144..=3670016).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 1179648 && version <= 3276800 {
+          if (1179648..=3276800).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -941,29 +946,29 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 262656 && version <= 655360 {
+          if (262656..=655360).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2818048 {
+          if (983040..=2818048).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 327680 && version <= 655360 {
+          if (327680..=655360).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 393728 {
+          if (262144..=393728).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::PseudoClassPlaceholderShown => {
         if let Some(version) = browsers.firefox {
-          if version >= 262144 && version <= 3276800 {
+          if (262144..=3276800).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -975,12 +980,12 @@ impl Feature {
       }
       Feature::Hyphens => {
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 393216 && version <= 2752512 {
+          if (393216..=2752512).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -1002,12 +1007,12 @@ impl Feature {
       }
       Feature::PseudoClassFullscreen => {
         if let Some(version) = browsers.chrome {
-          if version >= 983040 && version <= 4587520 {
+          if (983040..=4587520).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 655360 && version <= 4128768 {
+          if (655360..=4128768).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -1017,7 +1022,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 4128768 {
+          if (983040..=4128768).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1027,49 +1032,49 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 590336 {
+          if (262144..=590336).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::PseudoElementBackdrop => {
         if let Some(version) = browsers.chrome {
-          if version >= 1310720 && version <= 4587520 {
+          if (1310720..=4587520).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 4128768 {
+          if (983040..=4128768).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393216 && version <= 983552 {
+          if (393216..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 590336 {
+          if (262144..=590336).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::PseudoElementFileSelectorButton => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 5767168 {
+          if (262144..=5767168).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
-          if version >= 5177344 && version <= 5767168 {
+          if (5177344..=5767168).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1079,54 +1084,54 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 917504 {
+          if (197120..=917504).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 4849664 {
+          if (983040..=4849664).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 917504 {
+          if (196864..=917504).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 917504 {
+          if (262144..=917504).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::PseudoClassAutofill => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 6225920 {
+          if (262144..=6225920).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 5177344 && version <= 6225920 {
+          if (5177344..=6225920).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 918784 {
+          if (197120..=918784).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 5308416 {
+          if (983040..=5308416).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 917760 {
+          if (196864..=917760).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1138,44 +1143,44 @@ impl Feature {
       }
       Feature::TabSize => {
         if let Some(version) = browsers.firefox {
-          if version >= 262144 && version <= 5898240 {
+          if (262144..=5898240).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 656896 && version <= 786688 {
+          if (656896..=786688).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
       }
       Feature::MaxContent | Feature::MinContent => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1441792 && version <= 2949120 {
+          if (1441792..=2949120).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 196608 && version <= 4259840 {
+          if (196608..=4259840).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 458752 && version <= 852992 {
+          if (458752..=852992).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2097152 {
+          if (983040..=2097152).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 655616 {
+          if (393472..=655616).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1202,12 +1207,12 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 196608 && version <= 4259840 {
+          if (196608..=4259840).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 458752 && version <= 852992 {
+          if (458752..=852992).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1217,7 +1222,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 655616 {
+          if (393472..=655616).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1234,27 +1239,27 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1441792 && version <= 2949120 {
+          if (1441792..=2949120).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 458752 && version <= 852992 {
+          if (458752..=852992).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2097152 {
+          if (983040..=2097152).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 655616 {
+          if (393472..=655616).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1308,56 +1313,56 @@ impl Feature {
       }
       Feature::ZoomIn | Feature::ZoomOut => {
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 2359296 {
+          if (262144..=2359296).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 1507328 {
+          if (131072..=1507328).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 1507328 {
+          if (983040..=1507328).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 524288 {
+          if (196864..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Grab | Feature::Grabbing => {
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 4390912 {
+          if (262144..=4390912).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 1703936 {
+          if (131072..=1703936).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 3538944 {
+          if (983040..=3538944).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 655616 {
+          if (196864..=655616).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Sticky => {
         if let Some(version) = browsers.ios_saf {
-          if version >= 393216 && version <= 786944 {
+          if (393216..=786944).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 786688 {
+          if (393472..=786688).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1374,12 +1379,12 @@ impl Feature {
       | Feature::TextDecorationLine
       | Feature::TextDecoration => {
         if let Some(version) = browsers.chrome {
-          if version >= 1703936 && version <= 3670016 {
+          if (1703936..=3670016).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 393216 && version <= 2293760 {
+          if (393216..=2293760).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -1389,12 +1394,12 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 2293760 && version <= 2818048 {
+          if (2293760..=2818048).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 459008 && version <= 786432 {
+          if (459008..=786432).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1406,7 +1411,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 459008 && version <= 786432 {
+          if (459008..=786432).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1418,7 +1423,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -1463,7 +1468,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 983552 {
+          if (197120..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1473,7 +1478,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 983552 {
+          if (262144..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1485,27 +1490,27 @@ impl Feature {
       }
       Feature::ClipPath => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1572864 && version <= 3538944 {
+          if (1572864..=3538944).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 458752 && version <= 589824 {
+          if (458752..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2686976 {
+          if (983040..=2686976).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 458752 && version <= 589824 {
+          if (458752..=589824).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1554,33 +1559,33 @@ impl Feature {
       }
       Feature::ObjectFit | Feature::ObjectPosition => {
         if let Some(version) = browsers.opera {
-          if version >= 656896 && version <= 786688 {
+          if (656896..=786688).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
       }
       Feature::ShapeMargin | Feature::ShapeOutside | Feature::ShapeImageThreshold => {
         if let Some(version) = browsers.ios_saf {
-          if version >= 524288 && version <= 655360 {
+          if (524288..=655360).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 459008 && version <= 655360 {
+          if (459008..=655360).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::TextOverflow => {
         if let Some(version) = browsers.opera {
-          if version >= 589824 && version <= 786432 {
+          if (589824..=786432).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
       }
       Feature::AtViewport => {
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -1590,63 +1595,63 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 720896 && version <= 786688 {
+          if (720896..=786688).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
       }
       Feature::AtResolution => {
         if let Some(version) = browsers.android {
-          if version >= 131840 && version <= 262656 {
+          if (131840..=262656).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 1835008 {
+          if (262144..=1835008).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 197888 && version <= 983040 {
+          if (197888..=983040).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 262144 && version <= 984320 {
+          if (262144..=984320).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 591104 && version <= 786432 {
+          if (591104..=786432).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 262144 && version <= 984576 {
+          if (262144..=984576).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::TextAlignLast => {
         if let Some(version) = browsers.firefox {
-          if version >= 786432 && version <= 3145728 {
+          if (786432..=3145728).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
       }
       Feature::Pixelated => {
         if let Some(version) = browsers.firefox {
-          if version >= 198144 && version <= 6619136 {
+          if (198144..=6619136).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 327680 && version <= 393216 {
+          if (327680..=393216).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 722432 && version <= 786688 {
+          if (722432..=786688).contains(&version) {
             prefixes |= VendorPrefix::O;
           }
         }
@@ -1670,37 +1675,37 @@ impl Feature {
       | Feature::PaddingInlineStart
       | Feature::PaddingInlineEnd => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 4456448 {
+          if (262144..=4456448).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 196608 && version <= 2621440 {
+          if (196608..=2621440).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 786432 {
+          if (197120..=786432).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 3604480 {
+          if (983040..=3604480).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 786432 {
+          if (196864..=786432).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 590336 {
+          if (262144..=590336).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1712,57 +1717,57 @@ impl Feature {
       | Feature::PaddingBlockStart
       | Feature::PaddingBlockEnd => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 4456448 {
+          if (262144..=4456448).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 786432 {
+          if (197120..=786432).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 3604480 {
+          if (983040..=3604480).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 786432 {
+          if (196864..=786432).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 262144 && version <= 590336 {
+          if (262144..=590336).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Appearance => {
         if let Some(version) = browsers.android {
-          if version >= 131328 && version <= 263171 {
+          if (131328..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 262144 && version <= 5439488 {
+          if (262144..=5439488).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
-          if version >= 5177344 && version <= 5439488 {
+          if (5177344..=5439488).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 131072 && version <= 5177344 {
+          if (131072..=5177344).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -1772,17 +1777,17 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 197120 && version <= 983552 {
+          if (197120..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 4718592 {
+          if (983040..=4718592).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 196864 && version <= 983552 {
+          if (196864..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1798,7 +1803,7 @@ impl Feature {
       | Feature::ScrollSnapPointsX
       | Feature::ScrollSnapPointsY => {
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -1808,24 +1813,24 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 589824 && version <= 656128 {
+          if (589824..=656128).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 589824 && version <= 655616 {
+          if (589824..=655616).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::FlowInto | Feature::FlowFrom | Feature::RegionFragment => {
         if let Some(version) = browsers.chrome {
-          if version >= 983040 && version <= 1179648 {
+          if (983040..=1179648).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -1835,12 +1840,12 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 458752 && version <= 720896 {
+          if (458752..=720896).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 720896 {
+          if (393472..=720896).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1862,7 +1867,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 393216 && version <= 590592 {
+          if (393216..=590592).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1872,7 +1877,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393216 && version <= 590080 {
+          if (393216..=590080).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1884,12 +1889,12 @@ impl Feature {
       }
       Feature::WritingMode => {
         if let Some(version) = browsers.android {
-          if version >= 196608 && version <= 263171 {
+          if (196608..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 524288 && version <= 3080192 {
+          if (524288..=3080192).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1899,17 +1904,17 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 327680 && version <= 656128 {
+          if (327680..=656128).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 2228224 {
+          if (983040..=2228224).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 327936 && version <= 655616 {
+          if (327936..=655616).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1936,7 +1941,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 327680 && version <= 590592 {
+          if (327680..=590592).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1946,7 +1951,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 327936 && version <= 590080 {
+          if (327936..=590080).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -1958,7 +1963,7 @@ impl Feature {
       }
       Feature::PseudoClassReadOnly | Feature::PseudoClassReadWrite => {
         if let Some(version) = browsers.firefox {
-          if version >= 196608 && version <= 5046272 {
+          if (196608..=5046272).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -1968,27 +1973,27 @@ impl Feature {
       | Feature::TextEmphasisStyle
       | Feature::TextEmphasisColor => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 1638400 && version <= 6422528 {
+          if (1638400..=6422528).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 5177344 && version <= 6422528 {
+          if (5177344..=6422528).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 5570560 {
+          if (983040..=5570560).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 458752 {
+          if (393472..=458752).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -2015,7 +2020,7 @@ impl Feature {
       | Feature::GridColumnAlign
       | Feature::GridRowAlign => {
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 983040 {
+          if (786432..=983040).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -2027,7 +2032,7 @@ impl Feature {
       }
       Feature::TextSpacing => {
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1179648 {
+          if (786432..=1179648).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -2039,49 +2044,49 @@ impl Feature {
       }
       Feature::PseudoClassAnyLink => {
         if let Some(version) = browsers.android {
-          if version >= 263168 && version <= 263171 {
+          if (263168..=263171).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.chrome {
-          if version >= 983040 && version <= 4194304 {
+          if (983040..=4194304).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 196608 && version <= 3211264 {
+          if (196608..=3211264).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 393216 && version <= 524544 {
+          if (393216..=524544).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 983040 && version <= 3342336 {
+          if (983040..=3342336).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393472 && version <= 524288 {
+          if (393472..=524288).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 327680 && version <= 524800 {
+          if (327680..=524800).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
       }
       Feature::Isolate => {
         if let Some(version) = browsers.chrome {
-          if version >= 1114112 && version <= 3080192 {
+          if (1114112..=3080192).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 655360 && version <= 3211264 {
+          if (655360..=3211264).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
@@ -2098,21 +2103,21 @@ impl Feature {
       }
       Feature::Plaintext => {
         if let Some(version) = browsers.firefox {
-          if version >= 655360 && version <= 3211264 {
+          if (655360..=3211264).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
       }
       Feature::IsolateOverride => {
         if let Some(version) = browsers.firefox {
-          if version >= 1114112 && version <= 3211264 {
+          if (1114112..=3211264).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
       }
       Feature::OverscrollBehavior => {
         if let Some(version) = browsers.edge {
-          if version >= 786432 && version <= 1114112 {
+          if (786432..=1114112).contains(&version) {
             prefixes |= VendorPrefix::Ms;
           }
         }
@@ -2124,7 +2129,7 @@ impl Feature {
       }
       Feature::TextOrientation => {
         if let Some(version) = browsers.safari {
-          if version >= 655616 && version <= 852224 {
+          if (655616..=852224).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -2146,12 +2151,12 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 3145728 && version <= 6291456 {
+          if (3145728..=6291456).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 393216 && version <= 983552 {
+          if (393216..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -2161,7 +2166,7 @@ impl Feature {
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 393216 && version <= 983552 {
+          if (393216..=983552).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -2173,42 +2178,42 @@ impl Feature {
       }
       Feature::AnyPseudo => {
         if let Some(version) = browsers.chrome {
-          if version >= 786432 && version <= 5701632 {
+          if (786432..=5701632).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.edge {
-          if version >= 5177344 && version <= 5701632 {
+          if (5177344..=5701632).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.firefox {
-          if version >= 262144 && version <= 5111808 {
+          if (262144..=5111808).contains(&version) {
             prefixes |= VendorPrefix::Moz;
           }
         }
         if let Some(version) = browsers.opera {
-          if version >= 917504 && version <= 4784128 {
+          if (917504..=4784128).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.safari {
-          if version >= 327680 && version <= 851968 {
+          if (327680..=851968).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.ios_saf {
-          if version >= 327680 && version <= 851968 {
+          if (327680..=851968).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.samsung {
-          if version >= 65536 && version <= 917504 {
+          if (65536..=917504).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
         if let Some(version) = browsers.android {
-          if version >= 2424832 && version <= 5701632 {
+          if (2424832..=5701632).contains(&version) {
             prefixes |= VendorPrefix::WebKit;
           }
         }
@@ -2216,26 +2221,216 @@ impl Feature {
     }
     prefixes
   }
+
+  pub fn from_name(name: &str) -> Option<Feature> {
+    Some(match name {
+      "align-content" => Feature::AlignContent,
+      "align-items" => Feature::AlignItems,
+      "align-self" => Feature::AlignSelf,
+      "animation" => Feature::Animation,
+      "animation-delay" => Feature::AnimationDelay,
+      "animation-direction" => Feature::AnimationDirection,
+      "animation-duration" => Feature::AnimationDuration,
+      "animation-fill-mode" => Feature::AnimationFillMode,
+      "animation-iteration-count" => Feature::AnimationIterationCount,
+      "animation-name" => Feature::AnimationName,
+      "animation-play-state" => Feature::AnimationPlayState,
+      "animation-timing-function" => Feature::AnimationTimingFunction,
+      "any-pseudo" => Feature::AnyPseudo,
+      "appearance" => Feature::Appearance,
+      "@keyframes" => Feature::AtKeyframes,
+      "@resolution" => Feature::AtResolution,
+      "@viewport" => Feature::AtViewport,
+      "backdrop-filter" => Feature::BackdropFilter,
+      "backface-visibility" => Feature::BackfaceVisibility,
+      "background-clip" => Feature::BackgroundClip,
+      "background-origin" => Feature::BackgroundOrigin,
+      "background-size" => Feature::BackgroundSize,
+      "border-block-end" => Feature::BorderBlockEnd,
+      "border-block-start" => Feature::BorderBlockStart,
+      "border-bottom-left-radius" => Feature::BorderBottomLeftRadius,
+      "border-bottom-right-radius" => Feature::BorderBottomRightRadius,
+      "border-image" => Feature::BorderImage,
+      "border-inline-end" => Feature::BorderInlineEnd,
+      "border-inline-start" => Feature::BorderInlineStart,
+      "border-radius" => Feature::BorderRadius,
+      "border-top-left-radius" => Feature::BorderTopLeftRadius,
+      "border-top-right-radius" => Feature::BorderTopRightRadius,
+      "box-decoration-break" => Feature::BoxDecorationBreak,
+      "box-shadow" => Feature::BoxShadow,
+      "box-sizing" => Feature::BoxSizing,
+      "break-after" => Feature::BreakAfter,
+      "break-before" => Feature::BreakBefore,
+      "break-inside" => Feature::BreakInside,
+      "calc" => Feature::Calc,
+      "clip-path" => Feature::ClipPath,
+      "color-adjust" => Feature::ColorAdjust,
+      "column-count" => Feature::ColumnCount,
+      "column-fill" => Feature::ColumnFill,
+      "column-gap" => Feature::ColumnGap,
+      "column-rule" => Feature::ColumnRule,
+      "column-rule-color" => Feature::ColumnRuleColor,
+      "column-rule-style" => Feature::ColumnRuleStyle,
+      "column-rule-width" => Feature::ColumnRuleWidth,
+      "column-span" => Feature::ColumnSpan,
+      "column-width" => Feature::ColumnWidth,
+      "columns" => Feature::Columns,
+      "cross-fade" => Feature::CrossFade,
+      "display-flex" => Feature::DisplayFlex,
+      "display-grid" => Feature::DisplayGrid,
+      "element" => Feature::Element,
+      "fill" => Feature::Fill,
+      "fill-available" => Feature::FillAvailable,
+      "filter" => Feature::Filter,
+      "filter-function" => Feature::FilterFunction,
+      "fit-content" => Feature::FitContent,
+      "flex" => Feature::Flex,
+      "flex-basis" => Feature::FlexBasis,
+      "flex-direction" => Feature::FlexDirection,
+      "flex-flow" => Feature::FlexFlow,
+      "flex-grow" => Feature::FlexGrow,
+      "flex-shrink" => Feature::FlexShrink,
+      "flex-wrap" => Feature::FlexWrap,
+      "flow-from" => Feature::FlowFrom,
+      "flow-into" => Feature::FlowInto,
+      "font-feature-settings" => Feature::FontFeatureSettings,
+      "font-kerning" => Feature::FontKerning,
+      "font-language-override" => Feature::FontLanguageOverride,
+      "font-variant-ligatures" => Feature::FontVariantLigatures,
+      "grab" => Feature::Grab,
+      "grabbing" => Feature::Grabbing,
+      "grid-area" => Feature::GridArea,
+      "grid-column" => Feature::GridColumn,
+      "grid-column-align" => Feature::GridColumnAlign,
+      "grid-column-end" => Feature::GridColumnEnd,
+      "grid-column-start" => Feature::GridColumnStart,
+      "grid-row" => Feature::GridRow,
+      "grid-row-align" => Feature::GridRowAlign,
+      "grid-row-end" => Feature::GridRowEnd,
+      "grid-row-start" => Feature::GridRowStart,
+      "grid-template" => Feature::GridTemplate,
+      "grid-template-areas" => Feature::GridTemplateAreas,
+      "grid-template-columns" => Feature::GridTemplateColumns,
+      "grid-template-rows" => Feature::GridTemplateRows,
+      "hyphens" => Feature::Hyphens,
+      "image-rendering" => Feature::ImageRendering,
+      "image-set" => Feature::ImageSet,
+      "inline-flex" => Feature::InlineFlex,
+      "inline-grid" => Feature::InlineGrid,
+      "isolate" => Feature::Isolate,
+      "isolate-override" => Feature::IsolateOverride,
+      "justify-content" => Feature::JustifyContent,
+      "linear-gradient" => Feature::LinearGradient,
+      "margin-block-end" => Feature::MarginBlockEnd,
+      "margin-block-start" => Feature::MarginBlockStart,
+      "margin-inline-end" => Feature::MarginInlineEnd,
+      "margin-inline-start" => Feature::MarginInlineStart,
+      "mask" => Feature::Mask,
+      "mask-border" => Feature::MaskBorder,
+      "mask-border-outset" => Feature::MaskBorderOutset,
+      "mask-border-repeat" => Feature::MaskBorderRepeat,
+      "mask-border-slice" => Feature::MaskBorderSlice,
+      "mask-border-source" => Feature::MaskBorderSource,
+      "mask-border-width" => Feature::MaskBorderWidth,
+      "mask-clip" => Feature::MaskClip,
+      "mask-composite" => Feature::MaskComposite,
+      "mask-image" => Feature::MaskImage,
+      "mask-origin" => Feature::MaskOrigin,
+      "mask-position" => Feature::MaskPosition,
+      "mask-repeat" => Feature::MaskRepeat,
+      "mask-size" => Feature::MaskSize,
+      "max-content" => Feature::MaxContent,
+      "min-content" => Feature::MinContent,
+      "object-fit" => Feature::ObjectFit,
+      "object-position" => Feature::ObjectPosition,
+      "order" => Feature::Order,
+      "overscroll-behavior" => Feature::OverscrollBehavior,
+      "padding-block-end" => Feature::PaddingBlockEnd,
+      "padding-block-start" => Feature::PaddingBlockStart,
+      "padding-inline-end" => Feature::PaddingInlineEnd,
+      "padding-inline-start" => Feature::PaddingInlineStart,
+      "perspective" => Feature::Perspective,
+      "perspective-origin" => Feature::PerspectiveOrigin,
+      "pixelated" => Feature::Pixelated,
+      "place-self" => Feature::PlaceSelf,
+      "plaintext" => Feature::Plaintext,
+      "print-color-adjust" => Feature::PrintColorAdjust,
+      ":any-link" => Feature::PseudoClassAnyLink,
+      ":autofill" => Feature::PseudoClassAutofill,
+      ":fullscreen" => Feature::PseudoClassFullscreen,
+      ":placeholder-shown" => Feature::PseudoClassPlaceholderShown,
+      ":read-only" => Feature::PseudoClassReadOnly,
+      ":read-write" => Feature::PseudoClassReadWrite,
+      "::backdrop" => Feature::PseudoElementBackdrop,
+      "::file-selector-button" => Feature::PseudoElementFileSelectorButton,
+      "::placeholder" => Feature::PseudoElementPlaceholder,
+      "::selection" => Feature::PseudoElementSelection,
+      "radial-gradient" => Feature::RadialGradient,
+      "region-fragment" => Feature::RegionFragment,
+      "repeating-linear-gradient" => Feature::RepeatingLinearGradient,
+      "repeating-radial-gradient" => Feature::RepeatingRadialGradient,
+      "scroll-snap-coordinate" => Feature::ScrollSnapCoordinate,
+      "scroll-snap-destination" => Feature::ScrollSnapDestination,
+      "scroll-snap-points-x" => Feature::ScrollSnapPointsX,
+      "scroll-snap-points-y" => Feature::ScrollSnapPointsY,
+      "scroll-snap-type" => Feature::ScrollSnapType,
+      "shape-image-threshold" => Feature::ShapeImageThreshold,
+      "shape-margin" => Feature::ShapeMargin,
+      "shape-outside" => Feature::ShapeOutside,
+      "sticky" => Feature::Sticky,
+      "stretch" => Feature::Stretch,
+      "tab-size" => Feature::TabSize,
+      "text-align-last" => Feature::TextAlignLast,
+      "text-decoration" => Feature::TextDecoration,
+      "text-decoration-color" => Feature::TextDecorationColor,
+      "text-decoration-line" => Feature::TextDecorationLine,
+      "text-decoration-skip" => Feature::TextDecorationSkip,
+      "text-decoration-skip-ink" => Feature::TextDecorationSkipInk,
+      "text-decoration-style" => Feature::TextDecorationStyle,
+      "text-emphasis" => Feature::TextEmphasis,
+      "text-emphasis-color" => Feature::TextEmphasisColor,
+      "text-emphasis-position" => Feature::TextEmphasisPosition,
+      "text-emphasis-style" => Feature::TextEmphasisStyle,
+      "text-orientation" => Feature::TextOrientation,
+      "text-overflow" => Feature::TextOverflow,
+      "text-size-adjust" => Feature::TextSizeAdjust,
+      "text-spacing" => Feature::TextSpacing,
+      "touch-action" => Feature::TouchAction,
+      "transform" => Feature::Transform,
+      "transform-origin" => Feature::TransformOrigin,
+      "transform-style" => Feature::TransformStyle,
+      "transition" => Feature::Transition,
+      "transition-delay" => Feature::TransitionDelay,
+      "transition-duration" => Feature::TransitionDuration,
+      "transition-property" => Feature::TransitionProperty,
+      "transition-timing-function" => Feature::TransitionTimingFunction,
+      "user-select" => Feature::UserSelect,
+      "writing-mode" => Feature::WritingMode,
+      "zoom-in" => Feature::ZoomIn,
+      "zoom-out" => Feature::ZoomOut,
+      _ => return None,
+    })
+  }
 }
 
 pub fn is_flex_2009(browsers: Browsers) -> bool {
   if let Some(version) = browsers.android {
-    if version >= 131328 && version <= 262656 {
+    if (131328..=262656).contains(&version) {
       return true;
     }
   }
   if let Some(version) = browsers.chrome {
-    if version >= 262144 && version <= 1310720 {
+    if (262144..=1310720).contains(&version) {
       return true;
     }
   }
   if let Some(version) = browsers.ios_saf {
-    if version >= 197120 && version <= 393216 {
+    if (197120..=393216).contains(&version) {
       return true;
     }
   }
   if let Some(version) = browsers.safari {
-    if version >= 196864 && version <= 393216 {
+    if (196864..=393216).contains(&version) {
       return true;
     }
   }
@@ -2244,22 +2439,22 @@ pub fn is_flex_2009(browsers: Browsers) -> bool {
 
 pub fn is_webkit_gradient(browsers: Browsers) -> bool {
   if let Some(version) = browsers.android {
-    if version >= 131328 && version <= 196608 {
+    if (131328..=196608).contains(&version) {
       return true;
     }
   }
   if let Some(version) = browsers.chrome {
-    if version >= 262144 && version <= 589824 {
+    if (262144..=589824).contains(&version) {
       return true;
     }
   }
   if let Some(version) = browsers.ios_saf {
-    if version >= 197120 && version <= 393216 {
+    if (197120..=393216).contains(&version) {
       return true;
     }
   }
   if let Some(version) = browsers.safari {
-    if version >= 262144 && version <= 393216 {
+    if (262144..=393216).contains(&version) {
       return true;
     }
   }
@@ -1,14 +1,20 @@
 //! CSS serialization and source map generation.
 
-use crate::css_modules::CssModule;
+use crate::css_modules::{CssModule, NameKind};
 use crate::dependencies::{Dependency, DependencyOptions};
 use crate::error::{Error, ErrorLocation, PrinterError, PrinterErrorKind};
 use crate::rules::Location;
+#[cfg(feature = "sourcemap")]
+use crate::sourcemap::SourceMapOptions;
 use crate::targets::Browsers;
+use crate::values::url::is_absolute_url;
 use crate::vendor_prefix::VendorPrefix;
-use cssparser::{serialize_identifier, serialize_name};
+use cssparser::{serialize_identifier, serialize_name, serialize_string};
 #[cfg(feature = "sourcemap")]
 use parcel_sourcemap::{OriginalLocation, SourceMap};
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
 
 /// Options that control how CSS is serialized to a string.
 #[derive(Default)]
@@ -18,6 +24,9 @@ pub struct PrinterOptions<'a> {
   /// An optional reference to a source map to write mappings into.
   #[cfg(feature = "sourcemap")]
   pub source_map: Option<&'a mut SourceMap>,
+  /// Options that control how the source map is written, if one is provided.
+  #[cfg(feature = "sourcemap")]
+  pub source_map_options: SourceMapOptions<'a>,
   /// An optional project root path, used to generate relative paths for sources used in CSS module hashes.
   pub project_root: Option<&'a str>,
   /// Browser targets to output the CSS for.
@@ -33,12 +42,304 @@ pub struct PrinterOptions<'a> {
   /// A mapping of pseudo classes to replace with class names that can be applied
   /// from JavaScript. Useful for polyfills, for example.
   pub pseudo_classes: Option<PseudoClasses<'a>>,
+  /// Whether to preserve position keywords such as `left` and `top` rather than
+  /// converting them to percentages, e.g. in `background-position` and `transform-origin`.
+  pub preserve_position_keywords: bool,
+  /// Whether to print time, angle, and resolution values using whichever equivalent
+  /// unit is shortest when minifying, e.g. `.25turn` instead of `90deg`.
+  pub shortest_units: bool,
+  /// The maximum number of decimal places to print for numeric values.
+  pub precision: Precision,
+  /// How to choose between equivalent serializations of values, to improve compression of the output.
+  pub compression: Compression,
+  /// Options for rebasing and rewriting the urls of `url()` references and `@import` rules.
+  pub urls: UrlOptions<'a>,
+  /// Text to print at the start of the output, e.g. a license header. It is printed as is, and
+  /// should therefore be a valid CSS comment.
+  pub banner: Option<&'a str>,
+  /// Text to print at the end of the output. It is printed as is, like the `banner`.
+  pub footer: Option<&'a str>,
+  /// Whether to print a `/* source: path.css */` comment before the rules from each source file,
+  /// e.g. to debug a bundle. This only applies to style sheets, and is ignored when minifying.
+  pub source_comments: bool,
+  /// Whether to append the CSS module exports and references to the output as ICSS `:import` and
+  /// `:export` rules, so the output can be consumed by css-loader and other tools that exchange module
+  /// metadata via [ICSS](https://github.com/css-modules/icss). See [to_icss](crate::css_modules::to_icss).
+  pub icss: bool,
+}
+
+/// Options for rebasing and rewriting the urls of `url()` references, e.g. in `@font-face` sources,
+/// images, and cursors, and of `@import` rules. Urls replaced with placeholders by the
+/// `analyze_dependencies` option are not affected.
+///
+/// The source file of each url is determined from the source locations of the rules, so this
+/// requires the `sourcemap` feature. This is useful when bundling, where the urls in imported
+/// files are relative to those files rather than to the output.
+///
+/// See [PrinterOptions](PrinterOptions).
+#[derive(Default, Clone, Copy)]
+pub struct UrlOptions<'a> {
+  /// The path of the output file. When set, relative urls are rebased from the directory of the source
+  /// file they appear in to the directory of the output file, e.g. `../img/a.png` in `src/css/a.css`
+  /// becomes `../src/img/a.png` in `dist/a.css`.
+  pub output_path: Option<&'a Path>,
+  /// A callback to rewrite each url after it is rebased, e.g. to add a CDN prefix or a cache-busting
+  /// hash. Returning `None` keeps the url unchanged.
+  pub rewrite: Option<&'a UrlRewriter<'a>>,
+}
+
+/// A callback that rewrites a url, as used by [UrlOptions::rewrite].
+pub type UrlRewriter<'a> = dyn Fn(&UrlReference) -> Option<String> + Send + Sync + 'a;
+
+/// The kind of reference a url appears in, as passed to [UrlOptions::rewrite].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UrlKind {
+  /// A `url()` or `image-set()` reference.
+  Url,
+  /// The url of an `@import` rule.
+  Import,
+}
+
+/// A url passed to the [UrlOptions::rewrite] callback.
+#[derive(Debug)]
+pub struct UrlReference<'a> {
+  /// The url, after rebasing.
+  pub url: &'a str,
+  /// The original url, as written in the source.
+  pub original: &'a str,
+  /// The kind of reference the url appears in.
+  pub kind: UrlKind,
+  /// The filename of the source file the url appears in.
+  pub filename: &'a str,
+}
+
+/// Rebases a relative url from a source file to an output file, keeping any query and fragment.
+fn rebase_url(url: &str, filename: &str, output_path: &Path) -> Option<String> {
+  if url.is_empty() || is_absolute_url(url) {
+    return None;
+  }
+
+  let end = url.find(['?', '#']).unwrap_or(url.len());
+  let (path, suffix) = url.split_at(end);
+  let from = normalize_path(&Path::new(filename).parent()?.join(path));
+  let to = normalize_path(output_path.parent()?);
+  let relative = pathdiff::diff_paths(from, to)?;
+
+  let mut rebased = relative
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/");
+  if path.ends_with('/') {
+    rebased.push('/');
+  }
+  rebased.push_str(suffix);
+  Some(rebased)
+}
+
+/// Removes `.` components from a path, and resolves `..` components where possible.
+fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+        normalized.pop();
+      }
+      component => normalized.push(component),
+    }
+  }
+  normalized
+}
+
+/// Determines how the printer chooses between equivalent serializations of values, such as a named
+/// color and its hex form, or the quotes around a string. The shortest form of each value is not
+/// always the one that compresses best with gzip or brotli, since repeating the same form across the
+/// whole style sheet produces longer matches and a smaller alphabet.
+///
+/// See [PrinterOptions](PrinterOptions).
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "lowercase")
+)]
+pub enum Compression {
+  /// Print the shortest form of each value, and strings with double quotes.
+  #[default]
+  None,
+  /// Print each kind of value in a consistent form across the style sheet, even when it is slightly
+  /// longer: colors are always printed as hex rather than as names, e.g. `#f00` instead of `red`.
+  Consistent,
+  /// Serialize the style sheet with each combination of choices, i.e. named or hex colors and double
+  /// or single quotes, and keep the one with the smallest compressed size according to a fast estimate.
+  /// This is only supported when printing a whole style sheet, and otherwise behaves like `None`.
+  Estimate,
+}
+
+/// The serialization choices made by the [Compression](Compression) option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SerializationChoices {
+  /// Whether to print colors as hex rather than as names.
+  pub hex_colors: bool,
+  /// The quote character to print strings with.
+  pub quote: char,
+}
+
+impl SerializationChoices {
+  /// The candidates tried by [Compression::Estimate], in order of preference.
+  pub const CANDIDATES: [SerializationChoices; 4] = [
+    SerializationChoices {
+      hex_colors: false,
+      quote: '"',
+    },
+    SerializationChoices {
+      hex_colors: true,
+      quote: '"',
+    },
+    SerializationChoices {
+      hex_colors: false,
+      quote: '\'',
+    },
+    SerializationChoices {
+      hex_colors: true,
+      quote: '\'',
+    },
+  ];
+}
+
+impl Compression {
+  /// Returns the choices for this option, or `None` if they must be estimated from the output.
+  pub(crate) fn choices(&self) -> Option<SerializationChoices> {
+    match self {
+      Compression::None => Some(SerializationChoices::CANDIDATES[0]),
+      Compression::Consistent => Some(SerializationChoices::CANDIDATES[1]),
+      Compression::Estimate => None,
+    }
+  }
+}
+
+/// Estimates the size of the given output after compression, in bytes. This performs a greedy LZ77
+/// parse similar to deflate, and adds the order-0 entropy of the remaining literals to a fixed cost
+/// per match. The estimate is only meant to compare serializations of the same style sheet.
+pub(crate) fn estimate_compressed_size(s: &str) -> usize {
+  const MIN_MATCH: usize = 4;
+  const MAX_MATCH: usize = 258;
+  const WINDOW: usize = 32768;
+  const MATCH_BITS: f64 = 24.0;
+
+  let bytes = s.as_bytes();
+  let hash = |i: usize| {
+    let v = u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+    (v.wrapping_mul(2654435761) >> 16) as usize
+  };
+
+  let mut table = vec![usize::MAX; 1 << 16];
+  let mut counts = [0usize; 256];
+  let mut literals = 0;
+  let mut matches = 0;
+  let mut i = 0;
+  while i < bytes.len() {
+    if i + MIN_MATCH <= bytes.len() {
+      let h = hash(i);
+      let candidate = table[h];
+      table[h] = i;
+      if candidate != usize::MAX && i - candidate <= WINDOW {
+        let len = bytes[candidate..]
+          .iter()
+          .zip(&bytes[i..])
+          .take(MAX_MATCH)
+          .take_while(|(a, b)| a == b)
+          .count();
+        if len >= MIN_MATCH {
+          matches += 1;
+          i += len;
+          continue;
+        }
+      }
+    }
+
+    counts[bytes[i] as usize] += 1;
+    literals += 1;
+    i += 1;
+  }
+
+  let entropy: f64 = counts
+    .iter()
+    .filter(|count| **count > 0)
+    .map(|count| {
+      let p = *count as f64 / literals as f64;
+      -p * p.log2()
+    })
+    .sum();
+  ((entropy * literals as f64 + MATCH_BITS * matches as f64) / 8.0).ceil() as usize
+}
+
+/// The maximum number of decimal places to print for numeric values, e.g. percentages computed
+/// from aspect ratios or the results of `calc()` expressions. Values are rounded when serialized,
+/// and are printed with full precision when no limit is set.
+///
+/// See [PrinterOptions](PrinterOptions).
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize, serde::Deserialize),
+  serde(default, rename_all = "camelCase")
+)]
+pub struct Precision {
+  /// The precision for all values without a more specific setting below.
+  pub default: Option<u8>,
+  /// The precision for unitless numbers.
+  pub number: Option<u8>,
+  /// The precision for lengths.
+  pub length: Option<u8>,
+  /// The precision for percentages, e.g. `2` for `33.33%`.
+  pub percentage: Option<u8>,
+  /// The precision for angles.
+  pub angle: Option<u8>,
+  /// The precision for times.
+  pub time: Option<u8>,
+  /// The precision for resolutions.
+  pub resolution: Option<u8>,
+}
+
+/// The type of a numeric value, used to look up its [Precision](Precision).
+#[derive(Clone, Copy)]
+pub(crate) enum NumericKind {
+  Number,
+  Length,
+  Percentage,
+  Angle,
+  Time,
+  Resolution,
+}
+
+impl Precision {
+  /// Rounds the value to the precision for the given kind of value.
+  pub(crate) fn round(&self, value: f32, kind: NumericKind) -> f32 {
+    let places = match kind {
+      NumericKind::Number => self.number,
+      NumericKind::Length => self.length,
+      NumericKind::Percentage => self.percentage,
+      NumericKind::Angle => self.angle,
+      NumericKind::Time => self.time,
+      NumericKind::Resolution => self.resolution,
+    };
+
+    match places.or(self.default) {
+      Some(places) if value.is_finite() => {
+        let factor = 10f64.powi(places as i32);
+        ((value as f64 * factor).round() / factor) as f32
+      }
+      _ => value,
+    }
+  }
 }
 
 /// A mapping of user action pseudo classes to replace with class names.
 ///
 /// See [PrinterOptions](PrinterOptions).
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct PseudoClasses<'a> {
   /// The class name to replace `:hover` with.
   pub hover: Option<&'a str>,
@@ -82,6 +383,14 @@ pub struct Printer<'a, 'b, 'c, W> {
   pub(crate) dependencies: Option<Vec<Dependency>>,
   pub(crate) remove_imports: bool,
   pub(crate) pseudo_classes: Option<PseudoClasses<'a>>,
+  pub(crate) preserve_position_keywords: bool,
+  pub(crate) shortest_units: bool,
+  pub(crate) precision: Precision,
+  pub(crate) choices: SerializationChoices,
+  pub(crate) urls: UrlOptions<'a>,
+  pub(crate) source_comments: bool,
+  /// The source index of the last `/* source */` comment that was printed.
+  pub(crate) comment_source_index: Option<u32>,
 }
 
 impl<'a, 'b, 'c, W: std::fmt::Write + Sized> Printer<'a, 'b, 'c, W> {
@@ -114,6 +423,13 @@ impl<'a, 'b, 'c, W: std::fmt::Write + Sized> Printer<'a, 'b, 'c, W> {
       },
       remove_imports: matches!(&options.analyze_dependencies, Some(d) if d.remove_imports),
       pseudo_classes: options.pseudo_classes,
+      preserve_position_keywords: options.preserve_position_keywords,
+      shortest_units: options.shortest_units,
+      precision: options.precision,
+      choices: options.compression.choices().unwrap_or(SerializationChoices::CANDIDATES[0]),
+      urls: options.urls,
+      source_comments: options.source_comments && !options.minify,
+      comment_source_index: None,
     }
   }
 
@@ -140,6 +456,87 @@ impl<'a, 'b, 'c, W: std::fmt::Write + Sized> Printer<'a, 'b, 'c, W> {
     Ok(())
   }
 
+  /// Writes a raw string that may contain newline characters, e.g. a banner, to the underlying destination.
+  pub(crate) fn write_raw(&mut self, s: &str) -> Result<(), PrinterError> {
+    match s.rfind('\n') {
+      Some(index) => {
+        self.line += s.matches('\n').count() as u32;
+        self.col = (s.len() - index - 1) as u32;
+      }
+      None => self.col += s.len() as u32,
+    }
+    self.dest.write_str(s)?;
+    Ok(())
+  }
+
+  /// Writes a `/* source: path.css */` comment followed by a newline if the `source_comments` option
+  /// is enabled, and the given source differs from the one printed last.
+  pub(crate) fn source_comment(&mut self, source_index: u32) -> Result<(), PrinterError> {
+    if !self.source_comments || self.comment_source_index == Some(source_index) {
+      return Ok(());
+    }
+
+    let filename = match self.sources.and_then(|sources| sources.get(source_index as usize)) {
+      Some(filename) => filename,
+      None => return Ok(()),
+    };
+
+    self.comment_source_index = Some(source_index);
+    self.write_str("/* source: ")?;
+    self.write_str(filename)?;
+    self.write_str(" */")?;
+    self.newline()
+  }
+
+  /// Returns the url to print for a `url()` reference or `@import` rule, after rebasing and
+  /// rewriting it according to the [UrlOptions](UrlOptions).
+  pub(crate) fn rewrite_url<'u>(&self, url: &'u str, kind: UrlKind) -> Cow<'u, str> {
+    if self.urls.output_path.is_none() && self.urls.rewrite.is_none() {
+      return Cow::Borrowed(url);
+    }
+
+    let filename = self.filename();
+    let rebased = match (self.urls.output_path, self.sources) {
+      (Some(output_path), Some(_)) => rebase_url(url, filename, output_path),
+      _ => None,
+    };
+
+    let rewritten = self.urls.rewrite.and_then(|rewrite| {
+      rewrite(&UrlReference {
+        url: rebased.as_deref().unwrap_or(url),
+        original: url,
+        kind,
+        filename,
+      })
+    });
+
+    match rewritten.or(rebased) {
+      Some(url) => Cow::Owned(url),
+      None => Cow::Borrowed(url),
+    }
+  }
+
+  /// Writes a quoted string, escaping characters as needed. Strings are quoted with double quotes,
+  /// unless single quotes are chosen by the [Compression](Compression) option.
+  pub fn write_string(&mut self, s: &str) -> Result<(), PrinterError> {
+    if self.choices.quote == '"' {
+      serialize_string(s, self)?;
+      return Ok(());
+    }
+
+    self.write_char('\'')?;
+    for c in s.chars() {
+      match c {
+        '\'' => self.write_str("\\'")?,
+        '\\' => self.write_str("\\\\")?,
+        '\0' => self.write_char('\u{FFFD}')?,
+        '\x01'..='\x1F' | '\x7F' => write!(self, "\\{:x} ", c as u32)?,
+        c => self.write_char(c)?,
+      }
+    }
+    self.write_char('\'')
+  }
+
   /// Write a single character to the underlying destination.
   pub fn write_char(&mut self, c: char) -> Result<(), PrinterError> {
     if c == '\n' {
@@ -218,26 +615,41 @@ impl<'a, 'b, 'c, W: std::fmt::Write + Sized> Printer<'a, 'b, 'c, W> {
   #[cfg(feature = "sourcemap")]
   pub fn add_mapping(&mut self, loc: Location) {
     self.loc = loc;
+    self.add_declaration_mapping(loc);
+  }
+
+  /// Adds a mapping for a declaration to the source map, if any. Unlike [add_mapping](Printer::add_mapping),
+  /// this does not change the current location, which remains the location of the enclosing rule.
+  #[cfg(feature = "sourcemap")]
+  pub(crate) fn add_declaration_mapping(&mut self, loc: Location) {
+    self.add_named_mapping(loc, None)
+  }
 
+  /// Adds a mapping to the source map, if any, with an entry in the `names` of the source map.
+  /// This is used to map the scoped names generated by CSS modules back to the original names.
+  #[cfg(feature = "sourcemap")]
+  fn add_named_mapping(&mut self, loc: Location, name: Option<&str>) {
     if let Some(map) = &mut self.source_map {
       let mut original = OriginalLocation {
         original_line: loc.line,
         original_column: loc.column - 1,
         source: loc.source_index,
-        name: None,
+        name: name.map(|name| map.add_name(name)),
       };
 
       // Remap using input source map if possible.
       if let Some(Some(sm)) = self.source_maps.get_mut(loc.source_index as usize) {
         let mut found_mapping = false;
-        if let Some(mapping) = sm.find_closest_mapping(loc.line, loc.column - 1) {
+        if let Some(mapping) = crate::sourcemap::find_closest_mapping(sm, loc.line, loc.column - 1) {
           if let Some(orig) = mapping.original {
             let sources_len = map.get_sources().len();
             let source_index = map.add_source(sm.get_source(orig.source).unwrap());
             original.original_line = orig.original_line;
             original.original_column = orig.original_column;
             original.source = source_index;
-            original.name = orig.name;
+            if name.is_none() {
+              original.name = orig.name.and_then(|name| sm.get_name(name).ok()).map(|name| map.add_name(name));
+            }
 
             if map.get_sources().len() > sources_len {
               let content = sm.get_source_content(orig.source).unwrap().to_owned();
@@ -261,12 +673,34 @@ impl<'a, 'b, 'c, W: std::fmt::Write + Sized> Printer<'a, 'b, 'c, W> {
   /// as appropriate. If the `css_modules` option was enabled, then a hash
   /// is added, and the mapping is added to the CSS module.
   pub fn write_ident(&mut self, ident: &str) -> Result<(), PrinterError> {
+    self.write_scoped_ident(ident, NameKind::Other)
+  }
+
+  /// Returns whether CSS modules are enabled, and scope the given kind of name.
+  pub(crate) fn is_scoped(&self, kind: NameKind) -> bool {
+    matches!(&self.css_module, Some(css_module) if css_module.config.scopes(kind))
+  }
+
+  /// Writes a CSS identifier like [write_ident](Printer::write_ident), but only adds a hash
+  /// if CSS modules scope the given kind of name.
+  pub(crate) fn write_scoped_ident(&mut self, ident: &str, kind: NameKind) -> Result<(), PrinterError> {
+    if !self.is_scoped(kind) {
+      serialize_identifier(ident, self)?;
+      return Ok(());
+    }
+
+    // Map the scoped name back to the original name in the source map, until the end of the name.
+    #[cfg(feature = "sourcemap")]
+    self.add_named_mapping(self.loc, Some(ident));
+
     if let Some(css_module) = &mut self.css_module {
       let dest = &mut self.dest;
       let mut first = true;
-      css_module.config.pattern.write(
+      css_module.config.write_name(
         &css_module.hashes[self.loc.source_index as usize],
-        &css_module.sources[self.loc.source_index as usize],
+        &css_module.content_hashes[self.loc.source_index as usize],
+        css_module.sources[self.loc.source_index as usize],
+        &css_module.relative_paths[self.loc.source_index as usize],
         ident,
         |s| {
           self.col += s.len() as u32;
@@ -279,37 +713,82 @@ impl<'a, 'b, 'c, W: std::fmt::Write + Sized> Printer<'a, 'b, 'c, W> {
         },
       )?;
 
-      css_module.add_local(&ident, &ident, self.loc.source_index);
-    } else {
-      serialize_identifier(ident, self)?;
+      css_module.add_local(ident, ident, self.loc);
     }
 
+    #[cfg(feature = "sourcemap")]
+    self.add_declaration_mapping(self.loc);
+
     Ok(())
   }
 
   pub(crate) fn write_dashed_ident(&mut self, ident: &str, is_declaration: bool) -> Result<(), PrinterError> {
     self.write_str("--")?;
 
-    match &mut self.css_module {
-      Some(css_module) if css_module.config.dashed_idents => {
-        let dest = &mut self.dest;
-        css_module.config.pattern.write(
-          &css_module.hashes[self.loc.source_index as usize],
-          &css_module.sources[self.loc.source_index as usize],
-          &ident[2..],
-          |s| {
-            self.col += s.len() as u32;
-            serialize_name(s, dest)
-          },
-        )?;
+    match &self.css_module {
+      Some(css_module) if css_module.config.dashed_idents => self.write_scoped_dashed_ident(ident, is_declaration),
+      _ => {
+        serialize_name(&ident[2..], self)?;
+        Ok(())
+      }
+    }
+  }
 
-        if is_declaration {
-          css_module.add_dashed(ident, self.loc.source_index);
+  /// Writes the name of a custom property, renamed if it is declared in the current source file
+  /// and the CSS modules `custom_properties` option is enabled. Returns `false` without writing
+  /// anything if the name is not renamed.
+  pub(crate) fn write_scoped_custom_property(
+    &mut self,
+    ident: &str,
+    is_declaration: bool,
+  ) -> Result<bool, PrinterError> {
+    match &mut self.css_module {
+      Some(css_module) if css_module.is_scoped_custom_property(ident, self.loc.source_index) => {
+        if !is_declaration {
+          css_module.reference_dashed(ident, &None, self.loc);
         }
       }
-      _ => {
-        serialize_name(&ident[2..], self)?;
+      _ => return Ok(false),
+    }
+
+    self.write_str("--")?;
+    self.write_scoped_dashed_ident(ident, is_declaration)?;
+    Ok(true)
+  }
+
+  /// Writes the name of a scroll or view timeline. Timeline names are scoped when either the CSS modules
+  /// `timeline` or `dashed_idents` option is enabled, and references to them are marked as used.
+  pub(crate) fn write_timeline_name(&mut self, ident: &str, is_declaration: bool) -> Result<(), PrinterError> {
+    match &mut self.css_module {
+      Some(css_module) if css_module.config.timeline || css_module.config.dashed_idents => {
+        if !is_declaration {
+          css_module.reference_dashed(ident, &None, self.loc);
+        }
       }
+      _ => return self.write_dashed_ident(ident, is_declaration),
+    }
+
+    self.write_str("--")?;
+    self.write_scoped_dashed_ident(ident, is_declaration)
+  }
+
+  fn write_scoped_dashed_ident(&mut self, ident: &str, is_declaration: bool) -> Result<(), PrinterError> {
+    let css_module = self.css_module.as_mut().unwrap();
+    let dest = &mut self.dest;
+    css_module.config.write_name(
+      &css_module.hashes[self.loc.source_index as usize],
+      &css_module.content_hashes[self.loc.source_index as usize],
+      css_module.sources[self.loc.source_index as usize],
+      &css_module.relative_paths[self.loc.source_index as usize],
+      &ident[2..],
+      |s| {
+        self.col += s.len() as u32;
+        serialize_name(s, dest)
+      },
+    )?;
+
+    if is_declaration {
+      css_module.add_dashed(ident, self.loc);
     }
 
     Ok(())
@@ -7,7 +7,7 @@ use crate::context::PropertyHandlerContext;
 use crate::declaration::{DeclarationBlock, DeclarationList};
 use crate::error::{ParserError, PrinterError};
 use crate::macros::*;
-use crate::prefixes::{is_flex_2009, Feature};
+use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FromStandard, Parse, PropertyHandler, Shorthand, ToCss};
@@ -1194,12 +1194,12 @@ impl AlignHandler {
           let mut prefix = *prefix;
           if prefix.contains(VendorPrefix::None) {
             if let Some(targets) = self.targets {
-              prefix = Feature::$prop.prefixes_for(targets);
+              prefix = targets.flex_prefixes(Feature::$prop);
 
               // 2009 spec, implemented by webkit and firefox.
               $(
                 let mut prefixes_2009 = VendorPrefix::empty();
-                if is_flex_2009(targets) {
+                if targets.is_flex_2009() {
                   prefixes_2009 |= VendorPrefix::WebKit;
                 }
                 if prefix.contains(VendorPrefix::Moz) {
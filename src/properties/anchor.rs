@@ -0,0 +1,494 @@
+//! CSS properties related to anchor positioning.
+
+use crate::error::{ParserError, PrinterError};
+use crate::macros::enum_property;
+use crate::printer::Printer;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::DashedIdent;
+use crate::values::length::LengthPercentage;
+use crate::values::percentage::Percentage;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+use smallvec::SmallVec;
+
+/// A value for the [anchor-name](https://drafts.csswg.org/css-anchor-position-1/#propdef-anchor-name) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum AnchorName<'i> {
+  /// The `none` keyword.
+  None,
+  /// A list of anchor names.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Names(SmallVec<[DashedIdent<'i>; 1]>),
+}
+
+impl<'i> Parse<'i> for AnchorName<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(AnchorName::None);
+    }
+
+    let names = input.parse_comma_separated(DashedIdent::parse)?;
+    Ok(AnchorName::Names(names.into()))
+  }
+}
+
+impl<'i> ToCss for AnchorName<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      AnchorName::None => dest.write_str("none"),
+      AnchorName::Names(names) => {
+        let mut first = true;
+        for name in names {
+          if first {
+            first = false;
+          } else {
+            dest.delim(',', false)?;
+          }
+          name.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+/// A value for the [position-anchor](https://drafts.csswg.org/css-anchor-position-1/#propdef-position-anchor) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum PositionAnchor<'i> {
+  /// The `auto` keyword. Uses the implicit anchor element, if any.
+  Auto,
+  /// A named anchor.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Named(DashedIdent<'i>),
+}
+
+impl<'i> Parse<'i> for PositionAnchor<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(PositionAnchor::Auto);
+    }
+
+    Ok(PositionAnchor::Named(DashedIdent::parse(input)?))
+  }
+}
+
+impl<'i> ToCss for PositionAnchor<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      PositionAnchor::Auto => dest.write_str("auto"),
+      PositionAnchor::Named(name) => name.to_css(dest),
+    }
+  }
+}
+
+enum_property! {
+  /// A keyword used in the [position-area](https://drafts.csswg.org/css-anchor-position-1/#propdef-position-area) property.
+  #[allow(missing_docs)]
+  pub enum PositionAreaKeyword {
+    "center": Center,
+    "span-all": SpanAll,
+    "left": Left,
+    "right": Right,
+    "span-left": SpanLeft,
+    "span-right": SpanRight,
+    "x-start": XStart,
+    "x-end": XEnd,
+    "span-x-start": SpanXStart,
+    "span-x-end": SpanXEnd,
+    "self-x-start": SelfXStart,
+    "self-x-end": SelfXEnd,
+    "span-self-x-start": SpanSelfXStart,
+    "span-self-x-end": SpanSelfXEnd,
+    "top": Top,
+    "bottom": Bottom,
+    "span-top": SpanTop,
+    "span-bottom": SpanBottom,
+    "y-start": YStart,
+    "y-end": YEnd,
+    "span-y-start": SpanYStart,
+    "span-y-end": SpanYEnd,
+    "self-y-start": SelfYStart,
+    "self-y-end": SelfYEnd,
+    "span-self-y-start": SpanSelfYStart,
+    "span-self-y-end": SpanSelfYEnd,
+    "block-start": BlockStart,
+    "block-end": BlockEnd,
+    "span-block-start": SpanBlockStart,
+    "span-block-end": SpanBlockEnd,
+    "inline-start": InlineStart,
+    "inline-end": InlineEnd,
+    "span-inline-start": SpanInlineStart,
+    "span-inline-end": SpanInlineEnd,
+    "self-block-start": SelfBlockStart,
+    "self-block-end": SelfBlockEnd,
+    "span-self-block-start": SpanSelfBlockStart,
+    "span-self-block-end": SpanSelfBlockEnd,
+    "self-inline-start": SelfInlineStart,
+    "self-inline-end": SelfInlineEnd,
+    "span-self-inline-start": SpanSelfInlineStart,
+    "span-self-inline-end": SpanSelfInlineEnd,
+    "start": Start,
+    "end": End,
+    "span-start": SpanStart,
+    "span-end": SpanEnd,
+    "self-start": SelfStart,
+    "self-end": SelfEnd,
+    "span-self-start": SpanSelfStart,
+    "span-self-end": SpanSelfEnd,
+  }
+}
+
+#[derive(PartialEq)]
+enum AreaFamily {
+  Any,
+  Physical,
+  Logical,
+  SelfLogical,
+  Generic,
+  SelfGeneric,
+}
+
+impl PositionAreaKeyword {
+  /// Returns the family of keywords this keyword may be combined with, and whether it must appear
+  /// first (horizontal or block axis), second (vertical or inline axis), or in either position.
+  fn axis(&self) -> (AreaFamily, Option<bool>) {
+    use PositionAreaKeyword::*;
+    match self {
+      Center | SpanAll => (AreaFamily::Any, None),
+      Left | Right | SpanLeft | SpanRight | XStart | XEnd | SpanXStart | SpanXEnd | SelfXStart | SelfXEnd
+      | SpanSelfXStart | SpanSelfXEnd => (AreaFamily::Physical, Some(true)),
+      Top | Bottom | SpanTop | SpanBottom | YStart | YEnd | SpanYStart | SpanYEnd | SelfYStart | SelfYEnd
+      | SpanSelfYStart | SpanSelfYEnd => (AreaFamily::Physical, Some(false)),
+      BlockStart | BlockEnd | SpanBlockStart | SpanBlockEnd => (AreaFamily::Logical, Some(true)),
+      InlineStart | InlineEnd | SpanInlineStart | SpanInlineEnd => (AreaFamily::Logical, Some(false)),
+      SelfBlockStart | SelfBlockEnd | SpanSelfBlockStart | SpanSelfBlockEnd => {
+        (AreaFamily::SelfLogical, Some(true))
+      }
+      SelfInlineStart | SelfInlineEnd | SpanSelfInlineStart | SpanSelfInlineEnd => {
+        (AreaFamily::SelfLogical, Some(false))
+      }
+      Start | End | SpanStart | SpanEnd => (AreaFamily::Generic, None),
+      SelfStart | SelfEnd | SpanSelfStart | SpanSelfEnd => (AreaFamily::SelfGeneric, None),
+    }
+  }
+}
+
+/// A value for the [position-area](https://drafts.csswg.org/css-anchor-position-1/#propdef-position-area) property.
+///
+/// Both keywords are always stored, in canonical order. When only a single keyword is specified,
+/// the other is either `span-all` (for keywords that are explicit about their axis), or a copy of the first.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum PositionArea {
+  /// The `none` keyword.
+  None,
+  /// A region of the position-area grid.
+  Area {
+    /// The keyword for the horizontal or block axis.
+    first: PositionAreaKeyword,
+    /// The keyword for the vertical or inline axis.
+    second: PositionAreaKeyword,
+  },
+}
+
+impl<'i> Parse<'i> for PositionArea {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(PositionArea::None);
+    }
+
+    let first = PositionAreaKeyword::parse(input)?;
+    let second = match input.try_parse(PositionAreaKeyword::parse) {
+      Ok(second) => second,
+      Err(_) => {
+        let (first, second) = match first.axis() {
+          (_, Some(true)) => (first, PositionAreaKeyword::SpanAll),
+          (_, Some(false)) => (PositionAreaKeyword::SpanAll, first),
+          (_, None) => (first, first),
+        };
+        return Ok(PositionArea::Area { first, second });
+      }
+    };
+
+    let (first_family, first_slot) = first.axis();
+    let (second_family, second_slot) = second.axis();
+    if first_family != AreaFamily::Any && second_family != AreaFamily::Any && first_family != second_family {
+      return Err(input.new_custom_error(ParserError::InvalidValue));
+    }
+
+    let (first, second) = if first_slot == Some(false) || second_slot == Some(true) {
+      (second, first)
+    } else {
+      (first, second)
+    };
+
+    if first.axis().1 == Some(false) || second.axis().1 == Some(true) {
+      return Err(input.new_custom_error(ParserError::InvalidValue));
+    }
+
+    Ok(PositionArea::Area { first, second })
+  }
+}
+
+impl ToCss for PositionArea {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      PositionArea::None => dest.write_str("none"),
+      PositionArea::Area { first, second } => {
+        if first == second && first.axis().1.is_none() {
+          return first.to_css(dest);
+        }
+
+        if *second == PositionAreaKeyword::SpanAll && first.axis().1 == Some(true) {
+          return first.to_css(dest);
+        }
+
+        if *first == PositionAreaKeyword::SpanAll && second.axis().1 == Some(false) {
+          return second.to_css(dest);
+        }
+
+        first.to_css(dest)?;
+        dest.write_char(' ')?;
+        second.to_css(dest)
+      }
+    }
+  }
+}
+
+enum_property! {
+  /// A side keyword for the [anchor()](https://drafts.csswg.org/css-anchor-position-1/#anchor-pos) function.
+  pub enum AnchorSideKeyword {
+    /// The side of the anchor on the same side as the inset property.
+    "inside": Inside,
+    /// The side of the anchor opposite to the inset property.
+    "outside": Outside,
+    /// The top side of the anchor.
+    "top": Top,
+    /// The left side of the anchor.
+    "left": Left,
+    /// The right side of the anchor.
+    "right": Right,
+    /// The bottom side of the anchor.
+    "bottom": Bottom,
+    /// The start side of the anchor, in the containing block's writing mode.
+    "start": Start,
+    /// The end side of the anchor, in the containing block's writing mode.
+    "end": End,
+    /// The start side of the anchor, in the element's own writing mode.
+    "self-start": SelfStart,
+    /// The end side of the anchor, in the element's own writing mode.
+    "self-end": SelfEnd,
+    /// The center of the anchor.
+    "center": Center,
+  }
+}
+
+/// An [`<anchor-side>`](https://drafts.csswg.org/css-anchor-position-1/#typedef-anchor-side) value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum AnchorSide {
+  /// A side keyword.
+  Keyword(AnchorSideKeyword),
+  /// A position between the start (0%) and end (100%) sides of the anchor.
+  Percentage(Percentage),
+}
+
+impl<'i> Parse<'i> for AnchorSide {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if let Ok(percentage) = input.try_parse(Percentage::parse) {
+      return Ok(AnchorSide::Percentage(percentage));
+    }
+
+    Ok(AnchorSide::Keyword(AnchorSideKeyword::parse(input)?))
+  }
+}
+
+impl ToCss for AnchorSide {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      AnchorSide::Keyword(keyword) => keyword.to_css(dest),
+      AnchorSide::Percentage(percentage) => percentage.to_css(dest),
+    }
+  }
+}
+
+/// An [anchor()](https://drafts.csswg.org/css-anchor-position-1/#anchor-pos) function,
+/// which resolves to the position of a side of an anchor element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct AnchorFunction<'i> {
+  /// The name of the anchor element. If omitted, the default anchor is used.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub name: Option<DashedIdent<'i>>,
+  /// The side of the anchor to position against.
+  pub side: AnchorSide,
+  /// A fallback value, used if the anchor reference is invalid.
+  pub fallback: Option<LengthPercentage>,
+}
+
+impl<'i> Parse<'i> for AnchorFunction<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    input.expect_function_matching("anchor")?;
+    input.parse_nested_block(|input| {
+      let mut name = input.try_parse(DashedIdent::parse).ok();
+      let side = AnchorSide::parse(input)?;
+      if name.is_none() {
+        name = input.try_parse(DashedIdent::parse).ok();
+      }
+
+      let fallback = if input.try_parse(|input| input.expect_comma()).is_ok() {
+        Some(LengthPercentage::parse(input)?)
+      } else {
+        None
+      };
+
+      Ok(AnchorFunction { name, side, fallback })
+    })
+  }
+}
+
+impl<'i> ToCss for AnchorFunction<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("anchor(")?;
+    if let Some(name) = &self.name {
+      name.to_css(dest)?;
+      dest.write_char(' ')?;
+    }
+    self.side.to_css(dest)?;
+    if let Some(fallback) = &self.fallback {
+      dest.delim(',', false)?;
+      fallback.to_css(dest)?;
+    }
+    dest.write_char(')')
+  }
+}
+
+enum_property! {
+  /// A size keyword for the [anchor-size()](https://drafts.csswg.org/css-anchor-position-1/#anchor-size-fn) function.
+  pub enum AnchorSizeKeyword {
+    /// The width of the anchor.
+    "width": Width,
+    /// The height of the anchor.
+    "height": Height,
+    /// The size of the anchor in the block axis of the containing block.
+    "block": Block,
+    /// The size of the anchor in the inline axis of the containing block.
+    "inline": Inline,
+    /// The size of the anchor in the block axis of the element.
+    "self-block": SelfBlock,
+    /// The size of the anchor in the inline axis of the element.
+    "self-inline": SelfInline,
+  }
+}
+
+/// An [anchor-size()](https://drafts.csswg.org/css-anchor-position-1/#anchor-size-fn) function,
+/// which resolves to a dimension of an anchor element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct AnchorSizeFunction<'i> {
+  /// The name of the anchor element. If omitted, the default anchor is used.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub name: Option<DashedIdent<'i>>,
+  /// The dimension of the anchor to use. If omitted, the axis of the property it is used in is used.
+  pub size: Option<AnchorSizeKeyword>,
+  /// A fallback value, used if the anchor reference is invalid.
+  pub fallback: Option<LengthPercentage>,
+}
+
+impl<'i> Parse<'i> for AnchorSizeFunction<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    input.expect_function_matching("anchor-size")?;
+    input.parse_nested_block(|input| {
+      let mut name = input.try_parse(DashedIdent::parse).ok();
+      let size = input.try_parse(AnchorSizeKeyword::parse).ok();
+      if name.is_none() {
+        name = input.try_parse(DashedIdent::parse).ok();
+      }
+
+      let fallback = if name.is_none() && size.is_none() {
+        input.try_parse(LengthPercentage::parse).ok()
+      } else if input.try_parse(|input| input.expect_comma()).is_ok() {
+        Some(LengthPercentage::parse(input)?)
+      } else {
+        None
+      };
+
+      Ok(AnchorSizeFunction { name, size, fallback })
+    })
+  }
+}
+
+impl<'i> ToCss for AnchorSizeFunction<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("anchor-size(")?;
+    if let Some(name) = &self.name {
+      name.to_css(dest)?;
+    }
+    if let Some(size) = &self.size {
+      if self.name.is_some() {
+        dest.write_char(' ')?;
+      }
+      size.to_css(dest)?;
+    }
+    if let Some(fallback) = &self.fallback {
+      if self.name.is_some() || self.size.is_some() {
+        dest.delim(',', false)?;
+      }
+      fallback.to_css(dest)?;
+    }
+    dest.write_char(')')
+  }
+}
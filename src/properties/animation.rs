@@ -1,15 +1,22 @@
 //! CSS properties related to keyframe animations.
 
 use crate::context::PropertyHandlerContext;
+use crate::css_modules::NameKind;
 use crate::declaration::{DeclarationBlock, DeclarationList};
 use crate::error::{ParserError, PrinterError};
 use crate::macros::*;
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::{Property, PropertyId, VendorPrefix};
+#[cfg(feature = "serde")]
+use crate::serialization::ValueWrapper;
 use crate::targets::Browsers;
 use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss, Zero};
+use crate::values::ident::DashedIdent;
+use crate::values::length::{LengthPercentage, LengthPercentageOrAuto};
 use crate::values::number::CSSNumber;
+use crate::values::percentage::Percentage;
+use crate::values::size::Size2D;
 use crate::values::string::CowArcStr;
 use crate::values::{easing::EasingFunction, ident::CustomIdent, time::Time};
 #[cfg(feature = "visitor")]
@@ -37,6 +44,10 @@ pub enum AnimationName<'i> {
   /// A `<string>` name of a `@keyframes` rule.
   #[cfg_attr(feature = "serde", serde(borrow))]
   String(CowArcStr<'i>),
+  /// A reference to a `@keyframes` rule using the CSS modules `global()` function,
+  /// e.g. `global(fade)`. The name is not scoped.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Global(CustomIdent<'i>),
 }
 
 impl<'i> Parse<'i> for AnimationName<'i> {
@@ -49,6 +60,11 @@ impl<'i> Parse<'i> for AnimationName<'i> {
       return Ok(AnimationName::String(s.into()));
     }
 
+    if input.try_parse(|input| input.expect_function_matching("global")).is_ok() {
+      let ident = input.parse_nested_block(CustomIdent::parse)?;
+      return Ok(AnimationName::Global(ident));
+    }
+
     let ident = CustomIdent::parse(input)?;
     Ok(AnimationName::Ident(ident))
   }
@@ -62,27 +78,28 @@ impl<'i> ToCss for AnimationName<'i> {
     match self {
       AnimationName::None => dest.write_str("none"),
       AnimationName::Ident(s) => {
-        if let Some(css_module) = &mut dest.css_module {
-          css_module.reference(&s.0, dest.loc.source_index)
+        if let Some(css_module) = dest.css_module.as_mut().filter(|m| m.config.animation) {
+          css_module.reference(&s.0, dest.loc)
         }
-        s.to_css(dest)
+        s.to_css_scoped(dest, NameKind::Keyframes)
       }
       AnimationName::String(s) => {
-        if let Some(css_module) = &mut dest.css_module {
-          css_module.reference(&s, dest.loc.source_index)
+        if let Some(css_module) = dest.css_module.as_mut().filter(|m| m.config.animation) {
+          css_module.reference(s, dest.loc)
         }
 
         // CSS-wide keywords and `none` cannot remove quotes.
         match_ignore_ascii_case! { &*s,
           "none" | "initial" | "inherit" | "unset" | "default" | "revert" | "revert-layer" => {
-            serialize_string(&s, dest)?;
+            dest.write_string(s)?;
             Ok(())
           },
           _ => {
-            dest.write_ident(s.as_ref())
+            dest.write_scoped_ident(s.as_ref(), NameKind::Keyframes)
           }
         }
       }
+      AnimationName::Global(s) => s.to_css_unscoped(dest),
     }
   }
 }
@@ -191,6 +208,433 @@ impl Default for AnimationFillMode {
   }
 }
 
+enum_property! {
+  /// A value for the [animation-composition](https://drafts.csswg.org/css-animations-2/#animation-composition) property.
+  #[derive(Default)]
+  pub enum AnimationComposition {
+    /// The animation value replaces the underlying value.
+    #[default]
+    Replace,
+    /// The animation value is added to the underlying value.
+    Add,
+    /// The animation value is accumulated onto the underlying value.
+    Accumulate,
+  }
+}
+
+/// A value for the [animation-timeline](https://drafts.csswg.org/css-animations-2/#animation-timeline) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum AnimationTimeline<'i> {
+  /// The animation's timeline is a DocumentTimeline, more specifically the default document timeline.
+  #[default]
+  Auto,
+  /// The animation is not associated with a timeline.
+  None,
+  /// A timeline referenced by name.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  DashedIdent(DashedIdent<'i>),
+  /// The scroll() function.
+  Scroll(ScrollFunction),
+  /// The view() function.
+  View(ViewFunction),
+}
+
+impl<'i> Parse<'i> for AnimationTimeline<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(AnimationTimeline::Auto);
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(AnimationTimeline::None);
+    }
+
+    if let Ok(name) = input.try_parse(DashedIdent::parse) {
+      return Ok(AnimationTimeline::DashedIdent(name));
+    }
+
+    let location = input.current_source_location();
+    let f = input.expect_function()?.clone();
+    input.parse_nested_block(move |input| {
+      match_ignore_ascii_case! { &f,
+        "scroll" => ScrollFunction::parse(input).map(AnimationTimeline::Scroll),
+        "view" => ViewFunction::parse(input).map(AnimationTimeline::View),
+        _ => Err(location.new_unexpected_token_error(cssparser::Token::Function(f.clone())))
+      }
+    })
+  }
+}
+
+impl<'i> ToCss for AnimationTimeline<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      AnimationTimeline::Auto => dest.write_str("auto"),
+      AnimationTimeline::None => dest.write_str("none"),
+      AnimationTimeline::DashedIdent(name) => dest.write_timeline_name(name, false),
+      AnimationTimeline::Scroll(scroll) => {
+        dest.write_str("scroll(")?;
+        scroll.to_css(dest)?;
+        dest.write_char(')')
+      }
+      AnimationTimeline::View(view) => {
+        dest.write_str("view(")?;
+        view.to_css(dest)?;
+        dest.write_char(')')
+      }
+    }
+  }
+}
+
+/// The arguments of the [scroll()](https://drafts.csswg.org/scroll-animations-1/#scroll-notation) function.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ScrollFunction {
+  /// Specifies which element to use as the scroll container.
+  pub scroller: Scroller,
+  /// Specifies which axis of the scroll container to use as the progress for the timeline.
+  pub axis: ScrollAxis,
+}
+
+impl<'i> Parse<'i> for ScrollFunction {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut scroller = None;
+    let mut axis = None;
+    loop {
+      if scroller.is_none() {
+        scroller = input.try_parse(Scroller::parse).ok();
+      }
+
+      if axis.is_none() {
+        axis = input.try_parse(ScrollAxis::parse).ok();
+        if axis.is_some() {
+          continue;
+        }
+      }
+      break;
+    }
+
+    Ok(ScrollFunction {
+      scroller: scroller.unwrap_or_default(),
+      axis: axis.unwrap_or_default(),
+    })
+  }
+}
+
+impl ToCss for ScrollFunction {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let mut needs_space = false;
+    if self.scroller != Scroller::default() {
+      self.scroller.to_css(dest)?;
+      needs_space = true;
+    }
+
+    if self.axis != ScrollAxis::default() {
+      if needs_space {
+        dest.write_char(' ')?;
+      }
+      self.axis.to_css(dest)?;
+    }
+
+    Ok(())
+  }
+}
+
+enum_property! {
+  /// A scroll container, used within the [scroll()](https://drafts.csswg.org/scroll-animations-1/#typedef-scroller) function.
+  #[derive(Default)]
+  pub enum Scroller {
+    /// Specifies to use the document viewport as the scroll container.
+    "root": Root,
+    /// Specifies to use the nearest ancestor scroll container.
+    #[default]
+    "nearest": Nearest,
+    /// Specifies to use the element's own principal box as the scroll container.
+    "self": SelfElement,
+  }
+}
+
+enum_property! {
+  /// A scroll axis, used in the [scroll()](https://drafts.csswg.org/scroll-animations-1/#scroll-notation)
+  /// and [view()](https://drafts.csswg.org/scroll-animations-1/#view-notation) functions, and the
+  /// [scroll-timeline-axis](https://drafts.csswg.org/scroll-animations-1/#scroll-timeline-axis) and
+  /// [view-timeline-axis](https://drafts.csswg.org/scroll-animations-1/#view-timeline-axis) properties.
+  #[derive(Default)]
+  pub enum ScrollAxis {
+    /// Specifies to use the measure of progress along the block axis of the scroll container.
+    #[default]
+    Block,
+    /// Specifies to use the measure of progress along the inline axis of the scroll container.
+    Inline,
+    /// Specifies to use the measure of progress along the horizontal axis of the scroll container.
+    X,
+    /// Specifies to use the measure of progress along the vertical axis of the scroll container.
+    Y,
+  }
+}
+
+/// The arguments of the [view()](https://drafts.csswg.org/scroll-animations-1/#view-notation) function.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ViewFunction {
+  /// The axis of the scroll container to use.
+  pub axis: ScrollAxis,
+  /// Adjustments to the view progress visibility range.
+  pub inset: Size2D<LengthPercentageOrAuto>,
+}
+
+impl Default for ViewFunction {
+  fn default() -> Self {
+    ViewFunction {
+      axis: ScrollAxis::default(),
+      inset: Size2D(LengthPercentageOrAuto::Auto, LengthPercentageOrAuto::Auto),
+    }
+  }
+}
+
+impl<'i> Parse<'i> for ViewFunction {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut axis = None;
+    let mut inset = None;
+    loop {
+      if axis.is_none() {
+        axis = input.try_parse(ScrollAxis::parse).ok();
+      }
+
+      if inset.is_none() {
+        inset = input.try_parse(Size2D::parse).ok();
+        if inset.is_some() {
+          continue;
+        }
+      }
+      break;
+    }
+
+    Ok(ViewFunction {
+      axis: axis.unwrap_or_default(),
+      inset: inset.unwrap_or(Size2D(LengthPercentageOrAuto::Auto, LengthPercentageOrAuto::Auto)),
+    })
+  }
+}
+
+impl ToCss for ViewFunction {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let mut needs_space = false;
+    if self.axis != ScrollAxis::default() {
+      self.axis.to_css(dest)?;
+      needs_space = true;
+    }
+
+    if self.inset.0 != LengthPercentageOrAuto::Auto || self.inset.1 != LengthPercentageOrAuto::Auto {
+      if needs_space {
+        dest.write_char(' ')?;
+      }
+      self.inset.to_css(dest)?;
+    }
+
+    Ok(())
+  }
+}
+
+enum_property! {
+  /// A [view progress timeline range](https://drafts.csswg.org/scroll-animations-1/#view-timelines-ranges).
+  pub enum TimelineRangeName {
+    /// Represents the full range of the view progress timeline.
+    "cover": Cover,
+    /// Represents the range during which the principal box is either fully contained by,
+    /// or fully covers, its view progress visibility range within the scrollport.
+    "contain": Contain,
+    /// Represents the range during which the principal box is entering the view progress visibility range.
+    "entry": Entry,
+    /// Represents the range during which the principal box is exiting the view progress visibility range.
+    "exit": Exit,
+    /// Represents the range during which the principal box crosses the end border edge.
+    "entry-crossing": EntryCrossing,
+    /// Represents the range during which the principal box crosses the start border edge.
+    "exit-crossing": ExitCrossing,
+  }
+}
+
+/// A value for the [animation-range-start](https://drafts.csswg.org/scroll-animations-1/#animation-range-start)
+/// or [animation-range-end](https://drafts.csswg.org/scroll-animations-1/#animation-range-end) properties.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum AnimationAttachmentRange {
+  /// The start or end of the animation's attachment range is the start or end of its associated timeline.
+  #[default]
+  Normal,
+  /// The animation attachment range starts or ends at the specified point on the timeline,
+  /// measured from the start of the timeline.
+  #[cfg_attr(feature = "serde", serde(with = "ValueWrapper::<LengthPercentage>"))]
+  LengthPercentage(LengthPercentage),
+  /// The animation attachment range starts or ends at the specified point on the timeline,
+  /// measured from the start of the specified named timeline range.
+  TimelineRange {
+    /// The name of the timeline range.
+    name: TimelineRangeName,
+    /// The offset from the start of the named timeline range.
+    offset: LengthPercentage,
+  },
+}
+
+impl AnimationAttachmentRange {
+  fn parse<'i, 't>(input: &mut Parser<'i, 't>, default: f32) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
+      return Ok(AnimationAttachmentRange::Normal);
+    }
+
+    if let Ok(val) = input.try_parse(LengthPercentage::parse) {
+      return Ok(AnimationAttachmentRange::LengthPercentage(val));
+    }
+
+    let name = TimelineRangeName::parse(input)?;
+    let offset = input
+      .try_parse(LengthPercentage::parse)
+      .unwrap_or(LengthPercentage::Percentage(Percentage(default)));
+    Ok(AnimationAttachmentRange::TimelineRange { name, offset })
+  }
+
+  fn to_css<W>(&self, dest: &mut Printer<W>, default: f32) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      AnimationAttachmentRange::Normal => dest.write_str("normal"),
+      AnimationAttachmentRange::LengthPercentage(val) => val.to_css(dest),
+      AnimationAttachmentRange::TimelineRange { name, offset } => {
+        name.to_css(dest)?;
+        if *offset != LengthPercentage::Percentage(Percentage(default)) {
+          dest.write_char(' ')?;
+          offset.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+/// A value for the [animation-range-start](https://drafts.csswg.org/scroll-animations-1/#animation-range-start) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct AnimationRangeStart(pub AnimationAttachmentRange);
+
+impl<'i> Parse<'i> for AnimationRangeStart {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let range = AnimationAttachmentRange::parse(input, 0.0)?;
+    Ok(Self(range))
+  }
+}
+
+impl ToCss for AnimationRangeStart {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.0.to_css(dest, 0.0)
+  }
+}
+
+/// A value for the [animation-range-end](https://drafts.csswg.org/scroll-animations-1/#animation-range-end) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct AnimationRangeEnd(pub AnimationAttachmentRange);
+
+impl<'i> Parse<'i> for AnimationRangeEnd {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let range = AnimationAttachmentRange::parse(input, 1.0)?;
+    Ok(Self(range))
+  }
+}
+
+impl ToCss for AnimationRangeEnd {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.0.to_css(dest, 1.0)
+  }
+}
+
+define_list_shorthand! {
+  /// A value for the [animation-range](https://drafts.csswg.org/scroll-animations-1/#animation-range) shorthand property.
+  pub struct AnimationRange {
+    /// The start of the animation's attachment range.
+    start: AnimationRangeStart(AnimationRangeStart),
+    /// The end of the animation's attachment range.
+    end: AnimationRangeEnd(AnimationRangeEnd),
+  }
+}
+
+impl AnimationRange {
+  /// Returns the end value that is implied when only a start value is specified.
+  fn implied_end(start: &AnimationRangeStart) -> AnimationRangeEnd {
+    match &start.0 {
+      AnimationAttachmentRange::TimelineRange { name, .. } => {
+        AnimationRangeEnd(AnimationAttachmentRange::TimelineRange {
+          name: *name,
+          offset: LengthPercentage::Percentage(Percentage(1.0)),
+        })
+      }
+      _ => AnimationRangeEnd(AnimationAttachmentRange::Normal),
+    }
+  }
+}
+
+impl<'i> Parse<'i> for AnimationRange {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let start = AnimationRangeStart::parse(input)?;
+    let end = match input.try_parse(AnimationRangeEnd::parse) {
+      Ok(end) => end,
+      Err(_) => AnimationRange::implied_end(&start),
+    };
+    Ok(AnimationRange { start, end })
+  }
+}
+
+impl ToCss for AnimationRange {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.start.to_css(dest)?;
+    if self.end != AnimationRange::implied_end(&self.start) {
+      dest.write_char(' ')?;
+      self.end.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
+
 define_list_shorthand! {
   /// A value for the [animation](https://drafts.csswg.org/css-animations/#animation) shorthand property.
   #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
@@ -212,6 +656,22 @@ define_list_shorthand! {
     delay: AnimationDelay(Time, VendorPrefix),
     /// The animation fill mode.
     fill_mode: AnimationFillMode(AnimationFillMode, VendorPrefix),
+    /// The animation timeline.
+    ///
+    /// This is a reset-only sub-property: the shorthand always resets it to `auto`, and it is not serialized.
+    timeline: AnimationTimeline(AnimationTimeline<'i>),
+    /// The start of the animation's attachment range.
+    ///
+    /// This is a reset-only sub-property: the shorthand always resets it to `normal`, and it is not serialized.
+    range_start: AnimationRangeStart(AnimationRangeStart),
+    /// The end of the animation's attachment range.
+    ///
+    /// This is a reset-only sub-property: the shorthand always resets it to `normal`, and it is not serialized.
+    range_end: AnimationRangeEnd(AnimationRangeEnd),
+    /// How the animation is combined with the underlying value.
+    ///
+    /// This is a reset-only sub-property: the shorthand always resets it to `replace`, and it is not serialized.
+    composition: AnimationComposition(AnimationComposition),
   }
 }
 
@@ -258,6 +718,10 @@ impl<'i> Parse<'i> for Animation<'i> {
       play_state: play_state.unwrap_or(AnimationPlayState::Running),
       delay: delay.unwrap_or(Time::Seconds(0.0)),
       fill_mode: fill_mode.unwrap_or(AnimationFillMode::None),
+      timeline: AnimationTimeline::Auto,
+      range_start: AnimationRangeStart::default(),
+      range_end: AnimationRangeEnd::default(),
+      composition: AnimationComposition::default(),
     })
   }
 }
@@ -269,7 +733,9 @@ impl<'i> ToCss for Animation<'i> {
   {
     match &self.name {
       AnimationName::None => {}
-      AnimationName::Ident(CustomIdent(name)) | AnimationName::String(name) => {
+      AnimationName::Ident(CustomIdent(name))
+      | AnimationName::String(name)
+      | AnimationName::Global(CustomIdent(name)) => {
         if !self.duration.is_zero() || !self.delay.is_zero() {
           self.duration.to_css(dest)?;
           dest.write_char(' ')?;
@@ -320,6 +786,182 @@ impl<'i> ToCss for Animation<'i> {
 /// A list of animations.
 pub type AnimationList<'i> = SmallVec<[Animation<'i>; 1]>;
 
+/// A value for the [scroll-timeline-name](https://drafts.csswg.org/scroll-animations-1/#scroll-timeline-name)
+/// or [view-timeline-name](https://drafts.csswg.org/scroll-animations-1/#view-timeline-name) properties.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum TimelineName<'i> {
+  /// The timeline has no name.
+  None,
+  /// The name of the timeline.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  DashedIdent(DashedIdent<'i>),
+}
+
+impl<'i> Parse<'i> for TimelineName<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(TimelineName::None);
+    }
+
+    Ok(TimelineName::DashedIdent(DashedIdent::parse(input)?))
+  }
+}
+
+impl<'i> ToCss for TimelineName<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      TimelineName::None => dest.write_str("none"),
+      TimelineName::DashedIdent(name) => dest.write_timeline_name(name, true),
+    }
+  }
+}
+
+define_list_shorthand! {
+  /// A value for the [scroll-timeline](https://drafts.csswg.org/scroll-animations-1/#scroll-timeline-shorthand) shorthand property.
+  #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+  pub struct ScrollTimeline<'i> {
+    /// The name of the scroll timeline.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    name: ScrollTimelineName(TimelineName<'i>),
+    /// The axis of the scroll container to use.
+    axis: ScrollTimelineAxis(ScrollAxis),
+  }
+}
+
+impl<'i> Parse<'i> for ScrollTimeline<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let name = TimelineName::parse(input)?;
+    let axis = input.try_parse(ScrollAxis::parse).unwrap_or_default();
+    Ok(ScrollTimeline { name, axis })
+  }
+}
+
+impl<'i> ToCss for ScrollTimeline<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.name.to_css(dest)?;
+    if self.axis != ScrollAxis::default() {
+      dest.write_char(' ')?;
+      self.axis.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
+
+define_list_shorthand! {
+  /// A value for the [view-timeline](https://drafts.csswg.org/scroll-animations-1/#view-timeline-shorthand) shorthand property.
+  #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+  pub struct ViewTimeline<'i> {
+    /// The name of the view timeline.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    name: ViewTimelineName(TimelineName<'i>),
+    /// The axis of the scroll container to use.
+    axis: ViewTimelineAxis(ScrollAxis),
+    /// Adjustments to the view progress visibility range.
+    inset: ViewTimelineInset(Size2D<LengthPercentageOrAuto>),
+  }
+}
+
+impl<'i> Parse<'i> for ViewTimeline<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let name = TimelineName::parse(input)?;
+    let ViewFunction { axis, inset } = ViewFunction::parse(input)?;
+    Ok(ViewTimeline { name, axis, inset })
+  }
+}
+
+impl<'i> ToCss for ViewTimeline<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.name.to_css(dest)?;
+    if self.axis != ScrollAxis::default() {
+      dest.write_char(' ')?;
+      self.axis.to_css(dest)?;
+    }
+
+    if self.inset.0 != LengthPercentageOrAuto::Auto || self.inset.1 != LengthPercentageOrAuto::Auto {
+      dest.write_char(' ')?;
+      self.inset.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
+
+/// A value for the [timeline-scope](https://drafts.csswg.org/scroll-animations-1/#timeline-scope) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum TimelineScope<'i> {
+  /// No timeline names are declared in this element's subtree.
+  None,
+  /// All timeline names defined by descendants are declared in this element's subtree.
+  All,
+  /// The given timeline names are declared in this element's subtree.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  DashedIdents(SmallVec<[DashedIdent<'i>; 1]>),
+}
+
+impl<'i> Parse<'i> for TimelineScope<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(TimelineScope::None);
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("all")).is_ok() {
+      return Ok(TimelineScope::All);
+    }
+
+    Ok(TimelineScope::DashedIdents(
+      input.parse_comma_separated(DashedIdent::parse)?.into(),
+    ))
+  }
+}
+
+impl<'i> ToCss for TimelineScope<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      TimelineScope::None => dest.write_str("none"),
+      TimelineScope::All => dest.write_str("all"),
+      TimelineScope::DashedIdents(names) => {
+        let mut first = true;
+        for name in names {
+          if first {
+            first = false;
+          } else {
+            dest.delim(',', false)?;
+          }
+          dest.write_timeline_name(name, false)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
 #[derive(Default)]
 pub(crate) struct AnimationHandler<'i> {
   targets: Option<Browsers>,
@@ -331,6 +973,10 @@ pub(crate) struct AnimationHandler<'i> {
   play_states: Option<(SmallVec<[AnimationPlayState; 1]>, VendorPrefix)>,
   delays: Option<(SmallVec<[Time; 1]>, VendorPrefix)>,
   fill_modes: Option<(SmallVec<[AnimationFillMode; 1]>, VendorPrefix)>,
+  timelines: Option<SmallVec<[AnimationTimeline<'i>; 1]>>,
+  range_starts: Option<SmallVec<[AnimationRangeStart; 1]>>,
+  range_ends: Option<SmallVec<[AnimationRangeEnd; 1]>>,
+  compositions: Option<SmallVec<[AnimationComposition; 1]>>,
   has_any: bool,
 }
 
@@ -388,6 +1034,27 @@ impl<'i> PropertyHandler<'i> for AnimationHandler<'i> {
       AnimationPlayState(val, vp) => property!(play_states, val, vp),
       AnimationDelay(val, vp) => property!(delays, val, vp),
       AnimationFillMode(val, vp) => property!(fill_modes, val, vp),
+      AnimationTimeline(val) => {
+        self.timelines = Some(val.clone());
+        self.has_any = true;
+      }
+      AnimationRangeStart(val) => {
+        self.range_starts = Some(val.clone());
+        self.has_any = true;
+      }
+      AnimationRangeEnd(val) => {
+        self.range_ends = Some(val.clone());
+        self.has_any = true;
+      }
+      AnimationComposition(val) => {
+        self.compositions = Some(val.clone());
+        self.has_any = true;
+      }
+      AnimationRange(val) => {
+        self.range_starts = Some(val.iter().map(|r| r.start.clone()).collect());
+        self.range_ends = Some(val.iter().map(|r| r.end.clone()).collect());
+        self.has_any = true;
+      }
       Animation(val, vp) => {
         let names = val.iter().map(|b| b.name.clone()).collect();
         maybe_flush!(names, &names, vp);
@@ -421,6 +1088,14 @@ impl<'i> PropertyHandler<'i> for AnimationHandler<'i> {
         property!(play_states, &play_states, vp);
         property!(delays, &delays, vp);
         property!(fill_modes, &fill_modes, vp);
+
+        // The unprefixed shorthand resets the timeline, range, and composition sub-properties.
+        if vp.contains(VendorPrefix::None) {
+          self.timelines = Some(val.iter().map(|b| b.timeline.clone()).collect());
+          self.range_starts = Some(val.iter().map(|b| b.range_start.clone()).collect());
+          self.range_ends = Some(val.iter().map(|b| b.range_end.clone()).collect());
+          self.compositions = Some(val.iter().map(|b| b.composition).collect());
+        }
       }
       Unparsed(val) if is_animation_property(&val.property_id) => {
         self.flush(dest);
@@ -453,6 +1128,11 @@ impl<'i> AnimationHandler<'i> {
     let mut play_states = std::mem::take(&mut self.play_states);
     let mut delays = std::mem::take(&mut self.delays);
     let mut fill_modes = std::mem::take(&mut self.fill_modes);
+    let timelines = std::mem::take(&mut self.timelines);
+    let range_starts = std::mem::take(&mut self.range_starts);
+    let range_ends = std::mem::take(&mut self.range_ends);
+    let compositions = std::mem::take(&mut self.compositions);
+    let mut reset_by_shorthand = false;
 
     if let (
       Some((names, names_vp)),
@@ -513,6 +1193,10 @@ impl<'i> AnimationHandler<'i> {
               play_state,
               delay,
               fill_mode,
+              timeline: AnimationTimeline::Auto,
+              range_start: AnimationRangeStart::default(),
+              range_end: AnimationRangeEnd::default(),
+              composition: AnimationComposition::default(),
             }
           },
         )
@@ -523,6 +1207,7 @@ impl<'i> AnimationHandler<'i> {
             prefix = Feature::Animation.prefixes_for(targets)
           }
         }
+        reset_by_shorthand = prefix.contains(VendorPrefix::None);
         dest.push(Property::Animation(animations, prefix));
         names_vp.remove(intersection);
         durations_vp.remove(intersection);
@@ -559,6 +1244,50 @@ impl<'i> AnimationHandler<'i> {
     prop!(play_states, AnimationPlayState);
     prop!(delays, AnimationDelay);
     prop!(fill_modes, AnimationFillMode);
+
+    // The timeline, composition, and range sub-properties cannot be expressed in the animation shorthand,
+    // so they are output afterward unless they match the values the shorthand resets them to.
+    if let Some(timelines) = timelines {
+      if !reset_by_shorthand || timelines.iter().any(|t| *t != AnimationTimeline::Auto) {
+        dest.push(Property::AnimationTimeline(timelines));
+      }
+    }
+
+    if let Some(compositions) = compositions {
+      if !reset_by_shorthand || compositions.iter().any(|c| *c != AnimationComposition::default()) {
+        dest.push(Property::AnimationComposition(compositions));
+      }
+    }
+
+    let is_reset = |starts: &Option<SmallVec<[AnimationRangeStart; 1]>>,
+                    ends: &Option<SmallVec<[AnimationRangeEnd; 1]>>| {
+      reset_by_shorthand
+        && starts.iter().flatten().all(|s| *s == AnimationRangeStart::default())
+        && ends.iter().flatten().all(|e| *e == AnimationRangeEnd::default())
+    };
+
+    if is_reset(&range_starts, &range_ends) {
+      return;
+    }
+
+    match (range_starts, range_ends) {
+      (Some(mut starts), Some(mut ends)) if starts.len() == ends.len() => {
+        let ranges = starts
+          .drain(..)
+          .zip(ends.drain(..))
+          .map(|(start, end)| AnimationRange { start, end })
+          .collect();
+        dest.push(Property::AnimationRange(ranges))
+      }
+      (starts, ends) => {
+        if let Some(starts) = starts {
+          dest.push(Property::AnimationRangeStart(starts))
+        }
+        if let Some(ends) = ends {
+          dest.push(Property::AnimationRangeEnd(ends))
+        }
+      }
+    }
   }
 }
 
@@ -573,7 +1302,12 @@ fn is_animation_property(property_id: &PropertyId) -> bool {
     | PropertyId::AnimationPlayState(_)
     | PropertyId::AnimationDelay(_)
     | PropertyId::AnimationFillMode(_)
-    | PropertyId::Animation(_) => true,
+    | PropertyId::Animation(_)
+    | PropertyId::AnimationTimeline
+    | PropertyId::AnimationComposition
+    | PropertyId::AnimationRangeStart
+    | PropertyId::AnimationRangeEnd
+    | PropertyId::AnimationRange => true,
     _ => false,
   }
 }
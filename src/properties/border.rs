@@ -705,7 +705,7 @@ impl<'i> PropertyHandler<'i> for BorderHandler<'i> {
 }
 
 impl<'i> BorderHandler<'i> {
-  fn flush(&mut self, dest: &mut DeclarationList, context: &mut PropertyHandlerContext<'i, '_>) {
+  fn flush(&mut self, dest: &mut DeclarationList<'i>, context: &mut PropertyHandlerContext<'i, '_>) {
     if !self.has_any {
       return;
     }
@@ -716,7 +716,7 @@ impl<'i> BorderHandler<'i> {
     let logical_shorthand_supported = context.is_supported(Feature::LogicalBorderShorthand);
     macro_rules! logical_prop {
       ($ltr: ident, $ltr_key: ident, $rtl: ident, $rtl_key: ident, $val: expr) => {{
-        context.add_logical_rule(Property::$ltr($val.clone()), Property::$rtl($val.clone()));
+        context.add_logical_rule(dest, Property::$ltr($val.clone()), Property::$rtl($val.clone()));
       }};
     }
 
@@ -1269,6 +1269,7 @@ impl<'i> BorderHandler<'i> {
     macro_rules! logical_prop {
       ($ltr: ident, $ltr_key: ident, $rtl: ident, $rtl_key: ident) => {{
         context.add_logical_rule(
+          dest,
           Property::Unparsed(unparsed.with_property_id(PropertyId::$ltr)),
           Property::Unparsed(unparsed.with_property_id(PropertyId::$rtl)),
         );
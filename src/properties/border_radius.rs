@@ -300,10 +300,11 @@ impl<'i> BorderRadiusHandler<'i> {
               | Property::BorderStartEndRadius(val)
               | Property::BorderEndEndRadius(val)
               | Property::BorderEndStartRadius(val) => {
-                context.add_logical_rule(Property::$ltr(val.clone(), vp), Property::$rtl(val, vp));
+                context.add_logical_rule(dest, Property::$ltr(val.clone(), vp), Property::$rtl(val, vp));
               }
               Property::Unparsed(val) => {
                 context.add_logical_rule(
+                  dest,
                   Property::Unparsed(val.with_property_id(PropertyId::$ltr(vp))),
                   Property::Unparsed(val.with_property_id(PropertyId::$rtl(vp))),
                 );
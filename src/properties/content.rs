@@ -0,0 +1,349 @@
+//! CSS properties related to generated content.
+
+use super::custom::Attr;
+use super::list::{CounterStyle, PredefinedCounterStyle};
+use crate::error::{ParserError, PrinterError};
+use crate::macros::enum_property;
+use crate::printer::Printer;
+use crate::stylesheet::ParserOptions;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::CustomIdent;
+use crate::values::image::Image;
+use crate::values::string::CSSString;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+
+/// A value for the [content](https://drafts.csswg.org/css-content-3/#content-property) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum Content<'i> {
+  /// The `normal` keyword.
+  Normal,
+  /// The `none` keyword.
+  None,
+  /// A list of content items, with optional alternative text.
+  Items {
+    /// The content items.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    items: Vec<ContentItem<'i>>,
+    /// Alternative text for the content, used by assistive technologies.
+    alt: Option<Vec<ContentItem<'i>>>,
+  },
+}
+
+impl<'i> Parse<'i> for Content<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
+      return Ok(Content::Normal);
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(Content::None);
+    }
+
+    let mut items = vec![ContentItem::parse(input)?];
+    while let Ok(item) = input.try_parse(ContentItem::parse) {
+      items.push(item);
+    }
+
+    let alt = if input.try_parse(|input| input.expect_delim('/')).is_ok() {
+      let mut alt = vec![ContentItem::parse_alt(input)?];
+      while let Ok(item) = input.try_parse(ContentItem::parse_alt) {
+        alt.push(item);
+      }
+      Some(alt)
+    } else {
+      None
+    };
+
+    Ok(Content::Items { items, alt })
+  }
+}
+
+impl<'i> ToCss for Content<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      Content::Normal => dest.write_str("normal"),
+      Content::None => dest.write_str("none"),
+      Content::Items { items, alt } => {
+        write_items(items, dest)?;
+        if let Some(alt) = alt {
+          dest.delim('/', true)?;
+          write_items(alt, dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+fn write_items<W>(items: &[ContentItem], dest: &mut Printer<W>) -> Result<(), PrinterError>
+where
+  W: std::fmt::Write,
+{
+  let mut first = true;
+  let mut iter = items.iter().peekable();
+  while let Some(item) = iter.next() {
+    if first {
+      first = false;
+    } else {
+      dest.write_char(' ')?;
+    }
+
+    // Adjacent strings are concatenated when generating content, so merge them when minifying.
+    if let ContentItem::String(s) = item {
+      if dest.minify && matches!(iter.peek(), Some(ContentItem::String(_))) {
+        let mut merged = s.to_string();
+        while let Some(ContentItem::String(s)) = iter.peek() {
+          merged.push_str(s);
+          iter.next();
+        }
+        dest.write_string(&merged)?;
+        continue;
+      }
+    }
+
+    item.to_css(dest)?;
+  }
+  Ok(())
+}
+
+/// A single item within the value of the [content](Content) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ContentItem<'i> {
+  /// A string.
+  #[cfg_attr(
+    feature = "serde",
+    serde(borrow, with = "crate::serialization::ValueWrapper::<CSSString>")
+  )]
+  String(CSSString<'i>),
+  /// An image.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::ValueWrapper::<Image>"))]
+  Image(Image<'i>),
+  /// A [counter()](https://drafts.csswg.org/css-lists-3/#funcdef-counter) function.
+  Counter {
+    /// The name of the counter.
+    name: CustomIdent<'i>,
+    /// The counter style used to represent the counter value.
+    style: CounterStyle<'i>,
+  },
+  /// A [counters()](https://drafts.csswg.org/css-lists-3/#funcdef-counters) function.
+  Counters {
+    /// The name of the counter.
+    name: CustomIdent<'i>,
+    /// The string used to join the values of nested counters.
+    separator: CSSString<'i>,
+    /// The counter style used to represent the counter values.
+    style: CounterStyle<'i>,
+  },
+  /// An [attr()](Attr) function.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::ValueWrapper::<Attr>"))]
+  Attr(Attr<'i>),
+  /// A quote keyword.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::ValueWrapper::<Quote>"))]
+  Quote(Quote),
+  /// A [leader()](https://drafts.csswg.org/css-content-3/#leader-function) function.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::ValueWrapper::<Leader>"))]
+  Leader(Leader<'i>),
+}
+
+enum_property! {
+  /// A [quote](https://drafts.csswg.org/css-content-3/#typedef-quote) keyword, as used in the `content` property.
+  pub enum Quote {
+    /// An opening quote, as specified by the `quotes` property.
+    "open-quote": OpenQuote,
+    /// A closing quote, as specified by the `quotes` property.
+    "close-quote": CloseQuote,
+    /// Increments the quote nesting level without inserting a quote.
+    "no-open-quote": NoOpenQuote,
+    /// Decrements the quote nesting level without inserting a quote.
+    "no-close-quote": NoCloseQuote,
+  }
+}
+
+/// The fill style of a [leader()](https://drafts.csswg.org/css-content-3/#leader-function) function.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum Leader<'i> {
+  /// A dotted leader, equivalent to `leader(".")`.
+  Dotted,
+  /// A solid leader, equivalent to `leader("_")`.
+  Solid,
+  /// A space leader, equivalent to `leader(" ")`.
+  Space,
+  /// A custom leader string.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  String(CSSString<'i>),
+}
+
+impl<'i> ContentItem<'i> {
+  fn parse_alt<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    match ContentItem::parse(input)? {
+      item @ (ContentItem::String(_)
+      | ContentItem::Counter { .. }
+      | ContentItem::Counters { .. }
+      | ContentItem::Attr(_)) => Ok(item),
+      _ => Err(location.new_custom_error(ParserError::InvalidValue)),
+    }
+  }
+}
+
+impl<'i> Parse<'i> for ContentItem<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if let Ok(s) = input.try_parse(CSSString::parse) {
+      return Ok(ContentItem::String(s));
+    }
+
+    if let Ok(quote) = input.try_parse(Quote::parse) {
+      return Ok(ContentItem::Quote(quote));
+    }
+
+    if input.try_parse(|input| input.expect_function_matching("counter")).is_ok() {
+      return input.parse_nested_block(|input| {
+        let name = CustomIdent::parse(input)?;
+        let style = parse_counter_style(input)?;
+        Ok(ContentItem::Counter { name, style })
+      });
+    }
+
+    if input.try_parse(|input| input.expect_function_matching("counters")).is_ok() {
+      return input.parse_nested_block(|input| {
+        let name = CustomIdent::parse(input)?;
+        input.expect_comma()?;
+        let separator = CSSString::parse(input)?;
+        let style = parse_counter_style(input)?;
+        Ok(ContentItem::Counters { name, separator, style })
+      });
+    }
+
+    if input.try_parse(|input| input.expect_function_matching("attr")).is_ok() {
+      return input.parse_nested_block(|input| {
+        let attr = Attr::parse_nested(input, &ParserOptions::default(), 0)?;
+        Ok(ContentItem::Attr(attr))
+      });
+    }
+
+    if let Ok(leader) = input.try_parse(Leader::parse) {
+      return Ok(ContentItem::Leader(leader));
+    }
+
+    let location = input.current_source_location();
+    match Image::parse(input)? {
+      Image::None => Err(location.new_custom_error(ParserError::InvalidValue)),
+      image => Ok(ContentItem::Image(image)),
+    }
+  }
+}
+
+fn parse_counter_style<'i, 't>(
+  input: &mut Parser<'i, 't>,
+) -> Result<CounterStyle<'i>, ParseError<'i, ParserError<'i>>> {
+  if input.try_parse(|input| input.expect_comma()).is_ok() {
+    CounterStyle::parse(input)
+  } else {
+    Ok(CounterStyle::Predefined(PredefinedCounterStyle::Decimal))
+  }
+}
+
+fn write_counter_style<W>(style: &CounterStyle, dest: &mut Printer<W>) -> Result<(), PrinterError>
+where
+  W: std::fmt::Write,
+{
+  if *style != CounterStyle::Predefined(PredefinedCounterStyle::Decimal) {
+    dest.delim(',', false)?;
+    style.to_css(dest)?;
+  }
+  Ok(())
+}
+
+impl<'i> ToCss for ContentItem<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      ContentItem::String(s) => s.to_css(dest),
+      ContentItem::Image(image) => image.to_css(dest),
+      ContentItem::Counter { name, style } => {
+        dest.write_str("counter(")?;
+        name.to_css(dest)?;
+        write_counter_style(style, dest)?;
+        dest.write_char(')')
+      }
+      ContentItem::Counters { name, separator, style } => {
+        dest.write_str("counters(")?;
+        name.to_css(dest)?;
+        dest.delim(',', false)?;
+        separator.to_css(dest)?;
+        write_counter_style(style, dest)?;
+        dest.write_char(')')
+      }
+      ContentItem::Attr(attr) => attr.to_css(dest, false),
+      ContentItem::Quote(quote) => quote.to_css(dest),
+      ContentItem::Leader(leader) => leader.to_css(dest),
+    }
+  }
+}
+
+impl<'i> Parse<'i> for Leader<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    input.expect_function_matching("leader")?;
+    input.parse_nested_block(|input| {
+      if let Ok(s) = input.try_parse(CSSString::parse) {
+        return Ok(Leader::String(s));
+      }
+
+      let location = input.current_source_location();
+      let ident = input.expect_ident()?;
+      match_ignore_ascii_case! { &ident,
+        "dotted" => Ok(Leader::Dotted),
+        "solid" => Ok(Leader::Solid),
+        "space" => Ok(Leader::Space),
+        _ => Err(location.new_unexpected_token_error(Token::Ident(ident.clone())))
+      }
+    })
+  }
+}
+
+impl<'i> ToCss for Leader<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("leader(")?;
+    match self {
+      Leader::Dotted => dest.write_str("dotted")?,
+      Leader::Solid => dest.write_str("solid")?,
+      Leader::Space => dest.write_str("space")?,
+      Leader::String(s) => s.to_css(dest)?,
+    }
+    dest.write_char(')')
+  }
+}
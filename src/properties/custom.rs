@@ -20,11 +20,12 @@ use crate::values::number::CSSInteger;
 use crate::values::percentage::Percentage;
 use crate::values::resolution::Resolution;
 use crate::values::string::CowArcStr;
+use crate::values::syntax::{SyntaxComponentKind, SyntaxString};
 use crate::values::time::Time;
 use crate::values::url::Url;
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
-use crate::visitor::Visit;
+use crate::visitor::{Visit, VisitTypes, Visitor};
 use cssparser::*;
 
 #[cfg(feature = "serde")]
@@ -104,7 +105,12 @@ impl<'i> ToCss for CustomPropertyName<'i> {
     W: std::fmt::Write,
   {
     match self {
-      CustomPropertyName::Custom(c) => c.to_css(dest),
+      CustomPropertyName::Custom(c) => {
+        if dest.write_scoped_custom_property(&c.0, true)? {
+          return Ok(());
+        }
+        c.to_css(dest)
+      }
       CustomPropertyName::Unknown(u) => u.to_css(dest),
     }
   }
@@ -198,14 +204,185 @@ impl<'i> UnparsedProperty<'i> {
   }
 }
 
+/// Returns whether a value is valid for a property, rather than only as an unparsed value.
+fn is_valid_value(property_id: &PropertyId, css: &str) -> bool {
+  let property_id = PropertyId::from(property_id.name()).with_prefix(property_id.prefix());
+  let result = super::Property::parse_string(property_id, css, ParserOptions::default());
+  !matches!(result, Ok(super::Property::Unparsed(..)) | Err(..))
+}
+
+/// Returns whether the value at the current position may be valid for the property, or can never
+/// compute to a valid value because it consists of a single `attr()` function whose type cannot be
+/// valid for the property, or whose fallback is not a valid value for the property. The input is
+/// not consumed.
+pub(crate) fn is_valid_attr_value<'i, 't, T>(
+  property_id: &PropertyId<'i>,
+  input: &mut Parser<'i, 't>,
+  options: &ParserOptions<T>,
+) -> bool {
+  let state = input.state();
+  let result = input.try_parse(|input| {
+    input.expect_function_matching("attr")?;
+    let is_valid = input.parse_nested_block(|input| {
+      let start = input.state();
+      let attr = Attr::parse_nested(input, options, 0)?;
+      if !is_valid_attr_type(property_id, &attr.attr_type) {
+        return Ok(false);
+      }
+
+      // Fallbacks containing other substitution functions can only be validated at computed value time.
+      let fallback = match &attr.fallback {
+        Some(fallback) => fallback,
+        None => return Ok(true),
+      };
+      if fallback.0.iter().any(|token| {
+        matches!(
+          token,
+          TokenOrValue::Var(..) | TokenOrValue::Env(..) | TokenOrValue::Attr(..)
+        )
+      }) {
+        return Ok(true);
+      }
+
+      // Parse the fallback again as a value of the property.
+      input.reset(&start);
+      while !matches!(input.next()?, cssparser::Token::Comma) {}
+      let property = super::Property::parse(property_id.clone(), input, options);
+      while input.next().is_ok() {}
+      Ok(matches!(property, Ok(property) if !matches!(property, super::Property::Unparsed(..))))
+    })?;
+    input.expect_exhausted()?;
+    Ok::<_, ParseError<'i, ParserError<'i>>>(is_valid)
+  });
+  input.reset(&state);
+  result.unwrap_or(true)
+}
+
+/// Returns whether an `attr()` type may be valid for a property, by parsing an example value of it.
+fn is_valid_attr_type(property_id: &PropertyId, attr_type: &Option<AttrType>) -> bool {
+  let examples = match attr_type {
+    Some(AttrType::Type(SyntaxString::Components(components))) => {
+      components.iter().map(|component| example_value(&component.kind)).collect()
+    }
+    Some(AttrType::Unit(unit)) => vec![format!("1{}", unit)],
+    Some(AttrType::RawString) => vec!["\"\"".into()],
+    Some(AttrType::Type(SyntaxString::Universal)) | None => vec![],
+  };
+
+  examples.is_empty() || examples.iter().any(|example| is_valid_value(property_id, example))
+}
+
+/// A known property whose value is a typed [attr()](Attr) function, e.g. `width: attr(data-size px, 16px)`.
+///
+/// The attribute type and the fallback are validated against the property when parsing. Declarations
+/// where either can never be valid for the property are parsed as unparsed values instead, with a warning.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct AttrProperty<'i> {
+  /// The id of the property.
+  pub property_id: PropertyId<'i>,
+  /// The `attr()` function.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub value: Attr<'i>,
+}
+
+impl<'i> AttrProperty<'i> {
+  /// Parses a property value consisting of a single `attr()` function with a type. Returns `None`
+  /// without consuming any input if the value is anything else, or if the type or fallback is not
+  /// valid for the property.
+  pub fn parse<'t, T>(
+    property_id: &PropertyId<'i>,
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions<T>,
+  ) -> Option<Self> {
+    if !is_valid_attr_value(property_id, input, options) {
+      return None;
+    }
+
+    let value = input.try_parse(|input| {
+      input.expect_function_matching("attr")?;
+      let attr = input.parse_nested_block(|input| Attr::parse_nested(input, options, 0))?;
+      input.expect_exhausted()?;
+      if attr.attr_type.is_none() {
+        return Err(input.new_custom_error(ParserError::InvalidValue));
+      }
+      Ok(attr)
+    });
+
+    value.ok().map(|value| AttrProperty {
+      property_id: property_id.clone(),
+      value,
+    })
+  }
+}
+
+/// Returns an example value matching a syntax component.
+fn example_value(kind: &SyntaxComponentKind) -> String {
+  match kind {
+    SyntaxComponentKind::Length | SyntaxComponentKind::LengthPercentage => "1px",
+    SyntaxComponentKind::Number | SyntaxComponentKind::Integer => "1",
+    SyntaxComponentKind::Percentage => "1%",
+    SyntaxComponentKind::Color => "red",
+    SyntaxComponentKind::Image => "linear-gradient(red, red)",
+    SyntaxComponentKind::Url => "url(a)",
+    SyntaxComponentKind::Angle => "1deg",
+    SyntaxComponentKind::Time => "1s",
+    SyntaxComponentKind::Resolution => "1dppx",
+    SyntaxComponentKind::TransformFunction | SyntaxComponentKind::TransformList => "scale(1)",
+    SyntaxComponentKind::CustomIdent => "a",
+    SyntaxComponentKind::Literal(literal) => literal,
+  }
+  .into()
+}
+
 /// A raw list of CSS tokens, with embedded parsed values.
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "visitor", derive(Visit), visit(visit_token_list, TOKENS))]
 #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 #[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
 pub struct TokenList<'i>(#[cfg_attr(feature = "serde", serde(borrow))] pub Vec<TokenOrValue<'i>>);
 
+#[cfg(feature = "visitor")]
+impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for TokenList<'i> {
+  const CHILD_TYPES: VisitTypes = VisitTypes::from_bits_truncate(
+    <TokenOrValue<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits() | VisitTypes::TOKENS.bits(),
+  );
+
+  fn visit(&mut self, visitor: &mut V) {
+    if visitor.visit_types().contains(VisitTypes::TOKENS) {
+      visitor.visit_token_list(self)
+    } else {
+      self.visit_children(visitor)
+    }
+  }
+
+  fn visit_children(&mut self, visitor: &mut V) {
+    if !visitor.visit_types().contains(VisitTypes::TOKENS) {
+      return self.0.visit(visitor);
+    }
+
+    // Splice the tokens returned by the visitor into the list in place of the visited token.
+    let mut i = 0;
+    while i < self.0.len() {
+      match visitor.visit_token_at(&mut self.0, i) {
+        Some(tokens) => {
+          let len = tokens.len();
+          self.0.splice(i..i + 1, tokens);
+          i += len;
+        }
+        None => i += 1,
+      }
+    }
+  }
+}
+
 /// A raw CSS token, or a parsed value.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "visitor", derive(Visit), visit(visit_token, TOKENS), visit_types(TOKENS | COLORS | URLS | VARIABLES | ENVIRONMENT_VARIABLES | FUNCTIONS | LENGTHS | ANGLES | TIMES | RESOLUTIONS | DASHED_IDENTS))]
@@ -230,6 +407,12 @@ pub enum TokenOrValue<'i> {
   Var(Variable<'i>),
   /// A CSS environment variable reference.
   Env(EnvironmentVariable<'i>),
+  /// An `attr()` function reference.
+  Attr(Attr<'i>),
+  /// A tree-counting function, such as `sibling-index()`.
+  TreeCountingFunction(TreeCountingFunction),
+  /// A `toggle()` function.
+  Toggle(Toggle<'i>),
   /// A custom CSS function.
   Function(Function<'i>),
   /// A length.
@@ -274,7 +457,23 @@ impl<'i> TokenList<'i> {
   ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut tokens = vec![];
     TokenList::parse_into(input, &mut tokens, options, depth)?;
+    Ok(TokenList::trim_whitespace(tokens))
+  }
 
+  /// Parses a token list without converting colors and dimensions into typed values, so that
+  /// the tokens are printed exactly as written. Substitution functions such as `var()` are
+  /// rejected, so that the declaration falls back to an unparsed property, which is parsed
+  /// with the stylesheet's parser options.
+  pub(crate) fn parse_raw<'t>(
+    input: &mut Parser<'i, 't>,
+    depth: usize,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut tokens = vec![];
+    TokenList::parse_raw_into(input, &mut tokens, depth)?;
+    Ok(TokenList::trim_whitespace(tokens))
+  }
+
+  fn trim_whitespace(tokens: Vec<TokenOrValue<'i>>) -> Self {
     // Slice off leading and trailing whitespace if there are at least two tokens.
     // If there is only one token, we must preserve it. e.g. `--foo: ;` is valid.
     if tokens.len() >= 2 {
@@ -285,10 +484,100 @@ impl<'i> TokenList<'i> {
       if matches!(tokens.last(), Some(token) if token.is_whitespace()) {
         slice = &slice[..slice.len() - 1];
       }
-      return Ok(TokenList(slice.to_vec()));
+      return TokenList(slice.to_vec());
+    }
+
+    TokenList(tokens)
+  }
+
+  fn parse_raw_into<'t>(
+    input: &mut Parser<'i, 't>,
+    tokens: &mut Vec<TokenOrValue<'i>>,
+    depth: usize,
+  ) -> Result<(), ParseError<'i, ParserError<'i>>> {
+    if depth > 500 {
+      return Err(input.new_custom_error(ParserError::MaximumNestingDepth));
+    }
+
+    let mut last_is_delim = false;
+    let mut last_is_whitespace = false;
+    loop {
+      let state = input.state();
+      match input.next_including_whitespace_and_comments() {
+        Ok(&cssparser::Token::WhiteSpace(..)) | Ok(&cssparser::Token::Comment(..)) => {
+          if !last_is_delim {
+            tokens.push(Token::WhiteSpace(" ".into()).into());
+            last_is_whitespace = true;
+          }
+        }
+        Ok(cssparser::Token::Function(f)) => {
+          let f: CowArcStr<'i> = f.into();
+          match_ignore_ascii_case! { &*f,
+            "var" | "env" | "attr" | "toggle" => {
+              return Err(input.new_custom_error(ParserError::InvalidValue));
+            },
+            "url" => {
+              input.reset(&state);
+              tokens.push(TokenOrValue::Url(Url::parse(input)?));
+              last_is_delim = false;
+              last_is_whitespace = false;
+              continue;
+            },
+            _ => {}
+          }
+
+          let arguments = input.parse_nested_block(|input| TokenList::parse_raw(input, depth + 1))?;
+          tokens.push(TokenOrValue::Function(Function {
+            name: Ident(f),
+            arguments,
+          }));
+          last_is_delim = true;
+          last_is_whitespace = false;
+        }
+        Ok(&cssparser::Token::UnquotedUrl(_)) => {
+          input.reset(&state);
+          tokens.push(TokenOrValue::Url(Url::parse(input)?));
+          last_is_delim = false;
+          last_is_whitespace = false;
+        }
+        Ok(cssparser::Token::Ident(name)) if name.starts_with("--") => {
+          tokens.push(TokenOrValue::DashedIdent(name.into()));
+          last_is_delim = false;
+          last_is_whitespace = false;
+        }
+        Ok(token @ &cssparser::Token::ParenthesisBlock)
+        | Ok(token @ &cssparser::Token::SquareBracketBlock)
+        | Ok(token @ &cssparser::Token::CurlyBracketBlock) => {
+          tokens.push(Token::from(token).into());
+          let closing_delimiter = match token {
+            cssparser::Token::ParenthesisBlock => Token::CloseParenthesis,
+            cssparser::Token::SquareBracketBlock => Token::CloseSquareBracket,
+            cssparser::Token::CurlyBracketBlock => Token::CloseCurlyBracket,
+            _ => unreachable!(),
+          };
+
+          input.parse_nested_block(|input| TokenList::parse_raw_into(input, tokens, depth + 1))?;
+
+          tokens.push(closing_delimiter.into());
+          last_is_delim = true;
+          last_is_whitespace = false;
+        }
+        Ok(token) => {
+          last_is_delim = matches!(token, cssparser::Token::Delim(_) | cssparser::Token::Comma);
+          if last_is_delim && last_is_whitespace {
+            let last = tokens.last_mut().unwrap();
+            *last = Token::from(token).into();
+          } else {
+            tokens.push(Token::from(token).into());
+          }
+
+          last_is_whitespace = false;
+        }
+        Err(_) => break,
+      }
     }
 
-    return Ok(TokenList(tokens));
+    Ok(())
   }
 
   fn parse_into<'t, T>(
@@ -346,6 +635,28 @@ impl<'i> TokenList<'i> {
             tokens.push(env);
             last_is_delim = true;
             last_is_whitespace = false;
+          } else if let Ok(attr) = input.try_parse(|input| {
+            if f != "attr" {
+              return Err(input.new_custom_error(ParserError::InvalidValue));
+            }
+            input.parse_nested_block(|input| Attr::parse_nested(input, options, depth + 1))
+          }) {
+            tokens.push(TokenOrValue::Attr(attr));
+            last_is_delim = true;
+            last_is_whitespace = false;
+          } else if let Ok(func) = input.try_parse(|input| TreeCountingFunction::parse_nested(&f, input)) {
+            tokens.push(TokenOrValue::TreeCountingFunction(func));
+            last_is_delim = true;
+            last_is_whitespace = false;
+          } else if let Ok(toggle) = input.try_parse(|input| {
+            if f != "toggle" {
+              return Err(input.new_custom_error(ParserError::InvalidValue));
+            }
+            input.parse_nested_block(|input| Toggle::parse_nested(input, options, depth + 1))
+          }) {
+            tokens.push(TokenOrValue::Toggle(toggle));
+            last_is_delim = true;
+            last_is_whitespace = false;
           } else {
             let arguments = input.parse_nested_block(|input| TokenList::parse(input, options, depth + 1))?;
             tokens.push(TokenOrValue::Function(Function {
@@ -492,6 +803,18 @@ impl<'i> TokenList<'i> {
           env.to_css(dest, is_custom_property)?;
           self.write_whitespace_if_needed(i, dest)?
         }
+        TokenOrValue::Attr(attr) => {
+          attr.to_css(dest, is_custom_property)?;
+          self.write_whitespace_if_needed(i, dest)?
+        }
+        TokenOrValue::TreeCountingFunction(func) => {
+          func.to_css(dest)?;
+          self.write_whitespace_if_needed(i, dest)?
+        }
+        TokenOrValue::Toggle(toggle) => {
+          toggle.to_css(dest, is_custom_property)?;
+          self.write_whitespace_if_needed(i, dest)?
+        }
         TokenOrValue::Function(f) => {
           f.to_css(dest, is_custom_property)?;
           self.write_whitespace_if_needed(i, dest)?
@@ -848,7 +1171,7 @@ impl<'a> ToCss for Token<'a> {
       Token::AtKeyword(x) => cssparser::Token::AtKeyword(x.as_ref().into()).to_css(dest)?,
       Token::Hash(x) => cssparser::Token::Hash(x.as_ref().into()).to_css(dest)?,
       Token::IDHash(x) => cssparser::Token::IDHash(x.as_ref().into()).to_css(dest)?,
-      Token::String(x) => cssparser::Token::QuotedString(x.as_ref().into()).to_css(dest)?,
+      Token::String(x) => dest.write_string(x)?,
       Token::UnquotedUrl(x) => cssparser::Token::UnquotedUrl(x.as_ref().into()).to_css(dest)?,
       Token::Function(x) => cssparser::Token::Function(x.as_ref().into()).to_css(dest)?,
       Token::BadUrl(x) => cssparser::Token::BadUrl(x.as_ref().into()).to_css(dest)?,
@@ -911,6 +1234,84 @@ impl<'a> ToCss for Token<'a> {
 }
 
 impl<'i> TokenList<'i> {
+  /// Collects the names of the custom properties referenced via `var()` within the token list,
+  /// including within fallback values.
+  pub(crate) fn collect_variable_references(&self, names: &mut std::collections::HashSet<String>) {
+    for token in &self.0 {
+      match token {
+        TokenOrValue::Var(v) => {
+          names.insert(v.name.ident.0.to_string());
+          if let Some(fallback) = &v.fallback {
+            fallback.collect_variable_references(names);
+          }
+        }
+        TokenOrValue::Function(f) => f.arguments.collect_variable_references(names),
+        TokenOrValue::Env(EnvironmentVariable {
+          fallback: Some(fallback),
+          ..
+        })
+        | TokenOrValue::Attr(Attr {
+          fallback: Some(fallback),
+          ..
+        })
+        | TokenOrValue::UnresolvedColor(UnresolvedColor::RGB { alpha: fallback, .. })
+        | TokenOrValue::UnresolvedColor(UnresolvedColor::HSL { alpha: fallback, .. }) => {
+          fallback.collect_variable_references(names)
+        }
+        TokenOrValue::Toggle(t) => {
+          for value in &t.values {
+            value.collect_variable_references(names);
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  /// Replaces `var()` references to the given custom properties with their values, including
+  /// within fallback values. References with a fallback are replaced as well.
+  pub(crate) fn inline_variables(&mut self, values: &std::collections::HashMap<String, TokenList<'i>>) {
+    let mut i = 0;
+    while i < self.0.len() {
+      match &mut self.0[i] {
+        TokenOrValue::Var(v) => {
+          if v.name.from.is_none() {
+            if let Some(value) = values.get(v.name.ident.0.as_ref()) {
+              let len = value.0.len();
+              self.0.splice(i..i + 1, value.0.iter().cloned());
+              i += len;
+              continue;
+            }
+          }
+
+          if let Some(fallback) = &mut v.fallback {
+            fallback.inline_variables(values);
+          }
+        }
+        TokenOrValue::Function(f) => f.arguments.inline_variables(values),
+        TokenOrValue::Env(EnvironmentVariable {
+          fallback: Some(fallback),
+          ..
+        })
+        | TokenOrValue::Attr(Attr {
+          fallback: Some(fallback),
+          ..
+        })
+        | TokenOrValue::UnresolvedColor(UnresolvedColor::RGB { alpha: fallback, .. })
+        | TokenOrValue::UnresolvedColor(UnresolvedColor::HSL { alpha: fallback, .. }) => {
+          fallback.inline_variables(values)
+        }
+        TokenOrValue::Toggle(t) => {
+          for value in &mut t.values {
+            value.inline_variables(values);
+          }
+        }
+        _ => {}
+      }
+      i += 1;
+    }
+  }
+
   pub(crate) fn get_necessary_fallbacks(&self, targets: Browsers) -> ColorFallbackKind {
     let mut fallbacks = ColorFallbackKind::empty();
     for token in &self.0 {
@@ -931,6 +1332,16 @@ impl<'i> TokenList<'i> {
             fallbacks |= fallback.get_necessary_fallbacks(targets);
           }
         }
+        TokenOrValue::Attr(v) => {
+          if let Some(fallback) = &v.fallback {
+            fallbacks |= fallback.get_necessary_fallbacks(targets);
+          }
+        }
+        TokenOrValue::Toggle(t) => {
+          for value in &t.values {
+            fallbacks |= value.get_necessary_fallbacks(targets);
+          }
+        }
         _ => {}
       }
     }
@@ -938,6 +1349,43 @@ impl<'i> TokenList<'i> {
     fallbacks
   }
 
+  /// Collects the names of functions within the token list that are not supported by the given targets.
+  pub(crate) fn get_unsupported_functions(
+    &self,
+    targets: Browsers,
+    functions: &mut Vec<(&'static str, compat::Feature)>,
+  ) {
+    for token in &self.0 {
+      match token {
+        TokenOrValue::TreeCountingFunction(f) if !f.feature().is_compatible(targets) => {
+          functions.push((f.name(), f.feature()));
+        }
+        TokenOrValue::Toggle(t) => {
+          if !compat::Feature::Toggle.is_compatible(targets) {
+            functions.push(("toggle", compat::Feature::Toggle));
+          }
+          for value in &t.values {
+            value.get_unsupported_functions(targets, functions);
+          }
+        }
+        TokenOrValue::Function(f) => f.arguments.get_unsupported_functions(targets, functions),
+        TokenOrValue::Var(Variable {
+          fallback: Some(fallback),
+          ..
+        })
+        | TokenOrValue::Env(EnvironmentVariable {
+          fallback: Some(fallback),
+          ..
+        })
+        | TokenOrValue::Attr(Attr {
+          fallback: Some(fallback),
+          ..
+        }) => fallback.get_unsupported_functions(targets, functions),
+        _ => {}
+      }
+    }
+  }
+
   pub(crate) fn get_fallback(&self, kind: ColorFallbackKind) -> Self {
     let tokens = self
       .0
@@ -947,6 +1395,8 @@ impl<'i> TokenList<'i> {
         TokenOrValue::Function(f) => TokenOrValue::Function(f.get_fallback(kind)),
         TokenOrValue::Var(v) => TokenOrValue::Var(v.get_fallback(kind)),
         TokenOrValue::Env(e) => TokenOrValue::Env(e.get_fallback(kind)),
+        TokenOrValue::Attr(a) => TokenOrValue::Attr(a.get_fallback(kind)),
+        TokenOrValue::Toggle(t) => TokenOrValue::Toggle(t.get_fallback(kind)),
         _ => token.clone(),
       })
       .collect();
@@ -984,6 +1434,8 @@ impl<'i> TokenList<'i> {
           TokenOrValue::Function(f) => *f = f.get_fallback(lowest_fallback),
           TokenOrValue::Var(v) if v.fallback.is_some() => *v = v.get_fallback(lowest_fallback),
           TokenOrValue::Env(v) if v.fallback.is_some() => *v = v.get_fallback(lowest_fallback),
+          TokenOrValue::Attr(v) if v.fallback.is_some() => *v = v.get_fallback(lowest_fallback),
+          TokenOrValue::Toggle(t) => *t = t.get_fallback(lowest_fallback),
           _ => {}
         }
       }
@@ -1073,7 +1525,9 @@ impl<'i> Variable<'i> {
     W: std::fmt::Write,
   {
     dest.write_str("var(")?;
-    self.name.to_css(dest)?;
+    if self.name.from.is_some() || !dest.write_scoped_custom_property(&self.name.ident.0, false)? {
+      self.name.to_css(dest)?;
+    }
     if let Some(fallback) = &self.fallback {
       dest.delim(',', false)?;
       fallback.to_css(dest, is_custom_property)?;
@@ -1264,6 +1718,277 @@ impl<'i> EnvironmentVariable<'i> {
   }
 }
 
+/// A CSS [attr()](https://drafts.csswg.org/css-values-5/#attr-notation) function reference.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct Attr<'i> {
+  /// The namespace prefix of the attribute, if any.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub namespace: Option<Ident<'i>>,
+  /// The attribute name.
+  pub name: Ident<'i>,
+  /// The type that the attribute value is parsed as.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub attr_type: Option<AttrType<'i>>,
+  /// A fallback value in case the attribute is missing or invalid.
+  pub fallback: Option<TokenList<'i>>,
+}
+
+/// The type of an [attr()](Attr) function, which determines how the attribute value is parsed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum AttrType<'i> {
+  /// A `type()` function, which parses the attribute value according to a syntax definition.
+  Type(SyntaxString),
+  /// The `raw-string` keyword, which treats the attribute value as a string.
+  RawString,
+  /// A dimension unit (or `%`), which parses the attribute value as a number with the unit applied.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Unit(CowArcStr<'i>),
+}
+
+impl<'i> Attr<'i> {
+  pub(crate) fn parse_nested<'t, T>(
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions<T>,
+    depth: usize,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let first = Ident::parse(input)?;
+    let (namespace, name) = if input.try_parse(|input| input.expect_delim('|')).is_ok() {
+      (Some(first), Ident::parse(input)?)
+    } else {
+      (None, first)
+    };
+
+    let attr_type = input.try_parse(AttrType::parse).ok();
+    let fallback = if input.try_parse(|input| input.expect_comma()).is_ok() {
+      Some(TokenList::parse(input, options, depth + 1)?)
+    } else {
+      None
+    };
+
+    input.expect_exhausted()?;
+    Ok(Attr {
+      namespace,
+      name,
+      attr_type,
+      fallback,
+    })
+  }
+
+  pub(crate) fn to_css<W>(&self, dest: &mut Printer<W>, is_custom_property: bool) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("attr(")?;
+    if let Some(namespace) = &self.namespace {
+      namespace.to_css(dest)?;
+      dest.write_char('|')?;
+    }
+    self.name.to_css(dest)?;
+    if let Some(attr_type) = &self.attr_type {
+      dest.write_char(' ')?;
+      attr_type.to_css(dest)?;
+    }
+    if let Some(fallback) = &self.fallback {
+      dest.delim(',', false)?;
+      fallback.to_css(dest, is_custom_property)?;
+    }
+    dest.write_char(')')
+  }
+
+  fn get_fallback(&self, kind: ColorFallbackKind) -> Self {
+    Attr {
+      namespace: self.namespace.clone(),
+      name: self.name.clone(),
+      attr_type: self.attr_type.clone(),
+      fallback: self.fallback.as_ref().map(|fallback| fallback.get_fallback(kind)),
+    }
+  }
+}
+
+impl<'i> Parse<'i> for AttrType<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    match input.next()? {
+      cssparser::Token::Function(f) if f.eq_ignore_ascii_case("type") => input.parse_nested_block(|input| {
+        // The syntax is written unquoted here, unlike in @property rules.
+        let start = input.position();
+        while input.next().is_ok() {}
+        SyntaxString::parse_string(input.slice_from(start))
+          .map(AttrType::Type)
+          .map_err(|_| input.new_custom_error(ParserError::InvalidValue))
+      }),
+      cssparser::Token::Ident(ident) if ident.eq_ignore_ascii_case("raw-string") => Ok(AttrType::RawString),
+      cssparser::Token::Ident(unit) if is_attr_unit(unit) => Ok(AttrType::Unit(unit.into())),
+      cssparser::Token::Delim('%') => Ok(AttrType::Unit("%".into())),
+      t => Err(location.new_unexpected_token_error(t.clone())),
+    }
+  }
+}
+
+impl<'i> ToCss for AttrType<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      AttrType::Type(syntax) => {
+        dest.write_str("type(")?;
+        match syntax {
+          SyntaxString::Universal => dest.write_char('*')?,
+          SyntaxString::Components(components) => {
+            let mut first = true;
+            for component in components {
+              if first {
+                first = false;
+              } else {
+                dest.delim('|', true)?;
+              }
+              component.to_css(dest)?;
+            }
+          }
+        }
+        dest.write_char(')')
+      }
+      AttrType::RawString => dest.write_str("raw-string"),
+      AttrType::Unit(unit) => dest.write_str(unit),
+    }
+  }
+}
+
+fn is_attr_unit(unit: &str) -> bool {
+  let token = cssparser::Token::Dimension {
+    has_sign: false,
+    value: 0.0,
+    int_value: None,
+    unit: unit.into(),
+  };
+  LengthValue::try_from(&token).is_ok()
+    || Angle::try_from(&token).is_ok()
+    || Time::try_from(&token).is_ok()
+    || Resolution::try_from(&token).is_ok()
+    || matches!(unit.to_ascii_lowercase().as_str(), "hz" | "khz" | "fr")
+}
+
+/// A CSS [tree-counting function](https://drafts.csswg.org/css-values-5/#tree-counting).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum TreeCountingFunction {
+  /// The `sibling-index()` function.
+  SiblingIndex,
+  /// The `sibling-count()` function.
+  SiblingCount,
+}
+
+impl TreeCountingFunction {
+  fn parse_nested<'i, 't>(
+    name: &str,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let func = match_ignore_ascii_case! { name,
+      "sibling-index" => TreeCountingFunction::SiblingIndex,
+      "sibling-count" => TreeCountingFunction::SiblingCount,
+      _ => return Err(input.new_custom_error(ParserError::InvalidValue))
+    };
+
+    input.parse_nested_block(|input| input.expect_exhausted().map_err(|e| e.into()))?;
+    Ok(func)
+  }
+
+  /// Returns the name of the function.
+  pub fn name(&self) -> &'static str {
+    match self {
+      TreeCountingFunction::SiblingIndex => "sibling-index",
+      TreeCountingFunction::SiblingCount => "sibling-count",
+    }
+  }
+
+  fn feature(&self) -> compat::Feature {
+    match self {
+      TreeCountingFunction::SiblingIndex => compat::Feature::SiblingIndex,
+      TreeCountingFunction::SiblingCount => compat::Feature::SiblingCount,
+    }
+  }
+}
+
+impl ToCss for TreeCountingFunction {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str(self.name())?;
+    dest.write_str("()")
+  }
+}
+
+/// A CSS [toggle()](https://drafts.csswg.org/css-values-5/#toggle-notation) function.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct Toggle<'i> {
+  /// The values to cycle through.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub values: Vec<TokenList<'i>>,
+}
+
+impl<'i> Toggle<'i> {
+  fn parse_nested<'t, T>(
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions<T>,
+    depth: usize,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let values = input.parse_comma_separated(|input| {
+      let value = TokenList::parse(input, options, depth + 1)?;
+      if value.0.iter().all(|token| token.is_whitespace()) {
+        return Err(input.new_custom_error(ParserError::InvalidValue));
+      }
+      Ok(value)
+    })?;
+    Ok(Toggle { values })
+  }
+
+  fn to_css<W>(&self, dest: &mut Printer<W>, is_custom_property: bool) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("toggle(")?;
+    let mut first = true;
+    for value in &self.values {
+      if first {
+        first = false;
+      } else {
+        dest.delim(',', false)?;
+      }
+      value.to_css(dest, is_custom_property)?;
+    }
+    dest.write_char(')')
+  }
+
+  fn get_fallback(&self, kind: ColorFallbackKind) -> Self {
+    Toggle {
+      values: self.values.iter().map(|value| value.get_fallback(kind)).collect(),
+    }
+  }
+}
+
 /// A custom CSS function.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "visitor", derive(Visit))]
@@ -1280,7 +2005,7 @@ pub struct Function<'i> {
 }
 
 impl<'i> Function<'i> {
-  fn to_css<W>(&self, dest: &mut Printer<W>, is_custom_property: bool) -> Result<(), PrinterError>
+  pub(crate) fn to_css<W>(&self, dest: &mut Printer<W>, is_custom_property: bool) -> Result<(), PrinterError>
   where
     W: std::fmt::Write,
   {
@@ -6,7 +6,7 @@ use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
 use crate::macros::enum_property;
-use crate::prefixes::{is_flex_2009, Feature};
+use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{Parse, PropertyHandler, ToCss};
@@ -382,6 +382,26 @@ enum_property! {
   }
 }
 
+enum_property! {
+  /// A value for the [reading-flow](https://drafts.csswg.org/css-display-4/#reading-flow) property.
+  pub enum ReadingFlow {
+    /// Items are read in document order.
+    "normal": Normal,
+    /// Flex items are read in the visual order, following the writing mode.
+    "flex-visual": FlexVisual,
+    /// Flex items are read in the order of the flex flow.
+    "flex-flow": FlexFlow,
+    /// Grid items are read row by row, in the visual order.
+    "grid-rows": GridRows,
+    /// Grid items are read column by column, in the visual order.
+    "grid-columns": GridColumns,
+    /// Grid items are read in the order given by the `order` property.
+    "grid-order": GridOrder,
+    /// Items are read in document order, adjusted by the `reading-order` property.
+    "source-order": SourceOrder,
+  }
+}
+
 #[derive(Default)]
 pub(crate) struct DisplayHandler<'i> {
   targets: Option<Browsers>,
@@ -463,17 +483,15 @@ impl<'i> PropertyHandler<'i> for DisplayHandler<'i> {
       }) = display
       {
         if let Some(targets) = self.targets {
-          let prefixes = Feature::DisplayFlex.prefixes_for(targets);
+          let prefixes = targets.flex_prefixes(Feature::DisplayFlex);
 
           // Handle legacy -webkit-box/-moz-box values if needed.
-          if is_flex_2009(targets) {
-            if prefixes.contains(VendorPrefix::WebKit) {
-              dest.push(Property::Display(Display::Pair(DisplayPair {
-                inside: DisplayInside::Box(VendorPrefix::WebKit),
-                outside: outside.clone(),
-                is_list_item: false,
-              })));
-            }
+          if targets.is_flex_2009() {
+            dest.push(Property::Display(Display::Pair(DisplayPair {
+              inside: DisplayInside::Box(VendorPrefix::WebKit),
+              outside,
+              is_list_item: false,
+            })));
 
             if prefixes.contains(VendorPrefix::Moz) {
               dest.push(Property::Display(Display::Pair(DisplayPair {
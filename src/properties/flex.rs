@@ -8,7 +8,7 @@ use crate::context::PropertyHandlerContext;
 use crate::declaration::{DeclarationBlock, DeclarationList};
 use crate::error::{ParserError, PrinterError};
 use crate::macros::*;
-use crate::prefixes::{is_flex_2009, Feature};
+use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FromStandard, Parse, PropertyHandler, Shorthand, ToCss, Zero};
@@ -654,12 +654,12 @@ impl FlexHandler {
             let mut prefix = prefix;
             if prefix.contains(VendorPrefix::None) {
               if let Some(targets) = self.targets {
-                prefix = Feature::$prop.prefixes_for(targets);
+                prefix = targets.flex_prefixes(Feature::$prop);
 
                 // 2009 spec, implemented by webkit and firefox.
                 $(
                   let mut prefixes_2009 = VendorPrefix::empty();
-                  if is_flex_2009(targets) {
+                  if targets.is_flex_2009() {
                     prefixes_2009 |= VendorPrefix::WebKit;
                   }
                   if prefix.contains(VendorPrefix::Moz) {
@@ -716,9 +716,9 @@ impl FlexHandler {
 
     if let Some((direction, _)) = direction {
       if let Some(targets) = self.targets {
-        let prefixes = Feature::FlexDirection.prefixes_for(targets);
+        let prefixes = targets.flex_prefixes(Feature::FlexDirection);
         let mut prefixes_2009 = VendorPrefix::empty();
-        if is_flex_2009(targets) {
+        if targets.is_flex_2009() {
           prefixes_2009 |= VendorPrefix::WebKit;
         }
         if prefixes.contains(VendorPrefix::Moz) {
@@ -738,7 +738,7 @@ impl FlexHandler {
         let mut prefix = intersection;
         if prefix.contains(VendorPrefix::None) {
           if let Some(targets) = self.targets {
-            prefix = Feature::FlexFlow.prefixes_for(targets);
+            prefix = targets.flex_prefixes(Feature::FlexFlow);
             // Firefox only implemented the 2009 spec prefixed.
             prefix.remove(VendorPrefix::Moz);
           }
@@ -760,9 +760,9 @@ impl FlexHandler {
 
     if let Some(targets) = self.targets {
       if let Some((grow, _)) = grow {
-        let prefixes = Feature::FlexGrow.prefixes_for(targets);
+        let prefixes = targets.flex_prefixes(Feature::FlexGrow);
         let mut prefixes_2009 = VendorPrefix::empty();
-        if is_flex_2009(targets) {
+        if targets.is_flex_2009() {
           prefixes_2009 |= VendorPrefix::WebKit;
         }
         if prefixes.contains(VendorPrefix::Moz) {
@@ -782,7 +782,7 @@ impl FlexHandler {
         let mut prefix = intersection;
         if prefix.contains(VendorPrefix::None) {
           if let Some(targets) = self.targets {
-            prefix = Feature::Flex.prefixes_for(targets);
+            prefix = targets.flex_prefixes(Feature::Flex);
             // Firefox only implemented the 2009 spec prefixed.
             prefix.remove(VendorPrefix::Moz);
           }
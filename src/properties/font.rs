@@ -6,9 +6,9 @@ use super::{Property, PropertyId};
 use crate::compat::Feature;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::{DeclarationBlock, DeclarationList};
-use crate::error::{ParserError, PrinterError};
+use crate::error::{ErrorWithLocation, ParserError, PrinterError};
 use crate::macros::*;
-use crate::printer::Printer;
+use crate::printer::{Printer, PrinterOptions};
 use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss};
 use crate::values::number::CSSNumber;
 use crate::values::string::CowArcStr;
@@ -301,6 +301,28 @@ impl Into<Percentage> for &FontStretch {
   }
 }
 
+impl FontStretch {
+  /// Returns the equivalent keyword, which is the only form accepted by the `font` shorthand.
+  fn to_keyword(&self) -> Option<FontStretchKeyword> {
+    use FontStretchKeyword::*;
+    match self {
+      FontStretch::Keyword(keyword) => Some(*keyword),
+      FontStretch::Percentage(Percentage(val)) => Some(match *val {
+        0.5 => UltraCondensed,
+        0.625 => ExtraCondensed,
+        0.75 => Condensed,
+        0.875 => SemiCondensed,
+        1.0 => Normal,
+        1.125 => SemiExpanded,
+        1.25 => Expanded,
+        1.5 => ExtraExpanded,
+        2.0 => UltraExpanded,
+        _ => return None,
+      }),
+    }
+  }
+}
+
 impl ToCss for FontStretch {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -430,7 +452,7 @@ impl<'i> ToCss for FontFamily<'i> {
             return dest.write_str(&id);
           }
         }
-        serialize_string(&val, dest)?;
+        dest.write_string(val)?;
         Ok(())
       }
     }
@@ -657,6 +679,235 @@ impl ToCss for VerticalAlign {
   }
 }
 
+enum_property! {
+  /// A value for the [font-kerning](https://www.w3.org/TR/css-fonts-4/#font-kerning-prop) property.
+  #[derive(Default)]
+  pub enum FontKerning {
+    /// The browser determines whether kerning is applied.
+    #[default]
+    "auto": Auto,
+    /// Kerning is applied.
+    "normal": Normal,
+    /// Kerning is disabled.
+    "none": None,
+  }
+}
+
+enum_property! {
+  /// A value for the [font-optical-sizing](https://www.w3.org/TR/css-fonts-4/#font-optical-sizing-def) property.
+  #[derive(Default)]
+  pub enum FontOpticalSizing {
+    /// The browser may adjust the glyph shapes based on the font size.
+    #[default]
+    "auto": Auto,
+    /// Optical sizing is disabled.
+    "none": None,
+  }
+}
+
+enum_property! {
+  /// A font metric used to normalize the font size, as used in the `font-size-adjust` property.
+  #[derive(Default)]
+  pub enum FontSizeAdjustMetric {
+    /// The x-height divided by the font size.
+    #[default]
+    "ex-height": ExHeight,
+    /// The cap height divided by the font size.
+    "cap-height": CapHeight,
+    /// The advance measure of the "0" glyph divided by the font size.
+    "ch-width": ChWidth,
+    /// The advance measure of the "水" glyph divided by the font size.
+    "ic-width": IcWidth,
+    /// The vertical advance measure of the "水" glyph divided by the font size.
+    "ic-height": IcHeight,
+  }
+}
+
+/// The aspect value within the `font-size-adjust` property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum FontSizeAdjustValue {
+  /// Use the metric of the first available font.
+  FromFont,
+  /// An explicit aspect value.
+  Number(CSSNumber),
+}
+
+impl<'i> Parse<'i> for FontSizeAdjustValue {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("from-font")).is_ok() {
+      return Ok(FontSizeAdjustValue::FromFont);
+    }
+
+    let location = input.current_source_location();
+    let value = CSSNumber::parse(input)?;
+    if value < 0.0 {
+      return Err(location.new_custom_error(ParserError::InvalidValue));
+    }
+    Ok(FontSizeAdjustValue::Number(value))
+  }
+}
+
+impl ToCss for FontSizeAdjustValue {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      FontSizeAdjustValue::FromFont => dest.write_str("from-font"),
+      FontSizeAdjustValue::Number(value) => value.to_css(dest),
+    }
+  }
+}
+
+/// A value for the [font-size-adjust](https://www.w3.org/TR/css-fonts-5/#font-size-adjust-prop) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum FontSizeAdjust {
+  /// No font size adjustment.
+  #[default]
+  None,
+  /// Scale the font size so that the given metric matches the aspect value.
+  Value {
+    /// The font metric to normalize.
+    metric: FontSizeAdjustMetric,
+    /// The aspect value.
+    value: FontSizeAdjustValue,
+  },
+}
+
+impl<'i> Parse<'i> for FontSizeAdjust {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(FontSizeAdjust::None);
+    }
+
+    let metric = input.try_parse(FontSizeAdjustMetric::parse).unwrap_or_default();
+    let value = FontSizeAdjustValue::parse(input)?;
+    Ok(FontSizeAdjust::Value { metric, value })
+  }
+}
+
+impl ToCss for FontSizeAdjust {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      FontSizeAdjust::None => dest.write_str("none"),
+      FontSizeAdjust::Value { metric, value } => {
+        if *metric != FontSizeAdjustMetric::default() {
+          metric.to_css(dest)?;
+          dest.write_char(' ')?;
+        }
+        value.to_css(dest)
+      }
+    }
+  }
+}
+
+/// A value for the [font-variation-settings](https://www.w3.org/TR/css-fonts-4/#font-variation-settings-def) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum FontVariationSettings<'i> {
+  /// No variation axes are set explicitly.
+  #[default]
+  Normal,
+  /// A list of variation axis values.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Axes(Vec<FontVariationAxis<'i>>),
+}
+
+/// A variation axis value within the `font-variation-settings` property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct FontVariationAxis<'i> {
+  /// The four character axis tag.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub tag: CowArcStr<'i>,
+  /// The value of the axis.
+  pub value: CSSNumber,
+}
+
+impl<'i> Parse<'i> for FontVariationSettings<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
+      return Ok(FontVariationSettings::Normal);
+    }
+
+    let axes = input.parse_comma_separated(FontVariationAxis::parse)?;
+    Ok(FontVariationSettings::Axes(axes))
+  }
+}
+
+impl<'i> Parse<'i> for FontVariationAxis<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    let tag = input.expect_string_cloned()?;
+    // Axis tags are exactly four printable ASCII characters.
+    if tag.len() != 4 || !tag.bytes().all(|b| (0x20..=0x7e).contains(&b)) {
+      return Err(location.new_custom_error(ParserError::InvalidValue));
+    }
+
+    let value = CSSNumber::parse(input)?;
+    Ok(FontVariationAxis { tag: tag.into(), value })
+  }
+}
+
+impl<'i> ToCss for FontVariationSettings<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      FontVariationSettings::Normal => dest.write_str("normal"),
+      FontVariationSettings::Axes(axes) => {
+        let len = axes.len();
+        for (idx, axis) in axes.iter().enumerate() {
+          axis.to_css(dest)?;
+          if idx < len - 1 {
+            dest.delim(',', false)?;
+          }
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+impl<'i> ToCss for FontVariationAxis<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_string(&self.tag)?;
+    dest.write_char(' ')?;
+    self.value.to_css(dest)
+  }
+}
+
 define_shorthand! {
   /// A value for the [font](https://www.w3.org/TR/css-fonts-4/#font-prop) shorthand property.
   #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
@@ -776,7 +1027,11 @@ impl<'i> ToCss for Font<'i> {
     }
 
     if self.stretch != FontStretch::default() {
-      self.stretch.to_css(dest)?;
+      // Percentages are not valid in the shorthand, so always serialize a keyword.
+      match self.stretch.to_keyword() {
+        Some(keyword) => keyword.to_css(dest)?,
+        None => self.stretch.to_css(dest)?,
+      }
       dest.write_char(' ')?;
     }
 
@@ -810,6 +1065,10 @@ pub(crate) struct FontHandler<'i> {
   stretch: Option<FontStretch>,
   line_height: Option<LineHeight>,
   variant_caps: Option<FontVariantCaps>,
+  kerning: Option<FontKerning>,
+  optical_sizing: Option<FontOpticalSizing>,
+  size_adjust: Option<FontSizeAdjust>,
+  variation_settings: Option<FontVariationSettings<'i>>,
   has_any: bool,
 }
 
@@ -837,6 +1096,30 @@ impl<'i> PropertyHandler<'i> for FontHandler<'i> {
       FontStretch(val) => property!(stretch, val),
       FontVariantCaps(val) => property!(variant_caps, val),
       LineHeight(val) => property!(line_height, val),
+      FontKerning(val) => property!(kerning, val),
+      FontOpticalSizing(val) => property!(optical_sizing, val),
+      FontSizeAdjust(val) => {
+        if let self::FontSizeAdjust::Value { metric, value } = val {
+          // Older browsers only support a single number, which implies the ex-height metric.
+          let unsupported = if *metric != FontSizeAdjustMetric::ExHeight {
+            Some(metric.as_str())
+          } else if *value == FontSizeAdjustValue::FromFont {
+            Some("from-font")
+          } else {
+            None
+          };
+          if let Some(unsupported) = unsupported {
+            if !context.is_supported(Feature::FontSizeAdjustTwoValue) {
+              context.warnings.push(ErrorWithLocation {
+                kind: ParserError::UnsupportedValue(unsupported.to_owned().into()),
+                loc: context.loc,
+              });
+            }
+          }
+        }
+        property!(size_adjust, val)
+      }
+      FontVariationSettings(val) => property!(variation_settings, val),
       Font(val) => {
         self.family = Some(val.family.clone());
         self.size = Some(val.size.clone());
@@ -845,8 +1128,12 @@ impl<'i> PropertyHandler<'i> for FontHandler<'i> {
         self.stretch = Some(val.stretch.clone());
         self.line_height = Some(val.line_height.clone());
         self.variant_caps = Some(val.variant_caps.clone());
+        // The shorthand also resets these properties to their initial values.
+        self.kerning = Some(Default::default());
+        self.optical_sizing = Some(Default::default());
+        self.size_adjust = Some(Default::default());
+        self.variation_settings = Some(Default::default());
         self.has_any = true;
-        // TODO: reset other properties
       }
       Unparsed(val) if is_font_property(&val.property_id) => {
         self.finalize(dest, context);
@@ -875,6 +1162,10 @@ impl<'i> PropertyHandler<'i> for FontHandler<'i> {
     let stretch = std::mem::take(&mut self.stretch);
     let line_height = std::mem::take(&mut self.line_height);
     let variant_caps = std::mem::take(&mut self.variant_caps);
+    let kerning = std::mem::take(&mut self.kerning);
+    let optical_sizing = std::mem::take(&mut self.optical_sizing);
+    let size_adjust = std::mem::take(&mut self.size_adjust);
+    let variation_settings = std::mem::take(&mut self.variation_settings);
 
     if let Some(family) = &mut family {
       if family.len() > 1 {
@@ -884,64 +1175,112 @@ impl<'i> PropertyHandler<'i> for FontHandler<'i> {
       }
     }
 
-    if family.is_some()
-      && size.is_some()
-      && style.is_some()
-      && weight.is_some()
-      && stretch.is_some()
-      && line_height.is_some()
-      && variant_caps.is_some()
-    {
-      let caps = variant_caps.unwrap();
-      decls.push(Property::Font(Font {
-        family: family.unwrap(),
-        size: size.unwrap(),
-        style: style.unwrap(),
-        weight: weight.unwrap(),
-        stretch: stretch.unwrap(),
-        line_height: line_height.unwrap(),
-        variant_caps: if caps.is_css2() {
-          caps
-        } else {
-          FontVariantCaps::default()
-        },
-      }));
-
-      // The `font` property only accepts CSS 2.1 values for font-variant caps.
-      // If we have a CSS 3+ value, we need to add a separate property.
-      if !caps.is_css2() {
-        decls.push(Property::FontVariantCaps(variant_caps.unwrap()))
-      }
-    } else {
-      if let Some(val) = family {
-        decls.push(Property::FontFamily(val))
-      }
+    // Properties that the `font` shorthand resets but cannot set.
+    let mut reset = Vec::new();
+    if let Some(val) = kerning {
+      reset.push(Property::FontKerning(val));
+    }
+    if let Some(val) = optical_sizing {
+      reset.push(Property::FontOpticalSizing(val));
+    }
+    if let Some(val) = size_adjust {
+      reset.push(Property::FontSizeAdjust(val));
+    }
+    if let Some(val) = variation_settings {
+      reset.push(Property::FontVariationSettings(val));
+    }
 
-      if let Some(val) = size {
-        decls.push(Property::FontSize(val))
-      }
+    let mut longhands = Vec::new();
+    if let Some(val) = &family {
+      longhands.push(Property::FontFamily(val.clone()))
+    }
 
-      if let Some(val) = style {
-        decls.push(Property::FontStyle(val))
-      }
+    if let Some(val) = &size {
+      longhands.push(Property::FontSize(val.clone()))
+    }
 
-      if let Some(val) = variant_caps {
-        decls.push(Property::FontVariantCaps(val))
-      }
+    if let Some(val) = &style {
+      longhands.push(Property::FontStyle(val.clone()))
+    }
 
-      if let Some(val) = weight {
-        decls.push(Property::FontWeight(val))
-      }
+    if let Some(val) = &variant_caps {
+      longhands.push(Property::FontVariantCaps(*val))
+    }
 
-      if let Some(val) = stretch {
-        decls.push(Property::FontStretch(val))
-      }
+    if let Some(val) = &weight {
+      longhands.push(Property::FontWeight(val.clone()))
+    }
+
+    if let Some(val) = &stretch {
+      longhands.push(Property::FontStretch(val.clone()))
+    }
+
+    if let Some(val) = &line_height {
+      longhands.push(Property::LineHeight(val.clone()))
+    }
 
-      if let Some(val) = line_height {
-        decls.push(Property::LineHeight(val))
+    longhands.extend(reset.iter().cloned());
+
+    // The shorthand cannot represent a non-keyword font-stretch value.
+    if let (Some(family), Some(size), Some(style), Some(weight), Some(stretch), Some(line_height), Some(caps)) =
+      (family, size, style, weight, stretch, line_height, variant_caps)
+    {
+      if stretch.to_keyword().is_some() {
+        let mut shorthand = vec![Property::Font(Font {
+          family,
+          size,
+          style,
+          weight,
+          stretch,
+          line_height,
+          variant_caps: if caps.is_css2() {
+            caps
+          } else {
+            FontVariantCaps::default()
+          },
+        })];
+
+        // The `font` property only accepts CSS 2.1 values for font-variant caps.
+        // If we have a CSS 3+ value, we need to add a separate property.
+        if !caps.is_css2() {
+          shorthand.push(Property::FontVariantCaps(caps))
+        }
+
+        // Reset properties only need to be written if they differ from the initial value.
+        shorthand.extend(reset.into_iter().filter(|property| match property {
+          Property::FontKerning(val) => *val != FontKerning::default(),
+          Property::FontOpticalSizing(val) => *val != FontOpticalSizing::default(),
+          Property::FontSizeAdjust(val) => *val != FontSizeAdjust::default(),
+          Property::FontVariationSettings(val) => *val != FontVariationSettings::default(),
+          _ => true,
+        }));
+
+        if serialized_len(&shorthand) <= serialized_len(&longhands) {
+          decls.extend(shorthand);
+          return;
+        }
       }
     }
+
+    decls.extend(longhands);
+  }
+}
+
+fn serialized_len(properties: &[Property]) -> usize {
+  let mut s = String::new();
+  let mut printer = Printer::new(
+    &mut s,
+    PrinterOptions {
+      minify: true,
+      ..PrinterOptions::default()
+    },
+  );
+  for property in properties {
+    if property.to_css(&mut printer, false).is_err() || printer.write_char(';').is_err() {
+      return usize::MAX;
+    }
   }
+  s.len()
 }
 
 const SYSTEM_UI: FontFamily = FontFamily::Generic(GenericFontFamily::SystemUI);
@@ -992,6 +1331,10 @@ fn is_font_property(property_id: &PropertyId) -> bool {
     | PropertyId::FontStretch
     | PropertyId::FontVariantCaps
     | PropertyId::LineHeight
+    | PropertyId::FontKerning
+    | PropertyId::FontOpticalSizing
+    | PropertyId::FontSizeAdjust
+    | PropertyId::FontVariationSettings
     | PropertyId::Font => true,
     _ => false,
   }
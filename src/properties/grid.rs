@@ -2,9 +2,11 @@
 
 #![allow(non_upper_case_globals)]
 
+use crate::compat::Feature;
 use crate::context::PropertyHandlerContext;
+use crate::css_modules::NameKind;
 use crate::declaration::{DeclarationBlock, DeclarationList};
-use crate::error::{Error, ErrorLocation, ParserError, PrinterError, PrinterErrorKind};
+use crate::error::{Error, ErrorLocation, ErrorWithLocation, ParserError, PrinterError, PrinterErrorKind};
 use crate::macros::{define_shorthand, impl_shorthand};
 use crate::printer::Printer;
 use crate::properties::{Property, PropertyId};
@@ -18,6 +20,7 @@ use crate::visitor::Visit;
 use bitflags::bitflags;
 use cssparser::*;
 use smallvec::SmallVec;
+use std::collections::HashMap;
 
 #[cfg(feature = "serde")]
 use crate::serialization::ValueWrapper;
@@ -39,6 +42,65 @@ pub enum TrackSizing<'i> {
   /// A list of grid tracks.
   #[cfg_attr(feature = "serde", serde(borrow))]
   TrackList(TrackList<'i>),
+  /// The grid adopts the tracks of its parent grid in this axis.
+  Subgrid(SubgridLineNames<'i>),
+}
+
+/// The line names of a [subgrid](https://drafts.csswg.org/css-grid-2/#subgrids), as specified
+/// by the optional [`<line-name-list>`](https://drafts.csswg.org/css-grid-2/#typedef-line-name-list)
+/// following the `subgrid` keyword.
+///
+/// See [TrackSizing](TrackSizing).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct SubgridLineNames<'i> {
+  /// A list of line names or `repeat()` functions.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub items: Vec<LineNameListItem<'i>>,
+}
+
+/// Either a list of line names or a `repeat()` function within a subgrid line name list.
+///
+/// See [SubgridLineNames](SubgridLineNames).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum LineNameListItem<'i> {
+  /// A list of line names.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  LineNames(CustomIdentList<'i>),
+  /// A `repeat()` function.
+  NameRepeat(NameRepeat<'i>),
+}
+
+/// A [`<name-repeat>`](https://drafts.csswg.org/css-grid-2/#typedef-name-repeat) value,
+/// representing the `repeat()` function in a subgrid line name list.
+///
+/// See [LineNameListItem](LineNameListItem).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct NameRepeat<'i> {
+  /// The repeat count. Only `auto-fill` and positive integers are allowed.
+  pub count: RepeatCount,
+  /// The line names to repeat.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub line_names: Vec<CustomIdentList<'i>>,
 }
 
 /// A [`<track-list>`](https://drafts.csswg.org/css-grid-2/#typedef-track-list) value,
@@ -426,7 +488,7 @@ fn write_ident<W>(name: &str, dest: &mut Printer<W>) -> Result<(), PrinterError>
 where
   W: std::fmt::Write,
 {
-  if let Some(css_module) = &mut dest.css_module {
+  if let Some(css_module) = dest.css_module.as_ref().filter(|m| m.config.grid) {
     if let Some(last) = css_module.config.pattern.segments.last() {
       if !matches!(last, crate::css_modules::Segment::Local) {
         return Err(Error {
@@ -440,7 +502,7 @@ where
       }
     }
   }
-  dest.write_ident(name)?;
+  dest.write_scoped_ident(name, NameKind::Grid)?;
   Ok(())
 }
 
@@ -518,6 +580,11 @@ impl<'i> Parse<'i> for TrackSizing<'i> {
       return Ok(TrackSizing::None);
     }
 
+    if input.try_parse(|input| input.expect_ident_matching("subgrid")).is_ok() {
+      let line_names = SubgridLineNames::parse(input)?;
+      return Ok(TrackSizing::Subgrid(line_names));
+    }
+
     let track_list = TrackList::parse(input)?;
     Ok(TrackSizing::TrackList(track_list))
   }
@@ -531,6 +598,10 @@ impl<'i> ToCss for TrackSizing<'i> {
     match self {
       TrackSizing::None => dest.write_str("none"),
       TrackSizing::TrackList(list) => list.to_css(dest),
+      TrackSizing::Subgrid(line_names) => {
+        dest.write_str("subgrid")?;
+        line_names.to_css(dest)
+      }
     }
   }
 }
@@ -540,10 +611,99 @@ impl<'i> TrackSizing<'i> {
     match self {
       TrackSizing::None => true,
       TrackSizing::TrackList(list) => list.is_explicit(),
+      TrackSizing::Subgrid(..) => false,
     }
   }
 }
 
+impl<'i> Parse<'i> for SubgridLineNames<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut items = Vec::new();
+    let mut has_auto_fill = false;
+    loop {
+      if let Ok(names) = input.try_parse(parse_line_names) {
+        items.push(LineNameListItem::LineNames(names));
+      } else if let Ok(repeat) = input.try_parse(NameRepeat::parse) {
+        // Only a single auto-fill repeat is allowed.
+        if repeat.count == RepeatCount::AutoFill {
+          if has_auto_fill {
+            return Err(input.new_custom_error(ParserError::InvalidDeclaration));
+          }
+          has_auto_fill = true;
+        }
+        items.push(LineNameListItem::NameRepeat(repeat));
+      } else {
+        break;
+      }
+    }
+
+    Ok(SubgridLineNames { items })
+  }
+}
+
+impl<'i> ToCss for SubgridLineNames<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    for item in &self.items {
+      dest.whitespace()?;
+      match item {
+        LineNameListItem::LineNames(names) => serialize_line_names(names, dest)?,
+        LineNameListItem::NameRepeat(repeat) => repeat.to_css(dest)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<'i> Parse<'i> for NameRepeat<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    input.expect_function_matching("repeat")?;
+    input.parse_nested_block(|input| {
+      let location = input.current_source_location();
+      let count = match RepeatCount::parse(input)? {
+        RepeatCount::Number(n) if n < 1 => return Err(location.new_custom_error(ParserError::InvalidValue)),
+        RepeatCount::AutoFit => return Err(location.new_custom_error(ParserError::InvalidValue)),
+        count => count,
+      };
+      input.expect_comma()?;
+
+      let mut line_names = Vec::new();
+      while let Ok(names) = input.try_parse(parse_line_names) {
+        line_names.push(names);
+      }
+
+      if line_names.is_empty() {
+        return Err(input.new_custom_error(ParserError::InvalidDeclaration));
+      }
+
+      Ok(NameRepeat { count, line_names })
+    })
+  }
+}
+
+impl<'i> ToCss for NameRepeat<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("repeat(")?;
+    self.count.to_css(dest)?;
+    dest.delim(',', false)?;
+    let mut first = true;
+    for names in &self.line_names {
+      if first {
+        first = false;
+      } else {
+        dest.whitespace()?;
+      }
+      serialize_line_names(names, dest)?;
+    }
+    dest.write_char(')')
+  }
+}
+
 impl<'i> Parse<'i> for TrackSizeList {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut res = SmallVec::new();
@@ -623,6 +783,10 @@ impl<'i> Parse<'i> for GridTemplateAreas {
       row += 1;
     }
 
+    if row == 0 || !Self::is_rectangular(columns, &tokens) {
+      return Err(input.new_custom_error(ParserError::InvalidDeclaration));
+    }
+
     Ok(GridTemplateAreas::Areas { columns, areas: tokens })
   }
 }
@@ -661,6 +825,38 @@ impl GridTemplateAreas {
 
     Ok(column)
   }
+
+  /// Returns whether every named area forms a single filled-in rectangle, as required by the spec.
+  /// https://drafts.csswg.org/css-grid-2/#grid-template-areas-property
+  fn is_rectangular(columns: u32, tokens: &[Option<String>]) -> bool {
+    // Bounding box (min row, max row, min column, max column) and cell count for each area.
+    let mut bounds: HashMap<&str, (usize, usize, usize, usize, usize)> = HashMap::new();
+    let columns = columns as usize;
+    for (i, token) in tokens.iter().enumerate() {
+      if let Some(name) = token {
+        let (row, column) = (i / columns, i % columns);
+        let entry = bounds.entry(name.as_str()).or_insert((row, row, column, column, 0));
+        entry.0 = entry.0.min(row);
+        entry.1 = entry.1.max(row);
+        entry.2 = entry.2.min(column);
+        entry.3 = entry.3.max(column);
+        entry.4 += 1;
+      }
+    }
+
+    // If the number of cells matches the size of the bounding box, the area is a filled rectangle.
+    bounds.values().all(|(min_row, max_row, min_column, max_column, count)| {
+      (max_row - min_row + 1) * (max_column - min_column + 1) == *count
+    })
+  }
+
+  /// Returns the number of rows defined by the named areas.
+  fn rows(&self) -> usize {
+    match self {
+      GridTemplateAreas::None => 0,
+      GridTemplateAreas::Areas { columns, areas } => areas.len() / (*columns as usize).max(1),
+    }
+  }
 }
 
 static HTML_SPACE_CHARACTERS: &'static [char] = &['\u{0020}', '\u{0009}', '\u{000a}', '\u{000c}', '\u{000d}'];
@@ -816,6 +1012,10 @@ impl<'i> Parse<'i> for GridTemplate<'i> {
     }
 
     if !tokens.is_empty() {
+      if !GridTemplateAreas::is_rectangular(columns, &tokens) {
+        return Err(input.new_custom_error(ParserError::InvalidDeclaration));
+      }
+
       if line_names.len() == items.len() {
         line_names.push(Default::default());
       }
@@ -961,8 +1161,14 @@ impl<'i> GridTemplate<'i> {
   fn is_valid(rows: &TrackSizing, columns: &TrackSizing, areas: &GridTemplateAreas) -> bool {
     // The `grid-template` shorthand supports only explicit track values (i.e. no `repeat()`)
     // combined with grid-template-areas. If there are no areas, then any track values are allowed.
-    *areas == GridTemplateAreas::None
-      || (*rows != TrackSizing::None && rows.is_explicit() && columns.is_explicit())
+    // Each row of areas is paired with exactly one row track, so the number of rows must also match.
+    match rows {
+      _ if *areas == GridTemplateAreas::None => true,
+      TrackSizing::TrackList(list) => {
+        list.is_explicit() && list.items.len() == areas.rows() && columns.is_explicit()
+      }
+      TrackSizing::None | TrackSizing::Subgrid(..) => false,
+    }
   }
 }
 
@@ -1648,6 +1854,21 @@ impl<'i> PropertyHandler<'i> for GridHandler<'i> {
   ) -> bool {
     use Property::*;
 
+    let uses_subgrid = match property {
+      GridTemplateColumns(tracks) | GridTemplateRows(tracks) => matches!(tracks, TrackSizing::Subgrid(..)),
+      GridTemplate(self::GridTemplate { rows, columns, .. }) | Grid(self::Grid { rows, columns, .. }) => {
+        matches!(rows, TrackSizing::Subgrid(..)) || matches!(columns, TrackSizing::Subgrid(..))
+      }
+      _ => false,
+    };
+
+    if uses_subgrid && !context.is_supported(Feature::Subgrid) {
+      context.warnings.push(ErrorWithLocation {
+        kind: ParserError::UnsupportedValue("subgrid".into()),
+        loc: context.loc,
+      });
+    }
+
     match property {
       GridTemplateColumns(columns) => self.columns = Some(columns.clone()),
       GridTemplateRows(rows) => self.rows = Some(rows.clone()),
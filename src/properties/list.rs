@@ -2,6 +2,7 @@
 
 use super::{Property, PropertyId};
 use crate::context::PropertyHandlerContext;
+use crate::css_modules::NameKind;
 use crate::declaration::{DeclarationBlock, DeclarationList};
 use crate::error::{ParserError, PrinterError};
 use crate::macros::{define_shorthand, enum_property, shorthand_handler, shorthand_property};
@@ -91,6 +92,13 @@ pub enum CounterStyle<'i> {
     serde(borrow, with = "crate::serialization::ValueWrapper::<CustomIdent>")
   )]
   Name(CustomIdent<'i>),
+  /// A reference to a custom counter style using the CSS modules `global()` function,
+  /// e.g. `global(thumbs)`. The name is not scoped.
+  #[cfg_attr(
+    feature = "serde",
+    serde(borrow, with = "crate::serialization::ValueWrapper::<CustomIdent>")
+  )]
+  Global(CustomIdent<'i>),
   /// An inline [`symbols()`](https://www.w3.org/TR/css-counter-styles-3/#symbols-function) definition.
   Symbols {
     /// The counter system.
@@ -190,6 +198,11 @@ impl<'i> Parse<'i> for CounterStyle<'i> {
       });
     }
 
+    if input.try_parse(|input| input.expect_function_matching("global")).is_ok() {
+      let name = input.parse_nested_block(CustomIdent::parse)?;
+      return Ok(CounterStyle::Global(name));
+    }
+
     let name = CustomIdent::parse(input)?;
     Ok(CounterStyle::Name(name))
   }
@@ -203,11 +216,12 @@ impl ToCss for CounterStyle<'_> {
     match self {
       CounterStyle::Predefined(style) => style.to_css(dest),
       CounterStyle::Name(name) => {
-        if let Some(css_module) = &mut dest.css_module {
-          css_module.reference(&name.0, dest.loc.source_index)
+        if let Some(css_module) = dest.css_module.as_mut().filter(|m| m.config.counter_style) {
+          css_module.reference(&name.0, dest.loc)
         }
-        name.to_css(dest)
+        name.to_css_scoped(dest, NameKind::CounterStyle)
       }
+      CounterStyle::Global(name) => name.to_css_unscoped(dest),
       CounterStyle::Symbols { system: t, symbols } => {
         dest.write_str("symbols(")?;
         let mut needs_space = false;
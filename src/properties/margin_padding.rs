@@ -5,7 +5,8 @@ use crate::error::{ParserError, PrinterError};
 use crate::logical::PropertyCategory;
 use crate::macros::{define_shorthand, rect_shorthand, size_shorthand};
 use crate::printer::Printer;
-use crate::properties::{Property, PropertyId};
+use crate::properties::{position::InsetValue, Property, PropertyId};
+use crate::selector::Direction;
 use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss};
 use crate::values::{length::LengthPercentageOrAuto, rect::Rect, size::Size2D};
 #[cfg(feature = "visitor")]
@@ -54,7 +55,8 @@ rect_shorthand! {
 
 rect_shorthand! {
   /// A value for the [inset](https://drafts.csswg.org/css-logical/#propdef-inset) shorthand property.
-  pub struct Inset<LengthPercentageOrAuto> {
+  #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+  pub struct Inset<'i, InsetValue<'i>> {
     Top,
     Right,
     Bottom,
@@ -144,7 +146,8 @@ size_shorthand! {
 
 size_shorthand! {
   /// A value for the [inset-block](https://drafts.csswg.org/css-logical/#propdef-inset-block) shorthand property.
-  pub struct InsetBlock<LengthPercentageOrAuto> {
+  #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+  pub struct InsetBlock<'i, InsetValue<'i>> {
      /// The block start value.
     block_start: InsetBlockStart,
     /// The block end value.
@@ -154,7 +157,8 @@ size_shorthand! {
 
 size_shorthand! {
   /// A value for the [inset-inline](https://drafts.csswg.org/css-logical/#propdef-inset-inline) shorthand property.
-  pub struct InsetInline<LengthPercentageOrAuto> {
+  #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+  pub struct InsetInline<'i, InsetValue<'i>> {
     /// The inline start value.
     inline_start: InsetInlineStart,
     /// The inline end value.
@@ -163,29 +167,42 @@ size_shorthand! {
 }
 
 macro_rules! side_handler {
-  ($name: ident, $top: ident, $bottom: ident, $left: ident, $right: ident, $block_start: ident, $block_end: ident, $inline_start: ident, $inline_end: ident, $shorthand: ident, $block_shorthand: ident, $inline_shorthand: ident, $logical_shorthand: literal $(, $feature: ident, $shorthand_feature: ident)?) => {
+  ($name: ident, $value: ty, $top: ident, $bottom: ident, $left: ident, $right: ident, $block_start: ident, $block_end: ident, $inline_start: ident, $inline_end: ident, $shorthand: ident, $block_shorthand: ident, $inline_shorthand: ident, $logical_shorthand: literal $(, $feature: ident, $shorthand_feature: ident)?) => {
     #[derive(Debug, Default)]
     pub(crate) struct $name<'i> {
-      top: Option<LengthPercentageOrAuto>,
-      bottom: Option<LengthPercentageOrAuto>,
-      left: Option<LengthPercentageOrAuto>,
-      right: Option<LengthPercentageOrAuto>,
+      top: Option<$value>,
+      bottom: Option<$value>,
+      left: Option<$value>,
+      right: Option<$value>,
       block_start: Option<Property<'i>>,
       block_end: Option<Property<'i>>,
       inline_start: Option<Property<'i>>,
       inline_end: Option<Property<'i>>,
       has_any: bool,
-      category: PropertyCategory
+      category: PropertyCategory,
+      logical_first: bool
     }
 
     impl<'i> PropertyHandler<'i> for $name<'i> {
       fn handle_property(&mut self, property: &Property<'i>, dest: &mut DeclarationList<'i>, context: &mut PropertyHandlerContext<'i, '_>) -> bool {
         use Property::*;
 
+        // When logical properties must be compiled away, block values always map to top and bottom,
+        // and inline values map to left and right if the document direction is known. Handle these
+        // as physical properties so they can be merged with other sides, e.g. into a shorthand.
+        let logical_supported = true $(&& context.is_supported(Feature::$feature))?;
+        let inline_direction = if logical_supported { None } else { context.direction };
+
         macro_rules! property {
-          ($key: ident, $val: ident, $category: ident) => {{
+          ($key: ident, $val: expr, $category: ident) => {{
             if PropertyCategory::$category != self.category {
-              self.flush(dest, context);
+              // Physical properties following logical ones are kept pending rather than flushing,
+              // so that logical properties they fully override can be removed.
+              if self.category == PropertyCategory::Logical && PropertyCategory::$category == PropertyCategory::Physical {
+                self.logical_first = true;
+              } else {
+                self.flush(dest, context);
+              }
             }
             self.$key = Some($val.clone());
             self.category = PropertyCategory::$category;
@@ -205,11 +222,33 @@ macro_rules! side_handler {
           }};
         }
 
+        macro_rules! inline_property {
+          ($ltr: ident, $rtl: ident, $val: expr) => {
+            if inline_direction == Some(Direction::Ltr) {
+              property!($ltr, $val, Physical)
+            } else {
+              property!($rtl, $val, Physical)
+            }
+          };
+        }
+
         match &property {
           $top(val) => property!(top, val, Physical),
           $bottom(val) => property!(bottom, val, Physical),
           $left(val) => property!(left, val, Physical),
           $right(val) => property!(right, val, Physical),
+          $block_start(val) if !logical_supported => property!(top, val, Physical),
+          $block_end(val) if !logical_supported => property!(bottom, val, Physical),
+          $block_shorthand(val) if !logical_supported => {
+            property!(top, val.block_start, Physical);
+            property!(bottom, val.block_end, Physical);
+          }
+          $inline_start(val) if inline_direction.is_some() => inline_property!(left, right, val),
+          $inline_end(val) if inline_direction.is_some() => inline_property!(right, left, val),
+          $inline_shorthand(val) if inline_direction.is_some() => {
+            inline_property!(left, right, val.inline_start);
+            inline_property!(right, left, val.inline_end);
+          }
           $block_start(_) => logical_property!(block_start, property.clone()),
           $block_end(_) => logical_property!(block_end, property.clone()),
           $inline_start(_) => logical_property!(inline_start, property.clone()),
@@ -232,6 +271,7 @@ macro_rules! side_handler {
             self.block_end = None;
             self.inline_start = None;
             self.inline_end = None;
+            self.category = PropertyCategory::Physical;
             self.has_any = true;
           }
           Unparsed(val) if matches!(val.property_id, PropertyId::$top | PropertyId::$bottom | PropertyId::$left | PropertyId::$right | PropertyId::$block_start | PropertyId::$block_end | PropertyId::$inline_start | PropertyId::$inline_end | PropertyId::$block_shorthand | PropertyId::$inline_shorthand | PropertyId::$shorthand) => {
@@ -266,12 +306,38 @@ macro_rules! side_handler {
         }
 
         self.has_any = false;
+        let logical_supported = true $(&& context.is_supported(Feature::$feature))?;
 
+        if std::mem::take(&mut self.logical_first) {
+          // The logical properties were declared before the physical ones. A logical property maps to
+          // different sides depending on the writing mode and direction, so it can only be removed if
+          // all of the sides it may map to are overridden. The rest must be written first to preserve
+          // the cascade order.
+          let (top, bottom, left, right) = (self.top.is_some(), self.bottom.is_some(), self.left.is_some(), self.right.is_some());
+          if top && left && right {
+            self.block_start = None;
+          }
+          if bottom && left && right {
+            self.block_end = None;
+          }
+          if top && bottom && left && right {
+            self.inline_start = None;
+            self.inline_end = None;
+          }
+
+          self.flush_logical(dest, context, logical_supported);
+          self.flush_physical(dest, logical_supported);
+        } else {
+          self.flush_physical(dest, logical_supported);
+          self.flush_logical(dest, context, logical_supported);
+        }
+      }
+
+      fn flush_physical(&mut self, dest: &mut DeclarationList<'i>, logical_supported: bool) {
         let top = std::mem::take(&mut self.top);
         let bottom = std::mem::take(&mut self.bottom);
         let left = std::mem::take(&mut self.left);
         let right = std::mem::take(&mut self.right);
-        let logical_supported = true $(&& context.is_supported(Feature::$feature))?;
 
         if (!$logical_shorthand || logical_supported) && top.is_some() && bottom.is_some() && left.is_some() && right.is_some() {
           dest.push(Property::$shorthand($shorthand {
@@ -297,7 +363,9 @@ macro_rules! side_handler {
             dest.push(Property::$right(val));
           }
         }
+      }
 
+      fn flush_logical(&mut self, dest: &mut DeclarationList<'i>, context: &mut PropertyHandlerContext<'i, '_>, logical_supported: bool) {
         let block_start = std::mem::take(&mut self.block_start);
         let block_end = std::mem::take(&mut self.block_end);
         let inline_start = std::mem::take(&mut self.inline_start);
@@ -356,12 +424,14 @@ macro_rules! side_handler {
                 match $val {
                   Some(Property::$logical(val)) => {
                     context.add_logical_rule(
+                      dest,
                       Property::$ltr(val.clone()),
                       Property::$rtl(val)
                     );
                   }
                   Some(Property::Unparsed(val)) => {
                     context.add_logical_rule(
+                      dest,
                       Property::Unparsed(val.with_property_id(PropertyId::$ltr)),
                       Property::Unparsed(val.with_property_id(PropertyId::$rtl))
                     );
@@ -382,6 +452,7 @@ macro_rules! side_handler {
 
 side_handler!(
   MarginHandler,
+  LengthPercentageOrAuto,
   MarginTop,
   MarginBottom,
   MarginLeft,
@@ -400,6 +471,7 @@ side_handler!(
 
 side_handler!(
   PaddingHandler,
+  LengthPercentageOrAuto,
   PaddingTop,
   PaddingBottom,
   PaddingLeft,
@@ -418,6 +490,7 @@ side_handler!(
 
 side_handler!(
   ScrollMarginHandler,
+  LengthPercentageOrAuto,
   ScrollMarginTop,
   ScrollMarginBottom,
   ScrollMarginLeft,
@@ -434,6 +507,7 @@ side_handler!(
 
 side_handler!(
   ScrollPaddingHandler,
+  LengthPercentageOrAuto,
   ScrollPaddingTop,
   ScrollPaddingBottom,
   ScrollPaddingLeft,
@@ -450,6 +524,7 @@ side_handler!(
 
 side_handler!(
   InsetHandler,
+  InsetValue<'i>,
   Top,
   Bottom,
   Left,
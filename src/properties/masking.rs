@@ -14,8 +14,9 @@ use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, PropertyHandler, Shorthand, ToCss};
 use crate::values::image::ImageFallback;
 use crate::values::length::LengthOrNumber;
+use crate::values::position::{HorizontalPosition, Position, VerticalPosition};
 use crate::values::rect::Rect;
-use crate::values::{image::Image, position::Position, shape::BasicShape, url::Url};
+use crate::values::{image::Image, shape::BasicShape, url::Url};
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
@@ -565,12 +566,16 @@ impl<'i> Into<BorderImage<'i>> for MaskBorder<'i> {
 pub(crate) struct MaskHandler<'i> {
   images: Option<(SmallVec<[Image<'i>; 1]>, VendorPrefix)>,
   positions: Option<(SmallVec<[Position; 1]>, VendorPrefix)>,
+  position_x: Option<SmallVec<[HorizontalPosition; 1]>>,
+  position_y: Option<SmallVec<[VerticalPosition; 1]>>,
   sizes: Option<(SmallVec<[BackgroundSize; 1]>, VendorPrefix)>,
   repeats: Option<(SmallVec<[BackgroundRepeat; 1]>, VendorPrefix)>,
   clips: Option<(SmallVec<[MaskClip; 1]>, VendorPrefix)>,
   origins: Option<(SmallVec<[GeometryBox; 1]>, VendorPrefix)>,
   composites: Option<SmallVec<[MaskComposite; 1]>>,
   modes: Option<SmallVec<[MaskMode; 1]>>,
+  webkit_composites: Option<SmallVec<[WebKitMaskComposite; 1]>>,
+  webkit_source_types: Option<SmallVec<[WebKitMaskSourceType; 1]>>,
   border_source: Option<(Image<'i>, VendorPrefix)>,
   border_mode: Option<MaskBorderMode>,
   border_slice: Option<(BorderImageSlice, VendorPrefix)>,
@@ -639,6 +644,36 @@ impl<'i> PropertyHandler<'i> for MaskHandler<'i> {
       };
     }
 
+    macro_rules! reset_positions {
+      ($vp: expr) => {
+        // The unprefixed shorthands override mask-position-x and mask-position-y.
+        // Otherwise, flush so that the order of the properties is preserved.
+        if self.position_x.is_some() || self.position_y.is_some() {
+          if $vp.contains(VendorPrefix::None) {
+            self.position_x = None;
+            self.position_y = None;
+          } else {
+            self.flush_mask(dest, context);
+          }
+        }
+      };
+    }
+
+    macro_rules! position_component {
+      ($prop: ident, $key: ident, $val: expr) => {{
+        // Update the existing mask-position value in place if possible, otherwise
+        // store the component separately so it can be emitted after it.
+        match &mut self.positions {
+          Some((positions, VendorPrefix::None)) if positions.len() == $val.len() => {
+            for (position, v) in positions.iter_mut().zip($val.iter()) {
+              position.$key = v.clone();
+            }
+          }
+          _ => self.$prop = Some($val.clone()),
+        }
+      }};
+    }
+
     match property {
       Property::MaskImage(val, vp) => {
         if Image::should_preserve_fallbacks(val, self.images.as_ref().map(|v| &v.0), context.targets) {
@@ -646,14 +681,26 @@ impl<'i> PropertyHandler<'i> for MaskHandler<'i> {
         }
         property!(images, val, vp)
       }
-      Property::MaskPosition(val, vp) => property!(positions, val, vp),
+      Property::MaskPosition(val, vp) => {
+        reset_positions!(vp);
+        property!(positions, val, vp)
+      }
+      Property::MaskPositionX(val) => position_component!(position_x, x, val),
+      Property::MaskPositionY(val) => position_component!(position_y, y, val),
       Property::MaskSize(val, vp) => property!(sizes, val, vp),
       Property::MaskRepeat(val, vp) => property!(repeats, val, vp),
       Property::MaskClip(val, vp) => property!(clips, val, vp),
       Property::MaskOrigin(val, vp) => property!(origins, val, vp),
       Property::MaskComposite(val) => self.composites = Some(val.clone()),
       Property::MaskMode(val) => self.modes = Some(val.clone()),
+      Property::WebKitMaskComposite(val) => self.webkit_composites = Some(val.clone()),
+      Property::WebKitMaskSourceType(val, _) => self.webkit_source_types = Some(val.clone()),
       Property::Mask(val, prefix) => {
+        reset_positions!(prefix);
+        if self.webkit_composites.is_some() || self.webkit_source_types.is_some() {
+          self.flush_mask(dest, context);
+        }
+
         let images = val.iter().map(|b| b.image.clone()).collect();
         if Image::should_preserve_fallbacks(&images, self.images.as_ref().map(|v| &v.0), context.targets) {
           self.finalize(dest, context)
@@ -686,6 +733,7 @@ impl<'i> PropertyHandler<'i> for MaskHandler<'i> {
         property!(origins, &origins, prefix);
       }
       Property::Unparsed(val) if is_mask_property(&val.property_id) => {
+        self.flush_mask(dest, context);
         let mut unparsed = val.get_prefixed(context.targets, Feature::Mask);
         context.add_unparsed_fallbacks(&mut unparsed);
         dest.push(Property::Unparsed(unparsed));
@@ -709,6 +757,8 @@ impl<'i> PropertyHandler<'i> for MaskHandler<'i> {
         border_shorthand!(val, VendorPrefix::WebKit);
       }
       Property::Unparsed(val) if is_mask_border_property(&val.property_id) => {
+        self.flush_mask_border(dest, context);
+
         // Add vendor prefixes and expand color fallbacks.
         let mut val = val.clone();
         let mut prefix = val.property_id.prefix();
@@ -759,6 +809,10 @@ impl<'i> MaskHandler<'i> {
     let mut origins = std::mem::take(&mut self.origins);
     let mut composites = std::mem::take(&mut self.composites);
     let mut modes = std::mem::take(&mut self.modes);
+    let position_x = std::mem::take(&mut self.position_x);
+    let position_y = std::mem::take(&mut self.position_y);
+    let webkit_composites = std::mem::take(&mut self.webkit_composites);
+    let webkit_source_types = std::mem::take(&mut self.webkit_source_types);
 
     if let (
       Some((images, images_vp)),
@@ -915,41 +969,61 @@ impl<'i> MaskHandler<'i> {
     }
 
     prop!(positions, MaskPosition);
+
+    match (position_x, position_y) {
+      (Some(x), Some(y)) if x.len() == y.len() => {
+        let positions = Some((
+          izip!(x, y).map(|(x, y)| Position { x, y }).collect(),
+          VendorPrefix::None,
+        ));
+        prop!(positions, MaskPosition);
+      }
+      (x, y) => {
+        if let Some(x) = x {
+          dest.push(Property::MaskPositionX(x));
+        }
+        if let Some(y) = y {
+          dest.push(Property::MaskPositionY(y));
+        }
+      }
+    }
+
     prop!(sizes, MaskSize);
     prop!(repeats, MaskRepeat);
     prop!(clips, MaskClip);
     prop!(origins, MaskOrigin);
 
-    if let Some(composites) = composites {
-      let prefix = if let Some(targets) = context.targets {
-        Feature::MaskComposite.prefixes_for(targets)
-      } else {
-        VendorPrefix::None
-      };
-
-      if prefix.contains(VendorPrefix::WebKit) {
+    // An explicit -webkit-mask-composite or -webkit-mask-source-type is preserved as is,
+    // rather than generating one from the standard property.
+    if let Some(webkit_composites) = webkit_composites {
+      dest.push(Property::WebKitMaskComposite(webkit_composites));
+    } else if let (Some(composites), Some(targets)) = (&composites, context.targets) {
+      if Feature::MaskComposite.prefixes_for(targets).contains(VendorPrefix::WebKit) {
         dest.push(Property::WebKitMaskComposite(
           composites.iter().map(|c| (*c).into()).collect(),
         ));
       }
+    }
 
+    if let Some(composites) = composites {
       dest.push(Property::MaskComposite(composites))
     }
 
-    if let Some(modes) = modes {
-      let prefix = if let Some(targets) = context.targets {
-        Feature::Mask.prefixes_for(targets)
-      } else {
-        VendorPrefix::None
-      };
-
-      if prefix.contains(VendorPrefix::WebKit) {
+    if let Some(webkit_source_types) = webkit_source_types {
+      dest.push(Property::WebKitMaskSourceType(
+        webkit_source_types,
+        VendorPrefix::WebKit,
+      ));
+    } else if let (Some(modes), Some(targets)) = (&modes, context.targets) {
+      if Feature::Mask.prefixes_for(targets).contains(VendorPrefix::WebKit) {
         dest.push(Property::WebKitMaskSourceType(
           modes.iter().map(|c| (*c).into()).collect(),
           VendorPrefix::WebKit,
         ));
       }
+    }
 
+    if let Some(modes) = modes {
       dest.push(Property::MaskMode(modes))
     }
   }
@@ -1153,6 +1227,8 @@ fn is_mask_property(property_id: &PropertyId) -> bool {
   match property_id {
     PropertyId::MaskImage(_)
     | PropertyId::MaskPosition(_)
+    | PropertyId::MaskPositionX
+    | PropertyId::MaskPositionY
     | PropertyId::MaskSize(_)
     | PropertyId::MaskRepeat(_)
     | PropertyId::MaskClip(_)
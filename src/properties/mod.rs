@@ -43,6 +43,7 @@
 //!   Property::Background(smallvec![Background {
 //!     image: Image::Url(Url {
 //!       url: "img.png".into(),
+//!       modifiers: Vec::new(),
 //!       loc: Location { line: 1, column: 1 }
 //!     }),
 //!     color: CssColor::RGBA(cssparser::RGBA {
@@ -91,6 +92,7 @@
 #![deny(missing_docs)]
 
 pub mod align;
+pub mod anchor;
 pub mod animation;
 pub mod background;
 pub mod border;
@@ -98,6 +100,7 @@ pub mod border_image;
 pub mod border_radius;
 pub mod box_shadow;
 pub mod contain;
+pub mod content;
 pub mod css_modules;
 pub mod custom;
 pub mod display;
@@ -109,16 +112,20 @@ pub mod grid;
 pub mod list;
 pub(crate) mod margin_padding;
 pub mod masking;
+pub mod motion;
 pub mod outline;
 pub mod overflow;
 pub mod position;
 pub(crate) mod prefix_handler;
+pub mod scroll_snap;
 pub mod size;
 pub mod svg;
 pub mod text;
 pub mod transform;
 pub mod transition;
 pub mod ui;
+pub mod view_transition;
+pub mod will_change;
 
 use crate::declaration::DeclarationBlock;
 use crate::error::{ParserError, PrinterError};
@@ -132,13 +139,14 @@ use crate::traits::{Parse, ParseWithOptions, Shorthand, ToCss};
 use crate::values::number::{CSSInteger, CSSNumber};
 use crate::values::string::CowArcStr;
 use crate::values::{
-  alpha::*, color::*, easing::EasingFunction, ident::DashedIdentReference, image::*, length::*, position::*,
-  rect::*, shape::FillRule, size::Size2D, time::Time,
+  alpha::*, color::*, easing::EasingFunction, ident::DashedIdentReference, ident::NoneOrCustomIdentList, image::*,
+  length::*, position::*, rect::*, shape::FillRule, size::Size2D, time::Time,
 };
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
 use align::*;
+use anchor::*;
 use animation::*;
 use background::*;
 use border::*;
@@ -146,6 +154,7 @@ use border_image::*;
 use border_radius::*;
 use box_shadow::*;
 use contain::*;
+use content::*;
 use css_modules::*;
 use cssparser::*;
 use custom::*;
@@ -158,8 +167,10 @@ use grid::*;
 use list::*;
 use margin_padding::*;
 use masking::*;
+use motion::*;
 use outline::*;
 use overflow::*;
+use scroll_snap::*;
 use size::*;
 use smallvec::{smallvec, SmallVec};
 use svg::*;
@@ -167,6 +178,8 @@ use text::*;
 use transform::*;
 use transition::*;
 use ui::*;
+use view_transition::*;
+use will_change::*;
 
 macro_rules! define_properties {
   (
@@ -224,7 +237,10 @@ macro_rules! define_properties {
           (VendorPrefix::None, name_ref)
         };
 
+        // Some non-standard properties (e.g. -webkit-mask-composite) are defined with
+        // their prefix included in the name, so fall back to the full name.
         Self::from_name_and_prefix(name_ref, prefix)
+          .or_else(|_| Self::from_name_and_prefix(name.as_ref(), VendorPrefix::None))
           .unwrap_or_else(|_| PropertyId::Custom(name.into()))
       }
     }
@@ -667,6 +683,8 @@ macro_rules! define_properties {
       )+
       /// An unparsed property.
       Unparsed(UnparsedProperty<'i>),
+      /// A property whose value is a typed `attr()` function.
+      Attr(AttrProperty<'i>),
       /// A custom or unknown property.
       Custom(CustomProperty<'i>),
     }
@@ -696,7 +714,17 @@ macro_rules! define_properties {
         // and stored as an enum rather than a string. This lets property handlers more easily deal with it.
         // Ideally we'd only do this if var() or env() references were seen, but err on the safe side for now.
         input.reset(&state);
-        return Ok(Property::Unparsed(UnparsedProperty::parse(property_id, input, options)?))
+        if let Some(attr) = AttrProperty::parse(&property_id, input, options) {
+          return Ok(Property::Attr(attr))
+        }
+
+        let location = input.current_source_location();
+        let is_valid = is_valid_attr_value(&property_id, input, options);
+        let unparsed = UnparsedProperty::parse(property_id, input, options)?;
+        if !is_valid {
+          options.warn(location.new_custom_error(ParserError::InvalidValue));
+        }
+        return Ok(Property::Unparsed(unparsed))
       }
 
       /// Returns the property id for this property.
@@ -709,6 +737,7 @@ macro_rules! define_properties {
             $property(_, $(vp_name!($vp, p))?) => PropertyId::$property$((*vp_name!($vp, p)))?,
           )+
           Unparsed(unparsed) => unparsed.property_id.clone(),
+          Attr(attr) => attr.property_id.clone(),
           Custom(custom) => PropertyId::Custom(custom.name.clone())
         }
       }
@@ -759,6 +788,9 @@ macro_rules! define_properties {
           Unparsed(unparsed) => {
             unparsed.value.to_css(dest, false)
           }
+          Attr(attr) => {
+            attr.value.to_css(dest, false)
+          }
           Custom(custom) => {
             custom.value.to_css(dest, matches!(custom.name, CustomPropertyName::Custom(..)))
           }
@@ -822,6 +854,13 @@ macro_rules! define_properties {
             }
             (unparsed.property_id.name(), prefix)
           },
+          Attr(attr) => {
+            let mut prefix = attr.property_id.prefix();
+            if prefix.is_empty() {
+              prefix = VendorPrefix::None;
+            }
+            (attr.property_id.name(), prefix)
+          },
           Custom(custom) => {
             custom.name.to_css(dest)?;
             dest.delim(':', false)?;
@@ -911,6 +950,12 @@ macro_rules! define_properties {
             s.serialize_field("value", unparsed)?;
             return s.end()
           }
+          Attr(attr) => {
+            let mut s = serializer.serialize_struct("Property", 2)?;
+            s.serialize_field("property", "attr")?;
+            s.serialize_field("value", attr)?;
+            return s.end()
+          }
           Custom(unparsed) => {
             let mut s = serializer.serialize_struct("Property", 2)?;
             s.serialize_field("property", "custom")?;
@@ -1042,6 +1087,9 @@ macro_rules! define_properties {
             if name.as_ref() == "unparsed" {
               let value = UnparsedProperty::deserialize(deserializer)?;
               Ok(Property::Unparsed(value))
+            } else if name.as_ref() == "attr" {
+              let value = AttrProperty::deserialize(deserializer)?;
+              Ok(Property::Attr(value))
             } else {
               let value = CustomProperty::deserialize(deserializer)?;
               Ok(Property::Custom(value))
@@ -1121,6 +1169,19 @@ macro_rules! define_properties {
 
                 T::json_schema(gen)
               },
+              {
+                property!("attr");
+
+                #[derive(schemars::JsonSchema)]
+                struct T<'i> {
+                  #[schemars(rename = "property", schema_with = "property")]
+                  _property: &'i u8,
+                  #[schemars(rename = "value")]
+                  _value: AttrProperty<'i>,
+                }
+
+                T::json_schema(gen)
+              },
               {
                 property!("custom");
 
@@ -1166,39 +1227,49 @@ define_properties! {
   "color": Color(CssColor),
   "display": Display(Display),
   "visibility": Visibility(Visibility),
-
-  "width": Width(Size) [logical_group: Size, category: Physical],
-  "height": Height(Size) [logical_group: Size, category: Physical],
-  "min-width": MinWidth(Size) [logical_group: MinSize, category: Physical],
-  "min-height": MinHeight(Size) [logical_group: MinSize, category: Physical],
-  "max-width": MaxWidth(MaxSize) [logical_group: MaxSize, category: Physical],
-  "max-height": MaxHeight(MaxSize) [logical_group: MaxSize, category: Physical],
-  "block-size": BlockSize(Size) [logical_group: Size, category: Logical],
-  "inline-size": InlineSize(Size) [logical_group: Size, category: Logical],
-  "min-block-size": MinBlockSize(Size) [logical_group: MinSize, category: Logical],
-  "min-inline-size": MinInlineSize(Size) [logical_group: MinSize, category: Logical],
-  "max-block-size": MaxBlockSize(MaxSize) [logical_group: MaxSize, category: Logical],
-  "max-inline-size": MaxInlineSize(MaxSize) [logical_group: MaxSize, category: Logical],
+  "reading-flow": ReadingFlow(ReadingFlow),
+  "reading-order": ReadingOrder(CSSInteger),
+
+  "width": Width(Size<'i>) [logical_group: Size, category: Physical],
+  "height": Height(Size<'i>) [logical_group: Size, category: Physical],
+  "min-width": MinWidth(Size<'i>) [logical_group: MinSize, category: Physical],
+  "min-height": MinHeight(Size<'i>) [logical_group: MinSize, category: Physical],
+  "max-width": MaxWidth(MaxSize<'i>) [logical_group: MaxSize, category: Physical],
+  "max-height": MaxHeight(MaxSize<'i>) [logical_group: MaxSize, category: Physical],
+  "block-size": BlockSize(Size<'i>) [logical_group: Size, category: Logical],
+  "inline-size": InlineSize(Size<'i>) [logical_group: Size, category: Logical],
+  "min-block-size": MinBlockSize(Size<'i>) [logical_group: MinSize, category: Logical],
+  "min-inline-size": MinInlineSize(Size<'i>) [logical_group: MinSize, category: Logical],
+  "max-block-size": MaxBlockSize(MaxSize<'i>) [logical_group: MaxSize, category: Logical],
+  "max-inline-size": MaxInlineSize(MaxSize<'i>) [logical_group: MaxSize, category: Logical],
   "box-sizing": BoxSizing(BoxSizing, VendorPrefix) / WebKit / Moz,
 
+  // https://drafts.csswg.org/css-sizing-4/#intrinsic-size-override
+  "contain-intrinsic-width": ContainIntrinsicWidth(ContainIntrinsicLength) [logical_group: ContainIntrinsicSize, category: Physical],
+  "contain-intrinsic-height": ContainIntrinsicHeight(ContainIntrinsicLength) [logical_group: ContainIntrinsicSize, category: Physical],
+  "contain-intrinsic-block-size": ContainIntrinsicBlockSize(ContainIntrinsicLength) [logical_group: ContainIntrinsicSize, category: Logical],
+  "contain-intrinsic-inline-size": ContainIntrinsicInlineSize(ContainIntrinsicLength) [logical_group: ContainIntrinsicSize, category: Logical],
+  "contain-intrinsic-size": ContainIntrinsicSize(ContainIntrinsicSize) shorthand: true,
+
   "overflow": Overflow(Overflow) shorthand: true,
   "overflow-x": OverflowX(OverflowKeyword),
   "overflow-y": OverflowY(OverflowKeyword),
+  "overflow-clip-margin": OverflowClipMargin(OverflowClipMargin),
   "text-overflow": TextOverflow(TextOverflow, VendorPrefix) / O,
 
   // https://www.w3.org/TR/2020/WD-css-position-3-20200519
   "position": Position(position::Position),
-  "top": Top(LengthPercentageOrAuto) [logical_group: Inset, category: Physical],
-  "bottom": Bottom(LengthPercentageOrAuto) [logical_group: Inset, category: Physical],
-  "left": Left(LengthPercentageOrAuto) [logical_group: Inset, category: Physical],
-  "right": Right(LengthPercentageOrAuto) [logical_group: Inset, category: Physical],
-  "inset-block-start": InsetBlockStart(LengthPercentageOrAuto) [logical_group: Inset, category: Logical],
-  "inset-block-end": InsetBlockEnd(LengthPercentageOrAuto) [logical_group: Inset, category: Logical],
-  "inset-inline-start": InsetInlineStart(LengthPercentageOrAuto) [logical_group: Inset, category: Logical],
-  "inset-inline-end": InsetInlineEnd(LengthPercentageOrAuto) [logical_group: Inset, category: Logical],
-  "inset-block": InsetBlock(InsetBlock) shorthand: true,
-  "inset-inline": InsetInline(InsetInline) shorthand: true,
-  "inset": Inset(Inset) shorthand: true,
+  "top": Top(position::InsetValue<'i>) [logical_group: Inset, category: Physical],
+  "bottom": Bottom(position::InsetValue<'i>) [logical_group: Inset, category: Physical],
+  "left": Left(position::InsetValue<'i>) [logical_group: Inset, category: Physical],
+  "right": Right(position::InsetValue<'i>) [logical_group: Inset, category: Physical],
+  "inset-block-start": InsetBlockStart(position::InsetValue<'i>) [logical_group: Inset, category: Logical],
+  "inset-block-end": InsetBlockEnd(position::InsetValue<'i>) [logical_group: Inset, category: Logical],
+  "inset-inline-start": InsetInlineStart(position::InsetValue<'i>) [logical_group: Inset, category: Logical],
+  "inset-inline-end": InsetInlineEnd(position::InsetValue<'i>) [logical_group: Inset, category: Logical],
+  "inset-block": InsetBlock(InsetBlock<'i>) shorthand: true,
+  "inset-inline": InsetInline(InsetInline<'i>) shorthand: true,
+  "inset": Inset(Inset<'i>) shorthand: true,
 
   "border-spacing": BorderSpacing(Size2D<Length>),
 
@@ -1400,6 +1471,12 @@ define_properties! {
   "scroll-padding-inline": ScrollPaddingInline(ScrollPaddingInline) shorthand: true,
   "scroll-padding": ScrollPadding(ScrollPadding) shorthand: true,
 
+  "scroll-snap-type": ScrollSnapType(ScrollSnapType, VendorPrefix) / WebKit / Ms,
+  "scroll-snap-align": ScrollSnapAlign(ScrollSnapAlign),
+  "scroll-snap-stop": ScrollSnapStop(ScrollSnapStop),
+  "scroll-snap-points-x": ScrollSnapPointsX(ScrollSnapPoints, VendorPrefix) / Ms unprefixed: false,
+  "scroll-snap-points-y": ScrollSnapPointsY(ScrollSnapPoints, VendorPrefix) / Ms unprefixed: false,
+
   "font-weight": FontWeight(FontWeight),
   "font-size": FontSize(FontSize),
   "font-stretch": FontStretch(FontStretch),
@@ -1407,6 +1484,10 @@ define_properties! {
   "font-style": FontStyle(FontStyle),
   "font-variant-caps": FontVariantCaps(FontVariantCaps),
   "line-height": LineHeight(LineHeight),
+  "font-kerning": FontKerning(FontKerning),
+  "font-optical-sizing": FontOpticalSizing(FontOpticalSizing),
+  "font-size-adjust": FontSizeAdjust(FontSizeAdjust),
+  "font-variation-settings": FontVariationSettings(FontVariationSettings<'i>),
   "font": Font(Font<'i>) shorthand: true,
   "vertical-align": VerticalAlign(VerticalAlign),
   "font-palette": FontPalette(DashedIdentReference<'i>),
@@ -1415,6 +1496,7 @@ define_properties! {
   "transition-duration": TransitionDuration(SmallVec<[Time; 1]>, VendorPrefix) / WebKit / Moz / Ms,
   "transition-delay": TransitionDelay(SmallVec<[Time; 1]>, VendorPrefix) / WebKit / Moz / Ms,
   "transition-timing-function": TransitionTimingFunction(SmallVec<[EasingFunction; 1]>, VendorPrefix) / WebKit / Moz / Ms,
+  "transition-behavior": TransitionBehavior(SmallVec<[TransitionBehavior; 1]>),
   "transition": Transition(SmallVec<[Transition<'i>; 1]>, VendorPrefix) / WebKit / Moz / Ms shorthand: true,
 
   "animation-name": AnimationName(AnimationNameList<'i>, VendorPrefix) / WebKit / Moz / O,
@@ -1426,6 +1508,21 @@ define_properties! {
   "animation-delay": AnimationDelay(SmallVec<[Time; 1]>, VendorPrefix) / WebKit / Moz / O,
   "animation-fill-mode": AnimationFillMode(SmallVec<[AnimationFillMode; 1]>, VendorPrefix) / WebKit / Moz / O,
   "animation": Animation(AnimationList<'i>, VendorPrefix) / WebKit / Moz / O shorthand: true,
+  "animation-timeline": AnimationTimeline(SmallVec<[AnimationTimeline<'i>; 1]>),
+  "animation-composition": AnimationComposition(SmallVec<[AnimationComposition; 1]>),
+  "animation-range-start": AnimationRangeStart(SmallVec<[AnimationRangeStart; 1]>),
+  "animation-range-end": AnimationRangeEnd(SmallVec<[AnimationRangeEnd; 1]>),
+  "animation-range": AnimationRange(SmallVec<[AnimationRange; 1]>) shorthand: true,
+
+  // https://drafts.csswg.org/scroll-animations-1/
+  "scroll-timeline-name": ScrollTimelineName(SmallVec<[TimelineName<'i>; 1]>),
+  "scroll-timeline-axis": ScrollTimelineAxis(SmallVec<[ScrollAxis; 1]>),
+  "scroll-timeline": ScrollTimeline(SmallVec<[ScrollTimeline<'i>; 1]>) shorthand: true,
+  "view-timeline-name": ViewTimelineName(SmallVec<[TimelineName<'i>; 1]>),
+  "view-timeline-axis": ViewTimelineAxis(SmallVec<[ScrollAxis; 1]>),
+  "view-timeline-inset": ViewTimelineInset(SmallVec<[Size2D<LengthPercentageOrAuto>; 1]>),
+  "view-timeline": ViewTimeline(SmallVec<[ViewTimeline<'i>; 1]>) shorthand: true,
+  "timeline-scope": TimelineScope(TimelineScope<'i>),
 
   // https://drafts.csswg.org/css-transforms-2/
   "transform": Transform(TransformList, VendorPrefix) / WebKit / Moz / Ms / O,
@@ -1441,7 +1538,7 @@ define_properties! {
 
   // https://www.w3.org/TR/2021/CRD-css-text-3-20210422
   "text-transform": TextTransform(TextTransform),
-  "white-space": WhiteSpace(WhiteSpace),
+  "white-space": WhiteSpace(WhiteSpace) shorthand: true,
   "tab-size": TabSize(LengthOrNumber, VendorPrefix) / Moz / O,
   "word-break": WordBreak(WordBreak),
   "line-break": LineBreak(LineBreak),
@@ -1455,6 +1552,12 @@ define_properties! {
   "letter-spacing": LetterSpacing(Spacing),
   "text-indent": TextIndent(TextIndent),
 
+  // https://drafts.csswg.org/css-text-4/
+  "white-space-collapse": WhiteSpaceCollapse(WhiteSpaceCollapse),
+  "text-wrap-mode": TextWrapMode(TextWrapMode),
+  "text-wrap-style": TextWrapStyle(TextWrapStyle),
+  "text-wrap": TextWrap(TextWrap) shorthand: true,
+
   // https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506
   "text-decoration-line": TextDecorationLine(TextDecorationLine, VendorPrefix) / WebKit / Moz,
   "text-decoration-style": TextDecorationStyle(TextDecorationStyle, VendorPrefix) / WebKit / Moz,
@@ -1480,6 +1583,10 @@ define_properties! {
   "user-select": UserSelect(UserSelect, VendorPrefix) / WebKit / Moz / Ms,
   "accent-color": AccentColor(ColorOrAuto),
   "appearance": Appearance(Appearance<'i>, VendorPrefix) / WebKit / Moz / Ms,
+  "field-sizing": FieldSizing(FieldSizing),
+
+  // https://drafts.csswg.org/css-values-5/
+  "interpolate-size": InterpolateSize(InterpolateSize),
 
   // https://www.w3.org/TR/2020/WD-css-lists-3-20201117
   "list-style-type": ListStyleType(ListStyleType<'i>),
@@ -1488,6 +1595,9 @@ define_properties! {
   "list-style": ListStyle(ListStyle<'i>) shorthand: true,
   "marker-side": MarkerSide(MarkerSide),
 
+  // https://drafts.csswg.org/css-content-3/
+  "content": Content(Content<'i>),
+
   // CSS modules
   "composes": Composes(Composes<'i>) if css_modules,
 
@@ -1514,6 +1624,9 @@ define_properties! {
   "text-rendering": TextRendering(TextRendering),
   "image-rendering": ImageRendering(ImageRendering),
 
+  // https://drafts.csswg.org/css-images-3/
+  "object-position": ObjectPosition(Position),
+
   // https://www.w3.org/TR/css-masking-1/
   "clip-path": ClipPath(ClipPath<'i>, VendorPrefix) / WebKit,
   "clip-rule": ClipRule(FillRule),
@@ -1551,6 +1664,14 @@ define_properties! {
   "filter": Filter(FilterList<'i>, VendorPrefix) / WebKit,
   "backdrop-filter": BackdropFilter(FilterList<'i>, VendorPrefix) / WebKit,
 
+  // https://drafts.fxtf.org/motion-1/
+  "offset-path": OffsetPath(OffsetPath<'i>),
+  "offset-distance": OffsetDistance(LengthPercentage),
+  "offset-rotate": OffsetRotate(OffsetRotate),
+  "offset-anchor": OffsetAnchor(OffsetAnchor),
+  "offset-position": OffsetPosition(OffsetPosition),
+  "offset": Offset(Offset<'i>) shorthand: true,
+
   // https://drafts.csswg.org/css2/
   "z-index": ZIndex(position::ZIndex),
 
@@ -1558,6 +1679,18 @@ define_properties! {
   "container-type": ContainerType(ContainerType),
   "container-name": ContainerName(ContainerNameList<'i>),
   "container": Container(Container<'i>) shorthand: true,
+
+  // https://drafts.csswg.org/css-anchor-position-1/
+  "anchor-name": AnchorName(AnchorName<'i>),
+  "position-anchor": PositionAnchor(PositionAnchor<'i>),
+  "position-area": PositionArea(PositionArea),
+
+  // https://drafts.csswg.org/css-view-transitions-2/
+  "view-transition-name": ViewTransitionName(ViewTransitionName<'i>),
+  "view-transition-class": ViewTransitionClass(NoneOrCustomIdentList<'i>),
+
+  // https://drafts.csswg.org/css-will-change/
+  "will-change": WillChange(WillChange<'i>),
 }
 
 impl<'i, T: smallvec::Array<Item = V>, V: Parse<'i>> Parse<'i> for SmallVec<T> {
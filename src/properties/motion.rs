@@ -0,0 +1,536 @@
+//! CSS properties related to motion paths.
+
+use super::{Property, PropertyId};
+use crate::context::PropertyHandlerContext;
+use crate::declaration::{DeclarationBlock, DeclarationList};
+use crate::error::{ParserError, PrinterError};
+use crate::macros::{define_shorthand, enum_property, shorthand_handler};
+use crate::printer::Printer;
+use crate::properties::masking::GeometryBox;
+use crate::targets::Browsers;
+use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss, Zero};
+use crate::values::angle::Angle;
+use crate::values::length::LengthPercentage;
+use crate::values::position::Position;
+use crate::values::shape::BasicShape;
+use crate::values::string::CSSString;
+use crate::values::url::Url;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+
+enum_property! {
+  /// A [`<ray-size>`](https://drafts.fxtf.org/motion-1/#typedef-ray-size) value,
+  /// as used in the `ray()` function.
+  #[derive(Default)]
+  pub enum RaySize {
+    /// The distance to the closest side of the reference box.
+    #[default]
+    "closest-side": ClosestSide,
+    /// The distance to the closest corner of the reference box.
+    "closest-corner": ClosestCorner,
+    /// The distance to the farthest side of the reference box.
+    "farthest-side": FarthestSide,
+    /// The distance to the farthest corner of the reference box.
+    "farthest-corner": FarthestCorner,
+    /// The distance to the point where the ray intersects the reference box.
+    "sides": Sides,
+  }
+}
+
+/// A [`ray()`](https://drafts.fxtf.org/motion-1/#ray-function) function, as used in the `offset-path` property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct RayFunction {
+  /// The direction of the ray.
+  pub angle: Angle,
+  /// The length of the ray.
+  pub size: RaySize,
+  /// Whether the ray is shortened so that the element is fully contained within the path.
+  pub contain: bool,
+  /// The starting point of the ray. If omitted, the `offset-position` is used.
+  pub position: Option<Position>,
+}
+
+impl<'i> Parse<'i> for RayFunction {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    input.expect_function_matching("ray")?;
+    input.parse_nested_block(|input| {
+      let mut angle = None;
+      let mut size = None;
+      let mut contain = false;
+      let mut position = None;
+
+      loop {
+        if angle.is_none() {
+          if let Ok(value) = input.try_parse(Angle::parse) {
+            angle = Some(value);
+            continue;
+          }
+        }
+
+        if size.is_none() {
+          if let Ok(value) = input.try_parse(RaySize::parse) {
+            size = Some(value);
+            continue;
+          }
+        }
+
+        if !contain && input.try_parse(|input| input.expect_ident_matching("contain")).is_ok() {
+          contain = true;
+          continue;
+        }
+
+        if position.is_none() && input.try_parse(|input| input.expect_ident_matching("at")).is_ok() {
+          position = Some(Position::parse(input)?);
+          continue;
+        }
+
+        break;
+      }
+
+      let angle = match angle {
+        Some(angle) => angle,
+        None => return Err(input.new_custom_error(ParserError::InvalidValue)),
+      };
+
+      Ok(RayFunction {
+        angle,
+        size: size.unwrap_or_default(),
+        contain,
+        position,
+      })
+    })
+  }
+}
+
+impl ToCss for RayFunction {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("ray(")?;
+    self.angle.to_css(dest)?;
+    if self.size != RaySize::default() {
+      dest.write_char(' ')?;
+      self.size.to_css(dest)?;
+    }
+    if self.contain {
+      dest.write_str(" contain")?;
+    }
+    if let Some(position) = &self.position {
+      dest.write_str(" at ")?;
+      position.to_css(dest)?;
+    }
+    dest.write_char(')')
+  }
+}
+
+/// A value for the [offset-path](https://drafts.fxtf.org/motion-1/#offset-path-property) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum OffsetPath<'i> {
+  /// The element does not move along a path.
+  #[default]
+  None,
+  /// A url reference to an SVG shape element.
+  #[cfg_attr(feature = "serde", serde(borrow, with = "crate::serialization::ValueWrapper::<Url>"))]
+  Url(Url<'i>),
+  /// A ray, positioned according to the reference box.
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+  Ray {
+    /// The ray.
+    ray: Box<RayFunction>,
+    /// The reference box that the ray is positioned according to.
+    reference_box: GeometryBox,
+  },
+  /// An SVG path, positioned according to the reference box.
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+  Path {
+    /// The SVG path data.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    path: CSSString<'i>,
+    /// The reference box that the path is positioned according to.
+    reference_box: GeometryBox,
+  },
+  /// A basic shape, positioned according to the reference box.
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+  Shape {
+    /// A basic shape.
+    shape: Box<BasicShape>,
+    /// The reference box that the shape is positioned according to.
+    reference_box: GeometryBox,
+  },
+  /// The shape of a reference box.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::ValueWrapper::<GeometryBox>"))]
+  Box(GeometryBox),
+}
+
+impl<'i> OffsetPath<'i> {
+  fn parse_path<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if let Ok(ray) = input.try_parse(RayFunction::parse) {
+      return Ok(OffsetPath::Ray {
+        ray: Box::new(ray),
+        reference_box: GeometryBox::default(),
+      });
+    }
+
+    if let Ok(path) = input.try_parse(|input| {
+      input.expect_function_matching("path")?;
+      input.parse_nested_block(CSSString::parse)
+    }) {
+      return Ok(OffsetPath::Path {
+        path,
+        reference_box: GeometryBox::default(),
+      });
+    }
+
+    let shape = BasicShape::parse(input)?;
+    Ok(OffsetPath::Shape {
+      shape: Box::new(shape),
+      reference_box: GeometryBox::default(),
+    })
+  }
+
+  fn set_reference_box(&mut self, b: GeometryBox) {
+    match self {
+      OffsetPath::Ray { reference_box, .. }
+      | OffsetPath::Path { reference_box, .. }
+      | OffsetPath::Shape { reference_box, .. } => *reference_box = b,
+      _ => {}
+    }
+  }
+}
+
+impl<'i> Parse<'i> for OffsetPath<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(OffsetPath::None);
+    }
+
+    if let Ok(url) = input.try_parse(Url::parse) {
+      return Ok(OffsetPath::Url(url));
+    }
+
+    if let Ok(mut path) = input.try_parse(OffsetPath::parse_path) {
+      if let Ok(b) = input.try_parse(GeometryBox::parse) {
+        path.set_reference_box(b);
+      }
+      return Ok(path);
+    }
+
+    let b = GeometryBox::parse(input)?;
+    if let Ok(mut path) = input.try_parse(OffsetPath::parse_path) {
+      path.set_reference_box(b);
+      return Ok(path);
+    }
+
+    Ok(OffsetPath::Box(b))
+  }
+}
+
+impl<'i> ToCss for OffsetPath<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let reference_box = match self {
+      OffsetPath::None => return dest.write_str("none"),
+      OffsetPath::Url(url) => return url.to_css(dest),
+      OffsetPath::Box(b) => return b.to_css(dest),
+      OffsetPath::Ray { ray, reference_box } => {
+        ray.to_css(dest)?;
+        reference_box
+      }
+      OffsetPath::Path { path, reference_box } => {
+        dest.write_str("path(")?;
+        path.to_css(dest)?;
+        dest.write_char(')')?;
+        reference_box
+      }
+      OffsetPath::Shape { shape, reference_box } => {
+        shape.to_css(dest)?;
+        reference_box
+      }
+    };
+
+    if *reference_box != GeometryBox::default() {
+      dest.write_char(' ')?;
+      reference_box.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
+
+enum_property! {
+  /// A keyword for the `offset-rotate` property.
+  pub enum OffsetRotateKeyword {
+    /// The element is rotated by the angle of the direction of the path.
+    "auto": Auto,
+    /// The element is rotated by the angle of the direction of the path, plus 180 degrees.
+    "reverse": Reverse,
+  }
+}
+
+/// A value for the [offset-rotate](https://drafts.fxtf.org/motion-1/#offset-rotate-property) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct OffsetRotate {
+  /// Whether the rotation depends on the direction of the path.
+  pub keyword: Option<OffsetRotateKeyword>,
+  /// A fixed rotation, added to the direction of the path if a keyword is specified.
+  pub angle: Angle,
+}
+
+impl Default for OffsetRotate {
+  fn default() -> Self {
+    OffsetRotate {
+      keyword: Some(OffsetRotateKeyword::Auto),
+      angle: Angle::Deg(0.0),
+    }
+  }
+}
+
+impl<'i> Parse<'i> for OffsetRotate {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut keyword = input.try_parse(OffsetRotateKeyword::parse).ok();
+    let angle = input.try_parse(Angle::parse);
+    if keyword.is_none() {
+      keyword = input.try_parse(OffsetRotateKeyword::parse).ok();
+    }
+
+    match (keyword, angle) {
+      (None, Err(err)) => Err(err),
+      (keyword, angle) => Ok(OffsetRotate {
+        keyword,
+        angle: angle.unwrap_or(Angle::Deg(0.0)),
+      }),
+    }
+  }
+}
+
+impl ToCss for OffsetRotate {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if let Some(keyword) = &self.keyword {
+      keyword.to_css(dest)?;
+      if self.angle.is_zero() {
+        return Ok(());
+      }
+      dest.write_char(' ')?;
+    }
+
+    self.angle.to_css(dest)
+  }
+}
+
+/// A value for the [offset-anchor](https://drafts.fxtf.org/motion-1/#offset-anchor-property) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum OffsetAnchor {
+  /// The `offset-position` is used as the anchor, or the `transform-origin` if it is `normal`.
+  #[default]
+  Auto,
+  /// An explicit anchor point.
+  Position(Position),
+}
+
+impl<'i> Parse<'i> for OffsetAnchor {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(OffsetAnchor::Auto);
+    }
+
+    Ok(OffsetAnchor::Position(Position::parse(input)?))
+  }
+}
+
+impl ToCss for OffsetAnchor {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      OffsetAnchor::Auto => dest.write_str("auto"),
+      OffsetAnchor::Position(position) => position.to_css(dest),
+    }
+  }
+}
+
+/// A value for the [offset-position](https://drafts.fxtf.org/motion-1/#offset-position-property) property.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum OffsetPosition {
+  /// The element has no offset starting position.
+  #[default]
+  Normal,
+  /// The element's own position is used as the starting position.
+  Auto,
+  /// An explicit starting position.
+  Position(Position),
+}
+
+impl<'i> Parse<'i> for OffsetPosition {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
+      return Ok(OffsetPosition::Normal);
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(OffsetPosition::Auto);
+    }
+
+    Ok(OffsetPosition::Position(Position::parse(input)?))
+  }
+}
+
+impl ToCss for OffsetPosition {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      OffsetPosition::Normal => dest.write_str("normal"),
+      OffsetPosition::Auto => dest.write_str("auto"),
+      OffsetPosition::Position(position) => position.to_css(dest),
+    }
+  }
+}
+
+define_shorthand! {
+  /// A value for the [offset](https://drafts.fxtf.org/motion-1/#offset-shorthand) shorthand property.
+  #[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+  pub struct Offset<'i> {
+    /// The offset starting position.
+    position: OffsetPosition(OffsetPosition),
+    /// The path the element moves along.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    path: OffsetPath(OffsetPath<'i>),
+    /// The distance along the path.
+    distance: OffsetDistance(LengthPercentage),
+    /// The rotation of the element along the path.
+    rotate: OffsetRotate(OffsetRotate),
+    /// The anchor point of the element that is placed on the path.
+    anchor: OffsetAnchor(OffsetAnchor),
+  }
+}
+
+impl<'i> Parse<'i> for Offset<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let position = input.try_parse(OffsetPosition::parse).ok();
+    let path = input.try_parse(OffsetPath::parse).ok();
+    if position.is_none() && path.is_none() {
+      return Err(input.new_custom_error(ParserError::InvalidDeclaration));
+    }
+
+    let mut distance = None;
+    let mut rotate = None;
+    if path.is_some() {
+      loop {
+        if distance.is_none() {
+          if let Ok(value) = input.try_parse(LengthPercentage::parse) {
+            distance = Some(value);
+            continue;
+          }
+        }
+
+        if rotate.is_none() {
+          if let Ok(value) = input.try_parse(OffsetRotate::parse) {
+            rotate = Some(value);
+            continue;
+          }
+        }
+
+        break;
+      }
+    }
+
+    let anchor = if input.try_parse(|input| input.expect_delim('/')).is_ok() {
+      OffsetAnchor::parse(input)?
+    } else {
+      OffsetAnchor::default()
+    };
+
+    Ok(Offset {
+      position: position.unwrap_or_default(),
+      path: path.unwrap_or_default(),
+      distance: distance.unwrap_or(LengthPercentage::zero()),
+      rotate: rotate.unwrap_or_default(),
+      anchor,
+    })
+  }
+}
+
+impl<'i> ToCss for Offset<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let has_position = self.position != OffsetPosition::default();
+    let has_path = self.path != OffsetPath::None
+      || !self.distance.is_zero()
+      || self.rotate != OffsetRotate::default()
+      || !has_position;
+
+    if has_position {
+      self.position.to_css(dest)?;
+      if has_path {
+        dest.write_char(' ')?;
+      }
+    }
+
+    if has_path {
+      self.path.to_css(dest)?;
+
+      if !self.distance.is_zero() {
+        dest.write_char(' ')?;
+        self.distance.to_css(dest)?;
+      }
+
+      if self.rotate != OffsetRotate::default() {
+        dest.write_char(' ')?;
+        self.rotate.to_css(dest)?;
+      }
+    }
+
+    if self.anchor != OffsetAnchor::default() {
+      dest.delim('/', true)?;
+      self.anchor.to_css(dest)?;
+    }
+
+    Ok(())
+  }
+}
+
+shorthand_handler!(OffsetHandler -> Offset<'i> {
+  position: OffsetPosition(OffsetPosition),
+  path: OffsetPath(OffsetPath<'i>),
+  distance: OffsetDistance(LengthPercentage),
+  rotate: OffsetRotate(OffsetRotate),
+  anchor: OffsetAnchor(OffsetAnchor),
+});
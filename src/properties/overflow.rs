@@ -4,11 +4,12 @@ use super::{Property, PropertyId};
 use crate::compat::Feature;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::{DeclarationBlock, DeclarationList};
-use crate::error::{ParserError, PrinterError};
+use crate::error::{ErrorWithLocation, ParserError, PrinterError};
 use crate::macros::{define_shorthand, enum_property};
 use crate::printer::Printer;
 use crate::targets::Browsers;
-use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss};
+use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss, TrySign, Zero};
+use crate::values::length::Length;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
 use cssparser::*;
@@ -62,6 +63,91 @@ impl ToCss for Overflow {
   }
 }
 
+enum_property! {
+  /// A [visual box](https://www.w3.org/TR/css-overflow-3/#typedef-overflow-clip-margin-visual-box),
+  /// as used in the `overflow-clip-margin` property.
+  #[derive(Default)]
+  pub enum VisualBox {
+    /// The content box.
+    "content-box": ContentBox,
+    /// The padding box.
+    #[default]
+    "padding-box": PaddingBox,
+    /// The border box.
+    "border-box": BorderBox,
+  }
+}
+
+/// A value for the [overflow-clip-margin](https://www.w3.org/TR/css-overflow-3/#overflow-clip-margin) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct OverflowClipMargin {
+  /// The box edge from which the clip margin is measured.
+  pub visual_box: VisualBox,
+  /// How far outside the box the content may paint before being clipped.
+  pub margin: Length,
+}
+
+impl<'i> Parse<'i> for OverflowClipMargin {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut visual_box = None;
+    let mut margin = None;
+    loop {
+      if visual_box.is_none() {
+        if let Ok(value) = input.try_parse(VisualBox::parse) {
+          visual_box = Some(value);
+          continue;
+        }
+      }
+
+      if margin.is_none() {
+        let location = input.current_source_location();
+        if let Ok(value) = input.try_parse(Length::parse) {
+          if value.is_sign_negative() {
+            return Err(location.new_custom_error(ParserError::InvalidValue));
+          }
+          margin = Some(value);
+          continue;
+        }
+      }
+
+      break;
+    }
+
+    if visual_box.is_none() && margin.is_none() {
+      return Err(input.new_custom_error(ParserError::InvalidDeclaration));
+    }
+
+    Ok(OverflowClipMargin {
+      visual_box: visual_box.unwrap_or_default(),
+      margin: margin.unwrap_or_else(Length::zero),
+    })
+  }
+}
+
+impl ToCss for OverflowClipMargin {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if self.visual_box != VisualBox::default() {
+      self.visual_box.to_css(dest)?;
+      if self.margin.is_zero() {
+        return Ok(());
+      }
+      dest.write_char(' ')?;
+    }
+
+    self.margin.to_css(dest)
+  }
+}
+
 enum_property! {
   /// A value for the [text-overflow](https://www.w3.org/TR/css-overflow-3/#text-overflow) property.
   pub enum TextOverflow {
@@ -97,7 +183,30 @@ impl<'i> PropertyHandler<'i> for OverflowHandler {
   ) -> bool {
     use Property::*;
 
+    let uses_clip = match property {
+      OverflowX(val) | OverflowY(val) => *val == OverflowKeyword::Clip,
+      Overflow(val) => val.x == OverflowKeyword::Clip || val.y == OverflowKeyword::Clip,
+      _ => false,
+    };
+
+    if uses_clip && !context.is_supported(Feature::OverflowClip) {
+      context.warnings.push(ErrorWithLocation {
+        kind: ParserError::UnsupportedValue("clip".into()),
+        loc: context.loc,
+      });
+    }
+
     match property {
+      OverflowClipMargin(..) => {
+        if !context.is_supported(Feature::OverflowClipMargin) {
+          context.warnings.push(ErrorWithLocation {
+            kind: ParserError::UnsupportedProperty("overflow-clip-margin".into()),
+            loc: context.loc,
+          });
+        }
+        // The margin is independent of the other overflow properties, so it is output as is.
+        return false;
+      }
       OverflowX(val) => self.x = Some(*val),
       OverflowY(val) => self.y = Some(*val),
       Overflow(val) => {
@@ -1,5 +1,6 @@
 //! CSS properties related to positioning.
 
+use super::anchor::{AnchorFunction, AnchorSizeFunction};
 use super::Property;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
@@ -8,6 +9,7 @@ use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{Parse, PropertyHandler, ToCss};
+use crate::values::length::LengthPercentage;
 use crate::values::number::CSSInteger;
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
@@ -72,6 +74,61 @@ impl ToCss for Position {
   }
 }
 
+/// A value for the [inset properties](https://drafts.csswg.org/css-position-3/#insets),
+/// i.e. `top`, `right`, `bottom`, `left`, and their logical equivalents.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum InsetValue<'i> {
+  /// The `auto` keyword.
+  Auto,
+  /// An explicit length or percentage.
+  LengthPercentage(LengthPercentage),
+  /// An `anchor()` function.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Anchor(AnchorFunction<'i>),
+  /// An `anchor-size()` function.
+  AnchorSize(AnchorSizeFunction<'i>),
+}
+
+impl<'i> Parse<'i> for InsetValue<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(InsetValue::Auto);
+    }
+
+    if let Ok(anchor) = input.try_parse(AnchorFunction::parse) {
+      return Ok(InsetValue::Anchor(anchor));
+    }
+
+    if let Ok(anchor_size) = input.try_parse(AnchorSizeFunction::parse) {
+      return Ok(InsetValue::AnchorSize(anchor_size));
+    }
+
+    Ok(InsetValue::LengthPercentage(LengthPercentage::parse(input)?))
+  }
+}
+
+impl<'i> ToCss for InsetValue<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      InsetValue::Auto => dest.write_str("auto"),
+      InsetValue::LengthPercentage(value) => value.to_css(dest),
+      InsetValue::Anchor(anchor) => anchor.to_css(dest),
+      InsetValue::AnchorSize(anchor_size) => anchor_size.to_css(dest),
+    }
+  }
+}
+
 /// A value for the [z-index](https://drafts.csswg.org/css2/#z-index) property.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "visitor", derive(Visit))]
@@ -0,0 +1,355 @@
+//! CSS properties related to scroll snapping.
+
+use super::{Property, PropertyId};
+use crate::context::PropertyHandlerContext;
+use crate::declaration::DeclarationList;
+use crate::error::{ParserError, PrinterError};
+use crate::macros::enum_property;
+use crate::prefixes::Feature;
+use crate::printer::Printer;
+use crate::targets::Browsers;
+use crate::traits::{Parse, PropertyHandler, ToCss, Zero};
+use crate::values::length::LengthPercentage;
+use crate::values::percentage::Percentage;
+use crate::vendor_prefix::VendorPrefix;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+
+enum_property! {
+  /// An axis keyword, as used in the `scroll-snap-type` property.
+  pub enum ScrollSnapAxis {
+    /// The horizontal axis.
+    "x": X,
+    /// The vertical axis.
+    "y": Y,
+    /// The block axis.
+    "block": Block,
+    /// The inline axis.
+    "inline": Inline,
+    /// Both axes.
+    "both": Both,
+  }
+}
+
+enum_property! {
+  /// A strictness keyword, as used in the `scroll-snap-type` property.
+  #[derive(Default)]
+  pub enum ScrollSnapStrictness {
+    /// The scroll container must rest at a snap position when there are no active scrolling operations.
+    "mandatory": Mandatory,
+    /// The scroll container may come to rest at a snap position.
+    #[default]
+    "proximity": Proximity,
+  }
+}
+
+/// A value for the [scroll-snap-type](https://www.w3.org/TR/css-scroll-snap-1/#scroll-snap-type) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ScrollSnapType {
+  /// The scroll container does not snap.
+  None,
+  /// The scroll container snaps along the given axis.
+  Snap {
+    /// The axis to snap along. Only omitted in the legacy prefixed syntax, which does not accept an axis.
+    axis: Option<ScrollSnapAxis>,
+    /// How strictly snap positions are enforced.
+    strictness: ScrollSnapStrictness,
+  },
+}
+
+impl<'i> Parse<'i> for ScrollSnapType {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(ScrollSnapType::None);
+    }
+
+    let axis = input.try_parse(ScrollSnapAxis::parse).ok();
+    let strictness = if axis.is_some() {
+      input.try_parse(ScrollSnapStrictness::parse).unwrap_or_default()
+    } else {
+      ScrollSnapStrictness::parse(input)?
+    };
+
+    Ok(ScrollSnapType::Snap { axis, strictness })
+  }
+}
+
+impl ToCss for ScrollSnapType {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      ScrollSnapType::None => dest.write_str("none"),
+      ScrollSnapType::Snap { axis: None, strictness } => strictness.to_css(dest),
+      ScrollSnapType::Snap {
+        axis: Some(axis),
+        strictness,
+      } => {
+        axis.to_css(dest)?;
+        if *strictness != ScrollSnapStrictness::default() {
+          dest.write_char(' ')?;
+          strictness.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+impl ScrollSnapType {
+  /// Returns the value in the legacy syntax used by `-ms-scroll-snap-type`, which has no axis.
+  fn to_legacy(&self) -> ScrollSnapType {
+    match self {
+      ScrollSnapType::None => ScrollSnapType::None,
+      ScrollSnapType::Snap { strictness, .. } => ScrollSnapType::Snap {
+        axis: None,
+        strictness: *strictness,
+      },
+    }
+  }
+}
+
+enum_property! {
+  /// A snap position keyword, as used in the `scroll-snap-align` property.
+  pub enum ScrollSnapAlignKeyword {
+    /// The box does not define a snap position in this axis.
+    "none": None,
+    /// The start edge of the box is aligned with the snapport.
+    "start": Start,
+    /// The end edge of the box is aligned with the snapport.
+    "end": End,
+    /// The center of the box is aligned with the center of the snapport.
+    "center": Center,
+  }
+}
+
+/// A value for the [scroll-snap-align](https://www.w3.org/TR/css-scroll-snap-1/#scroll-snap-align) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ScrollSnapAlign {
+  /// The snap position in the block axis.
+  pub block: ScrollSnapAlignKeyword,
+  /// The snap position in the inline axis.
+  pub inline: ScrollSnapAlignKeyword,
+}
+
+impl<'i> Parse<'i> for ScrollSnapAlign {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let block = ScrollSnapAlignKeyword::parse(input)?;
+    let inline = input.try_parse(ScrollSnapAlignKeyword::parse).unwrap_or(block);
+    Ok(ScrollSnapAlign { block, inline })
+  }
+}
+
+impl ToCss for ScrollSnapAlign {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.block.to_css(dest)?;
+    if self.inline != self.block {
+      dest.write_char(' ')?;
+      self.inline.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
+
+enum_property! {
+  /// A value for the [scroll-snap-stop](https://www.w3.org/TR/css-scroll-snap-1/#scroll-snap-stop) property.
+  pub enum ScrollSnapStop {
+    /// The scroll container may pass over a snap position.
+    "normal": Normal,
+    /// The scroll container must not pass over a snap position.
+    "always": Always,
+  }
+}
+
+/// A value for the legacy [-ms-scroll-snap-points-x](https://learn.microsoft.com/en-us/previous-versions/windows/internet-explorer/ie-developer/compatibility/hh772036(v=vs.85))
+/// and `-ms-scroll-snap-points-y` properties.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ScrollSnapPoints {
+  /// Snap points at a regular interval, starting from an offset.
+  Interval {
+    /// The position of the first snap point.
+    start: LengthPercentage,
+    /// The distance between snap points.
+    step: LengthPercentage,
+  },
+  /// A list of snap point positions.
+  List {
+    /// The snap point positions.
+    points: Vec<LengthPercentage>,
+  },
+}
+
+impl<'i> Parse<'i> for ScrollSnapPoints {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    let f = input.expect_function()?;
+    match_ignore_ascii_case! { &f,
+      "snapinterval" => input.parse_nested_block(|input| {
+        let start = LengthPercentage::parse(input)?;
+        input.expect_comma()?;
+        let step = LengthPercentage::parse(input)?;
+        Ok(ScrollSnapPoints::Interval { start, step })
+      }),
+      "snaplist" => input.parse_nested_block(|input| {
+        let points = input.parse_comma_separated(LengthPercentage::parse)?;
+        Ok(ScrollSnapPoints::List { points })
+      }),
+      _ => Err(location.new_unexpected_token_error(Token::Ident(f.clone())))
+    }
+  }
+}
+
+impl ToCss for ScrollSnapPoints {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      ScrollSnapPoints::Interval { start, step } => {
+        dest.write_str("snapInterval(")?;
+        start.to_css(dest)?;
+        dest.delim(',', false)?;
+        step.to_css(dest)?;
+        dest.write_char(')')
+      }
+      ScrollSnapPoints::List { points } => {
+        dest.write_str("snapList(")?;
+        let len = points.len();
+        for (idx, point) in points.iter().enumerate() {
+          point.to_css(dest)?;
+          if idx < len - 1 {
+            dest.delim(',', false)?;
+          }
+        }
+        dest.write_char(')')
+      }
+    }
+  }
+}
+
+#[derive(Default)]
+pub(crate) struct ScrollSnapHandler {
+  targets: Option<Browsers>,
+  snap_type: Option<ScrollSnapType>,
+  ms_snap_type: Option<ScrollSnapType>,
+  ms_points_x: Option<ScrollSnapPoints>,
+  ms_points_y: Option<ScrollSnapPoints>,
+}
+
+impl ScrollSnapHandler {
+  pub fn new(targets: Option<Browsers>) -> ScrollSnapHandler {
+    ScrollSnapHandler {
+      targets,
+      ..ScrollSnapHandler::default()
+    }
+  }
+}
+
+impl<'i> PropertyHandler<'i> for ScrollSnapHandler {
+  fn handle_property(
+    &mut self,
+    property: &Property<'i>,
+    dest: &mut DeclarationList<'i>,
+    context: &mut PropertyHandlerContext<'i, '_>,
+  ) -> bool {
+    match property {
+      Property::ScrollSnapType(val, prefix) if *prefix == VendorPrefix::None => {
+        self.snap_type = Some(val.clone());
+      }
+      Property::ScrollSnapType(val, prefix) if *prefix == VendorPrefix::Ms => {
+        self.ms_snap_type = Some(val.clone());
+      }
+      Property::ScrollSnapPointsX(val, VendorPrefix::Ms) => {
+        self.ms_points_x = Some(val.clone());
+      }
+      Property::ScrollSnapPointsY(val, VendorPrefix::Ms) => {
+        self.ms_points_y = Some(val.clone());
+      }
+      Property::Unparsed(val)
+        if matches!(
+          val.property_id,
+          PropertyId::ScrollSnapType(_) | PropertyId::ScrollSnapPointsX(_) | PropertyId::ScrollSnapPointsY(_)
+        ) =>
+      {
+        self.finalize(dest, context);
+        dest.push(property.clone());
+      }
+      _ => return false,
+    }
+
+    true
+  }
+
+  fn finalize(&mut self, dest: &mut DeclarationList<'i>, context: &mut PropertyHandlerContext<'i, '_>) {
+    let snap_type = std::mem::take(&mut self.snap_type);
+    let mut ms_snap_type = std::mem::take(&mut self.ms_snap_type);
+    let mut ms_points_x = std::mem::take(&mut self.ms_points_x);
+    let mut ms_points_y = std::mem::take(&mut self.ms_points_y);
+
+    // IE and legacy Edge only support the prefixed syntax without an axis, and snap at the interval
+    // given by -ms-scroll-snap-points-x/y rather than at the positions of the children. This is lossy,
+    // so it is only done when enabled. Each snap point is assumed to be one page apart.
+    if let (true, Some(snap_type), None, Some(targets)) =
+      (context.legacy_scroll_snap, &snap_type, &ms_snap_type, self.targets)
+    {
+      if Feature::ScrollSnapType.prefixes_for(targets).contains(VendorPrefix::Ms) {
+        ms_snap_type = Some(snap_type.to_legacy());
+
+        if let ScrollSnapType::Snap { axis: Some(axis), .. } = snap_type {
+          let page = || ScrollSnapPoints::Interval {
+            start: LengthPercentage::zero(),
+            step: LengthPercentage::Percentage(Percentage(1.0)),
+          };
+          if ms_points_x.is_none()
+            && matches!(axis, ScrollSnapAxis::X | ScrollSnapAxis::Inline | ScrollSnapAxis::Both)
+          {
+            ms_points_x = Some(page());
+          }
+          if ms_points_y.is_none()
+            && matches!(axis, ScrollSnapAxis::Y | ScrollSnapAxis::Block | ScrollSnapAxis::Both)
+          {
+            ms_points_y = Some(page());
+          }
+        }
+      }
+    }
+
+    if let Some(val) = ms_snap_type {
+      dest.push(Property::ScrollSnapType(val, VendorPrefix::Ms));
+    }
+
+    if let Some(val) = ms_points_x {
+      dest.push(Property::ScrollSnapPointsX(val, VendorPrefix::Ms));
+    }
+
+    if let Some(val) = ms_points_y {
+      dest.push(Property::ScrollSnapPointsY(val, VendorPrefix::Ms));
+    }
+
+    if let Some(val) = snap_type {
+      dest.push(Property::ScrollSnapType(val, VendorPrefix::None));
+    }
+  }
+}
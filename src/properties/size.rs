@@ -2,13 +2,15 @@
 
 use crate::compat::Feature;
 use crate::context::PropertyHandlerContext;
-use crate::declaration::DeclarationList;
+use crate::declaration::{DeclarationBlock, DeclarationList};
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::logical::PropertyCategory;
+use crate::macros::{define_shorthand, enum_property, size_shorthand};
 use crate::printer::Printer;
-use crate::properties::{Property, PropertyId};
-use crate::traits::{Parse, PropertyHandler, ToCss};
-use crate::values::length::LengthPercentage;
+use crate::properties::{anchor::AnchorSizeFunction, Property, PropertyId};
+use crate::traits::{Parse, PropertyHandler, Shorthand, ToCss, TrySign};
+use crate::values::length::{Length, LengthPercentage};
+use crate::values::size::Size2D;
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
@@ -24,13 +26,14 @@ use crate::serialization::*;
 /// i.e. `width` and `height.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
 #[cfg_attr(
   feature = "serde",
   derive(serde::Serialize, serde::Deserialize),
   serde(tag = "type", rename_all = "kebab-case")
 )]
 #[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
-pub enum Size {
+pub enum Size<'i> {
   /// The `auto` keyword.
   Auto,
   /// An explicit length or percentage.
@@ -53,9 +56,12 @@ pub enum Size {
   Stretch(VendorPrefix),
   /// The `contain` keyword.
   Contain,
+  /// An `anchor-size()` function.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  AnchorSize(AnchorSizeFunction<'i>),
 }
 
-impl<'i> Parse<'i> for Size {
+impl<'i> Parse<'i> for Size<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let res = input.try_parse(|input| {
       let ident = input.expect_ident()?;
@@ -86,12 +92,16 @@ impl<'i> Parse<'i> for Size {
       return Ok(Size::FitContentFunction(res));
     }
 
+    if let Ok(res) = input.try_parse(AnchorSizeFunction::parse) {
+      return Ok(Size::AnchorSize(res));
+    }
+
     let lp = input.try_parse(LengthPercentage::parse)?;
     Ok(Size::LengthPercentage(lp))
   }
 }
 
-impl ToCss for Size {
+impl<'i> ToCss for Size<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
     W: std::fmt::Write,
@@ -124,6 +134,7 @@ impl ToCss for Size {
         dest.write_str(")")
       }
       LengthPercentage(l) => l.to_css(dest),
+      AnchorSize(a) => a.to_css(dest),
     }
   }
 }
@@ -133,13 +144,14 @@ impl ToCss for Size {
 /// e.g. `min-width` and `max-height`.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
 #[cfg_attr(
   feature = "serde",
   derive(serde::Serialize, serde::Deserialize),
   serde(tag = "type", rename_all = "kebab-case")
 )]
 #[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
-pub enum MaxSize {
+pub enum MaxSize<'i> {
   /// The `none` keyword.
   None,
   /// An explicit length or percentage.
@@ -162,9 +174,12 @@ pub enum MaxSize {
   Stretch(VendorPrefix),
   /// The `contain` keyword.
   Contain,
+  /// An `anchor-size()` function.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  AnchorSize(AnchorSizeFunction<'i>),
 }
 
-impl<'i> Parse<'i> for MaxSize {
+impl<'i> Parse<'i> for MaxSize<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let res = input.try_parse(|input| {
       let ident = input.expect_ident()?;
@@ -195,12 +210,16 @@ impl<'i> Parse<'i> for MaxSize {
       return Ok(MaxSize::FitContentFunction(res));
     }
 
+    if let Ok(res) = input.try_parse(AnchorSizeFunction::parse) {
+      return Ok(MaxSize::AnchorSize(res));
+    }
+
     let lp = input.try_parse(LengthPercentage::parse)?;
     Ok(MaxSize::LengthPercentage(lp))
   }
 }
 
-impl ToCss for MaxSize {
+impl<'i> ToCss for MaxSize<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
     W: std::fmt::Write,
@@ -233,6 +252,7 @@ impl ToCss for MaxSize {
         dest.write_str(")")
       }
       LengthPercentage(l) => l.to_css(dest),
+      AnchorSize(a) => a.to_css(dest),
     }
   }
 }
@@ -254,6 +274,69 @@ enum_property! {
   }
 }
 
+// https://drafts.csswg.org/css-sizing-4/#intrinsic-size-override
+
+/// A value for the [contain-intrinsic-width](https://drafts.csswg.org/css-sizing-4/#propdef-contain-intrinsic-width),
+/// [contain-intrinsic-height](https://drafts.csswg.org/css-sizing-4/#propdef-contain-intrinsic-height),
+/// [contain-intrinsic-block-size](https://drafts.csswg.org/css-sizing-4/#propdef-contain-intrinsic-block-size), and
+/// [contain-intrinsic-inline-size](https://drafts.csswg.org/css-sizing-4/#propdef-contain-intrinsic-inline-size) properties.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ContainIntrinsicLength {
+  /// Whether the `auto` keyword was specified, i.e. the last remembered size should be used if available.
+  pub auto: bool,
+  /// The explicit intrinsic size, or `None` for the `none` keyword.
+  pub length: Option<Length>,
+}
+
+impl<'i> Parse<'i> for ContainIntrinsicLength {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let auto = input.try_parse(|input| input.expect_ident_matching("auto")).is_ok();
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(ContainIntrinsicLength { auto, length: None });
+    }
+
+    let location = input.current_source_location();
+    let length = Length::parse(input)?;
+    if length.is_sign_negative() {
+      return Err(location.new_custom_error(ParserError::InvalidValue));
+    }
+
+    Ok(ContainIntrinsicLength {
+      auto,
+      length: Some(length),
+    })
+  }
+}
+
+impl ToCss for ContainIntrinsicLength {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if self.auto {
+      dest.write_str("auto ")?;
+    }
+
+    match &self.length {
+      Some(length) => length.to_css(dest),
+      None => dest.write_str("none"),
+    }
+  }
+}
+
+size_shorthand! {
+  /// A value for the [contain-intrinsic-size](https://drafts.csswg.org/css-sizing-4/#propdef-contain-intrinsic-size) shorthand property.
+  pub struct ContainIntrinsicSize<ContainIntrinsicLength> {
+    /// The intrinsic width.
+    width: ContainIntrinsicWidth,
+    /// The intrinsic height.
+    height: ContainIntrinsicHeight,
+  }
+}
+
 #[derive(Default)]
 pub(crate) struct SizeHandler;
 
@@ -355,3 +438,104 @@ impl<'i> PropertyHandler<'i> for SizeHandler {
 
   fn finalize(&mut self, _: &mut DeclarationList, _: &mut PropertyHandlerContext<'i, '_>) {}
 }
+
+#[derive(Default)]
+pub(crate) struct ContainIntrinsicSizeHandler {
+  width: Option<ContainIntrinsicLength>,
+  height: Option<ContainIntrinsicLength>,
+  block_size: Option<ContainIntrinsicLength>,
+  inline_size: Option<ContainIntrinsicLength>,
+  category: PropertyCategory,
+  has_any: bool,
+}
+
+impl<'i> PropertyHandler<'i> for ContainIntrinsicSizeHandler {
+  fn handle_property(
+    &mut self,
+    property: &Property<'i>,
+    dest: &mut DeclarationList<'i>,
+    context: &mut PropertyHandlerContext<'i, '_>,
+  ) -> bool {
+    let logical_supported = context.is_supported(Feature::LogicalContainIntrinsicSize);
+
+    macro_rules! property {
+      ($key: ident, $val: expr, $category: ident) => {{
+        if PropertyCategory::$category != self.category {
+          self.flush(dest);
+        }
+        self.$key = Some($val.clone());
+        self.category = PropertyCategory::$category;
+        self.has_any = true;
+      }};
+    }
+
+    // When logical properties are not supported, the block size maps to the height,
+    // and the inline size maps to the width, as with other sizing properties.
+    match property {
+      Property::ContainIntrinsicWidth(val) => property!(width, val, Physical),
+      Property::ContainIntrinsicHeight(val) => property!(height, val, Physical),
+      Property::ContainIntrinsicSize(val) => {
+        property!(width, val.width, Physical);
+        property!(height, val.height, Physical);
+      }
+      Property::ContainIntrinsicBlockSize(val) if logical_supported => property!(block_size, val, Logical),
+      Property::ContainIntrinsicInlineSize(val) if logical_supported => property!(inline_size, val, Logical),
+      Property::ContainIntrinsicBlockSize(val) => property!(height, val, Physical),
+      Property::ContainIntrinsicInlineSize(val) => property!(width, val, Physical),
+      Property::Unparsed(unparsed) => {
+        let property_id = match &unparsed.property_id {
+          PropertyId::ContainIntrinsicBlockSize if !logical_supported => PropertyId::ContainIntrinsicHeight,
+          PropertyId::ContainIntrinsicInlineSize if !logical_supported => PropertyId::ContainIntrinsicWidth,
+          PropertyId::ContainIntrinsicSize
+          | PropertyId::ContainIntrinsicWidth
+          | PropertyId::ContainIntrinsicHeight
+          | PropertyId::ContainIntrinsicBlockSize
+          | PropertyId::ContainIntrinsicInlineSize => unparsed.property_id.clone(),
+          _ => return false,
+        };
+
+        self.flush(dest);
+        dest.push(Property::Unparsed(unparsed.with_property_id(property_id)));
+      }
+      _ => return false,
+    }
+
+    true
+  }
+
+  fn finalize(&mut self, dest: &mut DeclarationList<'i>, _: &mut PropertyHandlerContext<'i, '_>) {
+    self.flush(dest);
+  }
+}
+
+impl ContainIntrinsicSizeHandler {
+  fn flush<'i>(&mut self, dest: &mut DeclarationList<'i>) {
+    if !self.has_any {
+      return;
+    }
+
+    self.has_any = false;
+
+    match (self.width.take(), self.height.take()) {
+      (Some(width), Some(height)) => {
+        dest.push(Property::ContainIntrinsicSize(ContainIntrinsicSize { width, height }))
+      }
+      (width, height) => {
+        if let Some(width) = width {
+          dest.push(Property::ContainIntrinsicWidth(width));
+        }
+        if let Some(height) = height {
+          dest.push(Property::ContainIntrinsicHeight(height));
+        }
+      }
+    }
+
+    if let Some(block_size) = self.block_size.take() {
+      dest.push(Property::ContainIntrinsicBlockSize(block_size));
+    }
+
+    if let Some(inline_size) = self.inline_size.take() {
+      dest.push(Property::ContainIntrinsicInlineSize(inline_size));
+    }
+  }
+}
@@ -212,20 +212,209 @@ impl ToCss for TextTransform {
 }
 
 enum_property! {
-  /// A value for the [white-space](https://www.w3.org/TR/2021/CRD-css-text-3-20210422/#white-space-property) property.
-  pub enum WhiteSpace {
+  /// A value for the [white-space-collapse](https://drafts.csswg.org/css-text-4/#white-space-collapsing) property.
+  #[derive(Default)]
+  pub enum WhiteSpaceCollapse {
     /// Sequences of white space are collapsed into a single character.
-    "normal": Normal,
-    /// White space is not collapsed.
-    "pre": Pre,
-    /// White space is collapsed, but no line wrapping occurs.
-    "nowrap": NoWrap,
-    /// White space is preserved, but line wrapping occurs.
-    "pre-wrap": PreWrap,
-    /// Like pre-wrap, but with different line breaking rules.
+    #[default]
+    "collapse": Collapse,
+    /// All white space is removed.
+    "discard": Discard,
+    /// White space and segment breaks are preserved.
+    "preserve": Preserve,
+    /// White space is collapsed, but segment breaks are preserved.
+    "preserve-breaks": PreserveBreaks,
+    /// White space is preserved, but segment breaks are collapsed.
+    "preserve-spaces": PreserveSpaces,
+    /// Like preserve, but preserved white space takes up space and provides wrap opportunities.
     "break-spaces": BreakSpaces,
-    /// White space is collapsed, but with different line breaking rules.
-    "pre-line": PreLine,
+  }
+}
+
+enum_property! {
+  /// A value for the [text-wrap-mode](https://drafts.csswg.org/css-text-4/#text-wrap-mode) property.
+  #[derive(Default)]
+  pub enum TextWrapMode {
+    /// Lines may break at allowed soft wrap opportunities.
+    #[default]
+    "wrap": Wrap,
+    /// Lines do not break at soft wrap opportunities.
+    "nowrap": NoWrap,
+  }
+}
+
+enum_property! {
+  /// A value for the [text-wrap-style](https://drafts.csswg.org/css-text-4/#text-wrap-style) property.
+  #[derive(Default)]
+  pub enum TextWrapStyle {
+    /// The UA chooses how to wrap lines, favoring speed over quality.
+    #[default]
+    Auto,
+    /// Line breaks are chosen to balance the remaining inline space across lines.
+    Balance,
+    /// The content on lines before the one being edited does not change when editing.
+    Stable,
+    /// Line breaks are chosen to favor better layout over speed.
+    Pretty,
+  }
+}
+
+define_shorthand! {
+  /// A value for the [white-space](https://drafts.csswg.org/css-text-4/#white-space-property) shorthand property.
+  pub struct WhiteSpace {
+    /// How white space is collapsed.
+    collapse: WhiteSpaceCollapse(WhiteSpaceCollapse),
+    /// Whether lines may wrap.
+    wrap_mode: TextWrapMode(TextWrapMode),
+  }
+}
+
+impl WhiteSpace {
+  /// Returns the legacy `white-space` keyword equivalent to this value, if any.
+  fn legacy_keyword(&self) -> Option<&'static str> {
+    use TextWrapMode::*;
+    use WhiteSpaceCollapse::*;
+    match (self.collapse, self.wrap_mode) {
+      (Collapse, Wrap) => Some("normal"),
+      (Collapse, NoWrap) => Some("nowrap"),
+      (Preserve, Wrap) => Some("pre-wrap"),
+      (Preserve, NoWrap) => Some("pre"),
+      (PreserveBreaks, Wrap) => Some("pre-line"),
+      (BreakSpaces, Wrap) => Some("break-spaces"),
+      _ => None,
+    }
+  }
+}
+
+impl<'i> Parse<'i> for WhiteSpace {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    let legacy = input.try_parse(|input| {
+      let location = input.current_source_location();
+      let ident = input.expect_ident()?;
+      match_ignore_ascii_case! { &ident,
+        "normal" => Ok((WhiteSpaceCollapse::Collapse, TextWrapMode::Wrap)),
+        "pre" => Ok((WhiteSpaceCollapse::Preserve, TextWrapMode::NoWrap)),
+        "pre-wrap" => Ok((WhiteSpaceCollapse::Preserve, TextWrapMode::Wrap)),
+        "pre-line" => Ok((WhiteSpaceCollapse::PreserveBreaks, TextWrapMode::Wrap)),
+        _ => Err(location.new_unexpected_token_error::<ParserError<'i>>(Token::Ident(ident.clone())))
+      }
+    });
+
+    if let Ok((collapse, wrap_mode)) = legacy {
+      return Ok(WhiteSpace { collapse, wrap_mode });
+    }
+
+    let mut collapse = None;
+    let mut wrap_mode = None;
+    loop {
+      if collapse.is_none() {
+        if let Ok(value) = input.try_parse(WhiteSpaceCollapse::parse) {
+          collapse = Some(value);
+          continue;
+        }
+      }
+
+      if wrap_mode.is_none() {
+        if let Ok(value) = input.try_parse(TextWrapMode::parse) {
+          wrap_mode = Some(value);
+          continue;
+        }
+      }
+
+      break;
+    }
+
+    if collapse.is_none() && wrap_mode.is_none() {
+      return Err(location.new_custom_error(ParserError::InvalidValue));
+    }
+
+    Ok(WhiteSpace {
+      collapse: collapse.unwrap_or_default(),
+      wrap_mode: wrap_mode.unwrap_or_default(),
+    })
+  }
+}
+
+impl ToCss for WhiteSpace {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    // Prefer the legacy keywords, which are shorter and supported everywhere.
+    if let Some(keyword) = self.legacy_keyword() {
+      return dest.write_str(keyword);
+    }
+
+    // Legacy keywords cover all combinations where both values are defaults.
+    self.collapse.to_css(dest)?;
+    if self.wrap_mode != TextWrapMode::default() {
+      dest.write_char(' ')?;
+      self.wrap_mode.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
+
+define_shorthand! {
+  /// A value for the [text-wrap](https://drafts.csswg.org/css-text-4/#text-wrap) shorthand property.
+  pub struct TextWrap {
+    /// Whether lines may wrap.
+    mode: TextWrapMode(TextWrapMode),
+    /// How lines are wrapped.
+    style: TextWrapStyle(TextWrapStyle),
+  }
+}
+
+impl<'i> Parse<'i> for TextWrap {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    let mut mode = None;
+    let mut style = None;
+    loop {
+      if mode.is_none() {
+        if let Ok(value) = input.try_parse(TextWrapMode::parse) {
+          mode = Some(value);
+          continue;
+        }
+      }
+
+      if style.is_none() {
+        if let Ok(value) = input.try_parse(TextWrapStyle::parse) {
+          style = Some(value);
+          continue;
+        }
+      }
+
+      break;
+    }
+
+    if mode.is_none() && style.is_none() {
+      return Err(location.new_custom_error(ParserError::InvalidValue));
+    }
+
+    Ok(TextWrap {
+      mode: mode.unwrap_or_default(),
+      style: style.unwrap_or_default(),
+    })
+  }
+}
+
+impl ToCss for TextWrap {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if self.style == TextWrapStyle::default() {
+      return self.mode.to_css(dest);
+    }
+
+    if self.mode != TextWrapMode::default() {
+      self.mode.to_css(dest)?;
+      dest.write_char(' ')?;
+    }
+
+    self.style.to_css(dest)
   }
 }
 
@@ -1117,6 +1306,130 @@ impl ToCss for TextEmphasisPosition {
   }
 }
 
+#[derive(Default)]
+pub(crate) struct WhiteSpaceHandler {
+  targets: Option<Browsers>,
+  collapse: Option<WhiteSpaceCollapse>,
+  wrap_mode: Option<TextWrapMode>,
+  wrap_style: Option<TextWrapStyle>,
+  white_space: Option<WhiteSpace>,
+}
+
+impl WhiteSpaceHandler {
+  pub fn new(targets: Option<Browsers>) -> WhiteSpaceHandler {
+    WhiteSpaceHandler {
+      targets,
+      ..WhiteSpaceHandler::default()
+    }
+  }
+}
+
+impl<'i> PropertyHandler<'i> for WhiteSpaceHandler {
+  fn handle_property(
+    &mut self,
+    property: &Property<'i>,
+    dest: &mut DeclarationList<'i>,
+    context: &mut PropertyHandlerContext<'i, '_>,
+  ) -> bool {
+    use Property::*;
+
+    match property {
+      WhiteSpaceCollapse(val) => self.collapse = Some(*val),
+      TextWrapMode(val) => self.wrap_mode = Some(*val),
+      TextWrapStyle(val) => self.wrap_style = Some(*val),
+      WhiteSpace(val) => {
+        self.collapse = Some(val.collapse);
+        self.wrap_mode = Some(val.wrap_mode);
+        self.white_space = Some(val.clone());
+      }
+      TextWrap(val) => {
+        self.wrap_mode = Some(val.mode);
+        self.wrap_style = Some(val.style);
+      }
+      Unparsed(val)
+        if matches!(
+          val.property_id,
+          PropertyId::WhiteSpaceCollapse
+            | PropertyId::TextWrapMode
+            | PropertyId::TextWrapStyle
+            | PropertyId::WhiteSpace
+            | PropertyId::TextWrap
+        ) =>
+      {
+        self.finalize(dest, context);
+        dest.push(property.clone());
+      }
+      _ => return false,
+    }
+
+    true
+  }
+
+  fn finalize(&mut self, dest: &mut DeclarationList, _: &mut PropertyHandlerContext<'i, '_>) {
+    let collapse = std::mem::take(&mut self.collapse);
+    let mut wrap_mode = std::mem::take(&mut self.wrap_mode);
+    let wrap_style = std::mem::take(&mut self.wrap_style);
+    let original = std::mem::take(&mut self.white_space);
+    let mut merged_mode = None;
+
+    if let (Some(collapse), Some(mode)) = (collapse, wrap_mode) {
+      let white_space = WhiteSpace {
+        collapse,
+        wrap_mode: mode,
+      };
+
+      // Use the white-space shorthand if the value can be represented by a legacy keyword, or
+      // the multi-value syntax is supported by all targets.
+      if white_space.legacy_keyword().is_some()
+        || self.targets.map_or(true, |targets| {
+          compat::Feature::WhiteSpaceCollapse.is_compatible(targets)
+        })
+      {
+        dest.push(Property::WhiteSpace(white_space));
+        merged_mode = wrap_mode.take();
+      } else if let Some(original) = original {
+        // The longhands are not supported by all targets, so keep the original white-space
+        // value, followed by the longhands that were overridden after it.
+        dest.push(Property::WhiteSpace(original.clone()));
+        if collapse != original.collapse {
+          dest.push(Property::WhiteSpaceCollapse(collapse));
+        }
+        if mode == original.wrap_mode {
+          merged_mode = wrap_mode.take();
+        }
+      } else {
+        dest.push(Property::WhiteSpaceCollapse(collapse));
+      }
+    } else if let Some(collapse) = collapse {
+      dest.push(Property::WhiteSpaceCollapse(collapse));
+    }
+
+    // text-wrap-style is supported by fewer browsers than the text-wrap shorthand, so
+    // don't split it out of the shorthand unless all targets support it.
+    if merged_mode.is_some()
+      && wrap_style.is_some()
+      && !self
+        .targets
+        .map_or(true, |targets| compat::Feature::TextWrapStyle.is_compatible(targets))
+    {
+      wrap_mode = merged_mode;
+    }
+
+    match (wrap_mode, wrap_style) {
+      (Some(mode), Some(style)) => dest.push(Property::TextWrap(TextWrap { mode, style })),
+      _ => {
+        if let Some(mode) = wrap_mode {
+          dest.push(Property::TextWrapMode(mode));
+        }
+
+        if let Some(style) = wrap_style {
+          dest.push(Property::TextWrapStyle(style));
+        }
+      }
+    }
+  }
+}
+
 #[derive(Default)]
 pub(crate) struct TextDecorationHandler<'i> {
   targets: Option<Browsers>,
@@ -1210,6 +1523,7 @@ impl<'i> PropertyHandler<'i> for TextDecorationHandler<'i> {
               dest.push(property.clone());
             } else {
               context.add_logical_rule(
+                dest,
                 Property::TextAlign(TextAlign::$ltr),
                 Property::TextAlign(TextAlign::$rtl),
               );
@@ -1259,16 +1573,32 @@ impl<'i> PropertyHandler<'i> for TextDecorationHandler<'i> {
     if let (Some((line, line_vp)), Some(thickness_val), Some((style, style_vp)), Some((color, color_vp))) =
       (&mut line, &mut thickness, &mut style, &mut color)
     {
-      let intersection = *line_vp | *style_vp | *color_vp;
+      let intersection = *line_vp & *style_vp & *color_vp;
       if !intersection.is_empty() {
         let mut prefix = intersection;
 
-        // Some browsers don't support thickness in the shorthand property yet.
-        let supports_thickness = if let Some(targets) = self.targets {
-          compat::Feature::TextDecorationThicknessShorthand.is_compatible(targets)
-        } else {
-          true
-        };
+        // Only add prefixes if one of the new sub-properties was used
+        if prefix.contains(VendorPrefix::None)
+          && (*style != TextDecorationStyle::default() || *color != CssColor::current_color())
+        {
+          if let Some(targets) = self.targets {
+            prefix = Feature::TextDecoration.prefixes_for(targets);
+          }
+        }
+
+        // The prefixed shorthands never accept a thickness, and some browsers don't support it in the
+        // unprefixed shorthand yet. In these cases, the thickness is output as a separate longhand instead.
+        let supports_thickness = prefix == VendorPrefix::None
+          && match (self.targets, &*thickness_val) {
+            (Some(targets), thickness) => {
+              compat::Feature::TextDecorationThicknessShorthand.is_compatible(targets)
+                && (!matches!(
+                  thickness,
+                  TextDecorationThickness::LengthPercentage(LengthPercentage::Percentage(_))
+                ) || compat::Feature::TextDecorationThicknessPercent.is_compatible(targets))
+            }
+            (None, _) => true,
+          };
 
         let mut decoration = TextDecoration {
           line: line.clone(),
@@ -1281,13 +1611,8 @@ impl<'i> PropertyHandler<'i> for TextDecorationHandler<'i> {
           color: color.clone(),
         };
 
-        // Only add prefixes if one of the new sub-properties was used
-        if prefix.contains(VendorPrefix::None)
-          && (*style != TextDecorationStyle::default() || *color != CssColor::current_color())
-        {
+        if prefix.contains(VendorPrefix::None) {
           if let Some(targets) = self.targets {
-            prefix = Feature::TextDecoration.prefixes_for(targets);
-
             let fallbacks = decoration.get_fallbacks(targets);
             for fallback in fallbacks {
               dest.push(Property::TextDecoration(fallback, prefix))
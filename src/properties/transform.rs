@@ -66,6 +66,7 @@ impl ToCss for TransformList {
           &mut base,
           PrinterOptions {
             minify: true,
+            precision: dest.precision,
             ..PrinterOptions::default()
           },
         ))?;
@@ -78,6 +79,7 @@ impl ToCss for TransformList {
             &mut decomposed,
             PrinterOptions {
               minify: true,
+              precision: dest.precision,
               ..PrinterOptions::default()
             },
           ))?;
@@ -93,6 +95,7 @@ impl ToCss for TransformList {
             &mut mat,
             PrinterOptions {
               minify: true,
+              precision: dest.precision,
               ..PrinterOptions::default()
             },
           ))?
@@ -101,6 +104,7 @@ impl ToCss for TransformList {
             &mut mat,
             PrinterOptions {
               minify: true,
+              precision: dest.precision,
               ..PrinterOptions::default()
             },
           ))?
@@ -2,10 +2,11 @@
 
 use super::{Property, PropertyId};
 use crate::compat;
+use crate::compat::Feature as CompatFeature;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::{DeclarationBlock, DeclarationList};
-use crate::error::{ParserError, PrinterError};
-use crate::macros::define_list_shorthand;
+use crate::error::{ErrorWithLocation, ParserError, PrinterError};
+use crate::macros::{define_list_shorthand, enum_property};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::masking::get_webkit_mask_property;
@@ -32,6 +33,20 @@ define_list_shorthand! {
     delay: TransitionDelay(Time, VendorPrefix),
     /// The easing function for the transition.
     timing_function: TransitionTimingFunction(EasingFunction, VendorPrefix),
+    /// Whether discrete properties are transitioned.
+    behavior: TransitionBehavior(TransitionBehavior),
+  }
+}
+
+enum_property! {
+  /// A value for the [transition-behavior](https://drafts.csswg.org/css-transitions-2/#transition-behavior-property) property.
+  #[derive(Default)]
+  pub enum TransitionBehavior {
+    /// Transitions are not started for discretely animated properties.
+    #[default]
+    "normal": Normal,
+    /// Transitions are started for discretely animated properties.
+    "allow-discrete": AllowDiscrete,
   }
 }
 
@@ -41,6 +56,7 @@ impl<'i> Parse<'i> for Transition<'i> {
     let mut duration = None;
     let mut delay = None;
     let mut timing_function = None;
+    let mut behavior = None;
 
     loop {
       if duration.is_none() {
@@ -64,6 +80,14 @@ impl<'i> Parse<'i> for Transition<'i> {
         }
       }
 
+      // Parsed before the property, which would otherwise accept these keywords as custom property names.
+      if behavior.is_none() {
+        if let Ok(value) = input.try_parse(TransitionBehavior::parse) {
+          behavior = Some(value);
+          continue;
+        }
+      }
+
       if property.is_none() {
         if let Ok(value) = input.try_parse(PropertyId::parse) {
           property = Some(value);
@@ -79,6 +103,7 @@ impl<'i> Parse<'i> for Transition<'i> {
       duration: duration.unwrap_or(Time::Seconds(0.0)),
       delay: delay.unwrap_or(Time::Seconds(0.0)),
       timing_function: timing_function.unwrap_or(EasingFunction::Ease),
+      behavior: behavior.unwrap_or_default(),
     })
   }
 }
@@ -104,6 +129,11 @@ impl<'i> ToCss for Transition<'i> {
       self.delay.to_css(dest)?;
     }
 
+    if self.behavior != TransitionBehavior::default() {
+      dest.write_char(' ')?;
+      self.behavior.to_css(dest)?;
+    }
+
     Ok(())
   }
 }
@@ -115,6 +145,7 @@ pub(crate) struct TransitionHandler<'i> {
   durations: Option<(SmallVec<[Time; 1]>, VendorPrefix)>,
   delays: Option<(SmallVec<[Time; 1]>, VendorPrefix)>,
   timing_functions: Option<(SmallVec<[EasingFunction; 1]>, VendorPrefix)>,
+  behaviors: Option<SmallVec<[TransitionBehavior; 1]>>,
   has_any: bool,
 }
 
@@ -136,6 +167,19 @@ impl<'i> PropertyHandler<'i> for TransitionHandler<'i> {
   ) -> bool {
     use Property::*;
 
+    let allows_discrete = match property {
+      TransitionBehavior(val) => val.contains(&self::TransitionBehavior::AllowDiscrete),
+      Transition(val, _) => val.iter().any(|t| t.behavior == self::TransitionBehavior::AllowDiscrete),
+      _ => false,
+    };
+
+    if allows_discrete && !context.is_supported(CompatFeature::TransitionBehavior) {
+      context.warnings.push(ErrorWithLocation {
+        kind: ParserError::UnsupportedValue("allow-discrete".into()),
+        loc: context.loc,
+      });
+    }
+
     macro_rules! maybe_flush {
       ($prop: ident, $val: expr, $vp: ident) => {{
         // If two vendor prefixes for the same property have different
@@ -182,6 +226,10 @@ impl<'i> PropertyHandler<'i> for TransitionHandler<'i> {
       TransitionDuration(val, vp) => property!(TransitionDuration, durations, val, vp),
       TransitionDelay(val, vp) => property!(TransitionDelay, delays, val, vp),
       TransitionTimingFunction(val, vp) => property!(TransitionTimingFunction, timing_functions, val, vp),
+      TransitionBehavior(val) => {
+        self.behaviors = Some(val.clone());
+        self.has_any = true;
+      }
       Transition(val, vp) => {
         let properties: SmallVec<[PropertyId; 1]> = val.iter().map(|b| b.property.clone()).collect();
         maybe_flush!(properties, &properties, vp);
@@ -200,6 +248,11 @@ impl<'i> PropertyHandler<'i> for TransitionHandler<'i> {
         property!(TransitionDuration, durations, &durations, vp);
         property!(TransitionDelay, delays, &delays, vp);
         property!(TransitionTimingFunction, timing_functions, &timing_functions, vp);
+
+        // transition-behavior is not vendor prefixed, so only the unprefixed shorthand resets it.
+        if vp.contains(VendorPrefix::None) {
+          self.behaviors = Some(val.iter().map(|b| b.behavior).collect());
+        }
       }
       Unparsed(val) if is_transition_property(&val.property_id) => {
         self.flush(dest, context);
@@ -228,6 +281,7 @@ impl<'i> TransitionHandler<'i> {
     let mut durations = std::mem::take(&mut self.durations);
     let mut delays = std::mem::take(&mut self.delays);
     let mut timing_functions = std::mem::take(&mut self.timing_functions);
+    let mut behaviors = std::mem::take(&mut self.behaviors);
 
     let rtl_properties = if let Some((properties, _)) = &mut properties {
       expand_properties(properties, self.targets, context)
@@ -247,6 +301,13 @@ impl<'i> TransitionHandler<'i> {
       // prefixes will be handled by outputing individual properties below.
       let intersection = *property_prefixes & *duration_prefixes & *delay_prefixes & *timing_prefixes;
       if !intersection.is_empty() {
+        // Browsers that don't support transition-behavior drop the whole shorthand if it includes one.
+        // In this case, the behavior is output as a separate longhand instead.
+        let supports_behavior = match self.targets {
+          Some(targets) => CompatFeature::TransitionBehavior.is_compatible(targets),
+          None => true,
+        };
+
         macro_rules! get_transitions {
           ($properties: ident) => {{
             // transition-property determines the number of transitions. The values of other
@@ -255,15 +316,22 @@ impl<'i> TransitionHandler<'i> {
             let mut durations_iter = durations.iter().cycle().cloned();
             let mut delays_iter = delays.iter().cycle().cloned();
             let mut timing_iter = timing_functions.iter().cycle().cloned();
+            let mut behaviors_iter = behaviors.iter().flatten().cycle().cloned();
             for property_id in $properties {
               let duration = durations_iter.next().unwrap_or(Time::Seconds(0.0));
               let delay = delays_iter.next().unwrap_or(Time::Seconds(0.0));
               let timing_function = timing_iter.next().unwrap_or(EasingFunction::Ease);
+              let behavior = if supports_behavior {
+                behaviors_iter.next().unwrap_or_default()
+              } else {
+                TransitionBehavior::default()
+              };
               let transition = Transition {
                 property: property_id.clone(),
                 duration,
                 delay,
                 timing_function,
+                behavior,
               };
 
               // Expand vendor prefixes into multiple transitions.
@@ -282,6 +350,7 @@ impl<'i> TransitionHandler<'i> {
         if let Some(rtl_properties) = &rtl_properties {
           let rtl_transitions = get_transitions!(rtl_properties);
           context.add_logical_rule(
+            dest,
             Property::Transition(transitions, intersection),
             Property::Transition(rtl_transitions, intersection),
           );
@@ -293,6 +362,13 @@ impl<'i> TransitionHandler<'i> {
         duration_prefixes.remove(intersection);
         delay_prefixes.remove(intersection);
         timing_prefixes.remove(intersection);
+
+        // The unprefixed shorthand includes the transition behavior.
+        if intersection.contains(VendorPrefix::None)
+          && (supports_behavior || behaviors.iter().flatten().all(|b| *b == TransitionBehavior::default()))
+        {
+          behaviors = None;
+        }
       }
     }
 
@@ -300,6 +376,7 @@ impl<'i> TransitionHandler<'i> {
       if !prefix.is_empty() {
         if let Some(rtl_properties) = rtl_properties {
           context.add_logical_rule(
+            dest,
             Property::TransitionProperty(properties, prefix),
             Property::TransitionProperty(rtl_properties, prefix),
           );
@@ -327,6 +404,10 @@ impl<'i> TransitionHandler<'i> {
       }
     }
 
+    if let Some(behaviors) = behaviors {
+      dest.push(Property::TransitionBehavior(behaviors));
+    }
+
     self.reset();
   }
 
@@ -335,6 +416,7 @@ impl<'i> TransitionHandler<'i> {
     self.durations = None;
     self.delays = None;
     self.timing_functions = None;
+    self.behaviors = None;
   }
 }
 
@@ -345,6 +427,7 @@ fn is_transition_property(property_id: &PropertyId) -> bool {
     | PropertyId::TransitionDuration(_)
     | PropertyId::TransitionDelay(_)
     | PropertyId::TransitionTimingFunction(_)
+    | PropertyId::TransitionBehavior
     | PropertyId::Transition(_) => true,
     _ => false,
   }
@@ -412,3 +412,23 @@ impl<'a> schemars::JsonSchema for Appearance<'a> {
     "Appearance".into()
   }
 }
+
+enum_property! {
+  /// A value for the [field-sizing](https://drafts.csswg.org/css-ui-4/#field-sizing) property.
+  pub enum FieldSizing {
+    /// The element has a fixed default size, regardless of its contents.
+    Fixed,
+    /// The element is sized to fit its contents.
+    Content,
+  }
+}
+
+enum_property! {
+  /// A value for the [interpolate-size](https://drafts.csswg.org/css-values-5/#interpolate-size) property.
+  pub enum InterpolateSize {
+    /// Only numeric sizes can be interpolated.
+    "numeric-only": NumericOnly,
+    /// Intrinsic size keywords such as `auto` and `fit-content` can also be interpolated.
+    "allow-keywords": AllowKeywords,
+  }
+}
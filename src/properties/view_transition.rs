@@ -0,0 +1,56 @@
+//! CSS properties related to view transitions.
+
+use crate::error::{ParserError, PrinterError};
+use crate::printer::Printer;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::CustomIdent;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+
+/// A value for the [view-transition-name](https://drafts.csswg.org/css-view-transitions-1/#view-transition-name-prop) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ViewTransitionName<'i> {
+  /// The element will not participate independently in a view transition.
+  None,
+  /// The element will participate in a view transition with an automatically generated name.
+  Auto,
+  /// The element will participate in a view transition with the given name.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Custom(CustomIdent<'i>),
+}
+
+impl<'i> Parse<'i> for ViewTransitionName<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(ViewTransitionName::None);
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(ViewTransitionName::Auto);
+    }
+
+    Ok(ViewTransitionName::Custom(CustomIdent::parse(input)?))
+  }
+}
+
+impl<'i> ToCss for ViewTransitionName<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      ViewTransitionName::None => dest.write_str("none"),
+      ViewTransitionName::Auto => dest.write_str("auto"),
+      ViewTransitionName::Custom(name) => name.to_css(dest),
+    }
+  }
+}
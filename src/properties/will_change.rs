@@ -0,0 +1,232 @@
+//! The CSS will-change property.
+
+use std::collections::HashSet;
+
+use super::custom::CustomPropertyName;
+use super::{Property, PropertyId};
+use crate::context::PropertyHandlerContext;
+use crate::declaration::DeclarationList;
+use crate::error::{ErrorWithLocation, ParserError, PrinterError};
+use crate::printer::Printer;
+use crate::traits::{Parse, PropertyHandler, ToCss};
+use crate::values::string::CowArcStr;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+use smallvec::SmallVec;
+
+/// An [`<animateable-feature>`](https://drafts.csswg.org/css-will-change/#typedef-animateable-feature) value,
+/// as used in the `will-change` property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum AnimateableFeature<'i> {
+  /// The scroll position of the element is expected to change.
+  ScrollPosition,
+  /// The contents of the element are expected to change.
+  Contents,
+  /// The given property is expected to change. Unknown property names are
+  /// represented as a custom property id.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Property(PropertyId<'i>),
+}
+
+impl<'i> Parse<'i> for AnimateableFeature<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+    match_ignore_ascii_case! { ident,
+      "scroll-position" => Ok(AnimateableFeature::ScrollPosition),
+      "contents" => Ok(AnimateableFeature::Contents),
+      "will-change" | "none" | "all" | "auto" | "initial" | "inherit" | "unset" | "default" => {
+        Err(location.new_unexpected_token_error(Token::Ident(ident.clone())))
+      },
+      _ => Ok(AnimateableFeature::Property(CowArcStr::from(ident.clone()).into()))
+    }
+  }
+}
+
+impl<'i> ToCss for AnimateableFeature<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      AnimateableFeature::ScrollPosition => dest.write_str("scroll-position"),
+      AnimateableFeature::Contents => dest.write_str("contents"),
+      AnimateableFeature::Property(property_id) => property_id.to_css(dest),
+    }
+  }
+}
+
+impl<'i> AnimateableFeature<'i> {
+  /// Returns whether the feature refers to a property that is declared in the given set
+  /// of property names, either directly or via one of its longhands.
+  fn is_used(&self, used_properties: &HashSet<String>) -> bool {
+    let property_id = match self {
+      AnimateableFeature::Property(property_id) => property_id,
+      _ => return true,
+    };
+
+    if used_properties.contains(property_id.name()) {
+      return true;
+    }
+
+    if let Some(longhands) = property_id.longhands() {
+      return longhands.iter().any(|longhand| used_properties.contains(longhand.name()));
+    }
+
+    false
+  }
+}
+
+/// A value for the [will-change](https://drafts.csswg.org/css-will-change/#will-change) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum WillChange<'i> {
+  /// No particular optimizations are hinted.
+  Auto,
+  /// A list of features that are expected to change.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Features(SmallVec<[AnimateableFeature<'i>; 1]>),
+}
+
+impl<'i> Parse<'i> for WillChange<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
+      return Ok(WillChange::Auto);
+    }
+
+    let features = input.parse_comma_separated(AnimateableFeature::parse)?;
+    Ok(WillChange::Features(features.into()))
+  }
+}
+
+impl<'i> ToCss for WillChange<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      WillChange::Auto => dest.write_str("auto"),
+      WillChange::Features(features) => {
+        let mut first = true;
+        for feature in features {
+          if first {
+            first = false;
+          } else {
+            dest.delim(',', false)?;
+          }
+          feature.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+#[derive(Default)]
+pub(crate) struct WillChangeHandler<'i> {
+  will_change: Option<WillChange<'i>>,
+}
+
+impl<'i> PropertyHandler<'i> for WillChangeHandler<'i> {
+  fn handle_property(
+    &mut self,
+    property: &Property<'i>,
+    dest: &mut DeclarationList<'i>,
+    context: &mut PropertyHandlerContext<'i, '_>,
+  ) -> bool {
+    match property {
+      Property::WillChange(val) => {
+        if let WillChange::Features(features) = val {
+          for feature in features {
+            if let AnimateableFeature::Property(property_id) = feature {
+              add_warnings(property_id, context);
+            }
+          }
+        }
+
+        self.will_change = Some(val.clone());
+      }
+      Property::Unparsed(val) if matches!(val.property_id, PropertyId::WillChange) => {
+        self.finalize(dest, context);
+        dest.push(property.clone());
+      }
+      _ => return false,
+    }
+
+    true
+  }
+
+  fn finalize(&mut self, dest: &mut DeclarationList<'i>, context: &mut PropertyHandlerContext<'i, '_>) {
+    let mut will_change = match std::mem::take(&mut self.will_change) {
+      Some(will_change) => will_change,
+      None => return,
+    };
+
+    if let (WillChange::Features(features), Some(used_properties)) = (&mut will_change, context.used_properties) {
+      features.retain(|feature| feature.is_used(used_properties));
+      if features.is_empty() {
+        return;
+      }
+    }
+
+    dest.push(Property::WillChange(will_change));
+  }
+}
+
+fn add_warnings<'i>(property_id: &PropertyId<'i>, context: &mut PropertyHandlerContext<'i, '_>) {
+  let kind = match property_id {
+    PropertyId::Custom(CustomPropertyName::Unknown(name)) => ParserError::UnknownProperty(name.0.clone()),
+    property_id if !is_animatable(property_id) => {
+      ParserError::NonAnimatableProperty(property_id.name().to_owned().into())
+    }
+    _ => return,
+  };
+
+  context.warnings.push(ErrorWithLocation { kind, loc: context.loc });
+}
+
+#[inline]
+fn is_animatable(property_id: &PropertyId) -> bool {
+  !matches!(
+    property_id,
+    PropertyId::TransitionProperty(_)
+      | PropertyId::TransitionDuration(_)
+      | PropertyId::TransitionDelay(_)
+      | PropertyId::TransitionTimingFunction(_)
+      | PropertyId::TransitionBehavior
+      | PropertyId::Transition(_)
+      | PropertyId::AnimationName(_)
+      | PropertyId::AnimationDuration(_)
+      | PropertyId::AnimationTimingFunction(_)
+      | PropertyId::AnimationIterationCount(_)
+      | PropertyId::AnimationDirection(_)
+      | PropertyId::AnimationPlayState(_)
+      | PropertyId::AnimationDelay(_)
+      | PropertyId::AnimationFillMode(_)
+      | PropertyId::Animation(_)
+      | PropertyId::AnimationTimeline
+      | PropertyId::AnimationComposition
+      | PropertyId::AnimationRangeStart
+      | PropertyId::AnimationRangeEnd
+      | PropertyId::AnimationRange
+      | PropertyId::ContainerType
+      | PropertyId::ContainerName
+      | PropertyId::Container
+  )
+}
@@ -0,0 +1,181 @@
+//! Compatibility and size reports.
+//!
+//! # Compatibility reports
+//!
+//! When a style sheet is minified for a set of browser targets using
+//! [StyleSheet::minify_with_report](crate::stylesheet::StyleSheet::minify_with_report),
+//! Lightning CSS records which features were down-leveled for the targets, and which
+//! features are not supported by the targets but could not be transpiled. This can be
+//! used to fail builds that rely on unsupported syntax, or to track the adoption of
+//! modern CSS features over time.
+//!
+//! The following features are tracked:
+//!
+//! * Nesting, and the `:is()`, `:not()`, `:dir()`, and `:lang()` selectors.
+//! * `@custom-media` rules and media query range syntax.
+//! * Logical properties.
+//! * `lab()`, `lch()`, `oklab()`, `oklch()`, and `color()` colors in color properties.
+//! * Properties and functions that cannot be transpiled, such as `field-sizing` or `sibling-index()`.
+//!
+//! # Size reports
+//!
+//! [StyleSheet::size_report](crate::stylesheet::StyleSheet::size_report) reports how many bytes of
+//! the serialized output each top-level rule and source file contributed, e.g. to find the largest
+//! selectors and `@media` blocks in a bundle after minification.
+
+use crate::compat::Feature;
+use crate::error::ErrorLocation;
+use crate::rules::Location;
+#[cfg(any(feature = "serde", feature = "nodejs"))]
+use serde::Serialize;
+
+/// A report of the features that were down-leveled or left unsupported while minifying a style sheet.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+pub struct CompatReport {
+  /// Features that were down-leveled to syntax supported by the targets.
+  pub lowered: Vec<FeatureUsage>,
+  /// Features that are not supported by all targets, and could not be transpiled.
+  pub unsupported: Vec<FeatureUsage>,
+}
+
+/// A feature used in a style sheet, and the locations where it was used.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+pub struct FeatureUsage {
+  /// The name of the feature, e.g. `css-nesting` or `lab-colors`.
+  /// These are the same names accepted by [FeatureData](crate::targets::FeatureData).
+  pub feature: &'static str,
+  /// The number of times the feature was used.
+  pub count: usize,
+  /// The locations of the rules in which the feature was used.
+  pub locations: Vec<ErrorLocation>,
+}
+
+impl CompatReport {
+  pub(crate) fn new(
+    lowered: &[(Feature, Location)],
+    unsupported: &[(Feature, Location)],
+    sources: &[String],
+  ) -> Self {
+    CompatReport {
+      lowered: group(lowered, sources),
+      unsupported: group(unsupported, sources),
+    }
+  }
+
+  /// Returns whether any features are used that the targets don't support and couldn't be transpiled.
+  pub fn has_unsupported(&self) -> bool {
+    !self.unsupported.is_empty()
+  }
+}
+
+fn group(entries: &[(Feature, Location)], sources: &[String]) -> Vec<FeatureUsage> {
+  let mut usages: Vec<FeatureUsage> = Vec::new();
+  for (feature, loc) in entries {
+    let filename = sources.get(loc.source_index as usize).cloned().unwrap_or_default();
+    let location = ErrorLocation::new(*loc, filename);
+    match usages.iter_mut().find(|usage| usage.feature == feature.name()) {
+      Some(usage) => {
+        usage.count += 1;
+        usage.locations.push(location);
+      }
+      None => usages.push(FeatureUsage {
+        feature: feature.name(),
+        count: 1,
+        locations: vec![location],
+      }),
+    }
+  }
+
+  usages
+}
+
+/// A report of the number of bytes each top-level rule and source file contributed to the
+/// serialized output of a style sheet.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+pub struct SizeReport {
+  /// The total size of the serialized style sheet, in bytes.
+  pub total: usize,
+  /// The size of each top-level rule, in the order they appear in the output.
+  pub rules: Vec<RuleSize>,
+  /// The combined size of the top-level rules from each source file, in the order of the sources.
+  pub sources: Vec<SourceSize>,
+}
+
+/// The size of a top-level rule in the serialized output.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+pub struct RuleSize {
+  /// A label for the rule: the selector of a style rule, or the name and prelude of an at-rule,
+  /// e.g. `@media (min-width:800px)`.
+  pub label: String,
+  /// The number of bytes the rule contributed to the output.
+  pub size: usize,
+  /// The location of the rule in the source file, if known.
+  pub location: Option<ErrorLocation>,
+}
+
+/// The combined size of the top-level rules from a source file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "serde", feature = "nodejs"), derive(Serialize))]
+pub struct SourceSize {
+  /// The filename of the source.
+  pub filename: String,
+  /// The number of bytes the rules from the source contributed to the output.
+  pub size: usize,
+}
+
+impl SizeReport {
+  pub(crate) fn new(total: usize, rules: Vec<(String, usize, Option<Location>)>, sources: &[String]) -> Self {
+    let mut source_sizes: Vec<SourceSize> = sources
+      .iter()
+      .map(|filename| SourceSize {
+        filename: filename.clone(),
+        size: 0,
+      })
+      .collect();
+
+    let rules = rules
+      .into_iter()
+      .map(|(css, size, loc)| {
+        if let Some(source) = loc.and_then(|loc| source_sizes.get_mut(loc.source_index as usize)) {
+          source.size += size;
+        }
+
+        RuleSize {
+          label: rule_label(&css),
+          size,
+          location: loc.map(|loc| {
+            let filename = sources.get(loc.source_index as usize).cloned().unwrap_or_default();
+            ErrorLocation::new(loc, filename)
+          }),
+        }
+      })
+      .collect();
+
+    SizeReport {
+      total,
+      rules,
+      sources: source_sizes,
+    }
+  }
+
+  /// Returns the `n` largest top-level rules, in descending order of size.
+  pub fn largest_rules(&self, n: usize) -> Vec<&RuleSize> {
+    let mut rules: Vec<&RuleSize> = self.rules.iter().collect();
+    rules.sort_by_key(|rule| std::cmp::Reverse(rule.size));
+    rules.truncate(n);
+    rules
+  }
+}
+
+/// Returns the text of a serialized rule before its block, with whitespace collapsed.
+fn rule_label(css: &str) -> String {
+  let prelude = match css.find('{') {
+    Some(index) => &css[..index],
+    None => css.trim_end().trim_end_matches(';'),
+  };
+  prelude.split_whitespace().collect::<Vec<_>>().join(" ")
+}
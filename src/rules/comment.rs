@@ -0,0 +1,82 @@
+//! Preserved comments.
+
+use super::Location;
+use crate::error::PrinterError;
+use crate::printer::Printer;
+use crate::traits::ToCss;
+use crate::unused_symbols::matches_pattern;
+use crate::values::string::CowArcStr;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+
+/// A comment between rules or declarations, preserved according to the [CommentPolicy](CommentPolicy)
+/// the style sheet was parsed with.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "visitor", derive(Visit), visit(visit_comment, COMMENTS))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct CommentRule<'i> {
+  /// The text of the comment, excluding the `/*` and `*/` delimiters.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub text: CowArcStr<'i>,
+  /// The location of the comment in the source file.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub loc: Location,
+}
+
+impl<'i> ToCss for CommentRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    #[cfg(feature = "sourcemap")]
+    dest.add_mapping(self.loc);
+    dest.write_str("/*")?;
+    dest.write_str(&self.text)?;
+    dest.write_str("*/")
+  }
+}
+
+/// Determines which comments between rules and declarations are preserved when parsing a style sheet.
+/// Preserved comments are kept through minification, and printed where they appeared
+/// in the source. All other comments are discarded.
+///
+/// See [ParserOptions](crate::stylesheet::ParserOptions).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize, serde::Deserialize),
+  serde(default, rename_all = "camelCase")
+)]
+pub struct CommentPolicy {
+  /// Whether to preserve comments starting with `!`, e.g. `/*! Copyright */`.
+  pub important: bool,
+  /// Whether to preserve comments containing a `@license` or `@preserve` annotation.
+  pub license: bool,
+  /// Additional patterns to match the trimmed text of comments against, e.g. `#region *`
+  /// and `#endregion*` for source region markers. A `*` wildcard matches any sequence of characters.
+  pub patterns: Vec<String>,
+}
+
+impl CommentPolicy {
+  /// Returns whether no comments are preserved by the policy.
+  pub fn is_empty(&self) -> bool {
+    !self.important && !self.license && self.patterns.is_empty()
+  }
+
+  /// Returns whether a comment with the given text, excluding the delimiters, is preserved.
+  pub fn preserves(&self, text: &str) -> bool {
+    if self.important && text.starts_with('!') {
+      return true;
+    }
+
+    if self.license && (text.contains("@license") || text.contains("@preserve")) {
+      return true;
+    }
+
+    let text = text.trim();
+    self.patterns.iter().any(|pattern| matches_pattern(pattern, text))
+  }
+}
@@ -4,6 +4,7 @@ use cssparser::*;
 
 use super::Location;
 use super::{CssRuleList, MinifyContext};
+use crate::css_modules::NameKind;
 use crate::error::{MinifyError, ParserError, PrinterError};
 use crate::media_query::MediaCondition;
 use crate::parser::DefaultAtRule;
@@ -55,7 +56,7 @@ impl<'i> ToCss for ContainerName<'i> {
   where
     W: std::fmt::Write,
   {
-    self.0.to_css(dest)
+    self.0.to_css_scoped(dest, NameKind::Container)
   }
 }
 
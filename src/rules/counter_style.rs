@@ -1,6 +1,7 @@
 //! The `@counter-style` rule.
 
 use super::Location;
+use crate::css_modules::NameKind;
 use crate::declaration::DeclarationBlock;
 use crate::error::PrinterError;
 use crate::printer::Printer;
@@ -35,7 +36,7 @@ impl<'i> ToCss for CounterStyleRule<'i> {
     #[cfg(feature = "sourcemap")]
     dest.add_mapping(self.loc);
     dest.write_str("@counter-style ")?;
-    self.name.to_css(dest)?;
+    self.name.to_css_scoped(dest, NameKind::CounterStyle)?;
     self.declarations.to_css_block(dest)
   }
 }
@@ -1,34 +1,165 @@
-//! The `@-moz-document` rule.
+//! The `@document` and `@-moz-document` rules.
 
 use super::Location;
 use super::{CssRuleList, MinifyContext};
-use crate::error::{MinifyError, PrinterError};
+use crate::error::{MinifyError, ParserError, PrinterError};
 use crate::parser::DefaultAtRule;
 use crate::printer::Printer;
-use crate::traits::ToCss;
+use crate::traits::{Parse, ToCss};
+use crate::values::string::CowArcStr;
+use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
+use cssparser::*;
 
-/// A [@-moz-document](https://www.w3.org/TR/2012/WD-css3-conditional-20120911/#at-document) rule.
+/// A [@document](https://www.w3.org/TR/2012/WD-css3-conditional-20120911/#at-document) rule,
+/// or its `@-moz-document` prefixed form.
 ///
-/// Note that only the `url-prefix()` function with no arguments is supported, and only the `-moz` prefix
-/// is allowed since Firefox was the only browser that ever implemented this rule.
+/// Firefox was the only browser that ever implemented this rule, and today it only supports
+/// `@-moz-document url-prefix()` with no arguments in web content, as a way to target Firefox.
+/// Other conditions are still used by user style sheets, e.g. in browser extensions.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "visitor", derive(Visit))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
 pub struct MozDocumentRule<'i, R = DefaultAtRule> {
-  /// Nested rules within the `@-moz-document` rule.
+  /// The vendor prefix for this rule, e.g. `@-moz-document`.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub vendor_prefix: VendorPrefix,
+  /// The conditions that determine which documents the rule applies to.
+  /// The rule applies if any of the conditions match.
+  #[cfg_attr(feature = "visitor", skip_visit)]
   #[cfg_attr(feature = "serde", serde(borrow))]
+  pub conditions: Vec<DocumentCondition<'i>>,
+  /// Nested rules within the `@document` rule.
   pub rules: CssRuleList<'i, R>,
   /// The location of the rule in the source file.
   #[cfg_attr(feature = "visitor", skip_visit)]
   pub loc: Location,
 }
 
+/// A document matching function, as used in the `@document` rule.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum DocumentCondition<'i> {
+  /// The `url()` function, which matches a document with the exact given URL.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Url(CowArcStr<'i>),
+  /// The `url-prefix()` function, which matches a document whose URL starts with the given string.
+  UrlPrefix(CowArcStr<'i>),
+  /// The `domain()` function, which matches a document on the given domain or one of its subdomains.
+  Domain(CowArcStr<'i>),
+  /// The `regexp()` function, which matches a document whose URL matches the given regular expression.
+  Regexp(CowArcStr<'i>),
+}
+
+impl<'i> Parse<'i> for DocumentCondition<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if let Ok(url) = input.try_parse(|input| input.expect_url()) {
+      return Ok(DocumentCondition::Url(url.into()));
+    }
+
+    let location = input.current_source_location();
+    let f = input.expect_function()?.clone();
+    match_ignore_ascii_case! { &f,
+      "url" => {
+        let url = input.parse_nested_block(|input| input.expect_string_cloned().map_err(|e| e.into()))?;
+        Ok(DocumentCondition::Url(url.into()))
+      },
+      "url-prefix" => {
+        let prefix = input.parse_nested_block(parse_string_or_raw)?;
+        Ok(DocumentCondition::UrlPrefix(prefix))
+      },
+      "domain" => {
+        let domain = input.parse_nested_block(parse_string_or_raw)?;
+        Ok(DocumentCondition::Domain(domain))
+      },
+      "regexp" => {
+        let regexp = input.parse_nested_block(|input| input.expect_string_cloned().map_err(|e| e.into()))?;
+        Ok(DocumentCondition::Regexp(regexp.into()))
+      },
+      _ => Err(location.new_unexpected_token_error(Token::Function(f)))
+    }
+  }
+}
+
+/// Parses a quoted string, or the raw source text of an unquoted argument, as Firefox does for
+/// the `url-prefix()` and `domain()` functions.
+fn parse_string_or_raw<'i, 't>(
+  input: &mut Parser<'i, 't>,
+) -> Result<CowArcStr<'i>, ParseError<'i, ParserError<'i>>> {
+  if let Ok(s) = input.try_parse(|input| input.expect_string_cloned()) {
+    input.expect_exhausted()?;
+    return Ok(s.into());
+  }
+
+  let start = input.position();
+  while input.next().is_ok() {}
+  Ok(input.slice_from(start).trim().into())
+}
+
+impl<'i> ToCss for DocumentCondition<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let (name, value) = match self {
+      DocumentCondition::Url(url) => ("url(", url),
+      DocumentCondition::UrlPrefix(prefix) => ("url-prefix(", prefix),
+      DocumentCondition::Domain(domain) => ("domain(", domain),
+      DocumentCondition::Regexp(regexp) => ("regexp(", regexp),
+    };
+
+    dest.write_str(name)?;
+    // An empty url-prefix() matches all documents, and is commonly used to target Firefox.
+    if !value.is_empty() || !matches!(self, DocumentCondition::UrlPrefix(..)) {
+      dest.write_string(value)?;
+    }
+    dest.write_char(')')
+  }
+}
+
+/// Determines how `@document` and `@-moz-document` rules are handled during minification.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "lowercase")
+)]
+pub enum DocumentRulePolicy {
+  /// Remove rules that cannot apply to any of the browser targets. No browser supports the unprefixed
+  /// `@document` rule, and `@-moz-document` is only supported by Firefox. Rules are preserved when no
+  /// targets are given.
+  #[default]
+  Auto,
+  /// Always preserve the rules, e.g. when compiling user style sheets.
+  Preserve,
+  /// Always remove the rules.
+  Remove,
+}
+
 impl<'i, T> MozDocumentRule<'i, T> {
-  pub(crate) fn minify(&mut self, context: &mut MinifyContext<'_, 'i>) -> Result<(), MinifyError> {
-    self.rules.minify(context, false)
+  pub(crate) fn minify(&mut self, context: &mut MinifyContext<'_, 'i>) -> Result<bool, MinifyError> {
+    let remove = match context.document_rules {
+      DocumentRulePolicy::Auto => match context.targets {
+        Some(targets) => !self.vendor_prefix.contains(VendorPrefix::Moz) || targets.firefox.is_none(),
+        None => false,
+      },
+      DocumentRulePolicy::Preserve => false,
+      DocumentRulePolicy::Remove => true,
+    };
+
+    if remove {
+      return Ok(true);
+    }
+
+    self.rules.minify(context, false)?;
+    Ok(false)
   }
 }
 
@@ -39,7 +170,18 @@ impl<'i, T: ToCss> ToCss for MozDocumentRule<'i, T> {
   {
     #[cfg(feature = "sourcemap")]
     dest.add_mapping(self.loc);
-    dest.write_str("@-moz-document url-prefix()")?;
+    dest.write_char('@')?;
+    self.vendor_prefix.to_css(dest)?;
+    dest.write_str("document ")?;
+    let mut first = true;
+    for condition in &self.conditions {
+      if first {
+        first = false;
+      } else {
+        dest.delim(',', false)?;
+      }
+      condition.to_css(dest)?;
+    }
     dest.whitespace()?;
     dest.write_char('{')?;
     dest.indent();
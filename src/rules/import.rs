@@ -6,12 +6,11 @@ use super::Location;
 use crate::dependencies::{Dependency, ImportDependency};
 use crate::error::PrinterError;
 use crate::media_query::MediaList;
-use crate::printer::Printer;
+use crate::printer::{Printer, UrlKind};
 use crate::traits::ToCss;
 use crate::values::string::CowArcStr;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
-use cssparser::*;
 
 /// A [@import](https://drafts.csswg.org/css-cascade/#at-import) rule.
 #[derive(Debug, PartialEq, Clone)]
@@ -37,30 +36,13 @@ pub struct ImportRule<'i> {
   pub loc: Location,
 }
 
-impl<'i> ToCss for ImportRule<'i> {
-  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+impl<'i> ImportRule<'i> {
+  /// Serializes the `layer()`, `supports()` and media query conditions of the rule,
+  /// each preceded by a space.
+  pub(crate) fn conditions_to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
     W: std::fmt::Write,
   {
-    let dep = if dest.dependencies.is_some() {
-      Some(ImportDependency::new(self, dest.filename()))
-    } else {
-      None
-    };
-
-    #[cfg(feature = "sourcemap")]
-    dest.add_mapping(self.loc);
-    dest.write_str("@import ")?;
-    if let Some(dep) = dep {
-      serialize_string(&dep.placeholder, dest)?;
-
-      if let Some(dependencies) = &mut dest.dependencies {
-        dependencies.push(Dependency::Import(dep))
-      }
-    } else {
-      serialize_string(&self.url, dest)?;
-    }
-
     if let Some(layer) = &self.layer {
       dest.write_str(" layer")?;
       if let Some(name) = layer {
@@ -84,6 +66,36 @@ impl<'i> ToCss for ImportRule<'i> {
       dest.write_char(' ')?;
       self.media.to_css(dest)?;
     }
+    Ok(())
+  }
+}
+
+impl<'i> ToCss for ImportRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let dep = if dest.dependencies.is_some() {
+      Some(ImportDependency::new(self, dest.filename()))
+    } else {
+      None
+    };
+
+    #[cfg(feature = "sourcemap")]
+    dest.add_mapping(self.loc);
+    dest.write_str("@import ")?;
+    if let Some(dep) = dep {
+      dest.write_string(&dep.placeholder)?;
+
+      if let Some(dependencies) = &mut dest.dependencies {
+        dependencies.push(Dependency::Import(dep))
+      }
+    } else {
+      let url = dest.rewrite_url(self.url.as_ref(), UrlKind::Import);
+      dest.write_string(&url)?;
+    }
+
+    self.conditions_to_css(dest)?;
     dest.write_str(";")
   }
 }
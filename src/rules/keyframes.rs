@@ -4,7 +4,8 @@ use super::supports::SupportsRule;
 use super::MinifyContext;
 use super::{CssRule, CssRuleList, Location};
 use crate::context::DeclarationContext;
-use crate::declaration::DeclarationBlock;
+use crate::css_modules::NameKind;
+use crate::declaration::{DeclarationBlock, DeclarationLocations};
 use crate::error::{ParserError, PrinterError};
 use crate::parser::ParserOptions;
 use crate::printer::Printer;
@@ -41,6 +42,11 @@ pub struct KeyframesRule<'i> {
   /// A vendor prefix for the rule, e.g. `@-webkit-keyframes`.
   #[cfg_attr(feature = "visitor", skip_visit)]
   pub vendor_prefix: VendorPrefix,
+  /// The names of other `@keyframes` rules with identical keyframes that were merged into this
+  /// rule during minification. With CSS modules, these are exported as aliases of this rule's name.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub aliases: Vec<KeyframesName<'i>>,
   /// The location of the rule in the source file.
   #[cfg_attr(feature = "visitor", skip_visit)]
   pub loc: Location,
@@ -87,6 +93,15 @@ impl<'i> Parse<'i> for KeyframesName<'i> {
   }
 }
 
+impl<'i> KeyframesName<'i> {
+  pub(crate) fn as_str(&self) -> &str {
+    match self {
+      KeyframesName::Ident(ident) => ident.0.as_ref(),
+      KeyframesName::Custom(string) => string.as_ref(),
+    }
+  }
+}
+
 impl<'i> ToCss for KeyframesName<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -94,16 +109,16 @@ impl<'i> ToCss for KeyframesName<'i> {
   {
     match self {
       KeyframesName::Ident(ident) => {
-        dest.write_ident(ident.0.as_ref())?;
+        dest.write_scoped_ident(ident.0.as_ref(), NameKind::Keyframes)?;
       }
       KeyframesName::Custom(s) => {
         // CSS-wide keywords and `none` cannot remove quotes.
         match_ignore_ascii_case! { &*s,
           "none" | "initial" | "inherit" | "unset" | "default" | "revert" | "revert-layer" => {
-            serialize_string(&s, dest)?;
+            dest.write_string(s)?;
           },
           _ => {
-            dest.write_ident(s.as_ref())?;
+            dest.write_scoped_ident(s.as_ref(), NameKind::Keyframes)?;
           }
         }
       }
@@ -115,6 +130,7 @@ impl<'i> ToCss for KeyframesName<'i> {
 impl<'i> KeyframesRule<'i> {
   pub(crate) fn minify(&mut self, context: &mut MinifyContext<'_, 'i>) {
     context.handler_context.context = DeclarationContext::Keyframes;
+    context.handler_context.loc = self.loc;
 
     for keyframe in &mut self.keyframes {
       keyframe
@@ -193,6 +209,11 @@ impl<'i> KeyframesRule<'i> {
               _ => property.clone(),
             })
             .collect(),
+          locations: DeclarationLocations {
+            declarations: keyframe.declarations.locations.declarations.clone(),
+            important_declarations: vec![],
+          },
+          comments: vec![],
         },
       })
       .collect();
@@ -203,6 +224,7 @@ impl<'i> KeyframesRule<'i> {
         name: self.name.clone(),
         keyframes,
         vendor_prefix: self.vendor_prefix,
+        aliases: self.aliases.clone(),
         loc: self.loc.clone(),
       })]),
       loc: self.loc.clone(),
@@ -258,6 +280,13 @@ impl<'i> ToCss for KeyframesRule<'i> {
     write_prefix!(Moz);
     write_prefix!(O);
     write_prefix!(None);
+
+    if let Some(css_module) = &mut dest.css_module {
+      for alias in &self.aliases {
+        css_module.add_local(alias.as_str(), self.name.as_str(), dest.loc);
+      }
+    }
+
     Ok(())
   }
 }
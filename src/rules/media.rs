@@ -33,13 +33,30 @@ impl<'i, T> MediaRule<'i, T> {
     context: &mut MinifyContext<'_, 'i>,
     parent_is_unused: bool,
   ) -> Result<bool, MinifyError> {
-    self.rules.minify(context, parent_is_unused)?;
-
     if let Some(custom_media) = &context.custom_media {
       self.query.transform_custom_media(self.loc, custom_media)?;
     }
 
-    Ok(self.rules.0.is_empty() || self.query.never_matches())
+    self.query.minify(*context.targets);
+
+    // Remove the rule if it can never match within the parent rules.
+    if self.query.never_matches() || context.parent_media.iter().any(|parent| parent.excludes(&self.query)) {
+      return Ok(true);
+    }
+
+    // Nested rules are simplified based on this query, unless the rule structure is preserved.
+    let is_parent = !context.preserve_rule_structure;
+    if is_parent {
+      context.parent_media.push(self.query.clone());
+    }
+
+    let res = self.rules.minify(context, parent_is_unused);
+    if is_parent {
+      context.parent_media.pop();
+    }
+    res?;
+
+    Ok(self.rules.0.is_empty())
   }
 }
 
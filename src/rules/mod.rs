@@ -36,6 +36,7 @@
 
 #![deny(missing_docs)]
 
+pub mod comment;
 pub mod container;
 pub mod counter_style;
 pub mod custom_media;
@@ -53,23 +54,41 @@ pub mod property;
 pub mod style;
 pub mod supports;
 pub mod unknown;
+pub mod value;
+pub mod view_transition;
 pub mod viewport;
 
+use self::comment::CommentRule;
 use self::font_palette_values::FontPaletteValuesRule;
 use self::layer::{LayerBlockRule, LayerStatementRule};
-use self::property::PropertyRule;
+use self::property::{PropertyRule, RegisteredProperties};
+use self::value::ValueRule;
+use crate::compat;
 use crate::context::PropertyHandlerContext;
-use crate::declaration::DeclarationHandler;
+use crate::css_modules::Config as CssModulesConfig;
+use crate::declaration::{DeclarationBlock, DeclarationHandler};
 use crate::dependencies::{Dependency, ImportDependency};
 use crate::error::{MinifyError, ParserError, PrinterError, PrinterErrorKind};
+use crate::logical::LogicalGroup;
+use crate::media_query::MediaList;
 use crate::parser::{DefaultAtRule, TopLevelRuleParser};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
+use crate::properties::animation::AnimationName;
+use crate::properties::css_modules::Specifier;
+use crate::properties::custom::{CustomProperty, CustomPropertyName, UnparsedProperty};
+use crate::properties::font::FontFamily;
+use crate::properties::{Property, PropertyId};
 use crate::rules::keyframes::KeyframesName;
-use crate::selector::{downlevel_selectors, get_prefix, is_equivalent};
-use crate::stylesheet::ParserOptions;
+use crate::selector::{
+  can_match, collect_css_module_names, downlevel_selectors, get_incompatible_features, get_prefix,
+  get_required_prefixes, is_equivalent, is_root_selector, references_unused_export, remove_redundant_selectors,
+  Component, SelectorList,
+};
+use crate::stylesheet::{ParserOptions, PrinterOptions, PurgeContent};
 use crate::targets::Browsers;
 use crate::traits::ToCss;
+use crate::unused_symbols::{SymbolKind, UnusedSymbols};
 use crate::values::string::CowArcStr;
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
@@ -78,18 +97,20 @@ use container::ContainerRule;
 use counter_style::CounterStyleRule;
 use cssparser::{parse_one_rule, AtRuleParser, ParseError, Parser, ParserInput};
 use custom_media::CustomMediaRule;
-use document::MozDocumentRule;
-use font_face::FontFaceRule;
+use document::{DocumentRulePolicy, MozDocumentRule};
+use font_face::{FontFaceProperty, FontFaceRule};
 use import::ImportRule;
 use keyframes::KeyframesRule;
 use media::MediaRule;
 use namespace::NamespaceRule;
 use nesting::NestingRule;
 use page::PageRule;
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use style::StyleRule;
-use supports::SupportsRule;
+use supports::{SupportsCondition, SupportsRule};
 use unknown::UnknownAtRule;
+use view_transition::ViewTransitionRule;
 use viewport::ViewportRule;
 
 pub(crate) trait ToCssWithContext<'a, 'i, T> {
@@ -124,8 +145,6 @@ pub struct Location {
 
 /// A CSS rule.
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "visitor", derive(Visit))]
-#[cfg_attr(feature = "visitor", visit(visit_rule, RULES))]
 #[cfg_attr(
   feature = "serde",
   derive(serde::Serialize),
@@ -170,6 +189,12 @@ pub enum CssRule<'i, R = DefaultAtRule> {
   Property(PropertyRule<'i>),
   /// A `@container` rule.
   Container(ContainerRule<'i, R>),
+  /// A `@view-transition` rule.
+  ViewTransition(ViewTransitionRule<'i>),
+  /// A preserved comment.
+  Comment(CommentRule<'i>),
+  /// A CSS modules `@value` rule.
+  Value(ValueRule<'i>),
   /// A placeholder for a rule that was removed.
   Ignored,
   /// An unknown at-rule.
@@ -305,6 +330,18 @@ impl<'i, 'de: 'i, R: serde::Deserialize<'de>> serde::Deserialize<'de> for CssRul
         let rule = ContainerRule::deserialize(deserializer)?;
         Ok(CssRule::Container(rule))
       }
+      "view-transition" => {
+        let rule = ViewTransitionRule::deserialize(deserializer)?;
+        Ok(CssRule::ViewTransition(rule))
+      }
+      "comment" => {
+        let rule = CommentRule::deserialize(deserializer)?;
+        Ok(CssRule::Comment(rule))
+      }
+      "value" => {
+        let rule = ValueRule::deserialize(deserializer)?;
+        Ok(CssRule::Value(rule))
+      }
       "ignored" => Ok(CssRule::Ignored),
       "unknown" => {
         let rule = UnknownAtRule::deserialize(deserializer)?;
@@ -347,6 +384,9 @@ impl<'a, 'i, T: ToCss> ToCssWithContext<'a, 'i, T> for CssRule<'i, T> {
       CssRule::LayerBlock(layer) => layer.to_css_with_context(dest, context),
       CssRule::Property(property) => property.to_css(dest),
       CssRule::Container(container) => container.to_css_with_context(dest, context),
+      CssRule::ViewTransition(rule) => rule.to_css(dest),
+      CssRule::Comment(comment) => comment.to_css(dest),
+      CssRule::Value(value) => value.to_css(dest),
       CssRule::Unknown(unknown) => unknown.to_css(dest),
       CssRule::Custom(rule) => rule.to_css(dest).map_err(|_| PrinterError {
         kind: PrinterErrorKind::FmtError,
@@ -376,6 +416,53 @@ impl<'i, T> CssRule<'i, T> {
     let mut parser = Parser::new(&mut input);
     Self::parse(&mut parser, &mut options)
   }
+
+  /// Returns the location of the rule in the source file, if known.
+  pub(crate) fn loc(&self) -> Option<Location> {
+    let loc = match self {
+      CssRule::Media(rule) => rule.loc,
+      CssRule::Import(rule) => rule.loc,
+      CssRule::Style(rule) => rule.loc,
+      CssRule::Keyframes(rule) => rule.loc,
+      CssRule::FontFace(rule) => rule.loc,
+      CssRule::FontPaletteValues(rule) => rule.loc,
+      CssRule::Page(rule) => rule.loc,
+      CssRule::Supports(rule) => rule.loc,
+      CssRule::CounterStyle(rule) => rule.loc,
+      CssRule::Namespace(rule) => rule.loc,
+      CssRule::MozDocument(rule) => rule.loc,
+      CssRule::Nesting(rule) => rule.loc,
+      CssRule::Viewport(rule) => rule.loc,
+      CssRule::CustomMedia(rule) => rule.loc,
+      CssRule::LayerStatement(rule) => rule.loc,
+      CssRule::LayerBlock(rule) => rule.loc,
+      CssRule::Property(rule) => rule.loc,
+      CssRule::Container(rule) => rule.loc,
+      CssRule::ViewTransition(rule) => rule.loc,
+      CssRule::Comment(rule) => rule.loc,
+      CssRule::Value(rule) => rule.loc,
+      CssRule::Unknown(rule) => rule.loc,
+      CssRule::Ignored | CssRule::Custom(..) => return None,
+    };
+    Some(loc)
+  }
+
+  /// Returns the source index of the rule, looking through `@media`, `@supports`, and `@layer` blocks
+  /// for the first rule within them, since the bundler wraps imported rules in these.
+  fn source_index(&self) -> Option<u32> {
+    let rules = match self {
+      CssRule::Media(rule) => &rule.rules,
+      CssRule::Supports(rule) => &rule.rules,
+      CssRule::LayerBlock(rule) => &rule.rules,
+      _ => return self.loc().map(|loc| loc.source_index),
+    };
+
+    rules
+      .0
+      .iter()
+      .find_map(|rule| rule.source_index())
+      .or_else(|| self.loc().map(|loc| loc.source_index))
+  }
 }
 
 impl<'i, T: ToCss> ToCss for CssRule<'i, T> {
@@ -395,6 +482,95 @@ pub struct CssRuleList<'i, R = DefaultAtRule>(
   #[cfg_attr(feature = "serde", serde(borrow))] pub Vec<CssRule<'i, R>>,
 );
 
+// Manually implemented to track the enclosing rules in the visitor context.
+#[cfg(feature = "visitor")]
+impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for CssRule<'i, T> {
+  // The union of the child types of each variant, as the derive would generate.
+  const CHILD_TYPES: VisitTypes = unsafe {
+    VisitTypes::from_bits_unchecked(
+      VisitTypes::RULES.bits()
+        | <MediaRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <ImportRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <StyleRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <KeyframesRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <FontFaceRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <FontPaletteValuesRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <PageRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <SupportsRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <CounterStyleRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <NamespaceRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <MozDocumentRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <NestingRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <ViewportRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <CustomMediaRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <LayerStatementRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <LayerBlockRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <PropertyRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <ContainerRule<'i, T> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <ViewTransitionRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <CommentRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <ValueRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <UnknownAtRule<'i> as Visit<'i, T, V>>::CHILD_TYPES.bits()
+        | <T as Visit<'i, T, V>>::CHILD_TYPES.bits(),
+    )
+  };
+
+  fn visit(&mut self, visitor: &mut V) {
+    if visitor.visit_types().contains(VisitTypes::RULES) {
+      // Outside of a rule list, the rule can only be replaced by a single rule.
+      if let Some(rules) = visitor.visit_rule(self) {
+        *self = rules.into_iter().next().unwrap_or(CssRule::Ignored);
+      }
+    } else {
+      self.visit_children(visitor)
+    }
+  }
+
+  fn visit_children(&mut self, visitor: &mut V) {
+    if !<Self as Visit<'i, T, V>>::CHILD_TYPES.intersects(visitor.visit_types()) {
+      return;
+    }
+
+    let pushed = match visitor.context() {
+      Some(context) => context.push(self),
+      None => false,
+    };
+
+    match self {
+      CssRule::Media(rule) => rule.visit(visitor),
+      CssRule::Import(rule) => rule.visit(visitor),
+      CssRule::Style(rule) => rule.visit(visitor),
+      CssRule::Keyframes(rule) => rule.visit(visitor),
+      CssRule::FontFace(rule) => rule.visit(visitor),
+      CssRule::FontPaletteValues(rule) => rule.visit(visitor),
+      CssRule::Page(rule) => rule.visit(visitor),
+      CssRule::Supports(rule) => rule.visit(visitor),
+      CssRule::CounterStyle(rule) => rule.visit(visitor),
+      CssRule::Namespace(rule) => rule.visit(visitor),
+      CssRule::MozDocument(rule) => rule.visit(visitor),
+      CssRule::Nesting(rule) => rule.visit(visitor),
+      CssRule::Viewport(rule) => rule.visit(visitor),
+      CssRule::CustomMedia(rule) => rule.visit(visitor),
+      CssRule::LayerStatement(rule) => rule.visit(visitor),
+      CssRule::LayerBlock(rule) => rule.visit(visitor),
+      CssRule::Property(rule) => rule.visit(visitor),
+      CssRule::Container(rule) => rule.visit(visitor),
+      CssRule::ViewTransition(rule) => rule.visit(visitor),
+      CssRule::Comment(rule) => rule.visit(visitor),
+      CssRule::Value(rule) => rule.visit(visitor),
+      CssRule::Ignored => {}
+      CssRule::Unknown(rule) => rule.visit(visitor),
+      CssRule::Custom(rule) => rule.visit(visitor),
+    }
+
+    if pushed {
+      if let Some(context) = visitor.context() {
+        context.pop();
+      }
+    }
+  }
+}
+
 // Manually implemented to avoid circular child types.
 #[cfg(feature = "visitor")]
 impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for CssRuleList<'i, T> {
@@ -409,7 +585,22 @@ impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for CssRuleList<
   }
 
   fn visit_children(&mut self, visitor: &mut V) {
-    self.0.visit(visitor)
+    if !visitor.visit_types().contains(VisitTypes::RULES) {
+      return self.0.visit(visitor);
+    }
+
+    // Splice the rules returned by the visitor into the list in place of the visited rule.
+    let mut i = 0;
+    while i < self.0.len() {
+      match visitor.visit_rule(&mut self.0[i]) {
+        Some(rules) => {
+          let len = rules.len();
+          self.0.splice(i..i + 1, rules);
+          i += len;
+        }
+        None => i += 1,
+      }
+    }
   }
 }
 
@@ -418,12 +609,681 @@ pub(crate) struct MinifyContext<'a, 'i> {
   pub handler: &'a mut DeclarationHandler<'i>,
   pub important_handler: &'a mut DeclarationHandler<'i>,
   pub handler_context: &'a mut PropertyHandlerContext<'i, 'a>,
-  pub unused_symbols: &'a HashSet<String>,
+  pub unused_symbols: &'a UnusedSymbols,
   pub custom_media: Option<HashMap<CowArcStr<'i>, CustomMediaRule<'i>>>,
   pub css_modules: bool,
+  pub document_rules: DocumentRulePolicy,
+  pub used_keyframes: Option<&'a HashSet<(u32, String)>>,
+  /// Whether `@keyframes` names are scoped to their source file by CSS modules.
+  pub scoped_keyframes: bool,
+  pub used_font_families: Option<&'a HashSet<String>>,
+  pub used_custom_properties: Option<&'a HashSet<String>>,
+  /// The custom properties registered by `@property` rules.
+  pub registered_properties: Option<&'a RegisteredProperties<'i>>,
+  /// Whether to preserve the structure of the rules, i.e. not merge or unwrap them.
+  pub preserve_rule_structure: bool,
+  /// Whether to sort declarations and style rules into a canonical order where it is safe.
+  pub canonical_order: bool,
+  /// Whether to remove overridden declarations and merge non-adjacent style rules with the same selectors.
+  pub restructure_rules: bool,
+  /// The media queries of the `@media` rules the current rule is nested in.
+  pub parent_media: Vec<MediaList<'i>>,
+  /// The conditions of the `@supports` rules the current rule is nested in.
+  pub parent_supports: Vec<SupportsCondition<'i>>,
 }
 
 impl<'i, T> CssRuleList<'i, T> {
+  /// Collects the names of all properties declared within the rule list, including nested rules.
+  /// Shorthand properties also contribute the names of their longhands.
+  pub(crate) fn collect_property_names(&self, names: &mut HashSet<String>) {
+    fn collect_declarations(declarations: &DeclarationBlock, names: &mut HashSet<String>) {
+      for (property, _) in declarations.iter() {
+        let property_id = property.property_id();
+        if let Some(longhands) = property_id.longhands() {
+          names.extend(longhands.iter().map(|longhand| longhand.name().to_owned()));
+        }
+        names.insert(property_id.name().to_owned());
+      }
+    }
+
+    for rule in &self.0 {
+      match rule {
+        CssRule::Style(style) => {
+          collect_declarations(&style.declarations, names);
+          style.rules.collect_property_names(names);
+        }
+        CssRule::Nesting(nesting) => {
+          collect_declarations(&nesting.style.declarations, names);
+          nesting.style.rules.collect_property_names(names);
+        }
+        CssRule::Keyframes(keyframes) => {
+          for keyframe in &keyframes.keyframes {
+            collect_declarations(&keyframe.declarations, names);
+          }
+        }
+        CssRule::Page(page) => {
+          collect_declarations(&page.declarations, names);
+          for margin_rule in &page.rules {
+            collect_declarations(&margin_rule.declarations, names);
+          }
+        }
+        CssRule::Media(media) => media.rules.collect_property_names(names),
+        CssRule::Supports(supports) => supports.rules.collect_property_names(names),
+        CssRule::MozDocument(document) => document.rules.collect_property_names(names),
+        CssRule::LayerBlock(layer) => layer.rules.collect_property_names(names),
+        CssRule::Container(container) => container.rules.collect_property_names(names),
+        _ => {}
+      }
+    }
+  }
+
+  /// Collects the names of all `@keyframes` rules referenced by `animation` and `animation-name`
+  /// declarations within the rule list, including nested rules. When CSS modules are enabled, names
+  /// are paired with the source index of the referencing rule, since keyframes are scoped to the file
+  /// they are declared in. Otherwise, the source index is always zero. Returns `false` if the referenced
+  /// names cannot be determined statically, e.g. because an animation uses `var()`.
+  pub(crate) fn collect_animation_names(&self, names: &mut HashSet<(u32, String)>, css_modules: bool) -> bool {
+    fn collect_declarations(
+      declarations: &DeclarationBlock,
+      source_index: Option<u32>,
+      names: &mut HashSet<(u32, String)>,
+    ) -> bool {
+      let mut insert = |name: &AnimationName| match name {
+        AnimationName::Ident(ident) => {
+          names.insert((source_index.unwrap_or(0), ident.0.to_string()));
+        }
+        AnimationName::String(string) => {
+          names.insert((source_index.unwrap_or(0), string.to_string()));
+        }
+        // Global references never refer to scoped keyframes.
+        AnimationName::Global(ident) if source_index.is_none() => {
+          names.insert((0, ident.0.to_string()));
+        }
+        AnimationName::Global(_) | AnimationName::None => {}
+      };
+
+      for (property, _) in declarations.iter() {
+        match property {
+          Property::AnimationName(animation_names, _) => animation_names.iter().for_each(&mut insert),
+          Property::Animation(animations, _) => animations.iter().for_each(|animation| insert(&animation.name)),
+          Property::Unparsed(unparsed)
+            if matches!(
+              unparsed.property_id,
+              PropertyId::AnimationName(_) | PropertyId::Animation(_)
+            ) =>
+          {
+            return false
+          }
+          _ => {}
+        }
+      }
+
+      true
+    }
+
+    for rule in &self.0 {
+      let collected = match rule {
+        CssRule::Style(style) => {
+          let source_index = css_modules.then_some(style.loc.source_index);
+          collect_declarations(&style.declarations, source_index, names)
+            && style.rules.collect_animation_names(names, css_modules)
+        }
+        CssRule::Nesting(nesting) => {
+          let source_index = css_modules.then_some(nesting.style.loc.source_index);
+          collect_declarations(&nesting.style.declarations, source_index, names)
+            && nesting.style.rules.collect_animation_names(names, css_modules)
+        }
+        CssRule::Media(media) => media.rules.collect_animation_names(names, css_modules),
+        CssRule::Supports(supports) => supports.rules.collect_animation_names(names, css_modules),
+        CssRule::MozDocument(document) => document.rules.collect_animation_names(names, css_modules),
+        CssRule::LayerBlock(layer) => layer.rules.collect_animation_names(names, css_modules),
+        CssRule::Container(container) => container.rules.collect_animation_names(names, css_modules),
+        // Unknown and custom at-rules may contain animations that we cannot see.
+        CssRule::Unknown(..) | CssRule::Custom(..) => false,
+        _ => true,
+      };
+
+      if !collected {
+        return false;
+      }
+    }
+
+    true
+  }
+
+  /// Merges top-level `@keyframes` rules with identical keyframes into the first such rule, and renames
+  /// the `animation` and `animation-name` references to the removed rules. Nothing is merged if the
+  /// references cannot be determined statically. With CSS modules, only rules from the same source file
+  /// are merged, and the removed names are exported as aliases of the remaining rule.
+  pub(crate) fn merge_duplicate_keyframes(&mut self, css_modules: bool) {
+    fn count_keyframes<'i, T>(
+      rules: &CssRuleList<'i, T>,
+      counts: &mut HashMap<(u32, String), usize>,
+      css_modules: bool,
+    ) {
+      for rule in &rules.0 {
+        match rule {
+          CssRule::Keyframes(keyframes) => {
+            *counts.entry(keyframes_key(keyframes, css_modules)).or_default() += 1;
+          }
+          CssRule::Media(media) => count_keyframes(&media.rules, counts, css_modules),
+          CssRule::Supports(supports) => count_keyframes(&supports.rules, counts, css_modules),
+          CssRule::MozDocument(document) => count_keyframes(&document.rules, counts, css_modules),
+          CssRule::LayerBlock(layer) => count_keyframes(&layer.rules, counts, css_modules),
+          CssRule::Container(container) => count_keyframes(&container.rules, counts, css_modules),
+          CssRule::Style(style) => count_keyframes(&style.rules, counts, css_modules),
+          _ => {}
+        }
+      }
+    }
+
+    fn keyframes_key(keyframes: &KeyframesRule, css_modules: bool) -> (u32, String) {
+      let source_index = if css_modules { keyframes.loc.source_index } else { 0 };
+      (source_index, keyframes.name.as_str().to_owned())
+    }
+
+    let mut names = HashSet::new();
+    if !self.collect_animation_names(&mut names, css_modules) {
+      return;
+    }
+
+    // Rules that share a name with another rule, e.g. vendor prefixed or conditional rules, are left alone.
+    let mut counts = HashMap::new();
+    count_keyframes(self, &mut counts, css_modules);
+
+    // Find the first rule with identical keyframes for each rule.
+    let mut canonical: Vec<usize> = Vec::new();
+    let mut merged = Vec::new();
+    for (index, rule) in self.0.iter().enumerate() {
+      let keyframes = match rule {
+        CssRule::Keyframes(keyframes) if counts.get(&keyframes_key(keyframes, css_modules)) == Some(&1) => {
+          keyframes
+        }
+        _ => continue,
+      };
+
+      let existing = canonical.iter().find(|existing| match &self.0[**existing] {
+        CssRule::Keyframes(existing) => {
+          existing.vendor_prefix == keyframes.vendor_prefix
+            && existing.keyframes == keyframes.keyframes
+            && (!css_modules || existing.loc.source_index == keyframes.loc.source_index)
+        }
+        _ => false,
+      });
+
+      match existing {
+        Some(existing) => merged.push((index, *existing)),
+        None => canonical.push(index),
+      }
+    }
+
+    if merged.is_empty() {
+      return;
+    }
+
+    let mut renames = HashMap::new();
+    for (index, existing) in &merged {
+      if let (CssRule::Keyframes(keyframes), CssRule::Keyframes(existing)) = (&self.0[*index], &self.0[*existing])
+      {
+        renames.insert(keyframes_key(keyframes, css_modules), existing.name.clone());
+      }
+    }
+
+    for (index, existing) in &merged {
+      if let CssRule::Keyframes(keyframes) = &self.0[*index] {
+        let name = keyframes.name.clone();
+        if let CssRule::Keyframes(existing) = &mut self.0[*existing] {
+          existing.aliases.push(name);
+        }
+      }
+    }
+
+    let removed: HashSet<usize> = merged.iter().map(|(index, _)| *index).collect();
+    let mut index = 0;
+    self.0.retain(|_| {
+      let keep = !removed.contains(&index);
+      index += 1;
+      keep
+    });
+
+    self.rename_animations(&renames, css_modules);
+  }
+
+  /// Renames the `@keyframes` referenced by `animation` and `animation-name` declarations within the rule list.
+  fn rename_animations(&mut self, renames: &HashMap<(u32, String), KeyframesName<'i>>, css_modules: bool) {
+    fn rename_declarations<'i>(
+      declarations: &mut DeclarationBlock<'i>,
+      source_index: Option<u32>,
+      renames: &HashMap<(u32, String), KeyframesName<'i>>,
+    ) {
+      let rename = |name: &mut AnimationName<'i>| {
+        let key = match name {
+          AnimationName::Ident(ident) => (source_index.unwrap_or(0), ident.0.to_string()),
+          AnimationName::String(string) => (source_index.unwrap_or(0), string.to_string()),
+          AnimationName::Global(ident) if source_index.is_none() => (0, ident.0.to_string()),
+          AnimationName::Global(_) | AnimationName::None => return,
+        };
+
+        match renames.get(&key) {
+          Some(KeyframesName::Ident(ident)) if matches!(name, AnimationName::Global(_)) => {
+            *name = AnimationName::Global(ident.clone())
+          }
+          Some(KeyframesName::Ident(ident)) => *name = AnimationName::Ident(ident.clone()),
+          Some(KeyframesName::Custom(string)) => *name = AnimationName::String(string.clone()),
+          None => {}
+        }
+      };
+
+      for property in declarations
+        .declarations
+        .iter_mut()
+        .chain(declarations.important_declarations.iter_mut())
+      {
+        match property {
+          Property::AnimationName(names, _) => names.iter_mut().for_each(rename),
+          Property::Animation(animations, _) => {
+            animations.iter_mut().for_each(|animation| rename(&mut animation.name))
+          }
+          _ => {}
+        }
+      }
+    }
+
+    for rule in &mut self.0 {
+      match rule {
+        CssRule::Style(style) => {
+          let source_index = css_modules.then_some(style.loc.source_index);
+          rename_declarations(&mut style.declarations, source_index, renames);
+          style.rules.rename_animations(renames, css_modules);
+        }
+        CssRule::Nesting(nesting) => {
+          let source_index = css_modules.then_some(nesting.style.loc.source_index);
+          rename_declarations(&mut nesting.style.declarations, source_index, renames);
+          nesting.style.rules.rename_animations(renames, css_modules);
+        }
+        CssRule::Media(media) => media.rules.rename_animations(renames, css_modules),
+        CssRule::Supports(supports) => supports.rules.rename_animations(renames, css_modules),
+        CssRule::MozDocument(document) => document.rules.rename_animations(renames, css_modules),
+        CssRule::LayerBlock(layer) => layer.rules.rename_animations(renames, css_modules),
+        CssRule::Container(container) => container.rules.rename_animations(renames, css_modules),
+        _ => {}
+      }
+    }
+  }
+
+  /// Collects the lowercased family names referenced by `font` and `font-family` declarations within
+  /// the rule list, including nested rules and keyframes. Returns `false` if the referenced families
+  /// cannot be determined statically, e.g. because a declaration uses `var()`.
+  pub(crate) fn collect_font_families(&self, names: &mut HashSet<String>) -> bool {
+    fn collect_declarations(declarations: &DeclarationBlock, names: &mut HashSet<String>) -> bool {
+      let mut insert = |family: &FontFamily| {
+        if let FontFamily::FamilyName(name) = family {
+          names.insert(name.to_lowercase());
+        }
+      };
+
+      for (property, _) in declarations.iter() {
+        match property {
+          Property::FontFamily(families) => families.iter().for_each(&mut insert),
+          Property::Font(font) => font.family.iter().for_each(&mut insert),
+          Property::Unparsed(unparsed)
+            if matches!(unparsed.property_id, PropertyId::FontFamily | PropertyId::Font) =>
+          {
+            return false
+          }
+          _ => {}
+        }
+      }
+
+      true
+    }
+
+    for rule in &self.0 {
+      let collected = match rule {
+        CssRule::Style(style) => {
+          collect_declarations(&style.declarations, names) && style.rules.collect_font_families(names)
+        }
+        CssRule::Nesting(nesting) => {
+          collect_declarations(&nesting.style.declarations, names)
+            && nesting.style.rules.collect_font_families(names)
+        }
+        CssRule::Keyframes(keyframes) => keyframes
+          .keyframes
+          .iter()
+          .all(|keyframe| collect_declarations(&keyframe.declarations, names)),
+        CssRule::Page(page) => {
+          collect_declarations(&page.declarations, names)
+            && page
+              .rules
+              .iter()
+              .all(|margin_rule| collect_declarations(&margin_rule.declarations, names))
+        }
+        CssRule::Media(media) => media.rules.collect_font_families(names),
+        CssRule::Supports(supports) => supports.rules.collect_font_families(names),
+        CssRule::MozDocument(document) => document.rules.collect_font_families(names),
+        CssRule::LayerBlock(layer) => layer.rules.collect_font_families(names),
+        CssRule::Container(container) => container.rules.collect_font_families(names),
+        // Unknown and custom at-rules may reference fonts that we cannot see.
+        CssRule::Unknown(..) | CssRule::Custom(..) => false,
+        _ => true,
+      };
+
+      if !collected {
+        return false;
+      }
+    }
+
+    true
+  }
+
+  /// Collects the custom properties declared within the rule list, and the `var()` references between
+  /// them and from other declarations. Returns `false` if the references cannot be determined, e.g.
+  /// because the rule list contains unknown at-rules.
+  pub(crate) fn collect_custom_property_references(
+    &self,
+    references: &mut CustomPropertyReferences,
+    preserve_root: bool,
+  ) -> bool {
+    fn collect_declarations(
+      declarations: &DeclarationBlock,
+      references: &mut CustomPropertyReferences,
+      is_root: bool,
+    ) {
+      for (property, _) in declarations.iter() {
+        match property {
+          Property::Custom(CustomProperty {
+            name: CustomPropertyName::Custom(name),
+            value,
+          }) => {
+            let name = name.0.to_string();
+            if is_root {
+              references.used.insert(name.clone());
+            }
+            value.collect_variable_references(references.dependencies.entry(name).or_default());
+          }
+          Property::Custom(CustomProperty { value, .. }) | Property::Unparsed(UnparsedProperty { value, .. }) => {
+            value.collect_variable_references(&mut references.used)
+          }
+          _ => {}
+        }
+      }
+    }
+
+    for rule in &self.0 {
+      let collected = match rule {
+        CssRule::Style(style) => {
+          let is_root = preserve_root && style.selectors.0.iter().all(is_root_selector);
+          collect_declarations(&style.declarations, references, is_root);
+          style.rules.collect_custom_property_references(references, preserve_root)
+        }
+        CssRule::Nesting(nesting) => {
+          collect_declarations(&nesting.style.declarations, references, false);
+          nesting
+            .style
+            .rules
+            .collect_custom_property_references(references, preserve_root)
+        }
+        CssRule::Keyframes(keyframes) => {
+          for keyframe in &keyframes.keyframes {
+            collect_declarations(&keyframe.declarations, references, false);
+          }
+          true
+        }
+        CssRule::Page(page) => {
+          collect_declarations(&page.declarations, references, false);
+          for margin_rule in &page.rules {
+            collect_declarations(&margin_rule.declarations, references, false);
+          }
+          true
+        }
+        CssRule::Media(media) => media.rules.collect_custom_property_references(references, preserve_root),
+        CssRule::Supports(supports) => {
+          supports.rules.collect_custom_property_references(references, preserve_root)
+        }
+        CssRule::MozDocument(document) => {
+          document.rules.collect_custom_property_references(references, preserve_root)
+        }
+        CssRule::LayerBlock(layer) => layer.rules.collect_custom_property_references(references, preserve_root),
+        CssRule::Container(container) => {
+          container.rules.collect_custom_property_references(references, preserve_root)
+        }
+        // Unknown and custom at-rules may reference custom properties that we cannot see.
+        CssRule::Unknown(..) | CssRule::Custom(..) => false,
+        _ => true,
+      };
+
+      if !collected {
+        return false;
+      }
+    }
+
+    true
+  }
+
+  /// Collects the names of the custom properties declared within the rule list, including those registered
+  /// with `@property`, indexed by the source index of the declaring rule.
+  pub(crate) fn collect_declared_custom_properties(&self, names: &mut [HashSet<String>]) {
+    fn collect_declarations(declarations: &DeclarationBlock, source_index: u32, names: &mut [HashSet<String>]) {
+      for (property, _) in declarations.iter() {
+        if let Property::Custom(CustomProperty {
+          name: CustomPropertyName::Custom(name),
+          ..
+        }) = property
+        {
+          insert(names, source_index, &name.0);
+        }
+      }
+    }
+
+    fn insert(names: &mut [HashSet<String>], source_index: u32, name: &str) {
+      if let Some(names) = names.get_mut(source_index as usize) {
+        names.insert(name.to_string());
+      }
+    }
+
+    for rule in &self.0 {
+      match rule {
+        CssRule::Style(style) => {
+          collect_declarations(&style.declarations, style.loc.source_index, names);
+          style.rules.collect_declared_custom_properties(names);
+        }
+        CssRule::Nesting(nesting) => {
+          collect_declarations(&nesting.style.declarations, nesting.style.loc.source_index, names);
+          nesting.style.rules.collect_declared_custom_properties(names);
+        }
+        CssRule::Keyframes(keyframes) => {
+          for keyframe in &keyframes.keyframes {
+            collect_declarations(&keyframe.declarations, keyframes.loc.source_index, names);
+          }
+        }
+        CssRule::Page(page) => {
+          collect_declarations(&page.declarations, page.loc.source_index, names);
+          for margin_rule in &page.rules {
+            collect_declarations(&margin_rule.declarations, page.loc.source_index, names);
+          }
+        }
+        CssRule::Property(property) => insert(names, property.loc.source_index, &property.name.0),
+        CssRule::Media(media) => media.rules.collect_declared_custom_properties(names),
+        CssRule::Supports(supports) => supports.rules.collect_declared_custom_properties(names),
+        CssRule::MozDocument(document) => document.rules.collect_declared_custom_properties(names),
+        CssRule::LayerBlock(layer) => layer.rules.collect_declared_custom_properties(names),
+        CssRule::Container(container) => container.rules.collect_declared_custom_properties(names),
+        _ => {}
+      }
+    }
+  }
+
+  /// Removes selectors that cannot match any element in documents with the given content,
+  /// and style rules where no selectors remain.
+  pub(crate) fn purge(&mut self, content: &PurgeContent) {
+    fn purge_style<T>(style: &mut StyleRule<T>, content: &PurgeContent) -> bool {
+      style.selectors.0.retain(|selector| can_match(selector, content));
+      if style.selectors.0.is_empty() {
+        return false;
+      }
+
+      style.rules.purge(content);
+      true
+    }
+
+    self.0.retain_mut(|rule| match rule {
+      CssRule::Style(style) => purge_style(style, content),
+      CssRule::Nesting(nesting) => purge_style(&mut nesting.style, content),
+      CssRule::Media(media) => {
+        media.rules.purge(content);
+        !media.rules.0.is_empty()
+      }
+      CssRule::Supports(supports) => {
+        supports.rules.purge(content);
+        !supports.rules.0.is_empty()
+      }
+      CssRule::MozDocument(document) => {
+        document.rules.purge(content);
+        !document.rules.0.is_empty()
+      }
+      CssRule::LayerBlock(layer) => {
+        layer.rules.purge(content);
+        true
+      }
+      CssRule::Container(container) => {
+        container.rules.purge(content);
+        !container.rules.0.is_empty()
+      }
+      _ => true,
+    });
+  }
+
+  /// Collects the classes, ids, and `@keyframes` names scoped by CSS modules within the rule list, and the
+  /// local classes composed by each class.
+  pub(crate) fn collect_css_module_names(
+    &self,
+    config: &CssModulesConfig,
+    names: &mut HashSet<String>,
+    composes: &mut HashMap<String, Vec<String>>,
+  ) {
+    fn collect_style<T>(
+      style: &StyleRule<T>,
+      config: &CssModulesConfig,
+      names: &mut HashSet<String>,
+      composes: &mut HashMap<String, Vec<String>>,
+    ) {
+      for selector in &style.selectors.0 {
+        collect_css_module_names(selector, config, names);
+      }
+
+      for (property, _) in style.declarations.iter() {
+        let value = match property {
+          Property::Composes(value) if matches!(value.from, None | Some(Specifier::SourceIndex(_))) => value,
+          _ => continue,
+        };
+
+        for selector in &style.selectors.0 {
+          if selector.len() != 1 {
+            continue;
+          }
+
+          if let Some(Component::Class(class)) = selector.iter_raw_match_order().next() {
+            composes
+              .entry(class.0.to_string())
+              .or_default()
+              .extend(value.names.iter().map(|name| name.0.to_string()));
+          }
+        }
+      }
+
+      style.rules.collect_css_module_names(config, names, composes);
+    }
+
+    for rule in &self.0 {
+      match rule {
+        CssRule::Style(style) => collect_style(style, config, names, composes),
+        CssRule::Nesting(nesting) => collect_style(&nesting.style, config, names, composes),
+        CssRule::Keyframes(keyframes) if config.animation => {
+          if let KeyframesName::Ident(ident) = &keyframes.name {
+            names.insert(ident.0.to_string());
+          }
+        }
+        CssRule::Media(media) => media.rules.collect_css_module_names(config, names, composes),
+        CssRule::Supports(supports) => supports.rules.collect_css_module_names(config, names, composes),
+        CssRule::MozDocument(document) => document.rules.collect_css_module_names(config, names, composes),
+        CssRule::LayerBlock(layer) => layer.rules.collect_css_module_names(config, names, composes),
+        CssRule::Container(container) => container.rules.collect_css_module_names(config, names, composes),
+        _ => {}
+      }
+    }
+  }
+
+  /// Removes selectors that can only match classes or ids scoped by CSS modules which are not used,
+  /// and style rules where no selectors remain.
+  pub(crate) fn remove_unused_exports(&mut self, config: &CssModulesConfig, used: &HashSet<String>) {
+    fn remove_style<T>(style: &mut StyleRule<T>, config: &CssModulesConfig, used: &HashSet<String>) -> bool {
+      style
+        .selectors
+        .0
+        .retain(|selector| !references_unused_export(selector, config, used));
+      if style.selectors.0.is_empty() {
+        return false;
+      }
+
+      style.rules.remove_unused_exports(config, used);
+      true
+    }
+
+    self.0.retain_mut(|rule| match rule {
+      CssRule::Style(style) => remove_style(style, config, used),
+      CssRule::Nesting(nesting) => remove_style(&mut nesting.style, config, used),
+      CssRule::Media(media) => {
+        media.rules.remove_unused_exports(config, used);
+        !media.rules.0.is_empty()
+      }
+      CssRule::Supports(supports) => {
+        supports.rules.remove_unused_exports(config, used);
+        !supports.rules.0.is_empty()
+      }
+      CssRule::MozDocument(document) => {
+        document.rules.remove_unused_exports(config, used);
+        !document.rules.0.is_empty()
+      }
+      CssRule::LayerBlock(layer) => {
+        layer.rules.remove_unused_exports(config, used);
+        true
+      }
+      CssRule::Container(container) => {
+        container.rules.remove_unused_exports(config, used);
+        !container.rules.0.is_empty()
+      }
+      _ => true,
+    });
+  }
+
+  /// Removes the `@keyframes` rules within the rule list for which the predicate returns `false`.
+  pub(crate) fn retain_keyframes<F: FnMut(&KeyframesRule<'i>) -> bool>(&mut self, f: &mut F) {
+    self.0.retain_mut(|rule| match rule {
+      CssRule::Keyframes(keyframes) => f(keyframes),
+      CssRule::Media(media) => {
+        media.rules.retain_keyframes(f);
+        true
+      }
+      CssRule::Supports(supports) => {
+        supports.rules.retain_keyframes(f);
+        true
+      }
+      CssRule::MozDocument(document) => {
+        document.rules.retain_keyframes(f);
+        true
+      }
+      CssRule::LayerBlock(layer) => {
+        layer.rules.retain_keyframes(f);
+        true
+      }
+      CssRule::Container(container) => {
+        container.rules.retain_keyframes(f);
+        true
+      }
+      _ => true,
+    });
+  }
+
   pub(crate) fn minify(
     &mut self,
     context: &mut MinifyContext<'_, 'i>,
@@ -434,12 +1294,31 @@ impl<'i, T> CssRuleList<'i, T> {
     for mut rule in self.0.drain(..) {
       match &mut rule {
         CssRule::Keyframes(keyframes) => {
-          if context.unused_symbols.contains(match &keyframes.name {
-            KeyframesName::Ident(ident) => ident.0.as_ref(),
-            KeyframesName::Custom(string) => string.as_ref(),
-          }) {
+          if context.unused_symbols.contains(
+            SymbolKind::Name,
+            match &keyframes.name {
+              KeyframesName::Ident(ident) => ident.0.as_ref(),
+              KeyframesName::Custom(string) => string.as_ref(),
+            },
+          ) {
             continue;
           }
+
+          if let Some(used_keyframes) = context.used_keyframes {
+            let source_index = if context.scoped_keyframes {
+              keyframes.loc.source_index
+            } else {
+              0
+            };
+            let name = match &keyframes.name {
+              KeyframesName::Ident(ident) => ident.0.to_string(),
+              KeyframesName::Custom(string) => string.to_string(),
+            };
+            if !used_keyframes.contains(&(source_index, name)) {
+              continue;
+            }
+          }
+
           keyframes.minify(context);
 
           macro_rules! set_prefix {
@@ -454,7 +1333,9 @@ impl<'i, T> CssRuleList<'i, T> {
 
           // If there is an existing rule with the same name and identical keyframes,
           // merge the vendor prefixes from this rule into it.
-          if let Some(existing_idx) = keyframe_rules.get(&keyframes.name) {
+          if let Some(existing_idx) =
+            keyframe_rules.get(&keyframes.name).filter(|_| !context.preserve_rule_structure)
+          {
             if let Some(CssRule::Keyframes(existing)) = &mut rules.get_mut(*existing_idx) {
               if existing.keyframes == keyframes.keyframes {
                 existing.vendor_prefix |= keyframes.vendor_prefix;
@@ -474,13 +1355,27 @@ impl<'i, T> CssRuleList<'i, T> {
             continue;
           }
         }
-        CssRule::CustomMedia(_) => {
+        CssRule::CustomMedia(custom_media) => {
           if context.custom_media.is_some() {
+            context
+              .handler_context
+              .lowered_features
+              .push((compat::Feature::CustomMediaQueries, custom_media.loc));
             continue;
           }
         }
         CssRule::Media(media) => {
-          if let Some(CssRule::Media(last_rule)) = rules.last_mut() {
+          if let Some(targets) = context.targets {
+            let mut features = Vec::new();
+            media.query.get_lowered_features(*targets, &mut features);
+            let loc = media.loc;
+            context
+              .handler_context
+              .lowered_features
+              .extend(features.into_iter().map(|feature| (feature, loc)));
+          }
+
+          if let Some(CssRule::Media(last_rule)) = rules.last_mut().filter(|_| !context.preserve_rule_structure) {
             if last_rule.query == media.query {
               last_rule.rules.0.extend(media.rules.0.drain(..));
               last_rule.minify(context, parent_is_unused)?;
@@ -491,9 +1386,26 @@ impl<'i, T> CssRuleList<'i, T> {
           if media.minify(context, parent_is_unused)? {
             continue;
           }
+
+          // If the query always matches within the parent rules, the wrapper is redundant.
+          if context.parent_media.iter().any(|parent| media.query.contains(parent)) {
+            rules.append(&mut media.rules.0);
+            continue;
+          }
         }
         CssRule::Supports(supports) => {
-          if let Some(CssRule::Supports(last_rule)) = rules.last_mut() {
+          if supports.never_matches(&context.parent_supports) {
+            continue;
+          }
+
+          if supports.always_matches(&context.parent_supports) {
+            supports.rules.minify(context, parent_is_unused)?;
+            rules.append(&mut supports.rules.0);
+            continue;
+          }
+
+          if let Some(CssRule::Supports(last_rule)) = rules.last_mut().filter(|_| !context.preserve_rule_structure)
+          {
             if last_rule.condition == supports.condition {
               last_rule.rules.0.extend(supports.rules.0.drain(..));
               last_rule.minify(context, parent_is_unused)?;
@@ -507,7 +1419,9 @@ impl<'i, T> CssRuleList<'i, T> {
           }
         }
         CssRule::Container(container) => {
-          if let Some(CssRule::Container(last_rule)) = rules.last_mut() {
+          if let Some(CssRule::Container(last_rule)) =
+            rules.last_mut().filter(|_| !context.preserve_rule_structure)
+          {
             if last_rule.name == container.name && last_rule.condition == container.condition {
               last_rule.rules.0.extend(container.rules.0.drain(..));
               last_rule.minify(context, parent_is_unused)?;
@@ -520,7 +1434,9 @@ impl<'i, T> CssRuleList<'i, T> {
           }
         }
         CssRule::LayerBlock(layer) => {
-          if let Some(CssRule::LayerBlock(last_rule)) = rules.last_mut() {
+          if let Some(CssRule::LayerBlock(last_rule)) =
+            rules.last_mut().filter(|_| !context.preserve_rule_structure)
+          {
             if last_rule.name == layer.name {
               last_rule.rules.0.extend(layer.rules.0.drain(..));
               last_rule.minify(context, parent_is_unused)?;
@@ -531,13 +1447,43 @@ impl<'i, T> CssRuleList<'i, T> {
             continue;
           }
         }
-        CssRule::MozDocument(document) => document.minify(context)?,
+        CssRule::MozDocument(document) => {
+          let remove = document.minify(context)?;
+          if remove {
+            continue;
+          }
+        }
+        CssRule::Viewport(viewport) => {
+          if let Some(targets) = context.targets {
+            if !viewport.vendor_prefix.contains(VendorPrefix::None)
+              && !Feature::AtViewport.prefixes_for(*targets).intersects(viewport.vendor_prefix)
+            {
+              continue;
+            }
+          }
+        }
         CssRule::Style(style) => {
           if parent_is_unused || style.minify(context, parent_is_unused)? {
             continue;
           }
 
           if let Some(targets) = context.targets {
+            let mut lowered = Vec::new();
+            let mut unsupported = Vec::new();
+            get_incompatible_features(&style.selectors, *targets, &mut lowered, &mut unsupported);
+            if !style.rules.0.is_empty() && !compat::Feature::CssNesting.is_compatible(*targets) {
+              lowered.push(compat::Feature::CssNesting);
+            }
+
+            let loc = style.loc;
+            let handler_context = &mut context.handler_context;
+            handler_context
+              .lowered_features
+              .extend(lowered.into_iter().map(|feature| (feature, loc)));
+            handler_context
+              .unsupported_features
+              .extend(unsupported.into_iter().map(|feature| (feature, loc)));
+
             style.vendor_prefix = get_prefix(&style.selectors);
             if style.vendor_prefix.contains(VendorPrefix::None) {
               style.vendor_prefix = downlevel_selectors(&mut style.selectors, *targets);
@@ -546,7 +1492,9 @@ impl<'i, T> CssRuleList<'i, T> {
 
           // Attempt to merge the new rule with the last rule we added.
           let mut merged = false;
-          if let Some(CssRule::Style(last_style_rule)) = rules.last_mut() {
+          if let Some(CssRule::Style(last_style_rule)) =
+            rules.last_mut().filter(|_| !context.preserve_rule_structure)
+          {
             if merge_style_rules(style, last_style_rule, context) {
               // If that was successful, then the last rule has been updated to include the
               // selectors/declarations of the new rule. This might mean that we can merge it
@@ -575,26 +1523,42 @@ impl<'i, T> CssRuleList<'i, T> {
           }
 
           if !logical.is_empty() {
+            // The generated rules use :dir() selectors, which were not authored, so don't report them.
+            let lowered_len = context.handler_context.lowered_features.len();
             let mut logical = CssRuleList(logical);
             logical.minify(context, parent_is_unused)?;
+            context.handler_context.lowered_features.truncate(lowered_len);
             rules.extend(logical.0)
           }
 
           rules.extend(supports);
           continue;
         }
-        CssRule::CounterStyle(counter_style) => {
-          if context.unused_symbols.contains(counter_style.name.0.as_ref()) {
-            continue;
-          }
+        CssRule::CounterStyle(counter_style)
+          if context.unused_symbols.contains(SymbolKind::Name, counter_style.name.0.as_ref()) =>
+        {
+          continue;
         }
         CssRule::Nesting(nesting) => {
           if nesting.minify(context, parent_is_unused)? {
             continue;
           }
         }
+        CssRule::FontFace(font_face) => {
+          if let Some(used_font_families) = context.used_font_families {
+            let is_unused = font_face.properties.iter().any(|property| match property {
+              FontFaceProperty::FontFamily(FontFamily::FamilyName(name)) => {
+                !used_font_families.contains(&name.to_lowercase())
+              }
+              _ => false,
+            });
+            if is_unused {
+              continue;
+            }
+          }
+        }
         CssRule::FontPaletteValues(f) => {
-          if context.unused_symbols.contains(f.name.0.as_ref()) {
+          if context.unused_symbols.contains(SymbolKind::Name, f.name.0.as_ref()) {
             continue;
           }
 
@@ -607,10 +1571,10 @@ impl<'i, T> CssRuleList<'i, T> {
             continue;
           }
         }
-        CssRule::Property(property) => {
-          if context.unused_symbols.contains(property.name.0.as_ref()) {
-            continue;
-          }
+        CssRule::Property(property)
+          if context.unused_symbols.contains(SymbolKind::Name, property.name.0.as_ref()) =>
+        {
+          continue;
         }
         _ => {}
       }
@@ -618,11 +1582,675 @@ impl<'i, T> CssRuleList<'i, T> {
       rules.push(rule)
     }
 
+    // These passes restructure the rules, so they are skipped when preserving the rule structure.
+    if !context.preserve_rule_structure {
+      if context.restructure_rules {
+        remove_overridden_declarations(&mut rules, *context.targets, context.css_modules);
+        merge_non_adjacent_style_rules(&mut rules, context);
+      }
+
+      if let Some(targets) = context.targets {
+        remove_obsolete_prefixed_rules(&mut rules, *targets);
+      }
+    }
+
+    if context.canonical_order {
+      // Declarations may have been merged from other rules, so sort them again before the rules.
+      for rule in &mut rules {
+        if let CssRule::Style(style) = rule {
+          sort_declarations(&mut style.declarations);
+        }
+      }
+      sort_style_rules(&mut rules);
+    }
+
     self.0 = rules;
     Ok(())
   }
 }
 
+/// Custom properties declared in a style sheet, and the `var()` references to them.
+#[derive(Default)]
+pub(crate) struct CustomPropertyReferences {
+  /// Custom properties that are referenced by declarations other than custom properties,
+  /// or that should be preserved regardless.
+  pub used: HashSet<String>,
+  /// The custom properties referenced by the values of each declared custom property.
+  pub dependencies: HashMap<String, HashSet<String>>,
+}
+
+impl CustomPropertyReferences {
+  /// Returns the custom properties that are used, either directly or via other used custom properties.
+  pub fn resolve(self) -> HashSet<String> {
+    let mut used = HashSet::new();
+    let mut stack: Vec<String> = self.used.into_iter().collect();
+    while let Some(name) = stack.pop() {
+      if let Some(dependencies) = self.dependencies.get(&name) {
+        stack.extend(dependencies.iter().filter(|dep| !used.contains(*dep)).cloned());
+      }
+      used.insert(name);
+    }
+    used
+  }
+}
+
+/// Returns whether the given shorthand is the same as the property, or sets it via its longhands.
+fn covers(shorthand: &PropertyId, property_id: &PropertyId) -> bool {
+  if shorthand == property_id {
+    return true;
+  }
+
+  match shorthand.longhands() {
+    Some(longhands) => longhands.iter().any(|longhand| covers(longhand, property_id)),
+    None => false,
+  }
+}
+
+/// Returns whether the order of two properties in the cascade may affect the computed style,
+/// i.e. they are the same property with or without a vendor prefix, one is a shorthand of the
+/// other, or they set the same value via logical and physical properties.
+fn properties_interact(a: &PropertyId, b: &PropertyId) -> bool {
+  if matches!(a, PropertyId::All) || matches!(b, PropertyId::All) || a.name() == b.name() {
+    return true;
+  }
+
+  if let (Some(a_group), Some(b_group)) = (a.logical_group(), b.logical_group()) {
+    if a_group == b_group {
+      return true;
+    }
+  }
+
+  covers(a, b) || covers(b, a)
+}
+
+/// Returns whether the order of two declarations may affect the computed style. This is more
+/// conservative than [properties_interact]: unknown properties may be shorthands of any other property,
+/// and a property is assumed to reset the properties its name is a prefix of, e.g. `font` resets
+/// `font-kerning` even though it cannot be set via the shorthand. Custom properties only interact
+/// with declarations of the same name.
+fn order_matters(a: &PropertyId, b: &PropertyId) -> bool {
+  fn is_prefix(a: &str, b: &str) -> bool {
+    matches!(b.strip_prefix(a), Some(rest) if rest.starts_with('-'))
+  }
+
+  match (a, b) {
+    (PropertyId::Custom(CustomPropertyName::Custom(a)), PropertyId::Custom(CustomPropertyName::Custom(b))) => {
+      a == b
+    }
+    (PropertyId::Custom(CustomPropertyName::Custom(_)), _)
+    | (_, PropertyId::Custom(CustomPropertyName::Custom(_))) => false,
+    (PropertyId::Custom(CustomPropertyName::Unknown(_)), _)
+    | (_, PropertyId::Custom(CustomPropertyName::Unknown(_))) => true,
+    _ => properties_interact(a, b) || is_prefix(a.name(), b.name()) || is_prefix(b.name(), a.name()),
+  }
+}
+
+/// Reorders items by their keys, while preserving the relative order of any two items that depend
+/// on each other. Items with equal keys keep their original order.
+fn canonical_order<T, K: Ord>(items: Vec<T>, keys: &[K], depends: impl Fn(usize, usize) -> bool) -> Vec<T> {
+  let len = items.len();
+  let mut successors = vec![Vec::new(); len];
+  let mut predecessors = vec![0; len];
+  for (i, successors) in successors.iter_mut().enumerate() {
+    for (j, count) in predecessors.iter_mut().enumerate().skip(i + 1) {
+      if depends(i, j) {
+        successors.push(j);
+        *count += 1;
+      }
+    }
+  }
+
+  let mut available: BinaryHeap<Reverse<(&K, usize)>> = (0..len)
+    .filter(|i| predecessors[*i] == 0)
+    .map(|i| Reverse((&keys[i], i)))
+    .collect();
+  let mut items: Vec<Option<T>> = items.into_iter().map(Some).collect();
+  let mut result = Vec::with_capacity(len);
+  while let Some(Reverse((_, i))) = available.pop() {
+    result.extend(items[i].take());
+    for &j in &successors[i] {
+      predecessors[j] -= 1;
+      if predecessors[j] == 0 {
+        available.push(Reverse((&keys[j], j)));
+      }
+    }
+  }
+
+  result
+}
+
+/// Sorts the declarations in a block by property name, except where their order may affect the
+/// computed style. Normal and `!important` declarations are sorted separately, since they never
+/// override each other.
+pub(crate) fn sort_declarations(declarations: &mut DeclarationBlock) {
+  fn sort(declarations: &mut Vec<Property>, locations: &mut Vec<Location>) {
+    if declarations.len() < 2 {
+      return;
+    }
+
+    let property_ids: Vec<PropertyId> = declarations.iter().map(|property| property.property_id()).collect();
+    let keys: Vec<&str> = property_ids.iter().map(|property_id| property_id.name()).collect();
+    let order = canonical_order((0..declarations.len()).collect(), &keys, |i, j| {
+      order_matters(&property_ids[i], &property_ids[j])
+    });
+
+    // Keep the source locations in the same order as the declarations.
+    if locations.len() == declarations.len() {
+      *locations = order.iter().map(|i| locations[*i]).collect();
+    }
+    let mut items: Vec<Option<Property>> = std::mem::take(declarations).into_iter().map(Some).collect();
+    *declarations = order.iter().filter_map(|i| items[*i].take()).collect();
+  }
+
+  sort(&mut declarations.declarations, &mut declarations.locations.declarations);
+  sort(
+    &mut declarations.important_declarations,
+    &mut declarations.locations.important_declarations,
+  );
+}
+
+/// Sorts each run of adjacent style rules by their selectors, except where the order of two rules
+/// may affect the computed style. Two rules can be swapped when none of their declarations interact,
+/// since no element matched by both can then be affected by their order in the cascade. Rules with
+/// nested rules are never moved, and other rules end the run.
+fn sort_style_rules<'i, T>(rules: &mut Vec<CssRule<'i, T>>) {
+  fn sort<'i, T>(run: Vec<CssRule<'i, T>>) -> Vec<CssRule<'i, T>> {
+    if run.len() < 2 {
+      return run;
+    }
+
+    let mut keys = Vec::with_capacity(run.len());
+    let mut property_ids = Vec::with_capacity(run.len());
+    for rule in &run {
+      if let CssRule::Style(style) = rule {
+        keys.push(style.selectors.to_css_string(PrinterOptions::default()).unwrap_or_default());
+        property_ids.push(
+          style
+            .declarations
+            .declarations
+            .iter()
+            .chain(style.declarations.important_declarations.iter())
+            .map(|property| property.property_id())
+            .collect::<Vec<_>>(),
+        );
+      }
+    }
+
+    canonical_order(run, &keys, |i, j| {
+      property_ids[i]
+        .iter()
+        .any(|a| property_ids[j].iter().any(|b| order_matters(a, b)))
+    })
+  }
+
+  let mut result = Vec::with_capacity(rules.len());
+  let mut run = Vec::new();
+  for rule in rules.drain(..) {
+    match &rule {
+      CssRule::Style(style) if style.rules.0.is_empty() => run.push(rule),
+      _ => {
+        result.append(&mut sort(std::mem::take(&mut run)));
+        result.push(rule);
+      }
+    }
+  }
+  result.append(&mut sort(run));
+  *rules = result;
+}
+
+/// Merges style rules into a later rule with the same selectors when the rules in between are not
+/// affected by the change in order. This is the case when none of the intervening rules declare a
+/// property that interacts with the properties of the moved rule, so no element can be matched by
+/// both with a different outcome. Rules that may contain other style rules, such as `@media`, stop
+/// the search.
+fn merge_non_adjacent_style_rules<'i, T>(rules: &mut Vec<CssRule<'i, T>>, context: &mut MinifyContext<'_, 'i>) {
+  /// A key shared by all properties that may interact with each other, i.e. the names of a property
+  /// and its longhands, and its logical property group.
+  #[derive(PartialEq, Eq, Hash)]
+  enum InteractionKey {
+    Name(String),
+    LogicalGroup(LogicalGroup),
+  }
+
+  fn add_interaction_keys(property_id: &PropertyId, keys: &mut Vec<InteractionKey>) {
+    keys.push(InteractionKey::Name(property_id.name().to_owned()));
+    if let Some(group) = property_id.logical_group() {
+      keys.push(InteractionKey::LogicalGroup(group));
+    }
+    if let Some(longhands) = property_id.longhands() {
+      for longhand in &longhands {
+        add_interaction_keys(longhand, keys);
+      }
+    }
+  }
+
+  fn declared_properties<'i>(declarations: &DeclarationBlock<'i>) -> Vec<PropertyId<'i>> {
+    declarations
+      .declarations
+      .iter()
+      .chain(declarations.important_declarations.iter())
+      .map(|property| property.property_id())
+      .collect()
+  }
+
+  // The index of the last rule with each selector list, and of the last rule declaring each interaction key.
+  let mut last_rules: HashMap<(String, VendorPrefix), usize> = HashMap::new();
+  let mut last_declared: HashMap<InteractionKey, usize> = HashMap::new();
+  let mut last_any: Option<usize> = None;
+  let mut last_all: Option<usize> = None;
+  let mut merged = false;
+  for j in 0..rules.len() {
+    let style = match &rules[j] {
+      CssRule::Style(style) if style.rules.0.is_empty() => style,
+      CssRule::Keyframes(..)
+      | CssRule::FontFace(..)
+      | CssRule::FontPaletteValues(..)
+      | CssRule::CounterStyle(..)
+      | CssRule::Property(..)
+      | CssRule::CustomMedia(..) => continue,
+      _ => {
+        last_rules.clear();
+        last_declared.clear();
+        last_any = None;
+        last_all = None;
+        continue;
+      }
+    };
+
+    let key = match style.selectors.to_css_string(PrinterOptions::default()) {
+      Ok(selectors) if style.is_compatible(*context.targets) => Some((selectors, style.vendor_prefix)),
+      _ => None,
+    };
+
+    if let Some(&i) = key.as_ref().and_then(|key| last_rules.get(key)) {
+      let (before, after) = rules.split_at_mut(j);
+      if let (CssRule::Style(prev), CssRule::Style(style)) = (&mut before[i], &mut after[0]) {
+        let is_after_prev = |index: Option<&usize>| matches!(index, Some(index) if *index > i);
+        if prev.selectors == style.selectors
+          && !prev.is_empty()
+          && prev.rules.0.is_empty()
+          && (!context.css_modules || prev.loc.source_index == style.loc.source_index)
+          && !is_after_prev(last_all.as_ref())
+          && declared_properties(&prev.declarations).iter().all(|property_id| {
+            if matches!(property_id, PropertyId::All) {
+              return !is_after_prev(last_any.as_ref());
+            }
+            let mut keys = Vec::new();
+            add_interaction_keys(property_id, &mut keys);
+            keys.iter().all(|key| !is_after_prev(last_declared.get(key)))
+          })
+        {
+          // Move the declarations of the previous rule before the ones in this rule, leaving it empty.
+          prev.declarations.declarations.append(&mut style.declarations.declarations);
+          prev
+            .declarations
+            .important_declarations
+            .append(&mut style.declarations.important_declarations);
+          std::mem::swap(&mut prev.declarations, &mut style.declarations);
+          style
+            .declarations
+            .minify(context.handler, context.important_handler, context.handler_context);
+          merged = true;
+        }
+      }
+    }
+
+    // Record the properties of this rule, including any merged from the previous rule.
+    if let CssRule::Style(style) = &rules[j] {
+      let mut keys = Vec::new();
+      for property_id in declared_properties(&style.declarations) {
+        if matches!(property_id, PropertyId::All) {
+          last_all = Some(j);
+        }
+        add_interaction_keys(&property_id, &mut keys);
+        last_any = Some(j);
+      }
+      for key in keys {
+        last_declared.insert(key, j);
+      }
+    }
+    if let Some(key) = key {
+      last_rules.insert(key, j);
+    }
+  }
+
+  if merged {
+    rules.retain(|rule| !matches!(rule, CssRule::Style(style) if style.is_empty()));
+  }
+}
+
+/// Removes declarations from style rules that are unconditionally overridden by later rules in the
+/// same list. A declaration is overridden if, for each of the selectors of its rule, a later rule with
+/// the same selector, i.e. matching the same elements with the same specificity, declares the same
+/// property or a shorthand of it with at least the same importance. Rules are only compared within the
+/// same list, so they are always subject to the same conditional rules such as `@media` and `@supports`.
+/// With CSS modules, only rules from the same source file are compared, since their class names are
+/// scoped differently.
+fn remove_overridden_declarations<'i, T>(
+  rules: &mut Vec<CssRule<'i, T>>,
+  targets: Option<Browsers>,
+  css_modules: bool,
+) {
+  /// The properties declared by later rules for a selector, including the longhands of shorthands.
+  #[derive(Default)]
+  struct Overriding<'i> {
+    normal: Vec<PropertyId<'i>>,
+    important: Vec<PropertyId<'i>>,
+  }
+
+  fn add_property<'i>(property_id: PropertyId<'i>, overriding: &mut Vec<PropertyId<'i>>) {
+    if let Some(longhands) = property_id.longhands() {
+      for longhand in longhands {
+        add_property(longhand, overriding);
+      }
+    }
+    if !overriding.contains(&property_id) {
+      overriding.push(property_id);
+    }
+  }
+
+  // Visit the rules in reverse, so the properties declared by all later rules are known for each selector.
+  let mut overriding: HashMap<(String, VendorPrefix, u32), Overriding> = HashMap::new();
+  let mut removed = false;
+  for rule in rules.iter_mut().rev() {
+    let style = match rule {
+      CssRule::Style(style) if !style.declarations.is_empty() => style,
+      _ => continue,
+    };
+
+    let source_index = if css_modules { style.loc.source_index } else { 0 };
+    let keys: Option<Vec<_>> = style
+      .selectors
+      .0
+      .iter()
+      .map(|selector| {
+        let selector = SelectorList::from(selector.clone()).to_css_string(PrinterOptions::default());
+        selector.ok().map(|selector| (selector, style.vendor_prefix, source_index))
+      })
+      .collect();
+    let keys = match keys {
+      Some(keys) => keys,
+      None => continue,
+    };
+
+    // CSS modules `composes` declarations accumulate rather than override each other.
+    let is_removable = |property: &Property| !matches!(property, Property::Composes(..));
+    let is_overridden = |property_id: &PropertyId, important: bool| {
+      !keys.is_empty()
+        && keys.iter().all(|key| match overriding.get(key) {
+          Some(overriding) => {
+            overriding.important.contains(property_id) || (!important && overriding.normal.contains(property_id))
+          }
+          None => false,
+        })
+    };
+    style
+      .declarations
+      .retain(|property, important| !is_removable(property) || !is_overridden(&property.property_id(), important));
+    if style.is_empty() {
+      removed = true;
+      continue;
+    }
+
+    if !style.is_compatible(targets) {
+      continue;
+    }
+
+    // Declarations that the targets may not support don't override earlier fallbacks.
+    let supported = |properties: &[Property<'i>]| -> Vec<PropertyId<'i>> {
+      properties
+        .iter()
+        .filter(|property| is_supported(property, targets))
+        .map(|property| property.property_id())
+        .collect()
+    };
+    let normal = supported(&style.declarations.declarations);
+    let important = supported(&style.declarations.important_declarations);
+    for key in keys {
+      let overriding = overriding.entry(key).or_default();
+      for property_id in &normal {
+        add_property(property_id.clone(), &mut overriding.normal);
+      }
+      for property_id in &important {
+        add_property(property_id.clone(), &mut overriding.important);
+      }
+    }
+  }
+
+  if removed {
+    rules.retain(|rule| !matches!(rule, CssRule::Style(style) if style.is_empty()));
+  }
+}
+
+/// Returns whether a declaration is supported by the targets as written, i.e. its value is not lowered
+/// when printed, e.g. `clamp()` or `lab()` colors, and it does not use properties, functions or vendor
+/// prefixed keywords that the targets may not support. Otherwise, earlier declarations of the same
+/// property act as fallbacks.
+fn is_supported(property: &Property, targets: Option<Browsers>) -> bool {
+  let targets = match targets {
+    Some(targets) => targets,
+    None => return true,
+  };
+
+  if matches!(crate::context::property_feature(property), Some(feature) if !feature.is_compatible(targets)) {
+    return false;
+  }
+
+  if let Property::Unparsed(UnparsedProperty { value, .. }) | Property::Custom(CustomProperty { value, .. }) =
+    property
+  {
+    let mut functions = Vec::new();
+    value.get_unsupported_functions(targets, &mut functions);
+    if !functions.is_empty() {
+      return false;
+    }
+  }
+
+  let value = match property.value_to_css_string(PrinterOptions::default()) {
+    Ok(value) => value,
+    Err(..) => return false,
+  };
+
+  if !value_prefixes(&value)
+    .iter()
+    .all(|prefix| is_prefix_supported(*prefix, targets))
+  {
+    return false;
+  }
+
+  let lowered = property.value_to_css_string(PrinterOptions {
+    targets: Some(targets),
+    ..PrinterOptions::default()
+  });
+  matches!(lowered, Ok(lowered) if lowered == value)
+}
+
+/// Returns the vendor prefixes of the identifiers and functions in a serialized value,
+/// e.g. `-webkit-fill-available`.
+fn value_prefixes(value: &str) -> Vec<VendorPrefix> {
+  let mut prefixes = Vec::new();
+  let mut is_token_start = true;
+  for (index, c) in value.char_indices() {
+    if is_token_start && c == '-' {
+      let rest = &value[index..];
+      for (prefix, vendor_prefix) in [
+        ("-webkit-", VendorPrefix::WebKit),
+        ("-moz-", VendorPrefix::Moz),
+        ("-ms-", VendorPrefix::Ms),
+        ("-o-", VendorPrefix::O),
+      ] {
+        if rest.starts_with(prefix) {
+          prefixes.push(vendor_prefix);
+        }
+      }
+    }
+    is_token_start = !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '\\' || !c.is_ascii());
+  }
+  prefixes
+}
+
+/// Returns whether all targets support vendor prefixed values with the given prefix. As the prefixed
+/// values supported by each browser are not known, this is only the case when all targets use the
+/// browser engine the prefix belongs to.
+fn is_prefix_supported(prefix: VendorPrefix, targets: Browsers) -> bool {
+  let Browsers {
+    android,
+    chrome,
+    edge,
+    firefox,
+    ie,
+    ios_saf,
+    opera,
+    safari,
+    samsung,
+    ..
+  } = targets;
+  let is_legacy_edge = matches!(edge, Some(version) if version < (79 << 16));
+  let is_webkit = firefox.is_none() && ie.is_none() && !is_legacy_edge;
+  let is_gecko = [android, chrome, edge, ie, ios_saf, opera, safari, samsung]
+    .iter()
+    .all(|version| version.is_none());
+  let is_trident = [android, chrome, firefox, ios_saf, opera, safari, samsung]
+    .iter()
+    .all(|version| version.is_none())
+    && (edge.is_none() || is_legacy_edge);
+  match prefix {
+    VendorPrefix::WebKit => is_webkit,
+    VendorPrefix::Moz => is_gecko,
+    VendorPrefix::Ms => is_trident,
+    _ => false,
+  }
+}
+
+/// Removes vendor prefixes from author-written prefixed `@keyframes` and style rules when an
+/// equivalent unprefixed rule exists, and the prefix either cannot match any of the targets,
+/// or the targets don't require it and the unprefixed rule covers the prefixed one. A prefixed
+/// `@keyframes` rule is covered if the unprefixed rule has the same keyframes, and a prefixed style
+/// rule if it is directly followed by the unprefixed rule, which declares all of its properties.
+/// Prefixed `@keyframes` rules are also removed when a later unprefixed rule with the same name is
+/// already printed with that prefix, since it would override them. Rules that are left without any
+/// prefixes are removed entirely.
+fn remove_obsolete_prefixed_rules<'i, T>(rules: &mut Vec<CssRule<'i, T>>, targets: Browsers) {
+  let is_prefixed = |prefix: VendorPrefix| !prefix.is_empty() && !prefix.contains(VendorPrefix::None);
+  let keyframes_prefixes = Feature::AtKeyframes.prefixes_for(targets);
+  let matching_prefixes = matching_prefixes(targets);
+
+  let mut unprefixed_keyframes = HashMap::new();
+  let mut unprefixed_selectors = Vec::new();
+  for (index, rule) in rules.iter().enumerate() {
+    match rule {
+      CssRule::Keyframes(keyframes) if keyframes.vendor_prefix.contains(VendorPrefix::None) => {
+        unprefixed_keyframes.insert(&keyframes.name, (index, keyframes));
+      }
+      CssRule::Style(style) if style.vendor_prefix.contains(VendorPrefix::None) => {
+        unprefixed_selectors.push(&style.selectors);
+      }
+      _ => {}
+    }
+  }
+
+  let prefixes: Vec<Option<VendorPrefix>> = rules
+    .iter()
+    .enumerate()
+    .map(|(index, rule)| match rule {
+      CssRule::Keyframes(keyframes) if is_prefixed(keyframes.vendor_prefix) => {
+        let (unprefixed_index, unprefixed) = *unprefixed_keyframes.get(&keyframes.name)?;
+        let mut prefix = keyframes.vendor_prefix & matching_prefixes;
+        if index < unprefixed_index {
+          prefix &= !unprefixed.vendor_prefix;
+        }
+        if keyframes.keyframes == unprefixed.keyframes {
+          prefix &= keyframes_prefixes;
+        }
+        Some(prefix)
+      }
+      CssRule::Style(style)
+        if is_prefixed(style.vendor_prefix)
+          && style.rules.0.is_empty()
+          && unprefixed_selectors
+            .iter()
+            .any(|selectors| is_equivalent(selectors, &style.selectors)) =>
+      {
+        let mut prefix = style.vendor_prefix & matching_prefixes;
+        if let Some(CssRule::Style(next)) = rules.get(index + 1) {
+          if next.vendor_prefix.contains(VendorPrefix::None)
+            && is_equivalent(&next.selectors, &style.selectors)
+            && declarations_cover(&next.declarations, &style.declarations)
+          {
+            prefix &= get_required_prefixes(&style.selectors, targets);
+          }
+        }
+        Some(prefix)
+      }
+      _ => None,
+    })
+    .collect();
+
+  let mut prefixes = prefixes.into_iter();
+  rules.retain_mut(|rule| match (rule, prefixes.next().flatten()) {
+    (_, Some(prefix)) if prefix.is_empty() => false,
+    (CssRule::Keyframes(keyframes), Some(prefix)) => {
+      keyframes.vendor_prefix = prefix;
+      true
+    }
+    (CssRule::Style(style), Some(prefix)) => {
+      style.vendor_prefix = prefix;
+      true
+    }
+    _ => true,
+  });
+}
+
+/// Returns the vendor prefixes that may be used by at least one of the targets.
+fn matching_prefixes(targets: Browsers) -> VendorPrefix {
+  let Browsers {
+    android,
+    chrome,
+    edge,
+    firefox,
+    ie,
+    ios_saf,
+    opera,
+    safari,
+    samsung,
+    ..
+  } = targets;
+  let mut prefixes = VendorPrefix::empty();
+  if [android, chrome, edge, ios_saf, opera, safari, samsung]
+    .iter()
+    .any(|version| version.is_some())
+  {
+    prefixes |= VendorPrefix::WebKit;
+  }
+  if firefox.is_some() {
+    prefixes |= VendorPrefix::Moz;
+  }
+  if ie.is_some() || edge.is_some() {
+    prefixes |= VendorPrefix::Ms;
+  }
+  if opera.is_some() {
+    prefixes |= VendorPrefix::O;
+  }
+  prefixes
+}
+
+/// Returns whether a declaration block sets all of the properties in another block, with at least
+/// the same importance.
+fn declarations_cover(declarations: &DeclarationBlock, other: &DeclarationBlock) -> bool {
+  let is_covered = |property: &Property, by: &[Property]| {
+    let property_id = property.property_id();
+    by.iter().any(|other| covers(&other.property_id(), &property_id))
+  };
+  other
+    .important_declarations
+    .iter()
+    .all(|property| is_covered(property, &declarations.important_declarations))
+    && other.declarations.iter().all(|property| {
+      is_covered(property, &declarations.declarations)
+        || is_covered(property, &declarations.important_declarations)
+    })
+}
+
 fn merge_style_rules<'i, T>(
   style: &mut StyleRule<'i, T>,
   last_style_rule: &mut StyleRule<'i, T>,
@@ -636,14 +2264,7 @@ fn merge_style_rules<'i, T>(
     && last_style_rule.rules.0.is_empty()
     && (!context.css_modules || style.loc.source_index == last_style_rule.loc.source_index)
   {
-    last_style_rule
-      .declarations
-      .declarations
-      .extend(style.declarations.declarations.drain(..));
-    last_style_rule
-      .declarations
-      .important_declarations
-      .extend(style.declarations.important_declarations.drain(..));
+    last_style_rule.declarations.append(&mut style.declarations);
     last_style_rule
       .declarations
       .minify(context.handler, context.important_handler, context.handler_context);
@@ -655,6 +2276,7 @@ fn merge_style_rules<'i, T>(
     // Append the selectors to the last rule if the declarations are the same, and all selectors are compatible.
     if style.is_compatible(*context.targets) && last_style_rule.is_compatible(*context.targets) {
       last_style_rule.selectors.0.extend(style.selectors.0.drain(..));
+      remove_redundant_selectors(&mut last_style_rule.selectors.0);
       return true;
     }
 
@@ -698,12 +2320,21 @@ impl<'a, 'i, T: ToCss> ToCssWithContext<'a, 'i, T> for CssRuleList<'i, T> {
   {
     let mut first = true;
     let mut last_without_block = false;
+    let mut last_comment = false;
 
     for rule in &self.0 {
       if let CssRule::Ignored = &rule {
         continue;
       }
 
+      // CSS modules values are exported rather than printed.
+      if let CssRule::Value(rule) = &rule {
+        if dest.css_module.is_some() {
+          rule.add_exports(dest)?;
+          continue;
+        }
+      }
+
       // Skip @import rules if collecting dependencies.
       if let CssRule::Import(rule) = &rule {
         if dest.remove_imports {
@@ -723,7 +2354,9 @@ impl<'a, 'i, T: ToCss> ToCssWithContext<'a, 'i, T> for CssRuleList<'i, T> {
       if first {
         first = false;
       } else {
+        // Comments are printed directly above the following rule.
         if !dest.minify
+          && !last_comment
           && !(last_without_block
             && matches!(
               rule,
@@ -734,11 +2367,17 @@ impl<'a, 'i, T: ToCss> ToCssWithContext<'a, 'i, T> for CssRuleList<'i, T> {
         }
         dest.newline()?;
       }
+      if dest.source_comments {
+        if let Some(source_index) = rule.source_index() {
+          dest.source_comment(source_index)?;
+        }
+      }
       rule.to_css_with_context(dest, context)?;
       last_without_block = matches!(
         rule,
         CssRule::Import(..) | CssRule::Namespace(..) | CssRule::LayerStatement(..)
       );
+      last_comment = matches!(rule, CssRule::Comment(..));
     }
 
     Ok(())
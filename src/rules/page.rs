@@ -316,13 +316,7 @@ impl<'a, 'o, 'i, T> cssparser::DeclarationParser<'i> for PageRuleParser<'a, 'o,
     name: CowRcStr<'i>,
     input: &mut cssparser::Parser<'i, 't>,
   ) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
-    parse_declaration(
-      name,
-      input,
-      &mut self.declarations.declarations,
-      &mut self.declarations.important_declarations,
-      &self.options,
-    )
+    parse_declaration(name, input, self.declarations, self.options, None)
   }
 }
 
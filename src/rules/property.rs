@@ -1,18 +1,27 @@
 //! The `@property` rule.
 
-use super::Location;
+use super::{CssRule, CssRuleList, CustomPropertyReferences, Location};
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
 use crate::{
-  error::{ParserError, PrinterError},
-  printer::Printer,
+  declaration::DeclarationBlock,
+  error::{ErrorWithLocation, ParserError, PrinterError},
+  printer::{Printer, PrinterOptions},
+  properties::{
+    custom::{self, CustomProperty, CustomPropertyName, TokenList, TokenOrValue, UnparsedProperty},
+    Property,
+  },
+  stylesheet::ParserOptions,
   traits::{Parse, ToCss},
   values::{
     ident::DashedIdent,
-    syntax::{ParsedComponent, SyntaxString},
+    length::Length,
+    percentage::DimensionPercentage,
+    syntax::{Multiplier, ParsedComponent, SyntaxString},
   },
 };
 use cssparser::*;
+use std::collections::{HashMap, HashSet};
 
 /// A [@property](https://drafts.css-houdini.org/css-properties-values-api/#at-property-rule) rule.
 #[derive(Debug, PartialEq, Clone)]
@@ -105,7 +114,9 @@ impl<'i> ToCss for PropertyRule<'i> {
     #[cfg(feature = "sourcemap")]
     dest.add_mapping(self.loc);
     dest.write_str("@property ")?;
-    self.name.to_css(dest)?;
+    if !dest.write_scoped_custom_property(&self.name.0, true)? {
+      self.name.to_css(dest)?;
+    }
     dest.whitespace()?;
     dest.write_char('{')?;
     dest.indent();
@@ -194,3 +205,244 @@ impl<'i> AtRuleParser<'i> for PropertyRuleDeclarationParser<'i> {
   type AtRule = ();
   type Error = ParserError<'i>;
 }
+
+/// The custom properties registered by the top-level `@property` rules of a style sheet, as used
+/// during minification to validate declarations and `var()` references against the registered
+/// syntax, and to inline the initial values of registered properties that are never declared.
+pub(crate) struct RegisteredProperties<'i> {
+  rules: HashMap<String, PropertyRule<'i>>,
+  /// The initial values of the registered properties, as token lists.
+  initial_values: HashMap<String, TokenList<'i>>,
+  /// The initial values of the registered properties that are never declared in the style sheet.
+  inlined_values: HashMap<String, TokenList<'i>>,
+}
+
+impl<'i> RegisteredProperties<'i> {
+  /// Collects the registered properties from a rule list, or returns `None` if there are none.
+  /// When `inline` is true, `var()` references to registered properties that are not declared
+  /// anywhere in the rule list will be replaced by their initial value.
+  pub fn new<T>(rules: &CssRuleList<'i, T>, inline: bool) -> Option<Self> {
+    fn collect<'i, T>(rules: &CssRuleList<'i, T>, registered: &mut HashMap<String, PropertyRule<'i>>) {
+      for rule in &rules.0 {
+        match rule {
+          CssRule::Property(property) => {
+            registered.insert(property.name.0.to_string(), property.clone());
+          }
+          CssRule::LayerBlock(layer) => collect(&layer.rules, registered),
+          _ => {}
+        }
+      }
+    }
+
+    let mut registered = HashMap::new();
+    collect(rules, &mut registered);
+    if registered.is_empty() {
+      return None;
+    }
+
+    let mut initial_values = HashMap::new();
+    for (name, rule) in &registered {
+      let mut tokens = Vec::new();
+      if let Some(initial_value) = &rule.initial_value {
+        if initial_value_tokens(initial_value, &mut tokens) {
+          initial_values.insert(name.clone(), TokenList(tokens));
+        }
+      }
+    }
+
+    let mut inlined_values = HashMap::new();
+    let mut references = CustomPropertyReferences::default();
+    if inline && rules.collect_custom_property_references(&mut references, false) {
+      for (name, value) in &initial_values {
+        if !references.dependencies.contains_key(name) {
+          inlined_values.insert(name.clone(), value.clone());
+        }
+      }
+    }
+
+    Some(RegisteredProperties {
+      rules: registered,
+      initial_values,
+      inlined_values,
+    })
+  }
+
+  /// Validates the declarations in a block against the registered properties, and adds warnings
+  /// for values that do not match the registered syntax.
+  pub fn validate(
+    &self,
+    declarations: &DeclarationBlock<'i>,
+    loc: Location,
+    warnings: &mut Vec<ErrorWithLocation<ParserError<'i>>>,
+  ) {
+    for (property, _) in declarations.iter() {
+      match property {
+        Property::Custom(CustomProperty {
+          name: CustomPropertyName::Custom(name),
+          value,
+        }) => {
+          if let Some(rule) = self.rules.get(name.0.as_ref()) {
+            if !matches_syntax(&rule.syntax, value) {
+              warnings.push(ErrorWithLocation {
+                kind: ParserError::InvalidRegisteredPropertyValue(name.0.clone()),
+                loc,
+              });
+            }
+          }
+        }
+        Property::Unparsed(UnparsedProperty { property_id, value }) => {
+          let mut names = HashSet::new();
+          value.collect_variable_references(&mut names);
+          if !names.iter().any(|name| self.initial_values.contains_key(name)) {
+            continue;
+          }
+
+          // Substitute the initial values of the registered properties, and check whether the
+          // result is valid for the property. Values containing other references can't be checked.
+          let mut value = value.clone();
+          value.inline_variables(&self.initial_values);
+          let mut remaining = HashSet::new();
+          value.collect_variable_references(&mut remaining);
+          if !remaining.is_empty() {
+            continue;
+          }
+
+          let mut css = String::new();
+          let mut printer = Printer::new(&mut css, PrinterOptions::default());
+          if value.to_css(&mut printer, false).is_err() {
+            continue;
+          }
+
+          let is_valid = matches!(
+            Property::parse_string(property_id.clone(), &css, ParserOptions::default()),
+            Ok(property) if !matches!(property, Property::Unparsed(..))
+          );
+          if !is_valid {
+            let mut names: Vec<_> = names
+              .into_iter()
+              .filter(|name| self.initial_values.contains_key(name))
+              .collect();
+            names.sort();
+            for name in names {
+              warnings.push(ErrorWithLocation {
+                kind: ParserError::IncompatibleRegisteredProperty {
+                  name: name.into(),
+                  property: property_id.name().to_owned().into(),
+                },
+                loc,
+              });
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  /// Replaces `var()` references to registered properties that are never declared with their initial value.
+  pub fn inline(&self, declarations: &mut DeclarationBlock<'i>) {
+    if self.inlined_values.is_empty() {
+      return;
+    }
+
+    for property in declarations
+      .declarations
+      .iter_mut()
+      .chain(declarations.important_declarations.iter_mut())
+    {
+      match property {
+        Property::Custom(CustomProperty { value, .. }) | Property::Unparsed(UnparsedProperty { value, .. }) => {
+          value.inline_variables(&self.inlined_values)
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Returns whether a custom property value matches a registered syntax. Values containing
+/// `var()` or `env()` references, and CSS-wide keywords, are always considered to match.
+fn matches_syntax(syntax: &SyntaxString, value: &TokenList) -> bool {
+  if *syntax == SyntaxString::Universal
+    || value
+      .0
+      .iter()
+      .any(|token| matches!(token, TokenOrValue::Var(..) | TokenOrValue::Env(..)))
+  {
+    return true;
+  }
+
+  let mut css = String::new();
+  let mut printer = Printer::new(&mut css, PrinterOptions::default());
+  if value.to_css(&mut printer, true).is_err() {
+    return true;
+  }
+
+  let css = css.trim();
+  if matches!(
+    css.to_ascii_lowercase().as_str(),
+    "initial" | "inherit" | "unset" | "revert" | "revert-layer"
+  ) {
+    return true;
+  }
+
+  let mut input = ParserInput::new(css);
+  let mut parser = Parser::new(&mut input);
+  let result = parser.parse_entirely(|input| syntax.parse_value(input));
+  result.is_ok()
+}
+
+/// Converts a parsed initial value to a list of tokens, as substituted for `var()` references.
+/// Returns `false` if the value cannot be represented as tokens, e.g. `calc()` expressions.
+fn initial_value_tokens<'i>(component: &ParsedComponent<'i>, tokens: &mut Vec<TokenOrValue<'i>>) -> bool {
+  let token = match component {
+    ParsedComponent::Length(Length::Value(length))
+    | ParsedComponent::LengthPercentage(DimensionPercentage::Dimension(length)) => {
+      TokenOrValue::Length(length.clone())
+    }
+    ParsedComponent::Percentage(percentage)
+    | ParsedComponent::LengthPercentage(DimensionPercentage::Percentage(percentage)) => {
+      TokenOrValue::Token(custom::Token::Percentage {
+        has_sign: percentage.0 < 0.0,
+        unit_value: percentage.0,
+        int_value: None,
+      })
+    }
+    ParsedComponent::Number(value) => TokenOrValue::Token(custom::Token::Number {
+      has_sign: *value < 0.0,
+      value: *value,
+      int_value: None,
+    }),
+    ParsedComponent::Integer(value) => TokenOrValue::Token(custom::Token::Number {
+      has_sign: *value < 0,
+      value: *value as f32,
+      int_value: Some(*value),
+    }),
+    ParsedComponent::Color(color) => TokenOrValue::Color(color.clone()),
+    ParsedComponent::Url(url) => TokenOrValue::Url(url.clone()),
+    ParsedComponent::Angle(angle) => TokenOrValue::Angle(angle.clone()),
+    ParsedComponent::Time(time) => TokenOrValue::Time(time.clone()),
+    ParsedComponent::Resolution(resolution) => TokenOrValue::Resolution(resolution.clone()),
+    ParsedComponent::CustomIdent(ident) => TokenOrValue::Token(custom::Token::Ident(ident.0.clone())),
+    ParsedComponent::Literal(ident) => TokenOrValue::Token(custom::Token::Ident(ident.0.clone())),
+    ParsedComponent::Token(token) => TokenOrValue::Token(token.clone()),
+    ParsedComponent::Repeated { components, multiplier } => {
+      for (i, component) in components.iter().enumerate() {
+        if i > 0 {
+          tokens.push(TokenOrValue::Token(match multiplier {
+            Multiplier::Comma => custom::Token::Comma,
+            _ => custom::Token::WhiteSpace(" ".into()),
+          }));
+        }
+        if !initial_value_tokens(component, tokens) {
+          return false;
+        }
+      }
+      return true;
+    }
+    _ => return false,
+  };
+
+  tokens.push(token);
+  true
+}
@@ -3,16 +3,19 @@
 use std::ops::Range;
 
 use super::Location;
-use super::MinifyContext;
+use super::{sort_declarations, MinifyContext};
 use crate::compat::Feature;
 use crate::context::DeclarationContext;
 use crate::declaration::DeclarationBlock;
-use crate::error::ParserError;
+use crate::error::{ErrorWithLocation, ParserError};
 use crate::error::{MinifyError, PrinterError, PrinterErrorKind};
 use crate::parser::DefaultAtRule;
 use crate::printer::Printer;
+use crate::properties::custom::{CustomProperty, CustomPropertyName};
+use crate::properties::Property;
 use crate::rules::{CssRuleList, StyleContext, ToCssWithContext};
-use crate::selector::{is_compatible, is_unused, SelectorList};
+use crate::selector::{is_compatible, is_impossible, is_unused, remove_redundant_selectors, SelectorList};
+use crate::stylesheet::PrinterOptions;
 use crate::targets::Browsers;
 use crate::traits::ToCss;
 use crate::vendor_prefix::VendorPrefix;
@@ -61,18 +64,63 @@ impl<'i, T> StyleRule<'i, T> {
           return Ok(true);
         }
 
-        self.declarations.declarations.clear();
-        self.declarations.important_declarations.clear();
+        self.declarations = DeclarationBlock::new();
         unused = true;
       }
     }
 
+    if self.selectors.0.iter().any(is_impossible) {
+      for selector in std::mem::take(&mut self.selectors.0) {
+        if !is_impossible(&selector) {
+          self.selectors.0.push(selector);
+          continue;
+        }
+
+        if let Ok(selector) = SelectorList::from(selector).to_css_string(PrinterOptions::default()) {
+          context.handler_context.warnings.push(ErrorWithLocation {
+            kind: ParserError::ImpossibleSelector(selector.into()),
+            loc: self.loc,
+          });
+        }
+      }
+
+      if self.selectors.0.is_empty() {
+        return Ok(true);
+      }
+    }
+
+    // Nested rules reference the whole selector list via `&`, so it must be preserved.
+    if self.rules.0.is_empty() {
+      remove_redundant_selectors(&mut self.selectors.0);
+    }
+
+    if let Some(used_custom_properties) = context.used_custom_properties {
+      let is_used = |property: &Property| match property {
+        Property::Custom(CustomProperty {
+          name: CustomPropertyName::Custom(name),
+          ..
+        }) => used_custom_properties.contains(name.0.as_ref()),
+        _ => true,
+      };
+      self.declarations.retain(|property, _| is_used(property));
+    }
+
+    if let Some(registered_properties) = context.registered_properties {
+      registered_properties.validate(&self.declarations, self.loc, &mut context.handler_context.warnings);
+      registered_properties.inline(&mut self.declarations);
+    }
+
     context.handler_context.context = DeclarationContext::StyleRule;
+    context.handler_context.loc = self.loc;
     self
       .declarations
       .minify(context.handler, context.important_handler, context.handler_context);
     context.handler_context.context = DeclarationContext::None;
 
+    if context.canonical_order {
+      sort_declarations(&mut self.declarations);
+    }
+
     if !self.rules.0.is_empty() {
       self.rules.minify(context, unused)?;
       if unused && self.rules.0.is_empty() {
@@ -226,7 +274,7 @@ impl<'a, 'i, T: ToCss> StyleRule<'i, T> {
       let mut i = 0;
       macro_rules! write {
         ($decls: ident, $important: literal) => {
-          for decl in &self.declarations.$decls {
+          for (index, decl) in self.declarations.$decls.iter().enumerate() {
             // The CSS modules `composes` property is handled specially, and omitted during printing.
             // We need to add the classes it references to the list for the selectors in this rule.
             if let crate::properties::Property::Composes(composes) = &decl {
@@ -236,13 +284,16 @@ impl<'a, 'i, T: ToCss> StyleRule<'i, T> {
 
               if let Some(css_module) = &mut dest.css_module {
                 css_module
-                  .handle_composes(&self.selectors, &composes, self.loc.source_index)
+                  .handle_composes(&self.selectors, &composes, self.loc)
                   .map_err(|e| dest.error(e, composes.loc))?;
                 continue;
               }
             }
 
+            self.declarations.write_comments(dest, Some(index), $important)?;
             dest.newline()?;
+            #[cfg(feature = "sourcemap")]
+            self.declarations.add_mapping(dest, index, $important);
             decl.to_css(dest, $important)?;
             if i != len - 1 || !dest.minify {
               dest.write_char(';')?;
@@ -255,6 +306,7 @@ impl<'a, 'i, T: ToCss> StyleRule<'i, T> {
 
       write!(declarations, false);
       write!(important_declarations, true);
+      self.declarations.write_comments(dest, None, false)?;
     }
 
     macro_rules! newline {
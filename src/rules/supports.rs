@@ -40,11 +40,42 @@ impl<'i, T> SupportsRule<'i, T> {
     context: &mut MinifyContext<'_, 'i>,
     parent_is_unused: bool,
   ) -> Result<(), MinifyError> {
+    // Nested rules are simplified based on this condition, unless the rule structure is preserved.
+    let is_parent = !context.preserve_rule_structure;
+    if is_parent {
+      context.parent_supports.push(self.condition.clone());
+    }
+
     if let Some(targets) = context.targets {
       self.condition.set_prefixes_for_targets(targets)
     }
 
-    self.rules.minify(context, parent_is_unused)
+    let res = self.rules.minify(context, parent_is_unused);
+    if is_parent {
+      context.parent_supports.pop();
+    }
+    res
+  }
+
+  /// Returns whether the condition is always true within the given parent conditions.
+  pub(crate) fn always_matches(&self, parents: &[SupportsCondition<'i>]) -> bool {
+    self
+      .condition
+      .conjuncts()
+      .iter()
+      .all(|condition| parents.iter().any(|parent| parent.conjuncts().contains(condition)))
+  }
+
+  /// Returns whether the condition is always false within the given parent conditions.
+  pub(crate) fn never_matches(&self, parents: &[SupportsCondition<'i>]) -> bool {
+    self.condition.conjuncts().iter().any(|condition| {
+      parents.iter().any(|parent| {
+        parent.conjuncts().iter().any(|parent| match (parent, condition) {
+          (SupportsCondition::Not(a), b) | (b, SupportsCondition::Not(a)) => **a == **b,
+          _ => false,
+        })
+      })
+    })
   }
 }
 
@@ -137,6 +168,14 @@ impl<'i> SupportsCondition<'i> {
     }
   }
 
+  /// Returns the conditions that must all be true for this condition to be true.
+  pub(crate) fn conjuncts(&self) -> Vec<&SupportsCondition<'i>> {
+    match self {
+      SupportsCondition::And(items) => items.iter().collect(),
+      _ => vec![self],
+    }
+  }
+
   fn set_prefixes_for_targets(&mut self, targets: &Browsers) {
     match self {
       SupportsCondition::Not(cond) => cond.set_prefixes_for_targets(targets),
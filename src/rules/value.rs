@@ -0,0 +1,397 @@
+//! The CSS modules `@value` rule.
+
+use super::Location;
+use crate::css_modules::value_placeholder;
+use crate::error::{ParserError, PrinterError};
+use crate::media_query::{MediaList, MediaQuery};
+use crate::printer::{Printer, PrinterOptions};
+use crate::properties::custom::{self, CustomProperty, TokenList, TokenOrValue, UnparsedProperty};
+use crate::properties::{Property, PropertyId};
+use crate::stylesheet::ParserOptions;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::CustomIdent;
+use crate::values::string::CowArcStr;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+use std::collections::HashMap;
+
+/// A CSS modules [@value](https://github.com/css-modules/postcss-modules-values) rule,
+/// which defines a named value or imports values from another module.
+///
+/// Values are parsed when the `values` option of the CSS modules [Config](crate::css_modules::Config)
+/// is enabled. References to them in declarations and media queries are substituted while parsing,
+/// and the rule itself is not printed. Instead, the values are added to the exports of the module.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ValueRule<'i> {
+  /// The value definition or import.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub value: ModuleValue<'i>,
+  /// The location of the rule in the source file.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub loc: Location,
+}
+
+/// A value defined or imported by a [@value](ValueRule) rule.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ModuleValue<'i> {
+  /// A value definition, e.g. `@value primary: #bf4040`.
+  Definition {
+    /// The name of the value.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    name: CustomIdent<'i>,
+    /// The value, with references to previously defined values substituted.
+    value: TokenList<'i>,
+  },
+  /// An import of values from another module, e.g. `@value primary, secondary as accent from "./colors.css"`.
+  Import {
+    /// The imported values.
+    names: Vec<ValueImport<'i>>,
+    /// The specifier of the module to import from.
+    specifier: CowArcStr<'i>,
+  },
+}
+
+/// A value imported by a [@value](ValueRule) rule.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ValueImport<'i> {
+  /// The name of the value in the imported module.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub name: CustomIdent<'i>,
+  /// The local name of the value, if it is renamed using `as`.
+  pub alias: Option<CustomIdent<'i>>,
+}
+
+impl<'i> ValueImport<'i> {
+  /// Returns the local name of the imported value.
+  pub fn local_name(&self) -> &CustomIdent<'i> {
+    self.alias.as_ref().unwrap_or(&self.name)
+  }
+}
+
+impl<'i> ValueRule<'i> {
+  /// Adds the values to the CSS module exports. Imported values are exported as placeholders,
+  /// which are returned as references to be replaced once the imported module is compiled.
+  pub(crate) fn add_exports<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let css_module = match &mut dest.css_module {
+      Some(css_module) => css_module,
+      None => return Ok(()),
+    };
+
+    match &self.value {
+      ModuleValue::Definition { name, value } => {
+        let mut s = String::new();
+        let mut printer = Printer::new(&mut s, PrinterOptions::default());
+        value.to_css(&mut printer, false)?;
+        css_module.add_value(&name.0, s, self.loc);
+      }
+      ModuleValue::Import { names, specifier } => {
+        for import in names {
+          css_module.add_value_import(&import.local_name().0, &import.name.0, specifier, self.loc);
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<'i> ToCss for ValueRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    #[cfg(feature = "sourcemap")]
+    dest.add_mapping(self.loc);
+    dest.write_str("@value ")?;
+    match &self.value {
+      ModuleValue::Definition { name, value } => {
+        dest.write_ident(&name.0)?;
+        dest.write_char(':')?;
+        dest.whitespace()?;
+        value.to_css(dest, false)?;
+      }
+      ModuleValue::Import { names, specifier } => {
+        let mut first = true;
+        for import in names {
+          if first {
+            first = false;
+          } else {
+            dest.delim(',', false)?;
+          }
+          dest.write_ident(&import.name.0)?;
+          if let Some(alias) = &import.alias {
+            dest.write_str(" as ")?;
+            dest.write_ident(&alias.0)?;
+          }
+        }
+        dest.write_str(" from ")?;
+        dest.write_string(specifier)?;
+      }
+    }
+    dest.write_char(';')
+  }
+}
+
+/// A value that references to are substituted with.
+#[derive(Debug)]
+struct ResolvedValue<'i> {
+  tokens: TokenList<'i>,
+  /// The source text of the value, if it can be parsed with the syntax of the place
+  /// it is referenced from. This is not known for imported values.
+  source: Option<&'i str>,
+}
+
+/// The values defined by the `@value` rules of a style sheet, used to substitute
+/// references to them while parsing.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleValues<'i> {
+  values: HashMap<CowArcStr<'i>, ResolvedValue<'i>>,
+}
+
+impl<'i> ModuleValues<'i> {
+  /// Parses the prelude of a `@value` rule, returning the value and its source text.
+  pub fn parse_rule<'t, T>(
+    &self,
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions<'_, 'i, T>,
+  ) -> Result<(ModuleValue<'i>, Option<&'i str>), ParseError<'i, ParserError<'i>>> {
+    if let Ok(import) = input.try_parse(|input| self.parse_import(input)) {
+      return Ok((import, None));
+    }
+
+    let name = CustomIdent::parse(input)?;
+    let _ = input.try_parse(|input| input.expect_colon());
+    let start = input.position();
+    let mut value = TokenList::parse(input, options, 0)?;
+    let mut source = Some(input.slice_from(start).trim());
+
+    // A value that is itself a reference is resolved to the referenced value, and can be
+    // parsed from the referenced value's source.
+    if let Some(resolved) = self.single_reference(&value) {
+      source = resolved.source;
+      value = resolved.tokens.clone();
+    } else if self.substitute(&mut value) {
+      source = None;
+    }
+
+    Ok((ModuleValue::Definition { name, value }, source))
+  }
+
+  fn parse_import<'t>(
+    &self,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<ModuleValue<'i>, ParseError<'i, ParserError<'i>>> {
+    let mut names = Vec::new();
+    loop {
+      let name = CustomIdent::parse(input)?;
+      let alias = if input.try_parse(|input| input.expect_ident_matching("as")).is_ok() {
+        Some(CustomIdent::parse(input)?)
+      } else {
+        None
+      };
+      names.push(ValueImport { name, alias });
+      if input.try_parse(|input| input.expect_comma()).is_err() {
+        break;
+      }
+    }
+
+    input.expect_ident_matching("from")?;
+    let specifier = if let Ok(specifier) = input.try_parse(|input| input.expect_string_cloned()) {
+      specifier.into()
+    } else {
+      // The specifier may also be given by a previously defined value, e.g. `@value colors: "./colors.css"`.
+      let location = input.current_source_location();
+      let name = input.expect_ident_cloned()?;
+      match self.values.get(&CowArcStr::from(name.clone())).map(|value| &value.tokens.0[..]) {
+        Some([TokenOrValue::Token(custom::Token::String(specifier))]) => specifier.clone(),
+        _ => return Err(location.new_unexpected_token_error(Token::Ident(name))),
+      }
+    };
+
+    input.expect_exhausted()?;
+    Ok(ModuleValue::Import { names, specifier })
+  }
+
+  /// Defines the values of a parsed `@value` rule.
+  pub fn insert(&mut self, value: &ModuleValue<'i>, source: Option<&'i str>, filename: &str) {
+    match value {
+      ModuleValue::Definition { name, value } => {
+        self.values.insert(
+          name.0.clone(),
+          ResolvedValue {
+            tokens: value.clone(),
+            source,
+          },
+        );
+      }
+      ModuleValue::Import { names, specifier } => {
+        for import in names {
+          let placeholder = value_placeholder(filename, &import.name.0, specifier);
+          self.values.insert(
+            import.local_name().0.clone(),
+            ResolvedValue {
+              tokens: TokenList(vec![TokenOrValue::Token(custom::Token::Ident(placeholder.into()))]),
+              source: None,
+            },
+          );
+        }
+      }
+    }
+  }
+
+  fn single_reference(&self, tokens: &TokenList<'i>) -> Option<&ResolvedValue<'i>> {
+    match &tokens.0[..] {
+      [TokenOrValue::Token(custom::Token::Ident(name))] => self.values.get(name),
+      _ => None,
+    }
+  }
+
+  /// Replaces references to values in a token list, including within function arguments.
+  /// Returns whether any references were found.
+  fn substitute(&self, tokens: &mut TokenList<'i>) -> bool {
+    let mut substituted = false;
+    let mut result = Vec::with_capacity(tokens.0.len());
+    for mut token in std::mem::take(&mut tokens.0) {
+      if let TokenOrValue::Token(custom::Token::Ident(name)) = &token {
+        if let Some(value) = self.values.get(name) {
+          result.extend(value.tokens.0.iter().cloned());
+          substituted = true;
+          continue;
+        }
+      }
+
+      if let TokenOrValue::Function(function) = &mut token {
+        substituted |= self.substitute(&mut function.arguments);
+      }
+      result.push(token);
+    }
+
+    tokens.0 = result;
+    substituted
+  }
+
+  /// Parses a declaration value that references values. Returns `None` without consuming
+  /// any input if the value does not reference any values.
+  pub fn parse_property<'t, T>(
+    &self,
+    property_id: &PropertyId<'i>,
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions<'_, 'i, T>,
+  ) -> Result<Option<Property<'i>>, ParseError<'i, ParserError<'i>>> {
+    // Names in `composes` refer to classes rather than values.
+    if self.values.is_empty() || matches!(property_id, PropertyId::Composes) {
+      return Ok(None);
+    }
+
+    let state = input.state();
+    let mut tokens = match TokenList::parse(input, options, 0) {
+      Ok(tokens) => tokens,
+      Err(_) => {
+        input.reset(&state);
+        return Ok(None);
+      }
+    };
+
+    // When the whole value is a single reference, parse the value's source so that it is
+    // validated and minified like any other value of the property.
+    if let Some(ResolvedValue {
+      source: Some(source), ..
+    }) = self.single_reference(&tokens)
+    {
+      let mut source_input = ParserInput::new(source);
+      let mut parser = Parser::new(&mut source_input);
+      return Property::parse(property_id.clone(), &mut parser, options).map(Some);
+    }
+
+    if !self.substitute(&mut tokens) {
+      input.reset(&state);
+      return Ok(None);
+    }
+
+    let property = match property_id {
+      PropertyId::Custom(name) => Property::Custom(CustomProperty {
+        name: name.clone(),
+        value: tokens,
+      }),
+      _ => Property::Unparsed(UnparsedProperty {
+        property_id: property_id.clone(),
+        value: tokens,
+      }),
+    };
+    Ok(Some(property))
+  }
+
+  /// Parses a media query list, where each query may be replaced by a reference to a value,
+  /// e.g. `@media small` with `@value small: (max-width: 599px)`.
+  pub fn parse_media_list<'t>(
+    &self,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<MediaList<'i>, ParseError<'i, ParserError<'i>>> {
+    if self.values.is_empty() {
+      return MediaList::parse(input);
+    }
+
+    let mut media_queries = vec![];
+    loop {
+      let queries = input.parse_until_before(Delimiter::Comma, |input| {
+        if let Ok(source) = input.try_parse(|input| self.parse_media_reference(input)) {
+          let mut source_input = ParserInput::new(source);
+          let mut parser = Parser::new(&mut source_input);
+          return Ok(MediaList::parse(&mut parser)?.media_queries);
+        }
+
+        Ok(vec![MediaQuery::parse(input)?])
+      });
+
+      match queries {
+        Ok(queries) => media_queries.extend(queries),
+        Err(err) => match err.kind {
+          ParseErrorKind::Basic(BasicParseErrorKind::EndOfInput) => break,
+          _ => return Err(err),
+        },
+      }
+
+      match input.next() {
+        Ok(&Token::Comma) => {}
+        Ok(_) => unreachable!(),
+        Err(_) => break,
+      }
+    }
+
+    Ok(MediaList { media_queries })
+  }
+
+  fn parse_media_reference<'t>(
+    &self,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<&'i str, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    let name = input.expect_ident_cloned()?;
+    input.expect_exhausted()?;
+    match self.values.get(&CowArcStr::from(name.clone())) {
+      Some(ResolvedValue {
+        source: Some(source), ..
+      }) => Ok(source),
+      _ => Err(location.new_unexpected_token_error(Token::Ident(name))),
+    }
+  }
+}
@@ -0,0 +1,188 @@
+//! The `@view-transition` rule.
+
+use super::Location;
+use crate::error::{ParserError, PrinterError};
+use crate::macros::enum_property;
+use crate::printer::Printer;
+use crate::properties::custom::CustomProperty;
+use crate::stylesheet::ParserOptions;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::NoneOrCustomIdentList;
+#[cfg(feature = "visitor")]
+use crate::visitor::Visit;
+use cssparser::*;
+
+/// A [@view-transition](https://drafts.csswg.org/css-view-transitions-2/#view-transition-rule) rule.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ViewTransitionRule<'i> {
+  /// Declarations in the `@view-transition` rule.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub properties: Vec<ViewTransitionProperty<'i>>,
+  /// The location of the rule in the source file.
+  #[cfg_attr(feature = "visitor", skip_visit)]
+  pub loc: Location,
+}
+
+/// A descriptor within a `@view-transition` rule.
+///
+///  See [ViewTransitionRule](ViewTransitionRule).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ViewTransitionProperty<'i> {
+  /// The `navigation` descriptor.
+  Navigation(Navigation),
+  /// The `types` descriptor.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Types(NoneOrCustomIdentList<'i>),
+  /// An unknown or unsupported descriptor.
+  Custom(CustomProperty<'i>),
+}
+
+enum_property! {
+  /// A value for the [navigation](https://drafts.csswg.org/css-view-transitions-2/#descdef-view-transition-navigation)
+  /// descriptor in a `@view-transition` rule.
+  pub enum Navigation {
+    /// There will be no transition.
+    None,
+    /// The transition will be enabled if the navigation is same-origin.
+    Auto,
+  }
+}
+
+pub(crate) struct ViewTransitionDeclarationParser;
+
+impl<'i> cssparser::DeclarationParser<'i> for ViewTransitionDeclarationParser {
+  type Declaration = ViewTransitionProperty<'i>;
+  type Error = ParserError<'i>;
+
+  fn parse_value<'t>(
+    &mut self,
+    name: CowRcStr<'i>,
+    input: &mut cssparser::Parser<'i, 't>,
+  ) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
+    let state = input.state();
+    match_ignore_ascii_case! { &name,
+      "navigation" => {
+        // https://drafts.csswg.org/css-view-transitions-2/#view-transition-navigation-descriptor
+        if let Ok(navigation) = Navigation::parse(input) {
+          return Ok(ViewTransitionProperty::Navigation(navigation))
+        }
+      },
+      "types" => {
+        // https://drafts.csswg.org/css-view-transitions-2/#types-cross-doc
+        if let Ok(types) = NoneOrCustomIdentList::parse(input) {
+          return Ok(ViewTransitionProperty::Types(types))
+        }
+      },
+      _ => {}
+    }
+
+    input.reset(&state);
+    return Ok(ViewTransitionProperty::Custom(CustomProperty::parse(
+      name.into(),
+      input,
+      &ParserOptions::default(),
+    )?));
+  }
+}
+
+/// Default methods reject all at rules.
+impl<'i> AtRuleParser<'i> for ViewTransitionDeclarationParser {
+  type Prelude = ();
+  type AtRule = ViewTransitionProperty<'i>;
+  type Error = ParserError<'i>;
+}
+
+impl<'i> ViewTransitionRule<'i> {
+  pub(crate) fn parse<'t>(
+    input: &mut Parser<'i, 't>,
+    loc: Location,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let properties = DeclarationListParser::new(input, ViewTransitionDeclarationParser)
+      .filter_map(Result::ok)
+      .collect();
+
+    Ok(ViewTransitionRule { properties, loc })
+  }
+}
+
+impl<'i> ToCss for ViewTransitionRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    #[cfg(feature = "sourcemap")]
+    dest.add_mapping(self.loc);
+    dest.write_str("@view-transition")?;
+    dest.whitespace()?;
+    dest.write_char('{')?;
+    dest.indent();
+    let len = self.properties.len();
+    for (i, prop) in self.properties.iter().enumerate() {
+      dest.newline()?;
+      prop.to_css(dest)?;
+      if i != len - 1 || !dest.minify {
+        dest.write_char(';')?;
+      }
+    }
+    dest.dedent();
+    dest.newline()?;
+    dest.write_char('}')
+  }
+}
+
+impl<'i> ToCss for ViewTransitionProperty<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    macro_rules! property {
+      ($prop: literal, $value: expr) => {{
+        dest.write_str($prop)?;
+        dest.delim(':', false)?;
+        $value.to_css(dest)
+      }};
+    }
+
+    match self {
+      ViewTransitionProperty::Navigation(navigation) => property!("navigation", navigation),
+      ViewTransitionProperty::Types(types) => {
+        dest.write_str("types")?;
+        dest.delim(':', false)?;
+        // Transition types are matched by :active-view-transition-type() in the document,
+        // so they are not scoped like other idents in CSS modules.
+        match types {
+          NoneOrCustomIdentList::None => dest.write_str("none"),
+          NoneOrCustomIdentList::Idents(idents) => {
+            let mut first = true;
+            for ident in idents {
+              if first {
+                first = false;
+              } else {
+                dest.write_char(' ')?;
+              }
+              serialize_identifier(&ident.0, dest)?;
+            }
+            Ok(())
+          }
+        }
+      }
+      ViewTransitionProperty::Custom(custom) => {
+        dest.write_str(custom.name.as_ref())?;
+        dest.delim(':', false)?;
+        custom.value.to_css(dest, true)
+      }
+    }
+  }
+}
@@ -1,15 +1,18 @@
 //! CSS selectors.
 
 use crate::compat::Feature;
+use crate::css_modules::NameKind;
 use crate::error::{ParserError, PrinterError};
 use crate::parser::DefaultAtRule;
 use crate::printer::Printer;
 use crate::properties::custom::TokenList;
 use crate::rules::{StyleContext, ToCssWithContext};
+use crate::stylesheet::PurgeContent;
 use crate::stylesheet::{ParserOptions, PrinterOptions};
 use crate::targets::Browsers;
 use crate::traits::{Parse, ToCss};
-use crate::values::ident::Ident;
+use crate::unused_symbols::{SymbolKind, UnusedSymbols};
+use crate::values::ident::{CustomIdent, Ident};
 use crate::values::string::CSSString;
 use crate::vendor_prefix::VendorPrefix;
 #[cfg(feature = "visitor")]
@@ -21,8 +24,7 @@ use parcel_selectors::{
   attr::{AttrSelectorOperator, ParsedAttrSelectorOperation, ParsedCaseSensitivity},
   parser::SelectorImpl,
 };
-use std::collections::HashMap;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 #[cfg(feature = "serde")]
@@ -230,6 +232,7 @@ impl<'a, 'o, 'i, T> parcel_selectors::parser::Parser<'i> for SelectorParser<'a,
       "first-letter" => FirstLetter,
       "cue" => Cue,
       "cue-region" => CueRegion,
+      "view-transition" => ViewTransition,
       "selection" => Selection(VendorPrefix::None),
       "-moz-selection" => Selection(VendorPrefix::Moz),
       "placeholder" => Placeholder(VendorPrefix::None),
@@ -271,6 +274,10 @@ impl<'a, 'o, 'i, T> parcel_selectors::parser::Parser<'i> for SelectorParser<'a,
     let pseudo_element = match_ignore_ascii_case! { &name,
       "cue" => CueFunction { selector: Box::new(Selector::parse(self, arguments)?) },
       "cue-region" => CueRegionFunction(Box::new(Selector::parse(self, arguments)?)),
+      "view-transition-group" => ViewTransitionGroup { part: ViewTransitionPartSelector::parse(arguments)? },
+      "view-transition-image-pair" => ViewTransitionImagePair { part: ViewTransitionPartSelector::parse(arguments)? },
+      "view-transition-old" => ViewTransitionOld { part: ViewTransitionPartSelector::parse(arguments)? },
+      "view-transition-new" => ViewTransitionNew { part: ViewTransitionPartSelector::parse(arguments)? },
       _ => {
         if !name.starts_with('-') {
           self.options.warn(arguments.new_custom_error(SelectorParseErrorKind::UnsupportedPseudoClassOrElement(name.clone())));
@@ -616,7 +623,7 @@ impl<'a, 'i, T> ToCssWithContext<'a, 'i, T> for PseudoClass<'i> {
 
         if let Some(class) = class {
           dest.write_char('.')?;
-          dest.write_ident(class)
+          dest.write_scoped_ident(class, NameKind::Class)
         } else {
           dest.write_str($s)
         }
@@ -758,20 +765,25 @@ impl<'i> PseudoClass<'i> {
     }
   }
 
-  pub(crate) fn get_necessary_prefixes(&self, targets: Browsers) -> VendorPrefix {
+  fn prefix_feature(&self) -> Option<crate::prefixes::Feature> {
     use crate::prefixes::Feature;
     use PseudoClass::*;
-    let feature = match self {
-      Fullscreen(p) if *p == VendorPrefix::None => Feature::PseudoClassFullscreen,
-      AnyLink(p) if *p == VendorPrefix::None => Feature::PseudoClassAnyLink,
-      ReadOnly(p) if *p == VendorPrefix::None => Feature::PseudoClassReadOnly,
-      ReadWrite(p) if *p == VendorPrefix::None => Feature::PseudoClassReadWrite,
-      PlaceholderShown(p) if *p == VendorPrefix::None => Feature::PseudoClassPlaceholderShown,
-      Autofill(p) if *p == VendorPrefix::None => Feature::PseudoClassAutofill,
-      _ => return VendorPrefix::empty(),
-    };
+    Some(match self {
+      Fullscreen(_) => Feature::PseudoClassFullscreen,
+      AnyLink(_) => Feature::PseudoClassAnyLink,
+      ReadOnly(_) => Feature::PseudoClassReadOnly,
+      ReadWrite(_) => Feature::PseudoClassReadWrite,
+      PlaceholderShown(_) => Feature::PseudoClassPlaceholderShown,
+      Autofill(_) => Feature::PseudoClassAutofill,
+      _ => return None,
+    })
+  }
 
-    feature.prefixes_for(targets)
+  pub(crate) fn get_necessary_prefixes(&self, targets: Browsers) -> VendorPrefix {
+    match self.prefix_feature() {
+      Some(feature) if self.get_prefix() == VendorPrefix::None => feature.prefixes_for(targets),
+      _ => VendorPrefix::empty(),
+    }
   }
 }
 
@@ -823,6 +835,29 @@ pub enum PseudoElement<'i> {
   },
   /// The [::cue-region()](https://w3c.github.io/webvtt/#cue-region-selector) functional pseudo element.
   CueRegionFunction(Box<Selector<'i>>),
+  /// The [::view-transition](https://drafts.csswg.org/css-view-transitions-1/#selectordef-view-transition) pseudo element.
+  ViewTransition,
+  /// The [::view-transition-group()](https://drafts.csswg.org/css-view-transitions-1/#selectordef-view-transition-group-pt-name-selector) functional pseudo element.
+  ViewTransitionGroup {
+    /// The view transition parts to match.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    part: ViewTransitionPartSelector<'i>,
+  },
+  /// The [::view-transition-image-pair()](https://drafts.csswg.org/css-view-transitions-1/#selectordef-view-transition-image-pair-pt-name-selector) functional pseudo element.
+  ViewTransitionImagePair {
+    /// The view transition parts to match.
+    part: ViewTransitionPartSelector<'i>,
+  },
+  /// The [::view-transition-old()](https://drafts.csswg.org/css-view-transitions-1/#selectordef-view-transition-old-pt-name-selector) functional pseudo element.
+  ViewTransitionOld {
+    /// The view transition parts to match.
+    part: ViewTransitionPartSelector<'i>,
+  },
+  /// The [::view-transition-new()](https://drafts.csswg.org/css-view-transitions-1/#selectordef-view-transition-new-pt-name-selector) functional pseudo element.
+  ViewTransitionNew {
+    /// The view transition parts to match.
+    part: ViewTransitionPartSelector<'i>,
+  },
   /// An unknown pseudo element.
   Custom {
     /// The name of the pseudo element.
@@ -838,6 +873,90 @@ pub enum PseudoElement<'i> {
   },
 }
 
+/// A [view transition part name](https://drafts.csswg.org/css-view-transitions-1/#typedef-pt-name-selector).
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum ViewTransitionPartName<'i> {
+  /// The `*` wildcard, matching all view transition names.
+  All,
+  /// A view transition name.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Name(CustomIdent<'i>),
+}
+
+/// A [view transition part selector](https://drafts.csswg.org/css-view-transitions-2/#typedef-pt-name-and-class-selector),
+/// used as the argument of the `::view-transition-*()` pseudo elements.
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct ViewTransitionPartSelector<'i> {
+  /// The view transition name to match. If omitted, all names are matched.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub name: Option<ViewTransitionPartName<'i>>,
+  /// The view transition classes that the part must have.
+  pub classes: Vec<CustomIdent<'i>>,
+}
+
+impl<'i> Parse<'i> for ViewTransitionPartSelector<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let name = if input.try_parse(|input| input.expect_delim('*')).is_ok() {
+      Some(ViewTransitionPartName::All)
+    } else {
+      input.try_parse(CustomIdent::parse).ok().map(ViewTransitionPartName::Name)
+    };
+
+    let mut classes = Vec::new();
+    while let Ok(class) = input.try_parse(|input| -> Result<CustomIdent<'i>, ParseError<'i, ParserError<'i>>> {
+      // Classes may not be separated by whitespace.
+      let location = input.current_source_location();
+      match input.next_including_whitespace()? {
+        Token::Delim('.') => {}
+        t => return Err(location.new_unexpected_token_error(t.clone())),
+      }
+      let location = input.current_source_location();
+      match input.next_including_whitespace()? {
+        Token::Ident(ident) => Ok(CustomIdent(ident.into())),
+        t => Err(location.new_unexpected_token_error(t.clone())),
+      }
+    }) {
+      classes.push(class);
+    }
+
+    if name.is_none() && classes.is_empty() {
+      return Err(input.new_custom_error(ParserError::InvalidValue));
+    }
+
+    Ok(ViewTransitionPartSelector { name, classes })
+  }
+}
+
+impl<'i> ToCss for ViewTransitionPartSelector<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match &self.name {
+      // The wildcard is implied when there are classes.
+      Some(ViewTransitionPartName::All) if !self.classes.is_empty() => {}
+      Some(ViewTransitionPartName::All) => dest.write_char('*')?,
+      Some(ViewTransitionPartName::Name(name)) => name.to_css(dest)?,
+      None => {}
+    }
+
+    for class in &self.classes {
+      dest.write_char('.')?;
+      class.to_css(dest)?;
+    }
+
+    Ok(())
+  }
+}
+
 /// A [webkit scrollbar](https://webkit.org/blog/363/styling-scrollbars/) pseudo element.
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
 #[cfg_attr(
@@ -922,6 +1041,27 @@ impl<'i> ToCss for PseudoElement<'i> {
         serialize_selector::<_, DefaultAtRule>(selector, dest, None, false)?;
         dest.write_char(')')
       }
+      ViewTransition => dest.write_str("::view-transition"),
+      ViewTransitionGroup { part } => {
+        dest.write_str("::view-transition-group(")?;
+        part.to_css(dest)?;
+        dest.write_char(')')
+      }
+      ViewTransitionImagePair { part } => {
+        dest.write_str("::view-transition-image-pair(")?;
+        part.to_css(dest)?;
+        dest.write_char(')')
+      }
+      ViewTransitionOld { part } => {
+        dest.write_str("::view-transition-old(")?;
+        part.to_css(dest)?;
+        dest.write_char(')')
+      }
+      ViewTransitionNew { part } => {
+        dest.write_str("::view-transition-new(")?;
+        part.to_css(dest)?;
+        dest.write_char(')')
+      }
       Placeholder(prefix) => {
         let vp = write_prefix!(prefix);
         if vp == VendorPrefix::WebKit || vp == VendorPrefix::Ms {
@@ -1015,18 +1155,23 @@ impl<'i> PseudoElement<'i> {
     }
   }
 
-  pub(crate) fn get_necessary_prefixes(&self, targets: Browsers) -> VendorPrefix {
+  fn prefix_feature(&self) -> Option<crate::prefixes::Feature> {
     use crate::prefixes::Feature;
     use PseudoElement::*;
-    let feature = match self {
-      Selection(p) if *p == VendorPrefix::None => Feature::PseudoElementSelection,
-      Placeholder(p) if *p == VendorPrefix::None => Feature::PseudoElementPlaceholder,
-      Backdrop(p) if *p == VendorPrefix::None => Feature::PseudoElementBackdrop,
-      FileSelectorButton(p) if *p == VendorPrefix::None => Feature::PseudoElementFileSelectorButton,
-      _ => return VendorPrefix::empty(),
-    };
+    Some(match self {
+      Selection(_) => Feature::PseudoElementSelection,
+      Placeholder(_) => Feature::PseudoElementPlaceholder,
+      Backdrop(_) => Feature::PseudoElementBackdrop,
+      FileSelectorButton(_) => Feature::PseudoElementFileSelectorButton,
+      _ => return None,
+    })
+  }
 
-    feature.prefixes_for(targets)
+  pub(crate) fn get_necessary_prefixes(&self, targets: Browsers) -> VendorPrefix {
+    match self.prefix_feature() {
+      Some(feature) if self.get_prefix() == VendorPrefix::None => feature.prefixes_for(targets),
+      _ => VendorPrefix::empty(),
+    }
   }
 }
 
@@ -1340,11 +1485,11 @@ impl<'a, 'i, T> ToCssWithContext<'a, 'i, T> for Component<'i> {
       Component::Nesting => serialize_nesting(dest, context, false),
       Component::Class(ref class) => {
         dest.write_char('.')?;
-        dest.write_ident(&class.0)
+        dest.write_scoped_ident(&class.0, NameKind::Class)
       }
       Component::ID(ref id) => {
         dest.write_char('#')?;
-        dest.write_ident(&id.0)
+        dest.write_scoped_ident(&id.0, NameKind::Id)
       }
       _ => {
         cssparser::ToCss::to_css(self, dest)?;
@@ -1695,6 +1840,65 @@ pub(crate) fn get_prefix(selectors: &SelectorList) -> VendorPrefix {
   prefix
 }
 
+/// Returns the vendor prefixes that the targets require for the prefixed pseudo classes
+/// and pseudo elements in the given selector list.
+pub(crate) fn get_required_prefixes(selectors: &SelectorList, targets: Browsers) -> VendorPrefix {
+  let mut prefixes = VendorPrefix::empty();
+  for selector in &selectors.0 {
+    for component in selector.iter() {
+      let feature = match component {
+        Component::NonTSPseudoClass(pc) if !pc.get_prefix().is_empty() => pc.prefix_feature(),
+        Component::PseudoElement(pe) if !pe.get_prefix().is_empty() => pe.prefix_feature(),
+        _ => None,
+      };
+
+      if let Some(feature) = feature {
+        prefixes |= feature.prefixes_for(targets);
+      }
+    }
+  }
+
+  prefixes
+}
+
+/// Adds the features used by the given selector list that are not supported by the targets.
+/// Features that will be down-leveled are added to `lowered`, and others to `unsupported`.
+pub(crate) fn get_incompatible_features(
+  selectors: &SelectorList,
+  targets: Browsers,
+  lowered: &mut Vec<Feature>,
+  unsupported: &mut Vec<Feature>,
+) {
+  for selector in &selectors.0 {
+    for component in selector.iter_raw_match_order() {
+      match component {
+        Component::NonTSPseudoClass(PseudoClass::Dir { .. }) if !Feature::CssDirPseudo.is_compatible(targets) => {
+          lowered.push(Feature::CssDirPseudo)
+        }
+        Component::NonTSPseudoClass(PseudoClass::Lang { languages })
+          if languages.len() > 1 && !Feature::LangList.is_compatible(targets) =>
+        {
+          lowered.push(Feature::LangList)
+        }
+        Component::Is(selectors) if !Feature::CssMatchesPseudo.is_compatible(targets) => {
+          if selectors.iter().all(|selector| !selector.has_combinator()) {
+            lowered.push(Feature::CssMatchesPseudo)
+          } else {
+            unsupported.push(Feature::CssMatchesPseudo)
+          }
+        }
+        Component::Negation(selectors)
+          if selectors.len() > 1 && !Feature::CssNotSelList.is_compatible(targets) =>
+        {
+          lowered.push(Feature::CssNotSelList)
+        }
+        Component::Has(..) if !Feature::CssHas.is_compatible(targets) => unsupported.push(Feature::CssHas),
+        _ => {}
+      }
+    }
+  }
+}
+
 const RTL_LANGS: &[&str] = &[
   "ae", "ar", "arc", "bcc", "bqi", "ckb", "dv", "fa", "glk", "he", "ku", "mzn", "nqo", "pnb", "ps", "sd", "ug",
   "ur", "yi",
@@ -1786,11 +1990,225 @@ fn downlevel_dir<'i>(dir: Direction, targets: Browsers) -> Component<'i> {
   }
 }
 
+/// Returns whether the selector may match an element in documents with the given content.
+/// A selector cannot match if any of its compound selectors requires a class, id, or tag name
+/// that does not appear in the content.
+pub(crate) fn can_match(selector: &Selector, content: &PurgeContent) -> bool {
+  selector.iter_raw_match_order().all(|component| match component {
+    Component::Class(name) => content.classes.contains(name.0.as_ref()),
+    Component::ID(name) => content.ids.contains(name.0.as_ref()),
+    Component::LocalName(local_name) => content.tags.contains(local_name.lower_name.0.as_ref()),
+    Component::Is(selectors)
+    | Component::Where(selectors)
+    | Component::Any(_, selectors)
+    | Component::Has(selectors) => selectors.iter().any(|selector| can_match(selector, content)),
+    // `:not()` matches elements that do not match its arguments, which may be any element.
+    _ => true,
+  })
+}
+
+/// Returns whether the selector can never match any element, e.g. because a compound selector
+/// contains two different ids, negates one of its own components, or `:root` has an ancestor
+/// or sibling.
+pub(crate) fn is_impossible(selector: &Selector) -> bool {
+  // Split the selector into compound selectors, from right to left. Pseudo-elements, slots, and
+  // parts are considered part of the compound they are attached to.
+  let mut compounds: Vec<Vec<&Component>> = vec![Vec::new()];
+  for component in selector.iter_raw_match_order() {
+    match component {
+      Component::Combinator(Combinator::PseudoElement | Combinator::SlotAssignment | Combinator::Part) => {}
+      Component::Combinator(_) => compounds.push(Vec::new()),
+      component => compounds.last_mut().unwrap().push(component),
+    }
+  }
+
+  let last = compounds.len() - 1;
+  compounds.iter().enumerate().any(|(index, compound)| {
+    let mut id = None;
+    let mut local_name = None;
+    compound.iter().any(|component| match component {
+      Component::ID(name) => matches!(id.replace(name), Some(prev) if prev != name),
+      Component::LocalName(name) => {
+        matches!(local_name.replace(&name.lower_name), Some(prev) if *prev != name.lower_name)
+      }
+      // The root element has no ancestors or siblings.
+      Component::Root => index != last,
+      // Pseudo-elements cannot be followed by combinators.
+      Component::PseudoElement(..) => index != 0,
+      // A compound that negates a subset of its own components matches nothing.
+      Component::Negation(selectors) => selectors.iter().any(|selector| {
+        selector
+          .iter_raw_match_order()
+          .all(|negated| !matches!(negated, Component::Combinator(..)) && compound.contains(&negated))
+      }),
+      Component::Is(selectors) | Component::Where(selectors) | Component::Any(_, selectors) => {
+        !selectors.is_empty() && selectors.iter().all(is_impossible)
+      }
+      _ => false,
+    })
+  })
+}
+
+/// Returns whether a selector contains a class or id that is scoped by CSS modules, or a nesting
+/// selector, which refers to a parent rule that is checked separately. Selectors within `:global()`
+/// are not scoped, and all selectors in a list such as `:is()` or `:not()` must be pure for the list
+/// to count.
+pub(crate) fn is_pure_css_modules_selector(selector: &Selector, config: &crate::css_modules::Config) -> bool {
+  selector.iter_raw_match_order().any(|component| match component {
+    Component::Class(_) => config.classes,
+    Component::ID(_) => config.ids,
+    Component::Nesting => true,
+    Component::NonTSPseudoClass(PseudoClass::Local { selector }) => is_pure_css_modules_selector(selector, config),
+    Component::Is(selectors)
+    | Component::Where(selectors)
+    | Component::Any(_, selectors)
+    | Component::Has(selectors)
+    | Component::Negation(selectors) => {
+      !selectors.is_empty() && selectors.iter().all(|selector| is_pure_css_modules_selector(selector, config))
+    }
+    _ => false,
+  })
+}
+
+/// Collects the classes and ids in a selector that are scoped by CSS modules, i.e. not within `:global()`.
+pub(crate) fn collect_css_module_names(
+  selector: &Selector,
+  config: &crate::css_modules::Config,
+  names: &mut HashSet<String>,
+) {
+  for component in selector.iter_raw_match_order() {
+    match component {
+      Component::Class(name) if config.classes => {
+        names.insert(name.0.to_string());
+      }
+      Component::ID(name) if config.ids => {
+        names.insert(name.0.to_string());
+      }
+      Component::NonTSPseudoClass(PseudoClass::Local { selector }) => {
+        collect_css_module_names(selector, config, names)
+      }
+      Component::Is(selectors)
+      | Component::Where(selectors)
+      | Component::Any(_, selectors)
+      | Component::Has(selectors)
+      | Component::Negation(selectors) => {
+        for selector in selectors.iter() {
+          collect_css_module_names(selector, config, names);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Returns whether a selector can only match elements with a class or id that is scoped by CSS modules,
+/// but not used by any of the modules that import the style sheet.
+pub(crate) fn references_unused_export(
+  selector: &Selector,
+  config: &crate::css_modules::Config,
+  used: &HashSet<String>,
+) -> bool {
+  selector.iter_raw_match_order().any(|component| match component {
+    Component::Class(name) => config.classes && !used.contains(name.0.as_ref()),
+    Component::ID(name) => config.ids && !used.contains(name.0.as_ref()),
+    Component::NonTSPseudoClass(PseudoClass::Local { selector }) => {
+      references_unused_export(selector, config, used)
+    }
+    // `:not()` may match elements without the class, so only lists that must match are considered.
+    Component::Is(selectors)
+    | Component::Where(selectors)
+    | Component::Any(_, selectors)
+    | Component::Has(selectors) => {
+      !selectors.is_empty()
+        && selectors
+          .iter()
+          .all(|selector| references_unused_export(selector, config, used))
+    }
+    _ => false,
+  })
+}
+
+/// Removes selectors from a list that only match elements that are also matched by another
+/// selector in the list with the same or higher specificity, e.g. `:where(.a.b)` in `.a, :where(.a.b)`
+/// or `*|div` in `div, *|div`. More specific selectors are kept even when another selector matches
+/// a superset of their elements, since removing them would lower the specificity of the declarations.
+pub(crate) fn remove_redundant_selectors(selectors: &mut smallvec::SmallVec<[Selector; 1]>) {
+  let mut i = 0;
+  while i < selectors.len() {
+    let selector = &selectors[i];
+    let redundant = selectors
+      .iter()
+      .enumerate()
+      .any(|(j, other)| j != i && subsumes(other, selector) && (j < i || !subsumes(selector, other)));
+    if redundant {
+      selectors.remove(i);
+    } else {
+      i += 1;
+    }
+  }
+}
+
+/// Returns whether `a` matches every element matched by `b` with at least the same specificity.
+fn subsumes<'i>(a: &Selector<'i>, b: &Selector<'i>) -> bool {
+  // Splits a selector into its compound selectors and the combinators between them, ignoring
+  // components that match all elements when no default namespace is declared.
+  fn split<'a, 'i>(selector: &'a Selector<'i>) -> (Vec<Vec<&'a Component<'i>>>, Vec<Combinator>) {
+    let mut compounds = vec![Vec::new()];
+    let mut combinators = Vec::new();
+    for component in selector.iter_raw_match_order() {
+      match component {
+        Component::Combinator(combinator) => {
+          combinators.push(*combinator);
+          compounds.push(Vec::new());
+        }
+        component => flatten(component, compounds.last_mut().unwrap()),
+      }
+    }
+    (compounds, combinators)
+  }
+
+  // Adds the component to a compound, unwrapping `:is()` and `:where()` with a single compound argument.
+  fn flatten<'a, 'i>(component: &'a Component<'i>, compound: &mut Vec<&'a Component<'i>>) {
+    match component {
+      Component::ExplicitAnyNamespace | Component::ExplicitUniversalType => {}
+      Component::Is(selectors) | Component::Where(selectors)
+        if selectors.len() == 1
+          && !selectors[0]
+            .iter_raw_match_order()
+            .any(|component| matches!(component, Component::Combinator(..))) =>
+      {
+        for component in selectors[0].iter_raw_match_order() {
+          flatten(component, compound);
+        }
+      }
+      component => compound.push(component),
+    }
+  }
+
+  if a.specificity() < b.specificity() {
+    return false;
+  }
+
+  let (a_compounds, a_combinators) = split(a);
+  let (b_compounds, b_combinators) = split(b);
+  a_combinators == b_combinators
+    && a_compounds
+      .iter()
+      .zip(b_compounds.iter())
+      .all(|(a, b)| a.iter().all(|component| b.contains(component)))
+}
+
+/// Returns whether the selector is exactly `:root`.
+pub(crate) fn is_root_selector(selector: &Selector) -> bool {
+  let mut iter = selector.iter_raw_match_order();
+  matches!((iter.next(), iter.next()), (Some(Component::Root), None))
+}
+
 /// Determines whether a selector list contains only unused selectors.
-/// A selector is considered unused if it contains a class or id component that exists in the set of unused symbols.
+/// A selector is considered unused if it contains a class, id, or attribute component that matches the set of unused symbols.
 pub(crate) fn is_unused(
   selectors: &mut std::slice::Iter<Selector>,
-  unused_symbols: &HashSet<String>,
+  unused_symbols: &UnusedSymbols,
   parent_is_unused: bool,
 ) -> bool {
   if unused_symbols.is_empty() {
@@ -1799,23 +2217,25 @@ pub(crate) fn is_unused(
 
   selectors.all(|selector| {
     for component in selector.iter_raw_match_order() {
-      match component {
-        Component::Class(name) | Component::ID(name) => {
-          if unused_symbols.contains(&name.0.to_string()) {
-            return true;
-          }
+      let unused = match component {
+        Component::Class(name) => unused_symbols.contains(SymbolKind::Class, name.0.as_ref()),
+        Component::ID(name) => unused_symbols.contains(SymbolKind::Id, name.0.as_ref()),
+        Component::AttributeInNoNamespaceExists { local_name, .. }
+        | Component::AttributeInNoNamespace { local_name, .. } => {
+          unused_symbols.contains(SymbolKind::Attribute, local_name.0.as_ref())
         }
-        Component::Is(is) | Component::Where(is) | Component::Any(_, is) => {
-          if is_unused(&mut is.iter(), unused_symbols, parent_is_unused) {
-            return true;
-          }
+        Component::AttributeOther(attr) => {
+          unused_symbols.contains(SymbolKind::Attribute, attr.local_name.0.as_ref())
         }
-        Component::Nesting => {
-          if parent_is_unused {
-            return true;
-          }
+        Component::Is(is) | Component::Where(is) | Component::Any(_, is) => {
+          is_unused(&mut is.iter(), unused_symbols, parent_is_unused)
         }
-        _ => {}
+        Component::Nesting => parent_is_unused,
+        _ => false,
+      };
+
+      if unused {
+        return true;
       }
     }
 
@@ -1825,7 +2245,7 @@ pub(crate) fn is_unused(
 
 #[cfg(feature = "visitor")]
 impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for SelectorList<'i> {
-  const CHILD_TYPES: VisitTypes = VisitTypes::SELECTORS;
+  const CHILD_TYPES: VisitTypes = VisitTypes::SELECTORS.union(VisitTypes::SELECTOR_COMPONENTS);
 
   fn visit(&mut self, visitor: &mut V) {
     if visitor.visit_types().contains(VisitTypes::SELECTORS) {
@@ -1844,11 +2264,59 @@ impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for SelectorList
 
 #[cfg(feature = "visitor")]
 impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for Selector<'i> {
-  const CHILD_TYPES: VisitTypes = VisitTypes::SELECTORS;
+  const CHILD_TYPES: VisitTypes = VisitTypes::SELECTORS.union(VisitTypes::SELECTOR_COMPONENTS);
 
   fn visit(&mut self, visitor: &mut V) {
-    visitor.visit_selector(self)
+    if visitor.visit_types().contains(VisitTypes::SELECTORS) {
+      visitor.visit_selector(self)
+    } else {
+      self.visit_children(visitor)
+    }
   }
 
-  fn visit_children(&mut self, _visitor: &mut V) {}
+  fn visit_children(&mut self, visitor: &mut V) {
+    if !visitor.visit_types().contains(VisitTypes::SELECTOR_COMPONENTS) {
+      return;
+    }
+
+    for component in self.iter_mut_raw_match_order() {
+      if !component.is_combinator() {
+        Visit::visit(component, visitor)
+      }
+    }
+  }
+}
+
+#[cfg(feature = "visitor")]
+impl<'i, T: Visit<'i, T, V>, V: Visitor<'i, T>> Visit<'i, T, V> for Component<'i> {
+  const CHILD_TYPES: VisitTypes = VisitTypes::SELECTORS.union(VisitTypes::SELECTOR_COMPONENTS);
+
+  fn visit(&mut self, visitor: &mut V) {
+    if visitor.visit_types().contains(VisitTypes::SELECTOR_COMPONENTS) {
+      visitor.visit_selector_component(self)
+    } else {
+      self.visit_children(visitor)
+    }
+  }
+
+  fn visit_children(&mut self, visitor: &mut V) {
+    // Visit the selectors nested within the component, e.g. the arguments of `:is()`.
+    match self {
+      Component::Negation(selectors)
+      | Component::Where(selectors)
+      | Component::Is(selectors)
+      | Component::Any(_, selectors)
+      | Component::Has(selectors) => {
+        for selector in selectors.iter_mut() {
+          Visit::visit(selector, visitor)
+        }
+      }
+      Component::Slotted(selector) | Component::Host(Some(selector)) => Visit::visit(selector, visitor),
+      Component::NonTSPseudoClass(PseudoClass::Local { selector } | PseudoClass::Global { selector })
+      | Component::PseudoElement(
+        PseudoElement::CueFunction { selector } | PseudoElement::CueRegionFunction(selector),
+      ) => Visit::visit(selector.as_mut(), visitor),
+      _ => {}
+    }
+  }
 }
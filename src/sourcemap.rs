@@ -0,0 +1,340 @@
+//! Source map utilities.
+//!
+//! Source maps for style sheets are generated by passing a [SourceMap](parcel_sourcemap::SourceMap)
+//! to the printer via [PrinterOptions](crate::printer::PrinterOptions). How the source map is written
+//! is controlled by [SourceMapOptions](SourceMapOptions). This module also contains utilities for
+//! combining and reading source maps produced elsewhere.
+//!
+//! # Index maps
+//!
+//! An [index map](https://tc39.es/source-map/#index-map) is a source map that consists of `sections`,
+//! each of which contains a regular source map for a range of the generated file, along with the line
+//! and column offset at which the range starts. This is useful when concatenating multiple independently
+//! compiled outputs, e.g. bundler chunks or multiple entries, because the mappings of each output can be
+//! embedded as is rather than being remapped.
+//!
+//! ```
+//! use lightningcss::sourcemap::IndexSourceMap;
+//! use parcel_sourcemap::SourceMap;
+//!
+//! let a = SourceMap::new("/");
+//! let b = SourceMap::new("/");
+//! // ... print style sheets with `a` and `b` as the source maps ...
+//!
+//! let mut map = IndexSourceMap::new();
+//! map.append(".a{color:red}\n", a);
+//! map.append(".b{color:green}", b);
+//! let json = map.to_json().unwrap();
+//! ```
+//!
+//! # Provenance
+//!
+//! [original_ranges](original_ranges) attributes a byte range of the generated output back to the
+//! source locations that produced it, e.g. to point an error overlay at the original file, or to
+//! compute how much of a bundle each source contributed.
+
+use crate::error::ErrorLocation;
+use parcel_sourcemap::{Mapping, OriginalLocation, SourceMap, SourceMapError, SourceMapErrorType};
+use serde_json::Value;
+use std::ops::Range;
+
+/// Options that control how a source map is written when printing a style sheet.
+///
+/// See [PrinterOptions](crate::printer::PrinterOptions).
+#[derive(Default, Clone, Copy)]
+pub struct SourceMapOptions<'a> {
+  /// Whether to omit the `sourcesContent` field, which embeds the original text of each source
+  /// file in the source map.
+  pub exclude_sources_content: bool,
+  /// Whether to append the source map to the CSS as a `data:` url in a `sourceMappingURL` comment,
+  /// rather than only emitting it as a separate artifact in [ToCssResult](crate::stylesheet::ToCssResult).
+  pub inline: bool,
+  /// The url of the separate source map file, e.g. `style.css.map`. When set, a `sourceMappingURL`
+  /// comment referencing it is appended to the CSS. This is ignored if the source map is inlined.
+  pub url: Option<&'a str>,
+  /// The `sourceRoot` of the source map, which consumers prepend to each source path when resolving
+  /// the sources, e.g. `webpack://`.
+  pub source_root: Option<&'a str>,
+  /// A callback to transform the path of each source before it is written to the source map, e.g. to
+  /// strip a monorepo prefix. By default, the paths are written as is, relative to the project root.
+  pub source_path: Option<&'a SourcePathRewriter<'a>>,
+}
+
+/// A callback that transforms a source path, as used by [SourceMapOptions::source_path].
+pub type SourcePathRewriter<'a> = dyn Fn(&str) -> String + Send + Sync + 'a;
+
+/// A source map consisting of sections, each with a source map for a range of the generated file.
+/// See the [module documentation](self) for details.
+#[derive(Debug, Default)]
+pub struct IndexSourceMap {
+  sections: Vec<(u32, u32, SourceMap)>,
+  line: u32,
+  column: u32,
+}
+
+impl IndexSourceMap {
+  /// Creates an empty index map.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a section starting at the given zero-based line and column of the generated file.
+  /// Sections must be added in order, and must not overlap.
+  pub fn add_section(&mut self, line: u32, column: u32, map: SourceMap) {
+    self.sections.push((line, column, map));
+  }
+
+  /// Adds a section for code appended to the end of the generated file, and advances the
+  /// offset of the next appended section past it. Columns are counted in bytes, as in the
+  /// mappings generated by the printer.
+  pub fn append(&mut self, code: &str, map: SourceMap) {
+    self.sections.push((self.line, self.column, map));
+    self.advance(code);
+  }
+
+  /// Advances the offset of the next appended section past code without a source map,
+  /// e.g. a separator or banner between the concatenated outputs.
+  pub fn advance(&mut self, code: &str) {
+    match code.rfind('\n') {
+      Some(index) => {
+        self.line += code.matches('\n').count() as u32;
+        self.column = (code.len() - index - 1) as u32;
+      }
+      None => self.column += code.len() as u32,
+    }
+  }
+
+  /// Serializes the index map to JSON.
+  pub fn to_json(&mut self) -> Result<String, SourceMapError> {
+    let mut sections = Vec::with_capacity(self.sections.len());
+    for (line, column, map) in &mut self.sections {
+      sections.push(format!(
+        r#"{{"offset":{{"line":{},"column":{}}},"map":{}}}"#,
+        line,
+        column,
+        map.to_json(None)?
+      ));
+    }
+
+    Ok(format!(r#"{{"version":3,"sections":[{}]}}"#, sections.join(",")))
+  }
+
+  /// Flattens the sections into a single regular source map.
+  pub fn to_source_map(&self, project_root: &str) -> Result<SourceMap, SourceMapError> {
+    let mut result = SourceMap::new(project_root);
+    for (line, column, map) in &self.sections {
+      add_section(&mut result, map, *line, *column)?;
+    }
+    Ok(result)
+  }
+}
+
+/// Copies the mappings, sources, and names of a source map into another, offset by the given line
+/// and column. The column offset only applies to the first line.
+fn add_section(dest: &mut SourceMap, map: &SourceMap, line: u32, column: u32) -> Result<(), SourceMapError> {
+  let mut sources = Vec::with_capacity(map.get_sources().len());
+  for (i, source) in map.get_sources().iter().enumerate() {
+    let index = dest.add_source(source);
+    let content = map.get_source_content(i as u32).unwrap_or_default();
+    dest.set_source_content(index as usize, content)?;
+    sources.push(index);
+  }
+
+  for mapping in map.get_mappings() {
+    let original = match mapping.original {
+      Some(original) => Some(OriginalLocation {
+        source: sources[original.source as usize],
+        name: match original.name {
+          Some(name) => Some(dest.add_name(map.get_name(name)?)),
+          None => None,
+        },
+        ..original
+      }),
+      None => None,
+    };
+
+    let generated_column = if mapping.generated_line == 0 {
+      mapping.generated_column + column
+    } else {
+      mapping.generated_column
+    };
+
+    dest.add_mapping(mapping.generated_line + line, generated_column, original);
+  }
+
+  Ok(())
+}
+
+/// A range of the generated output, and the source location that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OriginalRange {
+  /// The byte range of the generated output.
+  pub range: Range<usize>,
+  /// The location in the source file that the range was generated from.
+  pub location: ErrorLocation,
+}
+
+/// Returns the source locations that produced a byte range of the generated code, according to the
+/// source map that was written while printing the code. The range is split at each mapping, and the
+/// returned ranges are clipped to the requested range. Parts of the range without a mapping to a source
+/// location, e.g. a banner, are omitted.
+pub fn original_ranges(map: &SourceMap, code: &str, range: Range<usize>) -> Vec<OriginalRange> {
+  let line_starts: Vec<usize> = std::iter::once(0)
+    .chain(code.match_indices('\n').map(|(index, _)| index + 1))
+    .collect();
+
+  let mut mappings = map.get_mappings();
+  mappings.sort_by_key(|mapping| (mapping.generated_line, mapping.generated_column));
+  let offsets: Vec<usize> = mappings
+    .iter()
+    .map(|mapping| match line_starts.get(mapping.generated_line as usize) {
+      Some(start) => (start + mapping.generated_column as usize).min(code.len()),
+      None => code.len(),
+    })
+    .collect();
+
+  let mut result = Vec::new();
+  for (i, mapping) in mappings.iter().enumerate() {
+    let start = offsets[i].max(range.start);
+    let end = offsets.get(i + 1).copied().unwrap_or(code.len()).min(range.end);
+    if start >= end {
+      continue;
+    }
+
+    if let Some(original) = mapping.original {
+      result.push(OriginalRange {
+        range: start..end,
+        location: ErrorLocation {
+          filename: map.get_source(original.source).unwrap_or_default().to_owned(),
+          line: original.original_line,
+          column: original.original_column + 1,
+        },
+      });
+    }
+  }
+
+  result
+}
+
+/// Finds the closest mapping at or before the given zero-based generated position in a source map.
+pub(crate) fn find_closest_mapping(map: &mut SourceMap, line: u32, column: u32) -> Option<Mapping> {
+  let mapping = map.find_closest_mapping(line, column)?;
+  if mapping.generated_column == 0 && column > 0 {
+    // parcel_sourcemap returns the first mapping of the line for positions after the last mapping
+    // on the line, so find the last mapping before the position instead.
+    return map
+      .get_mappings()
+      .into_iter()
+      .rev()
+      .find(|m| m.generated_line == line && m.generated_column <= column)
+      .or(Some(mapping));
+  }
+
+  Some(mapping)
+}
+
+/// Serializes a source map to JSON according to the given options. The `inline` and `url` options
+/// are ignored, as they only affect the CSS.
+pub fn source_map_to_json(map: &mut SourceMap, options: &SourceMapOptions) -> Result<String, SourceMapError> {
+  let mut mappings = Vec::new();
+  map.write_vlq(&mut mappings)?;
+
+  let mut json = String::from(r#"{"version":3"#);
+  if let Some(source_root) = options.source_root {
+    json.push_str(r#","sourceRoot":"#);
+    json.push_str(&serde_json::to_string(source_root)?);
+  }
+  json.push_str(r#","mappings":"#);
+  json.push_str(&serde_json::to_string(&String::from_utf8(mappings)?)?);
+  json.push_str(r#","sources":"#);
+  match options.source_path {
+    Some(source_path) => {
+      let sources: Vec<String> = map.get_sources().iter().map(|source| source_path(source)).collect();
+      json.push_str(&serde_json::to_string(&sources)?);
+    }
+    None => json.push_str(&serde_json::to_string(map.get_sources())?),
+  }
+  if !options.exclude_sources_content {
+    json.push_str(r#","sourcesContent":"#);
+    json.push_str(&serde_json::to_string(map.get_sources_content())?);
+  }
+  json.push_str(r#","names":"#);
+  json.push_str(&serde_json::to_string(map.get_names())?);
+  json.push('}');
+  Ok(json)
+}
+
+/// Parses a source map from JSON. Both regular source maps and index maps are supported.
+/// The sections of index maps are flattened into a single source map.
+pub fn source_map_from_json(project_root: &str, json: &str) -> Result<SourceMap, SourceMapError> {
+  let value: Value = serde_json::from_str(json)?;
+  let sections = match value.get("sections").and_then(|sections| sections.as_array()) {
+    Some(sections) => sections,
+    None => return SourceMap::from_json(project_root, json),
+  };
+
+  let mut result = SourceMap::new(project_root);
+  for section in sections {
+    let offset = |key: &str| {
+      section
+        .get("offset")
+        .and_then(|offset| offset.get(key))
+        .and_then(|n| n.as_u64())
+        .ok_or_else(|| SourceMapError::new(SourceMapErrorType::JSONError))
+    };
+
+    // Sections that reference their map by url are not supported.
+    let map = match section.get("map") {
+      Some(map) => source_map_from_json(project_root, &map.to_string())?,
+      None => return Err(SourceMapError::new(SourceMapErrorType::JSONError)),
+    };
+
+    add_section(&mut result, &map, offset("line")? as u32, offset("column")? as u32)?;
+  }
+
+  Ok(result)
+}
+
+/// Parses a source map from a `data:` url, as found in a `sourceMappingURL` comment.
+/// Both regular source maps and index maps are supported.
+pub fn source_map_from_data_url(project_root: &str, data_url: &str) -> Result<SourceMap, SourceMapError> {
+  let error = || SourceMapError::new(SourceMapErrorType::DataUrlError);
+  let (header, data) = data_url
+    .strip_prefix("data:")
+    .and_then(|url| url.split_once(','))
+    .ok_or_else(error)?;
+
+  let mut params = header.split(';');
+  if !params.next().unwrap_or_default().eq_ignore_ascii_case("application/json") {
+    return Err(error());
+  }
+
+  if params.any(|param| param.eq_ignore_ascii_case("base64")) {
+    let data = data_encoding::BASE64.decode(data.as_bytes()).map_err(|_| error())?;
+    let json = String::from_utf8(data)?;
+    source_map_from_json(project_root, &json)
+  } else {
+    let json = String::from_utf8(percent_decode(data))?;
+    source_map_from_json(project_root, &json)
+  }
+}
+
+/// Decodes the `%XX` escapes in the data of a `data:` url.
+fn percent_decode(data: &str) -> Vec<u8> {
+  let bytes = data.as_bytes();
+  let mut result = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] == b'%' && i + 2 < bytes.len() {
+      let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+      if let Ok(byte) = u8::from_str_radix(hex, 16) {
+        result.push(byte);
+        i += 3;
+        continue;
+      }
+    }
+    result.push(bytes[i]);
+    i += 1;
+  }
+  result
+}
@@ -5,21 +5,28 @@
 
 use crate::compat::Feature;
 use crate::context::{DeclarationContext, PropertyHandlerContext};
-use crate::css_modules::{CssModule, CssModuleExports, CssModuleReferences};
+use crate::css_modules::{self, CssModule, CssModuleExports, CssModuleReferences, NameAllocator, NameRecorder};
 use crate::declaration::{DeclarationBlock, DeclarationHandler};
 use crate::dependencies::Dependency;
 use crate::error::{Error, ErrorLocation, MinifyErrorKind, ParserError, PrinterError, PrinterErrorKind};
 use crate::parser::{DefaultAtRuleParser, TopLevelRuleParser};
-use crate::printer::Printer;
-use crate::rules::{CssRule, CssRuleList, MinifyContext};
+use crate::printer::{estimate_compressed_size, Printer, SerializationChoices};
+use crate::report::{CompatReport, SizeReport};
+use crate::rules::document::DocumentRulePolicy;
+use crate::rules::keyframes::KeyframesName;
+use crate::rules::property::RegisteredProperties;
+use crate::rules::{CssRule, CssRuleList, CustomPropertyReferences, MinifyContext};
+use crate::selector::Direction;
 use crate::targets::Browsers;
 use crate::traits::ToCss;
+use crate::unused_symbols::{matches_pattern, UnusedSymbols};
 #[cfg(feature = "visitor")]
 use crate::visitor::{Visit, VisitTypes, Visitor};
 use cssparser::{AtRuleParser, Parser, ParserInput, RuleListParser};
 #[cfg(feature = "sourcemap")]
 use parcel_sourcemap::SourceMap;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 pub use crate::parser::ParserOptions;
 pub use crate::printer::PrinterOptions;
@@ -91,6 +98,13 @@ pub struct StyleSheet<'i, 'o, T: AtRuleParser<'i> = DefaultAtRuleParser> {
   pub sources: Vec<String>,
   /// The source map URL extracted from the original style sheet.
   pub(crate) source_map_urls: Vec<Option<String>>,
+  /// The hashes of the contents of each source, if needed by the CSS modules pattern.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub(crate) content_hashes: Vec<Option<u64>>,
+  /// The source maps provided for each source, e.g. by a preprocessor such as Sass.
+  #[cfg(feature = "sourcemap")]
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub(crate) input_source_maps: Vec<Option<SourceMap>>,
   #[cfg_attr(feature = "serde", serde(skip))]
   /// The options the style sheet was originally parsed with.
   options: ParserOptions<'o, 'i, T>,
@@ -104,7 +118,138 @@ pub struct MinifyOptions {
   pub targets: Option<Browsers>,
   /// A list of known unused symbols, including CSS class names,
   /// ids, and `@keyframe` names. The declarations of these will be removed.
+  /// Symbols can be restricted to classes with a `.` prefix, to ids with a `#` prefix, or
+  /// to attribute selectors by wrapping them in `[]`. A `*` wildcard matches any sequence
+  /// of characters, e.g. `.btn-*`.
   pub unused_symbols: HashSet<String>,
+  /// A fixed document direction to assume when lowering logical properties for
+  /// browsers that don't support them. When set, properties such as `margin-inline-start`
+  /// are compiled to a single physical property rather than duplicated `:dir()` rules.
+  pub direction: Option<Direction>,
+  /// Whether to remove entries from `will-change` declarations that refer to properties
+  /// which are not declared anywhere in the style sheet.
+  pub remove_unused_will_change: bool,
+  /// How `@document` and `@-moz-document` rules are handled.
+  pub document_rules: DocumentRulePolicy,
+  /// Whether to remove `@keyframes` rules that are not referenced by any `animation` or
+  /// `animation-name` declaration in the style sheet. This should only be enabled when the
+  /// style sheet is complete, i.e. the animations are not referenced from other style sheets,
+  /// inline styles, or JavaScript. No rules are removed if an animation name uses `var()`.
+  pub remove_unused_keyframes: bool,
+  /// Whether to remove `@font-face` rules whose family is not referenced by any `font` or
+  /// `font-family` declaration in the style sheet. As with `remove_unused_keyframes`, this should
+  /// only be enabled when the style sheet is complete.
+  pub remove_unused_font_faces: bool,
+  /// Font families whose `@font-face` rules are always preserved by `remove_unused_font_faces`,
+  /// e.g. because they are referenced from JavaScript or inline styles. Matched case-insensitively.
+  pub preserved_font_families: HashSet<String>,
+  /// Whether to remove custom property declarations that are never read by a `var()` reference,
+  /// either directly or via other custom properties that are read. As with `remove_unused_keyframes`,
+  /// this should only be enabled when the style sheet is complete.
+  pub remove_unused_custom_properties: bool,
+  /// Patterns for custom properties that are always preserved by `remove_unused_custom_properties`,
+  /// e.g. `--theme-*`. A `*` matches any sequence of characters.
+  pub preserved_custom_properties: Vec<String>,
+  /// Whether `remove_unused_custom_properties` preserves all custom properties declared in `:root` rules,
+  /// which are often read from JavaScript.
+  pub preserve_root_custom_properties: bool,
+  /// The content of the documents the style sheet applies to, e.g. as produced by scanning markup.
+  /// When set, rules with selectors that cannot match any element in the content are removed, along
+  /// with `@keyframes` and `@font-face` rules that are only referenced by removed rules.
+  pub purge: Option<PurgeContent>,
+  /// Whether to replace `var()` references to custom properties registered by `@property` rules
+  /// with their initial value, when the property is never declared in the style sheet. As with
+  /// `remove_unused_keyframes`, this should only be enabled when the style sheet is complete, i.e.
+  /// the properties are not set from other style sheets, inline styles, or JavaScript.
+  pub inline_registered_properties: bool,
+  /// Whether to merge `@keyframes` rules with identical keyframes but different names, and rename
+  /// the `animation` and `animation-name` references to the removed rules. As with `remove_unused_keyframes`,
+  /// this should only be enabled when the style sheet is complete. With CSS modules, the removed names
+  /// are exported as aliases of the remaining rule.
+  pub merge_duplicate_keyframes: bool,
+  /// Whether to preserve the structure of the rules in the style sheet. When enabled, rules are never
+  /// merged with adjacent rules, and nested conditional rules are not unwrapped or removed based on the
+  /// conditions of their parents, so each rule in the output corresponds to a rule in the source.
+  pub preserve_rule_structure: bool,
+  /// Whether to sort the declarations in each style rule by property name, and adjacent style rules by
+  /// their selectors, so the output is deterministic and compresses better. Declarations and rules are
+  /// only reordered when their order cannot affect the cascade, i.e. they don't set the same property
+  /// via shorthands, longhands, vendor prefixes, or logical and physical properties.
+  pub canonical_order: bool,
+  /// Whether to restructure style rules across the style sheet: declarations that are overridden by a later
+  /// rule with the same selectors are removed, and non-adjacent rules with the same selectors are merged when
+  /// none of the rules in between set an interacting property. This assumes that the style sheet is complete,
+  /// i.e. that the rules in between are not matched by other elements in a different order.
+  pub restructure_rules: bool,
+  /// Whether to lower `scroll-snap-type` to the legacy `-ms-scroll-snap-type` and `-ms-scroll-snap-points-x/y`
+  /// properties for IE and legacy Edge targets. The legacy syntax snaps at a fixed interval rather than at the
+  /// `scroll-snap-align` positions of the children, so the generated snap points assume each child fills the
+  /// scroll container.
+  pub legacy_scroll_snap: bool,
+}
+
+/// A minification preset, as used by [MinifyOptions::level].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum MinifyLevel {
+  /// Lossless minification that preserves the structure and order of the rules. Only the values,
+  /// declarations, selectors, and conditions within each rule are minified.
+  O1,
+  /// The default minification, which also merges adjacent rules and removes rules that can never apply.
+  #[default]
+  O2,
+  /// Aggressive minification that assumes the style sheet is complete, i.e. that it is not combined
+  /// with other style sheets, inline styles, or JavaScript that reference its symbols. In addition to
+  /// `O2`, unused `@keyframes`, `@font-face` rules, custom properties and `will-change` entries are removed,
+  /// duplicate `@keyframes` are merged, registered custom properties are inlined, style rules with the same
+  /// selectors are restructured across the style sheet, and declarations and rules are sorted into a canonical
+  /// order where it cannot affect the cascade.
+  O3,
+}
+
+impl MinifyOptions {
+  /// Returns the options for a minification preset. Options such as `targets` can be set on the result,
+  /// e.g. `MinifyOptions { targets, ..MinifyOptions::level(MinifyLevel::O3) }`.
+  pub fn level(level: MinifyLevel) -> MinifyOptions {
+    match level {
+      MinifyLevel::O1 => MinifyOptions {
+        preserve_rule_structure: true,
+        ..MinifyOptions::default()
+      },
+      MinifyLevel::O2 => MinifyOptions::default(),
+      MinifyLevel::O3 => MinifyOptions {
+        remove_unused_will_change: true,
+        remove_unused_keyframes: true,
+        remove_unused_font_faces: true,
+        remove_unused_custom_properties: true,
+        inline_registered_properties: true,
+        merge_duplicate_keyframes: true,
+        canonical_order: true,
+        restructure_rules: true,
+        ..MinifyOptions::default()
+      },
+    }
+  }
+}
+
+/// The class names, ids, and tag names used in a set of documents, as used by [MinifyOptions::purge].
+/// Each set must be complete: selectors referencing names not in the set are assumed to never match.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+  any(feature = "serde", feature = "nodejs"),
+  derive(serde::Serialize, serde::Deserialize),
+  serde(default)
+)]
+pub struct PurgeContent {
+  /// The class names used in the documents.
+  pub classes: HashSet<String>,
+  /// The ids used in the documents.
+  pub ids: HashSet<String>,
+  /// The tag names used in the documents, in lowercase.
+  pub tags: HashSet<String>,
 }
 
 /// A result returned from `to_css`, including the serialize CSS
@@ -122,6 +267,9 @@ pub struct ToCssResult {
   /// A list of dependencies (e.g. `@import` or `url()`) found in
   /// the style sheet, if the `analyze_dependencies` option is enabled.
   pub dependencies: Option<Vec<Dependency>>,
+  /// The source map serialized to JSON, if a `source_map` was provided and it was not inlined.
+  #[cfg(feature = "sourcemap")]
+  pub map: Option<String>,
 }
 
 impl<'i, 'o, T: AtRuleParser<'i>> StyleSheet<'i, 'o, T>
@@ -137,6 +285,9 @@ where
     StyleSheet {
       sources,
       source_map_urls: Vec::new(),
+      content_hashes: Vec::new(),
+      #[cfg(feature = "sourcemap")]
+      input_source_maps: Vec::new(),
       rules,
       options,
     }
@@ -150,7 +301,16 @@ where
       RuleListParser::new_for_stylesheet(&mut parser, TopLevelRuleParser::new(&mut options));
 
     let mut rules = vec![];
-    while let Some(rule) = rule_list_parser.next() {
+    loop {
+      rule_list_parser
+        .parser
+        .options
+        .parse_comments(rule_list_parser.input, &mut rules);
+      let rule = match rule_list_parser.next() {
+        Some(rule) => rule,
+        None => break,
+      };
+
       let rule = match rule {
         Ok((_, CssRule::Ignored)) => continue,
         Ok((_, rule)) => rule,
@@ -171,6 +331,11 @@ where
     Ok(StyleSheet {
       sources: vec![options.filename.clone()],
       source_map_urls: vec![parser.current_source_map_url().map(|s| s.to_owned())],
+      content_hashes: vec![
+        css_modules::needs_content_hash(&options.css_modules).then(|| css_modules::content_hash(code))
+      ],
+      #[cfg(feature = "sourcemap")]
+      input_source_maps: Vec::new(),
       rules: CssRuleList(rules),
       options,
     })
@@ -181,15 +346,165 @@ where
     self.source_map_urls.get(source_index)?.as_ref()
   }
 
-  /// Returns the inline source map associated with the source at the given index.
+  /// Returns the input source map associated with the source at the given index. This is either the
+  /// source map set with [set_input_source_map](StyleSheet::set_input_source_map), or the inline
+  /// source map referenced by a `sourceMappingURL` comment with a `data:` url.
   #[cfg(feature = "sourcemap")]
   pub fn source_map(&self, source_index: usize) -> Option<SourceMap> {
-    SourceMap::from_data_url("/", self.source_map_url(source_index)?).ok()
+    if let Some(Some(source_map)) = self.input_source_maps.get(source_index) {
+      return Some(source_map.clone());
+    }
+
+    crate::sourcemap::source_map_from_data_url("/", self.source_map_url(source_index)?).ok()
+  }
+
+  /// Sets the input source map for the source at the given index, e.g. the map produced by Sass, Less or
+  /// PostCSS when they generated the CSS. When printing with a source map, all mappings are remapped through
+  /// the input source map, so that they point at the original sources rather than the intermediate CSS.
+  /// This takes precedence over an inline source map referenced by a `sourceMappingURL` comment.
+  #[cfg(feature = "sourcemap")]
+  pub fn set_input_source_map(&mut self, source_index: usize, source_map: SourceMap) {
+    if self.input_source_maps.len() <= source_index {
+      self.input_source_maps.resize(source_index + 1, None);
+    }
+    self.input_source_maps[source_index] = Some(source_map);
+  }
+
+  /// Returns whether an input source map is associated with the source at the given index, either set with
+  /// [set_input_source_map](StyleSheet::set_input_source_map) or referenced by an inline `sourceMappingURL`.
+  #[cfg(feature = "sourcemap")]
+  pub fn has_input_source_map(&self, source_index: usize) -> bool {
+    matches!(self.input_source_maps.get(source_index), Some(Some(_)))
+      || matches!(self.source_map_url(source_index), Some(url) if url.starts_with("data"))
+  }
+
+  /// Removes the CSS module classes, ids, and `@keyframes` rules that are not used, given the names of the
+  /// exports that are imported from JavaScript, e.g. as determined by a bundler. Classes composed by used
+  /// classes are also kept. Selectors that can only match unused classes or ids are removed, along with
+  /// `@keyframes` rules that are only referenced by removed rules. Returns the names that are no longer
+  /// exported, in sorted order. This should be called before [minify](StyleSheet::minify), and has no
+  /// effect if CSS modules are not enabled.
+  pub fn remove_unused_exports(&mut self, used: &HashSet<String>) -> Vec<String> {
+    let config = match &self.options.css_modules {
+      Some(config) => config,
+      None => return Vec::new(),
+    };
+
+    let mut names = HashSet::new();
+    let mut composes = HashMap::new();
+    self.rules.collect_css_module_names(config, &mut names, &mut composes);
+
+    // Classes composed by a used class are used as well.
+    let mut used = used.clone();
+    let mut stack: Vec<String> = used.iter().cloned().collect();
+    while let Some(name) = stack.pop() {
+      for composed in composes.get(&name).into_iter().flatten() {
+        if used.insert(composed.clone()) {
+          stack.push(composed.clone());
+        }
+      }
+    }
+
+    self.rules.remove_unused_exports(config, &used);
+
+    let mut referenced = HashSet::new();
+    if config.animation && self.rules.collect_animation_names(&mut referenced, true) {
+      self.rules.retain_keyframes(&mut |keyframes| match &keyframes.name {
+        KeyframesName::Ident(ident) => {
+          used.contains(ident.0.as_ref())
+            || referenced.contains(&(keyframes.loc.source_index, ident.0.to_string()))
+        }
+        KeyframesName::Custom(..) => true,
+      });
+    }
+
+    let mut remaining = HashSet::new();
+    self.rules.collect_css_module_names(config, &mut remaining, &mut HashMap::new());
+    let mut removed: Vec<String> = names.difference(&remaining).cloned().collect();
+    removed.sort();
+    removed
   }
 
   /// Minify and transform the style sheet for the provided browser targets.
   pub fn minify(&mut self, options: MinifyOptions) -> Result<(), Error<MinifyErrorKind>> {
-    let mut context = PropertyHandlerContext::new(options.targets, &options.unused_symbols);
+    self.minify_with_report(options).map(|_| ())
+  }
+
+  /// Minify and transform the style sheet for the provided browser targets, and return a
+  /// [CompatReport](CompatReport) listing the features that were down-leveled, and the features
+  /// that are not supported by the targets but could not be transpiled.
+  pub fn minify_with_report(&mut self, options: MinifyOptions) -> Result<CompatReport, Error<MinifyErrorKind>> {
+    if let Some(content) = &options.purge {
+      self.rules.purge(content);
+    }
+
+    // Keyframes names are local to each source file when they are scoped by CSS modules.
+    let scoped_keyframes = matches!(&self.options.css_modules, Some(css_modules) if css_modules.animation);
+    if options.merge_duplicate_keyframes {
+      self.rules.merge_duplicate_keyframes(scoped_keyframes);
+    }
+
+    let used_properties = if options.remove_unused_will_change {
+      let mut names = HashSet::new();
+      self.rules.collect_property_names(&mut names);
+      Some(names)
+    } else {
+      None
+    };
+
+    let used_keyframes = if options.remove_unused_keyframes || options.purge.is_some() {
+      let mut names = HashSet::new();
+      if self.rules.collect_animation_names(&mut names, scoped_keyframes) {
+        Some(names)
+      } else {
+        None
+      }
+    } else {
+      None
+    };
+
+    let used_font_families = if options.remove_unused_font_faces || options.purge.is_some() {
+      let mut names = HashSet::new();
+      if self.rules.collect_font_families(&mut names) {
+        names.extend(options.preserved_font_families.iter().map(|name| name.to_lowercase()));
+        Some(names)
+      } else {
+        None
+      }
+    } else {
+      None
+    };
+
+    let used_custom_properties = if options.remove_unused_custom_properties {
+      let mut references = CustomPropertyReferences::default();
+      if self
+        .rules
+        .collect_custom_property_references(&mut references, options.preserve_root_custom_properties)
+      {
+        for name in references.dependencies.keys() {
+          if options
+            .preserved_custom_properties
+            .iter()
+            .any(|pattern| matches_pattern(pattern, name))
+          {
+            references.used.insert(name.clone());
+          }
+        }
+        Some(references.resolve())
+      } else {
+        None
+      }
+    } else {
+      None
+    };
+
+    let registered_properties = RegisteredProperties::new(&self.rules, options.inline_registered_properties);
+
+    let unused_symbols = UnusedSymbols::new(&options.unused_symbols);
+    let mut context = PropertyHandlerContext::new(options.targets, &unused_symbols);
+    context.direction = options.direction;
+    context.legacy_scroll_snap = options.legacy_scroll_snap;
+    context.used_properties = used_properties.as_ref();
     let mut handler = DeclarationHandler::new(options.targets);
     let mut important_handler = DeclarationHandler::new(options.targets);
 
@@ -215,9 +530,20 @@ where
       handler: &mut handler,
       important_handler: &mut important_handler,
       handler_context: &mut context,
-      unused_symbols: &options.unused_symbols,
+      unused_symbols: &unused_symbols,
       custom_media,
       css_modules: self.options.css_modules.is_some(),
+      document_rules: options.document_rules,
+      used_keyframes: used_keyframes.as_ref(),
+      scoped_keyframes,
+      used_font_families: used_font_families.as_ref(),
+      used_custom_properties: used_custom_properties.as_ref(),
+      registered_properties: registered_properties.as_ref(),
+      preserve_rule_structure: options.preserve_rule_structure,
+      canonical_order: options.canonical_order,
+      restructure_rules: options.restructure_rules,
+      parent_media: Vec::new(),
+      parent_supports: Vec::new(),
     };
 
     self.rules.minify(&mut ctx, false).map_err(|e| Error {
@@ -228,52 +554,380 @@ where
       )),
     })?;
 
+    if let Some(warnings) = &self.options.warnings {
+      if let Ok(mut warnings) = warnings.write() {
+        for warning in ctx.handler_context.warnings.drain(..) {
+          warnings.push(Error {
+            kind: warning.kind,
+            loc: Some(ErrorLocation::new(
+              warning.loc,
+              self.sources[warning.loc.source_index as usize].clone(),
+            )),
+          });
+        }
+      }
+    }
+
+    Ok(CompatReport::new(
+      &ctx.handler_context.lowered_features,
+      &ctx.handler_context.unsupported_features,
+      &self.sources,
+    ))
+  }
+
+  /// Minify and transform the style sheet for a set of modern browser targets, and return a copy
+  /// that is further lowered for a set of legacy targets. This is useful to serve modern CSS to
+  /// browsers that support it and fully transpiled CSS to older browsers, similar to the
+  /// `module`/`nomodule` pattern for JavaScript.
+  ///
+  /// The style sheet is only parsed once, and the legacy copy starts from the minified modern
+  /// rules, so rule merging, unused symbol removal and other target independent work is shared.
+  /// Serialize each style sheet with the corresponding targets in [PrinterOptions](PrinterOptions).
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
+  /// use lightningcss::targets::Browsers;
+  ///
+  /// let mut modern = StyleSheet::parse(".foo { color: lab(50% 20 -30) }", ParserOptions::default()).unwrap();
+  /// let legacy = modern
+  ///   .minify_dual(
+  ///     MinifyOptions {
+  ///       targets: Some(Browsers {
+  ///         safari: Some(16 << 16),
+  ///         ..Browsers::default()
+  ///       }),
+  ///       ..MinifyOptions::default()
+  ///     },
+  ///     Browsers::legacy(),
+  ///   )
+  ///   .unwrap();
+  ///
+  /// let modern_css = modern.to_css(PrinterOptions::default()).unwrap();
+  /// let legacy_css = legacy
+  ///   .to_css(PrinterOptions {
+  ///     targets: Some(Browsers::legacy()),
+  ///     ..PrinterOptions::default()
+  ///   })
+  ///   .unwrap();
+  /// assert_eq!(modern_css.code, ".foo {\n  color: lab(50% 20 -30);\n}\n");
+  /// assert!(legacy_css.code.starts_with(".foo {\n  color: #856caa;"));
+  /// ```
+  pub fn minify_dual(
+    &mut self,
+    options: MinifyOptions,
+    legacy_targets: Browsers,
+  ) -> Result<StyleSheet<'i, 'o, T>, Error<MinifyErrorKind>>
+  where
+    T: Clone,
+    T::AtRule: Clone,
+  {
+    let direction = options.direction;
+    let document_rules = options.document_rules;
+    let legacy_scroll_snap = options.legacy_scroll_snap;
+    self.minify(options)?;
+
+    let mut legacy = StyleSheet {
+      rules: self.rules.clone(),
+      sources: self.sources.clone(),
+      source_map_urls: self.source_map_urls.clone(),
+      content_hashes: self.content_hashes.clone(),
+      #[cfg(feature = "sourcemap")]
+      input_source_maps: self.input_source_maps.clone(),
+      options: self.options.clone(),
+    };
+
+    legacy.minify(MinifyOptions {
+      targets: Some(legacy_targets),
+      direction,
+      document_rules,
+      legacy_scroll_snap,
+      ..MinifyOptions::default()
+    })?;
+
+    Ok(legacy)
+  }
+
+  /// Allocates the scoped names of the style sheet with the name allocator in a deterministic order, sorted
+  /// by file path and then local name, rather than in the order they are printed. This keeps the names stable
+  /// when rules are reordered, or when the files of a bundle are loaded in a different order.
+  fn allocate_css_module_names(
+    &self,
+    config: &css_modules::Config,
+    allocator: &dyn NameAllocator,
+    options: &PrinterOptions,
+  ) -> Result<(), Error<PrinterErrorKind>> {
+    let recorder = Arc::new(NameRecorder::default());
+    let config = css_modules::Config {
+      name_allocator: Some(recorder.clone()),
+      ..config.clone()
+    };
+
+    let mut dest = String::new();
+    let mut printer = Printer::new(
+      &mut dest,
+      PrinterOptions {
+        project_root: options.project_root,
+        targets: options.targets,
+        ..PrinterOptions::default()
+      },
+    );
+    let mut references = HashMap::new();
+    let mut css_module = CssModule::new(
+      &config,
+      &self.sources,
+      &self.content_hashes,
+      options.project_root,
+      &mut references,
+    );
+    self.collect_declared_custom_properties(&mut css_module);
+    printer.css_module = Some(css_module);
+    self.rules.to_css(&mut printer)?;
+
+    for (path, local) in recorder.take_names() {
+      allocator.allocate(&path, &local);
+    }
+
     Ok(())
   }
 
+  /// Serializes the style sheet with each candidate set of choices for [Compression::Estimate],
+  /// and returns the one with the smallest estimated compressed size.
+  fn estimate_serialization_choices(
+    &self,
+    options: &PrinterOptions,
+  ) -> Result<SerializationChoices, Error<PrinterErrorKind>> {
+    let mut best = None;
+    for choices in SerializationChoices::CANDIDATES {
+      let mut dest = String::new();
+      let mut references = HashMap::new();
+      let mut printer = Printer::new(
+        &mut dest,
+        PrinterOptions {
+          minify: options.minify,
+          project_root: options.project_root,
+          targets: options.targets,
+          analyze_dependencies: options.analyze_dependencies.clone(),
+          pseudo_classes: options.pseudo_classes.clone(),
+          preserve_position_keywords: options.preserve_position_keywords,
+          shortest_units: options.shortest_units,
+          precision: options.precision,
+          ..PrinterOptions::default()
+        },
+      );
+      printer.sources = Some(&self.sources);
+      printer.choices = choices;
+      if let Some(config) = &self.options.css_modules {
+        let mut css_module = CssModule::new(
+          config,
+          &self.sources,
+          &self.content_hashes,
+          options.project_root,
+          &mut references,
+        );
+        self.collect_declared_custom_properties(&mut css_module);
+        printer.css_module = Some(css_module);
+      }
+      self.rules.to_css(&mut printer)?;
+
+      let size = estimate_compressed_size(&dest);
+      match best {
+        Some((_, best_size)) if best_size <= size => {}
+        _ => best = Some((choices, size)),
+      }
+    }
+
+    Ok(best.map(|(choices, _)| choices).unwrap_or(SerializationChoices::CANDIDATES[0]))
+  }
+
+  /// Serialize the style sheet, and return a [SizeReport](SizeReport) with the number of bytes each
+  /// top-level rule and source file contributed to the output. This is typically called after
+  /// [minify](StyleSheet::minify), with the same options that are used to produce the final output.
+  /// The size of each rule includes the rules nested within it, but not the whitespace between rules.
+  pub fn size_report(&self, options: PrinterOptions) -> Result<SizeReport, Error<PrinterErrorKind>> {
+    let rule_options = || PrinterOptions {
+      minify: options.minify,
+      project_root: options.project_root,
+      targets: options.targets,
+      analyze_dependencies: options.analyze_dependencies.clone(),
+      pseudo_classes: options.pseudo_classes.clone(),
+      preserve_position_keywords: options.preserve_position_keywords,
+      shortest_units: options.shortest_units,
+      precision: options.precision,
+      ..PrinterOptions::default()
+    };
+    let choices = match options.compression.choices() {
+      Some(choices) => choices,
+      None => self.estimate_serialization_choices(&options)?,
+    };
+
+    let remove_imports = matches!(&options.analyze_dependencies, Some(d) if d.remove_imports);
+    let mut rules = Vec::new();
+    for rule in &self.rules.0 {
+      match rule {
+        CssRule::Ignored => continue,
+        CssRule::Import(..) if remove_imports => continue,
+        _ => {}
+      }
+
+      let mut dest = String::new();
+      let mut references = HashMap::new();
+      let mut printer = Printer::new(&mut dest, rule_options());
+      printer.sources = Some(&self.sources);
+      printer.choices = choices;
+      if let Some(config) = &self.options.css_modules {
+        let mut css_module = CssModule::new(
+          config,
+          &self.sources,
+          &self.content_hashes,
+          options.project_root,
+          &mut references,
+        );
+        self.collect_declared_custom_properties(&mut css_module);
+        printer.css_module = Some(css_module);
+      }
+      rule.to_css(&mut printer)?;
+      let size = dest.len();
+      rules.push((dest, size, rule.loc()));
+    }
+
+    let total = self.to_css(options)?.code.len();
+    Ok(SizeReport::new(total, rules, &self.sources))
+  }
+
+  /// Collects the custom properties declared in the style sheet, which are renamed when the CSS modules
+  /// `custom_properties` option is enabled.
+  fn collect_declared_custom_properties(&self, css_module: &mut CssModule) {
+    if css_module.config.custom_properties && !css_module.config.dashed_idents {
+      self
+        .rules
+        .collect_declared_custom_properties(&mut css_module.declared_custom_properties);
+    }
+  }
+
   /// Serialize the style sheet to a CSS string.
   pub fn to_css(&self, options: PrinterOptions) -> Result<ToCssResult, Error<PrinterErrorKind>> {
+    if let Some(config) = &self.options.css_modules {
+      if let Some(allocator) = &config.name_allocator {
+        self.allocate_css_module_names(config, allocator.as_ref(), &options)?;
+      }
+    }
+
+    let choices = match options.compression.choices() {
+      Some(choices) => choices,
+      None => self.estimate_serialization_choices(&options)?,
+    };
+
     // Make sure we always have capacity > 0: https://github.com/napi-rs/napi-rs/issues/1124.
     let mut dest = String::with_capacity(1);
     let project_root = options.project_root.clone();
+    let banner = options.banner;
+    let footer = options.footer;
+    let icss = options.icss;
+    #[cfg(feature = "sourcemap")]
+    let source_map_options = options.source_map_options;
     let mut printer = Printer::new(&mut dest, options);
+    printer.choices = choices;
 
     #[cfg(feature = "sourcemap")]
     {
       printer.sources = Some(&self.sources);
     }
 
+    if printer.source_comments {
+      printer.sources = Some(&self.sources);
+    }
+
     #[cfg(feature = "sourcemap")]
     if printer.source_map.is_some() {
       printer.source_maps = self.sources.iter().enumerate().map(|(i, _)| self.source_map(i)).collect();
     }
 
+    let mut references = HashMap::new();
     if let Some(config) = &self.options.css_modules {
-      let mut references = HashMap::new();
-      printer.css_module = Some(CssModule::new(config, &self.sources, project_root, &mut references));
+      let mut css_module = CssModule::new(
+        config,
+        &self.sources,
+        &self.content_hashes,
+        project_root,
+        &mut references,
+      );
+      self.collect_declared_custom_properties(&mut css_module);
+      printer.css_module = Some(css_module);
+    }
 
-      self.rules.to_css(&mut printer)?;
-      printer.newline()?;
+    if let Some(banner) = banner {
+      printer.write_raw(banner)?;
+      printer.write_char('\n')?;
+    }
 
-      Ok(ToCssResult {
-        dependencies: printer.dependencies,
-        exports: Some(std::mem::take(
-          &mut printer.css_module.unwrap().exports_by_source_index[0],
-        )),
-        code: dest,
-        references: Some(references),
-      })
-    } else {
-      self.rules.to_css(&mut printer)?;
+    self.rules.to_css(&mut printer)?;
+    printer.newline()?;
+
+    if icss {
+      if let Some(css_module) = &printer.css_module {
+        let icss = css_modules::to_icss(
+          &css_module.exports_by_source_index[0],
+          css_module.references,
+          printer.minify,
+        );
+        if !icss.is_empty() {
+          if !printer.minify && !self.rules.0.is_empty() {
+            printer.write_char('\n')?;
+          }
+          printer.write_raw(&icss)?;
+        }
+      }
+    }
+
+    // Non-minified output already ends with a newline.
+    if let Some(footer) = footer {
+      if printer.minify {
+        printer.write_char('\n')?;
+      }
+      printer.write_raw(footer)?;
       printer.newline()?;
+    }
+
+    #[cfg(feature = "sourcemap")]
+    let mut map = None;
+    #[cfg(feature = "sourcemap")]
+    if let Some(source_map) = printer.source_map.take() {
+      let json = crate::sourcemap::source_map_to_json(source_map, &source_map_options).map_err(|_| Error {
+        kind: PrinterErrorKind::SourceMapError,
+        loc: None,
+      })?;
+      let url = if source_map_options.inline {
+        Some(format!(
+          "data:application/json;base64,{}",
+          data_encoding::BASE64.encode(json.as_bytes())
+        ))
+      } else {
+        map = Some(json);
+        source_map_options.url.map(|url| url.to_owned())
+      };
 
-      Ok(ToCssResult {
-        dependencies: printer.dependencies,
-        code: dest,
-        exports: None,
-        references: None,
-      })
+      if let Some(url) = url {
+        if printer.minify {
+          printer.write_char('\n')?;
+        }
+        printer.write_raw(&format!("/*# sourceMappingURL={} */", url))?;
+        printer.newline()?;
+      }
     }
+
+    let exports = printer
+      .css_module
+      .map(|mut css_module| std::mem::take(&mut css_module.exports_by_source_index[0]));
+    Ok(ToCssResult {
+      dependencies: printer.dependencies,
+      references: exports.as_ref().map(|_| references),
+      exports,
+      code: dest,
+      #[cfg(feature = "sourcemap")]
+      map,
+    })
   }
 }
 
@@ -341,7 +995,8 @@ impl<'i> StyleAttribute<'i> {
 
   /// Minify and transform the style attribute for the provided browser targets.
   pub fn minify(&mut self, options: MinifyOptions) {
-    let mut context = PropertyHandlerContext::new(options.targets, &options.unused_symbols);
+    let unused_symbols = UnusedSymbols::new(&options.unused_symbols);
+    let mut context = PropertyHandlerContext::new(options.targets, &unused_symbols);
     let mut handler = DeclarationHandler::new(options.targets);
     let mut important_handler = DeclarationHandler::new(options.targets);
     context.context = DeclarationContext::StyleAttribute;
@@ -368,6 +1023,8 @@ impl<'i> StyleAttribute<'i> {
       code: dest,
       exports: None,
       references: None,
+      #[cfg(feature = "sourcemap")]
+      map: None,
     })
   }
 }
@@ -1,8 +1,13 @@
 //! Browser target options.
 // This file is autogenerated by build-prefixes.js. DO NOT EDIT!
 
+#![allow(non_upper_case_globals)]
+
+use crate::{compat, prefixes, vendor_prefix::VendorPrefix};
+use bitflags::bitflags;
 #[cfg(any(feature = "serde", feature = "nodejs"))]
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Browser versions to compile CSS for.
 ///
@@ -34,6 +39,427 @@ pub struct Browsers {
   pub opera: Option<u32>,
   pub safari: Option<u32>,
   pub samsung: Option<u32>,
+  /// Compatibility data that overrides the data built into Lightning CSS.
+  /// See [with_feature_data](Browsers::with_feature_data).
+  #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))]
+  pub feature_data: Option<&'static FeatureData>,
+  /// Features that are always lowered, regardless of browser support.
+  #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))]
+  pub include: Features,
+  /// Features that are never lowered, regardless of browser support.
+  /// These take precedence over `include`.
+  #[cfg_attr(any(feature = "serde", feature = "nodejs"), serde(skip))]
+  pub exclude: Features,
+}
+
+impl Browsers {
+  /// Returns a copy of these targets that uses the given compatibility data in place of
+  /// the data built into Lightning CSS, for the features it covers.
+  ///
+  /// # Example
+  ///
+  /// This example treats CSS nesting as unsupported in all browsers, and `user-select`
+  /// as requiring a `-webkit-` prefix in all versions of Safari.
+  ///
+  /// ```
+  /// use lightningcss::targets::{Browsers, FeatureData, PrefixRange};
+  /// use lightningcss::vendor_prefix::VendorPrefix;
+  ///
+  /// let mut data = FeatureData::new();
+  /// data.set_support("css-nesting", Browsers::default()).unwrap();
+  /// data
+  ///   .set_prefixes(
+  ///     "user-select",
+  ///     vec![PrefixRange {
+  ///       prefix: VendorPrefix::WebKit,
+  ///       min: Browsers {
+  ///         safari: Some(3 << 16),
+  ///         ..Browsers::default()
+  ///       },
+  ///       max: Browsers::default(),
+  ///     }],
+  ///   )
+  ///   .unwrap();
+  ///
+  /// let targets = Browsers {
+  ///   safari: Some(16 << 16),
+  ///   ..Browsers::default()
+  /// }
+  /// .with_feature_data(Box::leak(Box::new(data)));
+  /// ```
+  pub fn with_feature_data(self, data: &'static FeatureData) -> Browsers {
+    Browsers {
+      feature_data: Some(data),
+      ..self
+    }
+  }
+
+  /// Returns whether a feature is forced to be compatible or incompatible by the
+  /// `include` and `exclude` flags or by user supplied feature data.
+  pub(crate) fn compatibility_override(&self, feature: compat::Feature) -> Option<bool> {
+    let flag = Features::from_compat(feature);
+    if self.exclude.intersects(flag) {
+      return Some(true);
+    }
+
+    if self.include.intersects(flag) {
+      return Some(false);
+    }
+
+    self.feature_data.and_then(|data| data.is_compatible(feature, *self))
+  }
+
+  /// Returns whether the 2009 flexbox syntax, e.g. `display: -webkit-box`, should be emitted.
+  pub(crate) fn is_flex_2009(&self) -> bool {
+    if self.exclude.contains(Features::LegacyFlexbox) {
+      return false;
+    }
+
+    self.include.contains(Features::LegacyFlexbox) || prefixes::is_flex_2009(*self)
+  }
+
+  /// Returns the vendor prefixes needed for a flexbox property, accounting for the `LegacyFlexbox`
+  /// flag. Firefox only implemented the 2009 syntax prefixed, and Microsoft only implemented
+  /// the 2012 syntax prefixed, so these prefixes always result in legacy syntax. The 2009 syntax
+  /// for WebKit is determined by [is_flex_2009](Browsers::is_flex_2009).
+  pub(crate) fn flex_prefixes(&self, feature: prefixes::Feature) -> VendorPrefix {
+    let mut prefixes = feature.prefixes_for(*self);
+    if self.exclude.contains(Features::LegacyFlexbox) {
+      prefixes.remove(VendorPrefix::Moz | VendorPrefix::Ms);
+    } else if self.include.contains(Features::LegacyFlexbox) {
+      prefixes |= VendorPrefix::Ms;
+    }
+    prefixes
+  }
+
+  fn versions(&self) -> [Option<u32>; 9] {
+    [
+      self.android,
+      self.chrome,
+      self.edge,
+      self.firefox,
+      self.ie,
+      self.ios_saf,
+      self.opera,
+      self.safari,
+      self.samsung,
+    ]
+  }
+}
+
+// Target presets. These are generated by build-prefixes.js from the bundled browserslist and caniuse data.
+impl Browsers {
+  /// Browsers matching the browserslist `defaults` query, i.e. `> 0.5%, last 2 versions, Firefox ESR, not dead`.
+  /// This is a sensible default for most websites.
+  pub fn defaults() -> Browsers {
+    Browsers {
+      android: Some(7012352),
+      chrome: Some(6750208),
+      edge: Some(7012352),
+      firefox: Some(6684672),
+      ios_saf: Some(786944),
+      opera: Some(6029312),
+      safari: Some(984576),
+      samsung: Some(1179648),
+      ..Browsers::default()
+    }
+  }
+
+  /// Browsers that support ES modules and CSS custom properties, which is a common baseline for
+  /// applications that ship a modern build, e.g. Chrome 61, Firefox 60, and Safari 11.
+  pub fn modern() -> Browsers {
+    Browsers {
+      android: Some(3997696),
+      chrome: Some(3997696),
+      edge: Some(5177344),
+      firefox: Some(3932160),
+      ios_saf: Some(720896),
+      opera: Some(3145728),
+      safari: Some(720896),
+      samsung: Some(524800),
+      ..Browsers::default()
+    }
+  }
+
+  /// Older browsers that are still found in the wild, including Internet Explorer 11, Android 4.4 and Safari 9.
+  /// This enables most of the lowering and prefixing that Lightning CSS supports.
+  pub fn legacy() -> Browsers {
+    Browsers {
+      android: Some(263168),
+      chrome: Some(3211264),
+      edge: Some(786432),
+      firefox: Some(3407872),
+      ie: Some(720896),
+      ios_saf: Some(589824),
+      opera: Some(2359296),
+      safari: Some(589824),
+      samsung: Some(327680),
+      ..Browsers::default()
+    }
+  }
+
+  /// The latest version of each browser that is not dead. Only features that are not yet supported by
+  /// current browsers are lowered.
+  pub fn fully_supported() -> Browsers {
+    Browsers {
+      android: Some(7077888),
+      chrome: Some(7077888),
+      edge: Some(7077888),
+      firefox: Some(7012352),
+      ios_saf: Some(1048832),
+      opera: Some(6094848),
+      safari: Some(1048832),
+      samsung: Some(1245184),
+      ..Browsers::default()
+    }
+  }
+}
+
+bitflags! {
+  /// Bit flags that represent individual syntax lowerings performed by Lightning CSS.
+  ///
+  /// These are used by the `include` and `exclude` fields of [Browsers](Browsers) to
+  /// override the decision made from browser compatibility data. Excluded features are
+  /// always kept verbatim, and included features are always lowered, even when all of the
+  /// targets support them.
+  ///
+  /// # Example
+  ///
+  /// This example keeps `lab()` colors and logical properties as written, while still
+  /// lowering everything else that Safari 12 doesn't support.
+  ///
+  /// ```
+  /// use lightningcss::targets::{Browsers, Features};
+  ///
+  /// let targets = Browsers {
+  ///   safari: Some(12 << 16),
+  ///   exclude: Features::LabColors | Features::LogicalProperties,
+  ///   ..Browsers::default()
+  /// };
+  /// ```
+  #[derive(Default)]
+  pub struct Features: u32 {
+    /// CSS nesting.
+    const Nesting                     = 1 << 0;
+    /// The `:not()` selector with a selector list.
+    const SelectorNotList             = 1 << 1;
+    /// The `:dir()` selector.
+    const SelectorDir                 = 1 << 2;
+    /// The `:lang()` selector with multiple languages.
+    const SelectorLangList            = 1 << 3;
+    /// The `:is()` selector.
+    const SelectorIs                  = 1 << 4;
+    /// Media query range syntax, e.g. `(width >= 100px)`.
+    const MediaRangeSyntax            = 1 << 5;
+    /// Media query interval syntax, e.g. `(100px <= width <= 200px)`.
+    const MediaIntervalSyntax         = 1 << 6;
+    /// The `@custom-media` rule.
+    const CustomMediaQueries          = 1 << 7;
+    /// The `lab()` and `lch()` color functions.
+    const LabColors                   = 1 << 8;
+    /// The `oklab()` and `oklch()` color functions.
+    const OklabColors                 = 1 << 9;
+    /// Display P3 colors.
+    const P3Colors                    = 1 << 10;
+    /// The `color()` function.
+    const ColorFunction               = 1 << 11;
+    /// Space separated `rgb()` and `hsl()` color syntax.
+    const SpaceSeparatedColorNotation = 1 << 12;
+    /// Hex colors with an alpha component, e.g. `#rrggbbaa`.
+    const HexAlphaColors              = 1 << 13;
+    /// Logical properties and values, such as `margin-inline-start` or `text-align: start`.
+    const LogicalProperties           = 1 << 14;
+    /// Gradients with double position color stops.
+    const DoublePositionGradients     = 1 << 15;
+    /// The `clamp()` function.
+    const Clamp                       = 1 << 16;
+    /// The `place-content`, `place-items` and `place-self` shorthands.
+    const PlaceProperties             = 1 << 17;
+    /// The two value `overflow` shorthand.
+    const OverflowShorthand           = 1 << 18;
+    /// The `image-set()` function.
+    const ImageSet                    = 1 << 19;
+    /// The `system-ui` font family.
+    const FontFamilySystemUi          = 1 << 20;
+    /// The legacy 2009 (`display: -webkit-box`) and 2012 (`display: -ms-flexbox`) flexbox syntaxes.
+    /// Including this flag emits the legacy properties for all flexbox declarations, e.g. for
+    /// old Android WebViews and IE 10, and excluding it only emits the standard syntax.
+    const LegacyFlexbox               = 1 << 21;
+    /// All selector lowerings.
+    const Selectors = Self::Nesting.bits | Self::SelectorNotList.bits | Self::SelectorDir.bits
+      | Self::SelectorLangList.bits | Self::SelectorIs.bits;
+    /// All media query lowerings.
+    const MediaQueries = Self::MediaRangeSyntax.bits | Self::MediaIntervalSyntax.bits
+      | Self::CustomMediaQueries.bits;
+    /// All color lowerings.
+    const Colors = Self::LabColors.bits | Self::OklabColors.bits | Self::P3Colors.bits
+      | Self::ColorFunction.bits | Self::SpaceSeparatedColorNotation.bits | Self::HexAlphaColors.bits;
+  }
+}
+
+impl Features {
+  fn from_compat(feature: compat::Feature) -> Features {
+    use compat::Feature::*;
+    match feature {
+      CssNesting => Features::Nesting,
+      CssNotSelList => Features::SelectorNotList,
+      CssDirPseudo => Features::SelectorDir,
+      LangList => Features::SelectorLangList,
+      CssMatchesPseudo => Features::SelectorIs,
+      MediaRangeSyntax => Features::MediaRangeSyntax,
+      MediaIntervalSyntax => Features::MediaIntervalSyntax,
+      CustomMediaQueries => Features::CustomMediaQueries,
+      LabColors => Features::LabColors,
+      OklabColors => Features::OklabColors,
+      P3Colors => Features::P3Colors,
+      ColorFunction => Features::ColorFunction,
+      SpaceSeparatedColorFunction => Features::SpaceSeparatedColorNotation,
+      CssRrggbbaa => Features::HexAlphaColors,
+      LogicalBorderRadius
+      | LogicalBorderShorthand
+      | LogicalBorders
+      | LogicalContainIntrinsicSize
+      | LogicalInset
+      | LogicalMargin
+      | LogicalMarginShorthand
+      | LogicalPadding
+      | LogicalPaddingShorthand
+      | LogicalSize
+      | LogicalTextAlign => Features::LogicalProperties,
+      DoublePositionGradients => Features::DoublePositionGradients,
+      Clamp => Features::Clamp,
+      PlaceContent | PlaceItems | PlaceSelf => Features::PlaceProperties,
+      OverflowShorthand => Features::OverflowShorthand,
+      ImageSet => Features::ImageSet,
+      FontFamilySystemUi => Features::FontFamilySystemUi,
+      _ => Features::empty(),
+    }
+  }
+}
+
+/// A range of browser versions that require a vendor prefix for a feature.
+#[derive(Debug, Clone)]
+pub struct PrefixRange {
+  /// The vendor prefix that is required.
+  pub prefix: VendorPrefix,
+  /// The first version of each browser that requires the prefix.
+  /// Browsers that are omitted never require it.
+  pub min: Browsers,
+  /// The last version of each browser that requires the prefix.
+  /// Browsers that are omitted require it in all versions after `min`.
+  pub max: Browsers,
+}
+
+/// An error returned when [FeatureData](FeatureData) is given a feature name that Lightning CSS does not know about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownFeatureError(pub String);
+
+impl std::fmt::Display for UnknownFeatureError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Unknown feature `{}`", self.0)
+  }
+}
+
+impl std::error::Error for UnknownFeatureError {}
+
+/// User supplied browser compatibility data, which overrides the data built into Lightning CSS
+/// for the features it covers. Use [Browsers::with_feature_data](Browsers::with_feature_data) to apply it.
+///
+/// Two kinds of data are supported: the minimum browser versions that support a feature,
+/// which determine whether syntax is lowered, and the vendor prefixes that are emitted, either for
+/// ranges of browser versions or for all targets.
+/// Feature names for support data are kebab-case identifiers such as `css-nesting`, `lab-colors`,
+/// or `overflow-clip-margin`. Feature names for prefix data are property, selector, or at-rule names
+/// such as `user-select`, `::placeholder`, or `@keyframes`.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureData {
+  support: HashMap<compat::Feature, Browsers>,
+  prefixes: HashMap<prefixes::Feature, Vec<PrefixRange>>,
+  fixed_prefixes: HashMap<prefixes::Feature, VendorPrefix>,
+}
+
+impl FeatureData {
+  /// Creates an empty set of compatibility data.
+  pub fn new() -> FeatureData {
+    FeatureData::default()
+  }
+
+  /// Sets the minimum versions of each browser that support a feature.
+  /// Browsers that are omitted are considered not to support it.
+  pub fn set_support(&mut self, feature: &str, min: Browsers) -> Result<(), UnknownFeatureError> {
+    let feature = compat::Feature::from_name(feature).ok_or_else(|| UnknownFeatureError(feature.into()))?;
+    self.support.insert(feature, min);
+    Ok(())
+  }
+
+  /// Sets the browser versions that require vendor prefixes for a feature.
+  /// An empty list means that the feature never requires a prefix.
+  pub fn set_prefixes(&mut self, feature: &str, ranges: Vec<PrefixRange>) -> Result<(), UnknownFeatureError> {
+    let feature = prefixes::Feature::from_name(feature).ok_or_else(|| UnknownFeatureError(feature.into()))?;
+    self.fixed_prefixes.remove(&feature);
+    self.prefixes.insert(feature, ranges);
+    Ok(())
+  }
+
+  /// Sets the vendor prefixes that are always emitted for a feature, regardless of the targeted
+  /// browser versions. This is useful to control which prefixed variants of pseudo elements and
+  /// pseudo classes such as `::selection`, `::placeholder`, or `:fullscreen` are generated.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lightningcss::targets::FeatureData;
+  /// use lightningcss::vendor_prefix::VendorPrefix;
+  ///
+  /// let mut data = FeatureData::new();
+  /// data.set_fixed_prefixes("::selection", VendorPrefix::Moz).unwrap();
+  /// data.set_fixed_prefixes(":fullscreen", VendorPrefix::WebKit).unwrap();
+  /// ```
+  pub fn set_fixed_prefixes(&mut self, feature: &str, prefixes: VendorPrefix) -> Result<(), UnknownFeatureError> {
+    let feature = prefixes::Feature::from_name(feature).ok_or_else(|| UnknownFeatureError(feature.into()))?;
+    self.prefixes.remove(&feature);
+    self.fixed_prefixes.insert(feature, prefixes);
+    Ok(())
+  }
+
+  pub(crate) fn is_compatible(&self, feature: compat::Feature, browsers: Browsers) -> Option<bool> {
+    let min = self.support.get(&feature)?;
+    Some(
+      browsers
+        .versions()
+        .into_iter()
+        .zip(min.versions())
+        .all(|(version, min)| match (version, min) {
+          (Some(version), Some(min)) => version >= min,
+          (Some(_), None) => false,
+          (None, _) => true,
+        }),
+    )
+  }
+
+  pub(crate) fn prefixes_for(&self, feature: prefixes::Feature, browsers: Browsers) -> Option<VendorPrefix> {
+    if let Some(prefixes) = self.fixed_prefixes.get(&feature) {
+      return Some(VendorPrefix::None | *prefixes);
+    }
+
+    let ranges = self.prefixes.get(&feature)?;
+    let mut prefixes = VendorPrefix::None;
+    for range in ranges {
+      let matches = browsers
+        .versions()
+        .into_iter()
+        .zip(range.min.versions().into_iter().zip(range.max.versions()))
+        .any(|(version, (min, max))| match (version, min, max) {
+          (Some(version), Some(min), Some(max)) => version >= min && version <= max,
+          (Some(version), Some(min), None) => version >= min,
+          _ => false,
+        });
+      if matches {
+        prefixes |= range.prefix;
+      }
+    }
+    Some(prefixes)
+  }
 }
 
 #[cfg(feature = "browserslist")]
@@ -47,6 +473,21 @@ impl Browsers {
     Self::from_distribs(resolve(query, &Opts::new())?)
   }
 
+  /// Parses a single browserslist query string, which may combine multiple queries
+  /// (e.g. `"last 2 versions, not dead"`), into Lightning CSS targets.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lightningcss::targets::Browsers;
+  ///
+  /// let targets = Browsers::from_browserslist_query("safari >= 13, firefox esr").unwrap();
+  /// assert!(targets.unwrap().safari.is_some());
+  /// ```
+  pub fn from_browserslist_query(query: &str) -> Result<Option<Browsers>, browserslist::Error> {
+    Self::from_browserslist([query])
+  }
+
   /// Finds browserslist configuration, selects queries by environment and loads the resulting queries into LightningCSS targets.
   ///
   /// Configuration resolution is modeled after the original `browserslist` nodeJS package.
@@ -74,6 +515,22 @@ impl Browsers {
     Self::from_distribs(execute(&Opts::new())?)
   }
 
+  /// Finds browserslist configuration starting from the given path rather than the current directory,
+  /// and loads the resulting queries into Lightning CSS targets. The path is usually the file being compiled,
+  /// so that the closest `browserslist`, `.browserslistrc` or `package.json` configuration file applies.
+  ///
+  /// Environment variables are resolved as described in [load_browserslist](Browsers::load_browserslist).
+  pub fn load_browserslist_from<P: AsRef<std::path::Path>>(
+    path: P,
+  ) -> Result<Option<Browsers>, browserslist::Error> {
+    use browserslist::{execute, Opts};
+
+    let path = path.as_ref().to_string_lossy();
+    let mut opts = Opts::new();
+    opts.path(&path);
+    Self::from_distribs(execute(&opts)?)
+  }
+
   fn from_distribs(distribs: Vec<browserslist::Distrib>) -> Result<Option<Browsers>, browserslist::Error> {
     let mut browsers = Browsers::default();
     let mut has_any = false;
@@ -0,0 +1,124 @@
+//! Matching of the symbols passed via [MinifyOptions::unused_symbols](crate::stylesheet::MinifyOptions::unused_symbols).
+//!
+//! Each symbol is interpreted as follows:
+//!
+//! * `.foo` matches class selectors only.
+//! * `#foo` matches id selectors only.
+//! * `[foo]` matches attribute selectors for the `foo` attribute, with any operator and value.
+//! * Any other name matches both classes and ids, as well as `@keyframes`, `@counter-style`,
+//!   `@font-palette-values`, `@property` and custom property names.
+//!
+//! Any of these may contain a `*` wildcard, which matches any sequence of characters,
+//! e.g. `.btn-*` or `[data-test-*]`.
+
+use std::collections::HashSet;
+
+/// The kind of symbol being matched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SymbolKind {
+  /// A named symbol, such as a `@keyframes` name.
+  Name,
+  /// A class selector.
+  Class,
+  /// An id selector.
+  Id,
+  /// An attribute selector.
+  Attribute,
+}
+
+/// A compiled set of unused symbols.
+#[derive(Debug, Default)]
+pub(crate) struct UnusedSymbols {
+  names: HashSet<String>,
+  classes: HashSet<String>,
+  ids: HashSet<String>,
+  attributes: HashSet<String>,
+  patterns: Vec<(SymbolKind, String)>,
+}
+
+impl UnusedSymbols {
+  pub fn new(symbols: &HashSet<String>) -> Self {
+    let mut unused = UnusedSymbols::default();
+    for symbol in symbols {
+      let (kind, name) = if let Some(name) = symbol.strip_prefix('.') {
+        (SymbolKind::Class, name)
+      } else if let Some(name) = symbol.strip_prefix('#') {
+        (SymbolKind::Id, name)
+      } else if let Some(name) = symbol.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        (SymbolKind::Attribute, name)
+      } else {
+        (SymbolKind::Name, symbol.as_str())
+      };
+
+      if name.contains('*') {
+        unused.patterns.push((kind, name.to_owned()));
+        continue;
+      }
+
+      let set = match kind {
+        SymbolKind::Name => &mut unused.names,
+        SymbolKind::Class => &mut unused.classes,
+        SymbolKind::Id => &mut unused.ids,
+        SymbolKind::Attribute => &mut unused.attributes,
+      };
+      set.insert(name.to_owned());
+    }
+
+    unused
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.names.is_empty()
+      && self.classes.is_empty()
+      && self.ids.is_empty()
+      && self.attributes.is_empty()
+      && self.patterns.is_empty()
+  }
+
+  /// Returns whether the symbol of the given kind is unused.
+  pub fn contains(&self, kind: SymbolKind, name: &str) -> bool {
+    // Plain names match classes and ids as well, for backward compatibility.
+    let applies =
+      |k: SymbolKind| k == kind || (k == SymbolKind::Name && matches!(kind, SymbolKind::Class | SymbolKind::Id));
+    let found = match kind {
+      SymbolKind::Name => self.names.contains(name),
+      SymbolKind::Class => self.classes.contains(name) || self.names.contains(name),
+      SymbolKind::Id => self.ids.contains(name) || self.names.contains(name),
+      SymbolKind::Attribute => self.attributes.contains(name),
+    };
+
+    found
+      || self
+        .patterns
+        .iter()
+        .any(|(k, pattern)| applies(*k) && matches_pattern(pattern, name))
+  }
+}
+
+/// Returns whether the name matches a pattern, where `*` matches any sequence of characters.
+pub(crate) fn matches_pattern(pattern: &str, name: &str) -> bool {
+  let mut parts = pattern.split('*');
+  let first = parts.next().unwrap_or_default();
+  let mut rest = match name.strip_prefix(first) {
+    Some(rest) => rest,
+    None => return false,
+  };
+
+  let mut parts = parts.peekable();
+  if parts.peek().is_none() {
+    return rest.is_empty();
+  }
+
+  while let Some(part) = parts.next() {
+    if parts.peek().is_none() {
+      return rest.ends_with(part);
+    }
+
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+
+  true
+}
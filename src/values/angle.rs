@@ -1,11 +1,11 @@
 //! CSS angle values.
 
 use super::calc::Calc;
-use super::length::serialize_dimension;
+use super::length::{serialize_dimension, serialize_shortest_dimension};
 use super::number::CSSNumber;
 use super::percentage::DimensionPercentage;
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{NumericKind, Printer};
 use crate::traits::{
   impl_op,
   private::{AddInternal, TryAdd},
@@ -120,8 +120,22 @@ impl ToCss for Angle {
       }
       Angle::Turn(val) => (*val, "turn"),
     };
+    if dest.minify && dest.shortest_units {
+      let deg = self.to_degrees();
+      return serialize_shortest_dimension(
+        &[
+          (value, unit),
+          (deg, "deg"),
+          (deg * 200.0 / 180.0, "grad"),
+          (self.to_radians(), "rad"),
+          (deg / 360.0, "turn"),
+        ],
+        NumericKind::Angle,
+        dest,
+      );
+    }
 
-    serialize_dimension(value, unit, dest)
+    serialize_dimension(dest.precision.round(value, NumericKind::Angle), unit, dest)
   }
 }
 
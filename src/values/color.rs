@@ -300,6 +300,21 @@ impl CssColor {
     P3::from(self).into()
   }
 
+  /// Returns the compatibility feature required by the color space of this color, if any.
+  pub(crate) fn get_feature(&self) -> Option<Feature> {
+    match self {
+      CssColor::LAB(lab) => match &**lab {
+        LABColor::LAB(..) | LABColor::LCH(..) => Some(Feature::LabColors),
+        LABColor::OKLAB(..) | LABColor::OKLCH(..) => Some(Feature::OklabColors),
+      },
+      CssColor::Predefined(predefined) => match &**predefined {
+        PredefinedColor::DisplayP3(..) => Some(Feature::P3Colors),
+        _ => Some(Feature::ColorFunction),
+      },
+      CssColor::CurrentColor | CssColor::RGBA(_) | CssColor::Float(..) => None,
+    }
+  }
+
   pub(crate) fn get_possible_fallbacks(&self, targets: Browsers) -> ColorFallbackKind {
     // Fallbacks occur in levels: Oklab -> Lab -> P3 -> RGB. We start with all levels
     // below and including the authored color space, and remove the ones that aren't
@@ -432,7 +447,7 @@ impl ToCss for CssColor {
       CssColor::RGBA(color) => {
         if color.alpha == 255 {
           let hex: u32 = ((color.red as u32) << 16) | ((color.green as u32) << 8) | (color.blue as u32);
-          if let Some(name) = short_color_name(hex) {
+          if let Some(name) = short_color_name(hex).filter(|_| !dest.choices.hex_colors) {
             return dest.write_str(name);
           }
 
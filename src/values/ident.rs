@@ -1,5 +1,6 @@
 //! CSS identifiers.
 
+use crate::css_modules::NameKind;
 use crate::error::{ParserError, PrinterError};
 use crate::printer::Printer;
 use crate::properties::css_modules::Specifier;
@@ -53,9 +54,92 @@ impl<'i> ToCss for CustomIdent<'i> {
   }
 }
 
+impl<'i> CustomIdent<'i> {
+  /// Writes the identifier, only adding a hash if CSS modules scope the given kind of name.
+  pub(crate) fn to_css_scoped<W>(&self, dest: &mut Printer<W>, kind: NameKind) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_scoped_ident(&self.0, kind)
+  }
+
+  /// Writes the identifier as is, even when CSS modules are enabled, e.g. for names
+  /// referenced via the `global()` function.
+  pub(crate) fn to_css_unscoped<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    serialize_identifier(&self.0, dest)?;
+    Ok(())
+  }
+}
+
 /// A list of CSS [`<custom-ident>`](https://www.w3.org/TR/css-values-4/#custom-idents) values.
 pub type CustomIdentList<'i> = SmallVec<[CustomIdent<'i>; 1]>;
 
+/// Either the `none` keyword, or a space-separated list of [`<custom-ident>`](https://www.w3.org/TR/css-values-4/#custom-idents) values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum NoneOrCustomIdentList<'i> {
+  /// The `none` keyword.
+  None,
+  /// A list of idents.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Idents(CustomIdentList<'i>),
+}
+
+impl<'i> Parse<'i> for NoneOrCustomIdentList<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(NoneOrCustomIdentList::None);
+    }
+
+    let mut idents = CustomIdentList::new();
+    while let Ok(ident) = input.try_parse(CustomIdent::parse) {
+      if ident.0.eq_ignore_ascii_case("none") {
+        return Err(input.new_custom_error(ParserError::InvalidValue));
+      }
+      idents.push(ident);
+    }
+
+    if idents.is_empty() {
+      return Err(input.new_error_for_next_token());
+    }
+
+    Ok(NoneOrCustomIdentList::Idents(idents))
+  }
+}
+
+impl<'i> ToCss for NoneOrCustomIdentList<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      NoneOrCustomIdentList::None => dest.write_str("none"),
+      NoneOrCustomIdentList::Idents(idents) => {
+        let mut first = true;
+        for ident in idents {
+          if first {
+            first = false;
+          } else {
+            dest.write_char(' ')?;
+          }
+          ident.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
 /// A CSS [`<dashed-ident>`](https://www.w3.org/TR/css-values-4/#dashed-idents) declaration.
 ///
 /// Dashed idents are used in cases where an identifier can be either author defined _or_ CSS-defined.
@@ -139,7 +223,7 @@ impl<'i> ToCss for DashedIdentReference<'i> {
   {
     match &mut dest.css_module {
       Some(css_module) if css_module.config.dashed_idents => {
-        if let Some(name) = css_module.reference_dashed(&self.ident.0, &self.from, dest.loc.source_index) {
+        if let Some(name) = css_module.reference_dashed(&self.ident.0, &self.from, dest.loc) {
           dest.write_str("--")?;
           serialize_name(&name, dest)?;
           return Ok(());
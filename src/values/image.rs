@@ -4,12 +4,14 @@ use super::color::ColorFallbackKind;
 use super::gradient::*;
 use super::resolution::Resolution;
 use crate::compat;
-use crate::dependencies::{Dependency, UrlDependency};
+use crate::dependencies::{Dependency, Location, PaintDependency, UrlDependency};
 use crate::error::{ParserError, PrinterError};
 use crate::prefixes::{is_webkit_gradient, Feature};
-use crate::printer::Printer;
+use crate::printer::{Printer, UrlKind};
+use crate::properties::custom::TokenList;
 use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, ToCss};
+use crate::values::ident::Ident;
 use crate::values::string::CowArcStr;
 use crate::values::url::Url;
 use crate::vendor_prefix::VendorPrefix;
@@ -39,6 +41,8 @@ pub enum Image<'i> {
   Gradient(Box<Gradient>),
   /// An `image-set()`.
   ImageSet(ImageSet<'i>),
+  /// A `paint()` function, referencing a CSS Painting API worklet.
+  Paint(PaintImage<'i>),
 }
 
 impl<'i> Default for Image<'i> {
@@ -323,6 +327,10 @@ impl<'i> Parse<'i> for Image<'i> {
       return Ok(Image::ImageSet(image_set));
     }
 
+    if let Ok(paint) = input.try_parse(PaintImage::parse) {
+      return Ok(Image::Paint(paint));
+    }
+
     Err(input.new_error_for_next_token())
   }
 }
@@ -337,7 +345,75 @@ impl<'i> ToCss for Image<'i> {
       Image::Url(url) => url.to_css(dest),
       Image::Gradient(grad) => grad.to_css(dest),
       Image::ImageSet(image_set) => image_set.to_css(dest),
+      Image::Paint(paint) => paint.to_css(dest),
+    }
+  }
+}
+
+/// A CSS [`paint()`](https://drafts.css-houdini.org/css-paint-api/#paint-notation) function.
+///
+/// The arguments are passed to the paint worklet as-is, so they are preserved as a raw token list
+/// and printed exactly as written. Arguments containing `var()` or other substitution functions
+/// cause the whole declaration to be parsed as an unparsed property instead.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub struct PaintImage<'i> {
+  /// The name of the paint worklet.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  pub name: Ident<'i>,
+  /// The arguments passed to the paint worklet.
+  pub arguments: TokenList<'i>,
+  /// The location where the `paint()` function was seen in the CSS source file.
+  pub loc: Location,
+}
+
+impl<'i> Parse<'i> for PaintImage<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let loc = input.current_source_location();
+    input.expect_function_matching("paint")?;
+    input.parse_nested_block(|input| {
+      let name = Ident::parse(input)?;
+      let arguments = if input.try_parse(|input| input.expect_comma()).is_ok() {
+        TokenList::parse_raw(input, 0)?
+      } else {
+        TokenList(vec![])
+      };
+
+      Ok(PaintImage {
+        name,
+        arguments,
+        loc: loc.into(),
+      })
+    })
+  }
+}
+
+impl<'i> ToCss for PaintImage<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if dest.dependencies.is_some() {
+      let dep = PaintDependency::new(self, dest.filename());
+      if let Some(dependencies) = &mut dest.dependencies {
+        dependencies.push(Dependency::Paint(dep));
+      }
+    }
+
+    dest.write_str("paint(")?;
+    self.name.to_css(dest)?;
+    if !self.arguments.0.is_empty() {
+      dest.delim(',', false)?;
+      self.arguments.to_css(dest, true)?;
     }
+    dest.write_char(')')
   }
 }
 
@@ -450,6 +526,7 @@ impl<'i> Parse<'i> for ImageSetOption<'i> {
     let image = if let Ok(url) = input.try_parse(|input| input.expect_url_or_string()) {
       Image::Url(Url {
         url: url.into(),
+        modifiers: Vec::new(),
         loc: loc.into(),
       })
     } else {
@@ -488,12 +565,13 @@ impl<'i> ImageSetOption<'i> {
           None
         };
         if let Some(dep) = dep {
-          serialize_string(&dep.placeholder, dest)?;
+          dest.write_string(&dep.placeholder)?;
           if let Some(dependencies) = &mut dest.dependencies {
             dependencies.push(Dependency::Url(dep))
           }
         } else {
-          serialize_string(&url.url, dest)?;
+          let url = dest.rewrite_url(url.url.as_ref(), UrlKind::Url);
+          dest.write_string(&url)?;
         }
       }
       _ => self.image.to_css(dest)?,
@@ -513,7 +591,7 @@ impl<'i> ImageSetOption<'i> {
 
     if let Some(file_type) = &self.file_type {
       dest.write_str(" type(")?;
-      serialize_string(&file_type, dest)?;
+      dest.write_string(file_type)?;
       dest.write_char(')')?;
     }
 
@@ -5,7 +5,7 @@ use super::calc::{Calc, MathFunction};
 use super::number::CSSNumber;
 use super::percentage::DimensionPercentage;
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{NumericKind, Printer, PrinterOptions};
 use crate::traits::TrySign;
 use crate::traits::{
   private::{AddInternal, TryAdd},
@@ -443,6 +443,7 @@ impl ToCss for LengthValue {
     W: std::fmt::Write,
   {
     let (value, unit) = self.to_unit_value();
+    let value = dest.precision.round(value, NumericKind::Length);
 
     // The unit can be omitted if the value is zero, except inside calc()
     // expressions, where unitless numbers won't be parsed as dimensions.
@@ -493,6 +494,59 @@ where
   }
 }
 
+/// Serializes the shortest of the given equivalent dimensions. Every candidate is rounded
+/// to the printer's precision first. The first candidate is always used as the baseline, and
+/// the others are only chosen if they are strictly shorter and can be printed without losing
+/// precision.
+pub(crate) fn serialize_shortest_dimension<W>(
+  candidates: &[(f32, &str)],
+  kind: NumericKind,
+  dest: &mut Printer<W>,
+) -> Result<(), PrinterError>
+where
+  W: std::fmt::Write,
+{
+  let mut best = String::new();
+  for (i, (value, unit)) in candidates.iter().enumerate() {
+    let value = dest.precision.round(*value, kind);
+    if i > 0 && !is_exact(value) {
+      continue;
+    }
+
+    let mut s = String::new();
+    let mut printer = Printer::new(
+      &mut s,
+      PrinterOptions {
+        minify: dest.minify,
+        targets: dest.targets,
+        shortest_units: dest.shortest_units,
+        precision: dest.precision,
+        ..PrinterOptions::default()
+      },
+    );
+    printer.in_calc = dest.in_calc;
+    serialize_dimension(value, unit, &mut printer)?;
+    if i == 0 || s.len() < best.len() {
+      best = s;
+    }
+  }
+
+  dest.write_str(&best)
+}
+
+/// Returns whether the number survives serialization unchanged.
+fn is_exact(value: f32) -> bool {
+  let mut s = String::new();
+  if cssparser::ToCss::to_css(&value, &mut s).is_err() {
+    return false;
+  }
+
+  match s.parse::<f32>() {
+    Ok(parsed) => (parsed - value).abs() <= value.abs() * 1e-6,
+    Err(_) => false,
+  }
+}
+
 impl LengthValue {
   /// Attempts to convert the value to pixels.
   /// Returns `None` if the conversion is not possible.
@@ -3,7 +3,7 @@
 use super::angle::impl_try_from_angle;
 use super::calc::Calc;
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{NumericKind, Printer};
 use crate::traits::private::AddInternal;
 use crate::traits::{Map, Op, Parse, Sign, ToCss, Zero};
 use cssparser::*;
@@ -34,20 +34,27 @@ impl ToCss for CSSNumber {
   where
     W: std::fmt::Write,
   {
-    let number = *self;
-    if number != 0.0 && number.abs() < 1.0 {
-      let mut s = String::new();
-      cssparser::ToCss::to_css(self, &mut s)?;
-      if number < 0.0 {
-        dest.write_char('-')?;
-        dest.write_str(s.trim_start_matches("-0"))
-      } else {
-        dest.write_str(s.trim_start_matches('0'))
-      }
+    serialize_number(dest.precision.round(*self, NumericKind::Number), dest)
+  }
+}
+
+/// Serializes a number without a leading zero, e.g. `.5`.
+pub(crate) fn serialize_number<W>(number: f32, dest: &mut Printer<W>) -> Result<(), PrinterError>
+where
+  W: std::fmt::Write,
+{
+  if number != 0.0 && number.abs() < 1.0 {
+    let mut s = String::new();
+    cssparser::ToCss::to_css(&number, &mut s)?;
+    if number < 0.0 {
+      dest.write_char('-')?;
+      dest.write_str(s.trim_start_matches("-0"))
     } else {
-      cssparser::ToCss::to_css(self, dest)?;
-      Ok(())
+      dest.write_str(s.trim_start_matches('0'))
     }
+  } else {
+    cssparser::ToCss::to_css(&number, dest)?;
+    Ok(())
   }
 }
 
@@ -4,7 +4,7 @@ use super::angle::{impl_try_from_angle, Angle};
 use super::calc::{Calc, MathFunction};
 use super::number::CSSNumber;
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{NumericKind, Printer};
 use crate::traits::private::AddInternal;
 use crate::traits::{impl_op, private::TryAdd, Op, Parse, Sign, ToCss, TryMap, TryOp, TrySign, Zero};
 #[cfg(feature = "visitor")]
@@ -41,20 +41,21 @@ impl ToCss for Percentage {
     W: std::fmt::Write,
   {
     use cssparser::ToCss;
-    let int_value = if (self.0 * 100.0).fract() == 0.0 {
-      Some(self.0 as i32)
+    let value = dest.precision.round(self.0 * 100.0, NumericKind::Percentage) / 100.0;
+    let int_value = if (value * 100.0).fract() == 0.0 {
+      Some(value as i32)
     } else {
       None
     };
     let percent = Token::Percentage {
-      has_sign: self.0 < 0.0,
-      unit_value: self.0,
+      has_sign: value < 0.0,
+      unit_value: value,
       int_value,
     };
-    if self.0 != 0.0 && self.0.abs() < 0.01 {
+    if value != 0.0 && value.abs() < 0.01 {
       let mut s = String::new();
       percent.to_css(&mut s)?;
-      if self.0 < 0.0 {
+      if value < 0.0 {
         dest.write_char('-')?;
         dest.write_str(s.trim_start_matches("-0"))
       } else {
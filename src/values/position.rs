@@ -170,6 +170,10 @@ impl ToCss for Position {
   where
     W: std::fmt::Write,
   {
+    if dest.preserve_position_keywords {
+      return self.to_css_with_keywords(dest);
+    }
+
     match (&self.x, &self.y) {
       (x_pos @ &HorizontalPosition::Side { side, offset: Some(_) }, &VerticalPosition::Length(ref y_lp))
         if side != HorizontalPositionKeyword::Left =>
@@ -272,6 +276,43 @@ impl ToCss for Position {
   }
 }
 
+impl Position {
+  /// Serializes the position without converting keywords to lengths or percentages.
+  fn to_css_with_keywords<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match (&self.x, &self.y) {
+      (x_pos @ HorizontalPosition::Side { offset: Some(_), .. }, VerticalPosition::Length(y_lp)) => {
+        x_pos.to_css(dest)?;
+        dest.write_str(" top ")?;
+        y_lp.to_css(dest)
+      }
+      (HorizontalPosition::Length(x_lp), y_pos @ VerticalPosition::Side { offset: Some(_), .. }) => {
+        dest.write_str("left ")?;
+        x_lp.to_css(dest)?;
+        dest.write_str(" ")?;
+        y_pos.to_css(dest)
+      }
+      (x_pos @ HorizontalPosition::Side { offset: Some(_), .. }, y) if y.is_center() => {
+        // If there is a side keyword with an offset, "center" must be a keyword not a percentage.
+        x_pos.to_css(dest)?;
+        dest.write_str(" center")
+      }
+      (x_pos, VerticalPosition::Center) => {
+        // `center` is assumed if omitted.
+        x_pos.to_css(dest)
+      }
+      (HorizontalPosition::Center, y_pos @ VerticalPosition::Side { offset: None, .. }) => y_pos.to_css(dest),
+      (x_pos, y_pos) => {
+        x_pos.to_css(dest)?;
+        dest.write_str(" ")?;
+        y_pos.to_css(dest)
+      }
+    }
+  }
+}
+
 /// A component within a [Position](Position) value, representing a position
 /// along either the horizontal or vertical axis of a box.
 ///
@@ -337,7 +378,7 @@ impl<S: ToCss> ToCss for PositionComponent<S> {
     use PositionComponent::*;
     match &self {
       Center => {
-        if dest.minify {
+        if dest.minify && !dest.preserve_position_keywords {
           dest.write_str("50%")
         } else {
           dest.write_str("center")
@@ -1,10 +1,10 @@
 //! CSS resolution values.
 
-use super::length::serialize_dimension;
+use super::length::{serialize_dimension, serialize_shortest_dimension};
 use super::number::CSSNumber;
 use crate::compat::Feature;
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{NumericKind, Printer};
 use crate::traits::{Parse, ToCss};
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
@@ -29,6 +29,17 @@ pub enum Resolution {
   Dppx(CSSNumber),
 }
 
+impl Resolution {
+  /// Returns the resolution in dots per inch.
+  pub fn to_dpi(&self) -> CSSNumber {
+    match self {
+      Resolution::Dpi(dpi) => *dpi,
+      Resolution::Dpcm(dpcm) => dpcm * 2.54,
+      Resolution::Dppx(dppx) => dppx * 96.0,
+    }
+  }
+}
+
 impl<'i> Parse<'i> for Resolution {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     // TODO: calc?
@@ -68,23 +79,36 @@ impl ToCss for Resolution {
   where
     W: std::fmt::Write,
   {
+    let dppx_unit = if let Some(targets) = dest.targets {
+      if Feature::XResolutionUnit.is_compatible(targets) {
+        "x"
+      } else {
+        "dppx"
+      }
+    } else {
+      "x"
+    };
+
     let (value, unit) = match self {
       Resolution::Dpi(dpi) => (*dpi, "dpi"),
       Resolution::Dpcm(dpcm) => (*dpcm, "dpcm"),
-      Resolution::Dppx(dppx) => {
-        if let Some(targets) = dest.targets {
-          if Feature::XResolutionUnit.is_compatible(targets) {
-            (*dppx, "x")
-          } else {
-            (*dppx, "dppx")
-          }
-        } else {
-          (*dppx, "x")
-        }
-      }
+      Resolution::Dppx(dppx) => (*dppx, dppx_unit),
     };
+    if dest.minify && dest.shortest_units {
+      let dpi = self.to_dpi();
+      return serialize_shortest_dimension(
+        &[
+          (value, unit),
+          (dpi, "dpi"),
+          (dpi / 2.54, "dpcm"),
+          (dpi / 96.0, dppx_unit),
+        ],
+        NumericKind::Resolution,
+        dest,
+      );
+    }
 
-    serialize_dimension(value, unit, dest)
+    serialize_dimension(dest.precision.round(value, NumericKind::Resolution), unit, dest)
   }
 }
 
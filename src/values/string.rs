@@ -333,7 +333,7 @@ impl<'i> ToCss for CSSString<'i> {
   where
     W: std::fmt::Write,
   {
-    serialize_string(&self.0, dest)?;
+    dest.write_string(&self.0)?;
     Ok(())
   }
 }
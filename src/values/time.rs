@@ -2,9 +2,10 @@
 
 use super::angle::impl_try_from_angle;
 use super::calc::Calc;
-use super::number::CSSNumber;
+use super::length::serialize_shortest_dimension;
+use super::number::{serialize_number, CSSNumber};
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{NumericKind, Printer};
 use crate::traits::private::AddInternal;
 use crate::traits::{impl_op, Map, Op, Parse, Sign, ToCss, Zero};
 #[cfg(feature = "visitor")]
@@ -100,26 +101,33 @@ impl ToCss for Time {
   {
     // 0.1s is shorter than 100ms
     // anything smaller is longer
-    match self {
+    let (value, unit) = match self {
       Time::Seconds(s) => {
         if *s > 0.0 && *s < 0.1 {
-          (*s * 1000.0).to_css(dest)?;
-          dest.write_str("ms")
+          (*s * 1000.0, "ms")
         } else {
-          s.to_css(dest)?;
-          dest.write_str("s")
+          (*s, "s")
         }
       }
       Time::Milliseconds(ms) => {
         if *ms == 0.0 || *ms >= 100.0 {
-          (*ms / 1000.0).to_css(dest)?;
-          dest.write_str("s")
+          (*ms / 1000.0, "s")
         } else {
-          ms.to_css(dest)?;
-          dest.write_str("ms")
+          (*ms, "ms")
         }
       }
+    };
+    if dest.minify && dest.shortest_units {
+      let ms = self.to_ms();
+      return serialize_shortest_dimension(
+        &[(value, unit), (ms / 1000.0, "s"), (ms, "ms")],
+        NumericKind::Time,
+        dest,
+      );
     }
+
+    serialize_number(dest.precision.round(value, NumericKind::Time), dest)?;
+    dest.write_str(unit)
   }
 }
 
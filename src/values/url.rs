@@ -2,8 +2,11 @@
 
 use crate::dependencies::{Dependency, Location, UrlDependency};
 use crate::error::{ParserError, PrinterError};
-use crate::printer::Printer;
+use crate::printer::{Printer, UrlKind};
+use crate::properties::custom::{Function, TokenList};
+use crate::stylesheet::ParserOptions;
 use crate::traits::{Parse, ToCss};
+use crate::values::ident::Ident;
 use crate::values::string::CowArcStr;
 #[cfg(feature = "visitor")]
 use crate::visitor::Visit;
@@ -20,21 +23,46 @@ pub struct Url<'i> {
   /// The url string.
   #[cfg_attr(feature = "serde", serde(borrow))]
   pub url: CowArcStr<'i>,
+  /// A list of [url modifiers](https://www.w3.org/TR/css-values-4/#typedef-url-modifier)
+  /// that follow the url string, e.g. `crossorigin(anonymous)`.
+  #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+  pub modifiers: Vec<UrlModifier<'i>>,
   /// The location where the `url()` was seen in the CSS source file.
   pub loc: Location,
 }
 
 impl<'i> PartialEq for Url<'i> {
   fn eq(&self, other: &Self) -> bool {
-    self.url == other.url
+    self.url == other.url && self.modifiers == other.modifiers
   }
 }
 
 impl<'i> Parse<'i> for Url<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let loc = input.current_source_location();
-    let url = input.expect_url()?.into();
-    Ok(Url { url, loc: loc.into() })
+    if let Ok(url) = input.try_parse(|input| input.expect_url().map(|url| url.into())) {
+      return Ok(Url {
+        url,
+        modifiers: Vec::new(),
+        loc: loc.into(),
+      });
+    }
+
+    // Modifiers are only allowed in the quoted `url("...")` form, which is tokenized as a function.
+    input.expect_function_matching("url")?;
+    input.parse_nested_block(|input| {
+      let url = input.expect_string_cloned()?.into();
+      let mut modifiers = Vec::new();
+      while !input.is_exhausted() {
+        modifiers.push(UrlModifier::parse(input)?);
+      }
+
+      Ok(Url {
+        url,
+        modifiers,
+        loc: loc.into(),
+      })
+    })
   }
 }
 
@@ -53,7 +81,8 @@ impl<'i> ToCss for Url<'i> {
     // be replaced without escaping more easily. Quotes may be removed later during minification.
     if let Some(dep) = dep {
       dest.write_str("url(")?;
-      serialize_string(&dep.placeholder, dest)?;
+      dest.write_string(&dep.placeholder)?;
+      self.write_modifiers(dest)?;
       dest.write_char(')')?;
 
       if let Some(dependencies) = &mut dest.dependencies {
@@ -64,18 +93,19 @@ impl<'i> ToCss for Url<'i> {
     }
 
     use cssparser::ToCss;
-    if dest.minify {
+    let url = dest.rewrite_url(self.url.as_ref(), UrlKind::Url);
+    if dest.minify && self.modifiers.is_empty() {
       let mut buf = String::new();
-      Token::UnquotedUrl(CowRcStr::from(self.url.as_ref())).to_css(&mut buf)?;
+      Token::UnquotedUrl(CowRcStr::from(url.as_ref())).to_css(&mut buf)?;
 
       // If the unquoted url is longer than it would be quoted (e.g. `url("...")`)
       // then serialize as a string and choose the shorter version.
-      if buf.len() > self.url.len() + 7 {
+      if buf.len() > url.len() + 7 {
         let mut buf2 = String::new();
-        serialize_string(&self.url, &mut buf2)?;
+        serialize_string(&url, &mut buf2)?;
         if buf2.len() + 5 < buf.len() {
           dest.write_str("url(")?;
-          dest.write_str(&buf2)?;
+          dest.write_string(&url)?;
           return dest.write_char(')');
         }
       }
@@ -83,7 +113,8 @@ impl<'i> ToCss for Url<'i> {
       dest.write_str(&buf)?;
     } else {
       dest.write_str("url(")?;
-      serialize_string(&self.url, dest)?;
+      dest.write_string(&url)?;
+      self.write_modifiers(dest)?;
       dest.write_char(')')?;
     }
 
@@ -92,43 +123,102 @@ impl<'i> ToCss for Url<'i> {
 }
 
 impl<'i> Url<'i> {
+  fn write_modifiers<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    for modifier in &self.modifiers {
+      dest.write_char(' ')?;
+      modifier.to_css(dest)?;
+    }
+    Ok(())
+  }
+
   /// Returns whether the URL is absolute, and not relative.
   pub fn is_absolute(&self) -> bool {
-    let url = self.url.as_ref();
+    is_absolute_url(self.url.as_ref())
+  }
+}
 
-    // Quick checks. If the url starts with '.', it is relative.
-    if url.starts_with('.') {
-      return false;
-    }
+/// Returns whether the given URL is absolute, and not relative.
+pub(crate) fn is_absolute_url(url: &str) -> bool {
+  // Quick checks. If the url starts with '.', it is relative.
+  if url.starts_with('.') {
+    return false;
+  }
 
-    // If the url starts with '/' it is absolute.
-    if url.starts_with('/') {
-      return true;
-    }
+  // If the url starts with '/' it is absolute.
+  if url.starts_with('/') {
+    return true;
+  }
 
-    // If the url starts with '#' we have a fragment URL.
-    // These are resolved relative to the document rather than the CSS file.
-    // https://drafts.csswg.org/css-values-4/#local-urls
-    if url.starts_with('#') {
-      return true;
-    }
+  // If the url starts with '#' we have a fragment URL.
+  // These are resolved relative to the document rather than the CSS file.
+  // https://drafts.csswg.org/css-values-4/#local-urls
+  if url.starts_with('#') {
+    return true;
+  }
 
-    // Otherwise, we might have a scheme. These must start with an ascii alpha character.
-    // https://url.spec.whatwg.org/#scheme-start-state
-    if !url.starts_with(|c| matches!(c, 'a'..='z' | 'A'..='Z')) {
-      return false;
+  // Otherwise, we might have a scheme. These must start with an ascii alpha character.
+  // https://url.spec.whatwg.org/#scheme-start-state
+  if !url.starts_with(|c: char| c.is_ascii_alphabetic()) {
+    return false;
+  }
+
+  // https://url.spec.whatwg.org/#scheme-state
+  for b in url.as_bytes() {
+    let c = *b as char;
+    match c {
+      'a'..='z' | 'A'..='Z' | '0'..='9' | '+' | '-' | '.' => {}
+      ':' => return true,
+      _ => break,
     }
+  }
+
+  false
+}
 
-    // https://url.spec.whatwg.org/#scheme-state
-    for b in url.as_bytes() {
-      let c = *b as char;
-      match c {
-        'a'..='z' | 'A'..='Z' | '0'..='9' | '+' | '-' | '.' => {}
-        ':' => return true,
-        _ => break,
+/// A [`<url-modifier>`](https://www.w3.org/TR/css-values-4/#typedef-url-modifier) within a `url()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "visitor", derive(Visit))]
+#[cfg_attr(feature = "into_owned", derive(lightningcss_derive::IntoOwned))]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+#[cfg_attr(feature = "jsonschema", derive(schemars::JsonSchema))]
+pub enum UrlModifier<'i> {
+  /// An identifier modifier, e.g. `crossorigin`.
+  #[cfg_attr(feature = "serde", serde(borrow))]
+  Ident(Ident<'i>),
+  /// A functional modifier, e.g. `crossorigin(anonymous)`.
+  Function(Function<'i>),
+}
+
+impl<'i> Parse<'i> for UrlModifier<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    match input.next()? {
+      Token::Ident(ident) => Ok(UrlModifier::Ident(ident.clone().into())),
+      Token::Function(name) => {
+        let name = name.clone().into();
+        let arguments = input.parse_nested_block(|input| TokenList::parse(input, &ParserOptions::default(), 0))?;
+        Ok(UrlModifier::Function(Function { name, arguments }))
       }
+      t => Err(location.new_unexpected_token_error(t.clone())),
     }
+  }
+}
 
-    false
+impl<'i> ToCss for UrlModifier<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      UrlModifier::Ident(ident) => ident.to_css(dest),
+      UrlModifier::Function(f) => f.to_css(dest, false),
+    }
   }
 }
@@ -52,14 +52,17 @@
 
 use crate::{
   declaration::DeclarationBlock,
-  media_query::{MediaFeature, MediaFeatureValue, MediaList, MediaQuery},
+  media_query::{MediaCondition, MediaFeature, MediaFeatureValue, MediaList, MediaQuery},
   parser::DefaultAtRule,
   properties::{
     custom::{EnvironmentVariable, Function, TokenList, TokenOrValue, Variable},
     Property,
   },
-  rules::{supports::SupportsCondition, CssRule, CssRuleList},
-  selector::{Selector, SelectorList},
+  rules::{
+    comment::CommentRule, container::ContainerName, layer::LayerName, supports::SupportsCondition, CssRule,
+    CssRuleList,
+  },
+  selector::{Component, Selector, SelectorList},
   values::{
     angle::Angle,
     color::CssColor,
@@ -74,6 +77,8 @@ use crate::{
 };
 use bitflags::bitflags;
 use smallvec::SmallVec;
+#[cfg(feature = "async_visitor")]
+use std::{future::Future, pin::Pin};
 
 pub(crate) use lightningcss_derive::Visit;
 
@@ -121,6 +126,10 @@ bitflags! {
     const FUNCTIONS = 1 << 17;
     /// Visit a token.
     const TOKENS = 1 << 18;
+    /// Visit preserved comments.
+    const COMMENTS = 1 << 19;
+    /// Visit the components of selectors, e.g. classes and attribute selectors.
+    const SELECTOR_COMPONENTS = 1 << 20;
   }
 }
 
@@ -146,16 +155,62 @@ pub trait Visitor<'i, T: Visit<'i, T, Self> = DefaultAtRule>: Sized {
     Self::TYPES
   }
 
+  /// Returns the context in which values are visited, if the visitor tracks it. By default, this returns
+  /// `None`. A visitor can return a [VisitorContext](VisitorContext) stored in itself to access the rules
+  /// enclosing the value being visited from within any of the other methods.
+  #[inline]
+  fn context(&mut self) -> Option<&mut VisitorContext<'i>> {
+    None
+  }
+
   /// Visits a rule list.
   #[inline]
   fn visit_rule_list(&mut self, rules: &mut CssRuleList<'i, T>) {
     rules.visit_children(self)
   }
 
-  /// Visits a rule.
+  /// Visits a rule. By default, the children of the rule are visited, and the rule is kept.
+  ///
+  /// Returning a list of rules replaces the rule with them, e.g. to expand an at-rule into several style
+  /// rules. Returning an empty list removes the rule. The returned rules are not visited. A rule that is
+  /// visited outside of a rule list, e.g. within a custom at-rule, can only be replaced by a single rule,
+  /// and is replaced by the first of the returned rules.
+  ///
+  /// ```
+  /// use lightningcss::{
+  ///   rules::CssRule,
+  ///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+  ///   visitor::{Visitor, Visit, VisitTypes},
+  /// };
+  /// use smallvec::{smallvec, SmallVec};
+  ///
+  /// // Removes `@font-face` rules, and duplicates style rules.
+  /// struct MyVisitor;
+  /// impl<'i> Visitor<'i> for MyVisitor {
+  ///   const TYPES: VisitTypes = VisitTypes::RULES;
+  ///
+  ///   fn visit_rule(&mut self, rule: &mut CssRule<'i>) -> Option<SmallVec<[CssRule<'i>; 1]>> {
+  ///     match rule {
+  ///       CssRule::FontFace(..) => Some(smallvec![]),
+  ///       CssRule::Style(..) => Some(smallvec![rule.clone(), rule.clone()]),
+  ///       _ => None,
+  ///     }
+  ///   }
+  /// }
+  ///
+  /// let mut stylesheet = StyleSheet::parse(
+  ///   "@font-face { font-family: Foo } .foo { color: red }",
+  ///   ParserOptions::default()
+  /// ).unwrap();
+  /// stylesheet.visit(&mut MyVisitor);
+  ///
+  /// let res = stylesheet.to_css(PrinterOptions { minify: true, ..Default::default() }).unwrap();
+  /// assert_eq!(res.code, ".foo{color:red}.foo{color:red}");
+  /// ```
   #[inline]
-  fn visit_rule(&mut self, rule: &mut CssRule<'i, T>) {
-    rule.visit_children(self)
+  fn visit_rule(&mut self, rule: &mut CssRule<'i, T>) -> Option<SmallVec<[CssRule<'i, T>; 1]>> {
+    rule.visit_children(self);
+    None
   }
 
   /// Visits a declaration block.
@@ -259,9 +314,49 @@ pub trait Visitor<'i, T: Visit<'i, T, Self> = DefaultAtRule>: Sized {
     selectors.visit_children(self)
   }
 
-  /// Visits a selector.
-  #[allow(unused_variables)]
-  fn visit_selector(&mut self, selector: &mut Selector<'i>) {}
+  /// Visits a selector. By default, the components of the selector are visited.
+  #[inline]
+  fn visit_selector(&mut self, selector: &mut Selector<'i>) {
+    selector.visit_children(self)
+  }
+
+  /// Visits a component of a selector, e.g. a class, attribute selector, or pseudo class. Components are
+  /// visited in the order they are stored, i.e. right to left, and combinators are not visited. By default,
+  /// the selectors nested within the component are visited, e.g. the arguments of `:is()` or `:not()`.
+  ///
+  /// ```
+  /// use lightningcss::{
+  ///   selector::Component,
+  ///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+  ///   visitor::{Visitor, Visit, VisitTypes},
+  /// };
+  ///
+  /// // Prefixes class names, including within `:is()` and `:not()`.
+  /// struct MyVisitor;
+  /// impl<'i> Visitor<'i> for MyVisitor {
+  ///   const TYPES: VisitTypes = VisitTypes::SELECTOR_COMPONENTS;
+  ///
+  ///   fn visit_selector_component(&mut self, component: &mut Component<'i>) {
+  ///     match component {
+  ///       Component::Class(name) => *name = format!("tw-{}", name).into(),
+  ///       _ => component.visit_children(self),
+  ///     }
+  ///   }
+  /// }
+  ///
+  /// let mut stylesheet = StyleSheet::parse(
+  ///   ".foo:is(.bar, div):not(.baz) > [data-x] { color: red }",
+  ///   ParserOptions::default()
+  /// ).unwrap();
+  /// stylesheet.visit(&mut MyVisitor);
+  ///
+  /// let res = stylesheet.to_css(PrinterOptions { minify: true, ..Default::default() }).unwrap();
+  /// assert_eq!(res.code, ".tw-foo:is(.tw-bar,div):not(.tw-baz)>[data-x]{color:red}");
+  /// ```
+  #[inline]
+  fn visit_selector_component(&mut self, component: &mut Component<'i>) {
+    component.visit_children(self)
+  }
 
   /// Visits a custom function.
   #[inline]
@@ -280,6 +375,427 @@ pub trait Visitor<'i, T: Visit<'i, T, Self> = DefaultAtRule>: Sized {
   fn visit_token(&mut self, token: &mut TokenOrValue<'i>) {
     token.visit_children(self)
   }
+
+  /// Visits a comment between rules or declarations, preserved according to the
+  /// [CommentPolicy](crate::rules::comment::CommentPolicy) the style sheet was parsed with.
+  ///
+  /// ```
+  /// use lightningcss::{
+  ///   rules::comment::{CommentPolicy, CommentRule},
+  ///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+  ///   visitor::{Visitor, Visit, VisitTypes},
+  /// };
+  ///
+  /// // Collects `/* rtl:* */` annotations.
+  /// struct MyVisitor(Vec<String>);
+  /// impl<'i> Visitor<'i> for MyVisitor {
+  ///   const TYPES: VisitTypes = VisitTypes::COMMENTS;
+  ///
+  ///   fn visit_comment(&mut self, comment: &mut CommentRule<'i>) {
+  ///     self.0.push(comment.text.trim().to_string());
+  ///   }
+  /// }
+  ///
+  /// let mut stylesheet = StyleSheet::parse(
+  ///   "/* rtl:begin:ignore */ .foo { /* rtl:ignore */ padding-left: 2px; color: red }",
+  ///   ParserOptions {
+  ///     preserve_comments: CommentPolicy {
+  ///       patterns: vec!["rtl:*".into()],
+  ///       ..CommentPolicy::default()
+  ///     },
+  ///     ..ParserOptions::default()
+  ///   }
+  /// ).unwrap();
+  /// let mut visitor = MyVisitor(Vec::new());
+  /// stylesheet.visit(&mut visitor);
+  /// assert_eq!(visitor.0, vec!["rtl:begin:ignore", "rtl:ignore"]);
+  ///
+  /// let res = stylesheet.to_css(PrinterOptions { minify: true, ..Default::default() }).unwrap();
+  /// assert_eq!(res.code, "/* rtl:begin:ignore */.foo{/* rtl:ignore */padding-left:2px;color:red}");
+  /// ```
+  #[allow(unused_variables)]
+  fn visit_comment(&mut self, comment: &mut CommentRule<'i>) {}
+
+  /// Visits the token or value at the given index within a token list, e.g. the value of an unparsed or
+  /// custom property, or the arguments of a function. The surrounding tokens can be inspected, e.g. to
+  /// find the arguments that follow a function name. By default, this calls
+  /// [visit_token](Visitor::visit_token), and the token is kept.
+  ///
+  /// Returning a list of tokens replaces the token with them, and returning an empty list removes the
+  /// token. The returned tokens are not visited.
+  ///
+  /// ```
+  /// use lightningcss::{
+  ///   properties::custom::{Token, TokenOrValue},
+  ///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+  ///   values::length::LengthValue,
+  ///   visitor::{Visitor, Visit, VisitTypes},
+  /// };
+  /// use smallvec::{smallvec, SmallVec};
+  ///
+  /// // Expands `theme(spacing)` into two lengths.
+  /// struct MyVisitor;
+  /// impl<'i> Visitor<'i> for MyVisitor {
+  ///   const TYPES: VisitTypes = VisitTypes::TOKENS;
+  ///
+  ///   fn visit_token_at(
+  ///     &mut self,
+  ///     tokens: &mut [TokenOrValue<'i>],
+  ///     index: usize,
+  ///   ) -> Option<SmallVec<[TokenOrValue<'i>; 1]>> {
+  ///     match &tokens[index] {
+  ///       TokenOrValue::Function(f) if f.name == "theme" => Some(smallvec![
+  ///         TokenOrValue::Length(LengthValue::Px(4.0)),
+  ///         TokenOrValue::Token(Token::WhiteSpace(" ".into())),
+  ///         TokenOrValue::Length(LengthValue::Px(8.0)),
+  ///       ]),
+  ///       _ => {
+  ///         tokens[index].visit(self);
+  ///         None
+  ///       }
+  ///     }
+  ///   }
+  /// }
+  ///
+  /// let mut stylesheet = StyleSheet::parse(
+  ///   ".foo { --gap: theme(spacing); margin: 0 theme(spacing) }",
+  ///   ParserOptions::default()
+  /// ).unwrap();
+  /// stylesheet.visit(&mut MyVisitor);
+  ///
+  /// let res = stylesheet.to_css(PrinterOptions { minify: true, ..Default::default() }).unwrap();
+  /// assert_eq!(res.code, ".foo{--gap:4px 8px;margin:0 4px 8px}");
+  /// ```
+  #[inline]
+  fn visit_token_at(
+    &mut self,
+    tokens: &mut [TokenOrValue<'i>],
+    index: usize,
+  ) -> Option<SmallVec<[TokenOrValue<'i>; 1]>> {
+    tokens[index].visit(self);
+    None
+  }
+}
+
+/// The rules that enclose a value being visited, e.g. the selectors of the enclosing style rule, or the
+/// conditions of the enclosing `@media` rules. The context is tracked while traversing a style sheet when
+/// it is returned from [Visitor::context].
+///
+/// A rule is added to the context while its children are visited, including its own prelude, e.g. the
+/// selectors of a style rule.
+///
+/// # Example
+///
+/// ```
+/// use lightningcss::{
+///   stylesheet::{StyleSheet, ParserOptions},
+///   visitor::{Visit, Visitor, VisitorContext, VisitTypes},
+///   visit_types,
+///   values::length::LengthValue,
+/// };
+///
+/// struct MyVisitor<'i> {
+///   context: VisitorContext<'i>,
+///   lengths: Vec<(usize, usize)>,
+/// }
+///
+/// impl<'i> Visitor<'i> for MyVisitor<'i> {
+///   const TYPES: VisitTypes = visit_types!(LENGTHS);
+///
+///   fn context(&mut self) -> Option<&mut VisitorContext<'i>> {
+///     Some(&mut self.context)
+///   }
+///
+///   fn visit_length(&mut self, _length: &mut LengthValue) {
+///     self.lengths.push((self.context.depth(), self.context.media().count()));
+///   }
+/// }
+///
+/// let mut stylesheet = StyleSheet::parse(
+///   ".foo { width: 1px } @media (width > 2px) { .bar { width: 3px } }",
+///   ParserOptions::default(),
+/// ).unwrap();
+///
+/// let mut visitor = MyVisitor { context: VisitorContext::new(), lengths: vec![] };
+/// stylesheet.visit(&mut visitor);
+/// assert_eq!(visitor.lengths, vec![(1, 0), (0, 1), (1, 1)]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VisitorContext<'i> {
+  rules: Vec<ContextRule<'i>>,
+}
+
+/// A rule enclosing a value being visited, as tracked by a [VisitorContext](VisitorContext).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextRule<'i> {
+  /// A style rule, with its selectors.
+  Style(SelectorList<'i>),
+  /// A `@media` rule, with its query.
+  Media(MediaList<'i>),
+  /// A `@supports` rule, with its condition.
+  Supports(SupportsCondition<'i>),
+  /// A `@layer` block rule, with its name if any.
+  Layer(Option<LayerName<'i>>),
+  /// A `@container` rule, with its name if any, and its condition.
+  Container(Option<ContainerName<'i>>, MediaCondition<'i>),
+}
+
+impl<'i> VisitorContext<'i> {
+  /// Creates an empty context.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the enclosing rules, from the outermost to the innermost.
+  pub fn rules(&self) -> &[ContextRule<'i>] {
+    &self.rules
+  }
+
+  /// Returns the selectors of the innermost enclosing style rule, if any.
+  pub fn selectors(&self) -> Option<&SelectorList<'i>> {
+    self.rules.iter().rev().find_map(|rule| match rule {
+      ContextRule::Style(selectors) => Some(selectors),
+      _ => None,
+    })
+  }
+
+  /// Returns the queries of the enclosing `@media` rules, from the outermost to the innermost.
+  pub fn media(&self) -> impl Iterator<Item = &MediaList<'i>> {
+    self.rules.iter().filter_map(|rule| match rule {
+      ContextRule::Media(query) => Some(query),
+      _ => None,
+    })
+  }
+
+  /// Returns the conditions of the enclosing `@supports` rules, from the outermost to the innermost.
+  pub fn supports(&self) -> impl Iterator<Item = &SupportsCondition<'i>> {
+    self.rules.iter().filter_map(|rule| match rule {
+      ContextRule::Supports(condition) => Some(condition),
+      _ => None,
+    })
+  }
+
+  /// Returns the nesting depth, i.e. the number of enclosing style rules.
+  pub fn depth(&self) -> usize {
+    self.rules.iter().filter(|rule| matches!(rule, ContextRule::Style(..))).count()
+  }
+
+  /// Adds a rule to the context before visiting its children. Returns whether the rule was added.
+  pub(crate) fn push<R>(&mut self, rule: &CssRule<'i, R>) -> bool {
+    let rule = match rule {
+      CssRule::Style(style) => ContextRule::Style(style.selectors.clone()),
+      CssRule::Nesting(nesting) => ContextRule::Style(nesting.style.selectors.clone()),
+      CssRule::Media(media) => ContextRule::Media(media.query.clone()),
+      CssRule::Supports(supports) => ContextRule::Supports(supports.condition.clone()),
+      CssRule::LayerBlock(layer) => ContextRule::Layer(layer.name.clone()),
+      CssRule::Container(container) => ContextRule::Container(container.name.clone(), container.condition.clone()),
+      _ => return false,
+    };
+
+    self.rules.push(rule);
+    true
+  }
+
+  /// Removes the innermost rule from the context after visiting its children.
+  pub(crate) fn pop(&mut self) {
+    self.rules.pop();
+  }
+}
+
+/// A future returned by an [AsyncVisitor](AsyncVisitor).
+#[cfg(feature = "async_visitor")]
+pub type VisitFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// A trait for asynchronously transforming values in a StyleSheet, e.g. to resolve `url()` references
+/// to assets, or to replace tokens with values fetched from a design token service. Run it with
+/// [visit_async](visit_async). This requires the `async_visitor` feature.
+///
+/// Only urls and tokens may be visited asynchronously, as selected by [TYPES](AsyncVisitor::TYPES).
+/// Tokens are visited as a whole, i.e. the arguments of a function token are part of the token rather
+/// than visited separately, and urls within token lists are only visited if tokens are not.
+///
+/// # Example
+///
+/// ```
+/// use lightningcss::{
+///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+///   visitor::{visit_async, AsyncVisitor, VisitFuture, VisitTypes},
+///   visit_types,
+///   values::url::Url,
+/// };
+///
+/// struct AssetResolver;
+/// impl<'i> AsyncVisitor<'i> for AssetResolver {
+///   const TYPES: VisitTypes = visit_types!(URLS);
+///
+///   fn visit_url<'a>(&'a mut self, url: &'a mut Url<'i>) -> VisitFuture<'a> {
+///     Box::pin(async move {
+///       // ... look up the url in an asset manifest ...
+///       url.url = format!("/assets/{}", url.url).into();
+///     })
+///   }
+/// }
+///
+/// # async fn transform() {
+/// let mut stylesheet = StyleSheet::parse(".foo { background: url(bg.png) }", ParserOptions::default()).unwrap();
+/// visit_async(&mut stylesheet, &mut AssetResolver).await;
+///
+/// let res = stylesheet.to_css(PrinterOptions { minify: true, ..Default::default() }).unwrap();
+/// assert_eq!(res.code, ".foo{background:url(/assets/bg.png)}");
+/// # }
+/// ```
+#[cfg(feature = "async_visitor")]
+pub trait AsyncVisitor<'i>: Send {
+  /// The types of values that this visitor should visit. Only `URLS` and `TOKENS` are supported.
+  const TYPES: VisitTypes;
+
+  /// Visits a url.
+  fn visit_url<'a>(&'a mut self, _url: &'a mut Url<'i>) -> VisitFuture<'a> {
+    Box::pin(async {})
+  }
+
+  /// Visits a token or value in an unparsed property.
+  fn visit_token<'a>(&'a mut self, _token: &'a mut TokenOrValue<'i>) -> VisitFuture<'a> {
+    Box::pin(async {})
+  }
+}
+
+/// Visits a value with an [AsyncVisitor](AsyncVisitor). Style sheets with custom at-rules are not supported.
+///
+/// The values are visited in two passes: the urls and tokens are first collected from the value, then
+/// passed to the visitor one at a time, and finally written back into the value. The value is therefore
+/// only borrowed while collecting and writing back the results, not across await points in the visitor.
+#[cfg(feature = "async_visitor")]
+pub async fn visit_async<'i, V, A>(value: &mut V, visitor: &mut A)
+where
+  V: Visit<'i, DefaultAtRule, AsyncValues<'i>>,
+  A: AsyncVisitor<'i>,
+{
+  let mut values = AsyncValues {
+    types: A::TYPES & (VisitTypes::URLS | VisitTypes::TOKENS),
+    urls: Vec::new(),
+    tokens: Vec::new(),
+    apply: false,
+  };
+  value.visit(&mut values);
+
+  for url in &mut values.urls {
+    visitor.visit_url(url).await;
+  }
+  for token in &mut values.tokens {
+    visitor.visit_token(token).await;
+  }
+
+  // Write the results back in the same order the values were collected.
+  values.urls.reverse();
+  values.tokens.reverse();
+  values.apply = true;
+  value.visit(&mut values);
+}
+
+/// The urls and tokens of a value being visited by [visit_async](visit_async).
+#[cfg(feature = "async_visitor")]
+pub struct AsyncValues<'i> {
+  types: VisitTypes,
+  urls: Vec<Url<'i>>,
+  tokens: Vec<TokenOrValue<'i>>,
+  apply: bool,
+}
+
+#[cfg(feature = "async_visitor")]
+impl<'i> Visitor<'i> for AsyncValues<'i> {
+  const TYPES: VisitTypes = VisitTypes::empty();
+
+  fn visit_types(&self) -> VisitTypes {
+    self.types
+  }
+
+  fn visit_url(&mut self, url: &mut Url<'i>) {
+    if self.apply {
+      if let Some(result) = self.urls.pop() {
+        *url = result;
+      }
+    } else {
+      self.urls.push(url.clone());
+    }
+  }
+
+  fn visit_token(&mut self, token: &mut TokenOrValue<'i>) {
+    if self.apply {
+      if let Some(result) = self.tokens.pop() {
+        *token = result;
+      }
+    } else {
+      self.tokens.push(token.clone());
+    }
+  }
+}
+
+/// Visits the rules in a rule list in parallel, e.g. the top-level rules of a style sheet. This requires
+/// the `rayon` feature.
+///
+/// Each rule is visited by its own clone of the visitor, as if it were the only rule in the list, so the
+/// visitor must not depend on the rules visited before it. Rules may be replaced or removed by
+/// [visit_rule](Visitor::visit_rule) as usual. Once all rules are visited, the clones are passed to `merge`
+/// one at a time in the order of the rules, so the merged results are the same on every run regardless of
+/// how the rules were scheduled.
+///
+/// # Example
+///
+/// ```
+/// use lightningcss::{
+///   stylesheet::{StyleSheet, ParserOptions},
+///   values::color::CssColor,
+///   visitor::{visit_parallel, Visitor, VisitTypes},
+/// };
+///
+/// // Counts the colors used in a style sheet.
+/// #[derive(Clone, Default)]
+/// struct ColorCounter {
+///   colors: usize,
+/// }
+///
+/// impl<'i> Visitor<'i> for ColorCounter {
+///   const TYPES: VisitTypes = VisitTypes::COLORS;
+///
+///   fn visit_color(&mut self, _color: &mut CssColor) {
+///     self.colors += 1;
+///   }
+/// }
+///
+/// let mut stylesheet = StyleSheet::parse(
+///   ".foo { color: red; background: blue } .bar { color: green }",
+///   ParserOptions::default()
+/// ).unwrap();
+///
+/// let mut counter = ColorCounter::default();
+/// visit_parallel(&mut stylesheet.rules, &mut counter, |counter, other| counter.colors += other.colors);
+/// assert_eq!(counter.colors, 3);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn visit_parallel<'i, T, V, M>(rules: &mut CssRuleList<'i, T>, visitor: &mut V, mut merge: M)
+where
+  T: Visit<'i, T, V> + Send,
+  V: Visitor<'i, T> + Clone + Send + Sync,
+  M: FnMut(&mut V, V),
+{
+  use rayon::prelude::*;
+
+  let initial: &V = visitor;
+  let results: Vec<(Vec<CssRule<'i, T>>, V)> = std::mem::take(&mut rules.0)
+    .into_par_iter()
+    .map(|rule| {
+      let mut list = CssRuleList(vec![rule]);
+      let mut visitor = initial.clone();
+      list.visit(&mut visitor);
+      (list.0, visitor)
+    })
+    .collect();
+
+  for (list, result) in results {
+    rules.0.extend(list);
+    merge(visitor, result);
+  }
 }
 
 /// A trait for values that can be visited by a [Visitor](Visitor).